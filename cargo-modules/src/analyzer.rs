@@ -2,6 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 
 use ra_ap_cfg::{self as cfg};
@@ -83,18 +84,29 @@ pub fn cargo_config(
 ) -> project_model::CargoConfig {
     let all_targets = false;
 
+    // `cargo metadata --no-deps` reports no resolve graph, so rust-analyzer
+    // can't tell which features are active. Enable them as cfg atoms instead.
+    let feature_atoms = enabled_features(project_options)
+        .into_iter()
+        .map(|feature| cfg::CfgAtom::KeyValue {
+            key: hir::Symbol::intern("feature"),
+            value: hir::Symbol::intern(&feature),
+        });
+
     // Crates to enable/disable `#[cfg(test)]` on
     let cfg_overrides = match load_options.cfg_test {
         true => project_model::CfgOverrides {
             global: cfg::CfgDiff::new(
-                vec![cfg::CfgAtom::Flag(hir::Symbol::intern("test"))],
+                std::iter::once(cfg::CfgAtom::Flag(hir::Symbol::intern("test")))
+                    .chain(feature_atoms)
+                    .collect(),
                 Vec::new(),
             ),
             selective: Default::default(),
         },
         false => project_model::CfgOverrides {
             global: cfg::CfgDiff::new(
-                Vec::new(),
+                feature_atoms.collect(),
                 vec![cfg::CfgAtom::Flag(hir::Symbol::intern("test"))],
             ),
             selective: Default::default(),
@@ -167,6 +179,102 @@ pub fn cargo_config(
     }
 }
 
+/// Features of the analyzed package activated by the feature options
+///
+/// Reads the `[features]` table of the package manifest and returns the
+/// requested features (plus `default` unless disabled, or every feature with
+/// `all_features`) together with the features they enable in turn. If the
+/// manifest can't be read, only the requested features are returned.
+pub fn enabled_features(project_options: &ProjectOptions) -> BTreeSet<String> {
+    let manifest_path = if project_options.manifest_path.is_dir() {
+        project_options.manifest_path.join("Cargo.toml")
+    } else {
+        project_options.manifest_path.clone()
+    };
+
+    let manifest = std::fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|content| content.parse::<toml::Table>().ok())
+        .unwrap_or_default();
+
+    resolve_features(&manifest, project_options)
+}
+
+/// Expand the feature options against a parsed package manifest
+fn resolve_features(manifest: &toml::Table, project_options: &ProjectOptions) -> BTreeSet<String> {
+    let features = manifest.get("features").and_then(|f| f.as_table());
+
+    let mut requested: Vec<String> = if project_options.all_features {
+        let mut all: Vec<String> = features
+            .map(|f| f.keys().cloned().collect())
+            .unwrap_or_default();
+        all.extend(implicit_dependency_features(manifest));
+        all
+    } else {
+        // Cargo accepts comma or space separated lists within a single argument
+        project_options
+            .features
+            .iter()
+            .flat_map(|f| f.split([',', ' ']))
+            .filter(|f| !f.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    if !project_options.no_default_features && !project_options.all_features {
+        requested.push("default".to_string());
+    }
+
+    let mut enabled = BTreeSet::new();
+    while let Some(feature) = requested.pop() {
+        // Features of dependencies (`dep/feature`) don't apply to this package
+        if feature.contains('/') || !enabled.insert(feature.clone()) {
+            continue;
+        }
+
+        let implied = features
+            .and_then(|f| f.get(&feature))
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str())
+            .filter(|v| !v.starts_with("dep:"));
+        requested.extend(implied.map(str::to_string));
+    }
+
+    // `default` is only a cfg if the package declares it
+    if features.is_none_or(|f| !f.contains_key("default")) {
+        enabled.remove("default");
+    }
+
+    enabled
+}
+
+/// Optional dependencies that get an implicit feature of the same name
+///
+/// A dependency referenced as `dep:name` in the `[features]` table has no implicit feature.
+fn implicit_dependency_features(manifest: &toml::Table) -> Vec<String> {
+    let explicit: BTreeSet<&str> = manifest
+        .get("features")
+        .and_then(|f| f.as_table())
+        .into_iter()
+        .flat_map(|f| f.values())
+        .filter_map(|v| v.as_array())
+        .flatten()
+        .filter_map(|v| v.as_str()?.strip_prefix("dep:"))
+        .collect();
+
+    manifest
+        .get("dependencies")
+        .and_then(|d| d.as_table())
+        .into_iter()
+        .flatten()
+        .filter(|(_, dep)| dep.get("optional").and_then(|o| o.as_bool()) == Some(true))
+        .map(|(name, _)| name.as_str())
+        .filter(|name| !explicit.contains(name))
+        .map(str::to_string)
+        .collect()
+}
+
 pub fn load_config() -> load_cargo::LoadCargoConfig {
     // Disable build script execution to prevent hanging
     // This is the most common cause of hangs in rust-analyzer-based tools
@@ -678,6 +786,60 @@ pub fn module_file(module: hir::Module, db: &ide::RootDatabase, vfs: &vfs::Vfs)
     Some(path.to_owned())
 }
 
+/// Location of an item's definition in its source file
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SourceSpan {
+    pub file: PathBuf,
+    /// 1-based line of the first character of the item
+    pub line_start: usize,
+    /// 0-based byte column of the first character of the item
+    pub column_start: usize,
+    /// 1-based line of the last character of the item
+    pub line_end: usize,
+    /// 0-based byte column just past the last character of the item
+    pub column_end: usize,
+}
+
+/// Find where an item is defined, using the same navigation target as "go to definition"
+///
+/// Returns `None` for items without source, such as builtin types.
+pub fn source_span(
+    module_def_hir: hir::ModuleDef,
+    db: &ide::RootDatabase,
+    vfs: &vfs::Vfs,
+) -> Option<SourceSpan> {
+    use ide::TryToNav as _;
+    use ide_db::base_db::SourceDatabase as _;
+
+    let nav = module_def_hir.try_to_nav(db)?.call_site;
+    let vfs_path = vfs.file_path(nav.file_id);
+    let file: &Path = vfs_path.as_path()?.as_ref();
+    let text = db.file_text(nav.file_id).text(db);
+    let text = text.as_bytes();
+
+    let (line_start, column_start) = line_col(text, u32::from(nav.full_range.start()) as usize);
+    let (line_end, column_end) = line_col(text, u32::from(nav.full_range.end()) as usize);
+
+    Some(SourceSpan {
+        file: file.to_owned(),
+        line_start,
+        column_start,
+        line_end,
+        column_end,
+    })
+}
+
+/// Convert a byte offset into a 1-based line and 0-based byte column
+fn line_col(text: &[u8], offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.iter().filter(|&&b| b == b'\n').count() + 1;
+    let column = before
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(before.len(), |newline| before.len() - newline - 1);
+    (line, column)
+}
+
 pub fn moduledef_is_crate(module_def_hir: hir::ModuleDef, _db: &ide::RootDatabase) -> bool {
     let hir::ModuleDef::Module(module) = module_def_hir else {
         return false;
@@ -740,6 +902,67 @@ mod tests {
         assert!(!opts.sysroot);
     }
 
+    fn feature_options(
+        features: &[&str],
+        no_default_features: bool,
+        all_features: bool,
+    ) -> ProjectOptions {
+        ProjectOptions {
+            lib: true,
+            bin: None,
+            package: None,
+            no_default_features,
+            all_features,
+            features: features.iter().map(|f| f.to_string()).collect(),
+            target: None,
+            manifest_path: PathBuf::from("Cargo.toml"),
+        }
+    }
+
+    #[test]
+    fn test_resolve_features() {
+        let manifest: toml::Table = r#"
+            [features]
+            default = ["std"]
+            std = []
+            full = ["std", "extra", "dep:serde", "log/std"]
+            extra = []
+
+            [dependencies]
+            serde = { version = "1", optional = true }
+            log = { version = "0.4", optional = true }
+        "#
+        .parse()
+        .unwrap();
+
+        let resolve = |options: &ProjectOptions| {
+            resolve_features(&manifest, options)
+                .into_iter()
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            resolve(&feature_options(&[], false, false)),
+            ["default", "std"]
+        );
+        assert_eq!(
+            resolve(&feature_options(&[], true, false)),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            resolve(&feature_options(&["full"], true, false)),
+            ["extra", "full", "std"]
+        );
+        assert_eq!(
+            resolve(&feature_options(&["extra,std"], true, false)),
+            ["extra", "std"]
+        );
+        assert_eq!(
+            resolve(&feature_options(&[], false, true)),
+            ["default", "extra", "full", "log", "std"]
+        );
+    }
+
     #[test]
     fn test_cargo_config_with_test_enabled() {
         let project_opts = ProjectOptions {
@@ -847,6 +1070,57 @@ mod tests {
         assert!(!has_test_cfg(&attrs));
     }
 
+    #[test]
+    fn test_line_col() {
+        let text = b"mod a;\n\npub struct Foo {\n    x: u8,\n}\n";
+        assert_eq!(line_col(text, 0), (1, 0));
+        assert_eq!(line_col(text, 8), (3, 0));
+        assert_eq!(line_col(text, 12), (3, 4));
+        assert_eq!(line_col(text, 37), (5, 1));
+        // Offsets past the end are clamped
+        assert_eq!(line_col(text, 1000), (6, 0));
+    }
+
+    #[test]
+    fn test_source_span() -> anyhow::Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        std::fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"span-demo\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )?;
+        std::fs::create_dir(temp_dir.path().join("src"))?;
+        std::fs::write(
+            temp_dir.path().join("src").join("lib.rs"),
+            "//! Demo\n\npub struct Point {\n    pub x: i32,\n}\n",
+        )?;
+
+        let (krate, host, vfs, _) = crate::analyze_crate_with_vfs(
+            temp_dir.path(),
+            None,
+            crate::AnalysisConfig::standard(),
+        )?;
+        let db = host.raw_database();
+        let point = krate
+            .root_module()
+            .declarations(db)
+            .into_iter()
+            .find(|def| matches!(def, hir::ModuleDef::Adt(_)))
+            .expect("Point should be declared in the crate root");
+
+        let span = source_span(point, db, &vfs).expect("Point should have a source span");
+        assert!(span.file.ends_with(Path::new("src").join("lib.rs")));
+        assert_eq!(
+            (
+                span.line_start,
+                span.column_start,
+                span.line_end,
+                span.column_end
+            ),
+            (3, 0, 5, 1)
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_crate_name_canonicalization() {
         // This would need a mock database to test properly
@@ -4,6 +4,7 @@
 
 use ra_ap_hir::{self as hir};
 use ra_ap_ide::{self as ide};
+use ra_ap_vfs::{self as vfs};
 
 use crate::analyzer;
 
@@ -51,6 +52,14 @@ impl Item {
         analyzer::display_path(self.hir, db, edition)
     }
 
+    pub fn source_span(
+        &self,
+        db: &ide::RootDatabase,
+        vfs: &vfs::Vfs,
+    ) -> Option<analyzer::SourceSpan> {
+        analyzer::source_span(self.hir, db, vfs)
+    }
+
     pub fn kind_ordering(&self, _db: &ide::RootDatabase, _edition: ide::Edition) -> u8 {
         // Return ordering based on item kind for sorting
         // Lower numbers come first
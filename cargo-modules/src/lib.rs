@@ -15,9 +15,10 @@ use std::path::Path;
 use anyhow::Result;
 use ra_ap_hir::{self as hir};
 use ra_ap_ide::{self as ide};
+use ra_ap_vfs::{self as vfs};
 
 pub use crate::{
-    analyzer::LoadOptions,
+    analyzer::{LoadOptions, SourceSpan},
     item::Item,
     options::{GeneralOptions, ProjectOptions},
     tree::{ModuleTree, Tree, TreeBuilder},
@@ -98,6 +99,20 @@ pub fn analyze_crate(
     package: Option<&str>,
     config: AnalysisConfig,
 ) -> Result<(hir::Crate, ide::AnalysisHost, ide::Edition)> {
+    let (crate_id, analysis_host, _vfs, edition) = analyze_crate_with_vfs(path, package, config)?;
+
+    Ok((crate_id, analysis_host, edition))
+}
+
+/// Analyzes a Rust crate like [`analyze_crate`], also returning the virtual file system
+///
+/// The VFS maps the file ids used by the analysis back to paths on disk, which is
+/// needed to resolve item source locations (see [`Item::source_span`]).
+pub fn analyze_crate_with_vfs(
+    path: &Path,
+    package: Option<&str>,
+    config: AnalysisConfig,
+) -> Result<(hir::Crate, ide::AnalysisHost, vfs::Vfs, ide::Edition)> {
     let general_options = GeneralOptions { verbose: false };
 
     let project_options = ProjectOptions {
@@ -116,10 +131,7 @@ pub fn analyze_crate(
         sysroot: config.sysroot,
     };
 
-    let (crate_id, analysis_host, _vfs, edition) =
-        analyzer::load_workspace(&general_options, &project_options, &load_options)?;
-
-    Ok((crate_id, analysis_host, edition))
+    analyzer::load_workspace(&general_options, &project_options, &load_options)
 }
 
 /// Builds a module tree from a crate analysis
@@ -195,19 +195,44 @@ main() {
       \"mcp__rust-docs__cache_crate_from_cratesio\",
       \"mcp__rust-docs__cache_crate_from_github\",
       \"mcp__rust-docs__cache_crate_from_local\",
+      \"mcp__rust-docs__cache_latest\",
+      \"mcp__rust-docs__ensure_ready\",
       \"mcp__rust-docs__remove_crate\",
+      \"mcp__rust-docs__clear_all_caches\",
+      \"mcp__rust-docs__reindex\",
+      \"mcp__rust-docs__prune_indexes\",
       \"mcp__rust-docs__list_cached_crates\",
+      \"mcp__rust-docs__server_info\",
       \"mcp__rust-docs__list_crate_versions\",
       \"mcp__rust-docs__get_crates_metadata\",
       \"mcp__rust-docs__list_crate_items\",
+      \"mcp__rust-docs__stream_crate_items\",
       \"mcp__rust-docs__search_items\",
       \"mcp__rust-docs__search_items_preview\",
       \"mcp__rust-docs__get_item_details\",
       \"mcp__rust-docs__get_item_docs\",
+      \"mcp__rust-docs__get_docs_for_names\",
+      \"mcp__rust-docs__get_item_raw\",
       \"mcp__rust-docs__get_item_source\",
+      \"mcp__rust-docs__get_blanket_impls\",
+      \"mcp__rust-docs__get_repr_info\",
+      \"mcp__rust-docs__list_cfg_attributes\",
+      \"mcp__rust-docs__get_all_methods\",
+      \"mcp__rust-docs__get_supertraits\",
+      \"mcp__rust-docs__export_docs_markdown\",
+      \"mcp__rust-docs__export_items_ndjson\",
+      \"mcp__rust-docs__get_changed_items_since\",
+      \"mcp__rust-docs__diff_visibility\",
       \"mcp__rust-docs__get_dependencies\",
+      \"mcp__rust-docs__diff_dependencies\",
+      \"mcp__rust-docs__get_dependents_in_cache\",
       \"mcp__rust-docs__structure\",
-      \"mcp__rust-docs__search_items_fuzzy\"${NC}"
+      \"mcp__rust-docs__list_crate_targets\",
+      \"mcp__rust-docs__get_crate_edition\",
+      \"mcp__rust-docs__get_crate_source_tree\",
+      \"mcp__rust-docs__get_usage_examples\",
+      \"mcp__rust-docs__search_items_fuzzy\",
+      \"mcp__rust-docs__find_similar_items\"${NC}"
             else
                 warn "Failed to add rust-docs-mcp to Claude Code"
                 echo
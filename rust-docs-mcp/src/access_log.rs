@@ -0,0 +1,214 @@
+//! Access log of tool calls for usage analytics
+//!
+//! When enabled with `--access-log <path>`, every tool call appends one JSON
+//! line with its timestamp, tool name, and the crate, version and item id it
+//! was called with, if any. Lines are handed to a background task that writes
+//! them through a buffered writer, so logging never blocks a tool call. The
+//! access log is separate from the tracing logs on stderr.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::{mpsc, oneshot};
+
+/// One line of the access log
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AccessLogEntry {
+    /// When the tool was called, in RFC 3339 format
+    pub timestamp: String,
+    pub tool: String,
+    #[serde(rename = "crate", default, skip_serializing_if = "Option::is_none")]
+    pub crate_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_id: Option<u64>,
+}
+
+impl AccessLogEntry {
+    /// Entry for a call of `tool`, taking the crate, version and item id from its parameters
+    pub fn new(tool: &str, params: Option<&serde_json::Value>) -> Self {
+        let field = |key: &str| params.and_then(|params| params.get(key));
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            tool: tool.to_string(),
+            crate_name: field("crate_name")
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string),
+            version: field("version")
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string),
+            item_id: field("item_id").and_then(serde_json::Value::as_u64),
+        }
+    }
+}
+
+enum Message {
+    Line(String),
+    /// Write out everything sent before, then reply
+    Flush(oneshot::Sender<()>),
+}
+
+impl std::fmt::Debug for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Line(line) => f.debug_tuple("Line").field(line).finish(),
+            Self::Flush(_) => f.write_str("Flush"),
+        }
+    }
+}
+
+/// Handle to the access log writer, disabled unless opened with [`AccessLog::open`]
+#[derive(Debug, Clone, Default)]
+pub struct AccessLog {
+    sender: Option<mpsc::UnboundedSender<Message>>,
+}
+
+impl AccessLog {
+    /// Append to the access log at `path`, creating it if needed
+    ///
+    /// Must be called from within a Tokio runtime, which runs the writer task.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create access log directory {}", parent.display())
+            })?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open access log {}", path.display()))?;
+        let runtime = tokio::runtime::Handle::try_current()
+            .context("The access log needs a Tokio runtime")?;
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let writer = BufWriter::new(tokio::fs::File::from_std(file));
+        runtime.spawn(write_entries(writer, receiver, path.display().to_string()));
+
+        Ok(Self {
+            sender: Some(sender),
+        })
+    }
+
+    /// Whether tool calls are logged
+    pub fn is_enabled(&self) -> bool {
+        self.sender.is_some()
+    }
+
+    /// Log a call of `tool` with `params`, without waiting for it to be written
+    pub fn record(&self, tool: &str, params: Option<&impl Serialize>) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        let params = params.and_then(|params| serde_json::to_value(params).ok());
+        let entry = AccessLogEntry::new(tool, params.as_ref());
+        if let Ok(line) = serde_json::to_string(&entry) {
+            // Fails only once the writer is gone, and logging must not fail the call
+            let _ = sender.send(Message::Line(line));
+        }
+    }
+
+    /// Wait until every entry recorded so far is written to the file
+    pub async fn flush(&self) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        let (done, written) = oneshot::channel();
+        if sender.send(Message::Flush(done)).is_ok() {
+            let _ = written.await;
+        }
+    }
+}
+
+/// Write entries as they arrive, flushing whenever no more are queued
+async fn write_entries(
+    mut writer: BufWriter<tokio::fs::File>,
+    mut receiver: mpsc::UnboundedReceiver<Message>,
+    path: String,
+) {
+    while let Some(mut message) = receiver.recv().await {
+        loop {
+            let result = match message {
+                Message::Line(line) => writer.write_all(format!("{line}\n").as_bytes()).await,
+                Message::Flush(done) => {
+                    let result = writer.flush().await;
+                    let _ = done.send(());
+                    result
+                }
+            };
+            if let Err(e) = result {
+                tracing::warn!("Failed to write access log {}: {}", path, e);
+            }
+
+            match receiver.try_recv() {
+                Ok(next) => message = next,
+                Err(_) => break,
+            }
+        }
+        if let Err(e) = writer.flush().await {
+            tracing::warn!("Failed to flush access log {}: {}", path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_entry_from_params() {
+        let params = serde_json::json!({
+            "crate_name": "serde",
+            "version": "1.0.0",
+            "item_id": 42,
+            "context_lines": 3,
+        });
+        let entry = AccessLogEntry::new("get_item_source", Some(&params));
+        assert_eq!(entry.tool, "get_item_source");
+        assert_eq!(entry.crate_name.as_deref(), Some("serde"));
+        assert_eq!(entry.version.as_deref(), Some("1.0.0"));
+        assert_eq!(entry.item_id, Some(42));
+
+        let json = serde_json::to_string(&AccessLogEntry::new("server_info", None)).unwrap();
+        assert!(json.contains(r#""tool":"server_info""#));
+        assert!(!json.contains("crate"));
+    }
+
+    #[tokio::test]
+    async fn test_access_log_appends_lines() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("logs/access.jsonl");
+        std::fs::create_dir_all(temp_dir.path().join("logs"))?;
+        std::fs::write(&path, "{\"timestamp\":\"earlier\",\"tool\":\"reindex\"}\n")?;
+
+        let log = AccessLog::open(&path)?;
+        assert!(log.is_enabled());
+        let params = serde_json::json!({"crate_name": "semver", "version": "1.0.0"});
+        log.record("list_crate_items", Some(&params));
+        log.record("list_cached_crates", None::<&()>);
+        log.flush().await;
+
+        let contents = std::fs::read_to_string(&path)?;
+        let entries: Vec<AccessLogEntry> = contents
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        let tools: Vec<&str> = entries.iter().map(|entry| entry.tool.as_str()).collect();
+        assert_eq!(tools, ["reindex", "list_crate_items", "list_cached_crates"]);
+        assert_eq!(entries[1].crate_name.as_deref(), Some("semver"));
+
+        // A disabled log records nothing
+        let disabled = AccessLog::default();
+        disabled.record("list_cached_crates", None::<&()>);
+        disabled.flush().await;
+        assert!(!disabled.is_enabled());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+//! Edition and minimum supported Rust version of cached crates
+//!
+//! Reads `edition` and `rust-version` from the `[package]` section of a
+//! package's `Cargo.toml`. Values declared with `workspace = true` are resolved
+//! against `[workspace.package]` of the nearest workspace root manifest.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use toml::Value;
+
+/// Edition Cargo assumes when a manifest does not declare one
+const DEFAULT_EDITION: &str = "2015";
+
+/// Edition and `rust-version` of a package
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrateEdition {
+    pub edition: String,
+    pub rust_version: Option<String>,
+}
+
+/// Read the edition and `rust-version` of the package rooted at `package_dir`
+///
+/// Workspace-inherited values are looked up in the manifests of `package_dir`
+/// and its ancestors, up to and including `root_dir`.
+pub fn read_edition(package_dir: &Path, root_dir: &Path) -> Result<CrateEdition> {
+    let manifest = read_manifest(&package_dir.join("Cargo.toml"))?;
+    let package = manifest
+        .get("package")
+        .context("Cargo.toml has no [package] section")?;
+
+    let inherits = |key: &str| package.get(key).is_some_and(is_inherited);
+    let workspace_package = if inherits("edition") || inherits("rust-version") {
+        find_workspace_package(package_dir, root_dir)?
+    } else {
+        None
+    };
+
+    let field = |key: &str| -> Result<Option<String>> {
+        match package.get(key) {
+            Some(value) if is_inherited(value) => workspace_package
+                .as_ref()
+                .and_then(|table| table.get(key))
+                .and_then(Value::as_str)
+                .map(|value| Some(value.to_string()))
+                .with_context(|| {
+                    format!("'{key}' is inherited from the workspace, but no [workspace.package] declares it")
+                }),
+            Some(value) => Ok(value.as_str().map(str::to_string)),
+            None => Ok(None),
+        }
+    };
+    let edition = field("edition")?;
+    let rust_version = field("rust-version")?;
+
+    Ok(CrateEdition {
+        edition: edition.unwrap_or_else(|| DEFAULT_EDITION.to_string()),
+        rust_version,
+    })
+}
+
+fn read_manifest(path: &Path) -> Result<Value> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read Cargo.toml at {}", path.display()))?;
+    toml::from_str(&content)
+        .with_context(|| format!("Failed to parse Cargo.toml at {}", path.display()))
+}
+
+/// Whether a manifest value is `{ workspace = true }`
+fn is_inherited(value: &Value) -> bool {
+    value.get("workspace").and_then(Value::as_bool) == Some(true)
+}
+
+/// Find `[workspace.package]` in the nearest manifest declaring a `[workspace]`
+fn find_workspace_package(package_dir: &Path, root_dir: &Path) -> Result<Option<Value>> {
+    for dir in package_dir.ancestors() {
+        let manifest_path = dir.join("Cargo.toml");
+        if manifest_path.is_file() {
+            let manifest = read_manifest(&manifest_path)?;
+            if let Some(workspace) = manifest.get("workspace") {
+                return Ok(workspace.get("package").cloned());
+            }
+        }
+        if dir == root_dir {
+            break;
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_edition() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"plain\"\nversion = \"0.1.0\"\nedition = \"2021\"\nrust-version = \"1.70\"\n",
+        )?;
+        assert_eq!(
+            read_edition(root, root)?,
+            CrateEdition {
+                edition: "2021".to_string(),
+                rust_version: Some("1.70".to_string()),
+            }
+        );
+
+        // Without an edition Cargo assumes 2015
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"old\"\nversion = \"0.1.0\"\n",
+        )?;
+        assert_eq!(read_edition(root, root)?.edition, "2015");
+        assert_eq!(read_edition(root, root)?.rust_version, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_workspace_inherited_edition() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nedition = \"2024\"\nrust-version = \"1.85\"\n",
+        )?;
+        let member = root.join("crates/member");
+        fs::create_dir_all(&member)?;
+        fs::write(
+            member.join("Cargo.toml"),
+            "[package]\nname = \"member\"\nversion = \"0.1.0\"\nedition.workspace = true\nrust-version = { workspace = true }\n",
+        )?;
+
+        let edition = read_edition(&member, root)?;
+        assert_eq!(edition.edition, "2024");
+        assert_eq!(edition.rust_version.as_deref(), Some("1.85"));
+
+        // Inheriting a value the workspace does not declare is an error
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        )?;
+        assert!(read_edition(&member, root).is_err());
+
+        Ok(())
+    }
+}
@@ -1,2 +1,6 @@
+pub mod edition;
 pub mod outputs;
+pub mod source_tree;
+pub mod targets;
 pub mod tools;
+pub mod usages;
@@ -13,10 +13,24 @@ pub struct StructureNode {
     pub name: String,
     pub path: String,
     pub visibility: String,
+    /// Where the item is defined, if it has source in the analyzed crate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<SourceLocation>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub children: Option<Vec<StructureNode>>,
 }
 
+/// Source location of a structure node
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct SourceLocation {
+    /// Path relative to the crate root (e.g. "src/lib.rs")
+    pub filename: String,
+    pub line_start: usize,
+    pub column_start: usize,
+    pub line_end: usize,
+    pub column_end: usize,
+}
+
 /// Output from structure (analyze_crate_structure) operation
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct StructureOutput {
@@ -39,6 +53,107 @@ impl StructureOutput {
     }
 }
 
+/// A single Cargo target of a crate
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct CrateTarget {
+    pub name: String,
+    /// One of "lib", "bin", "example", "test" or "bench"
+    pub kind: String,
+    /// Path of the target's root source file, relative to the package directory
+    pub path: String,
+}
+
+/// Output from list_crate_targets operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ListCrateTargetsOutput {
+    pub targets: Vec<CrateTarget>,
+}
+
+impl ListCrateTargetsOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// Output from get_crate_edition operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct GetCrateEditionOutput {
+    /// Rust edition of the crate (2015, 2018, 2021 or 2024)
+    pub edition: String,
+    /// Minimum supported Rust version declared with `rust-version`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rust_version: Option<String>,
+    /// Whether the crate is on edition 2024 and may use its syntax and semantics
+    pub edition_2024: bool,
+}
+
+impl GetCrateEditionOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// A reference to an item in the crate's own examples or tests
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct UsageExample {
+    /// Path relative to the package directory (e.g. "examples/demo.rs")
+    pub file: String,
+    /// 1-based line of the reference
+    pub line: usize,
+    /// The referencing line with the lines around it
+    pub snippet: String,
+}
+
+/// Output from get_usage_examples operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct GetUsageExamplesOutput {
+    pub usages: Vec<UsageExample>,
+    /// Whether more usages exist than were returned
+    pub truncated: bool,
+}
+
+impl GetUsageExamplesOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// A file of a crate's source tree
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct SourceFile {
+    /// Path relative to the package directory (e.g. "src/lib.rs")
+    pub path: String,
+    /// Size in bytes, present when stats were requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// Number of lines, present when stats were requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lines: Option<usize>,
+}
+
+/// Output from get_crate_source_tree operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct GetSourceTreeOutput {
+    pub files: Vec<SourceFile>,
+    /// Whether the crate has more files than were returned
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+impl GetSourceTreeOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
 /// Error output for analysis tools
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct AnalysisErrorOutput {
@@ -74,11 +189,19 @@ mod tests {
                 name: "root".to_string(),
                 path: "".to_string(),
                 visibility: "public".to_string(),
+                source: None,
                 children: Some(vec![StructureNode {
                     kind: "struct".to_string(),
                     name: "MyStruct".to_string(),
                     path: "my_mod".to_string(),
                     visibility: "public".to_string(),
+                    source: Some(SourceLocation {
+                        filename: "src/my_mod.rs".to_string(),
+                        line_start: 3,
+                        column_start: 0,
+                        line_end: 5,
+                        column_end: 1,
+                    }),
                     children: None,
                 }]),
             },
@@ -0,0 +1,160 @@
+//! Source file tree of a cached package
+//!
+//! Lists the files an agent is most likely to read: everything under `src/`
+//! and `examples/`, plus `Cargo.toml` and `build.rs`. It is a file system view
+//! complementing the module tree of `structure`, to pick the files to open
+//! with `get_item_source`.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::analysis::outputs::SourceFile;
+
+/// Maximum number of files listed for one package
+pub const MAX_SOURCE_TREE_FILES: usize = 2000;
+
+/// Top-level files listed when they exist
+const ROOT_FILES: [&str; 2] = ["Cargo.toml", "build.rs"];
+
+/// Directories listed recursively when they exist
+const ROOT_DIRS: [&str; 2] = ["src", "examples"];
+
+/// Files of a package and whether more were left out
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceTree {
+    pub files: Vec<SourceFile>,
+    /// Whether the package has more files than were returned
+    pub truncated: bool,
+}
+
+/// List the source files of the package at `package_dir`, in path order within each directory
+///
+/// With `include_stats`, each file's size in bytes and number of lines are read too.
+/// Symlinks are skipped, so the listing stays inside the package.
+pub fn source_tree(package_dir: &Path, include_stats: bool) -> Result<SourceTree> {
+    let mut paths = Vec::new();
+    for file in ROOT_FILES {
+        if package_dir.join(file).is_file() {
+            paths.push(package_dir.join(file));
+        }
+    }
+    for dir in ROOT_DIRS {
+        collect_files(&package_dir.join(dir), &mut paths)?;
+    }
+
+    let truncated = paths.len() > MAX_SOURCE_TREE_FILES;
+    paths.truncate(MAX_SOURCE_TREE_FILES);
+
+    let files = paths
+        .into_iter()
+        .map(|path| {
+            let relative = path
+                .strip_prefix(package_dir)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            let (size, lines) = if include_stats {
+                let content = fs::read(&path)
+                    .with_context(|| format!("Failed to read source file: {}", path.display()))?;
+                (Some(content.len() as u64), Some(count_lines(&content)))
+            } else {
+                (None, None)
+            };
+            Ok(SourceFile {
+                path: relative,
+                size,
+                lines,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(SourceTree { files, truncated })
+}
+
+/// Append the files below `dir` to `paths` in path order, nothing if it does not exist
+fn collect_files(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let mut entries = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(&entry.path(), paths)?;
+        } else if file_type.is_file() {
+            paths.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/// Number of lines in `content`, counting a last line without a newline
+fn count_lines(content: &[u8]) -> usize {
+    let newlines = content.iter().filter(|&&byte| byte == b'\n').count();
+    if content.last().is_some_and(|&byte| byte != b'\n') {
+        newlines + 1
+    } else {
+        newlines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) -> Result<()> {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_count_lines() {
+        assert_eq!(count_lines(b""), 0);
+        assert_eq!(count_lines(b"one"), 1);
+        assert_eq!(count_lines(b"one\n"), 1);
+        assert_eq!(count_lines(b"one\ntwo"), 2);
+    }
+
+    #[test]
+    fn test_source_tree() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        write(root, "Cargo.toml", "[package]\nname = \"demo\"\n")?;
+        write(root, "src/lib.rs", "pub mod parser;\n")?;
+        write(root, "src/parser/mod.rs", "pub fn parse() {}\n")?;
+        write(root, "examples/demo.rs", "fn main() {}")?;
+        write(root, "tests/integration.rs", "#[test]\nfn it_works() {}\n")?;
+        write(root, "README.md", "# demo\n")?;
+
+        let tree = source_tree(root, false)?;
+        let paths: Vec<&str> = tree.files.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "Cargo.toml",
+                "src/lib.rs",
+                "src/parser/mod.rs",
+                "examples/demo.rs"
+            ]
+        );
+        assert!(!tree.truncated);
+        assert!(tree.files.iter().all(|file| file.size.is_none()));
+
+        let tree = source_tree(root, true)?;
+        let manifest = &tree.files[0];
+        assert_eq!(manifest.size, Some(24));
+        assert_eq!(manifest.lines, Some(2));
+        let example = &tree.files[3];
+        assert_eq!(example.lines, Some(1));
+
+        Ok(())
+    }
+}
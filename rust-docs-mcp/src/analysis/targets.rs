@@ -0,0 +1,288 @@
+//! Cargo target discovery for cached crates
+//!
+//! Lists the library, binary, example, test and bench targets of a package by
+//! reading the explicit `[lib]`, `[[bin]]`, `[[example]]`, `[[test]]` and
+//! `[[bench]]` sections of its `Cargo.toml` and applying Cargo's target
+//! auto-discovery rules for the conventional directories.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use toml::Value;
+
+use crate::analysis::outputs::CrateTarget;
+
+/// A target kind together with where Cargo looks for it
+struct TargetKind {
+    /// Kind name reported in the output (e.g. "bin")
+    kind: &'static str,
+    /// Array-of-tables key in Cargo.toml (e.g. "bin" for `[[bin]]`)
+    section: &'static str,
+    /// `[package]` flag that disables auto-discovery (e.g. "autobins")
+    auto_flag: &'static str,
+    /// Directory scanned for auto-discovered targets
+    dir: &'static str,
+}
+
+const TARGET_KINDS: [TargetKind; 4] = [
+    TargetKind {
+        kind: "bin",
+        section: "bin",
+        auto_flag: "autobins",
+        dir: "src/bin",
+    },
+    TargetKind {
+        kind: "example",
+        section: "example",
+        auto_flag: "autoexamples",
+        dir: "examples",
+    },
+    TargetKind {
+        kind: "test",
+        section: "test",
+        auto_flag: "autotests",
+        dir: "tests",
+    },
+    TargetKind {
+        kind: "bench",
+        section: "bench",
+        auto_flag: "autobenches",
+        dir: "benches",
+    },
+];
+
+/// Discover all targets of the package rooted at `package_dir`
+///
+/// Targets are returned in the order lib, bin, example, test, bench, sorted by
+/// name within each kind. Paths are relative to `package_dir`.
+pub fn discover_targets(package_dir: &Path) -> Result<Vec<CrateTarget>> {
+    let manifest_path = package_dir.join("Cargo.toml");
+    let content = fs::read_to_string(&manifest_path)
+        .with_context(|| format!("Failed to read Cargo.toml at {}", manifest_path.display()))?;
+    let manifest: Value = toml::from_str(&content)
+        .with_context(|| format!("Failed to parse Cargo.toml at {}", manifest_path.display()))?;
+
+    let package = manifest.get("package");
+    let package_name = package
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+        .unwrap_or_default();
+
+    let mut targets = Vec::new();
+
+    if let Some(lib) = discover_lib(package_dir, &manifest, package_name) {
+        targets.push(lib);
+    }
+
+    for target_kind in &TARGET_KINDS {
+        // Explicit targets take precedence over auto-discovered ones with the same name
+        let mut by_name = BTreeMap::new();
+
+        let auto_enabled = package
+            .and_then(|p| p.get(target_kind.auto_flag))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+        if auto_enabled {
+            for (name, path) in autodiscover(package_dir, target_kind.dir) {
+                by_name.insert(name, path);
+            }
+
+            if target_kind.kind == "bin" && package_dir.join("src/main.rs").is_file() {
+                by_name.insert(package_name.to_string(), "src/main.rs".to_string());
+            }
+        }
+
+        let explicit = manifest
+            .get(target_kind.section)
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten();
+        for table in explicit {
+            let Some(name) = table.get("name").and_then(|n| n.as_str()) else {
+                continue;
+            };
+            let path = table
+                .get("path")
+                .and_then(|p| p.as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| {
+                    default_target_path(package_dir, target_kind, name, package_name)
+                });
+            by_name.insert(name.to_string(), path);
+        }
+
+        targets.extend(by_name.into_iter().map(|(name, path)| CrateTarget {
+            name,
+            kind: target_kind.kind.to_string(),
+            path,
+        }));
+    }
+
+    Ok(targets)
+}
+
+/// Find the library target, declared via `[lib]` or by the presence of `src/lib.rs`
+fn discover_lib(package_dir: &Path, manifest: &Value, package_name: &str) -> Option<CrateTarget> {
+    let lib = manifest.get("lib");
+    let path = lib
+        .and_then(|l| l.get("path"))
+        .and_then(|p| p.as_str())
+        .map(str::to_string)
+        .or_else(|| {
+            package_dir
+                .join("src/lib.rs")
+                .is_file()
+                .then(|| "src/lib.rs".to_string())
+        })?;
+
+    let name = lib
+        .and_then(|l| l.get("name"))
+        .and_then(|n| n.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| package_name.replace('-', "_"));
+
+    Some(CrateTarget {
+        name,
+        kind: "lib".to_string(),
+        path,
+    })
+}
+
+/// Auto-discover targets in `dir`: `<dir>/<name>.rs` and `<dir>/<name>/main.rs`
+fn autodiscover(package_dir: &Path, dir: &str) -> Vec<(String, String)> {
+    let Ok(entries) = fs::read_dir(package_dir.join(dir)) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
+
+            if path.is_file() {
+                let name = file_name.strip_suffix(".rs")?;
+                Some((name.to_string(), format!("{dir}/{file_name}")))
+            } else if path.join("main.rs").is_file() {
+                Some((file_name.clone(), format!("{dir}/{file_name}/main.rs")))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Path Cargo infers for an explicit target that does not set `path`
+fn default_target_path(
+    package_dir: &Path,
+    target_kind: &TargetKind,
+    name: &str,
+    package_name: &str,
+) -> String {
+    // A `[[bin]]` named after the package defaults to src/main.rs
+    if target_kind.kind == "bin"
+        && name == package_name
+        && package_dir.join("src/main.rs").is_file()
+    {
+        return "src/main.rs".to_string();
+    }
+
+    let dir_path = format!("{}/{name}/main.rs", target_kind.dir);
+    if package_dir.join(&dir_path).is_file() {
+        dir_path
+    } else {
+        format!("{}/{name}.rs", target_kind.dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn touch(root: &Path, path: &str) -> Result<()> {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, "fn main() {}")?;
+        Ok(())
+    }
+
+    fn kinds_and_names(targets: &[CrateTarget]) -> Vec<(&str, &str)> {
+        targets
+            .iter()
+            .map(|t| (t.kind.as_str(), t.name.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_discover_autodetected_targets() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"my-tool\"\nversion = \"0.1.0\"\n",
+        )?;
+        touch(root, "src/lib.rs")?;
+        touch(root, "src/main.rs")?;
+        touch(root, "src/bin/helper.rs")?;
+        touch(root, "src/bin/multi/main.rs")?;
+        touch(root, "examples/demo.rs")?;
+        touch(root, "tests/integration.rs")?;
+        touch(root, "benches/speed.rs")?;
+
+        let targets = discover_targets(root)?;
+        assert_eq!(
+            kinds_and_names(&targets),
+            vec![
+                ("lib", "my_tool"),
+                ("bin", "helper"),
+                ("bin", "multi"),
+                ("bin", "my-tool"),
+                ("example", "demo"),
+                ("test", "integration"),
+                ("bench", "speed"),
+            ]
+        );
+
+        let multi = targets.iter().find(|t| t.name == "multi").unwrap();
+        assert_eq!(multi.path, "src/bin/multi/main.rs");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_discover_explicit_targets() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        fs::write(
+            root.join("Cargo.toml"),
+            r#"
+[package]
+name = "cli"
+version = "0.1.0"
+autoexamples = false
+
+[[bin]]
+name = "cli-main"
+path = "cmd/main.rs"
+
+[[example]]
+name = "declared"
+"#,
+        )?;
+        touch(root, "cmd/main.rs")?;
+        touch(root, "examples/declared.rs")?;
+        touch(root, "examples/ignored.rs")?;
+
+        let targets = discover_targets(root)?;
+        assert_eq!(
+            kinds_and_names(&targets),
+            vec![("bin", "cli-main"), ("example", "declared")]
+        );
+        assert_eq!(targets[0].path, "cmd/main.rs");
+        assert_eq!(targets[1].path, "examples/declared.rs");
+
+        Ok(())
+    }
+}
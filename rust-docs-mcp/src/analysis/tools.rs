@@ -1,11 +1,18 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
 
-use crate::analysis::outputs::{AnalysisErrorOutput, StructureNode, StructureOutput};
+use crate::analysis::edition::read_edition;
+use crate::analysis::outputs::{
+    AnalysisErrorOutput, GetCrateEditionOutput, GetSourceTreeOutput, GetUsageExamplesOutput,
+    ListCrateTargetsOutput, SourceLocation, StructureNode, StructureOutput,
+};
+use crate::analysis::source_tree::source_tree;
+use crate::analysis::targets::discover_targets;
+use crate::analysis::usages::find_usages;
 use crate::cache::{CrateCache, workspace::WorkspaceHandler};
 
 // Use StructureNode from outputs module instead
@@ -70,6 +77,78 @@ pub struct AnalyzeCrateStructureParams {
     pub max_depth: Option<i64>,
 }
 
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ListCrateTargetsParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+
+    #[schemars(description = "The version of the crate")]
+    pub version: String,
+
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct GetCrateEditionParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+
+    #[schemars(description = "The version of the crate")]
+    pub version: String,
+
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct GetCrateSourceTreeParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+
+    #[schemars(description = "The version of the crate")]
+    pub version: String,
+
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+
+    #[schemars(description = "Include each file's size in bytes and line count (default: false)")]
+    pub include_stats: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct GetUsageExamplesParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+
+    #[schemars(description = "The version of the crate")]
+    pub version: String,
+
+    #[schemars(
+        description = "Name of the item to find usages of (e.g., 'Version' or 'Version::parse'), matched as a whole word"
+    )]
+    pub item_name: String,
+
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+
+    #[schemars(
+        description = "Number of context lines to include before and after each usage (default: 3)"
+    )]
+    pub context_lines: Option<i64>,
+
+    #[schemars(description = "Maximum number of usages to return (default: 10, max: 50)")]
+    pub limit: Option<i64>,
+}
+
 #[derive(Debug, Clone)]
 pub struct AnalysisTools {
     cache: Arc<RwLock<CrateCache>>,
@@ -85,6 +164,8 @@ impl AnalysisTools {
         params: AnalyzeCrateStructureParams,
     ) -> Result<StructureOutput, AnalysisErrorOutput> {
         let cache = self.cache.write().await;
+        // The source is read after the lock is released, so keep it from being removed
+        let _pin = cache.pin(&params.crate_name, &params.version);
 
         // Ensure the crate source is available (without requiring docs)
         match cache
@@ -118,6 +199,144 @@ impl AnalysisTools {
             ))),
         }
     }
+
+    pub async fn list_crate_targets(
+        &self,
+        params: ListCrateTargetsParams,
+    ) -> Result<ListCrateTargetsOutput, AnalysisErrorOutput> {
+        let cache = self.cache.write().await;
+        let _pin = cache.pin(&params.crate_name, &params.version);
+
+        // Only the source is needed, so this also works for binary-only packages
+        let source_path = cache
+            .ensure_crate_or_member_source(
+                &params.crate_name,
+                &params.version,
+                params.member.as_deref(),
+                None, // Use default source
+            )
+            .await
+            .map_err(|e| {
+                AnalysisErrorOutput::new(format!("Failed to ensure crate source is available: {e}"))
+            })?;
+        drop(cache);
+
+        match discover_targets(&source_path) {
+            Ok(targets) => Ok(ListCrateTargetsOutput { targets }),
+            Err(e) => Err(AnalysisErrorOutput::new(format!(
+                "Failed to list crate targets: {e:#}"
+            ))),
+        }
+    }
+
+    pub async fn get_crate_edition(
+        &self,
+        params: GetCrateEditionParams,
+    ) -> Result<GetCrateEditionOutput, AnalysisErrorOutput> {
+        let cache = self.cache.write().await;
+        let _pin = cache.pin(&params.crate_name, &params.version);
+
+        let source_path = cache
+            .ensure_crate_or_member_source(
+                &params.crate_name,
+                &params.version,
+                params.member.as_deref(),
+                None, // Use default source
+            )
+            .await
+            .map_err(|e| {
+                AnalysisErrorOutput::new(format!("Failed to ensure crate source is available: {e}"))
+            })?;
+        // Workspace-inherited values are resolved against the crate's root manifest
+        let root_path = if params.member.is_some() {
+            cache
+                .ensure_crate_or_member_source(&params.crate_name, &params.version, None, None)
+                .await
+                .map_err(|e| {
+                    AnalysisErrorOutput::new(format!(
+                        "Failed to ensure crate source is available: {e}"
+                    ))
+                })?
+        } else {
+            source_path.clone()
+        };
+        drop(cache);
+
+        match read_edition(&source_path, &root_path) {
+            Ok(edition) => Ok(GetCrateEditionOutput {
+                edition_2024: edition.edition == "2024",
+                edition: edition.edition,
+                rust_version: edition.rust_version,
+            }),
+            Err(e) => Err(AnalysisErrorOutput::new(format!(
+                "Failed to read crate edition: {e:#}"
+            ))),
+        }
+    }
+
+    pub async fn get_crate_source_tree(
+        &self,
+        params: GetCrateSourceTreeParams,
+    ) -> Result<GetSourceTreeOutput, AnalysisErrorOutput> {
+        let cache = self.cache.write().await;
+        let _pin = cache.pin(&params.crate_name, &params.version);
+
+        let source_path = cache
+            .ensure_crate_or_member_source(
+                &params.crate_name,
+                &params.version,
+                params.member.as_deref(),
+                None, // Use default source
+            )
+            .await
+            .map_err(|e| {
+                AnalysisErrorOutput::new(format!("Failed to ensure crate source is available: {e}"))
+            })?;
+        drop(cache);
+
+        match source_tree(&source_path, params.include_stats.unwrap_or(false)) {
+            Ok(tree) => Ok(GetSourceTreeOutput {
+                files: tree.files,
+                truncated: tree.truncated,
+            }),
+            Err(e) => Err(AnalysisErrorOutput::new(format!(
+                "Failed to list crate source files: {e:#}"
+            ))),
+        }
+    }
+
+    pub async fn get_usage_examples(
+        &self,
+        params: GetUsageExamplesParams,
+    ) -> Result<GetUsageExamplesOutput, AnalysisErrorOutput> {
+        let cache = self.cache.write().await;
+        let _pin = cache.pin(&params.crate_name, &params.version);
+
+        let source_path = cache
+            .ensure_crate_or_member_source(
+                &params.crate_name,
+                &params.version,
+                params.member.as_deref(),
+                None, // Use default source
+            )
+            .await
+            .map_err(|e| {
+                AnalysisErrorOutput::new(format!("Failed to ensure crate source is available: {e}"))
+            })?;
+        drop(cache);
+
+        let context_lines = params.context_lines.unwrap_or(3).max(0) as usize;
+        let limit = params.limit.unwrap_or(10).clamp(1, 50) as usize;
+        match find_usages(&source_path, &params.item_name, context_lines, limit) {
+            Ok(scan) => Ok(GetUsageExamplesOutput {
+                usages: scan.usages,
+                truncated: scan.truncated,
+            }),
+            Err(e) => Err(AnalysisErrorOutput::new(format!(
+                "Failed to find usage examples: {e:#}"
+            ))),
+        }
+    }
 }
 
 async fn analyze_with_cargo_modules(
@@ -137,12 +356,15 @@ async fn analyze_with_cargo_modules(
         };
 
         // Analyze the crate using the public API
-        let (crate_id, analysis_host, edition) = rust_analyzer_modules::analyze_crate(
-            manifest_path.parent().unwrap(),
-            package.as_deref(),
-            config,
-        )
-        .map_err(|e| format!("Failed to analyze crate: {e}"))?;
+        let crate_root = manifest_path.parent().unwrap();
+        let (crate_id, analysis_host, vfs, edition) =
+            rust_analyzer_modules::analyze_crate_with_vfs(crate_root, package.as_deref(), config)
+                .map_err(|e| format!("Failed to analyze crate: {e}"))?;
+
+        // Item paths from the VFS are canonical, so compare against the canonical root
+        let crate_root = crate_root
+            .canonicalize()
+            .unwrap_or_else(|_| crate_root.to_path_buf());
 
         let db = analysis_host.raw_database();
 
@@ -153,12 +375,12 @@ async fn analyze_with_cargo_modules(
             .map_err(|e| format!("Failed to build tree: {e}"))?;
 
         // Format the tree structure
-        let tree_node = format_tree(&tree, db, edition);
+        let tree_node = format_tree(&tree, db, &vfs, &crate_root, edition);
         Ok(StructureOutput {
             status: "success".to_string(),
             message: "Module structure analysis completed".to_string(),
             tree: tree_node,
-            usage_hint: "Use the 'path' and 'name' fields to search for items with search_items_preview tool".to_string(),
+            usage_hint: "Use the 'path' and 'name' fields to search for items with search_items_preview tool. The 'source' field gives each item's file and line range relative to the crate root".to_string(),
         })
     })
     .await;
@@ -174,11 +396,15 @@ async fn analyze_with_cargo_modules(
 fn format_tree(
     tree: &rust_analyzer_modules::Tree<rust_analyzer_modules::Item>,
     db: &ra_ap_ide::RootDatabase,
+    vfs: &ra_ap_vfs::Vfs,
+    crate_root: &Path,
     edition: ra_ap_ide::Edition,
 ) -> StructureNode {
     fn format_node(
         node: &rust_analyzer_modules::Tree<rust_analyzer_modules::Item>,
         db: &ra_ap_ide::RootDatabase,
+        vfs: &ra_ap_vfs::Vfs,
+        crate_root: &Path,
         edition: ra_ap_ide::Edition,
     ) -> StructureNode {
         let item = &node.node;
@@ -189,23 +415,36 @@ fn format_tree(
         let path = item.display_path(db, edition);
         let visibility = item.visibility(db, edition).to_string();
 
+        // Items defined outside the crate (e.g. in dependencies) have no usable source
+        let source = item.source_span(db, vfs).and_then(|span| {
+            let relative = span.file.strip_prefix(crate_root).ok()?;
+            Some(SourceLocation {
+                filename: relative.to_string_lossy().to_string(),
+                line_start: span.line_start,
+                column_start: span.column_start,
+                line_end: span.line_end,
+                column_end: span.column_end,
+            })
+        });
+
         StructureNode {
             kind,
             name,
             path,
             visibility,
+            source,
             children: if node.subtrees.is_empty() {
                 None
             } else {
                 Some(
                     node.subtrees
                         .iter()
-                        .map(|subtree| format_node(subtree, db, edition))
+                        .map(|subtree| format_node(subtree, db, vfs, crate_root, edition))
                         .collect(),
                 )
             },
         }
     }
 
-    format_node(tree, db, edition)
+    format_node(tree, db, vfs, crate_root, edition)
 }
@@ -0,0 +1,246 @@
+//! Usage examples of items from a crate's own examples and tests
+//!
+//! Scans the `examples/` and `tests/` directories of a package, and the
+//! `#[test]` functions and `#[cfg(test)]` modules under `src/`, for whole-word
+//! references to an item name. Each reference is returned with a window of
+//! the lines around it. The number of snippets and their total size are
+//! bounded so the result stays small enough to show to an agent.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::analysis::outputs::UsageExample;
+use crate::docs::query::source_window;
+
+/// Maximum characters of snippet text returned for one query
+const MAX_SNIPPET_CHARS: usize = 8000;
+
+/// Usages found in a package and whether more were left out
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageScan {
+    pub usages: Vec<UsageExample>,
+    /// Whether more usages exist than were returned
+    pub truncated: bool,
+}
+
+/// Find up to `limit` usages of `name` in the examples and tests of the package at `package_dir`
+///
+/// Examples come first, then integration tests, then unit tests in `src/`.
+/// A usage inside the snippet of an earlier one in the same file is skipped.
+pub fn find_usages(
+    package_dir: &Path,
+    name: &str,
+    context_lines: usize,
+    limit: usize,
+) -> Result<UsageScan> {
+    let name = name.trim();
+    if name.is_empty() {
+        anyhow::bail!("Item name must not be empty");
+    }
+
+    let mut usages = Vec::new();
+    let mut snippet_chars = 0;
+    let mut truncated = false;
+
+    'files: for (dir, tests_only) in [("examples", false), ("tests", false), ("src", true)] {
+        for path in rust_files(&package_dir.join(dir))? {
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read source file: {}", path.display()))?;
+            let lines: Vec<&str> = content.lines().collect();
+            let test_ranges = if tests_only {
+                test_line_ranges(&lines)
+            } else {
+                vec![(0, lines.len())]
+            };
+            let file = path
+                .strip_prefix(package_dir)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+
+            let mut covered_until = 0;
+            for (index, line) in lines.iter().enumerate() {
+                if index < covered_until
+                    || !test_ranges
+                        .iter()
+                        .any(|(start, end)| (*start..*end).contains(&index))
+                    || !mentions(line, name)
+                {
+                    continue;
+                }
+
+                let snippet = source_window(&lines, index + 1, index + 1, context_lines);
+                if usages.len() >= limit
+                    || snippet_chars + snippet.chars().count() > MAX_SNIPPET_CHARS
+                {
+                    truncated = true;
+                    break 'files;
+                }
+                snippet_chars += snippet.chars().count();
+                covered_until = index + context_lines + 1;
+                usages.push(UsageExample {
+                    file: file.clone(),
+                    line: index + 1,
+                    snippet,
+                });
+            }
+        }
+    }
+
+    Ok(UsageScan { usages, truncated })
+}
+
+/// The `.rs` files below `dir` in path order, or none if it does not exist
+fn rust_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+    for entry in
+        fs::read_dir(dir).with_context(|| format!("Failed to read directory: {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(rust_files(&path)?);
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Whether `line` contains `name` as a whole word
+fn mentions(line: &str, name: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(name).any(|(start, _)| {
+        let before = line[..start].chars().next_back();
+        let after = line[start + name.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
+
+/// 0-based line ranges of the items annotated with `#[test]` or `#[cfg(test)]`
+///
+/// An item extends from its attribute to the brace closing its body. Braces
+/// are counted without parsing, so braces in strings or comments can shift the
+/// end of a range.
+fn test_line_ranges(lines: &[&str]) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let attribute = lines[index].trim_start();
+        if !(attribute.starts_with("#[test]") || attribute.starts_with("#[cfg(test)]")) {
+            index += 1;
+            continue;
+        }
+
+        let start = index;
+        let mut depth = 0usize;
+        let mut opened = false;
+        while index < lines.len() {
+            for c in lines[index].chars() {
+                match c {
+                    '{' => {
+                        depth += 1;
+                        opened = true;
+                    }
+                    '}' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+            }
+            index += 1;
+            if opened && depth == 0 {
+                break;
+            }
+        }
+        ranges.push((start, index));
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_mentions_whole_words() {
+        assert!(mentions("let v = Version::parse(\"1.0.0\");", "Version"));
+        assert!(mentions(
+            "let v = Version::parse(\"1.0.0\");",
+            "Version::parse"
+        ));
+        assert!(!mentions("let req = VersionReq::STAR;", "Version"));
+        assert!(!mentions("let my_Version = 1;", "Version"));
+    }
+
+    #[test]
+    fn test_test_line_ranges() {
+        let source = "\
+pub fn parse() {}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn works() {
+        parse();
+    }
+}
+";
+        let lines: Vec<&str> = source.lines().collect();
+        assert_eq!(test_line_ranges(&lines), [(2, 9)]);
+    }
+
+    #[test]
+    fn test_find_usages() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src"))?;
+        fs::create_dir_all(root.join("examples"))?;
+        fs::create_dir_all(root.join("tests"))?;
+        fs::write(
+            root.join("src/lib.rs"),
+            "pub struct Parser;\n\nimpl Parser {\n    pub fn new() -> Parser { Parser }\n}\n\n#[test]\nfn builds() {\n    let _ = Parser::new();\n}\n",
+        )?;
+        fs::write(
+            root.join("examples/demo.rs"),
+            "fn main() {\n    let parser = demo::Parser::new();\n}\n",
+        )?;
+        fs::write(
+            root.join("tests/parse.rs"),
+            "#[test]\nfn parses() {\n    let _ = demo::Parser::new();\n}\n",
+        )?;
+
+        let scan = find_usages(root, "Parser", 1, 10)?;
+        let found: Vec<(&str, usize)> = scan
+            .usages
+            .iter()
+            .map(|usage| (usage.file.as_str(), usage.line))
+            .collect();
+        // The definition and impl in src/ are not test code
+        assert_eq!(
+            found,
+            [
+                ("examples/demo.rs", 2),
+                ("tests/parse.rs", 3),
+                ("src/lib.rs", 9)
+            ]
+        );
+        assert!(!scan.truncated);
+        assert_eq!(
+            scan.usages[0].snippet,
+            "fn main() {\n    let parser = demo::Parser::new();\n}"
+        );
+
+        let scan = find_usages(root, "Parser", 1, 2)?;
+        assert_eq!(scan.usages.len(), 2);
+        assert!(scan.truncated);
+
+        assert!(find_usages(root, "Missing", 1, 10)?.usages.is_empty());
+        assert!(find_usages(root, " ", 1, 10).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+//! `.crate` archive handling
+//!
+//! A `.crate` file is a gzipped tarball holding a single package under a
+//! `name-version/` directory. crates.io downloads use this format, and so do
+//! vendored dependencies and files copied from private registries, which can
+//! be cached directly with the `archive` source type.
+
+use crate::cache::constants::CARGO_TOML;
+use anyhow::{Context, Result, anyhow, bail};
+use flate2::read::GzDecoder;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use tar::Archive;
+use toml::Value;
+
+/// File extensions accepted as crate archives
+const ARCHIVE_EXTENSIONS: &[&str] = &[".crate", ".tar.gz", ".tgz"];
+
+/// Whether `path` names a crate archive rather than a directory
+pub fn is_crate_archive(path: &str) -> bool {
+    ARCHIVE_EXTENSIONS.iter().any(|ext| path.ends_with(ext))
+}
+
+/// Unpack the crate archive at `archive_path` into `dest`
+///
+/// The top-level `name-version/` directory is stripped, and entries that would
+/// escape `dest` are skipped.
+pub fn unpack_crate_archive(archive_path: &Path, dest: &Path) -> Result<()> {
+    let tar_gz = File::open(archive_path)
+        .with_context(|| format!("Failed to open crate archive: {}", archive_path.display()))?;
+    let tar = GzDecoder::new(tar_gz);
+    let mut archive = Archive::new(tar);
+
+    // Extract with proper path handling
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?;
+
+        // Skip the top-level directory (crate-version/)
+        let components: Vec<_> = path.components().collect();
+        if components.len() > 1 {
+            let relative_path: PathBuf = components[1..].iter().collect();
+
+            // Validate that the path doesn't escape the destination directory
+            // Check for path traversal attempts
+            let has_parent_refs = relative_path
+                .components()
+                .any(|c| matches!(c, Component::ParentDir));
+
+            if has_parent_refs {
+                tracing::warn!(
+                    "Skipping entry with parent directory reference: {}",
+                    path.display()
+                );
+                continue;
+            }
+
+            let dest_path = dest.join(&relative_path);
+
+            // Additional validation: ensure the destination is within dest
+            let canonical_source = dest.canonicalize().unwrap_or_else(|_| dest.to_path_buf());
+
+            if let Ok(canonical_dest) = dest_path.canonicalize() {
+                if !canonical_dest.starts_with(&canonical_source) {
+                    tracing::warn!(
+                        "Skipping entry that would escape destination: {}",
+                        path.display()
+                    );
+                    continue;
+                }
+            } else if let Some(parent) = dest_path.parent() {
+                // For files that don't exist yet, check the parent
+                if matches!(parent.canonicalize(), Ok(canonical_parent) if !canonical_parent.starts_with(&canonical_source))
+                {
+                    tracing::warn!(
+                        "Skipping entry with parent outside destination: {}",
+                        path.display()
+                    );
+                    continue;
+                }
+            }
+
+            if let Some(parent) = dest_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            entry.unpack(&dest_path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the package version from the `Cargo.toml` inside a crate archive
+pub fn archive_package_version(archive_path: &Path) -> Result<String> {
+    let tar_gz = File::open(archive_path)
+        .with_context(|| format!("Failed to open crate archive: {}", archive_path.display()))?;
+    let mut archive = Archive::new(GzDecoder::new(tar_gz));
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?;
+        let components: Vec<_> = path.components().collect();
+        if components.len() != 2 || components[1].as_os_str() != CARGO_TOML {
+            continue;
+        }
+
+        let mut content = String::new();
+        entry
+            .read_to_string(&mut content)
+            .context("Failed to read Cargo.toml from crate archive")?;
+        let manifest: Value =
+            toml::from_str(&content).context("Failed to parse Cargo.toml from crate archive")?;
+        return manifest
+            .get("package")
+            .and_then(|package| package.get("version"))
+            .and_then(|version| version.as_str())
+            .map(String::from)
+            .ok_or_else(|| anyhow!("No 'version' field found in [package] section"));
+    }
+
+    bail!(
+        "No Cargo.toml found in crate archive: {}",
+        archive_path.display()
+    )
+}
+
+/// Resolve the version to cache a crate archive under
+///
+/// Returns the version and whether it was read from the archive rather than
+/// provided. A provided version must match the archive's manifest.
+pub fn resolve_archive_version(
+    path: &str,
+    provided_version: Option<&str>,
+) -> Result<(String, bool)> {
+    let expanded_path =
+        shellexpand::full(path).with_context(|| format!("Failed to expand path: {path}"))?;
+    let archive_path = Path::new(expanded_path.as_ref());
+
+    if !archive_path.is_file() {
+        bail!("Crate archive does not exist: {}", archive_path.display());
+    }
+
+    let actual_version = archive_package_version(archive_path)?;
+    match provided_version {
+        Some(provided) if provided != actual_version => bail!(
+            "Version mismatch: provided version '{provided}' does not match actual version '{actual_version}' in the archive's Cargo.toml"
+        ),
+        Some(_) => Ok((actual_version, false)),
+        None => Ok((actual_version, true)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{Compression, write::GzEncoder};
+    use tempfile::TempDir;
+
+    /// Build a `.crate` archive with the given files under `name-version/`
+    fn build_archive(path: &Path, prefix: &str, files: &[(&str, &str)]) -> Result<()> {
+        let encoder = GzEncoder::new(File::create(path)?, Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, format!("{prefix}/{name}"), content.as_bytes())?;
+        }
+        builder.into_inner()?.finish()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_unpack_crate_archive() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let archive_path = temp_dir.path().join("demo-0.2.0.crate");
+        build_archive(
+            &archive_path,
+            "demo-0.2.0",
+            &[
+                (
+                    CARGO_TOML,
+                    "[package]\nname = \"demo\"\nversion = \"0.2.0\"\n",
+                ),
+                ("src/lib.rs", "pub fn demo() {}\n"),
+            ],
+        )?;
+
+        assert!(is_crate_archive("vendor/demo-0.2.0.crate"));
+        assert!(is_crate_archive("demo.tar.gz"));
+        assert!(!is_crate_archive("./vendor/demo"));
+
+        assert_eq!(archive_package_version(&archive_path)?, "0.2.0");
+        let archive = archive_path.to_str().unwrap();
+        assert_eq!(
+            resolve_archive_version(archive, None)?,
+            ("0.2.0".to_string(), true)
+        );
+        assert!(resolve_archive_version(archive, Some("0.1.0")).is_err());
+
+        let dest = temp_dir.path().join("source");
+        std::fs::create_dir_all(&dest)?;
+        unpack_crate_archive(&archive_path, &dest)?;
+        assert!(dest.join(CARGO_TOML).exists());
+        assert!(dest.join("src/lib.rs").exists());
+
+        Ok(())
+    }
+}
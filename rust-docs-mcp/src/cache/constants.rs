@@ -6,16 +6,30 @@ pub const CACHE_DIR: &str = "cache";
 pub const CRATES_DIR: &str = "crates";
 pub const MEMBERS_DIR: &str = "members";
 pub const SOURCE_DIR: &str = "source";
+pub const GIT_DIR: &str = "git";
 pub const SEARCH_INDEX_DIR: &str = "search_index";
 pub const TARGET_DIR: &str = "target";
+pub const PRIVATE_TARGET_DIR: &str = "target-private";
+pub const HIDDEN_TARGET_DIR: &str = "target-hidden";
+pub const PLATFORM_TARGET_DIR: &str = "target-platform";
+pub const TARGETS_DIR: &str = "targets";
 pub const DOC_DIR: &str = "doc";
 pub const BACKUP_DIR_PREFIX: &str = "rust-docs-mcp-backup";
 
 /// File names
 pub const METADATA_FILE: &str = "metadata.json";
 pub const DOCS_FILE: &str = "docs.json";
+pub const PRIVATE_DOCS_FILE: &str = "docs.private.json";
+pub const HIDDEN_DOCS_FILE: &str = "docs.hidden.json";
+pub const FULL_DOCS_FILE: &str = "docs.full.json";
 pub const DEPENDENCIES_FILE: &str = "dependencies.json";
+pub const LAYOUT_VERSION_FILE: &str = "CACHE_LAYOUT_VERSION";
+pub const DOCGEN_LOCK_FILE: &str = "docgen.lock";
+pub const LAST_ACCESSED_FILE: &str = "last_accessed";
 
 /// Cargo files
 pub const CARGO_TOML: &str = "Cargo.toml";
 pub const CARGO_LOCK: &str = "Cargo.lock";
+
+/// Value of the `members` parameter that selects a workspace's `default-members`
+pub const DEFAULT_MEMBERS_SENTINEL: &str = "default";
@@ -0,0 +1,533 @@
+//! Explanations of common documentation build failures
+//!
+//! A failed `cargo rustdoc` run reports the full cargo and compiler output,
+//! which buries the cause. [`explain_error`] recognizes the usual causes in
+//! that output and replaces it with a short explanation, keeping an excerpt of
+//! the log around the first error.
+//!
+//! Failed builds are also counted by category in the
+//! `rust_docs_mcp_docgen_failures_total` counter, so operators can notice when
+//! failures rise, e.g. after a toolchain change.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::cache::docgen::BinaryOnlyError;
+use crate::cache::proc_macro::ProcMacroError;
+
+/// Name of the counter of failed documentation builds, labeled by category
+pub const DOCGEN_FAILURES_METRIC: &str = "rust_docs_mcp_docgen_failures_total";
+
+/// Lines of the build log kept from the first error on
+const LOG_EXCERPT_LINES: usize = 20;
+
+/// Maximum characters of the build log kept in an explained failure
+const LOG_EXCERPT_CHARS: usize = 2000;
+
+/// Kind of a documentation build failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureCategory {
+    /// A build script could not find a library installed on the system
+    MissingSystemLibrary,
+    /// The crate does not compile with the feature flags it was built with
+    FeatureFlags,
+    /// The crate needs a newer edition or Rust version than the toolchain supports
+    EditionMismatch,
+    /// A procedural macro panicked while expanding
+    ProcMacroPanic,
+    /// Dependencies could not be downloaded
+    NetworkFetch,
+}
+
+impl FailureCategory {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MissingSystemLibrary => "missing_system_library",
+            Self::FeatureFlags => "feature_flags",
+            Self::EditionMismatch => "edition_mismatch",
+            Self::ProcMacroPanic => "proc_macro_panic",
+            Self::NetworkFetch => "network_fetch",
+        }
+    }
+}
+
+impl fmt::Display for FailureCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Error returned when documentation generation failed for a recognized reason
+///
+/// Callers can detect it with `error.downcast_ref::<DocgenFailure>()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocgenFailure {
+    pub category: FailureCategory,
+    /// What went wrong and how to address it
+    pub explanation: String,
+    /// Excerpt of the build log from the first error on
+    pub log: String,
+}
+
+impl fmt::Display for DocgenFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Documentation generation failed ({}): {}\n\nBuild log excerpt:\n{}",
+            self.category, self.explanation, self.log
+        )
+    }
+}
+
+impl std::error::Error for DocgenFailure {}
+
+/// Category label of a failed documentation build in the failure counter
+///
+/// Coarser than [`FailureCategory`]: every failure gets a label, including the
+/// ones the build log does not explain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureLabel {
+    /// The package has no library target
+    BinaryOnly,
+    /// A procedural macro crate could not be documented, or a macro panicked
+    ProcMacro,
+    /// The crate did not compile, for any reason not labeled otherwise
+    CompileError,
+    /// Dependencies or prebuilt docs could not be downloaded
+    Network,
+    /// rustdoc ran longer than its timeout
+    Timeout,
+    /// A workspace member could not be found or read
+    Workspace,
+}
+
+impl FailureLabel {
+    pub const ALL: [FailureLabel; 6] = [
+        Self::BinaryOnly,
+        Self::ProcMacro,
+        Self::CompileError,
+        Self::Network,
+        Self::Timeout,
+        Self::Workspace,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::BinaryOnly => "binary_only",
+            Self::ProcMacro => "proc_macro",
+            Self::CompileError => "compile_error",
+            Self::Network => "network",
+            Self::Timeout => "timeout",
+            Self::Workspace => "workspace",
+        }
+    }
+
+    /// Label of a documentation build `error`
+    ///
+    /// Recognizes the errors documentation generation returns; anything else
+    /// is counted as a compile error, since rustdoc failed on the crate.
+    pub fn of(error: &anyhow::Error) -> Self {
+        if error.downcast_ref::<BinaryOnlyError>().is_some() {
+            return Self::BinaryOnly;
+        }
+        if error.downcast_ref::<ProcMacroError>().is_some() {
+            return Self::ProcMacro;
+        }
+        if let Some(failure) = error.downcast_ref::<DocgenFailure>() {
+            return match failure.category {
+                FailureCategory::ProcMacroPanic => Self::ProcMacro,
+                FailureCategory::NetworkFetch => Self::Network,
+                FailureCategory::MissingSystemLibrary
+                | FailureCategory::FeatureFlags
+                | FailureCategory::EditionMismatch => Self::CompileError,
+            };
+        }
+        if format!("{error:#}").contains("Rustdoc execution timed out") {
+            return Self::Timeout;
+        }
+        Self::CompileError
+    }
+}
+
+impl fmt::Display for FailureLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Counter of failed documentation builds by label, shared by every clone
+#[derive(Debug, Clone, Default)]
+pub struct DocgenFailureCounter {
+    counts: Arc<[AtomicU64; FailureLabel::ALL.len()]>,
+}
+
+impl DocgenFailureCounter {
+    pub fn name(&self) -> &'static str {
+        DOCGEN_FAILURES_METRIC
+    }
+
+    /// Count one failure with `label`
+    pub fn record(&self, label: FailureLabel) {
+        self.counts[label as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Failures counted with `label` since the server started
+    pub fn get(&self, label: FailureLabel) -> u64 {
+        self.counts[label as usize].load(Ordering::Relaxed)
+    }
+
+    /// Counts by label name, leaving out labels without failures
+    pub fn snapshot(&self) -> BTreeMap<String, u64> {
+        FailureLabel::ALL
+            .iter()
+            .map(|label| (label.name().to_string(), self.get(*label)))
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+}
+
+/// Explain a failed documentation build from its log
+///
+/// Returns `None` when the log matches none of the known failure patterns, so
+/// the caller can report it unchanged.
+pub fn explain_error(log: &str) -> Option<DocgenFailure> {
+    let (category, explanation) = if let Some(cause) = find_network_failure(log) {
+        (
+            FailureCategory::NetworkFetch,
+            format!(
+                "Dependencies could not be downloaded ({cause}). Check the network connection \
+                or registry configuration, then retry."
+            ),
+        )
+    } else if let Some(library) = find_missing_system_library(log) {
+        let build_script = find_failed_build_script(log)
+            .map(|package| format!("The build script of {package}"))
+            .unwrap_or_else(|| "A build script".to_string());
+        (
+            FailureCategory::MissingSystemLibrary,
+            format!(
+                "{build_script} could not find the system library {library}. Install its \
+                development package (e.g. with the system package manager) and retry; this \
+                cannot be fixed from the crate's side."
+            ),
+        )
+    } else if log.contains("proc macro panicked") || log.contains("proc-macro derive panicked") {
+        (
+            FailureCategory::ProcMacroPanic,
+            "A procedural macro panicked while expanding. Macros that read files or \
+            environment variables at build time often fail outside the crate's own \
+            repository; try another version or source of the crate."
+                .to_string(),
+        )
+    } else if let Some(requirement) = find_edition_requirement(log) {
+        (
+            FailureCategory::EditionMismatch,
+            format!(
+                "The crate or one of its dependencies needs a newer toolchain: {requirement}. \
+                Use a more recent docgen toolchain (--docgen-toolchain) or an older version \
+                of the crate."
+            ),
+        )
+    } else if let Some(error) = find_feature_error(log) {
+        (
+            FailureCategory::FeatureFlags,
+            format!(
+                "The crate does not compile with the selected feature flags: {error}. Some \
+                features are mutually exclusive or need others enabled; documentation is \
+                generated with all features, default features and no default features in turn."
+            ),
+        )
+    } else {
+        return None;
+    };
+
+    Some(DocgenFailure {
+        category,
+        explanation,
+        log: log_excerpt(log),
+    })
+}
+
+/// The message reporting a network failure
+fn find_network_failure(log: &str) -> Option<String> {
+    const PATTERNS: [&str; 6] = [
+        "failed to download",
+        "failed to get `",
+        "Unable to update registry",
+        "failed to fetch",
+        "Couldn't resolve host",
+        "spurious network error",
+    ];
+    log.lines()
+        .find(|line| PATTERNS.iter().any(|pattern| line.contains(pattern)))
+        .map(|line| trim_error_prefix(line).to_string())
+}
+
+/// The system library a build script failed to find
+fn find_missing_system_library(log: &str) -> Option<String> {
+    for line in log.lines() {
+        let line = line.trim();
+        // pkg-config: "Package openssl was not found in the pkg-config search path."
+        if let Some(rest) = line.strip_prefix("Package ")
+            && let Some((package, _)) = rest.split_once(" was not found in the pkg-config")
+        {
+            return Some(package.to_string());
+        }
+        // Linker: "/usr/bin/ld: cannot find -lsqlite3: No such file or directory"
+        if let Some((_, rest)) = line.split_once("cannot find -l") {
+            let library = rest.split_whitespace().next().unwrap_or(rest);
+            return Some(library.trim_end_matches(':').to_string());
+        }
+        // system-deps: "The system library `alsa` required by crate `alsa-sys` was not found."
+        if let Some((_, rest)) = line.split_once("The system library ")
+            && let Some((library, _)) = rest.split_once(" required by")
+        {
+            return Some(library.trim_matches('`').to_string());
+        }
+    }
+    None
+}
+
+/// The package whose build script failed, e.g. "`openssl-sys v0.9.102`"
+fn find_failed_build_script(log: &str) -> Option<&str> {
+    log.lines().find_map(|line| {
+        line.split_once("failed to run custom build command for ")
+            .map(|(_, package)| package.trim())
+    })
+}
+
+/// The edition or Rust version requirement the toolchain does not meet
+fn find_edition_requirement(log: &str) -> Option<String> {
+    log.lines()
+        .find(|line| {
+            (line.contains("feature `edition") && line.contains("is required"))
+                || (line.contains("edition") && line.contains("is unstable"))
+                || line.contains("requires rustc ")
+        })
+        .map(|line| trim_error_prefix(line).to_string())
+}
+
+/// The first error mentioning features
+fn find_feature_error(log: &str) -> Option<String> {
+    log.lines()
+        .find(|line| {
+            line.trim_start().starts_with("error")
+                && (line.contains("feature") || line.contains("mutually exclusive"))
+        })
+        .map(|line| trim_error_prefix(line).to_string())
+}
+
+/// Strip the leading `error:` or `error[E0000]:` of a compiler or cargo message
+fn trim_error_prefix(line: &str) -> &str {
+    let line = line.trim();
+    match line
+        .strip_prefix("error")
+        .filter(|rest| rest.starts_with([':', '[']))
+    {
+        Some(rest) => rest
+            .split_once(": ")
+            .map_or(rest, |(_, message)| message)
+            .trim(),
+        None => line,
+    }
+}
+
+/// The log from its first error on, limited in lines and characters
+fn log_excerpt(log: &str) -> String {
+    let lines: Vec<&str> = log.lines().collect();
+    let start = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with("error"))
+        .unwrap_or(0);
+    let excerpt = lines[start..]
+        .iter()
+        .take(LOG_EXCERPT_LINES)
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    match excerpt.char_indices().nth(LOG_EXCERPT_CHARS) {
+        Some((truncate_at, _)) => format!("{}... (truncated)", &excerpt[..truncate_at]),
+        None => excerpt,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(log: &str) -> Option<FailureCategory> {
+        explain_error(log).map(|failure| failure.category)
+    }
+
+    #[test]
+    fn test_missing_system_library() {
+        let log = "\
+   Compiling openssl-sys v0.9.102
+error: failed to run custom build command for `openssl-sys v0.9.102`
+
+Caused by:
+  process didn't exit successfully: `/tmp/target/debug/build/openssl-sys/build-script-main` (exit status: 101)
+  --- stderr
+  Package openssl was not found in the pkg-config search path.
+  Perhaps you should add the directory containing `openssl.pc'
+";
+        let failure = explain_error(log).unwrap();
+        assert_eq!(failure.category, FailureCategory::MissingSystemLibrary);
+        assert!(failure.explanation.contains("`openssl-sys v0.9.102`"));
+        assert!(failure.explanation.contains("system library openssl"));
+        assert!(
+            failure
+                .log
+                .starts_with("error: failed to run custom build command")
+        );
+
+        let linker = "error: linking with `cc` failed: exit status: 1\n  = note: /usr/bin/ld: cannot find -lsqlite3: No such file or directory\n";
+        let failure = explain_error(linker).unwrap();
+        assert_eq!(failure.category, FailureCategory::MissingSystemLibrary);
+        assert!(failure.explanation.contains("sqlite3"));
+    }
+
+    #[test]
+    fn test_feature_flag_error() {
+        let log = "\
+   Compiling tls-choice v0.1.0
+error: features `rustls` and `native-tls` are mutually exclusive
+ --> src/lib.rs:2:1
+error: could not compile `tls-choice` (lib) due to 1 previous error
+";
+        let failure = explain_error(log).unwrap();
+        assert_eq!(failure.category, FailureCategory::FeatureFlags);
+        assert!(
+            failure
+                .explanation
+                .contains("features `rustls` and `native-tls` are mutually exclusive")
+        );
+
+        let missing = "error: Package `demo v0.1.0` does not have feature `serde`";
+        assert_eq!(category(missing), Some(FailureCategory::FeatureFlags));
+    }
+
+    #[test]
+    fn test_edition_mismatch() {
+        let log = "\
+error: failed to parse manifest at `/tmp/demo/Cargo.toml`
+
+Caused by:
+  feature `edition2024` is required
+";
+        assert_eq!(category(log), Some(FailureCategory::EditionMismatch));
+
+        let msrv = "error: package `demo v0.1.0` cannot be built because it requires rustc 1.90 or newer, while the currently active rustc version is 1.80.0";
+        let failure = explain_error(msrv).unwrap();
+        assert_eq!(failure.category, FailureCategory::EditionMismatch);
+        assert!(failure.explanation.contains("requires rustc 1.90"));
+    }
+
+    #[test]
+    fn test_proc_macro_panic() {
+        let log = "\
+error: proc macro panicked
+ --> src/lib.rs:3:1
+  |
+3 | include_assets!(\"../assets\");
+  |
+  = help: message: could not read directory ../assets
+";
+        assert_eq!(category(log), Some(FailureCategory::ProcMacroPanic));
+    }
+
+    #[test]
+    fn test_network_fetch_failure() {
+        let log = "\
+    Updating crates.io index
+error: failed to get `serde` as a dependency of package `demo v0.1.0`
+
+Caused by:
+  download of config.json failed
+
+Caused by:
+  [6] Couldn't resolve host name (Could not resolve host: index.crates.io)
+";
+        let failure = explain_error(log).unwrap();
+        assert_eq!(failure.category, FailureCategory::NetworkFetch);
+        assert!(failure.to_string().contains("(network_fetch)"));
+        assert!(failure.to_string().contains("Build log excerpt:"));
+    }
+
+    #[test]
+    fn test_unrecognized_failure() {
+        let log = "error[E0308]: mismatched types\n --> src/lib.rs:1:20\n";
+        assert_eq!(explain_error(log), None);
+    }
+
+    #[test]
+    fn test_failure_labels() {
+        let label = |error: anyhow::Error| FailureLabel::of(&error);
+        assert_eq!(
+            label(
+                BinaryOnlyError {
+                    package: "demo".to_string()
+                }
+                .into()
+            ),
+            FailureLabel::BinaryOnly
+        );
+        let network = explain_error("error: failed to download `serde v1.0.0`").unwrap();
+        assert_eq!(label(network.into()), FailureLabel::Network);
+        let panic = explain_error("error: proc macro panicked\n").unwrap();
+        assert_eq!(label(panic.into()), FailureLabel::ProcMacro);
+        assert_eq!(
+            label(anyhow::anyhow!(
+                "Rustdoc execution timed out after 600 seconds"
+            )),
+            FailureLabel::Timeout
+        );
+        assert_eq!(
+            label(anyhow::anyhow!("error[E0308]: mismatched types")),
+            FailureLabel::CompileError
+        );
+    }
+
+    #[test]
+    fn test_failure_counter() {
+        let counter = DocgenFailureCounter::default();
+        assert_eq!(counter.name(), DOCGEN_FAILURES_METRIC);
+        assert!(counter.snapshot().is_empty());
+
+        let shared = counter.clone();
+        shared.record(FailureLabel::CompileError);
+        shared.record(FailureLabel::CompileError);
+        counter.record(FailureLabel::Workspace);
+
+        assert_eq!(counter.get(FailureLabel::CompileError), 2);
+        assert_eq!(counter.get(FailureLabel::Timeout), 0);
+        assert_eq!(
+            counter.snapshot(),
+            [
+                ("compile_error".to_string(), 2),
+                ("workspace".to_string(), 1)
+            ]
+            .into_iter()
+            .collect()
+        );
+    }
+
+    #[test]
+    fn test_log_excerpt_is_truncated() {
+        let log = format!(
+            "   Compiling demo\n{}",
+            "error: feature `x` is broken\n".repeat(100)
+        );
+        let failure = explain_error(&log).unwrap();
+        assert_eq!(failure.log.lines().count(), LOG_EXCERPT_LINES);
+        assert!(!failure.log.contains("Compiling"));
+
+        let long_line = format!("error: feature {}", "x".repeat(5000));
+        let failure = explain_error(&long_line).unwrap();
+        assert!(failure.log.ends_with("... (truncated)"));
+        assert!(failure.log.chars().count() <= LOG_EXCERPT_CHARS + 16);
+    }
+}
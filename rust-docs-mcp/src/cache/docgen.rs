@@ -1,28 +1,254 @@
 //! Documentation generation for Rust crates
 //!
 //! This module handles running `cargo rustdoc` to generate JSON documentation
-//! for both regular crates and workspace members.
+//! for both regular crates and workspace members. When the docgen toolchain is
+//! not installed, crates.io crates fall back to the prebuilt JSON on docs.rs.
 
+use crate::analysis::targets::discover_targets;
 use crate::cache::constants::*;
-use crate::cache::downloader::ProgressCallback;
+use crate::cache::diagnosis::{self, DocgenFailureCounter, FailureLabel};
+use crate::cache::docsrs::{self, DOC_SOURCE_DOCSRS, DOCSRS_URL};
+use crate::cache::downloader::{CrateDownloader, ProgressCallback};
+use crate::cache::lock::LockFile;
+use crate::cache::proc_macro::{self, ProcMacroError};
 use crate::cache::storage::CacheStorage;
+use crate::cache::types::DocsVariant;
 use crate::cache::workspace::WorkspaceHandler;
 use crate::rustdoc;
 use crate::search::indexer::SearchIndexer;
 use anyhow::{Context, Result, bail};
+use std::fmt;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+/// How long to wait for another process generating the same documentation
+///
+/// Longer than the rustdoc timeout, so a lock is only taken over once its
+/// holder can no longer be running rustdoc.
+const DOCGEN_LOCK_TIMEOUT: Duration = Duration::from_secs(rustdoc::RUSTDOC_TIMEOUT_SECS + 600);
+
+/// Error returned for packages without a library target, which rustdoc cannot document
+///
+/// Callers can detect it with `error.downcast_ref::<BinaryOnlyError>()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BinaryOnlyError {
+    pub package: String,
+}
+
+impl fmt::Display for BinaryOnlyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Cannot generate documentation for binary-only package '{}'. \
+            This package contains only binary targets and no library to document. \
+            rustdoc can only generate documentation for library targets.",
+            self.package
+        )
+    }
+}
+
+impl std::error::Error for BinaryOnlyError {}
 
 /// Service for generating documentation from Rust crates
 #[derive(Debug, Clone)]
 pub struct DocGenerator {
     storage: CacheStorage,
+    toolchain: String,
+    /// Extra rustdoc arguments, validated by [`rustdoc::parse_rustdoc_args`]
+    rustdoc_args: Vec<String>,
+    client: reqwest::Client,
+    docsrs_url: String,
+    /// Failed builds by category, shared with the clones of this generator
+    failures: DocgenFailureCounter,
 }
 
 impl DocGenerator {
-    /// Create a new documentation generator
+    /// Create a new documentation generator using the pinned toolchain
     pub fn new(storage: CacheStorage) -> Self {
-        Self { storage }
+        Self::with_toolchain(storage, rustdoc::REQUIRED_TOOLCHAIN)
+    }
+
+    /// Create a new documentation generator that runs rustdoc with `toolchain`
+    pub fn with_toolchain(storage: CacheStorage, toolchain: impl Into<String>) -> Self {
+        Self {
+            storage,
+            toolchain: toolchain.into(),
+            rustdoc_args: Vec::new(),
+            client: CrateDownloader::build_http_client(),
+            docsrs_url: DOCSRS_URL.to_string(),
+            failures: DocgenFailureCounter::default(),
+        }
+    }
+
+    /// Download prebuilt documentation from `url` instead of docs.rs
+    pub fn with_docsrs_url(mut self, url: impl Into<String>) -> Self {
+        self.docsrs_url = url.into();
+        self
+    }
+
+    /// Base URL prebuilt documentation is downloaded from
+    pub fn docsrs_url(&self) -> &str {
+        &self.docsrs_url
+    }
+
+    /// Pass `args` to every rustdoc invocation, e.g. `--cfg docsrs`
+    ///
+    /// The arguments must have been checked with [`rustdoc::parse_rustdoc_args`].
+    pub fn with_rustdoc_args(mut self, args: Vec<String>) -> Self {
+        self.rustdoc_args = args;
+        self
+    }
+
+    /// Write generated docs through `storage`, e.g. after its permissions changed
+    pub fn with_storage(mut self, storage: CacheStorage) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// rustdoc settings for the package at `package_dir`
+    ///
+    /// Combines the configured rustdoc arguments with the settings the package
+    /// declares for docs.rs, so the generated docs match the ones on docs.rs.
+    fn rustdoc_options(&self, package_dir: &Path) -> rustdoc::RustdocOptions {
+        rustdoc::RustdocOptions {
+            extra_args: self.rustdoc_args.clone(),
+            docs_rs: docsrs::read_docs_rs_settings(package_dir),
+        }
+    }
+
+    /// Counter of failed documentation builds, by category
+    pub fn failures(&self) -> DocgenFailureCounter {
+        self.failures.clone()
+    }
+
+    /// Count a failed documentation build and pass its error on
+    fn record_failure(&self, error: anyhow::Error) -> anyhow::Error {
+        let label = FailureLabel::of(&error);
+        tracing::debug!("Counting documentation build failure as {}", label);
+        self.failures.record(label);
+        error
+    }
+
+    /// Get the toolchain used to invoke `cargo rustdoc`
+    pub fn toolchain(&self) -> &str {
+        &self.toolchain
+    }
+
+    /// Bail with a [`BinaryOnlyError`] if the package at `package_dir` has no library target
+    ///
+    /// Runs before `cargo rustdoc` so binary-only packages fail without a wasted
+    /// build. If the targets cannot be determined the check passes, and the
+    /// rustdoc error is handled after the build instead.
+    fn ensure_library_target(package_dir: &Path, package_name: &str) -> Result<()> {
+        match discover_targets(package_dir) {
+            Ok(targets) if !targets.iter().any(|target| target.kind == "lib") => {
+                Err(BinaryOnlyError {
+                    package: package_name.to_string(),
+                }
+                .into())
+            }
+            Ok(_) => Ok(()),
+            Err(e) => {
+                tracing::debug!(
+                    "Could not discover targets in {}: {}",
+                    package_dir.display(),
+                    e
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Replace a rustdoc failure for a proc-macro crate with a [`ProcMacroError`]
+    ///
+    /// The error then explains the limitation and lists the crate's macros
+    /// instead of only showing the compiler output. Other failures are passed
+    /// to [`Self::explain_build_failure`].
+    fn explain_proc_macro_failure(
+        package_dir: &Path,
+        package_name: &str,
+        error: anyhow::Error,
+    ) -> anyhow::Error {
+        if proc_macro::is_proc_macro(package_dir) {
+            ProcMacroError::new(package_dir, package_name, format!("{error:#}")).into()
+        } else {
+            Self::explain_build_failure(error)
+        }
+    }
+
+    /// Replace a rustdoc failure with a [`DocgenFailure`](diagnosis::DocgenFailure)
+    /// if its cause is recognized
+    ///
+    /// The explanation and a log excerpt then stand in for the full cargo output.
+    fn explain_build_failure(error: anyhow::Error) -> anyhow::Error {
+        match diagnosis::explain_error(&format!("{error:#}")) {
+            Some(failure) => failure.into(),
+            None => error,
+        }
+    }
+
+    /// Take the doc generation lock of a crate or workspace member
+    ///
+    /// Waits while another server process using the same cache generates the
+    /// same documentation, so callers should check for its output afterwards.
+    /// Variant and target builds pass their build directory name as `build`.
+    async fn acquire_docgen_lock(
+        &self,
+        name: &str,
+        version: &str,
+        member_path: Option<&str>,
+        build: Option<&str>,
+    ) -> Result<LockFile> {
+        let lock_path = self
+            .storage
+            .docgen_lock_path(name, version, member_path, build)?;
+        if let Some(parent) = lock_path.parent() {
+            self.storage.ensure_dir(parent)?;
+        }
+        LockFile::acquire(&lock_path, DOCGEN_LOCK_TIMEOUT).await
+    }
+
+    /// Modification time of a generated docs file, if it exists
+    ///
+    /// Variant and target docs are regenerated in place, so their callers
+    /// compare it before and after taking the lock to tell whether another
+    /// process wrote the docs while they waited.
+    fn docs_modified(docs_path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(docs_path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    /// Whether to download the docs of a crate from docs.rs instead of running rustdoc
+    ///
+    /// Only crates.io crates are on docs.rs, and they are only downloaded when
+    /// the docgen toolchain is not installed. Crates from other sources fail
+    /// with the toolchain error, since they can only be documented locally.
+    async fn use_docsrs(&self, name: &str, version: &str) -> Result<bool> {
+        let Err(toolchain_error) = rustdoc::validate_toolchain(&self.toolchain).await else {
+            return Ok(false);
+        };
+
+        let source = self
+            .storage
+            .load_metadata(name, version, None)
+            .map(|metadata| metadata.source)
+            .unwrap_or_else(|_| "crates.io".to_string());
+        if source != "crates.io" {
+            bail!(
+                "{toolchain_error:#}. Crates from {source} sources can only be documented locally; \
+                only crates.io crates fall back to the prebuilt documentation on docs.rs"
+            );
+        }
+
+        tracing::warn!(
+            "{:#}. Falling back to docs.rs for {}-{}",
+            toolchain_error,
+            name,
+            version
+        );
+        Ok(true)
     }
 
     /// Clean up the target directory to save disk space
@@ -56,7 +282,14 @@ impl DocGenerator {
         let source_path = self.storage.source_path(name, version)?;
         let docs_path = self.storage.docs_path(name, version, None)?;
 
-        // Check if docs already exist (another thread might have generated them)
+        if !source_path.exists() {
+            bail!("Source not found for {name}-{version}. Download it first.");
+        }
+
+        // Only one process runs rustdoc in the source directory at a time
+        let _lock = self.acquire_docgen_lock(name, version, None, None).await?;
+
+        // Check if docs already exist (another process might have generated them)
         if docs_path.exists() {
             tracing::info!(
                 "Docs already exist for {}-{}, skipping generation",
@@ -69,9 +302,9 @@ impl DocGenerator {
             return Ok(docs_path);
         }
 
-        if !source_path.exists() {
-            bail!("Source not found for {name}-{version}. Download it first.");
-        }
+        Self::ensure_library_target(&source_path, name).map_err(|e| self.record_failure(e))?;
+
+        let from_docsrs = self.use_docsrs(name, version).await?;
 
         tracing::info!("Generating documentation for {}-{}", name, version);
 
@@ -80,26 +313,50 @@ impl DocGenerator {
             callback(10);
         }
 
-        // Run cargo rustdoc with JSON output using unified function
-        rustdoc::run_cargo_rustdoc_json(&source_path, None, None).await?;
+        if from_docsrs {
+            let json = docsrs::fetch_docs_json(&self.client, &self.docsrs_url, name, version)
+                .await
+                .context("The docgen toolchain is not installed and the docs.rs fallback failed")
+                .inspect_err(|_| self.failures.record(FailureLabel::Network))?;
+            std::fs::write(&docs_path, json).context("Failed to write documentation to cache")?;
+        } else {
+            // Run cargo rustdoc with JSON output using unified function
+            rustdoc::run_cargo_rustdoc_json(
+                &self.toolchain,
+                &source_path,
+                None,
+                None,
+                false,
+                None,
+                &self.rustdoc_options(&source_path),
+            )
+            .await
+            .map_err(|e| {
+                self.record_failure(Self::explain_proc_macro_failure(&source_path, name, e))
+            })?;
+
+            // Find the generated JSON file in target/doc
+            let doc_dir = source_path.join(TARGET_DIR).join(DOC_DIR);
+            let lib_name = Self::lib_name(&source_path.join(CARGO_TOML), name);
+            let json_file = self.find_json_doc(&doc_dir, &lib_name)?;
+
+            // Copy the JSON file to our cache location
+            std::fs::copy(&json_file, &docs_path)
+                .context("Failed to copy documentation to cache")?;
+        }
 
         // Rustdoc complete - report 70%
         if let Some(ref callback) = progress_callback {
             callback(70);
         }
 
-        // Find the generated JSON file in target/doc
-        let doc_dir = source_path.join(TARGET_DIR).join(DOC_DIR);
-        let json_file = self.find_json_doc(&doc_dir, name)?;
-
-        // Copy the JSON file to our cache location
-        std::fs::copy(&json_file, &docs_path).context("Failed to copy documentation to cache")?;
-
         // Generate and save dependency information
         self.generate_dependencies(name, version).await?;
 
         // Update metadata to reflect that docs are now generated
         self.storage.save_metadata(name, version)?;
+        self.storage
+            .set_doc_source(name, version, from_docsrs.then_some(DOC_SOURCE_DOCSRS))?;
 
         // Report 80% before indexing
         if let Some(ref callback) = progress_callback {
@@ -143,6 +400,7 @@ impl DocGenerator {
         }
 
         if !member_full_path.exists() {
+            self.failures.record(FailureLabel::Workspace);
             bail!(
                 "Workspace member not found at path: {}",
                 member_full_path.display()
@@ -151,11 +409,32 @@ impl DocGenerator {
 
         // Get the actual package name from the member's Cargo.toml
         let member_cargo_toml = member_full_path.join(CARGO_TOML);
-        let package_name = WorkspaceHandler::get_package_name(&member_cargo_toml)?;
+        let package_name = WorkspaceHandler::get_package_name(&member_cargo_toml)
+            .inspect_err(|_| self.failures.record(FailureLabel::Workspace))?;
+
+        Self::ensure_library_target(&member_full_path, &package_name)
+            .map_err(|e| self.record_failure(e))?;
 
         // Use the full member path directly
         let docs_path = self.storage.docs_path(name, version, Some(member_path))?;
 
+        let _lock = self
+            .acquire_docgen_lock(name, version, Some(member_path), None)
+            .await?;
+
+        if docs_path.exists() {
+            tracing::info!(
+                "Docs for workspace member {} of {}-{} were generated by another process",
+                member_path,
+                name,
+                version
+            );
+            if let Some(callback) = progress_callback {
+                callback(100);
+            }
+            return Ok(docs_path);
+        }
+
         tracing::info!(
             "Generating documentation for workspace member {} (package: {}) in {}-{}",
             member_path,
@@ -180,15 +459,27 @@ impl DocGenerator {
 
         // Run cargo rustdoc with JSON output for the specific package using unified function
         rustdoc::run_cargo_rustdoc_json(
+            &self.toolchain,
             &source_path,
             Some(&package_name),
             Some(&member_target_dir),
+            false,
+            None,
+            &self.rustdoc_options(&member_full_path),
         )
-        .await?;
+        .await
+        .map_err(|e| {
+            self.record_failure(Self::explain_proc_macro_failure(
+                &member_full_path,
+                &package_name,
+                e,
+            ))
+        })?;
 
         // Find the generated JSON file in the member-specific target/doc directory
         let doc_dir = member_target_dir.join(DOC_DIR);
-        let json_file = self.find_json_doc(&doc_dir, &package_name)?;
+        let lib_name = Self::lib_name(&member_cargo_toml, &package_name);
+        let json_file = self.find_json_doc(&doc_dir, &lib_name)?;
 
         // Ensure the member directory exists in cache
         if let Some(parent) = docs_path.parent() {
@@ -228,9 +519,243 @@ impl DocGenerator {
         Ok(docs_path)
     }
 
+    /// Generate JSON documentation that includes private or hidden items
+    ///
+    /// The output is stored next to the regular docs so all variants can be
+    /// cached at the same time. Dependency information and the search index are
+    /// shared with the regular docs and are not regenerated.
+    pub async fn generate_variant_docs(
+        &self,
+        name: &str,
+        version: &str,
+        member_path: Option<&str>,
+        variant: DocsVariant,
+    ) -> Result<PathBuf> {
+        let source_path = self.storage.source_path(name, version)?;
+        if !source_path.exists() {
+            bail!("Source not found for {name}-{version}. Download it first.");
+        }
+
+        let (package_name, target_dir) = match member_path {
+            Some(member) => {
+                let member_cargo_toml = source_path.join(member).join(CARGO_TOML);
+                let package_name = WorkspaceHandler::get_package_name(&member_cargo_toml)
+                    .inspect_err(|_| self.failures.record(FailureLabel::Workspace))?;
+                let sanitized_member = member.replace(['/', '\\'], "-");
+                let target_dir =
+                    source_path.join(format!("{}-{sanitized_member}", variant.target_dir()));
+                (Some(package_name), target_dir)
+            }
+            None => (None, source_path.join(variant.target_dir())),
+        };
+
+        let docs_path = self
+            .storage
+            .variant_docs_path(name, version, member_path, variant)?;
+        let previous = Self::docs_modified(&docs_path);
+        let _lock = self
+            .acquire_docgen_lock(name, version, member_path, Some(variant.target_dir()))
+            .await?;
+        if Self::docs_modified(&docs_path).is_some_and(|modified| Some(modified) != previous) {
+            tracing::info!(
+                "Documentation with {} for {}-{} was generated by another process",
+                variant.items(),
+                name,
+                version
+            );
+            return Ok(docs_path);
+        }
+
+        tracing::info!(
+            "Generating documentation with {} for {}-{}{}",
+            variant.items(),
+            name,
+            version,
+            member_path
+                .map(|m| format!(" (member: {m})"))
+                .unwrap_or_default()
+        );
+
+        let mut options = self.rustdoc_options(&source_path.join(member_path.unwrap_or_default()));
+        if variant == DocsVariant::Hidden
+            && !options
+                .extra_args
+                .iter()
+                .any(|arg| arg == "--document-hidden-items")
+        {
+            options
+                .extra_args
+                .push("--document-hidden-items".to_string());
+        }
+
+        rustdoc::run_cargo_rustdoc_json(
+            &self.toolchain,
+            &source_path,
+            package_name.as_deref(),
+            Some(&target_dir),
+            variant == DocsVariant::Private,
+            None,
+            &options,
+        )
+        .await
+        .map_err(|e| self.record_failure(Self::explain_build_failure(e)))?;
+
+        let doc_dir = target_dir.join(DOC_DIR);
+        let lib_name = Self::lib_name(
+            &source_path
+                .join(member_path.unwrap_or_default())
+                .join(CARGO_TOML),
+            package_name.as_deref().unwrap_or(name),
+        );
+        let json_file = self.find_json_doc(&doc_dir, &lib_name)?;
+
+        if let Some(parent) = docs_path.parent() {
+            self.storage.ensure_dir(parent)?;
+        }
+        std::fs::copy(&json_file, &docs_path).with_context(|| {
+            format!(
+                "Failed to copy documentation with {} to cache",
+                variant.items()
+            )
+        })?;
+
+        // Clean up the variant's target directory to save space
+        if target_dir.exists() {
+            std::fs::remove_dir_all(&target_dir).with_context(|| {
+                format!("Failed to remove target directory {}", target_dir.display())
+            })?;
+        }
+
+        Ok(docs_path)
+    }
+
+    /// Generate JSON documentation for a specific target platform
+    ///
+    /// Like [`generate_variant_docs`](Self::generate_variant_docs), the output is
+    /// stored separately from the host docs (one file per target triple), and the
+    /// dependency information and search index of the host docs are reused.
+    pub async fn generate_target_docs(
+        &self,
+        name: &str,
+        version: &str,
+        member_path: Option<&str>,
+        target: &str,
+    ) -> Result<PathBuf> {
+        rustdoc::validate_target_triple(target)?;
+
+        let source_path = self.storage.source_path(name, version)?;
+        if !source_path.exists() {
+            bail!("Source not found for {name}-{version}. Download it first.");
+        }
+
+        // Each target builds in its own directory, so builds for different
+        // targets can run at the same time
+        let build_dir = format!("{PLATFORM_TARGET_DIR}-{target}");
+        let (package_name, target_dir) = match member_path {
+            Some(member) => {
+                let member_cargo_toml = source_path.join(member).join(CARGO_TOML);
+                let package_name = WorkspaceHandler::get_package_name(&member_cargo_toml)
+                    .inspect_err(|_| self.failures.record(FailureLabel::Workspace))?;
+                let sanitized_member = member.replace(['/', '\\'], "-");
+                let target_dir = source_path.join(format!("{build_dir}-{sanitized_member}"));
+                (Some(package_name), target_dir)
+            }
+            None => (None, source_path.join(&build_dir)),
+        };
+
+        let package_dir = source_path.join(member_path.unwrap_or_default());
+        if proc_macro::is_proc_macro(&package_dir) {
+            self.failures.record(FailureLabel::ProcMacro);
+            return Err(ProcMacroError::new(
+                &package_dir,
+                package_name.as_deref().unwrap_or(name),
+                "proc-macro crates are compiled for the host, so they have no \
+                documentation for other targets. Query them without 'target'",
+            )
+            .into());
+        }
+
+        let docs_path = self
+            .storage
+            .target_docs_path(name, version, member_path, target)?;
+        let previous = Self::docs_modified(&docs_path);
+        let _lock = self
+            .acquire_docgen_lock(name, version, member_path, Some(&build_dir))
+            .await?;
+        if Self::docs_modified(&docs_path).is_some_and(|modified| Some(modified) != previous) {
+            tracing::info!(
+                "Documentation for target {} of {}-{} was generated by another process",
+                target,
+                name,
+                version
+            );
+            return Ok(docs_path);
+        }
+
+        tracing::info!(
+            "Generating documentation for target {} for {}-{}{}",
+            target,
+            name,
+            version,
+            member_path
+                .map(|m| format!(" (member: {m})"))
+                .unwrap_or_default()
+        );
+
+        rustdoc::run_cargo_rustdoc_json(
+            &self.toolchain,
+            &source_path,
+            package_name.as_deref(),
+            Some(&target_dir),
+            false,
+            Some(target),
+            &self.rustdoc_options(&package_dir),
+        )
+        .await
+        .map_err(|e| self.record_failure(Self::explain_build_failure(e)))?;
+
+        // Cargo places cross-compiled output under a directory named after the target
+        let doc_dir = target_dir.join(target).join(DOC_DIR);
+        let lib_name = Self::lib_name(
+            &source_path
+                .join(member_path.unwrap_or_default())
+                .join(CARGO_TOML),
+            package_name.as_deref().unwrap_or(name),
+        );
+        let json_file = self.find_json_doc(&doc_dir, &lib_name)?;
+
+        if let Some(parent) = docs_path.parent() {
+            self.storage.ensure_dir(parent)?;
+        }
+        std::fs::copy(&json_file, &docs_path)
+            .context("Failed to copy target documentation to cache")?;
+
+        // Clean up the platform target directory to save space
+        if target_dir.exists() {
+            std::fs::remove_dir_all(&target_dir)
+                .context("Failed to remove platform target directory")?;
+        }
+
+        Ok(docs_path)
+    }
+
+    /// Name of the library target declared in a manifest, which rustdoc names its JSON after
+    ///
+    /// Falls back to `package_name` if the manifest cannot be read.
+    fn lib_name(cargo_toml_path: &Path, package_name: &str) -> String {
+        WorkspaceHandler::get_lib_name(cargo_toml_path).unwrap_or_else(|e| {
+            tracing::debug!(
+                "Could not read lib name from {}: {}",
+                cargo_toml_path.display(),
+                e
+            );
+            package_name.replace('-', "_")
+        })
+    }
+
     /// Find the JSON documentation file for a crate in the target/doc directory
     fn find_json_doc(&self, doc_dir: &Path, crate_name: &str) -> Result<PathBuf> {
-        // The JSON file is typically named after the crate, with hyphens replaced by underscores
+        // The JSON file is named after the lib target, with hyphens replaced by underscores
         let json_name = crate_name.replace('-', "_");
         let json_file = doc_dir.join(format!("{json_name}.json"));
 
@@ -264,20 +789,10 @@ impl DocGenerator {
 
         tracing::info!("Generating dependency information for {}-{}", name, version);
 
-        // Run cargo metadata to get dependency information
-        let output = Command::new("cargo")
-            .args(["metadata", "--format-version", "1"])
-            .current_dir(&source_path)
-            .output()
-            .context("Failed to run cargo metadata")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            bail!("Failed to generate dependency metadata: {stderr}");
-        }
+        let metadata = Self::cargo_metadata(&source_path.join(CARGO_TOML))?;
 
         // Save the raw metadata output
-        tokio::fs::write(&deps_path, &output.stdout)
+        tokio::fs::write(&deps_path, &metadata)
             .await
             .context("Failed to write dependencies to cache")?;
 
@@ -306,23 +821,7 @@ impl DocGenerator {
 
         // Path to the member's Cargo.toml
         let member_cargo_toml = source_path.join(member_path).join(CARGO_TOML);
-
-        // Run cargo metadata with --manifest-path for the specific member
-        let output = Command::new("cargo")
-            .args([
-                "metadata",
-                "--format-version",
-                "1",
-                "--manifest-path",
-                &member_cargo_toml.to_string_lossy(),
-            ])
-            .output()
-            .context("Failed to run cargo metadata")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            bail!("Failed to generate dependency metadata: {stderr}");
-        }
+        let metadata = Self::cargo_metadata(&member_cargo_toml)?;
 
         // Ensure the member directory exists
         if let Some(parent) = deps_path.parent() {
@@ -335,19 +834,54 @@ impl DocGenerator {
         }
 
         // Save the raw metadata output
-        tokio::fs::write(&deps_path, &output.stdout)
+        tokio::fs::write(&deps_path, &metadata)
             .await
             .context("Failed to write dependencies to cache")?;
 
         Ok(())
     }
 
-    /// Load dependency information from cache
-    pub async fn load_dependencies(&self, name: &str, version: &str) -> Result<serde_json::Value> {
-        let deps_path = self.storage.dependencies_path(name, version, None)?;
+    /// Run `cargo metadata` for the package whose manifest is at `manifest_path`
+    fn cargo_metadata(manifest_path: &Path) -> Result<Vec<u8>> {
+        let output = Command::new("cargo")
+            .args(["metadata", "--format-version", "1", "--manifest-path"])
+            .arg(manifest_path)
+            .output()
+            .context("Failed to run cargo metadata")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Failed to generate dependency metadata: {stderr}");
+        }
+
+        Ok(output.stdout)
+    }
+
+    /// Load dependency information of a crate or workspace member from cache
+    ///
+    /// The `cargo metadata` output is saved when the docs are generated, so
+    /// queries only read it back. If it is missing, e.g. for crates cached by
+    /// older versions, it is computed once from the cached source and saved.
+    pub async fn load_dependencies(
+        &self,
+        name: &str,
+        version: &str,
+        member_name: Option<&str>,
+    ) -> Result<serde_json::Value> {
+        let deps_path = self.storage.dependencies_path(name, version, member_name)?;
 
         if !deps_path.exists() {
-            bail!("Dependencies not found for {name}-{version}");
+            if !self.storage.source_path(name, version)?.exists() {
+                bail!("Dependencies not found for {name}-{version}");
+            }
+            self.storage.ensure_writable()?;
+            match member_name {
+                Some(member) => {
+                    self.generate_workspace_member_dependencies(name, version, member)
+                        .await?
+                }
+                None => self.generate_dependencies(name, version).await?,
+            }
         }
 
         let json_string = tokio::fs::read_to_string(&deps_path)
@@ -368,7 +902,48 @@ impl DocGenerator {
         member_name: Option<&str>,
     ) -> Result<serde_json::Value> {
         let docs_path = self.storage.docs_path(name, version, member_name)?;
+        self.load_docs_file(&docs_path, name, version, member_name)
+            .await
+    }
+
+    /// Load documentation including private or hidden items from cache
+    pub async fn load_variant_docs(
+        &self,
+        name: &str,
+        version: &str,
+        member_name: Option<&str>,
+        variant: DocsVariant,
+    ) -> Result<serde_json::Value> {
+        let docs_path = self
+            .storage
+            .variant_docs_path(name, version, member_name, variant)?;
+        self.load_docs_file(&docs_path, name, version, member_name)
+            .await
+    }
+
+    /// Load documentation generated for a specific target platform from cache
+    pub async fn load_target_docs(
+        &self,
+        name: &str,
+        version: &str,
+        member_name: Option<&str>,
+        target: &str,
+    ) -> Result<serde_json::Value> {
+        let docs_path = self
+            .storage
+            .target_docs_path(name, version, member_name, target)?;
+        self.load_docs_file(&docs_path, name, version, member_name)
+            .await
+    }
 
+    /// Read and parse a documentation JSON file
+    async fn load_docs_file(
+        &self,
+        docs_path: &Path,
+        name: &str,
+        version: &str,
+        member_name: Option<&str>,
+    ) -> Result<serde_json::Value> {
         if !docs_path.exists() {
             if let Some(member) = member_name {
                 bail!("Documentation not found for workspace member {member} in {name}-{version}");
@@ -377,13 +952,14 @@ impl DocGenerator {
             }
         }
 
-        let json_string = tokio::fs::read_to_string(&docs_path)
+        let json_string = tokio::fs::read_to_string(docs_path)
             .await
             .context("Failed to read documentation file")?;
 
         let docs: serde_json::Value =
             serde_json::from_str(&json_string).context("Failed to parse documentation JSON")?;
 
+        self.storage.touch(name, version, member_name);
         Ok(docs)
     }
 
@@ -450,6 +1026,304 @@ mod tests {
         assert!(format!("{docgen:?}").contains("DocGenerator"));
     }
 
+    #[test]
+    fn test_docgen_toolchain_override() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = CacheStorage::new(Some(temp_dir.path().to_path_buf())).unwrap();
+
+        let docgen = DocGenerator::new(storage.clone());
+        assert_eq!(docgen.toolchain(), rustdoc::REQUIRED_TOOLCHAIN);
+
+        let docgen = DocGenerator::with_toolchain(storage, "nightly-2024-10-01");
+        assert_eq!(docgen.toolchain(), "nightly-2024-10-01");
+    }
+
+    #[tokio::test]
+    async fn test_generate_docs_for_non_host_target() -> Result<()> {
+        const TARGET: &str = "wasm32-unknown-unknown";
+
+        // Requires the pinned toolchain with the wasm32 standard library installed
+        let installed = Command::new("rustup")
+            .args([
+                "target",
+                "list",
+                "--installed",
+                "--toolchain",
+                rustdoc::REQUIRED_TOOLCHAIN,
+            ])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| {
+                rustdoc::target_installed(&String::from_utf8_lossy(&output.stdout), TARGET)
+            })
+            .unwrap_or(false);
+        if !installed {
+            eprintln!("Skipping: target {TARGET} is not installed");
+            return Ok(());
+        }
+
+        let temp_dir = TempDir::new()?;
+        let storage = CacheStorage::new(Some(temp_dir.path().to_path_buf()))?;
+        let source_path = storage.source_path("platform-api", "0.1.0")?;
+        fs::create_dir_all(source_path.join("src"))?;
+        fs::write(
+            source_path.join(CARGO_TOML),
+            "[package]\nname = \"platform-api\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )?;
+        fs::write(
+            source_path.join("src/lib.rs"),
+            "#[cfg(target_arch = \"wasm32\")]\npub fn wasm_only() {}\n",
+        )?;
+
+        let docgen = DocGenerator::new(storage.clone());
+        docgen
+            .generate_target_docs("platform-api", "0.1.0", None, TARGET)
+            .await?;
+
+        assert!(storage.has_target_docs("platform-api", "0.1.0", None, TARGET));
+        assert!(!storage.has_docs("platform-api", "0.1.0", None));
+
+        let docs = docgen
+            .load_target_docs("platform-api", "0.1.0", None, TARGET)
+            .await?;
+        let has_wasm_only = docs["index"]
+            .as_object()
+            .is_some_and(|index| index.values().any(|item| item["name"] == "wasm_only"));
+        assert!(has_wasm_only, "wasm32-only item should be documented");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_missing_toolchain_falls_back_to_docsrs() -> Result<()> {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::TcpListener;
+
+        let docs = serde_json::json!({
+            "root": 0,
+            "crate_version": "0.1.0",
+            "includes_private": false,
+            "index": {},
+            "paths": {},
+            "external_crates": {},
+            "target": { "triple": "x86_64-unknown-linux-gnu", "target_features": [] },
+            "format_version": rustdoc_types::FORMAT_VERSION,
+        });
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(docs.to_string().as_bytes())?;
+        let body = encoder.finish()?;
+
+        // A mock docs.rs that records the request line and serves the JSON
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let port = listener.local_addr()?.port();
+        let server = std::thread::spawn(move || -> std::io::Result<String> {
+            let (mut stream, _) = listener.accept()?;
+            let mut request_line = String::new();
+            BufReader::new(&stream).read_line(&mut request_line)?;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            )?;
+            stream.write_all(&body)?;
+            Ok(request_line)
+        });
+
+        let temp_dir = TempDir::new()?;
+        let storage = CacheStorage::new(Some(temp_dir.path().to_path_buf()))?;
+        for name in ["hosted", "unhosted"] {
+            let source_path = storage.source_path(name, "0.1.0")?;
+            fs::create_dir_all(source_path.join("src"))?;
+            fs::write(
+                source_path.join(CARGO_TOML),
+                format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n"),
+            )?;
+            fs::write(source_path.join("src/lib.rs"), "pub fn f() {}\n")?;
+        }
+        storage.save_metadata("hosted", "0.1.0")?;
+        storage.save_metadata_with_source("unhosted", "0.1.0", "github", None, None)?;
+
+        // No nightly is installed under this name
+        let docgen = DocGenerator::with_toolchain(storage.clone(), "nightly-1970-01-01")
+            .with_docsrs_url(format!("http://127.0.0.1:{port}"));
+
+        docgen.generate_docs("hosted", "0.1.0", None).await?;
+        let request_line = server.join().unwrap()?;
+        assert!(
+            request_line.starts_with(&format!(
+                "GET /crate/hosted/0.1.0/json/{}.gz ",
+                rustdoc_types::FORMAT_VERSION
+            )),
+            "docs.rs received: {request_line}"
+        );
+        assert!(storage.has_docs("hosted", "0.1.0", None));
+        assert_eq!(
+            storage
+                .load_metadata("hosted", "0.1.0", None)?
+                .doc_source
+                .as_deref(),
+            Some(DOC_SOURCE_DOCSRS)
+        );
+
+        // Crates not published on crates.io still need the toolchain
+        let error = docgen
+            .generate_docs("unhosted", "0.1.0", None)
+            .await
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("can only be documented locally"),
+            "Unexpected error: {error}"
+        );
+        assert!(!storage.has_docs("unhosted", "0.1.0", None));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_generate_docs_waits_for_lock() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = CacheStorage::new(Some(temp_dir.path().to_path_buf()))?;
+        let source_path = storage.source_path("locked", "0.1.0")?;
+        fs::create_dir_all(source_path.join("src"))?;
+        fs::write(
+            source_path.join(CARGO_TOML),
+            "[package]\nname = \"locked\"\nversion = \"0.1.0\"\n",
+        )?;
+
+        // Another process is generating the docs
+        let docgen = DocGenerator::new(storage.clone());
+        let lock = docgen
+            .acquire_docgen_lock("locked", "0.1.0", None, None)
+            .await?;
+
+        let waiter = tokio::spawn({
+            let docgen = docgen.clone();
+            async move { docgen.generate_docs("locked", "0.1.0", None).await }
+        });
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(
+            !waiter.is_finished(),
+            "generate_docs should wait for the lock"
+        );
+
+        // The waiter loads the other process's output instead of running rustdoc
+        let docs_path = storage.docs_path("locked", "0.1.0", None)?;
+        fs::write(&docs_path, "{}")?;
+        drop(lock);
+        assert_eq!(waiter.await??, docs_path);
+        assert!(
+            !storage
+                .docgen_lock_path("locked", "0.1.0", None, None)?
+                .exists()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_generate_variant_docs_waits_for_lock() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = CacheStorage::new(Some(temp_dir.path().to_path_buf()))?;
+        let source_path = storage.source_path("locked", "0.1.0")?;
+        fs::create_dir_all(source_path.join("src"))?;
+        fs::write(
+            source_path.join(CARGO_TOML),
+            "[package]\nname = \"locked\"\nversion = \"0.1.0\"\n",
+        )?;
+
+        // Another process is generating the private docs
+        let variant = DocsVariant::Private;
+        let docgen = DocGenerator::new(storage.clone());
+        let lock = docgen
+            .acquire_docgen_lock("locked", "0.1.0", None, Some(variant.target_dir()))
+            .await?;
+
+        // Regular docs are not blocked by the variant build
+        let regular_lock = docgen
+            .acquire_docgen_lock("locked", "0.1.0", None, None)
+            .await?;
+        drop(regular_lock);
+
+        let waiter = tokio::spawn({
+            let docgen = docgen.clone();
+            async move {
+                docgen
+                    .generate_variant_docs("locked", "0.1.0", None, variant)
+                    .await
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(
+            !waiter.is_finished(),
+            "generate_variant_docs should wait for the lock"
+        );
+
+        // The waiter loads the other process's output instead of running rustdoc
+        let docs_path = storage.variant_docs_path("locked", "0.1.0", None, variant)?;
+        fs::write(&docs_path, "{}")?;
+        drop(lock);
+        assert_eq!(waiter.await??, docs_path);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ensure_library_target() {
+        let temp_dir = TempDir::new().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&src_dir).unwrap();
+        fs::write(
+            temp_dir.path().join(CARGO_TOML),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        fs::write(src_dir.join("main.rs"), "fn main() {}\n").unwrap();
+
+        let error = DocGenerator::ensure_library_target(temp_dir.path(), "demo").unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<BinaryOnlyError>(),
+            Some(&BinaryOnlyError {
+                package: "demo".to_string()
+            })
+        );
+
+        fs::write(src_dir.join("lib.rs"), "").unwrap();
+        assert!(DocGenerator::ensure_library_target(temp_dir.path(), "demo").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_binary_only_failure_is_counted() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = CacheStorage::new(Some(temp_dir.path().to_path_buf()))?;
+        let source_path = storage.source_path("demo-cli", "0.1.0")?;
+        fs::create_dir_all(source_path.join("src"))?;
+        fs::write(
+            source_path.join(CARGO_TOML),
+            "[package]\nname = \"demo-cli\"\nversion = \"0.1.0\"\n",
+        )?;
+        fs::write(source_path.join("src").join("main.rs"), "fn main() {}\n")?;
+
+        let docgen = DocGenerator::new(storage);
+        let failures = docgen.failures();
+        let error = docgen
+            .generate_docs("demo-cli", "0.1.0", None)
+            .await
+            .unwrap_err();
+        assert!(error.downcast_ref::<BinaryOnlyError>().is_some());
+
+        assert_eq!(failures.get(FailureLabel::BinaryOnly), 1);
+        assert_eq!(failures.get(FailureLabel::CompileError), 0);
+        assert_eq!(
+            failures.snapshot(),
+            [("binary_only".to_string(), 1)].into_iter().collect()
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_find_json_doc_not_found() {
         let temp_dir = TempDir::new().unwrap();
@@ -480,6 +1354,34 @@ mod tests {
         assert_eq!(result, json_file);
     }
 
+    #[test]
+    fn test_find_json_doc_uses_renamed_lib_target() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = CacheStorage::new(Some(temp_dir.path().to_path_buf())).unwrap();
+        let docgen = DocGenerator::new(storage);
+
+        let manifest = temp_dir.path().join(CARGO_TOML);
+        fs::write(
+            &manifest,
+            "[package]\nname = \"foo\"\nversion = \"0.1.0\"\n\n[lib]\nname = \"foo_core\"\n",
+        )
+        .unwrap();
+
+        // Other JSON files in the directory must not be picked up instead
+        let doc_dir = temp_dir.path().join(DOC_DIR);
+        fs::create_dir_all(&doc_dir).unwrap();
+        fs::write(doc_dir.join("aaa.json"), "{}").unwrap();
+        let json_file = doc_dir.join("foo_core.json");
+        fs::write(&json_file, "{}").unwrap();
+
+        let lib_name = DocGenerator::lib_name(&manifest, "foo");
+        assert_eq!(lib_name, "foo_core");
+        assert_eq!(
+            docgen.find_json_doc(&doc_dir, &lib_name).unwrap(),
+            json_file
+        );
+    }
+
     #[test]
     fn test_find_json_doc_with_underscore_conversion() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,304 @@
+//! Prebuilt rustdoc JSON from docs.rs, and the settings crates declare for it
+//!
+//! docs.rs publishes the rustdoc JSON of the crates.io releases it builds.
+//! Fetching it needs no local nightly toolchain, so it stands in for local
+//! generation on machines without one. Only JSON in the format version this
+//! build reads is requested.
+//!
+//! When docs are generated locally, the `[package.metadata.docs.rs]` table of
+//! the manifest is read so the build matches the one docs.rs would make. Items
+//! of crates.io crates also link to their page on docs.rs, see [`item_url`].
+
+use crate::cache::constants::CARGO_TOML;
+use crate::rustdoc::{self, DocsRsSettings};
+use anyhow::{Context, Result, bail};
+use flate2::read::GzDecoder;
+use std::io::Read;
+use std::path::Path;
+use toml::Value;
+
+/// Base URL of docs.rs
+pub const DOCSRS_URL: &str = "https://docs.rs";
+
+/// `doc_source` of documentation downloaded from docs.rs
+pub const DOC_SOURCE_DOCSRS: &str = "docsrs";
+
+/// `doc_source` of documentation generated with the local toolchain
+pub const DOC_SOURCE_LOCAL: &str = "local";
+
+/// URL of the gzip-compressed rustdoc JSON of a crate version in the supported format
+pub fn docs_json_url(base_url: &str, name: &str, version: &str) -> String {
+    format!(
+        "{}/crate/{name}/{version}/json/{}.gz",
+        base_url.trim_end_matches('/'),
+        rustdoc_types::FORMAT_VERSION
+    )
+}
+
+/// URL of the docs.rs page of an item, e.g.
+/// `https://docs.rs/serde/1.0.200/serde/de/trait.Deserialize.html`
+///
+/// `path` is the item's full path starting with the crate name, as in
+/// `ItemInfo.path`. Returns `None` for items without a page of their own, such
+/// as fields, methods and impls, and for proc macros, whose page depends on
+/// whether they are derives, attributes or function-like.
+pub fn item_url(
+    base_url: &str,
+    crate_name: &str,
+    version: &str,
+    kind: &str,
+    path: &[String],
+) -> Option<String> {
+    let base_url = format!("{}/{crate_name}/{version}", base_url.trim_end_matches('/'));
+    if kind == "module" && !path.is_empty() {
+        return Some(format!("{base_url}/{}/index.html", path.join("/")));
+    }
+
+    let (name, parents) = path.split_last()?;
+    if parents.is_empty() {
+        return None;
+    }
+    let prefix = match kind {
+        "struct" => "struct",
+        "enum" => "enum",
+        "union" => "union",
+        "trait" => "trait",
+        "trait_alias" => "traitalias",
+        "function" => "fn",
+        "type_alias" => "type",
+        "constant" => "constant",
+        "static" => "static",
+        "macro" => "macro",
+        "primitive" => "primitive",
+        "extern_type" => "foreigntype",
+        _ => return None,
+    };
+    Some(format!(
+        "{base_url}/{}/{prefix}.{name}.html",
+        parents.join("/")
+    ))
+}
+
+/// Decompress rustdoc JSON downloaded from docs.rs and check its format version
+pub fn decode_docs_json(compressed: &[u8]) -> Result<Vec<u8>> {
+    let mut json = Vec::new();
+    GzDecoder::new(compressed)
+        .read_to_end(&mut json)
+        .context("Failed to decompress rustdoc JSON from docs.rs")?;
+
+    let docs: serde_json::Value =
+        serde_json::from_slice(&json).context("Failed to parse rustdoc JSON from docs.rs")?;
+    rustdoc::check_format_version(&docs)?;
+
+    Ok(json)
+}
+
+/// Download the rustdoc JSON of a crates.io crate version from docs.rs
+pub async fn fetch_docs_json(
+    client: &reqwest::Client,
+    base_url: &str,
+    name: &str,
+    version: &str,
+) -> Result<Vec<u8>> {
+    let url = docs_json_url(base_url, name, version);
+    tracing::info!(
+        "Downloading rustdoc JSON for {}-{} from {}",
+        name,
+        version,
+        url
+    );
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .with_context(|| format!("Network error while downloading docs of {name}-{version}"))?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        bail!(
+            "docs.rs has no rustdoc JSON in format version {} for {name}-{version}",
+            rustdoc_types::FORMAT_VERSION
+        );
+    }
+    if !status.is_success() {
+        bail!("Failed to download docs of {name}-{version} from docs.rs: HTTP {status}");
+    }
+
+    let compressed = response
+        .bytes()
+        .await
+        .context("Failed to read rustdoc JSON from docs.rs")?;
+    decode_docs_json(&compressed)
+}
+
+/// Read the docs.rs settings of the package at `package_dir`, if it declares any
+pub fn read_docs_rs_settings(package_dir: &Path) -> Option<DocsRsSettings> {
+    let content = std::fs::read_to_string(package_dir.join(CARGO_TOML)).ok()?;
+    let manifest = toml::from_str::<Value>(&content).ok()?;
+    docs_rs_settings(&manifest)
+}
+
+/// The docs.rs settings in `[package.metadata.docs.rs]` of a parsed manifest
+///
+/// Only rustdoc arguments that [`rustdoc::parse_rustdoc_args`] accepts are kept,
+/// and feature names with characters Cargo does not allow are dropped, since
+/// the manifest comes from the crate being documented.
+pub fn docs_rs_settings(manifest: &Value) -> Option<DocsRsSettings> {
+    let metadata = manifest
+        .get("package")?
+        .get("metadata")?
+        .get("docs")?
+        .get("rs")?;
+    let strings = |key: &str| -> Vec<String> {
+        metadata
+            .get(key)
+            .and_then(Value::as_array)
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let flag = |key: &str| metadata.get(key).and_then(Value::as_bool).unwrap_or(false);
+
+    let features = strings("features")
+        .into_iter()
+        .filter(|feature| {
+            !feature.is_empty()
+                && feature
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '+' | '.' | '/' | '?'))
+        })
+        .collect();
+    Some(DocsRsSettings {
+        features,
+        all_features: flag("all-features"),
+        no_default_features: flag("no-default-features"),
+        rustdoc_args: rustdoc::filter_rustdoc_args(&strings("rustdoc-args")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_docs_json_url() {
+        assert_eq!(
+            docs_json_url("https://docs.rs/", "serde", "1.0.200"),
+            format!(
+                "https://docs.rs/crate/serde/1.0.200/json/{}.gz",
+                rustdoc_types::FORMAT_VERSION
+            )
+        );
+    }
+
+    #[test]
+    fn test_item_url() {
+        let path = |path: &str| -> Vec<String> { path.split("::").map(str::to_string).collect() };
+        let url =
+            |kind: &str, item: &str| item_url(DOCSRS_URL, "serde", "1.0.200", kind, &path(item));
+
+        assert_eq!(
+            url("trait", "serde::de::Deserialize").as_deref(),
+            Some("https://docs.rs/serde/1.0.200/serde/de/trait.Deserialize.html")
+        );
+        assert_eq!(
+            url("function", "serde::de::value::from_str").as_deref(),
+            Some("https://docs.rs/serde/1.0.200/serde/de/value/fn.from_str.html")
+        );
+        assert_eq!(
+            url("type_alias", "serde::Result").as_deref(),
+            Some("https://docs.rs/serde/1.0.200/serde/type.Result.html")
+        );
+        assert_eq!(
+            url("macro", "serde::forward_to_deserialize_any").as_deref(),
+            Some("https://docs.rs/serde/1.0.200/serde/macro.forward_to_deserialize_any.html")
+        );
+        assert_eq!(
+            url("module", "serde::de").as_deref(),
+            Some("https://docs.rs/serde/1.0.200/serde/de/index.html")
+        );
+        assert_eq!(
+            url("module", "serde").as_deref(),
+            Some("https://docs.rs/serde/1.0.200/serde/index.html")
+        );
+        assert_eq!(
+            item_url(
+                "https://docs.rs/",
+                "serde-json",
+                "1.0.0",
+                "struct",
+                &path("serde_json::Value")
+            )
+            .as_deref(),
+            Some("https://docs.rs/serde-json/1.0.0/serde_json/struct.Value.html")
+        );
+
+        // Members without a page of their own, and items without a known path
+        assert_eq!(url("field", "serde::de::Unexpected::Bool"), None);
+        assert_eq!(url("impl", "serde::de::Deserialize"), None);
+        assert_eq!(url("function", "deserialize"), None);
+        assert_eq!(
+            item_url(DOCSRS_URL, "serde", "1.0.200", "function", &[]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_decode_docs_json() {
+        let json = format!(r#"{{"format_version":{}}}"#, rustdoc_types::FORMAT_VERSION);
+        assert_eq!(
+            decode_docs_json(&gzip(json.as_bytes())).unwrap(),
+            json.as_bytes()
+        );
+
+        let newer = format!(
+            r#"{{"format_version":{}}}"#,
+            rustdoc_types::FORMAT_VERSION + 1
+        );
+        assert!(decode_docs_json(&gzip(newer.as_bytes())).is_err());
+        assert!(decode_docs_json(json.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_docs_rs_settings() {
+        let manifest: Value = toml::from_str(
+            r#"
+[package]
+name = "demo"
+
+[package.metadata.docs.rs]
+features = ["serde", "tokio/rt", "--config=evil"]
+no-default-features = true
+rustdoc-args = ["--cfg", "docsrs", "--html-in-header", "katex.html"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            docs_rs_settings(&manifest),
+            Some(DocsRsSettings {
+                features: vec!["serde".to_string(), "tokio/rt".to_string()],
+                all_features: false,
+                no_default_features: true,
+                rustdoc_args: vec!["--cfg".to_string(), "docsrs".to_string()],
+            })
+        );
+
+        let manifest: Value = toml::from_str("[package]\nname = \"demo\"\n").unwrap();
+        assert_eq!(docs_rs_settings(&manifest), None);
+    }
+}
@@ -3,42 +3,37 @@
 //! This module handles downloading crates from various sources including
 //! crates.io, GitHub repositories, and local filesystem paths.
 
+use crate::cache::archive::unpack_crate_archive;
 use crate::cache::constants::*;
+use crate::cache::lock::LockFile;
+use crate::cache::policy::SourcePolicy;
+use crate::cache::registry::Registry;
 use crate::cache::source::{GitReference, SourceDetector, SourceType};
 use crate::cache::storage::CacheStorage;
 use crate::cache::tools::{
-    CacheCrateFromCratesIOParams, CacheCrateFromGitHubParams, CacheCrateFromLocalParams,
+    CacheCrateFromArchiveParams, CacheCrateFromCratesIOParams, CacheCrateFromGitHubParams,
+    CacheCrateFromLocalParams,
 };
-use crate::cache::utils::copy_directory_contents;
-use anyhow::{Context, Result, bail};
-use flate2::read::GzDecoder;
+use crate::cache::utils::{copy_directory_contents, copy_directory_contents_with_progress};
+use anyhow::{Context, Result, anyhow, bail};
 use futures::StreamExt;
-use git2::{Cred, FetchOptions, RemoteCallbacks};
+use git2::{AutotagOption, Cred, FetchOptions, RemoteCallbacks, Repository};
 use std::env;
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tar::Archive;
+use std::time::Duration;
 use zeroize::Zeroizing;
 
 /// Progress callback function type for reporting download/operation progress (0-100)
 pub type ProgressCallback = Arc<dyn Fn(u8) + Send + Sync>;
 
-/// Constants for download operations
-const LOCK_TIMEOUT_SECS: u64 = 60;
-const LOCK_POLL_INTERVAL_MS: u64 = 100;
+/// How long to wait for another process downloading the same crate
+const LOCK_TIMEOUT: Duration = Duration::from_secs(60);
 
-/// RAII guard for cleaning up lock files
-struct LockGuard {
-    path: PathBuf,
-}
-
-impl Drop for LockGuard {
-    fn drop(&mut self) {
-        let _ = std::fs::remove_file(&self.path);
-    }
-}
+/// Default depth of git clones, only the commit of the requested ref
+pub const DEFAULT_GIT_DEPTH: u32 = 1;
 
 /// Unified crate source enum that reuses the parameter structs from tools
 #[derive(Debug, Clone)]
@@ -46,6 +41,7 @@ pub enum CrateSource {
     CratesIO(CacheCrateFromCratesIOParams),
     GitHub(CacheCrateFromGitHubParams),
     LocalPath(CacheCrateFromLocalParams),
+    Archive(CacheCrateFromArchiveParams),
 }
 
 /// Service for downloading crates from various sources
@@ -53,17 +49,58 @@ pub enum CrateSource {
 pub struct CrateDownloader {
     storage: CacheStorage,
     client: reqwest::Client,
+    registry: Registry,
+    git_depth: u32,
+    keep_git_history: bool,
+    source_policy: SourcePolicy,
 }
 
 impl CrateDownloader {
     /// Create a new crate downloader
     pub fn new(storage: CacheStorage) -> Self {
         let client = Self::build_http_client();
-        Self { storage, client }
+        Self {
+            storage,
+            client,
+            registry: Registry::default(),
+            git_depth: DEFAULT_GIT_DEPTH,
+            keep_git_history: false,
+            source_policy: SourcePolicy::default(),
+        }
+    }
+
+    /// Download crates and read the sparse index from `registry` instead of crates.io
+    pub fn with_registry(mut self, registry: Registry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Write downloads through `storage`, e.g. after its permissions changed
+    pub fn with_storage(mut self, storage: CacheStorage) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Clone git repositories `depth` commits deep, or with their full history if zero
+    pub fn with_git_depth(mut self, depth: u32) -> Self {
+        self.git_depth = depth;
+        self
+    }
+
+    /// Keep the `.git` directory of cloned repositories, see [`CacheStorage::git_path`]
+    pub fn with_git_history(mut self, enabled: bool) -> Self {
+        self.keep_git_history = enabled;
+        self
+    }
+
+    /// Only download or copy crates from the sources `policy` allows
+    pub fn with_source_policy(mut self, policy: SourcePolicy) -> Self {
+        self.source_policy = policy;
+        self
     }
 
     /// Build the HTTP client with proper configuration
-    fn build_http_client() -> reqwest::Client {
+    pub(crate) fn build_http_client() -> reqwest::Client {
         let user_agent = Self::format_user_agent();
 
         tracing::info!("Creating HTTP client with User-Agent: {}", user_agent);
@@ -94,6 +131,7 @@ impl CrateDownloader {
         progress_callback: Option<ProgressCallback>,
     ) -> Result<PathBuf> {
         let source_type = SourceDetector::detect(source);
+        self.source_policy.check(&source_type)?;
 
         match source_type {
             SourceType::CratesIo => self.download_crate(name, version, progress_callback).await,
@@ -107,13 +145,30 @@ impl CrateDownloader {
                     GitReference::Tag(tag) => tag,
                     GitReference::Default => "main".to_string(),
                 };
-                self.download_from_github(name, &version_str, &url, repo_path.as_deref())
+                self.download_from_github(
+                    name,
+                    &version_str,
+                    &url,
+                    repo_path.as_deref(),
+                    progress_callback,
+                )
+                .await
+            }
+            SourceType::Local { path } => {
+                self.copy_from_local(name, version, &path, progress_callback)
                     .await
             }
-            SourceType::Local { path } => self.copy_from_local(name, version, &path).await,
+            SourceType::Archive { path } => {
+                self.unpack_from_archive(name, version, &path, progress_callback)
+            }
         }
     }
 
+    /// Sources crates may be downloaded or copied from
+    pub fn source_policy(&self) -> &SourcePolicy {
+        &self.source_policy
+    }
+
     /// Download a crate from crates.io
     async fn download_crate(
         &self,
@@ -134,62 +189,40 @@ impl CrateDownloader {
         let crate_path = self.storage.crate_path(name, version)?;
         let lock_path = crate_path.with_extension("lock");
 
-        // Check if another process is already downloading
-        if lock_path.exists() {
-            tracing::info!(
-                "Another process is downloading {}-{}, waiting...",
-                name,
-                version
-            );
-            // Wait for the other process to finish (simple polling)
-            let start = std::time::Instant::now();
-            while lock_path.exists()
-                && start.elapsed() < std::time::Duration::from_secs(LOCK_TIMEOUT_SECS)
-            {
-                tokio::time::sleep(std::time::Duration::from_millis(LOCK_POLL_INTERVAL_MS)).await;
-            }
-
-            // Check if it was successfully cached by the other process
-            if self.storage.is_cached(name, version) {
-                tracing::info!("Crate {}-{} was cached by another process", name, version);
-                return self.storage.source_path(name, version);
-            }
-        }
-
-        // Create lock file
         if let Some(parent) = lock_path.parent() {
             self.storage.ensure_dir(parent)?;
         }
-        std::fs::write(&lock_path, "downloading").context("Failed to create lock file")?;
 
-        // Ensure lock file is removed on exit
-        let _lock_guard = LockGuard {
-            path: lock_path.clone(),
-        };
+        // Wait if another process is already downloading, the lock file is removed on exit
+        let _lock = LockFile::acquire(&lock_path, LOCK_TIMEOUT).await?;
+
+        // Check if it was successfully cached by the other process
+        if self.storage.is_cached(name, version) {
+            tracing::info!("Crate {}-{} was cached by another process", name, version);
+            return self.storage.source_path(name, version);
+        }
 
+        let url = self.registry.download_url(name, version);
         tracing::info!(
-            "Starting fresh download of {}-{} from crates.io",
+            "Starting fresh download of {}-{} from {}",
             name,
-            version
+            version,
+            url
         );
 
-        let url = format!("https://crates.io/api/v1/crates/{name}/{version}/download");
-        tracing::debug!("Download URL: {}", url);
-
         let response = self
             .client
             .get(&url)
             .send()
             .await
-            .with_context(|| format!("Failed to download {name}-{version}"))?;
+            .with_context(|| format!("Network error while downloading {name}-{version}"))?;
 
-        if !response.status().is_success() {
-            bail!(
-                "Failed to download {}-{}: HTTP {}",
-                name,
-                version,
-                response.status()
-            );
+        let status = response.status();
+        if is_not_found_status(status) {
+            return Err(self.not_found_error(name, version, status).await);
+        }
+        if !status.is_success() {
+            bail!("Failed to download {name}-{version}: HTTP {status}");
         }
 
         // Save to a temporary file first - make path unique to avoid concurrent conflicts
@@ -227,77 +260,114 @@ impl CrateDownloader {
         let source_path = self.storage.source_path(name, version)?;
         self.storage.ensure_dir(&source_path)?;
 
-        let tar_gz = File::open(&temp_file_path).context("Failed to open downloaded file")?;
-        let tar = GzDecoder::new(tar_gz);
-        let mut archive = Archive::new(tar);
-
-        // Extract with proper path handling
-        for entry in archive.entries()? {
-            let mut entry = entry?;
-            let path = entry.path()?;
-
-            // Skip the top-level directory (crate-version/)
-            let components: Vec<_> = path.components().collect();
-            if components.len() > 1 {
-                let relative_path: PathBuf = components[1..].iter().collect();
-
-                // Validate that the path doesn't escape the destination directory
-                // Check for path traversal attempts
-                let has_parent_refs = relative_path
-                    .components()
-                    .any(|c| matches!(c, std::path::Component::ParentDir));
-
-                if has_parent_refs {
-                    tracing::warn!(
-                        "Skipping entry with parent directory reference: {}",
-                        path.display()
-                    );
-                    continue;
-                }
+        unpack_crate_archive(&temp_file_path, &source_path)
+            .with_context(|| format!("Failed to extract {name}-{version}"))?;
 
-                let dest_path = source_path.join(&relative_path);
+        // Clean up temp file
+        std::fs::remove_file(&temp_file_path).ok();
 
-                // Additional validation: ensure the destination is within source_path
-                let canonical_source = source_path
-                    .canonicalize()
-                    .unwrap_or_else(|_| source_path.clone());
+        // Save metadata for the cached crate
+        self.storage.save_metadata(name, version)?;
 
-                if let Ok(canonical_dest) = dest_path.canonicalize() {
-                    if !canonical_dest.starts_with(&canonical_source) {
-                        tracing::warn!(
-                            "Skipping entry that would escape destination: {}",
-                            path.display()
-                        );
-                        continue;
-                    }
-                } else if let Some(parent) = dest_path.parent() {
-                    // For files that don't exist yet, check the parent
-                    if matches!(parent.canonicalize(), Ok(canonical_parent) if !canonical_parent.starts_with(&canonical_source))
-                    {
-                        tracing::warn!(
-                            "Skipping entry with parent outside destination: {}",
-                            path.display()
-                        );
-                        continue;
-                    }
-                }
+        if self.check_yanked(name, version).await == Some(true) {
+            tracing::warn!(
+                "{}-{} has been yanked from {}",
+                name,
+                version,
+                self.registry.name()
+            );
+            self.storage.set_yanked(name, version, true)?;
+        }
 
-                if let Some(parent) = dest_path.parent() {
-                    std::fs::create_dir_all(parent)?;
-                }
+        tracing::info!("Successfully downloaded and extracted {}-{}", name, version);
+        Ok(source_path)
+    }
 
-                entry.unpack(&dest_path)?;
+    /// Look up whether a crates.io version is yanked in the sparse index
+    ///
+    /// Returns `None` if the index could not be reached or does not list the
+    /// version, so a lookup failure never blocks caching.
+    async fn check_yanked(&self, name: &str, version: &str) -> Option<bool> {
+        match self.fetch_index_entry(name).await {
+            Ok(index_entry) => is_version_yanked(&index_entry, version),
+            Err(e) => {
+                tracing::debug!("{}", e);
+                None
             }
         }
+    }
 
-        // Clean up temp file
-        std::fs::remove_file(&temp_file_path).ok();
+    /// List the versions of a crate published on crates.io, oldest first
+    pub async fn get_available_versions(&self, name: &str) -> Result<Vec<IndexVersion>> {
+        let index_entry = self.fetch_index_entry(name).await?;
+        Ok(parse_index_entry(&index_entry))
+    }
 
-        // Save metadata for the cached crate
-        self.storage.save_metadata(name, version)?;
+    /// Explain a download the registry refused as missing, naming the registry
+    ///
+    /// crates.io answers 403 rather than 404 for crates it does not know, so the
+    /// sparse index is consulted to tell an unknown crate from an unknown version.
+    async fn not_found_error(
+        &self,
+        name: &str,
+        version: &str,
+        status: reqwest::StatusCode,
+    ) -> anyhow::Error {
+        let registry = self.registry.name();
+        match self.try_fetch_index_entry(name).await {
+            Ok(None) => anyhow!("Crate '{name}' not found on {registry}"),
+            Ok(Some(index_entry)) => {
+                let versions = parse_index_entry(&index_entry);
+                if versions.iter().any(|entry| entry.version == version) {
+                    // The index lists the version, so the download itself was refused
+                    return anyhow!("Failed to download {name}-{version}: HTTP {status}");
+                }
+                match latest_version(&versions, false) {
+                    Some(latest) => anyhow!(
+                        "Version '{version}' of crate '{name}' not found on {registry} (latest version is {latest})"
+                    ),
+                    None => {
+                        anyhow!("Version '{version}' of crate '{name}' not found on {registry}")
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::debug!("{:#}", e);
+                anyhow!("Crate {name}-{version} not found on {registry} (HTTP {status})")
+            }
+        }
+    }
 
-        tracing::info!("Successfully downloaded and extracted {}-{}", name, version);
-        Ok(source_path)
+    /// Fetch the sparse index entry of a crate
+    async fn fetch_index_entry(&self, name: &str) -> Result<String> {
+        self.try_fetch_index_entry(name)
+            .await?
+            .ok_or_else(|| anyhow!("Crate '{name}' not found on {}", self.registry.name()))
+    }
+
+    /// Fetch the sparse index entry of a crate, or `None` if the index does not list it
+    async fn try_fetch_index_entry(&self, name: &str) -> Result<Option<String>> {
+        let url = self.registry.index_entry_url(name);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("Network error while looking up {name} in the index"))?;
+
+        let status = response.status();
+        if is_not_found_status(status) {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            bail!("Index lookup for {name} returned {status}");
+        }
+
+        response
+            .text()
+            .await
+            .map(Some)
+            .with_context(|| format!("Failed to read index entry for {name}"))
     }
 
     /// Download a crate from GitHub repository
@@ -307,6 +377,7 @@ impl CrateDownloader {
         version: &str,
         repo_url: &str,
         repo_path: Option<&str>,
+        progress_callback: Option<ProgressCallback>,
     ) -> Result<PathBuf> {
         // Check if already cached
         if self.storage.is_cached(name, version) {
@@ -318,38 +389,18 @@ impl CrateDownloader {
         let crate_path = self.storage.crate_path(name, version)?;
         let lock_path = crate_path.with_extension("lock");
 
-        // Check if another process is already downloading
-        if lock_path.exists() {
-            tracing::info!(
-                "Another process is downloading {}-{}, waiting...",
-                name,
-                version
-            );
-            // Wait for the other process to finish (simple polling)
-            let start = std::time::Instant::now();
-            while lock_path.exists()
-                && start.elapsed() < std::time::Duration::from_secs(LOCK_TIMEOUT_SECS)
-            {
-                tokio::time::sleep(std::time::Duration::from_millis(LOCK_POLL_INTERVAL_MS)).await;
-            }
-
-            // Check if it was successfully cached by the other process
-            if self.storage.is_cached(name, version) {
-                tracing::info!("Crate {}-{} was cached by another process", name, version);
-                return self.storage.source_path(name, version);
-            }
-        }
-
-        // Create lock file
         if let Some(parent) = lock_path.parent() {
             self.storage.ensure_dir(parent)?;
         }
-        std::fs::write(&lock_path, "downloading").context("Failed to create lock file")?;
 
-        // Ensure lock file is removed on exit
-        let _lock_guard = LockGuard {
-            path: lock_path.clone(),
-        };
+        // Wait if another process is already downloading, the lock file is removed on exit
+        let _lock = LockFile::acquire(&lock_path, LOCK_TIMEOUT).await?;
+
+        // Check if it was successfully cached by the other process
+        if self.storage.is_cached(name, version) {
+            tracing::info!("Crate {}-{} was cached by another process", name, version);
+            return self.storage.source_path(name, version);
+        }
 
         tracing::info!(
             "Downloading crate {}-{} from GitHub: {}",
@@ -365,73 +416,40 @@ impl CrateDownloader {
             fs::remove_dir_all(&temp_dir).context("Failed to clean temp directory")?;
         }
 
+        // The version parameter here is actually the branch or tag name, the
+        // default branch is used for "main" and "master"
+        let reference = if version != "main" && version != "master" {
+            // Validate git reference name to prevent potential issues
+            if !Self::is_valid_git_ref(version) {
+                bail!("Invalid git reference name: {version}");
+            }
+            Some(version)
+        } else {
+            None
+        };
+
         // Set up GitHub authentication if token is available
         let github_token = env::var("GITHUB_TOKEN").ok().map(Zeroizing::new);
-        let has_token = github_token.is_some();
-
-        // Configure git authentication callbacks
-        let mut fetch_options = FetchOptions::new();
-        let mut callbacks = RemoteCallbacks::new();
-
-        if let Some(token) = github_token {
+        if github_token.is_some() {
             tracing::debug!("Using GITHUB_TOKEN for authentication");
-            callbacks.credentials(move |_url, username_from_url, _allowed_types| {
-                Cred::userpass_plaintext(username_from_url.unwrap_or("git"), &token)
-            });
         } else {
             tracing::debug!("No GITHUB_TOKEN found, using unauthenticated access");
         }
 
-        fetch_options.remote_callbacks(callbacks);
-
-        // Clone the repository with authentication
-        let mut builder = git2::build::RepoBuilder::new();
-        builder.fetch_options(fetch_options);
-
-        let repo = builder
-            .clone(repo_url, &temp_dir)
-            .with_context(|| {
-                let mut msg = format!("Failed to clone repository: {repo_url}");
-                if !has_token && repo_url.contains("github.com") {
-                    msg.push_str("\nNote: Set GITHUB_TOKEN environment variable for private repositories and higher rate limits");
-                }
-                msg
-            })?;
-
-        // Checkout the specific branch or tag (version contains the branch/tag name)
-        // The version parameter here is actually the branch or tag name
-        if version != "main" && version != "master" {
-            // Validate git reference name to prevent potential issues
-            if !Self::is_valid_git_ref(version) {
-                bail!("Invalid git reference name: {version}");
+        clone_repository(
+            repo_url,
+            &temp_dir,
+            reference,
+            self.git_depth,
+            github_token.as_ref(),
+        )
+        .with_context(|| {
+            let mut msg = format!("Failed to clone repository: {repo_url}");
+            if github_token.is_none() && repo_url.contains("github.com") {
+                msg.push_str("\nNote: Set GITHUB_TOKEN environment variable for private repositories and higher rate limits");
             }
-
-            // Try to checkout as a branch first
-            let refname = format!("refs/remotes/origin/{version}");
-            if let Ok(reference) = repo.find_reference(&refname) {
-                let oid = reference
-                    .target()
-                    .ok_or_else(|| anyhow::anyhow!("Reference has no target"))?;
-                repo.set_head_detached(oid)
-                    .with_context(|| format!("Failed to checkout branch: {version}"))?;
-                repo.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))
-                    .with_context(|| format!("Failed to checkout branch: {version}"))?;
-            } else {
-                // Try as a tag
-                let tag_ref = format!("refs/tags/{version}");
-                if let Ok(reference) = repo.find_reference(&tag_ref) {
-                    let oid = reference
-                        .target()
-                        .ok_or_else(|| anyhow::anyhow!("Reference has no target"))?;
-                    repo.set_head_detached(oid)
-                        .with_context(|| format!("Failed to checkout tag: {version}"))?;
-                    repo.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))
-                        .with_context(|| format!("Failed to checkout tag: {version}"))?;
-                } else {
-                    bail!("Could not find branch or tag: {version}");
-                }
-            }
-        }
+            msg
+        })?;
 
         // Determine source path within the repository
         let repo_source_path = if let Some(path) = repo_path {
@@ -453,8 +471,18 @@ impl CrateDownloader {
         let source_path = self.storage.source_path(name, version)?;
         self.storage.ensure_dir(&source_path)?;
 
-        copy_directory_contents(&repo_source_path, &source_path)
-            .context("Failed to copy repository contents")?;
+        copy_directory_contents_with_progress(
+            &repo_source_path,
+            &source_path,
+            progress_callback.as_ref(),
+        )
+        .context("Failed to copy repository contents")?;
+
+        if self.keep_git_history {
+            let git_path = self.storage.git_path(name, version)?;
+            move_directory(&temp_dir.join(".git"), &git_path)
+                .context("Failed to keep the git history")?;
+        }
 
         // Clean up temp directory
         fs::remove_dir_all(&temp_dir).ok();
@@ -486,6 +514,7 @@ impl CrateDownloader {
         name: &str,
         version: &str,
         local_path: &str,
+        progress_callback: Option<ProgressCallback>,
     ) -> Result<PathBuf> {
         tracing::info!(
             "Copying crate {}-{} from local path: {}",
@@ -516,8 +545,12 @@ impl CrateDownloader {
         let source_path = self.storage.source_path(name, version)?;
         self.storage.ensure_dir(&source_path)?;
 
-        copy_directory_contents(source_path_input, &source_path)
-            .context("Failed to copy local directory contents")?;
+        copy_directory_contents_with_progress(
+            source_path_input,
+            &source_path,
+            progress_callback.as_ref(),
+        )
+        .context("Failed to copy local directory contents")?;
 
         // Save metadata with source information
         self.storage
@@ -527,8 +560,61 @@ impl CrateDownloader {
         Ok(source_path)
     }
 
+    /// Unpack a crate from a local `.crate` archive
+    fn unpack_from_archive(
+        &self,
+        name: &str,
+        version: &str,
+        archive_path: &str,
+        progress_callback: Option<ProgressCallback>,
+    ) -> Result<PathBuf> {
+        tracing::info!(
+            "Unpacking crate {}-{} from archive: {}",
+            name,
+            version,
+            archive_path
+        );
+
+        let expanded_path = shellexpand::full(archive_path)
+            .with_context(|| format!("Failed to expand path: {archive_path}"))?;
+        let archive = Path::new(expanded_path.as_ref());
+
+        if !archive.is_file() {
+            bail!("Crate archive does not exist: {}", archive.display());
+        }
+
+        let source_path = self.storage.source_path(name, version)?;
+        self.storage.ensure_dir(&source_path)?;
+
+        unpack_crate_archive(archive, &source_path)
+            .with_context(|| format!("Failed to extract crate archive: {}", archive.display()))?;
+
+        if !source_path.join(CARGO_TOML).exists() {
+            bail!(
+                "No Cargo.toml found in crate archive: {}",
+                archive.display()
+            );
+        }
+
+        // Save metadata with source information
+        self.storage.save_metadata_with_source(
+            name,
+            version,
+            "archive",
+            Some(archive_path),
+            None,
+        )?;
+
+        if let Some(callback) = progress_callback {
+            callback(100);
+        }
+
+        tracing::info!("Successfully unpacked {}-{} from archive", name, version);
+        Ok(source_path)
+    }
+
     /// Validate git reference name to prevent potential issues
-    fn is_valid_git_ref(ref_name: &str) -> bool {
+    pub(crate) fn is_valid_git_ref(ref_name: &str) -> bool {
         // Git references must not:
         // - Be empty
         // - Contain ".." (directory traversal)
@@ -556,11 +642,334 @@ impl CrateDownloader {
     }
 }
 
+/// Remote callbacks that authenticate with `github_token`, if any
+fn git_remote_callbacks(github_token: Option<&Zeroizing<String>>) -> RemoteCallbacks<'static> {
+    let mut callbacks = RemoteCallbacks::new();
+    if let Some(token) = github_token.cloned() {
+        callbacks.credentials(move |_url, username_from_url, _allowed_types| {
+            Cred::userpass_plaintext(username_from_url.unwrap_or("git"), &token)
+        });
+    }
+    callbacks
+}
+
+/// Fetch options that authenticate with `github_token`, if any
+fn git_fetch_options(github_token: Option<&Zeroizing<String>>) -> FetchOptions<'static> {
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(git_remote_callbacks(github_token));
+    fetch_options
+}
+
+/// Name of the default branch of `repo_url`, e.g. `master`, read from the remote's HEAD
+pub fn default_branch(repo_url: &str, github_token: Option<&Zeroizing<String>>) -> Result<String> {
+    let mut remote = git2::Remote::create_detached(repo_url)
+        .with_context(|| format!("Invalid repository URL: {repo_url}"))?;
+    let connection = remote
+        .connect_auth(
+            git2::Direction::Fetch,
+            Some(git_remote_callbacks(github_token)),
+            None,
+        )
+        .with_context(|| format!("Failed to connect to repository: {repo_url}"))?;
+    let head = connection
+        .default_branch()
+        .with_context(|| format!("Failed to read the default branch of {repo_url}"))?;
+    let head = head
+        .as_str()
+        .ok_or_else(|| anyhow!("Default branch of {repo_url} is not valid UTF-8"))?;
+    head.strip_prefix("refs/heads/")
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("Unexpected default branch reference of {repo_url}: {head}"))
+}
+
+/// Branch to cache a GitHub repository from when neither a branch nor a tag was given
+///
+/// URLs like `https://github.com/user/repo/tree/dev/crates/foo` name their
+/// branch; for plain repository URLs the remote is asked for its default branch.
+pub async fn resolve_default_branch(github_url: &str) -> Result<String> {
+    let repo_url = match SourceDetector::detect(Some(github_url)) {
+        SourceType::GitHub {
+            reference: GitReference::Branch(branch),
+            ..
+        } => return Ok(branch),
+        SourceType::GitHub { url, .. } => url,
+        _ => bail!("Not a GitHub repository URL: {github_url}"),
+    };
+
+    let github_token = env::var("GITHUB_TOKEN").ok().map(Zeroizing::new);
+    tokio::task::spawn_blocking(move || default_branch(&repo_url, github_token.as_ref()))
+        .await
+        .context("Default branch detection panicked")?
+}
+
+/// Clone `repo_url` into `dest` and check out `reference`, a branch or tag name
+///
+/// `None` checks out the default branch. With a `depth` above zero only the
+/// requested ref is fetched, with that many commits of history. A full clone
+/// is made if `depth` is zero or the remote has no branch or tag named
+/// `reference`, e.g. for a commit SHA.
+pub fn clone_repository(
+    repo_url: &str,
+    dest: &Path,
+    reference: Option<&str>,
+    depth: u32,
+    github_token: Option<&Zeroizing<String>>,
+) -> Result<Repository> {
+    if depth > 0 {
+        if let Some(repo) = shallow_clone(repo_url, dest, reference, depth, github_token)? {
+            if let Some(reference) = reference {
+                checkout_reference(&repo, reference)?;
+            }
+            return Ok(repo);
+        }
+        tracing::info!(
+            "Could not fetch '{}' shallowly from {}, falling back to a full clone",
+            reference.unwrap_or_default(),
+            repo_url
+        );
+        fs::remove_dir_all(dest).context("Failed to clean temp directory")?;
+    }
+
+    let repo = git2::build::RepoBuilder::new()
+        .fetch_options(git_fetch_options(github_token))
+        .clone(repo_url, dest)?;
+    if let Some(reference) = reference {
+        checkout_reference(&repo, reference)?;
+    }
+    Ok(repo)
+}
+
+/// Move `src` to `dest`, copying it when they are on different file systems
+fn move_directory(src: &Path, dest: &Path) -> Result<()> {
+    if fs::rename(src, dest).is_ok() {
+        return Ok(());
+    }
+    copy_directory_contents(src, dest)
+}
+
+/// Fetch only `reference`, or the default branch for `None`, `depth` commits deep
+///
+/// Returns `None` if the remote has no branch or tag named `reference`.
+fn shallow_clone(
+    repo_url: &str,
+    dest: &Path,
+    reference: Option<&str>,
+    depth: u32,
+    github_token: Option<&Zeroizing<String>>,
+) -> Result<Option<Repository>> {
+    let depth = i32::try_from(depth).unwrap_or(i32::MAX);
+    let mut fetch_options = git_fetch_options(github_token);
+    fetch_options.depth(depth);
+
+    let Some(reference) = reference else {
+        let repo = git2::build::RepoBuilder::new()
+            .fetch_options(fetch_options)
+            .clone(repo_url, dest)?;
+        return Ok(Some(repo));
+    };
+
+    // The ref may be a branch or a tag, fetch whichever the remote has
+    fetch_options.download_tags(AutotagOption::None);
+    let repo = Repository::init(dest).context("Failed to create repository")?;
+    {
+        let mut remote = repo.remote("origin", repo_url)?;
+        let refspecs = [
+            format!("+refs/heads/{reference}:refs/remotes/origin/{reference}"),
+            format!("+refs/tags/{reference}:refs/tags/{reference}"),
+        ];
+        remote.fetch(&refspecs, Some(&mut fetch_options), None)?;
+    }
+
+    let found = repo
+        .find_reference(&format!("refs/remotes/origin/{reference}"))
+        .is_ok()
+        || repo
+            .find_reference(&format!("refs/tags/{reference}"))
+            .is_ok();
+    Ok(found.then_some(repo))
+}
+
+/// Check out the branch or tag `reference` of a cloned repository
+fn checkout_reference(repo: &Repository, reference: &str) -> Result<()> {
+    // Try to checkout as a branch first, then as a tag
+    let (refname, kind) = if repo
+        .find_reference(&format!("refs/remotes/origin/{reference}"))
+        .is_ok()
+    {
+        (format!("refs/remotes/origin/{reference}"), "branch")
+    } else if repo
+        .find_reference(&format!("refs/tags/{reference}"))
+        .is_ok()
+    {
+        (format!("refs/tags/{reference}"), "tag")
+    } else {
+        bail!("Could not find branch or tag: {reference}");
+    };
+
+    let oid = repo
+        .find_reference(&refname)?
+        .target()
+        .ok_or_else(|| anyhow::anyhow!("Reference has no target"))?;
+    repo.set_head_detached(oid)
+        .with_context(|| format!("Failed to checkout {kind}: {reference}"))?;
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))
+        .with_context(|| format!("Failed to checkout {kind}: {reference}"))?;
+    Ok(())
+}
+
+/// Path of a crate's entry in the sparse registry index
+///
+/// Follows Cargo's layout: `1/a`, `2/ab`, `3/a/abc` and `ab/cd/abcd...`.
+pub fn sparse_index_path(name: &str) -> String {
+    let name = name.to_lowercase();
+    match name.len() {
+        1 => format!("1/{name}"),
+        2 => format!("2/{name}"),
+        3 => format!("3/{}/{name}", &name[..1]),
+        _ => format!("{}/{}/{name}", &name[..2], &name[2..4]),
+    }
+}
+
+/// Whether a registry response means the requested crate or version does not exist
+///
+/// crates.io serves downloads from a bucket that answers 403 for missing files.
+fn is_not_found_status(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::NOT_FOUND
+        || status == reqwest::StatusCode::FORBIDDEN
+        || status == reqwest::StatusCode::GONE
+}
+
+/// A version of a crate listed in the sparse index
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct IndexVersion {
+    #[serde(rename = "vers")]
+    pub version: String,
+    #[serde(default)]
+    pub yanked: bool,
+}
+
+/// Parse a sparse index entry, which holds one JSON object per published version
+pub fn parse_index_entry(index_entry: &str) -> Vec<IndexVersion> {
+    index_entry
+        .lines()
+        .filter_map(|line| serde_json::from_str::<IndexVersion>(line).ok())
+        .collect()
+}
+
+/// Find the `yanked` flag of `version` in a sparse index entry
+pub fn is_version_yanked(index_entry: &str, version: &str) -> Option<bool> {
+    parse_index_entry(index_entry)
+        .into_iter()
+        .find(|entry| entry.version == version)
+        .map(|entry| entry.yanked)
+}
+
+/// Pick the newest version that has not been yanked
+///
+/// Pre-releases are skipped unless `allow_prerelease` is set.
+pub fn latest_version(versions: &[IndexVersion], allow_prerelease: bool) -> Option<String> {
+    versions
+        .iter()
+        .filter(|entry| !entry.yanked)
+        .filter_map(|entry| semver::Version::parse(&entry.version).ok())
+        .filter(|version| allow_prerelease || version.pre.is_empty())
+        .max()
+        .map(|version| version.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    /// Create a bare repository in `dir` whose default branch is `trunk`
+    ///
+    /// It holds two commits of a `fixture` crate, versions 0.1.0 and 0.2.0,
+    /// and the first one is tagged `0.1.0`. Returns the URL to clone it from.
+    fn bare_repository_fixture(dir: &Path) -> Result<String> {
+        let repo = Repository::init_bare(dir)?;
+        let signature = git2::Signature::now("Fixture", "fixture@example.com")?;
+        let lib = repo.blob(b"pub fn fixture() {}\n")?;
+        let mut src = repo.treebuilder(None)?;
+        src.insert("lib.rs", lib, 0o100644)?;
+        let src = src.write()?;
+
+        let mut parent = None;
+        for version in ["0.1.0", "0.2.0"] {
+            let manifest = format!("[package]\nname = \"fixture\"\nversion = \"{version}\"\n");
+            let manifest = repo.blob(manifest.as_bytes())?;
+            let mut root = repo.treebuilder(None)?;
+            root.insert(CARGO_TOML, manifest, 0o100644)?;
+            root.insert("src", src, 0o040000)?;
+            let tree = repo.find_tree(root.write()?)?;
+
+            let parents: Vec<_> = parent.iter().collect();
+            let oid = repo.commit(
+                Some("refs/heads/trunk"),
+                &signature,
+                &signature,
+                &format!("Release {version}"),
+                &tree,
+                &parents,
+            )?;
+            let commit = repo.find_commit(oid)?;
+            if version == "0.1.0" {
+                repo.tag_lightweight(version, commit.as_object(), false)?;
+            }
+            parent = Some(commit);
+        }
+        repo.set_head("refs/heads/trunk")?;
+
+        Ok(format!("file://{}", dir.display()))
+    }
+
+    #[test]
+    fn test_sparse_index_path() {
+        assert_eq!(sparse_index_path("a"), "1/a");
+        assert_eq!(sparse_index_path("cc"), "2/cc");
+        assert_eq!(sparse_index_path("syn"), "3/s/syn");
+        assert_eq!(sparse_index_path("Serde"), "se/rd/serde");
+    }
+
+    #[test]
+    fn test_is_version_yanked() {
+        let index_entry = concat!(
+            r#"{"name":"demo","vers":"0.1.0","deps":[],"cksum":"00","features":{},"yanked":false}"#,
+            "\n",
+            r#"{"name":"demo","vers":"0.1.1","deps":[],"cksum":"01","features":{},"yanked":true}"#,
+            "\n",
+        );
+
+        assert_eq!(is_version_yanked(index_entry, "0.1.0"), Some(false));
+        assert_eq!(is_version_yanked(index_entry, "0.1.1"), Some(true));
+        assert_eq!(is_version_yanked(index_entry, "0.2.0"), None);
+    }
+
+    #[test]
+    fn test_latest_version() {
+        let index_entry = concat!(
+            r#"{"name":"demo","vers":"0.9.0","deps":[],"cksum":"00","features":{},"yanked":false}"#,
+            "\n",
+            r#"{"name":"demo","vers":"1.10.0","deps":[],"cksum":"01","features":{},"yanked":false}"#,
+            "\n",
+            r#"{"name":"demo","vers":"1.2.0","deps":[],"cksum":"02","features":{},"yanked":false}"#,
+            "\n",
+            r#"{"name":"demo","vers":"1.11.0","deps":[],"cksum":"03","features":{},"yanked":true}"#,
+            "\n",
+            r#"{"name":"demo","vers":"2.0.0-beta.1","deps":[],"cksum":"04","features":{},"yanked":false}"#,
+            "\n",
+        );
+        let versions = parse_index_entry(index_entry);
+        assert_eq!(versions.len(), 5);
+
+        assert_eq!(latest_version(&versions, false).as_deref(), Some("1.10.0"));
+        assert_eq!(
+            latest_version(&versions, true).as_deref(),
+            Some("2.0.0-beta.1")
+        );
+        assert_eq!(latest_version(&[], false), None);
+    }
+
     #[test]
     fn test_downloader_creation() {
         let temp_dir = TempDir::new().unwrap();
@@ -571,6 +980,55 @@ mod tests {
         assert!(format!("{downloader:?}").contains("CrateDownloader"));
     }
 
+    #[tokio::test]
+    async fn test_download_uses_registry_mirror() -> Result<()> {
+        use std::io::{BufRead, BufReader};
+        use std::net::TcpListener;
+
+        // A mock mirror that records the request line and answers 404
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let port = listener.local_addr()?.port();
+        let server = std::thread::spawn(move || -> std::io::Result<String> {
+            let (mut stream, _) = listener.accept()?;
+            let mut request_line = String::new();
+            BufReader::new(&stream).read_line(&mut request_line)?;
+            stream.write_all(
+                b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            )?;
+            Ok(request_line)
+        });
+
+        let temp_dir = TempDir::new()?;
+        let storage = CacheStorage::new(Some(temp_dir.path().to_path_buf()))?;
+        let registry = Registry::default()
+            .with_download_url(&format!(
+                "http://127.0.0.1:{port}/mirror/{{name}}/{{version}}.crate"
+            ))?
+            .with_index_url(&format!("http://127.0.0.1:{port}/index"))?;
+        let downloader = CrateDownloader::new(storage).with_registry(registry);
+
+        let error = downloader
+            .download_crate("serde", "1.0.0", None)
+            .await
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("404"),
+            "Unexpected error: {error}"
+        );
+        // The error names the mirror, not crates.io
+        assert!(
+            error.to_string().contains(&format!("127.0.0.1:{port}")),
+            "Unexpected error: {error}"
+        );
+
+        let request_line = server.join().unwrap()?;
+        assert!(
+            request_line.starts_with("GET /mirror/serde/1.0.0.crate "),
+            "Mirror received: {request_line}"
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_user_agent_set() {
         // Initialize logging for the test
@@ -625,4 +1083,77 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_resolve_default_branch() -> Result<()> {
+        // The fixture's default branch is trunk, not main
+        let temp_dir = TempDir::new()?;
+        let repo_url = bare_repository_fixture(&temp_dir.path().join("remote.git"))?;
+        assert_eq!(default_branch(&repo_url, None)?, "trunk");
+
+        // Tree URLs already name their branch
+        assert_eq!(
+            resolve_default_branch("https://github.com/user/repo/tree/dev/crates/foo").await?,
+            "dev"
+        );
+        assert!(
+            resolve_default_branch("https://example.com/repo")
+                .await
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_clone_of_tag() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let repo_url = bare_repository_fixture(&temp_dir.path().join("remote.git"))?;
+        let dest = temp_dir.path().join("fixture");
+
+        // libgit2 cannot fetch shallowly from a local path, so clone in full
+        clone_repository(&repo_url, &dest, Some("0.1.0"), 0, None)?;
+
+        // The tagged commit is checked out, not the tip of the default branch
+        let manifest = fs::read_to_string(dest.join(CARGO_TOML))?;
+        assert!(manifest.contains("version = \"0.1.0\""));
+        assert!(dest.join("src/lib.rs").exists());
+        assert!(
+            clone_repository(
+                &repo_url,
+                &temp_dir.path().join("missing"),
+                Some("9.9.9"),
+                0,
+                None
+            )
+            .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[ignore = "clones from github.com"]
+    fn test_shallow_clone_of_tag() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let dest = temp_dir.path().join("itoa");
+
+        let repo = clone_repository(
+            "https://github.com/dtolnay/itoa",
+            &dest,
+            Some("1.0.11"),
+            DEFAULT_GIT_DEPTH,
+            None,
+        )?;
+
+        // Only the tagged commit was fetched, and its source is checked out
+        assert!(repo.is_shallow());
+        assert!(repo.find_reference("refs/tags/1.0.11").is_ok());
+        assert!(repo.find_reference("refs/remotes/origin/master").is_err());
+        let manifest = fs::read_to_string(dest.join(CARGO_TOML))?;
+        assert!(manifest.contains("version = \"1.0.11\""));
+        assert!(dest.join("src/lib.rs").exists());
+
+        Ok(())
+    }
 }
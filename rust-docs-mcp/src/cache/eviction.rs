@@ -0,0 +1,243 @@
+//! Policies choosing which cached crates to evict when space is needed
+//!
+//! A policy orders the cached crate versions from first to last to evict:
+//! `lru` by last access, `lfu` by number of accesses and `age` by when the
+//! version was cached. Crates cached from local paths are exempt unless
+//! configured otherwise, since the user put them there deliberately.
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Names accepted by [`EvictionPolicy::parse`]
+const POLICY_NAMES: [&str; 3] = ["lru", "lfu", "age"];
+
+/// A cached crate version that may be evicted
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvictionCandidate {
+    pub name: String,
+    pub version: String,
+    /// Source the version was cached from, e.g. "crates.io" or "local"
+    pub source: String,
+    pub size_bytes: u64,
+    pub cached_at: DateTime<Utc>,
+    /// When the version was last used, if it was used since this was tracked
+    pub last_accessed: Option<DateTime<Utc>>,
+    /// How often the version was used, counted once per access resolution
+    pub access_count: u64,
+}
+
+impl EvictionCandidate {
+    /// Last use, or when the version was cached if it was never used
+    fn last_used(&self) -> DateTime<Utc> {
+        self.last_accessed.unwrap_or(self.cached_at)
+    }
+}
+
+/// Orders eviction candidates, the first to evict sorting first
+pub trait EvictionStrategy: fmt::Debug + Send + Sync {
+    fn compare(&self, a: &EvictionCandidate, b: &EvictionCandidate) -> Ordering;
+}
+
+/// Evict the least recently accessed versions first
+#[derive(Debug)]
+struct LeastRecentlyAccessed;
+
+impl EvictionStrategy for LeastRecentlyAccessed {
+    fn compare(&self, a: &EvictionCandidate, b: &EvictionCandidate) -> Ordering {
+        a.last_used().cmp(&b.last_used())
+    }
+}
+
+/// Evict the least frequently accessed versions first, the least recent among equals
+#[derive(Debug)]
+struct LeastFrequentlyAccessed;
+
+impl EvictionStrategy for LeastFrequentlyAccessed {
+    fn compare(&self, a: &EvictionCandidate, b: &EvictionCandidate) -> Ordering {
+        a.access_count
+            .cmp(&b.access_count)
+            .then_with(|| a.last_used().cmp(&b.last_used()))
+    }
+}
+
+/// Evict the versions that were cached longest ago first
+#[derive(Debug)]
+struct OldestCached;
+
+impl EvictionStrategy for OldestCached {
+    fn compare(&self, a: &EvictionCandidate, b: &EvictionCandidate) -> Ordering {
+        a.cached_at.cmp(&b.cached_at)
+    }
+}
+
+/// Policy selecting which cached crate versions to evict first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Least recently accessed
+    #[default]
+    Lru,
+    /// Least frequently accessed
+    Lfu,
+    /// Oldest `cached_at`
+    Age,
+}
+
+impl EvictionPolicy {
+    /// Parse a policy name: `lru`, `lfu` or `age`
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "lru" => Ok(Self::Lru),
+            "lfu" => Ok(Self::Lfu),
+            "age" => Ok(Self::Age),
+            other => bail!(
+                "Unknown eviction policy '{other}'. Must be one of: {}",
+                POLICY_NAMES.join(", ")
+            ),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Lru => "lru",
+            Self::Lfu => "lfu",
+            Self::Age => "age",
+        }
+    }
+
+    fn strategy(&self) -> &'static dyn EvictionStrategy {
+        match self {
+            Self::Lru => &LeastRecentlyAccessed,
+            Self::Lfu => &LeastFrequentlyAccessed,
+            Self::Age => &OldestCached,
+        }
+    }
+
+    /// Choose the versions to evict to free at least `bytes_to_free` bytes
+    ///
+    /// Candidates are taken in policy order, ties broken by name and version,
+    /// until enough space is freed or none are left. Local crates are skipped
+    /// unless `evict_local` is set.
+    pub fn select_victims(
+        &self,
+        mut candidates: Vec<EvictionCandidate>,
+        bytes_to_free: u64,
+        evict_local: bool,
+    ) -> Vec<EvictionCandidate> {
+        let strategy = self.strategy();
+        candidates.retain(|candidate| evict_local || candidate.source != "local");
+        candidates.sort_by(|a, b| {
+            strategy
+                .compare(a, b)
+                .then_with(|| (&a.name, &a.version).cmp(&(&b.name, &b.version)))
+        });
+
+        let mut freed = 0;
+        candidates
+            .into_iter()
+            .take_while(|candidate| {
+                let needed = freed < bytes_to_free;
+                freed += candidate.size_bytes;
+                needed
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for EvictionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A crates.io candidate cached `cached_days` ago and last used `accessed_days` ago
+    fn candidate(
+        name: &str,
+        cached_days: i64,
+        accessed_days: Option<i64>,
+        access_count: u64,
+    ) -> EvictionCandidate {
+        let now = Utc::now();
+        EvictionCandidate {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            source: "crates.io".to_string(),
+            size_bytes: 100,
+            cached_at: now - chrono::Duration::days(cached_days),
+            last_accessed: accessed_days.map(|days| now - chrono::Duration::days(days)),
+            access_count,
+        }
+    }
+
+    /// A seeded cache where each policy picks a different first victim
+    fn seeded_cache() -> Vec<EvictionCandidate> {
+        vec![
+            // Cached long ago, but used often and recently
+            candidate("old-popular", 30, Some(0), 50),
+            // Used rarely, but yesterday
+            candidate("rare", 5, Some(1), 1),
+            // Used often, but not for a while
+            candidate("stale", 10, Some(8), 20),
+            // Never used since it was cached
+            candidate("unused", 3, None, 0),
+        ]
+    }
+
+    fn victim_names(policy: EvictionPolicy, bytes_to_free: u64) -> Vec<String> {
+        policy
+            .select_victims(seeded_cache(), bytes_to_free, false)
+            .into_iter()
+            .map(|victim| victim.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_lru_selects_least_recently_used() {
+        // A never used version counts as used when it was cached
+        assert_eq!(victim_names(EvictionPolicy::Lru, 100), ["stale"]);
+        assert_eq!(victim_names(EvictionPolicy::Lru, 150), ["stale", "unused"]);
+    }
+
+    #[test]
+    fn test_lfu_selects_least_frequently_used() {
+        assert_eq!(victim_names(EvictionPolicy::Lfu, 100), ["unused"]);
+        assert_eq!(victim_names(EvictionPolicy::Lfu, 200), ["unused", "rare"]);
+    }
+
+    #[test]
+    fn test_age_selects_oldest_cached() {
+        assert_eq!(victim_names(EvictionPolicy::Age, 100), ["old-popular"]);
+        assert_eq!(
+            victim_names(EvictionPolicy::Age, 300),
+            ["old-popular", "stale", "rare"]
+        );
+    }
+
+    #[test]
+    fn test_local_crates_are_exempt_by_default() {
+        let mut cache = seeded_cache();
+        cache[0].source = "local".to_string();
+
+        let victims = EvictionPolicy::Age.select_victims(cache.clone(), 100, false);
+        assert_eq!(victims[0].name, "stale");
+
+        let victims = EvictionPolicy::Age.select_victims(cache, 100, true);
+        assert_eq!(victims[0].name, "old-popular");
+    }
+
+    #[test]
+    fn test_parse_policy() -> Result<()> {
+        assert_eq!(EvictionPolicy::parse("LRU")?, EvictionPolicy::Lru);
+        assert_eq!(EvictionPolicy::parse("lfu")?, EvictionPolicy::Lfu);
+        assert_eq!(EvictionPolicy::parse(" age ")?, EvictionPolicy::Age);
+        assert_eq!(EvictionPolicy::default().to_string(), "lru");
+        assert!(EvictionPolicy::parse("fifo").is_err());
+        assert!(victim_names(EvictionPolicy::Lru, 0).is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,374 @@
+//! Versioning and migration of the on-disk cache layout
+//!
+//! The cache root contains a `CACHE_LAYOUT_VERSION` file recording the layout
+//! version the cache was written with. Caches created before the file existed
+//! are treated as version 0 and can be upgraded with `rust-docs-mcp migrate`.
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cache::constants::*;
+use crate::cache::member_utils::normalize_member_path;
+
+/// Layout version written by this build
+///
+/// Version 1 requires a `source` field in every metadata file and stores
+/// workspace members in flat, normalized directories (`members/crates-rmcp`).
+pub const CACHE_LAYOUT_VERSION: u32 = 1;
+
+/// How the layout of an existing cache relates to this build
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutStatus {
+    /// The cache uses the current layout
+    Current,
+    /// The cache uses an older layout that `migrate` can upgrade
+    Outdated(u32),
+    /// The cache was written by a newer build and must not be modified
+    Unsupported(u32),
+}
+
+/// Summary of a layout migration
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationReport {
+    pub from_version: u32,
+    pub to_version: u32,
+    /// Number of metadata files that were rewritten
+    pub metadata_updated: usize,
+    /// Number of workspace member directories moved to their normalized name
+    pub members_normalized: usize,
+}
+
+/// Path of the layout version file for a cache root
+pub fn layout_version_path(cache_dir: &Path) -> PathBuf {
+    cache_dir.join(LAYOUT_VERSION_FILE)
+}
+
+/// Read the layout version of a cache
+///
+/// A cache without a version file is version 0 if it already contains crates,
+/// otherwise it is new and `None` is returned.
+pub fn read_layout_version(cache_dir: &Path) -> Result<Option<u32>> {
+    let version_path = layout_version_path(cache_dir);
+    if version_path.exists() {
+        let content = fs::read_to_string(&version_path).with_context(|| {
+            format!(
+                "Failed to read cache layout version: {}",
+                version_path.display()
+            )
+        })?;
+        let version = content.trim().parse::<u32>().with_context(|| {
+            format!(
+                "Invalid cache layout version '{}' in {}",
+                content.trim(),
+                version_path.display()
+            )
+        })?;
+        return Ok(Some(version));
+    }
+
+    let has_crates = fs::read_dir(cache_dir.join(CRATES_DIR))
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false);
+    Ok(has_crates.then_some(0))
+}
+
+/// Write the current layout version to the cache root
+pub fn write_layout_version(cache_dir: &Path) -> Result<()> {
+    let version_path = layout_version_path(cache_dir);
+    fs::write(&version_path, format!("{CACHE_LAYOUT_VERSION}\n")).with_context(|| {
+        format!(
+            "Failed to write cache layout version: {}",
+            version_path.display()
+        )
+    })
+}
+
+/// Compare a cache's layout version with the one this build expects
+pub fn layout_status(version: u32) -> LayoutStatus {
+    match version {
+        v if v == CACHE_LAYOUT_VERSION => LayoutStatus::Current,
+        v if v < CACHE_LAYOUT_VERSION => LayoutStatus::Outdated(v),
+        v => LayoutStatus::Unsupported(v),
+    }
+}
+
+/// Upgrade a cache to the current layout version
+pub fn migrate(cache_dir: &Path) -> Result<MigrationReport> {
+    let from_version = read_layout_version(cache_dir)?.unwrap_or(CACHE_LAYOUT_VERSION);
+    let mut report = MigrationReport {
+        from_version,
+        to_version: CACHE_LAYOUT_VERSION,
+        ..MigrationReport::default()
+    };
+
+    if let LayoutStatus::Unsupported(version) = layout_status(from_version) {
+        bail!(
+            "Cache layout version {version} is newer than the supported version {CACHE_LAYOUT_VERSION}. Update rust-docs-mcp to use this cache"
+        );
+    }
+
+    if from_version < 1 {
+        migrate_v0_to_v1(cache_dir, &mut report)?;
+    }
+
+    write_layout_version(cache_dir)?;
+    Ok(report)
+}
+
+/// Add missing `source` fields and flatten nested member directories
+fn migrate_v0_to_v1(cache_dir: &Path, report: &mut MigrationReport) -> Result<()> {
+    for version_dir in crate_version_dirs(cache_dir)? {
+        let members_dir = version_dir.join(MEMBERS_DIR);
+        if members_dir.is_dir() {
+            report.members_normalized += normalize_member_dirs(&members_dir)?;
+        }
+
+        let mut metadata_files = vec![version_dir.join(METADATA_FILE)];
+        if members_dir.is_dir() {
+            for entry in fs::read_dir(&members_dir)? {
+                metadata_files.push(entry?.path().join(METADATA_FILE));
+            }
+        }
+
+        for metadata_path in metadata_files {
+            if metadata_path.is_file() && upgrade_metadata_file(&metadata_path)? {
+                report.metadata_updated += 1;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// List every `crates/<name>/<version>` directory
+fn crate_version_dirs(cache_dir: &Path) -> Result<Vec<PathBuf>> {
+    let crates_dir = cache_dir.join(CRATES_DIR);
+    let mut dirs = Vec::new();
+
+    if !crates_dir.exists() {
+        return Ok(dirs);
+    }
+
+    for crate_entry in fs::read_dir(&crates_dir)? {
+        let crate_entry = crate_entry?;
+        if !crate_entry.file_type()?.is_dir() {
+            continue;
+        }
+        for version_entry in fs::read_dir(crate_entry.path())? {
+            let version_entry = version_entry?;
+            if version_entry.file_type()?.is_dir() {
+                dirs.push(version_entry.path());
+            }
+        }
+    }
+
+    Ok(dirs)
+}
+
+/// Move members stored at their nested path (`members/crates/rmcp`) to the
+/// normalized directory (`members/crates-rmcp`)
+fn normalize_member_dirs(members_dir: &Path) -> Result<usize> {
+    let mut nested = Vec::new();
+    find_nested_members(members_dir, members_dir, &mut nested)?;
+
+    let mut moved = 0;
+    for (member_path, dir) in &nested {
+        let target = members_dir.join(normalize_member_path(member_path));
+        if target.exists() {
+            tracing::warn!(
+                "Skipping member {member_path}: {} already exists",
+                target.display()
+            );
+            continue;
+        }
+        fs::rename(dir, &target).with_context(|| {
+            format!(
+                "Failed to move member {} to {}",
+                dir.display(),
+                target.display()
+            )
+        })?;
+        remove_empty_parents(dir, members_dir);
+        moved += 1;
+    }
+
+    Ok(moved)
+}
+
+/// Collect member directories that are nested below the top level of `members_dir`
+fn find_nested_members(
+    members_dir: &Path,
+    dir: &Path,
+    nested: &mut Vec<(String, PathBuf)>,
+) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let path = entry.path();
+        let is_member = path.join(METADATA_FILE).is_file() || path.join(DOCS_FILE).is_file();
+
+        if is_member && dir != members_dir {
+            let relative = path.strip_prefix(members_dir)?;
+            let member_path = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            nested.push((member_path, path));
+        } else if !is_member {
+            find_nested_members(members_dir, &path, nested)?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove the directories left empty after moving a nested member
+fn remove_empty_parents(moved_from: &Path, members_dir: &Path) {
+    let mut current = moved_from.parent();
+    while let Some(dir) = current {
+        if dir == members_dir || fs::remove_dir(dir).is_err() {
+            break;
+        }
+        current = dir.parent();
+    }
+}
+
+/// Fill in fields that older layouts did not write. Returns true if the file changed
+fn upgrade_metadata_file(metadata_path: &Path) -> Result<bool> {
+    let content = fs::read_to_string(metadata_path)
+        .with_context(|| format!("Failed to read {}", metadata_path.display()))?;
+    let mut metadata: serde_json::Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", metadata_path.display()))?;
+
+    let Some(object) = metadata.as_object_mut() else {
+        return Ok(false);
+    };
+
+    let mut changed = false;
+    if !object.contains_key("source") {
+        object.insert("source".to_string(), "crates.io".into());
+        changed = true;
+    }
+    if let Some(member_info) = object
+        .get_mut("member_info")
+        .and_then(|info| info.as_object_mut())
+        && let Some(original) = member_info
+            .get("original_path")
+            .and_then(|p| p.as_str())
+            .map(normalize_member_path)
+        && member_info.get("normalized_path").and_then(|p| p.as_str()) != Some(original.as_str())
+    {
+        member_info.insert("normalized_path".to_string(), original.into());
+        changed = true;
+    }
+
+    if changed {
+        fs::write(metadata_path, serde_json::to_string_pretty(&metadata)?)
+            .with_context(|| format!("Failed to write {}", metadata_path.display()))?;
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Build a cache as written before layout versioning existed
+    fn create_v0_cache(root: &Path) -> Result<PathBuf> {
+        let version_dir = root.join(CRATES_DIR).join("mycrate").join("1.0.0");
+        fs::create_dir_all(version_dir.join(SOURCE_DIR))?;
+        fs::write(
+            version_dir.join(METADATA_FILE),
+            r#"{"name":"mycrate","version":"1.0.0","cached_at":"2024-01-01T00:00:00Z","doc_generated":true,"size_bytes":0}"#,
+        )?;
+
+        let nested_member = version_dir.join(MEMBERS_DIR).join("crates").join("core");
+        fs::create_dir_all(&nested_member)?;
+        fs::write(nested_member.join(DOCS_FILE), "{}")?;
+        fs::write(
+            nested_member.join(METADATA_FILE),
+            r#"{"name":"core","version":"1.0.0","cached_at":"2024-01-01T00:00:00Z","doc_generated":true,"size_bytes":0,"member_info":{"original_path":"crates/core","normalized_path":"crates/core","package_name":"core"}}"#,
+        )?;
+
+        Ok(version_dir)
+    }
+
+    #[test]
+    fn test_new_cache_has_no_layout_version() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        assert_eq!(read_layout_version(temp_dir.path())?, None);
+
+        write_layout_version(temp_dir.path())?;
+        assert_eq!(
+            read_layout_version(temp_dir.path())?,
+            Some(CACHE_LAYOUT_VERSION)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_layout_status() {
+        assert_eq!(layout_status(CACHE_LAYOUT_VERSION), LayoutStatus::Current);
+        assert_eq!(layout_status(0), LayoutStatus::Outdated(0));
+        assert_eq!(
+            layout_status(CACHE_LAYOUT_VERSION + 1),
+            LayoutStatus::Unsupported(CACHE_LAYOUT_VERSION + 1)
+        );
+    }
+
+    #[test]
+    fn test_migrate_v0_cache() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let version_dir = create_v0_cache(temp_dir.path())?;
+        assert_eq!(read_layout_version(temp_dir.path())?, Some(0));
+
+        let report = migrate(temp_dir.path())?;
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.to_version, CACHE_LAYOUT_VERSION);
+        assert_eq!(report.members_normalized, 1);
+        assert_eq!(report.metadata_updated, 2);
+        assert_eq!(
+            read_layout_version(temp_dir.path())?,
+            Some(CACHE_LAYOUT_VERSION)
+        );
+
+        let metadata: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(version_dir.join(METADATA_FILE))?)?;
+        assert_eq!(metadata["source"], "crates.io");
+
+        let members_dir = version_dir.join(MEMBERS_DIR);
+        let member_dir = members_dir.join("crates-core");
+        assert!(member_dir.join(DOCS_FILE).is_file());
+        assert!(!members_dir.join("crates").exists());
+        let member_metadata: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(member_dir.join(METADATA_FILE))?)?;
+        assert_eq!(
+            member_metadata["member_info"]["normalized_path"],
+            "crates-core"
+        );
+
+        // Migrating again is a no-op
+        let report = migrate(temp_dir.path())?;
+        assert_eq!(report.from_version, CACHE_LAYOUT_VERSION);
+        assert_eq!(report.metadata_updated, 0);
+        assert_eq!(report.members_normalized, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_refuses_newer_layout() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        fs::write(
+            layout_version_path(temp_dir.path()),
+            format!("{}", CACHE_LAYOUT_VERSION + 1),
+        )?;
+
+        assert!(migrate(temp_dir.path()).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+//! Lock files shared between server processes
+//!
+//! Several server processes can use the same cache directory. Downloads and
+//! documentation generation write into a crate's directory, so each of them
+//! takes a lock file first. Other processes wait until the lock is released and
+//! then use the result instead of repeating the work.
+
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How often a waiting process checks whether a lock was released
+pub const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A held lock file, removed when dropped
+#[derive(Debug)]
+pub struct LockFile {
+    path: PathBuf,
+}
+
+impl LockFile {
+    /// Create the lock file at `path`, or return `None` if it already exists
+    pub fn try_acquire(path: &Path) -> Result<Option<Self>> {
+        match OpenOptions::new().write(true).create_new(true).open(path) {
+            Ok(mut file) => {
+                // The holder's pid helps to tell a stale lock apart when debugging
+                let _ = write!(file, "{}", std::process::id());
+                Ok(Some(Self {
+                    path: path.to_path_buf(),
+                }))
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(None),
+            Err(e) => {
+                Err(e).with_context(|| format!("Failed to create lock file: {}", path.display()))
+            }
+        }
+    }
+
+    /// Create the lock file at `path`, waiting while another process holds it
+    ///
+    /// A lock still held after `timeout` is assumed to be left behind by a
+    /// process that died, and is taken over.
+    pub async fn acquire(path: &Path, timeout: Duration) -> Result<Self> {
+        let start = Instant::now();
+        let mut waiting = false;
+        loop {
+            if let Some(lock) = Self::try_acquire(path)? {
+                return Ok(lock);
+            }
+
+            if start.elapsed() >= timeout {
+                tracing::warn!(
+                    "Lock {} still held after {:?}, taking it over",
+                    path.display(),
+                    timeout
+                );
+                std::fs::remove_file(path).ok();
+                continue;
+            }
+
+            if !waiting {
+                tracing::info!(
+                    "Waiting for lock held by another process: {}",
+                    path.display()
+                );
+                waiting = true;
+            }
+            tokio::time::sleep(LOCK_POLL_INTERVAL).await;
+        }
+    }
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_lock_file_is_exclusive() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("docs.lock");
+
+        let lock = LockFile::try_acquire(&path)?.expect("lock should be free");
+        assert!(LockFile::try_acquire(&path)?.is_none());
+
+        // A waiter gets the lock once the holder releases it
+        let waiter = tokio::spawn({
+            let path = path.clone();
+            async move { LockFile::acquire(&path, Duration::from_secs(30)).await }
+        });
+        tokio::time::sleep(LOCK_POLL_INTERVAL * 3).await;
+        assert!(!waiter.is_finished());
+        drop(lock);
+        let lock = waiter.await??;
+        assert!(path.exists());
+
+        drop(lock);
+        assert!(!path.exists());
+
+        // A lock left behind by a dead process is taken over after the timeout
+        std::fs::write(&path, "stale")?;
+        let _lock = LockFile::acquire(&path, Duration::ZERO).await?;
+        assert!(path.exists());
+
+        Ok(())
+    }
+}
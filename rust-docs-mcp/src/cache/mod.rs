@@ -6,8 +6,19 @@
 //!
 //! - [`service`] - Main caching service that coordinates all cache operations
 //! - [`storage`] - Low-level storage operations for cached crates
-//! - [`downloader`] - Downloads crates from various sources (crates.io, GitHub, local)
+//! - [`archive`] - Unpacking `.crate` archives
+//! - [`lock`] - Lock files shared between server processes
+//! - [`layout`] - On-disk layout versioning and migration
+//! - [`downloader`] - Downloads crates from various sources (crates.io, GitHub, local, archives)
+//! - [`eviction`] - Policies choosing which cached crates to evict
+//! - [`watchdog`] - Background task keeping the cache within its size budget
+//! - [`pins`] - Reference counts keeping in-use crates from being removed
+//! - [`policy`] - Policy restricting the sources crates may be cached from
+//! - [`proc_macro`] - Proc-macro crate detection and their exported macros
+//! - [`registry`] - Download and index URLs of crates.io or a mirror
 //! - [`docgen`] - Generates JSON documentation using cargo rustdoc
+//! - [`diagnosis`] - Explanations of common documentation build failures
+//! - [`docsrs`] - Prebuilt rustdoc JSON downloaded from docs.rs
 //! - [`source`] - Source type detection and parsing (crates.io, GitHub, local paths)
 //! - [`tools`] - MCP tool implementations for cache operations
 //! - [`transaction`] - Transactional updates with automatic rollback
@@ -16,11 +27,21 @@
 //! - [`workspace`] - Workspace crate handling
 //! - [`outputs`] - Output types for cache operations
 
+pub mod archive;
 pub mod constants;
+pub mod diagnosis;
 pub mod docgen;
+pub mod docsrs;
 pub mod downloader;
+pub mod eviction;
+pub mod layout;
+pub mod lock;
 pub mod member_utils;
 pub mod outputs;
+pub mod pins;
+pub mod policy;
+pub mod proc_macro;
+pub mod registry;
 pub mod service;
 pub mod source;
 pub mod storage;
@@ -30,6 +51,7 @@ pub mod tools;
 pub mod transaction;
 pub mod types;
 pub mod utils;
+pub mod watchdog;
 pub mod workspace;
 
 pub use service::CrateCache;
@@ -5,7 +5,9 @@
 //! deserialized in tests for type-safe validation.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::timeout::ToolCallStats;
 
 /// Output from async cache_crate operations - returns task ID for monitoring
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -19,6 +21,9 @@ pub struct CacheTaskStartedOutput {
     pub source_details: Option<String>,
     pub status: String,
     pub message: String,
+    /// Whether an identical request was already running, so its task was returned
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub existing_task: bool,
 }
 
 impl CacheTaskStartedOutput {
@@ -46,6 +51,13 @@ pub enum CacheCrateOutput {
         results: Option<Vec<String>>,
         #[serde(skip_serializing_if = "Option::is_none")]
         updated: Option<bool>,
+        /// Set when the cached version has been yanked from crates.io
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        yanked: Option<bool>,
+        /// Where the documentation came from: "local" when generated with the
+        /// docgen toolchain, "docsrs" when downloaded prebuilt from docs.rs
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        doc_source: Option<String>,
     },
     /// Partial success when caching workspace members
     #[serde(rename = "partial_success")]
@@ -118,6 +130,175 @@ impl RemoveCrateOutput {
     }
 }
 
+/// Output from clear_all_caches operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ClearAllCachesOutput {
+    pub status: String,
+    pub message: String,
+    pub crates_removed: usize,
+    pub reclaimed: SizeInfo,
+}
+
+impl ClearAllCachesOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// A search index that could not be rebuilt by reindex
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ReindexFailure {
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member: Option<String>,
+    pub error: String,
+}
+
+/// Output from reindex operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ReindexOutput {
+    pub status: String,
+    pub message: String,
+    /// Number of search indexes rebuilt successfully
+    pub rebuilt: usize,
+    pub failed: Vec<ReindexFailure>,
+}
+
+impl ReindexOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// A search index removed by prune_indexes
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct PrunedIndex {
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    pub version: String,
+    /// Normalized member path, for workspace member indexes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member: Option<String>,
+    pub bytes: u64,
+}
+
+/// Output from prune_indexes operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct PruneIndexesOutput {
+    pub status: String,
+    pub message: String,
+    pub removed: Vec<PrunedIndex>,
+    pub reclaimed: SizeInfo,
+}
+
+impl PruneIndexesOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// Output from server_info operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ServerInfoOutput {
+    pub name: String,
+    pub version: String,
+    /// Latest MCP protocol version supported by the server
+    pub protocol_version: String,
+    pub cache_dir: String,
+    /// False if the cache was written by a newer version and is read-only
+    pub cache_writable: bool,
+    /// Whether the server was started with --read-only
+    #[serde(default)]
+    pub read_only: bool,
+    /// Unix mode given to cache directories, e.g. "0700"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_permissions: Option<String>,
+    pub cached_crates: usize,
+    pub cached_versions: usize,
+    /// Caching tasks that are queued or running
+    pub active_tasks: usize,
+    pub docgen_toolchain: String,
+    /// Rustdoc JSON format version this server understands
+    pub rustdoc_format_version: u32,
+    /// Extra arguments passed to rustdoc, such as `--cfg docsrs`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rustdoc_args: Vec<String>,
+    pub registry_download_url: String,
+    pub registry_index_url: String,
+    /// Base URL of docs.rs, used for the docs fallback and item links
+    pub docsrs_url: String,
+    /// Commits fetched when cloning git repositories, zero for the full history
+    pub git_depth: u32,
+    /// Whether the git history of cloned repositories is kept in the cache
+    pub keep_git_history: bool,
+    /// Sources crates may be cached from, empty if every source is allowed
+    pub allowed_sources: Vec<String>,
+    /// Policy choosing which cached crates to evict first: lru, lfu or age
+    pub eviction_policy: String,
+    /// Whether crates cached from local paths may be evicted
+    pub evict_local_crates: bool,
+    /// Size the cached crates may take up before the watchdog evicts some
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cache_bytes: Option<u64>,
+    /// Size of the cached crates at the watchdog's last check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_size_bytes: Option<u64>,
+    /// Failed documentation builds by category since the server started, the
+    /// `rust_docs_mcp_docgen_failures_total` counter
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub docgen_failures: BTreeMap<String, u64>,
+    /// Calls and durations by tool since the server started, the
+    /// `rust_docs_mcp_tool_calls_total` counter and
+    /// `rust_docs_mcp_tool_duration_seconds` histogram
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tool_calls: BTreeMap<String, ToolCallStats>,
+    pub query_timeout_secs: u64,
+    pub cache_timeout_secs: u64,
+    pub max_concurrent_tasks: usize,
+    pub max_response_bytes: usize,
+    /// Time search_items may scan items for, absent if unlimited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_time_budget_ms: Option<u64>,
+    pub auto_regenerate_incompatible: bool,
+}
+
+impl ServerInfoOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// Output from ensure_ready operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct EnsureReadyOutput {
+    /// Whether the docs are available for queries
+    pub ready: bool,
+    /// Whether the docs had to be generated by this call
+    pub generated: bool,
+    pub elapsed_ms: u64,
+    /// Why the docs could not be made available
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl EnsureReadyOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
 /// Version information for a cached crate
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct VersionInfo {
@@ -128,6 +309,12 @@ pub struct VersionInfo {
     pub size_human: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub members: Option<Vec<String>>,
+    /// Whether the version has been yanked from crates.io
+    #[serde(default)]
+    pub yanked: bool,
+    /// When the version or one of its members was last queried
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_accessed: Option<String>,
 }
 
 /// Size information with human-readable format
@@ -186,6 +373,12 @@ pub struct CrateMetadata {
     pub member: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub workspace_members: Option<Vec<String>>,
+    /// Whether the version has been yanked from crates.io
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub yanked: Option<bool>,
+    /// Why metadata could not be loaded for a cached crate (e.g. a corrupt metadata file)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 /// Output from get_crates_metadata operation
@@ -238,6 +431,8 @@ mod tests {
             members: None,
             results: None,
             updated: None,
+            yanked: None,
+            doc_source: None,
         };
 
         let json = output.to_json();
@@ -0,0 +1,86 @@
+//! Reference counts of crate versions in use by running queries
+//!
+//! Most queries hold the cache lock while they read a crate, but some, such as
+//! structure analysis and fuzzy search, release it before the slow part of the
+//! work. A [`CratePin`] keeps the crate version they read from being removed
+//! until the query has finished.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Number of running queries per crate version
+#[derive(Debug, Clone, Default)]
+pub struct CratePins {
+    counts: Arc<Mutex<HashMap<(String, String), usize>>>,
+}
+
+impl CratePins {
+    /// Mark a crate version as in use until the returned pin is dropped
+    pub fn pin(&self, name: &str, version: &str) -> CratePin {
+        let key = (name.to_string(), version.to_string());
+        *self.lock().entry(key.clone()).or_default() += 1;
+        CratePin {
+            pins: self.clone(),
+            key,
+        }
+    }
+
+    /// Whether any running query uses the crate version
+    pub fn is_pinned(&self, name: &str, version: &str) -> bool {
+        self.lock()
+            .contains_key(&(name.to_string(), version.to_string()))
+    }
+
+    /// Whether any running query uses any crate version
+    pub fn any_pinned(&self) -> bool {
+        !self.lock().is_empty()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<(String, String), usize>> {
+        // The map stays consistent even if a holder panicked
+        self.counts
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Keeps a crate version from being removed while it is alive
+#[derive(Debug)]
+pub struct CratePin {
+    pins: CratePins,
+    key: (String, String),
+}
+
+impl Drop for CratePin {
+    fn drop(&mut self) {
+        let mut counts = self.pins.lock();
+        if let Some(count) = counts.get_mut(&self.key) {
+            *count -= 1;
+            if *count == 0 {
+                counts.remove(&self.key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pins_are_reference_counted() {
+        let pins = CratePins::default();
+        assert!(!pins.any_pinned());
+
+        let first = pins.pin("serde", "1.0.0");
+        let second = pins.pin("serde", "1.0.0");
+        assert!(pins.is_pinned("serde", "1.0.0"));
+        assert!(!pins.is_pinned("serde", "1.0.1"));
+
+        drop(first);
+        assert!(pins.is_pinned("serde", "1.0.0"));
+        drop(second);
+        assert!(!pins.is_pinned("serde", "1.0.0"));
+        assert!(!pins.any_pinned());
+    }
+}
@@ -0,0 +1,189 @@
+//! Policy restricting the sources crates may be cached from
+//!
+//! Every source is allowed by default. Operators of locked-down environments
+//! can list the allowed sources instead, e.g. `cratesio,github.com/my-org` to
+//! only fetch from crates.io and the repositories of one GitHub organization.
+
+use crate::cache::source::SourceType;
+use anyhow::{Result, bail};
+
+/// Source kinds that can be named in a policy, besides git hosts
+const SOURCE_KINDS: [&str; 4] = ["cratesio", "github", "local", "archive"];
+
+/// A source allowed by a [`SourcePolicy`]
+#[derive(Debug, Clone, PartialEq)]
+enum AllowedSource {
+    /// The crates.io registry, or the mirror configured in its place
+    CratesIo,
+    /// Any GitHub repository
+    GitHub,
+    /// Local crate directories
+    Local,
+    /// Local `.crate` archives
+    Archive,
+    /// Git repositories on a host, optionally below a path, e.g. `github.com/my-org`
+    GitHost(String),
+}
+
+impl AllowedSource {
+    fn parse(entry: &str) -> Result<Self> {
+        let entry = entry.trim().to_lowercase();
+        match entry.as_str() {
+            "cratesio" | "crates.io" => Ok(Self::CratesIo),
+            "github" => Ok(Self::GitHub),
+            "local" => Ok(Self::Local),
+            "archive" => Ok(Self::Archive),
+            host if host.contains('.') && !host.contains("://") => {
+                Ok(Self::GitHost(host.trim_end_matches('/').to_string()))
+            }
+            other => bail!(
+                "Unknown allowed source '{other}'. Must be one of: {}, or a git host such as 'github.com/my-org'",
+                SOURCE_KINDS.join(", ")
+            ),
+        }
+    }
+
+    fn allows(&self, source: &SourceType) -> bool {
+        match (self, source) {
+            (Self::CratesIo, SourceType::CratesIo)
+            | (Self::GitHub, SourceType::GitHub { .. })
+            | (Self::Local, SourceType::Local { .. })
+            | (Self::Archive, SourceType::Archive { .. }) => true,
+            (Self::GitHost(allowed), SourceType::GitHub { url, .. }) => {
+                let location = url
+                    .split_once("://")
+                    .map_or(url.as_str(), |(_, rest)| rest)
+                    .to_lowercase();
+                location == *allowed || location.starts_with(&format!("{allowed}/"))
+            }
+            _ => false,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Self::CratesIo => "cratesio",
+            Self::GitHub => "github",
+            Self::Local => "local",
+            Self::Archive => "archive",
+            Self::GitHost(host) => host,
+        }
+    }
+}
+
+/// Sources crates may be cached from
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourcePolicy {
+    /// Allowed sources, or `None` to allow every source
+    allowed: Option<Vec<AllowedSource>>,
+}
+
+impl SourcePolicy {
+    /// Parse a comma-separated list of allowed sources
+    ///
+    /// Entries are `cratesio`, `github`, `local`, `archive` or a git host with
+    /// an optional path prefix such as `github.com/my-org`.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let allowed = spec
+            .split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .map(AllowedSource::parse)
+            .collect::<Result<Vec<_>>>()?;
+        if allowed.is_empty() {
+            bail!("The list of allowed sources is empty");
+        }
+        Ok(Self {
+            allowed: Some(allowed),
+        })
+    }
+
+    /// Whether every source is allowed
+    pub fn allows_all(&self) -> bool {
+        self.allowed.is_none()
+    }
+
+    /// Names of the allowed sources, empty if every source is allowed
+    pub fn allowed_sources(&self) -> Vec<String> {
+        self.allowed
+            .iter()
+            .flatten()
+            .map(|source| source.name().to_string())
+            .collect()
+    }
+
+    /// Fail unless the policy allows caching from `source`
+    pub fn check(&self, source: &SourceType) -> Result<()> {
+        let Some(allowed) = &self.allowed else {
+            return Ok(());
+        };
+        if allowed.iter().any(|entry| entry.allows(source)) {
+            return Ok(());
+        }
+
+        let description = match source {
+            SourceType::CratesIo => "crates.io".to_string(),
+            SourceType::GitHub { url, .. } => format!("GitHub repository {url}"),
+            SourceType::Local { path } => format!("local path {path}"),
+            SourceType::Archive { path } => format!("crate archive {path}"),
+        };
+        bail!(
+            "Source not allowed by policy: {description}. Allowed sources: {}",
+            self.allowed_sources().join(", ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::source::GitReference;
+
+    fn github(url: &str) -> SourceType {
+        SourceType::GitHub {
+            url: url.to_string(),
+            repo_path: None,
+            reference: GitReference::Default,
+        }
+    }
+
+    #[test]
+    fn test_source_policy() -> Result<()> {
+        let policy = SourcePolicy::default();
+        assert!(policy.allows_all());
+        assert!(policy.check(&github("https://github.com/any/repo")).is_ok());
+
+        let policy = SourcePolicy::parse("cratesio, GitHub.com/my-org/")?;
+        assert_eq!(policy.allowed_sources(), ["cratesio", "github.com/my-org"]);
+        assert!(policy.check(&SourceType::CratesIo).is_ok());
+        assert!(
+            policy
+                .check(&github("https://github.com/my-org/repo"))
+                .is_ok()
+        );
+        assert!(
+            policy
+                .check(&github("https://github.com/my-org-fork/repo"))
+                .is_err()
+        );
+        let error = policy
+            .check(&SourceType::Local {
+                path: "/tmp/crate".to_string(),
+            })
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("Source not allowed by policy: local path /tmp/crate")
+        );
+
+        assert!(
+            SourcePolicy::parse("github")?
+                .check(&github("https://github.com/a/b"))
+                .is_ok()
+        );
+        assert!(SourcePolicy::parse("cratesio,gitlab").is_err());
+        assert!(SourcePolicy::parse(" , ").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+//! Proc-macro crate detection
+//!
+//! Proc-macro crates (`[lib] proc-macro = true`) are compiled for the host and
+//! export nothing but macros. When rustdoc cannot document one, the macros it
+//! exports are read from its source instead so the error can still tell the
+//! caller what the crate provides.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use toml::Value;
+
+use crate::analysis::targets::discover_targets;
+use crate::cache::constants::CARGO_TOML;
+
+/// Error returned when documentation cannot be generated for a proc-macro crate
+///
+/// Callers can detect it with `error.downcast_ref::<ProcMacroError>()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcMacroError {
+    pub package: String,
+    /// Why the documentation could not be generated
+    pub reason: String,
+    /// Macros exported by the crate, see [`exported_macros`]
+    pub macros: Vec<String>,
+}
+
+impl fmt::Display for ProcMacroError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Cannot generate documentation for proc-macro crate '{}': {}. \
+            Proc-macro crates only export macros, which are usually documented by the \
+            crate that re-exports them (e.g. serde for serde_derive).",
+            self.package, self.reason
+        )?;
+        if self.macros.is_empty() {
+            write!(f, " No exported macros were found in its source.")
+        } else {
+            write!(f, " Exported macros: {}.", self.macros.join(", "))
+        }
+    }
+}
+
+impl std::error::Error for ProcMacroError {}
+
+impl ProcMacroError {
+    /// Create an error for the proc-macro crate at `package_dir`, listing its macros
+    pub fn new(package_dir: &Path, package: &str, reason: impl Into<String>) -> Self {
+        Self {
+            package: package.to_string(),
+            reason: reason.into(),
+            macros: exported_macros(package_dir),
+        }
+    }
+}
+
+/// Whether the package at `package_dir` is a proc-macro crate
+pub fn is_proc_macro(package_dir: &Path) -> bool {
+    let Ok(content) = fs::read_to_string(package_dir.join(CARGO_TOML)) else {
+        return false;
+    };
+    let Ok(manifest) = toml::from_str::<Value>(&content) else {
+        return false;
+    };
+
+    // `proc_macro` is the deprecated spelling, still accepted by Cargo
+    let lib = manifest.get("lib");
+    ["proc-macro", "proc_macro"].iter().any(|key| {
+        lib.and_then(|lib| lib.get(*key))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    })
+}
+
+/// Macros exported by the proc-macro crate at `package_dir`
+///
+/// Proc macros must be defined in the crate root, so only the library's root
+/// file is scanned. Function-like macros are reported as `name!`, derives as
+/// `derive(Name)` and attribute macros as `#[name]`.
+pub fn exported_macros(package_dir: &Path) -> Vec<String> {
+    let lib_path = discover_targets(package_dir)
+        .ok()
+        .and_then(|targets| targets.into_iter().find(|target| target.kind == "lib"))
+        .map(|lib| package_dir.join(lib.path));
+
+    match lib_path.map(fs::read_to_string) {
+        Some(Ok(source)) => macros_in_source(&source),
+        _ => Vec::new(),
+    }
+}
+
+/// Find the proc macro definitions in the source of a crate root
+fn macros_in_source(source: &str) -> Vec<String> {
+    let mut macros = Vec::new();
+    // Attribute seen on a previous line, waiting for the function it applies to
+    let mut pending: Option<&str> = None;
+
+    for line in source.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("#[proc_macro_derive(") {
+            let name = rest.split([',', ')']).next().unwrap_or_default().trim();
+            macros.push(format!("derive({name})"));
+            pending = None;
+        } else if line.starts_with("#[proc_macro_attribute]") {
+            pending = Some("attribute");
+        } else if line.starts_with("#[proc_macro]") {
+            pending = Some("function");
+        } else if let Some(kind) = pending
+            && let Some(name) = fn_name(line)
+        {
+            macros.push(match kind {
+                "attribute" => format!("#[{name}]"),
+                _ => format!("{name}!"),
+            });
+            pending = None;
+        }
+    }
+
+    macros
+}
+
+/// Name of the function declared on `line`, if any
+fn fn_name(line: &str) -> Option<&str> {
+    let (_, rest) = line.split_once("fn ")?;
+    let end = rest
+        .find(|c: char| !c.is_alphanumeric() && c != '_')
+        .unwrap_or(rest.len());
+    (end > 0).then(|| &rest[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_proc_macro_crate() -> anyhow::Result<()> {
+        let temp_dir = TempDir::new()?;
+        fs::create_dir_all(temp_dir.path().join("src"))?;
+        fs::write(
+            temp_dir.path().join(CARGO_TOML),
+            "[package]\nname = \"demo-derive\"\nversion = \"0.1.0\"\n\n[lib]\nproc-macro = true\n",
+        )?;
+        fs::write(
+            temp_dir.path().join("src/lib.rs"),
+            r#"
+use proc_macro::TokenStream;
+
+#[proc_macro_derive(Hello, attributes(hello))]
+pub fn derive_hello(input: TokenStream) -> TokenStream { input }
+
+#[proc_macro_attribute]
+pub fn traced(_attr: TokenStream, item: TokenStream) -> TokenStream { item }
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn make_answer(_input: TokenStream) -> TokenStream { TokenStream::new() }
+
+fn helper() {}
+"#,
+        )?;
+
+        assert!(is_proc_macro(temp_dir.path()));
+        assert_eq!(
+            exported_macros(temp_dir.path()),
+            vec!["derive(Hello)", "#[traced]", "make_answer!"]
+        );
+
+        let error = ProcMacroError::new(temp_dir.path(), "demo-derive", "rustdoc failed");
+        assert!(error.to_string().contains("Exported macros: derive(Hello)"));
+
+        fs::write(
+            temp_dir.path().join(CARGO_TOML),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+        )?;
+        assert!(!is_proc_macro(temp_dir.path()));
+
+        Ok(())
+    }
+}
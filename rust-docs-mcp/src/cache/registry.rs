@@ -0,0 +1,246 @@
+//! Registry endpoints used to download crates and link their docs
+//!
+//! Crates are fetched from crates.io and linked to docs.rs by default. A
+//! [`Registry`] can point the downloads, the sparse index and the docs.rs
+//! fallback at a mirror instead, such as an internal crates.io proxy or a mock
+//! server in tests.
+
+use crate::cache::docsrs::DOCSRS_URL;
+use anyhow::{Result, bail};
+use reqwest::Url;
+
+/// Download URL template of crates.io
+pub const CRATES_IO_DOWNLOAD_URL: &str =
+    "https://crates.io/api/v1/crates/{name}/{version}/download";
+
+/// Base URL of the crates.io sparse registry index
+pub const CRATES_IO_INDEX_URL: &str = "https://index.crates.io";
+
+/// Placeholders substituted in a download URL template
+const PLACEHOLDERS: [&str; 2] = ["{name}", "{version}"];
+
+/// Download, index and docs.rs endpoints of the registry crates are fetched from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Registry {
+    download_template: String,
+    index_url: String,
+    docsrs_url: String,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self {
+            download_template: CRATES_IO_DOWNLOAD_URL.to_string(),
+            index_url: CRATES_IO_INDEX_URL.to_string(),
+            docsrs_url: DOCSRS_URL.to_string(),
+        }
+    }
+}
+
+impl Registry {
+    /// Download crates from `url` instead of crates.io
+    ///
+    /// `url` is a template containing `{name}` and `{version}`, e.g.
+    /// `https://mirror.example.com/crates/{name}/{name}-{version}.crate`. A URL
+    /// without placeholders is treated as a base URL, and
+    /// `/{name}/{version}/download` is appended to it as Cargo does.
+    pub fn with_download_url(mut self, url: &str) -> Result<Self> {
+        let url = url.trim();
+        let template = if url.contains('{') {
+            url.to_string()
+        } else {
+            format!(
+                "{}/{{name}}/{{version}}/download",
+                url.trim_end_matches('/')
+            )
+        };
+
+        validate_template(&template)?;
+        self.download_template = template;
+        Ok(self)
+    }
+
+    /// Read the sparse index from `url` instead of index.crates.io
+    pub fn with_index_url(mut self, url: &str) -> Result<Self> {
+        let url = url.trim().trim_end_matches('/');
+        validate_url(url)?;
+        self.index_url = url.to_string();
+        Ok(self)
+    }
+
+    /// Fetch prebuilt docs and link items to `url` instead of docs.rs
+    pub fn with_docsrs_url(mut self, url: &str) -> Result<Self> {
+        let url = url.trim().trim_end_matches('/');
+        validate_url(url)?;
+        self.docsrs_url = url.to_string();
+        Ok(self)
+    }
+
+    /// Name of the registry in messages: `crates.io`, or the host of a mirror
+    pub fn name(&self) -> String {
+        if self.download_template == CRATES_IO_DOWNLOAD_URL {
+            return "crates.io".to_string();
+        }
+        Url::parse(&self.download_url("name", "0.0.0"))
+            .ok()
+            .and_then(|url| {
+                let host = url.host_str()?.to_string();
+                Some(match url.port() {
+                    Some(port) => format!("{host}:{port}"),
+                    None => host,
+                })
+            })
+            .unwrap_or_else(|| self.download_template.clone())
+    }
+
+    /// Download URL template with `{name}` and `{version}` placeholders
+    pub fn download_template(&self) -> &str {
+        &self.download_template
+    }
+
+    /// Base URL of the sparse index
+    pub fn index_url(&self) -> &str {
+        &self.index_url
+    }
+
+    /// Base URL of docs.rs
+    pub fn docsrs_url(&self) -> &str {
+        &self.docsrs_url
+    }
+
+    /// URL of the `.crate` archive of a crate version
+    pub fn download_url(&self, name: &str, version: &str) -> String {
+        self.download_template
+            .replace("{name}", name)
+            .replace("{version}", version)
+    }
+
+    /// URL of a crate's entry in the sparse index
+    pub fn index_entry_url(&self, name: &str) -> String {
+        format!(
+            "{}/{}",
+            self.index_url,
+            crate::cache::downloader::sparse_index_path(name)
+        )
+    }
+}
+
+/// Check that a download template only uses known placeholders and expands to a valid URL
+fn validate_template(template: &str) -> Result<()> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            bail!("Unclosed placeholder in registry URL '{template}'");
+        };
+        let placeholder = &rest[start..start + len + 1];
+        if !PLACEHOLDERS.contains(&placeholder) {
+            bail!(
+                "Unknown placeholder {placeholder} in registry URL '{template}'. Supported placeholders: {}",
+                PLACEHOLDERS.join(", ")
+            );
+        }
+        rest = &rest[start + len + 1..];
+    }
+
+    for placeholder in PLACEHOLDERS {
+        if !template.contains(placeholder) {
+            bail!("Registry URL '{template}' must contain {placeholder}");
+        }
+    }
+
+    validate_url(
+        &template
+            .replace("{name}", "name")
+            .replace("{version}", "0.0.0"),
+    )
+}
+
+/// Check that `url` is an absolute http(s) URL
+fn validate_url(url: &str) -> Result<()> {
+    let parsed = match Url::parse(url) {
+        Ok(parsed) => parsed,
+        Err(e) => bail!("Invalid registry URL '{url}': {e}"),
+    };
+    if !matches!(parsed.scheme(), "http" | "https") {
+        bail!("Registry URL '{url}' must use http or https");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_registry_is_crates_io() {
+        let registry = Registry::default();
+        assert_eq!(
+            registry.download_url("serde", "1.0.0"),
+            "https://crates.io/api/v1/crates/serde/1.0.0/download"
+        );
+        assert_eq!(
+            registry.index_entry_url("serde"),
+            "https://index.crates.io/se/rd/serde"
+        );
+        assert_eq!(registry.docsrs_url(), "https://docs.rs");
+        assert_eq!(registry.name(), "crates.io");
+    }
+
+    #[test]
+    fn test_download_url_template() -> Result<()> {
+        let registry = Registry::default()
+            .with_download_url("https://mirror.example.com/crates/{name}/{name}-{version}.crate")?
+            .with_index_url("https://mirror.example.com/index/")?
+            .with_docsrs_url("https://docs.example.com/")?;
+        assert_eq!(
+            registry.download_url("serde", "1.0.0"),
+            "https://mirror.example.com/crates/serde/serde-1.0.0.crate"
+        );
+        assert_eq!(
+            registry.index_entry_url("syn"),
+            "https://mirror.example.com/index/3/s/syn"
+        );
+        assert_eq!(registry.docsrs_url(), "https://docs.example.com");
+        assert_eq!(registry.name(), "mirror.example.com");
+
+        // A plain base URL gets Cargo's default download path
+        let registry = Registry::default().with_download_url("http://localhost:8080/api/")?;
+        assert_eq!(
+            registry.download_url("serde", "1.0.0"),
+            "http://localhost:8080/api/serde/1.0.0/download"
+        );
+        assert_eq!(registry.name(), "localhost:8080");
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_registry_urls_are_rejected() {
+        let registry = Registry::default();
+        assert!(
+            registry
+                .clone()
+                .with_download_url("https://mirror.example.com/{crate}/{version}")
+                .is_err()
+        );
+        assert!(
+            registry
+                .clone()
+                .with_download_url("https://mirror.example.com/{name}/latest")
+                .is_err()
+        );
+        assert!(
+            registry
+                .clone()
+                .with_download_url("https://mirror.example.com/{name/{version}")
+                .is_err()
+        );
+        assert!(registry.clone().with_download_url("not a url").is_err());
+        assert!(
+            registry
+                .clone()
+                .with_index_url("ftp://mirror.example.com")
+                .is_err()
+        );
+        assert!(registry.with_docsrs_url("docs.example.com").is_err());
+    }
+}
@@ -1,11 +1,23 @@
+use crate::cache::archive::resolve_archive_version;
 use crate::cache::constants::*;
-use crate::cache::docgen::DocGenerator;
-use crate::cache::downloader::{CrateDownloader, CrateSource};
+use crate::cache::diagnosis::DocgenFailureCounter;
+use crate::cache::docgen::{BinaryOnlyError, DocGenerator};
+use crate::cache::docsrs::DOC_SOURCE_LOCAL;
+use crate::cache::downloader::{
+    CrateDownloader, CrateSource, IndexVersion, resolve_default_branch,
+};
+use crate::cache::eviction::{EvictionCandidate, EvictionPolicy};
 use crate::cache::member_utils::normalize_member_path;
+use crate::cache::outputs::{PrunedIndex, ReindexFailure};
+use crate::cache::pins::{CratePin, CratePins};
+use crate::cache::policy::SourcePolicy;
+use crate::cache::registry::Registry;
 use crate::cache::storage::{CacheStorage, MemberInfo};
 use crate::cache::transaction::CacheTransaction;
+use crate::cache::types::DocsVariant;
 use crate::cache::utils::CacheResponse;
 use crate::cache::workspace::WorkspaceHandler;
+use crate::rustdoc;
 use anyhow::{Context, Result, bail};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -16,22 +28,156 @@ pub struct CrateCache {
     pub(crate) storage: CacheStorage,
     downloader: CrateDownloader,
     doc_generator: DocGenerator,
+    auto_regenerate_incompatible: bool,
+    pins: CratePins,
+    eviction_policy: EvictionPolicy,
+    evict_local: bool,
 }
 
 impl CrateCache {
     /// Create a new crate cache instance
     pub fn new(cache_dir: Option<PathBuf>) -> Result<Self> {
-        let storage = CacheStorage::new(cache_dir)?;
+        Self::with_toolchain(cache_dir, rustdoc::REQUIRED_TOOLCHAIN)
+    }
+
+    /// Create a new crate cache instance that generates docs with `toolchain`
+    pub fn with_toolchain(cache_dir: Option<PathBuf>, toolchain: &str) -> Result<Self> {
+        Self::open(cache_dir, toolchain, false)
+    }
+
+    /// Open a crate cache that generates docs with `toolchain`
+    ///
+    /// A `read_only` cache serves only what is already cached, refusing every
+    /// download, docgen run and removal, and never writes to the cache directory.
+    pub fn open(cache_dir: Option<PathBuf>, toolchain: &str, read_only: bool) -> Result<Self> {
+        let storage = CacheStorage::open(cache_dir, read_only)?;
         let downloader = CrateDownloader::new(storage.clone());
-        let doc_generator = DocGenerator::new(storage.clone());
+        let doc_generator = DocGenerator::with_toolchain(storage.clone(), toolchain);
 
         Ok(Self {
             storage,
             downloader,
             doc_generator,
+            auto_regenerate_incompatible: false,
+            pins: CratePins::default(),
+            eviction_policy: EvictionPolicy::default(),
+            evict_local: false,
         })
     }
 
+    /// Whether a cached crate version was found to be yanked when it was downloaded
+    fn is_yanked(&self, name: &str, version: &str) -> bool {
+        self.storage
+            .load_metadata(name, version, None)
+            .map(|metadata| metadata.yanked)
+            .unwrap_or(false)
+    }
+
+    /// Where the documentation of a cached crate version came from: "local" or "docsrs"
+    fn doc_source(&self, name: &str, version: &str) -> String {
+        self.storage
+            .load_metadata(name, version, None)
+            .ok()
+            .and_then(|metadata| metadata.doc_source)
+            .unwrap_or_else(|| DOC_SOURCE_LOCAL.to_string())
+    }
+
+    /// Regenerate cached docs whose rustdoc JSON format version is incompatible
+    /// instead of failing to load them
+    pub fn with_auto_regenerate_incompatible(mut self, enabled: bool) -> Self {
+        self.auto_regenerate_incompatible = enabled;
+        self
+    }
+
+    /// Pass `args` to every rustdoc invocation, e.g. `--cfg docsrs`
+    pub fn with_rustdoc_args(mut self, args: Vec<String>) -> Self {
+        self.doc_generator = self.doc_generator.with_rustdoc_args(args);
+        self
+    }
+
+    /// Download crates.io crates and fetch docs.rs docs through `registry`, e.g. a mirror
+    pub fn with_registry(mut self, registry: Registry) -> Self {
+        self.doc_generator = self.doc_generator.with_docsrs_url(registry.docsrs_url());
+        self.downloader = self.downloader.with_registry(registry);
+        self
+    }
+
+    /// Base URL of docs.rs, used for the fallback and for item links
+    pub fn docsrs_url(&self) -> &str {
+        self.doc_generator.docsrs_url()
+    }
+
+    /// Clone git repositories `depth` commits deep, or with their full history if zero
+    pub fn with_git_depth(mut self, depth: u32) -> Self {
+        self.downloader = self.downloader.with_git_depth(depth);
+        self
+    }
+
+    /// Keep the git history of cloned repositories next to their sources
+    pub fn with_git_history(mut self, enabled: bool) -> Self {
+        self.downloader = self.downloader.with_git_history(enabled);
+        self
+    }
+
+    /// Only cache crates from the sources `policy` allows
+    pub fn with_source_policy(mut self, policy: SourcePolicy) -> Self {
+        self.downloader = self.downloader.with_source_policy(policy);
+        self
+    }
+
+    /// Sources crates may be cached from
+    pub fn source_policy(&self) -> &SourcePolicy {
+        self.downloader.source_policy()
+    }
+
+    /// Choose the crate versions to evict with `policy`
+    pub fn with_eviction_policy(mut self, policy: EvictionPolicy) -> Self {
+        self.eviction_policy = policy;
+        self
+    }
+
+    /// Also evict crates cached from local paths, which are exempt by default
+    pub fn with_evict_local(mut self, enabled: bool) -> Self {
+        self.evict_local = enabled;
+        self
+    }
+
+    /// Policy choosing the crate versions to evict
+    pub fn eviction_policy(&self) -> EvictionPolicy {
+        self.eviction_policy
+    }
+
+    /// Create cache directories with the Unix mode `mode`, e.g. `0o700`
+    pub fn with_cache_permissions(mut self, mode: Option<u32>) -> Result<Self> {
+        self.storage = self.storage.with_permissions(mode)?;
+        self.downloader = self.downloader.with_storage(self.storage.clone());
+        self.doc_generator = self.doc_generator.with_storage(self.storage.clone());
+        Ok(self)
+    }
+
+    /// Counter of failed documentation builds, by category
+    pub fn docgen_failures(&self) -> DocgenFailureCounter {
+        self.doc_generator.failures()
+    }
+
+    /// Whether the cache was opened in read-only mode
+    pub fn is_read_only(&self) -> bool {
+        self.storage.is_read_only()
+    }
+
+    /// Fail if `name`-`version` would have to be cached while the cache is read-only
+    fn ensure_can_cache(&self, name: &str, version: &str) -> Result<()> {
+        if self.is_read_only() {
+            bail!("{name}-{version} is not cached and the server is read-only");
+        }
+        Ok(())
+    }
+
+    /// Get the root directory of the cache
+    pub fn cache_dir(&self) -> &Path {
+        self.storage.cache_dir()
+    }
+
     /// Ensure a crate's documentation is available, downloading and generating if necessary
     pub async fn ensure_crate_docs(
         &self,
@@ -118,13 +264,13 @@ impl CrateCache {
                 // Load and return the generated docs
                 self.load_docs(name, version, None).await
             }
+            // Binary-only packages are normally caught before the build; this
+            // handles the ones only cargo could tell apart
             Err(e) if e.to_string().contains("This is a binary-only package") => {
-                // This is a binary-only package, return appropriate error
-                bail!(
-                    "Cannot generate documentation for binary-only package '{name}'. \
-                    This package contains only binary targets and no library to document. \
-                    rustdoc can only generate documentation for library targets."
-                )
+                Err(BinaryOnlyError {
+                    package: name.to_string(),
+                }
+                .into())
             }
             Err(e) => Err(e),
         }
@@ -190,6 +336,7 @@ impl CrateCache {
     ) -> Result<rustdoc_types::Crate> {
         // If member is specified, use workspace member logic
         if let Some(member_path) = member {
+            self.check_member_failure(name, version, member_path)?;
             return self
                 .ensure_workspace_member_docs(name, version, None, member_path)
                 .await;
@@ -218,6 +365,151 @@ impl CrateCache {
         self.ensure_crate_docs(name, version, None).await
     }
 
+    /// Fail fast with the recorded error if a workspace member's docs failed to build
+    fn check_member_failure(&self, name: &str, version: &str, member_path: &str) -> Result<()> {
+        if let Some(error) = self.storage.member_failure(name, version, member_path) {
+            bail!(
+                "Member {member_path} of {name}-{version} failed to build: {error}\n\
+                Re-cache it with cache_crate and update: true to try again."
+            );
+        }
+        Ok(())
+    }
+
+    /// Ensure documentation including private or hidden items is available for a crate or workspace member
+    ///
+    /// Variant docs are generated on first use with `--document-private-items` or
+    /// `--document-hidden-items` and cached separately from the regular docs.
+    pub async fn ensure_crate_or_member_variant_docs(
+        &self,
+        name: &str,
+        version: &str,
+        member: Option<&str>,
+        variant: DocsVariant,
+    ) -> Result<rustdoc_types::Crate> {
+        if let Some(member_path) = member {
+            self.check_member_failure(name, version, member_path)?;
+        }
+
+        if !self
+            .storage
+            .has_variant_docs(name, version, member, variant)
+        {
+            self.ensure_can_cache(name, version)?;
+            // Downloads the source if needed and rejects workspaces without a member
+            self.ensure_crate_or_member_source(name, version, member, None)
+                .await?;
+            self.doc_generator
+                .generate_variant_docs(name, version, member, variant)
+                .await?;
+        }
+
+        let mut json_value = self
+            .doc_generator
+            .load_variant_docs(name, version, member, variant)
+            .await?;
+
+        if let Err(mismatch) = rustdoc::check_format_version(&json_value) {
+            if !self.auto_regenerate_incompatible {
+                bail!(
+                    "Cached documentation with {} for {} is incompatible: {mismatch}. \
+                    Re-cache it with cache_crate and update: true, or start the server with \
+                    --auto-regenerate-incompatible.",
+                    variant.items(),
+                    Self::display_name(name, version, member)
+                );
+            }
+
+            tracing::warn!(
+                "Regenerating documentation with {} for {}: {}",
+                variant.items(),
+                Self::display_name(name, version, member),
+                mismatch
+            );
+            self.storage.ensure_writable()?;
+            self.doc_generator
+                .generate_variant_docs(name, version, member, variant)
+                .await?;
+            json_value = self
+                .doc_generator
+                .load_variant_docs(name, version, member, variant)
+                .await?;
+            rustdoc::check_format_version(&json_value)?;
+        }
+
+        serde_json::from_value(json_value).with_context(|| {
+            format!(
+                "Failed to parse documentation JSON with {}",
+                variant.items()
+            )
+        })
+    }
+
+    /// Ensure documentation for a specific target platform is available
+    ///
+    /// Target docs are generated on first use with `cargo rustdoc --target` and
+    /// cached per target triple, separately from the host docs.
+    pub async fn ensure_crate_or_member_target_docs(
+        &self,
+        name: &str,
+        version: &str,
+        member: Option<&str>,
+        target: &str,
+    ) -> Result<rustdoc_types::Crate> {
+        if let Some(member_path) = member {
+            self.check_member_failure(name, version, member_path)?;
+        }
+
+        if !self.storage.has_target_docs(name, version, member, target) {
+            self.ensure_can_cache(name, version)?;
+            // Downloads the source if needed and rejects workspaces without a member
+            self.ensure_crate_or_member_source(name, version, member, None)
+                .await?;
+            self.doc_generator
+                .generate_target_docs(name, version, member, target)
+                .await?;
+        }
+
+        let mut json_value = self
+            .doc_generator
+            .load_target_docs(name, version, member, target)
+            .await?;
+
+        if let Err(mismatch) = rustdoc::check_format_version(&json_value) {
+            if !self.auto_regenerate_incompatible {
+                bail!(
+                    "Cached {target} documentation for {} is incompatible: {mismatch}. \
+                    Re-cache it with cache_crate and update: true, or start the server with \
+                    --auto-regenerate-incompatible.",
+                    Self::display_name(name, version, member)
+                );
+            }
+
+            tracing::warn!(
+                "Regenerating {} documentation for {}: {}",
+                target,
+                Self::display_name(name, version, member),
+                mismatch
+            );
+            self.storage.ensure_writable()?;
+            self.doc_generator
+                .generate_target_docs(name, version, member, target)
+                .await?;
+            json_value = self
+                .doc_generator
+                .load_target_docs(name, version, member, target)
+                .await?;
+            rustdoc::check_format_version(&json_value)?;
+        }
+
+        serde_json::from_value(json_value).context("Failed to parse target documentation JSON")
+    }
+
+    /// List the versions of a crate published on crates.io
+    pub async fn get_available_versions(&self, name: &str) -> Result<Vec<IndexVersion>> {
+        self.downloader.get_available_versions(name).await
+    }
+
     /// Download or copy a crate based on source type
     pub async fn download_or_copy_crate(
         &self,
@@ -226,6 +518,7 @@ impl CrateCache {
         source: Option<&str>,
         progress_callback: Option<crate::cache::downloader::ProgressCallback>,
     ) -> Result<PathBuf> {
+        self.ensure_can_cache(name, version)?;
         self.downloader
             .download_or_copy_crate(name, version, source, progress_callback)
             .await
@@ -238,6 +531,7 @@ impl CrateCache {
         version: &str,
         progress_callback: Option<crate::cache::downloader::ProgressCallback>,
     ) -> Result<PathBuf> {
+        self.ensure_can_cache(name, version)?;
         self.doc_generator
             .generate_docs(name, version, progress_callback)
             .await
@@ -251,6 +545,7 @@ impl CrateCache {
         member_path: &str,
         progress_callback: Option<crate::cache::downloader::ProgressCallback>,
     ) -> Result<PathBuf> {
+        self.ensure_can_cache(name, version)?;
         self.doc_generator
             .generate_workspace_member_docs(name, version, member_path, progress_callback)
             .await
@@ -263,10 +558,38 @@ impl CrateCache {
         version: &str,
         member_name: Option<&str>,
     ) -> Result<rustdoc_types::Crate> {
-        let json_value = self
+        let mut json_value = self
             .doc_generator
             .load_docs(name, version, member_name)
             .await?;
+
+        if let Err(mismatch) = rustdoc::check_format_version(&json_value) {
+            if !self.auto_regenerate_incompatible {
+                bail!(
+                    "Cached documentation for {} is incompatible: {mismatch}. \
+                    It was likely generated by a different nightly toolchain. \
+                    Re-cache it with cache_crate and update: true, or start the server with \
+                    --auto-regenerate-incompatible.",
+                    Self::display_name(name, version, member_name)
+                );
+            }
+
+            tracing::warn!(
+                "Regenerating documentation for {}: {}",
+                Self::display_name(name, version, member_name),
+                mismatch
+            );
+            json_value = self.regenerate_docs(name, version, member_name).await?;
+
+            if let Err(mismatch) = rustdoc::check_format_version(&json_value) {
+                bail!(
+                    "Regenerated documentation for {} is still incompatible: {mismatch}. \
+                    Check the toolchain configured with --docgen-toolchain.",
+                    Self::display_name(name, version, member_name)
+                );
+            }
+        }
+
         let context_msg = if member_name.is_some() {
             "Failed to parse member documentation JSON"
         } else {
@@ -277,6 +600,43 @@ impl CrateCache {
         Ok(crate_docs)
     }
 
+    /// Delete the cached docs for a crate or member and generate them again
+    async fn regenerate_docs(
+        &self,
+        name: &str,
+        version: &str,
+        member_name: Option<&str>,
+    ) -> Result<serde_json::Value> {
+        self.storage.ensure_writable()?;
+        let docs_path = self.storage.docs_path(name, version, member_name)?;
+        std::fs::remove_file(&docs_path).with_context(|| {
+            format!(
+                "Failed to remove incompatible documentation: {}",
+                docs_path.display()
+            )
+        })?;
+
+        match member_name {
+            Some(member) => {
+                self.generate_workspace_member_docs(name, version, member, None)
+                    .await?
+            }
+            None => self.generate_docs(name, version, None).await?,
+        };
+
+        self.doc_generator
+            .load_docs(name, version, member_name)
+            .await
+    }
+
+    /// Human-readable name of a crate or workspace member for messages
+    fn display_name(name: &str, version: &str, member_name: Option<&str>) -> String {
+        match member_name {
+            Some(member) => format!("workspace member {member} in {name}-{version}"),
+            None => format!("{name}-{version}"),
+        }
+    }
+
     /// Get cached versions of a crate
     pub async fn get_cached_versions(&self, name: &str) -> Result<Vec<String>> {
         let cached = self.storage.list_cached_crates()?;
@@ -296,11 +656,241 @@ impl CrateCache {
         self.storage.list_cached_crates()
     }
 
+    /// Mark a crate version as in use by a query until the returned pin is dropped
+    ///
+    /// Pinned versions cannot be removed. Queries that release the cache lock
+    /// before they are done reading a crate should hold a pin instead.
+    pub fn pin(&self, name: &str, version: &str) -> CratePin {
+        self.pins.pin(name, version)
+    }
+
     /// Remove a cached crate version
+    ///
+    /// Fails without removing anything while a running query has the version pinned.
     pub async fn remove_crate(&self, name: &str, version: &str) -> Result<()> {
+        if self.pins.is_pinned(name, version) {
+            bail!("{name}-{version} is in use by a running query, try again later");
+        }
         self.storage.remove_crate(name, version)
     }
 
+    /// Remove all cached crates, returning the number of bytes reclaimed
+    ///
+    /// Fails without removing anything while a running query has a crate pinned.
+    pub async fn clear_all_caches(&self) -> Result<u64> {
+        if self.pins.any_pinned() {
+            bail!("Cached crates are in use by a running query, try again later");
+        }
+        self.storage.clear_all()
+    }
+
+    /// Delete and recreate the search index of a crate or workspace member from its cached docs
+    pub async fn rebuild_search_index(
+        &self,
+        name: &str,
+        version: &str,
+        member_name: Option<&str>,
+    ) -> Result<()> {
+        // The indexer appends to an existing index, so start from an empty one
+        self.storage
+            .remove_search_index(name, version, member_name)?;
+        self.create_search_index(name, version, member_name).await
+    }
+
+    /// Restrict the cached docs of a crate or workspace member to the items under `modules`
+    ///
+    /// See [`DocQuery::scoped_to_modules`](crate::docs::query::DocQuery::scoped_to_modules)
+    /// for what is kept. With `keep_full`, the unfiltered docs are kept in
+    /// `docs.full.json`. The search index is rebuilt from the scoped docs.
+    /// Returns the number of items kept.
+    pub async fn scope_docs(
+        &self,
+        name: &str,
+        version: &str,
+        member_name: Option<&str>,
+        modules: &[String],
+        keep_full: bool,
+    ) -> Result<usize> {
+        self.storage.ensure_writable()?;
+
+        let crate_data = self.load_docs(name, version, member_name).await?;
+        let scoped = crate::docs::query::DocQuery::new(crate_data).scoped_to_modules(modules)?;
+
+        let docs_path = self.storage.docs_path(name, version, member_name)?;
+        // Scoping again keeps the docs set aside the first time
+        if keep_full {
+            let full_docs_path = self.storage.full_docs_path(name, version, member_name)?;
+            if !full_docs_path.exists() {
+                std::fs::copy(&docs_path, &full_docs_path)
+                    .context("Failed to keep the unfiltered documentation")?;
+            }
+        }
+        let json =
+            serde_json::to_vec(&scoped).context("Failed to serialize scoped documentation")?;
+        std::fs::write(&docs_path, json).context("Failed to write scoped documentation")?;
+
+        self.rebuild_search_index(name, version, member_name)
+            .await?;
+
+        tracing::info!(
+            "Scoped docs of {} to {} items",
+            Self::display_name(name, version, member_name),
+            scoped.index.len()
+        );
+        Ok(scoped.index.len())
+    }
+
+    /// Rebuild the search index of every cached crate and workspace member that has docs
+    ///
+    /// Source and docs are left untouched. At most `max_concurrent` indexes are
+    /// rebuilt at once. Returns the number of rebuilt indexes and the failures.
+    pub async fn reindex_all(&self, max_concurrent: usize) -> Result<(usize, Vec<ReindexFailure>)> {
+        use futures::StreamExt;
+
+        self.storage.ensure_writable()?;
+
+        let mut targets = Vec::new();
+        for metadata in self.storage.list_cached_crates()? {
+            let name = metadata.name;
+            let version = metadata.version;
+            if self.storage.has_docs(&name, &version, None) {
+                targets.push((name.clone(), version.clone(), None));
+            }
+            for member in self
+                .storage
+                .list_workspace_members(&name, &version)
+                .unwrap_or_default()
+            {
+                if self.storage.has_docs(&name, &version, Some(&member)) {
+                    targets.push((name.clone(), version.clone(), Some(member)));
+                }
+            }
+        }
+
+        let results: Vec<_> = futures::stream::iter(targets)
+            .map(|(name, version, member)| async move {
+                let result = self
+                    .rebuild_search_index(&name, &version, member.as_deref())
+                    .await;
+                (name, version, member, result)
+            })
+            .buffer_unordered(max_concurrent.max(1))
+            .collect()
+            .await;
+
+        let mut rebuilt = 0;
+        let mut failed = Vec::new();
+        for (crate_name, version, member, result) in results {
+            match result {
+                Ok(()) => rebuilt += 1,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to rebuild search index for {crate_name}-{version}: {e:#}"
+                    );
+                    failed.push(ReindexFailure {
+                        crate_name,
+                        version,
+                        member,
+                        error: format!("{e:#}"),
+                    });
+                }
+            }
+        }
+        failed.sort_by(|a, b| {
+            (&a.crate_name, &a.version, &a.member).cmp(&(&b.crate_name, &b.version, &b.member))
+        });
+
+        Ok((rebuilt, failed))
+    }
+
+    /// Remove search indexes whose documentation is no longer cached
+    ///
+    /// Indexes of crate versions pinned by a running query are left in place.
+    pub async fn prune_indexes(&self) -> Result<Vec<PrunedIndex>> {
+        self.storage.ensure_writable()?;
+
+        let mut pruned = Vec::new();
+        for orphan in self.storage.find_orphaned_indexes()? {
+            if self.pins.is_pinned(&orphan.name, &orphan.version) {
+                tracing::info!(
+                    "Skipping orphaned search index of {}-{}, in use by a running query",
+                    orphan.name,
+                    orphan.version
+                );
+                continue;
+            }
+
+            let bytes = self.storage.remove_orphaned_index(&orphan)?;
+            tracing::info!(
+                "Removed orphaned search index {} ({} bytes)",
+                orphan.path.display(),
+                bytes
+            );
+            pruned.push(PrunedIndex {
+                crate_name: orphan.name,
+                version: orphan.version,
+                member: orphan.member,
+                bytes,
+            });
+        }
+
+        Ok(pruned)
+    }
+
+    /// Cached crate versions with their size and usage, to choose eviction victims from
+    pub fn eviction_candidates(&self) -> Result<Vec<EvictionCandidate>> {
+        let candidates = self
+            .storage
+            .list_cached_crates()?
+            .into_iter()
+            .map(|metadata| EvictionCandidate {
+                last_accessed: self
+                    .storage
+                    .last_accessed(&metadata.name, &metadata.version, None),
+                access_count: self
+                    .storage
+                    .access_count(&metadata.name, &metadata.version, None),
+                name: metadata.name,
+                version: metadata.version,
+                source: metadata.source,
+                size_bytes: metadata.size_bytes,
+                cached_at: metadata.cached_at,
+            })
+            .collect();
+        Ok(candidates)
+    }
+
+    /// Evict crate versions chosen by the eviction policy until at least
+    /// `bytes_to_free` bytes are freed, returning the evicted versions
+    ///
+    /// Versions pinned by a running query are never evicted, so fewer bytes
+    /// than requested may be freed.
+    pub async fn evict(&self, bytes_to_free: u64) -> Result<Vec<EvictionCandidate>> {
+        self.storage.ensure_writable()?;
+
+        let candidates = self
+            .eviction_candidates()?
+            .into_iter()
+            .filter(|candidate| !self.pins.is_pinned(&candidate.name, &candidate.version))
+            .collect();
+        let victims =
+            self.eviction_policy
+                .select_victims(candidates, bytes_to_free, self.evict_local);
+
+        for victim in &victims {
+            self.storage.remove_crate(&victim.name, &victim.version)?;
+            tracing::info!(
+                "Evicted {}-{} ({} bytes) with the {} policy",
+                victim.name,
+                victim.version,
+                victim.size_bytes,
+                self.eviction_policy
+            );
+        }
+
+        Ok(victims)
+    }
+
     /// Check if docs exist without ensuring they're generated
     pub fn has_docs(&self, crate_name: &str, version: &str, member: Option<&str>) -> bool {
         self.storage.has_docs(crate_name, version, member)
@@ -327,6 +917,42 @@ impl CrateCache {
         }
     }
 
+    /// Look up a single item in already cached docs without parsing the whole file
+    ///
+    /// Only the requested entry is deserialized, which keeps memory flat for very
+    /// large crates. Returns `None` when the docs are not cached, were generated
+    /// with another format version or lack the item, so callers can fall back to
+    /// a full load that generates, regenerates or reports errors as needed.
+    pub async fn find_cached_item(
+        &self,
+        crate_name: &str,
+        version: &str,
+        member: Option<&str>,
+        target: Option<&str>,
+        item_id: u32,
+    ) -> Result<Option<rustdoc_types::Item>> {
+        let docs_path = match target {
+            Some(target) => self
+                .storage
+                .target_docs_path(crate_name, version, member, target)?,
+            None => self.storage.docs_path(crate_name, version, member)?,
+        };
+        if !docs_path.exists() {
+            return Ok(None);
+        }
+
+        let lookup = tokio::task::spawn_blocking(move || {
+            crate::docs::lookup::find_item(&docs_path, rustdoc_types::Id(item_id))
+        })
+        .await??;
+
+        if lookup.format_version != Some(rustdoc_types::FORMAT_VERSION) {
+            return Ok(None);
+        }
+        self.storage.touch(crate_name, version, member);
+        Ok(lookup.item)
+    }
+
     /// Get the source path for a crate
     pub fn get_source_path(&self, name: &str, version: &str) -> Result<PathBuf> {
         self.storage.source_path(name, version)
@@ -391,9 +1017,16 @@ impl CrateCache {
         Ok(source_path)
     }
 
-    /// Load dependency information from cache
-    pub async fn load_dependencies(&self, name: &str, version: &str) -> Result<serde_json::Value> {
-        self.doc_generator.load_dependencies(name, version).await
+    /// Load the `cargo metadata` output of a crate or workspace member from cache
+    pub async fn load_dependencies(
+        &self,
+        name: &str,
+        version: &str,
+        member_name: Option<&str>,
+    ) -> Result<serde_json::Value> {
+        self.doc_generator
+            .load_dependencies(name, version, member_name)
+            .await
     }
 
     /// Internal implementation for caching a crate during update
@@ -439,7 +1072,8 @@ impl CrateCache {
             self.ensure_crate_docs(crate_name, version, source_str)
                 .await?;
 
-            Ok(CacheResponse::success_updated(crate_name, version))
+            Ok(CacheResponse::success_updated(crate_name, version)
+                .with_doc_source(self.doc_source(crate_name, version)))
         }
     }
 
@@ -462,7 +1096,7 @@ impl CrateCache {
                 } else if let Some(tag) = &params.tag {
                     tag.clone()
                 } else {
-                    // This should not happen due to validation in the tool layer
+                    // This should not happen, the default branch is resolved before extraction
                     String::new()
                 };
 
@@ -492,6 +1126,17 @@ impl CrateCache {
                 Some(params.path.clone()),
                 params.update.unwrap_or(false),
             ),
+            CrateSource::Archive(params) => (
+                params.crate_name.clone(),
+                params
+                    .version
+                    .clone()
+                    .expect("Version should be resolved before extraction"),
+                // A crate archive holds a single package
+                None,
+                Some(params.path.clone()),
+                params.update.unwrap_or(false),
+            ),
         }
     }
 
@@ -509,6 +1154,27 @@ impl CrateCache {
     ) -> CacheResponse {
         use futures::future::join_all;
 
+        // Expand the "default" keyword to the workspace's default members
+        let default_members;
+        let members = if members == [DEFAULT_MEMBERS_SENTINEL] {
+            match self
+                .resolve_default_members(crate_name, version, source_str)
+                .await
+            {
+                Ok(resolved) => {
+                    default_members = resolved;
+                    default_members.as_slice()
+                }
+                Err(e) => {
+                    return CacheResponse::error(format!(
+                        "Failed to resolve default workspace members: {e}"
+                    ));
+                }
+            }
+        } else {
+            members
+        };
+
         // Create futures for all member caching operations
         let member_futures: Vec<_> = members
             .iter()
@@ -541,6 +1207,7 @@ impl CrateCache {
                     results.push(format!("Successfully cached member: {member}"));
                 }
                 Err(e) => {
+                    self.record_member_failure(crate_name, version, source_str, &member, &e);
                     errors.push(format!("Failed to cache member {member}: {e}"));
                 }
             }
@@ -560,6 +1227,72 @@ impl CrateCache {
         }
     }
 
+    /// Persist a member's build failure so queries report it instead of rebuilding
+    fn record_member_failure(
+        &self,
+        crate_name: &str,
+        version: &str,
+        source_str: Option<&str>,
+        member_path: &str,
+        error: &anyhow::Error,
+    ) {
+        // The package name is informational; fall back to the member path if unreadable
+        let package_name = self
+            .storage
+            .source_path(crate_name, version)
+            .and_then(|source| {
+                WorkspaceHandler::get_package_name(&source.join(member_path).join(CARGO_TOML))
+            })
+            .unwrap_or_else(|_| member_path.to_string());
+        let member_info = MemberInfo {
+            original_path: member_path.to_string(),
+            normalized_path: normalize_member_path(member_path),
+            package_name,
+        };
+
+        if let Err(e) = self.storage.record_member_failure(
+            crate_name,
+            version,
+            source_str.unwrap_or("unknown"),
+            member_info,
+            &format!("{error:#}"),
+        ) {
+            tracing::warn!("Failed to record build failure of member {member_path}: {e}");
+        }
+    }
+
+    /// Resolve the `default-members` of a workspace, downloading its source if needed
+    async fn resolve_default_members(
+        &self,
+        crate_name: &str,
+        version: &str,
+        source_str: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let source_path = self
+            .ensure_crate_source(crate_name, version, source_str)
+            .await?;
+        let cargo_toml_path = source_path.join(CARGO_TOML);
+
+        if !WorkspaceHandler::is_workspace(&cargo_toml_path)? {
+            bail!(
+                "{crate_name}-{version} is not a workspace; members=\"{DEFAULT_MEMBERS_SENTINEL}\" only applies to workspace crates"
+            );
+        }
+
+        let members = WorkspaceHandler::get_default_members(&cargo_toml_path)?;
+        if members.is_empty() {
+            bail!("No default members found for workspace {crate_name}-{version}");
+        }
+
+        tracing::info!(
+            "Expanded default members for {}-{}: {:?}",
+            crate_name,
+            version,
+            members
+        );
+        Ok(members)
+    }
+
     /// Generate workspace detection response
     fn generate_workspace_response(
         &self,
@@ -573,6 +1306,7 @@ impl CrateCache {
             CrateSource::CratesIO(_) => "cratesio",
             CrateSource::GitHub(_) => "github",
             CrateSource::LocalPath(_) => "local",
+            CrateSource::Archive(_) => "archive",
         };
 
         CacheResponse::workspace_detected(crate_name, version, members, source_type, updated)
@@ -716,6 +1450,48 @@ impl CrateCache {
                         .to_json();
                 }
             }
+        } else if let CrateSource::Archive(mut params) = source {
+            match resolve_archive_version(&params.path, params.version.as_deref()) {
+                Ok((resolved_version, auto_detected)) => {
+                    params.version = Some(resolved_version.clone());
+
+                    if auto_detected {
+                        tracing::info!(
+                            "Auto-detected version '{}' from crate archive for crate '{}'",
+                            resolved_version,
+                            params.crate_name
+                        );
+                    }
+
+                    CrateSource::Archive(params)
+                }
+                Err(e) => {
+                    return CacheResponse::error(format!("Failed to read crate archive: {e}"))
+                        .to_json();
+                }
+            }
+        } else if let CrateSource::GitHub(mut params) = source {
+            // Without a branch or tag, cache the repository's default branch under its name
+            if params.branch.is_none() && params.tag.is_none() {
+                match resolve_default_branch(&params.github_url).await {
+                    Ok(branch) => {
+                        tracing::info!(
+                            "Detected default branch '{}' of {} for crate '{}'",
+                            branch,
+                            params.github_url,
+                            params.crate_name
+                        );
+                        params.branch = Some(branch);
+                    }
+                    Err(e) => {
+                        return CacheResponse::error(format!(
+                            "Failed to detect the default branch: {e:#}"
+                        ))
+                        .to_json();
+                    }
+                }
+            }
+            CrateSource::GitHub(params)
         } else {
             source
         };
@@ -732,7 +1508,7 @@ impl CrateCache {
             members
         );
 
-        // Validate GitHub source
+        // Validate GitHub source, the default branch was resolved above if neither was given
         if matches!(&source, CrateSource::GitHub(_)) && version.is_empty() {
             return CacheResponse::error("Either branch or tag must be specified").to_json();
         }
@@ -785,7 +1561,10 @@ impl CrateCache {
                     "cache_crate_with_source: {} docs exist, returning success",
                     crate_name
                 );
-                return CacheResponse::success(&crate_name, &version).to_json();
+                return CacheResponse::success(&crate_name, &version)
+                    .with_yanked(self.is_yanked(&crate_name, &version))
+                    .with_doc_source(self.doc_source(&crate_name, &version))
+                    .to_json();
             }
             tracing::info!(
                 "cache_crate_with_source: {} is cached but docs not generated, continuing",
@@ -799,9 +1578,22 @@ impl CrateCache {
             tm.update_step(tid, 1, "Downloading crate source").await;
         }
 
+        // Report download/copy progress through the task's step description
+        let progress_callback = match (&task_manager, &task_id) {
+            (Some(tm), Some(tid)) => {
+                Some(tm.step_progress_callback(tid, 1, "Downloading crate source"))
+            }
+            _ => None,
+        };
+
         // First, download the crate if not already cached
         let source_path = match self
-            .download_or_copy_crate(&crate_name, &version, source_str.as_deref(), None)
+            .download_or_copy_crate(
+                &crate_name,
+                &version,
+                source_str.as_deref(),
+                progress_callback,
+            )
             .await
         {
             Ok(path) => {
@@ -869,7 +1661,10 @@ impl CrateCache {
                     "cache_crate_with_source: docs generated successfully for {}",
                     crate_name
                 );
-                CacheResponse::success(&crate_name, &version).to_json()
+                CacheResponse::success(&crate_name, &version)
+                    .with_yanked(self.is_yanked(&crate_name, &version))
+                    .with_doc_source(self.doc_source(&crate_name, &version))
+                    .to_json()
             }
             Err(e) => {
                 tracing::error!(
@@ -904,6 +1699,12 @@ impl CrateCache {
                             crate_name, params.path, e
                         )
                     }
+                    CrateSource::Archive(params) => {
+                        format!(
+                            "Failed to cache crate '{}' from archive '{}': {}",
+                            crate_name, params.path, e
+                        )
+                    }
                 };
                 CacheResponse::error(error_msg).to_json()
             }
@@ -922,3 +1723,137 @@ impl CrateCache {
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_load_docs_rejects_incompatible_format_version() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cache = CrateCache::new(Some(temp_dir.path().to_path_buf()))?;
+
+        let docs_path = cache.storage.docs_path("foo", "1.0.0", None)?;
+        std::fs::create_dir_all(docs_path.parent().unwrap())?;
+        let docs = serde_json::json!({
+            "format_version": rustdoc_types::FORMAT_VERSION + 1,
+            "root": 0,
+            "index": {},
+        });
+        std::fs::write(&docs_path, docs.to_string())?;
+
+        let err = cache.load_docs("foo", "1.0.0", None).await.unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("foo-1.0.0 is incompatible"));
+        assert!(message.contains(&format!(
+            "format version {}",
+            rustdoc_types::FORMAT_VERSION + 1
+        )));
+        assert!(message.contains("update: true"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ensure_crate_docs_bails_early_for_binary_only_package() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cache = CrateCache::new(Some(temp_dir.path().join("cache")))?;
+
+        let crate_dir = temp_dir.path().join("bin-only");
+        std::fs::create_dir_all(crate_dir.join("src"))?;
+        std::fs::write(
+            crate_dir.join(CARGO_TOML),
+            "[package]\nname = \"bin-only\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )?;
+        std::fs::write(crate_dir.join("src/main.rs"), "fn main() {}\n")?;
+
+        let err = cache
+            .ensure_crate_docs("bin-only", "0.1.0", crate_dir.to_str())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<BinaryOnlyError>(),
+            Some(&BinaryOnlyError {
+                package: "bin-only".to_string()
+            })
+        );
+
+        // cargo rustdoc never ran, so nothing was built
+        let source_path = cache.storage.source_path("bin-only", "0.1.0")?;
+        assert!(source_path.join(CARGO_TOML).exists());
+        assert!(!source_path.join(TARGET_DIR).exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_remove_crate_is_rejected_while_pinned() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cache = Arc::new(tokio::sync::RwLock::new(CrateCache::new(Some(
+            temp_dir.path().to_path_buf(),
+        ))?));
+        let source_path = cache.read().await.storage.source_path("foo", "1.0.0")?;
+        std::fs::create_dir_all(&source_path)?;
+
+        // A slow query pins the crate and keeps reading after releasing the lock
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+        let (finish_tx, finish_rx) = tokio::sync::oneshot::channel::<()>();
+        let query = tokio::spawn({
+            let cache = cache.clone();
+            async move {
+                let _pin = cache.read().await.pin("foo", "1.0.0");
+                let _ = started_tx.send(());
+                let _ = finish_rx.await;
+            }
+        });
+        started_rx.await?;
+
+        let err = cache
+            .write()
+            .await
+            .remove_crate("foo", "1.0.0")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("in use by a running query"));
+        assert!(cache.write().await.clear_all_caches().await.is_err());
+        assert!(source_path.exists());
+
+        let _ = finish_tx.send(());
+        query.await?;
+
+        cache.write().await.remove_crate("foo", "1.0.0").await?;
+        assert!(!source_path.exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_evict_follows_policy() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cache = CrateCache::new(Some(temp_dir.path().to_path_buf()))?;
+        for (name, source) in [("a", "crates.io"), ("b", "crates.io"), ("c", "local")] {
+            let source_path = cache.storage.source_path(name, "1.0.0")?;
+            std::fs::create_dir_all(&source_path)?;
+            std::fs::write(source_path.join("lib.rs"), "pub fn f() {}\n")?;
+            cache
+                .storage
+                .save_metadata_with_source(name, "1.0.0", source, None, None)?;
+        }
+        cache.storage.touch("b", "1.0.0", None);
+
+        // "a" was used least recently, and the local "c" is exempt
+        let evicted = cache.evict(1).await?;
+        let names: Vec<_> = evicted.iter().map(|victim| victim.name.as_str()).collect();
+        assert_eq!(names, ["a"]);
+        assert!(!cache.storage.crate_path("a", "1.0.0")?.exists());
+
+        // Pinned versions are kept however much space is requested
+        let _pin = cache.pin("b", "1.0.0");
+        assert!(cache.evict(u64::MAX).await?.is_empty());
+        assert!(cache.storage.crate_path("b", "1.0.0")?.exists());
+        assert!(cache.storage.crate_path("c", "1.0.0")?.exists());
+
+        Ok(())
+    }
+}
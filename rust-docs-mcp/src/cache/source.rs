@@ -1,8 +1,9 @@
 //! Source type detection and parsing for crates
 //!
 //! This module handles the detection and parsing of different crate sources,
-//! including crates.io, GitHub repositories, and local paths.
+//! including crates.io, GitHub repositories, local paths and crate archives.
 
+use crate::cache::archive::is_crate_archive;
 use serde::{Deserialize, Serialize};
 
 /// Represents the different sources from which a crate can be obtained
@@ -25,6 +26,11 @@ pub enum SourceType {
         /// The local path to the crate
         path: String,
     },
+    /// Crate from a local `.crate` archive
+    Archive {
+        /// The local path to the archive
+        path: String,
+    },
 }
 
 /// Git reference type (branch or tag)
@@ -47,6 +53,10 @@ impl SourceDetector {
             Some(s) => {
                 if s.starts_with("http://") || s.starts_with("https://") {
                     Self::parse_url(s)
+                } else if is_crate_archive(s) {
+                    SourceType::Archive {
+                        path: s.to_string(),
+                    }
                 } else if Self::is_local_path(s) {
                     SourceType::Local {
                         path: s.to_string(),
@@ -169,6 +179,18 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_detect_crate_archives() {
+        assert!(matches!(
+            SourceDetector::detect(Some("./vendor/serde-1.0.215.crate")),
+            SourceType::Archive { .. }
+        ));
+        assert!(matches!(
+            SourceDetector::detect(Some("~/Downloads/serde-1.0.215.tar.gz")),
+            SourceType::Archive { .. }
+        ));
+    }
+
     #[test]
     fn test_detect_github_urls() {
         match SourceDetector::detect(Some("https://github.com/rust-lang/rust")) {
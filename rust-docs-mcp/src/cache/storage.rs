@@ -2,10 +2,21 @@ use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use crate::cache::constants::*;
-use crate::cache::types::CrateIdentifier;
+use crate::cache::layout::{
+    CACHE_LAYOUT_VERSION, LayoutStatus, layout_status, read_layout_version, write_layout_version,
+};
+use crate::cache::types::{CrateIdentifier, DocsVariant};
 use crate::cache::utils::copy_directory_contents;
+use crate::rustdoc::validate_target_triple;
+
+/// How old an access timestamp may get before [`CacheStorage::touch`] rewrites it
+const ACCESS_RESOLUTION: Duration = Duration::from_secs(60);
+
+/// Error returned for modifications of the cache while the server is read-only
+pub const READ_ONLY_ERROR: &str = "Server is read-only: the cache cannot be modified";
 
 /// Unified metadata for both crates and workspace members
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -19,6 +30,15 @@ pub struct CacheMetadata {
     pub source: String,
     #[serde(default)]
     pub source_path: Option<String>,
+    /// Whether the version has been yanked from crates.io
+    #[serde(default)]
+    pub yanked: bool,
+    /// Error from the last failed documentation build, if it failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Where the documentation came from, `None` if it was generated locally
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc_source: Option<String>,
 
     // Member-specific fields (None for main crates)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -35,7 +55,36 @@ pub struct MemberInfo {
     pub package_name: String,
 }
 
+/// A search index whose documentation is no longer cached
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrphanedIndex {
+    pub name: String,
+    pub version: String,
+    /// Normalized member path (e.g., "crates-rmcp") for workspace member indexes
+    pub member: Option<String>,
+    /// Path of the search index directory
+    pub path: PathBuf,
+}
+
 /// Default source for backward compatibility
+/// Read the access count from a last-accessed sidecar file
+///
+/// Files written before accesses were counted hold only a timestamp and
+/// count as a single access.
+fn read_access_count(path: &Path) -> u64 {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return 0;
+    };
+    let mut lines = contents.lines();
+    if lines.next().is_none() {
+        return 0;
+    }
+    lines
+        .next()
+        .and_then(|count| count.trim().parse().ok())
+        .unwrap_or(1)
+}
+
 fn default_source() -> String {
     "crates.io".to_string()
 }
@@ -44,11 +93,103 @@ fn default_source() -> String {
 #[derive(Debug, Clone)]
 pub struct CacheStorage {
     cache_dir: PathBuf,
+    /// False if the cache was written with a layout this build cannot migrate
+    writable: bool,
+    /// Set when the server must not modify the cache at all
+    read_only: bool,
+    /// Unix mode given to cache directories, e.g. `0o700`
+    permissions: Option<u32>,
+}
+
+/// Parse a `--cache-perms` mode such as `0700`, `700` or `0o700`
+pub fn parse_cache_permissions(value: &str) -> Result<u32> {
+    let digits = value.trim();
+    let digits = digits.strip_prefix("0o").unwrap_or(digits);
+    if digits.is_empty() || !digits.chars().all(|c| ('0'..='7').contains(&c)) {
+        bail!("Invalid cache permissions '{value}': expected an octal mode such as 0700");
+    }
+    let mode = u32::from_str_radix(digits, 8)
+        .with_context(|| format!("Invalid cache permissions '{value}'"))?;
+    if mode > 0o777 {
+        bail!("Invalid cache permissions '{value}': only the 0777 permission bits may be set");
+    }
+    if mode & 0o700 != 0o700 {
+        bail!(
+            "Invalid cache permissions '{value}': the owner needs read, write and execute access"
+        );
+    }
+    Ok(mode)
+}
+
+/// Set the Unix mode of `path`
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+        .with_context(|| format!("Failed to set permissions of {}", path.display()))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) -> Result<()> {
+    Ok(())
+}
+
+/// Withhold the permissions `mode` withholds from every file and directory the
+/// process creates from now on, on top of the current umask
+///
+/// The umask is inherited by git and rustdoc, so their output is covered too.
+#[cfg(unix)]
+pub fn restrict_umask(mode: u32) {
+    let mask = !mode & 0o777;
+    // SAFETY: umask only swaps the file mode creation mask of the process
+    unsafe {
+        let previous = libc::umask(mask as libc::mode_t);
+        libc::umask(previous | mask as libc::mode_t);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn restrict_umask(_mode: u32) {}
+
+/// Give the directories below `path` the mode `mode` and strip the permissions
+/// it withholds from files, leaving symlinks alone
+#[cfg(unix)]
+fn restrict_tree(path: &Path, mode: u32) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let file_type = fs::symlink_metadata(path)
+        .with_context(|| format!("Failed to read metadata of {}", path.display()))?
+        .file_type();
+    if file_type.is_dir() {
+        set_mode(path, mode)?;
+        for entry in fs::read_dir(path)? {
+            restrict_tree(&entry?.path(), mode)?;
+        }
+    } else if file_type.is_file() {
+        let current = fs::metadata(path)?.permissions().mode() & 0o777;
+        let restricted = current & mode;
+        if restricted != current {
+            set_mode(path, restricted)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn restrict_tree(_path: &Path, _mode: u32) -> Result<()> {
+    Ok(())
 }
 
 impl CacheStorage {
     /// Create a new cache storage instance
     pub fn new(custom_cache_dir: Option<PathBuf>) -> Result<Self> {
+        Self::open(custom_cache_dir, false)
+    }
+
+    /// Open the cache storage, forbidding every modification if `read_only`
+    ///
+    /// A read-only cache is never created or stamped with a layout version, so
+    /// it can be served from a directory the server cannot write to.
+    pub fn open(custom_cache_dir: Option<PathBuf>, read_only: bool) -> Result<Self> {
         let cache_dir = match custom_cache_dir {
             Some(dir) => dir,
             None => dirs::home_dir()
@@ -57,9 +198,102 @@ impl CacheStorage {
                 .join(CACHE_DIR),
         };
 
-        fs::create_dir_all(&cache_dir).context("Failed to create cache directory")?;
+        if !read_only {
+            fs::create_dir_all(&cache_dir).context("Failed to create cache directory")?;
+        }
+
+        let writable = match read_layout_version(&cache_dir)? {
+            None => {
+                if !read_only {
+                    write_layout_version(&cache_dir)?;
+                }
+                true
+            }
+            Some(version) => match layout_status(version) {
+                LayoutStatus::Current => true,
+                LayoutStatus::Outdated(version) => {
+                    tracing::warn!(
+                        "Cache at {} uses layout version {version} (current: {CACHE_LAYOUT_VERSION}). Run `rust-docs-mcp migrate` to upgrade it",
+                        cache_dir.display()
+                    );
+                    true
+                }
+                LayoutStatus::Unsupported(version) => {
+                    tracing::warn!(
+                        "Cache at {} uses layout version {version}, which is newer than this build supports ({CACHE_LAYOUT_VERSION}). The cache is read-only until rust-docs-mcp is updated",
+                        cache_dir.display()
+                    );
+                    false
+                }
+            },
+        };
+
+        Ok(Self {
+            cache_dir,
+            writable,
+            read_only,
+            permissions: None,
+        })
+    }
+
+    /// Create cache directories with the Unix mode `mode`, starting with the cache root.
+    /// Ignored on other platforms
+    ///
+    /// A read-only cache keeps the permissions it has.
+    pub fn with_permissions(mut self, mode: Option<u32>) -> Result<Self> {
+        if let Some(mode) = mode {
+            if self.read_only {
+                tracing::debug!("Cache is read-only, leaving its permissions unchanged");
+            } else if cfg!(unix) {
+                set_mode(&self.cache_dir, mode)?;
+            } else {
+                tracing::warn!("--cache-perms is only supported on Unix and is ignored");
+            }
+        }
+        self.permissions = mode;
+        Ok(self)
+    }
+
+    /// Unix mode given to cache directories, if one was configured
+    pub fn permissions(&self) -> Option<u32> {
+        self.permissions
+    }
+
+    /// Apply the configured permissions to everything cached for `name`-`version`,
+    /// including files downloaders and rustdoc wrote with the process umask
+    pub fn restrict_crate_permissions(&self, name: &str, version: &str) -> Result<()> {
+        let Some(mode) = self.permissions else {
+            return Ok(());
+        };
+        let crate_path = self.crate_path(name, version)?;
+        if crate_path.exists() {
+            restrict_tree(&crate_path, mode)?;
+        }
+        Ok(())
+    }
+
+    /// Whether this build can modify the cache
+    pub fn is_writable(&self) -> bool {
+        self.writable
+    }
+
+    /// Whether the server was started in read-only mode
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
 
-        Ok(Self { cache_dir })
+    /// Fail if the server is read-only or the cache layout is too new to be modified by this build
+    pub fn ensure_writable(&self) -> Result<()> {
+        if self.read_only {
+            bail!("{READ_ONLY_ERROR}");
+        }
+        if !self.writable {
+            bail!(
+                "Cache at {} was written by a newer version of rust-docs-mcp and cannot be modified",
+                self.cache_dir.display()
+            );
+        }
+        Ok(())
     }
 
     /// Get the cache directory path
@@ -102,6 +336,13 @@ impl CacheStorage {
         Ok(self.crate_path(name, version)?.join(SOURCE_DIR))
     }
 
+    /// Get the path of the git history kept for a crate cloned from a repository
+    ///
+    /// Holds the `.git` directory of the clone, without a working tree.
+    pub fn git_path(&self, name: &str, version: &str) -> Result<PathBuf> {
+        Ok(self.crate_path(name, version)?.join(GIT_DIR))
+    }
+
     /// Get the documentation JSON path for a crate or workspace member
     pub fn docs_path(
         &self,
@@ -117,6 +358,86 @@ impl CacheStorage {
         Ok(base_path.join(DOCS_FILE))
     }
 
+    /// Get the path of documentation JSON that includes private or hidden items
+    pub fn variant_docs_path(
+        &self,
+        name: &str,
+        version: &str,
+        member_name: Option<&str>,
+        variant: DocsVariant,
+    ) -> Result<PathBuf> {
+        let base_path = if let Some(member) = member_name {
+            self.member_path(name, version, member)?
+        } else {
+            self.crate_path(name, version)?
+        };
+        Ok(base_path.join(variant.docs_file()))
+    }
+
+    /// Get the path of the unfiltered documentation JSON kept when docs are scoped to modules
+    pub fn full_docs_path(
+        &self,
+        name: &str,
+        version: &str,
+        member_name: Option<&str>,
+    ) -> Result<PathBuf> {
+        let base_path = if let Some(member) = member_name {
+            self.member_path(name, version, member)?
+        } else {
+            self.crate_path(name, version)?
+        };
+        Ok(base_path.join(FULL_DOCS_FILE))
+    }
+
+    /// Get the path of the lock file held while documentation is generated
+    ///
+    /// Member locks live in the crate directory too, so that a failed build
+    /// leaves no member directory behind. `build` names a separate build of the
+    /// same sources, such as a docs variant or a target platform, which gets
+    /// its own lock.
+    pub fn docgen_lock_path(
+        &self,
+        name: &str,
+        version: &str,
+        member_name: Option<&str>,
+        build: Option<&str>,
+    ) -> Result<PathBuf> {
+        use crate::cache::member_utils::{normalize_member_path, validate_member_path};
+
+        let mut file_name = String::new();
+        if let Some(member) = member_name {
+            validate_member_path(member)?;
+            file_name.push_str(&normalize_member_path(member));
+            file_name.push('.');
+        }
+        if let Some(build) = build {
+            file_name.push_str(build);
+            file_name.push('.');
+        }
+        file_name.push_str(DOCGEN_LOCK_FILE);
+        Ok(self.crate_path(name, version)?.join(file_name))
+    }
+
+    /// Get the documentation JSON path for a specific target platform
+    ///
+    /// Docs for each target live in their own `targets/<triple>` directory so that,
+    /// for example, Windows and Linux docs of the same crate don't collide.
+    pub fn target_docs_path(
+        &self,
+        name: &str,
+        version: &str,
+        member_name: Option<&str>,
+        target: &str,
+    ) -> Result<PathBuf> {
+        validate_target_triple(target)?;
+        let base_path = if let Some(member) = member_name {
+            self.member_path(name, version, member)?
+        } else {
+            self.crate_path(name, version)?
+        };
+        Ok(base_path.join(TARGETS_DIR).join(target).join(DOCS_FILE))
+    }
+
     /// Get the metadata path for a crate or workspace member
     pub fn metadata_path(
         &self,
@@ -189,6 +510,32 @@ impl CacheStorage {
             .unwrap_or(false)
     }
 
+    /// Check if documentation including private or hidden items is generated
+    pub fn has_variant_docs(
+        &self,
+        name: &str,
+        version: &str,
+        member_name: Option<&str>,
+        variant: DocsVariant,
+    ) -> bool {
+        self.variant_docs_path(name, version, member_name, variant)
+            .map(|p| p.exists())
+            .unwrap_or(false)
+    }
+
+    /// Check if documentation for a specific target platform is generated
+    pub fn has_target_docs(
+        &self,
+        name: &str,
+        version: &str,
+        member_name: Option<&str>,
+        target: &str,
+    ) -> bool {
+        self.target_docs_path(name, version, member_name, target)
+            .map(|p| p.exists())
+            .unwrap_or(false)
+    }
+
     /// Check if a search index exists for a crate or workspace member
     pub fn has_search_index(&self, name: &str, version: &str, member_name: Option<&str>) -> bool {
         self.search_index_path(name, version, member_name)
@@ -198,8 +545,22 @@ impl CacheStorage {
 
     /// Ensure a directory exists
     pub fn ensure_dir(&self, path: &Path) -> Result<()> {
+        self.ensure_writable()?;
+        let created: Vec<PathBuf> = match self.permissions {
+            Some(_) => path
+                .ancestors()
+                .take_while(|ancestor| !ancestor.exists())
+                .map(Path::to_path_buf)
+                .collect(),
+            None => Vec::new(),
+        };
         fs::create_dir_all(path)
             .with_context(|| format!("Failed to create directory: {}", path.display()))?;
+        if let Some(mode) = self.permissions {
+            for dir in created {
+                set_mode(&dir, mode)?;
+            }
+        }
         Ok(())
     }
 
@@ -240,6 +601,8 @@ impl CacheStorage {
         source_path: Option<&str>,
         member_info: Option<MemberInfo>,
     ) -> Result<()> {
+        self.ensure_writable()?;
+
         // Extract member path as owned string to avoid borrowing issues
         let member_path_string = member_info.as_ref().map(|info| info.original_path.clone());
         let member_path_str = member_path_string.as_deref();
@@ -251,6 +614,12 @@ impl CacheStorage {
 
         let size_bytes = self.calculate_dir_size(&base_path)?;
 
+        // Keep the yanked flag recorded at download time and the documentation
+        // source when metadata is rewritten
+        let existing = self.load_metadata(name, version, member_path_str).ok();
+        let yanked = existing.as_ref().is_some_and(|existing| existing.yanked);
+        let doc_source = existing.and_then(|existing| existing.doc_source);
+
         let metadata = CacheMetadata {
             name: name.to_string(),
             version: version.to_string(),
@@ -259,6 +628,9 @@ impl CacheStorage {
             size_bytes,
             source: source.to_string(),
             source_path: source_path.map(String::from),
+            yanked,
+            last_error: None,
+            doc_source,
             member_info,
         };
 
@@ -268,6 +640,159 @@ impl CacheStorage {
         Ok(())
     }
 
+    /// Record that documentation could not be generated for a workspace member
+    ///
+    /// Later queries for the member report `error` instead of attempting the
+    /// build again. Caching the member successfully clears the failure.
+    pub fn record_member_failure(
+        &self,
+        name: &str,
+        version: &str,
+        source: &str,
+        member_info: MemberInfo,
+        error: &str,
+    ) -> Result<()> {
+        let member_path = self.member_path(name, version, &member_info.original_path)?;
+        self.ensure_dir(&member_path)?;
+
+        let metadata = CacheMetadata {
+            name: name.to_string(),
+            version: version.to_string(),
+            cached_at: chrono::Utc::now(),
+            doc_generated: false,
+            size_bytes: 0,
+            source: source.to_string(),
+            source_path: None,
+            yanked: false,
+            last_error: Some(error.to_string()),
+            doc_source: None,
+            member_info: Some(member_info),
+        };
+
+        let json = serde_json::to_string_pretty(&metadata)?;
+        fs::write(member_path.join(METADATA_FILE), json)?;
+        Ok(())
+    }
+
+    /// Get the recorded documentation build failure of a workspace member, if any
+    pub fn member_failure(&self, name: &str, version: &str, member_name: &str) -> Option<String> {
+        if self.has_docs(name, version, Some(member_name)) {
+            return None;
+        }
+        self.load_metadata(name, version, Some(member_name))
+            .ok()
+            .and_then(|metadata| metadata.last_error)
+    }
+
+    /// Record whether a cached crate version has been yanked
+    pub fn set_yanked(&self, name: &str, version: &str, yanked: bool) -> Result<()> {
+        self.ensure_writable()?;
+
+        let mut metadata = self.load_metadata(name, version, None)?;
+        metadata.yanked = yanked;
+        let json = serde_json::to_string_pretty(&metadata)?;
+        fs::write(self.metadata_path(name, version, None)?, json)?;
+        Ok(())
+    }
+
+    /// Record where the documentation of a cached crate version came from
+    ///
+    /// `None` marks documentation generated with the local toolchain.
+    pub fn set_doc_source(
+        &self,
+        name: &str,
+        version: &str,
+        doc_source: Option<&str>,
+    ) -> Result<()> {
+        self.ensure_writable()?;
+
+        let mut metadata = self.load_metadata(name, version, None)?;
+        metadata.doc_source = doc_source.map(String::from);
+        let json = serde_json::to_string_pretty(&metadata)?;
+        fs::write(self.metadata_path(name, version, None)?, json)?;
+        Ok(())
+    }
+
+    /// Get the path of the file recording when a crate or workspace member was last used
+    pub fn last_accessed_path(
+        &self,
+        name: &str,
+        version: &str,
+        member_name: Option<&str>,
+    ) -> Result<PathBuf> {
+        let base_path = if let Some(member) = member_name {
+            self.member_path(name, version, member)?
+        } else {
+            self.crate_path(name, version)?
+        };
+        Ok(base_path.join(LAST_ACCESSED_FILE))
+    }
+
+    /// Record that a crate or workspace member was just used
+    ///
+    /// The time is kept in a sidecar file rather than the metadata, and is
+    /// rewritten at most once per [`ACCESS_RESOLUTION`], so a repeated query
+    /// costs a single `stat`. Each rewrite also increments the access count
+    /// kept on the file's second line. Using a member also counts as using its
+    /// crate version. Failures are only logged, since queries must not fail
+    /// because of them.
+    pub fn touch(&self, name: &str, version: &str, member_name: Option<&str>) {
+        if !self.writable || self.read_only {
+            return;
+        }
+
+        let members = member_name.into_iter().map(Some).chain([None]);
+        for member in members {
+            let path = match self.last_accessed_path(name, version, member) {
+                Ok(path) => path,
+                Err(e) => {
+                    tracing::debug!("Not recording access of {}-{}: {}", name, version, e);
+                    return;
+                }
+            };
+
+            let fresh = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age < ACCESS_RESOLUTION);
+            if fresh {
+                continue;
+            }
+
+            let count = read_access_count(&path) + 1;
+            let contents = format!("{}\n{count}\n", chrono::Utc::now().to_rfc3339());
+            if let Err(e) = fs::write(&path, contents) {
+                tracing::debug!("Failed to record access in {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// When a crate or workspace member was last used, if it was used since this was tracked
+    pub fn last_accessed(
+        &self,
+        name: &str,
+        version: &str,
+        member_name: Option<&str>,
+    ) -> Option<chrono::DateTime<chrono::Utc>> {
+        let path = self.last_accessed_path(name, version, member_name).ok()?;
+        let contents = fs::read_to_string(path).ok()?;
+        let timestamp = contents.lines().next()?;
+        chrono::DateTime::parse_from_rfc3339(timestamp.trim())
+            .ok()
+            .map(|time| time.with_timezone(&chrono::Utc))
+    }
+
+    /// How often a crate or workspace member was used
+    ///
+    /// Accesses are counted at [`ACCESS_RESOLUTION`], so queries made in quick
+    /// succession count once.
+    pub fn access_count(&self, name: &str, version: &str, member_name: Option<&str>) -> u64 {
+        self.last_accessed_path(name, version, member_name)
+            .map(|path| read_access_count(&path))
+            .unwrap_or(0)
+    }
+
     /// Load metadata for a crate or workspace member
     pub fn load_metadata(
         &self,
@@ -323,6 +848,9 @@ impl CacheStorage {
                                     size_bytes: 0,
                                     source: default_source(),
                                     source_path: None,
+                                    yanked: false,
+                                    last_error: None,
+                                    doc_source: None,
                                     member_info: None,
                                 }
                             }
@@ -336,6 +864,84 @@ impl CacheStorage {
         Ok(cached_crates)
     }
 
+    /// Find search indexes whose documentation is no longer cached
+    ///
+    /// Scans the cache directory rather than the metadata, so indexes of crate
+    /// versions and members that are no longer listed as cached are found too.
+    pub fn find_orphaned_indexes(&self) -> Result<Vec<OrphanedIndex>> {
+        let crates_dir = self.cache_dir.join(CRATES_DIR);
+        let mut orphans = Vec::new();
+
+        if !crates_dir.exists() {
+            return Ok(orphans);
+        }
+
+        for crate_entry in fs::read_dir(&crates_dir)? {
+            let crate_entry = crate_entry?;
+            if !crate_entry.file_type()?.is_dir() {
+                continue;
+            }
+            let name = crate_entry.file_name().to_string_lossy().to_string();
+
+            for version_entry in fs::read_dir(crate_entry.path())? {
+                let version_entry = version_entry?;
+                if !version_entry.file_type()?.is_dir() {
+                    continue;
+                }
+                let version = version_entry.file_name().to_string_lossy().to_string();
+
+                // The crate itself and each member directory may hold an index
+                let mut bases = vec![(None, version_entry.path())];
+                let members_dir = version_entry.path().join(MEMBERS_DIR);
+                if members_dir.is_dir() {
+                    for member_entry in fs::read_dir(&members_dir)? {
+                        let member_entry = member_entry?;
+                        if member_entry.file_type()?.is_dir() {
+                            let member = member_entry.file_name().to_string_lossy().to_string();
+                            bases.push((Some(member), member_entry.path()));
+                        }
+                    }
+                }
+
+                for (member, base) in bases {
+                    let index_path = base.join(SEARCH_INDEX_DIR);
+                    if index_path.is_dir() && !base.join(DOCS_FILE).exists() {
+                        orphans.push(OrphanedIndex {
+                            name: name.clone(),
+                            version: version.clone(),
+                            member,
+                            path: index_path,
+                        });
+                    }
+                }
+            }
+        }
+
+        orphans.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(orphans)
+    }
+
+    /// Remove an orphaned search index and the directories it leaves empty
+    ///
+    /// A crate version directory holding nothing but the index is removed as
+    /// well, so it no longer shows up as cached. Returns the bytes reclaimed.
+    pub fn remove_orphaned_index(&self, orphan: &OrphanedIndex) -> Result<u64> {
+        self.ensure_writable()?;
+        let bytes = self.calculate_dir_size(&orphan.path)?;
+        fs::remove_dir_all(&orphan.path)
+            .with_context(|| format!("Failed to remove search index: {}", orphan.path.display()))?;
+
+        let crates_dir = self.cache_dir.join(CRATES_DIR);
+        for dir in orphan.path.ancestors().skip(1) {
+            // Only empty directories can be removed, so stop at the first one in use
+            if dir == crates_dir || fs::remove_dir(dir).is_err() {
+                break;
+            }
+        }
+
+        Ok(bytes)
+    }
+
     /// Get all workspace members for a cached crate
     pub fn list_workspace_members(&self, name: &str, version: &str) -> Result<Vec<String>> {
         let members_dir = self.crate_path(name, version)?.join(MEMBERS_DIR);
@@ -355,6 +961,10 @@ impl CacheStorage {
                 match fs::read_to_string(&metadata_path) {
                     Ok(content) => match serde_json::from_str::<CacheMetadata>(&content) {
                         Ok(metadata) => {
+                            // Members whose docs failed to build are not cached
+                            if metadata.last_error.is_some() {
+                                continue;
+                            }
                             if let Some(member_info) = metadata.member_info {
                                 members.push(member_info.original_path);
                                 continue;
@@ -392,6 +1002,7 @@ impl CacheStorage {
 
     /// Remove a cached crate version
     pub fn remove_crate(&self, name: &str, version: &str) -> Result<()> {
+        self.ensure_writable()?;
         let path = self.crate_path(name, version)?;
         if path.exists() {
             fs::remove_dir_all(&path)
@@ -400,6 +1011,41 @@ impl CacheStorage {
         Ok(())
     }
 
+    /// Remove the search index of a crate or workspace member, keeping its docs
+    pub fn remove_search_index(
+        &self,
+        name: &str,
+        version: &str,
+        member_name: Option<&str>,
+    ) -> Result<()> {
+        self.ensure_writable()?;
+        let path = self.search_index_path(name, version, member_name)?;
+        if path.exists() {
+            fs::remove_dir_all(&path)
+                .with_context(|| format!("Failed to remove search index: {name}/{version}"))?;
+        }
+        Ok(())
+    }
+
+    /// Remove every cached crate, including docs and search indexes
+    ///
+    /// The cache root is preserved and the crates directory is recreated empty.
+    /// Returns the number of bytes reclaimed.
+    pub fn clear_all(&self) -> Result<u64> {
+        self.ensure_writable()?;
+        let crates_dir = self.cache_dir.join(CRATES_DIR);
+        let reclaimed = self.calculate_dir_size(&crates_dir)?;
+
+        if crates_dir.exists() {
+            fs::remove_dir_all(&crates_dir).with_context(|| {
+                format!("Failed to remove cache directory: {}", crates_dir.display())
+            })?;
+        }
+        self.ensure_dir(&crates_dir)?;
+
+        Ok(reclaimed)
+    }
+
     /// Copy a crate to a temporary backup location
     pub fn backup_crate_to_temp(&self, name: &str, version: &str) -> Result<PathBuf> {
         let source = self.crate_path(name, version)?;
@@ -472,6 +1118,259 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_parse_cache_permissions() {
+        assert_eq!(parse_cache_permissions("0700").unwrap(), 0o700);
+        assert_eq!(parse_cache_permissions("750").unwrap(), 0o750);
+        assert_eq!(parse_cache_permissions("0o700").unwrap(), 0o700);
+        assert!(parse_cache_permissions("").is_err());
+        assert!(parse_cache_permissions("0800").is_err());
+        assert!(parse_cache_permissions("1777").is_err());
+        assert!(parse_cache_permissions("0500").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cache_permissions_applied_to_directories_and_files() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+        let mode_of =
+            |path: &Path| -> Result<u32> { Ok(fs::metadata(path)?.permissions().mode() & 0o777) };
+
+        let temp_dir = TempDir::new()?;
+        let cache_dir = temp_dir.path().join("cache");
+        let storage = CacheStorage::new(Some(cache_dir.clone()))?.with_permissions(Some(0o700))?;
+        assert_eq!(mode_of(&cache_dir)?, 0o700);
+
+        let source = storage.source_path("serde", "1.0.0")?;
+        storage.ensure_dir(&source)?;
+        assert_eq!(mode_of(&source)?, 0o700);
+        assert_eq!(mode_of(&storage.crate_path("serde", "1.0.0")?)?, 0o700);
+        assert_eq!(mode_of(&cache_dir.join(CRATES_DIR))?, 0o700);
+
+        let file = source.join("lib.rs");
+        fs::write(&file, "pub fn hello() {}")?;
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o644))?;
+        storage.restrict_crate_permissions("serde", "1.0.0")?;
+        assert_eq!(mode_of(&file)?, 0o600);
+        Ok(())
+    }
+
+    #[test]
+    fn test_clear_all_empties_crates_dir() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = CacheStorage::new(Some(temp_dir.path().to_path_buf()))?;
+
+        let source = storage.source_path("serde", "1.0.0")?;
+        storage.ensure_dir(&source)?;
+        fs::write(source.join("lib.rs"), "pub fn hello() {}")?;
+        let index = storage.search_index_path("serde", "1.0.0", None)?;
+        storage.ensure_dir(&index)?;
+        fs::write(index.join("meta.json"), "{}")?;
+
+        let reclaimed = storage.clear_all()?;
+        assert_eq!(reclaimed, 19);
+
+        // Cache root and crates directory are preserved but empty
+        let crates_dir = temp_dir.path().join(CRATES_DIR);
+        assert!(temp_dir.path().exists());
+        assert!(crates_dir.exists());
+        assert_eq!(fs::read_dir(&crates_dir)?.count(), 0);
+        assert!(storage.list_cached_crates()?.is_empty());
+
+        // Clearing an already empty cache reclaims nothing
+        assert_eq!(storage.clear_all()?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_layout_version_gates_writes() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = CacheStorage::new(Some(temp_dir.path().to_path_buf()))?;
+        assert_eq!(
+            read_layout_version(temp_dir.path())?,
+            Some(CACHE_LAYOUT_VERSION)
+        );
+        storage.ensure_writable()?;
+
+        // A cache written by a newer build is left untouched
+        fs::write(
+            temp_dir.path().join(LAYOUT_VERSION_FILE),
+            format!("{}", CACHE_LAYOUT_VERSION + 1),
+        )?;
+        let storage = CacheStorage::new(Some(temp_dir.path().to_path_buf()))?;
+        assert!(storage.ensure_writable().is_err());
+        assert!(storage.remove_crate("serde", "1.0.0").is_err());
+        assert!(storage.clear_all().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_only_storage() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = CacheStorage::new(Some(temp_dir.path().to_path_buf()))?;
+        storage.ensure_dir(&storage.source_path("demo", "0.1.1")?)?;
+        storage.save_metadata("demo", "0.1.1")?;
+
+        let storage = CacheStorage::open(Some(temp_dir.path().to_path_buf()), true)?;
+        assert!(storage.is_writable());
+        let error = storage.ensure_writable().unwrap_err();
+        assert_eq!(error.to_string(), READ_ONLY_ERROR);
+        assert!(storage.remove_crate("demo", "0.1.1").is_err());
+        assert!(storage.is_cached("demo", "0.1.1"));
+
+        // Queries are not recorded either
+        storage.touch("demo", "0.1.1", None);
+        assert!(storage.last_accessed("demo", "0.1.1", None).is_none());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_only_storage_on_unwritable_directory() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new()?;
+
+        // A missing cache is not created
+        let missing = temp_dir.path().join("missing");
+        let storage = CacheStorage::open(Some(missing.clone()), true)?;
+        assert!(!missing.exists());
+        assert!(storage.list_cached_crates()?.is_empty());
+
+        let cache_dir = temp_dir.path().join("cache");
+        let storage = CacheStorage::new(Some(cache_dir.clone()))?;
+        storage.ensure_dir(&storage.source_path("demo", "0.1.1")?)?;
+        storage.save_metadata("demo", "0.1.1")?;
+
+        fs::set_permissions(&cache_dir, fs::Permissions::from_mode(0o555))?;
+        // Root ignores directory permissions, so there is nothing to check
+        if fs::write(cache_dir.join("probe"), "").is_ok() {
+            fs::set_permissions(&cache_dir, fs::Permissions::from_mode(0o755))?;
+            return Ok(());
+        }
+
+        let opened = CacheStorage::open(Some(cache_dir.clone()), true)
+            .and_then(|storage| storage.with_permissions(Some(0o700)));
+        let mode = fs::metadata(&cache_dir)?.permissions().mode() & 0o777;
+        fs::set_permissions(&cache_dir, fs::Permissions::from_mode(0o755))?;
+
+        let storage = opened?;
+        assert_eq!(mode, 0o555);
+        assert!(storage.is_cached("demo", "0.1.1"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_yanked_flag_survives_metadata_rewrite() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = CacheStorage::new(Some(temp_dir.path().to_path_buf()))?;
+        storage.ensure_dir(&storage.source_path("demo", "0.1.1")?)?;
+
+        storage.save_metadata("demo", "0.1.1")?;
+        assert!(!storage.load_metadata("demo", "0.1.1", None)?.yanked);
+
+        storage.set_yanked("demo", "0.1.1", true)?;
+        storage.save_metadata("demo", "0.1.1")?;
+        assert!(storage.load_metadata("demo", "0.1.1", None)?.yanked);
+        assert!(storage.list_cached_crates()?[0].yanked);
+        Ok(())
+    }
+
+    #[test]
+    fn test_touch_records_last_access() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = CacheStorage::new(Some(temp_dir.path().to_path_buf()))?;
+        storage.ensure_dir(&storage.member_path("demo", "0.1.1", "crates/core")?)?;
+        assert!(storage.last_accessed("demo", "0.1.1", None).is_none());
+
+        let before = chrono::Utc::now() - chrono::Duration::seconds(1);
+        storage.touch("demo", "0.1.1", Some("crates/core"));
+        let member_access = storage
+            .last_accessed("demo", "0.1.1", Some("crates/core"))
+            .expect("member access recorded");
+        assert!(member_access >= before);
+        // Using a member also counts as using the crate version
+        assert!(storage.last_accessed("demo", "0.1.1", None).is_some());
+
+        // A recent timestamp is not rewritten
+        storage.touch("demo", "0.1.1", Some("crates/core"));
+        assert_eq!(
+            storage.last_accessed("demo", "0.1.1", Some("crates/core")),
+            Some(member_access)
+        );
+        assert_eq!(
+            storage.access_count("demo", "0.1.1", Some("crates/core")),
+            1
+        );
+
+        // A stale timestamp is rewritten and the access counted
+        let path = storage.last_accessed_path("demo", "0.1.1", None)?;
+        fs::write(&path, "2020-01-01T00:00:00+00:00\n4\n")?;
+        let stale = std::time::SystemTime::now() - 2 * ACCESS_RESOLUTION;
+        fs::File::options()
+            .write(true)
+            .open(&path)?
+            .set_modified(stale)?;
+        storage.touch("demo", "0.1.1", None);
+        assert_eq!(storage.access_count("demo", "0.1.1", None), 5);
+        assert!(storage.last_accessed("demo", "0.1.1", None) >= Some(before));
+
+        // Crates that are not cached are left alone
+        storage.touch("missing", "1.0.0", None);
+        assert!(!storage.crate_path("missing", "1.0.0")?.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_member_failure_is_recorded_and_cleared() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = CacheStorage::new(Some(temp_dir.path().to_path_buf()))?;
+        let member_info = MemberInfo {
+            original_path: "crates/cli".to_string(),
+            normalized_path: "crates-cli".to_string(),
+            package_name: "cli".to_string(),
+        };
+
+        assert!(
+            storage
+                .member_failure("demo", "0.1.0", "crates/cli")
+                .is_none()
+        );
+
+        storage.record_member_failure(
+            "demo",
+            "0.1.0",
+            "crates.io",
+            member_info.clone(),
+            "binary-only package",
+        )?;
+        assert_eq!(
+            storage
+                .member_failure("demo", "0.1.0", "crates/cli")
+                .as_deref(),
+            Some("binary-only package")
+        );
+        let metadata = storage.load_metadata("demo", "0.1.0", Some("crates/cli"))?;
+        assert!(!metadata.doc_generated);
+        assert!(storage.list_workspace_members("demo", "0.1.0")?.is_empty());
+
+        // A successful build clears the failure
+        let docs_path = storage.docs_path("demo", "0.1.0", Some("crates/cli"))?;
+        fs::write(&docs_path, "{}")?;
+        storage.save_metadata_with_source("demo", "0.1.0", "crates.io", None, Some(member_info))?;
+        assert!(
+            storage
+                .member_failure("demo", "0.1.0", "crates/cli")
+                .is_none()
+        );
+        assert_eq!(
+            storage.list_workspace_members("demo", "0.1.0")?,
+            vec!["crates/cli".to_string()]
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_crate_path_validation() {
         let temp_dir = TempDir::new().unwrap();
@@ -589,5 +1488,17 @@ mod tests {
                 .search_index_path("valid", version, Some(malicious_member))
                 .is_err()
         );
+
+        // Target triples are used as directory names
+        assert!(
+            storage
+                .target_docs_path("valid", version, None, "../../etc")
+                .is_err()
+        );
+        assert!(
+            storage
+                .target_docs_path("valid", version, None, "x86_64-pc-windows-msvc")
+                .is_ok()
+        );
     }
 }
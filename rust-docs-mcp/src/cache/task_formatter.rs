@@ -4,7 +4,7 @@
 //! optimized for LLM (AI agent) consumption. All output is designed to be clear,
 //! scannable, and include actionable commands.
 
-use super::task_manager::{CachingTask, TaskStatus};
+use super::task_manager::{CachingTask, TaskId, TaskStatus};
 use chrono::{DateTime, Utc};
 use std::time::SystemTime;
 
@@ -95,6 +95,8 @@ pub fn format_single_task(task: &CachingTask) -> String {
                     step_str,
                     get_stage_context(stage.as_str())
                 )
+            } else if task.status == TaskStatus::Pending {
+                "**Current Stage**: Queued, waiting for a free caching slot".to_string()
             } else {
                 "**Current Stage**: Initializing".to_string()
             };
@@ -184,6 +186,7 @@ The crate has been successfully cached and documentation is available. You can n
 - Consider using a different source (github or local)
 
 ## Available Actions
+- **Retry this task**: `cache_operations({{task_id: "{}", retry_failed: true}})`
 - **Clear this task**: `cache_operations({{task_id: "{}", clear: true}})`
 "#,
                 task.task_id,
@@ -196,6 +199,7 @@ The crate has been successfully cached and documentation is available. You can n
                 format_timestamp(task.completed_at.unwrap_or(task.started_at)),
                 format_duration(task.elapsed_secs()),
                 error_msg,
+                task.task_id,
                 task.task_id
             )
         }
@@ -276,7 +280,10 @@ pub fn format_task_list(tasks: Vec<CachingTask>) -> String {
         output.push_str(&format!("- **In Progress**: {}\n", in_progress.len()));
     }
     if !pending.is_empty() {
-        output.push_str(&format!("- **Pending**: {}\n", pending.len()));
+        output.push_str(&format!(
+            "- **Queued** (waiting for a free slot): {}\n",
+            pending.len()
+        ));
     }
     if !completed.is_empty() {
         output.push_str(&format!("- **Completed**: {}\n", completed.len()));
@@ -305,7 +312,10 @@ pub fn format_task_list(tasks: Vec<CachingTask>) -> String {
     }
 
     if !pending.is_empty() {
-        output.push_str(&format!("\n## Pending ({})\n\n", pending.len()));
+        output.push_str(&format!("\n## Queued ({})\n\n", pending.len()));
+        output.push_str(
+            "These tasks are waiting for a running task to finish before they start.\n\n",
+        );
         for task in &pending {
             output.push_str(&format_task_summary(task));
             output.push_str("\n---\n");
@@ -341,6 +351,9 @@ pub fn format_task_list(tasks: Vec<CachingTask>) -> String {
     if !in_progress.is_empty() {
         output.push_str("- **Cancel tasks**: Use `cache_operations` with task_id and cancel: true for each task\n");
     }
+    if !failed.is_empty() {
+        output.push_str("- **Retry all failed**: `cache_operations({retry_failed: true})`\n");
+    }
     if !completed.is_empty() || !failed.is_empty() || !cancelled.is_empty() {
         output.push_str(
             "- **Clear all completed/failed/cancelled**: `cache_operations({clear: true})`\n",
@@ -399,9 +412,13 @@ fn format_task_summary(task: &CachingTask) -> String {
         }
         TaskStatus::Pending => {
             output.push_str(&format!(
-                "**Started**: {}  \n",
+                "**Queued**: {}  \n",
                 format_timestamp(task.started_at)
             ));
+            output.push_str(&format!(
+                "**Waiting**: {}\n",
+                format_duration(task.elapsed_secs())
+            ));
             output.push_str("\n**Actions**:\n");
             output.push_str(&format!(
                 "- Cancel: `cache_operations({{task_id: \"{}\", cancel: true}})`\n",
@@ -442,6 +459,10 @@ fn format_task_summary(task: &CachingTask) -> String {
                 "- View details: `cache_operations({{task_id: \"{}\"}})`\n",
                 task.task_id
             ));
+            output.push_str(&format!(
+                "- Retry: `cache_operations({{task_id: \"{}\", retry_failed: true}})`\n",
+                task.task_id
+            ));
             output.push_str(&format!(
                 "- Clear: `cache_operations({{task_id: \"{}\", clear: true}})`\n",
                 task.task_id
@@ -508,3 +529,44 @@ pub fn format_clear_result(tasks: Vec<CachingTask>) -> String {
 
     output
 }
+
+/// Format the result of retrying failed tasks
+///
+/// `retried` pairs each failed task with the ID of the task that replaced it.
+pub fn format_retry_result(
+    retried: Vec<(CachingTask, TaskId)>,
+    not_retried: Vec<(CachingTask, String)>,
+) -> String {
+    if retried.is_empty() && not_retried.is_empty() {
+        return "# No Tasks Retried\n\nNo failed tasks were found to retry.".to_string();
+    }
+
+    let mut output = String::from("# Tasks Retried\n\n");
+    if !retried.is_empty() {
+        output.push_str(&format!(
+            "Started {} failed task(s) again, replacing them with new tasks:\n\n",
+            retried.len()
+        ));
+        for (task, new_task_id) in &retried {
+            output.push_str(&format!(
+                "- `{}` → `{}` ({}-{})\n",
+                task.task_id, new_task_id, task.crate_name, task.version
+            ));
+        }
+        output.push_str(
+            "\nUse `cache_operations({task_id: \"<new task id>\"})` to monitor progress.\n",
+        );
+    }
+
+    if !not_retried.is_empty() {
+        output.push_str(&format!("\n## Not Retried ({})\n\n", not_retried.len()));
+        for (task, reason) in &not_retried {
+            output.push_str(&format!(
+                "- `{}` ({}-{}): {}\n",
+                task.task_id, task.crate_name, task.version, reason
+            ));
+        }
+    }
+
+    output
+}
@@ -3,10 +3,13 @@
 //! This module provides task tracking and management for background caching operations.
 //! Each caching operation gets a unique task ID and can be monitored, cancelled, or cleared.
 
+use crate::cache::downloader::ProgressCallback;
+use crate::cache::tools::CacheCrateParams;
 use dashmap::DashMap;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::SystemTime;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
@@ -16,7 +19,7 @@ pub type TaskId = String;
 /// Status of a caching task
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TaskStatus {
-    /// Task is queued but not yet started
+    /// Task is queued waiting for a free concurrency slot
     Pending,
     /// Task is currently executing
     InProgress,
@@ -43,7 +46,7 @@ impl TaskStatus {
     /// Convert status to display string
     pub fn display(&self) -> &'static str {
         match self {
-            TaskStatus::Pending => "PENDING",
+            TaskStatus::Pending => "QUEUED",
             TaskStatus::InProgress => "IN PROGRESS",
             TaskStatus::Completed => "COMPLETED ✓",
             TaskStatus::Failed => "FAILED ✗",
@@ -110,6 +113,8 @@ pub struct CachingTask {
     pub source_type: String,
     /// Optional source details (e.g., GitHub URL, local path)
     pub source_details: Option<String>,
+    /// Parameters the task was started with, to start it again on retry
+    pub request: Option<CacheCrateParams>,
     /// Current status
     pub status: TaskStatus,
     /// Current stage (if in progress)
@@ -135,6 +140,7 @@ impl CachingTask {
         version: String,
         source_type: String,
         source_details: Option<String>,
+        request: Option<CacheCrateParams>,
     ) -> Self {
         Self {
             task_id: Uuid::new_v4().to_string(),
@@ -142,6 +148,7 @@ impl CachingTask {
             version,
             source_type,
             source_details,
+            request,
             status: TaskStatus::Pending,
             stage: None,
             current_step: None,
@@ -203,6 +210,89 @@ impl CachingTask {
             TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled
         )
     }
+
+    /// Whether both tasks cache the same crate version from the same source and members,
+    /// with the same options
+    pub fn is_same_request(&self, other: &CachingTask) -> bool {
+        self.crate_name == other.crate_name
+            && self.version == other.version
+            && self.source_type == other.source_type
+            && self.source_location() == other.source_location()
+            && self.members() == other.members()
+            && self.options() == other.options()
+    }
+
+    /// Whether this task caches the crate version and source `request` asks for
+    ///
+    /// The name and version are only compared when the request gives them, as
+    /// they may be read from the manifest of a local crate instead.
+    pub fn matches_request(&self, request: &CacheCrateParams) -> bool {
+        (request.crate_name.is_empty() || self.crate_name == request.crate_name)
+            && request
+                .version
+                .as_ref()
+                .is_none_or(|version| *version == self.version)
+            && self.source_type == request.source_type
+            && self.source_location() == request.github_url.as_deref().or(request.path.as_deref())
+    }
+
+    /// GitHub URL or path the crate is cached from, if not crates.io
+    fn source_location(&self) -> Option<&str> {
+        match &self.request {
+            Some(request) => request.github_url.as_deref().or(request.path.as_deref()),
+            None => self.source_details.as_deref(),
+        }
+    }
+
+    /// Workspace members requested, sorted
+    fn members(&self) -> Vec<&str> {
+        let mut members: Vec<&str> = self
+            .request
+            .iter()
+            .flat_map(|request| request.members.iter().flatten())
+            .map(String::as_str)
+            .collect();
+        members.sort_unstable();
+        members.dedup();
+        members
+    }
+
+    /// Options of the request that change the work done, with defaults filled in
+    fn options(&self) -> RequestOptions<'_> {
+        let Some(request) = &self.request else {
+            return RequestOptions::default();
+        };
+        let mut scope: Vec<&str> = request.scope.iter().flatten().map(String::as_str).collect();
+        scope.sort_unstable();
+        scope.dedup();
+
+        RequestOptions {
+            branch: request.branch.as_deref(),
+            tag: request.tag.as_deref(),
+            update: request.update.unwrap_or(false),
+            target: request.target.as_deref(),
+            scope,
+            keep_full_docs: request.keep_full_docs.unwrap_or(false),
+        }
+    }
+}
+
+/// Options of a caching request that make two requests for the same crate different work
+#[derive(Debug, Default, PartialEq)]
+struct RequestOptions<'a> {
+    branch: Option<&'a str>,
+    tag: Option<&'a str>,
+    update: bool,
+    target: Option<&'a str>,
+    scope: Vec<&'a str>,
+    keep_full_docs: bool,
+}
+
+/// Default number of caching tasks allowed to run concurrently (one per CPU)
+pub fn default_max_concurrent_tasks() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
 }
 
 /// Manager for caching tasks
@@ -210,16 +300,53 @@ impl CachingTask {
 pub struct TaskManager {
     /// Map of task IDs to tasks using DashMap for concurrent access
     tasks: Arc<DashMap<TaskId, CachingTask>>,
+    /// Bounds the number of caching tasks running at the same time
+    permits: Arc<Semaphore>,
+    /// Maximum number of concurrently running caching tasks
+    max_concurrent_tasks: usize,
+    /// Held while looking for a running task and registering a new one, so
+    /// identical requests arriving together share a single task
+    registering: Arc<Mutex<()>>,
 }
 
 impl TaskManager {
     /// Create a new task manager
     pub fn new() -> Self {
+        Self::with_max_concurrent_tasks(default_max_concurrent_tasks())
+    }
+
+    /// Create a new task manager allowing at most `max_concurrent_tasks` running tasks
+    pub fn with_max_concurrent_tasks(max_concurrent_tasks: usize) -> Self {
+        let max_concurrent_tasks = max_concurrent_tasks.max(1);
         Self {
             tasks: Arc::new(DashMap::new()),
+            permits: Arc::new(Semaphore::new(max_concurrent_tasks)),
+            max_concurrent_tasks,
+            registering: Arc::new(Mutex::new(())),
         }
     }
 
+    /// Maximum number of caching tasks that may run concurrently
+    pub fn max_concurrent_tasks(&self) -> usize {
+        self.max_concurrent_tasks
+    }
+
+    /// Try to take a concurrency slot without waiting
+    pub fn try_acquire_permit(&self) -> Option<OwnedSemaphorePermit> {
+        self.permits.clone().try_acquire_owned().ok()
+    }
+
+    /// Wait for a free concurrency slot
+    ///
+    /// The slot is released when the returned permit is dropped.
+    pub async fn acquire_permit(&self) -> OwnedSemaphorePermit {
+        self.permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("task semaphore is never closed")
+    }
+
     /// Create and register a new task
     pub async fn create_task(
         &self,
@@ -227,12 +354,46 @@ impl TaskManager {
         version: String,
         source_type: String,
         source_details: Option<String>,
+        request: Option<CacheCrateParams>,
     ) -> CachingTask {
-        let task = CachingTask::new(crate_name, version, source_type, source_details);
+        let task = CachingTask::new(crate_name, version, source_type, source_details, request);
         self.tasks.insert(task.task_id.clone(), task.clone());
         task
     }
 
+    /// Return the running task for the same request, or create and register a new one
+    ///
+    /// A non-terminal task caching the same crate version from the same source
+    /// and workspace members is returned instead of starting a duplicate build.
+    /// The flag is true if the task was newly created.
+    pub async fn find_or_create_task(
+        &self,
+        crate_name: String,
+        version: String,
+        source_type: String,
+        source_details: Option<String>,
+        request: Option<CacheCrateParams>,
+    ) -> (CachingTask, bool) {
+        let task = CachingTask::new(crate_name, version, source_type, source_details, request);
+
+        let _registering = self
+            .registering
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let existing = self
+            .tasks
+            .iter()
+            .filter(|entry| !entry.value().is_terminal() && entry.value().is_same_request(&task))
+            .map(|entry| entry.value().clone())
+            .max_by_key(|task| task.started_at);
+        if let Some(existing) = existing {
+            return (existing, false);
+        }
+
+        self.tasks.insert(task.task_id.clone(), task.clone());
+        (task, true)
+    }
+
     /// Get a task by ID
     pub async fn get_task(&self, task_id: &str) -> Option<CachingTask> {
         self.tasks.get(task_id).map(|r| r.clone())
@@ -256,6 +417,15 @@ impl TaskManager {
         result
     }
 
+    /// Find the most recently started non-terminal task caching what `request` asks for
+    pub async fn find_active_task(&self, request: &CacheCrateParams) -> Option<CachingTask> {
+        self.tasks
+            .iter()
+            .filter(|entry| !entry.value().is_terminal() && entry.value().matches_request(request))
+            .map(|entry| entry.value().clone())
+            .max_by_key(|task| task.started_at)
+    }
+
     /// Update task status
     pub async fn update_status(&self, task_id: &str, status: TaskStatus) -> bool {
         if let Some(mut task) = self.tasks.get_mut(task_id) {
@@ -291,6 +461,23 @@ impl TaskManager {
         }
     }
 
+    /// Create a progress callback that appends the reported percentage to a step description
+    pub fn step_progress_callback(
+        &self,
+        task_id: &str,
+        step: u8,
+        description: impl Into<String>,
+    ) -> ProgressCallback {
+        let tasks = Arc::clone(&self.tasks);
+        let task_id = task_id.to_string();
+        let description = description.into();
+        Arc::new(move |percent| {
+            if let Some(mut task) = tasks.get_mut(&task_id) {
+                task.set_step(step, format!("{description} ({percent}%)"));
+            }
+        })
+    }
+
     /// Set task error and mark as failed
     pub async fn set_error(&self, task_id: &str, error: String) -> bool {
         if let Some(mut task) = self.tasks.get_mut(task_id) {
@@ -352,3 +539,196 @@ impl Default for TaskManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn create_test_task(manager: &TaskManager, name: &str) -> CachingTask {
+        manager
+            .create_task(
+                name.to_string(),
+                "1.0.0".to_string(),
+                "cratesio".to_string(),
+                None,
+                None,
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_find_or_create_task_reuses_running_task() {
+        let manager = TaskManager::new();
+        let request = |members: Option<Vec<&str>>| {
+            serde_json::from_value::<CacheCrateParams>(serde_json::json!({
+                "crate_name": "demo",
+                "source_type": "local",
+                "path": "/tmp/demo",
+                "members": members,
+            }))
+            .unwrap()
+        };
+        let find_or_create = |members: Option<Vec<&str>>| {
+            manager.find_or_create_task(
+                "demo".to_string(),
+                "0.1.0".to_string(),
+                "local".to_string(),
+                Some("/tmp/demo".to_string()),
+                Some(request(members)),
+            )
+        };
+
+        let (first, created) = find_or_create(Some(vec!["a", "b"])).await;
+        assert!(created);
+
+        // Members are compared regardless of their order
+        let (second, created) = find_or_create(Some(vec!["b", "a"])).await;
+        assert!(!created);
+        assert_eq!(second.task_id, first.task_id);
+
+        // Other members are a different request
+        let (other, created) = find_or_create(Some(vec!["a"])).await;
+        assert!(created);
+        assert_ne!(other.task_id, first.task_id);
+
+        // A finished task is not reused
+        manager
+            .update_status(&first.task_id, TaskStatus::Completed)
+            .await;
+        let (third, created) = find_or_create(Some(vec!["a", "b"])).await;
+        assert!(created);
+        assert_ne!(third.task_id, first.task_id);
+        assert_eq!(manager.list_tasks(None).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_find_or_create_task_distinguishes_options() {
+        let manager = TaskManager::new();
+        let find_or_create = |options: serde_json::Value| {
+            let mut request = serde_json::json!({
+                "crate_name": "demo",
+                "source_type": "cratesio",
+                "version": "1.0.0",
+            });
+            request
+                .as_object_mut()
+                .unwrap()
+                .extend(options.as_object().unwrap().clone());
+            manager.find_or_create_task(
+                "demo".to_string(),
+                "1.0.0".to_string(),
+                "cratesio".to_string(),
+                None,
+                Some(serde_json::from_value::<CacheCrateParams>(request).unwrap()),
+            )
+        };
+
+        let (plain, created) = find_or_create(serde_json::json!({})).await;
+        assert!(created);
+
+        // Defaults are the same request as leaving the option out
+        let (same, created) = find_or_create(serde_json::json!({"update": false})).await;
+        assert!(!created);
+        assert_eq!(same.task_id, plain.task_id);
+
+        // An update does not join a plain caching task
+        let (update, created) = find_or_create(serde_json::json!({"update": true})).await;
+        assert!(created);
+        assert_ne!(update.task_id, plain.task_id);
+
+        // Neither do docs for another target
+        let (target, created) =
+            find_or_create(serde_json::json!({"target": "wasm32-unknown-unknown"})).await;
+        assert!(created);
+        assert_ne!(target.task_id, plain.task_id);
+
+        let (same_target, created) =
+            find_or_create(serde_json::json!({"target": "wasm32-unknown-unknown"})).await;
+        assert!(!created);
+        assert_eq!(same_target.task_id, target.task_id);
+    }
+
+    #[tokio::test]
+    async fn test_find_active_task_matches_version() {
+        let manager = TaskManager::new();
+        let request = |version: &str| {
+            serde_json::from_value::<CacheCrateParams>(serde_json::json!({
+                "crate_name": "demo",
+                "source_type": "cratesio",
+                "version": version,
+            }))
+            .unwrap()
+        };
+        let mut tasks = Vec::new();
+        for version in ["1.0.0", "2.0.0"] {
+            let (task, _) = manager
+                .find_or_create_task(
+                    "demo".to_string(),
+                    version.to_string(),
+                    "cratesio".to_string(),
+                    None,
+                    Some(request(version)),
+                )
+                .await;
+            tasks.push(task);
+        }
+
+        // The older task is found even though a newer version is caching
+        let found = manager.find_active_task(&request("1.0.0")).await.unwrap();
+        assert_eq!(found.task_id, tasks[0].task_id);
+        assert!(manager.find_active_task(&request("3.0.0")).await.is_none());
+
+        manager
+            .update_status(&tasks[1].task_id, TaskStatus::Completed)
+            .await;
+        assert!(manager.find_active_task(&request("2.0.0")).await.is_none());
+    }
+
+    #[test]
+    fn test_max_concurrent_tasks_is_at_least_one() {
+        let manager = TaskManager::with_max_concurrent_tasks(0);
+        assert_eq!(manager.max_concurrent_tasks(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_excess_tasks_stay_pending_until_permit_free() {
+        let manager = TaskManager::with_max_concurrent_tasks(1);
+        let first = create_test_task(&manager, "first").await;
+        let second = create_test_task(&manager, "second").await;
+
+        // First task takes the only slot and starts
+        let first_permit = manager.try_acquire_permit().expect("slot should be free");
+        manager
+            .update_stage(&first.task_id, CachingStage::Downloading)
+            .await;
+
+        // Second task has to queue for a slot
+        assert!(manager.try_acquire_permit().is_none());
+        let waiter = {
+            let manager = manager.clone();
+            let task_id = second.task_id.clone();
+            tokio::spawn(async move {
+                let _permit = manager.acquire_permit().await;
+                manager
+                    .update_stage(&task_id, CachingStage::Downloading)
+                    .await;
+            })
+        };
+
+        tokio::task::yield_now().await;
+        let queued = manager.get_task(&second.task_id).await.unwrap();
+        assert_eq!(queued.status, TaskStatus::Pending);
+        let running = manager.get_task(&first.task_id).await.unwrap();
+        assert_eq!(running.status, TaskStatus::InProgress);
+
+        // Finishing the first task frees the slot for the queued one
+        manager
+            .update_status(&first.task_id, TaskStatus::Completed)
+            .await;
+        drop(first_permit);
+        waiter.await.unwrap();
+
+        let started = manager.get_task(&second.task_id).await.unwrap();
+        assert_eq!(started.status, TaskStatus::InProgress);
+    }
+}
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::RwLock;
 
 use rmcp::schemars;
@@ -7,16 +8,48 @@ use serde::{Deserialize, Serialize};
 
 use crate::cache::{
     CrateCache,
-    downloader::CrateSource,
+    archive::resolve_archive_version,
+    downloader::{self, CrateSource, resolve_default_branch},
     outputs::{
-        CacheCrateOutput, CacheTaskStartedOutput, CrateMetadata, ErrorOutput,
-        GetCratesMetadataOutput, ListCachedCratesOutput, ListCrateVersionsOutput,
-        RemoveCrateOutput, SizeInfo, VersionInfo,
+        CacheCrateOutput, CacheTaskStartedOutput, ClearAllCachesOutput, CrateMetadata,
+        EnsureReadyOutput, ErrorOutput, GetCratesMetadataOutput, ListCachedCratesOutput,
+        ListCrateVersionsOutput, PruneIndexesOutput, ReindexOutput, RemoveCrateOutput,
+        ServerInfoOutput, SizeInfo, VersionInfo,
     },
+    source::{GitReference, SourceType},
+    storage::READ_ONLY_ERROR,
     task_formatter,
     task_manager::{CachingStage, TaskManager, TaskStatus},
     utils::format_bytes,
+    watchdog::CacheSizeGauge,
 };
+use crate::config::ServiceConfig;
+use crate::rustdoc;
+use crate::timeout::ToolMetrics;
+
+/// Workspace members to cache: a list of member paths or a keyword such as "default"
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum MembersSelection {
+    Keyword(String),
+    Paths(Vec<String>),
+}
+
+/// Deserialize `members` from either a list of paths or a single keyword
+///
+/// A keyword is kept as a one-element list so it can be expanded once the
+/// workspace manifest is available.
+fn deserialize_members<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(
+        Option::<MembersSelection>::deserialize(deserializer)?.map(|selection| match selection {
+            MembersSelection::Keyword(keyword) => vec![keyword],
+            MembersSelection::Paths(paths) => paths,
+        }),
+    )
+}
 
 /// Parameters for the unified cache_crate tool
 ///
@@ -24,10 +57,13 @@ use crate::cache::{
 /// and the `source_type` field determines which fields are required.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CacheCrateParams {
-    #[schemars(description = "The name of the crate")]
+    #[schemars(
+        description = "The name of the crate. Optional for source_type='local', where it is read from Cargo.toml; required for local workspaces, which are cached under this name"
+    )]
+    #[serde(default)]
     pub crate_name: String,
 
-    #[schemars(description = "Source type: must be 'cratesio', 'github', or 'local'")]
+    #[schemars(description = "Source type: must be 'cratesio', 'github', 'local', or 'archive'")]
     pub source_type: String,
 
     // CratesIO parameters
@@ -42,29 +78,85 @@ pub struct CacheCrateParams {
     )]
     pub github_url: Option<String>,
     #[schemars(
-        description = "Branch name (REQUIRED for source_type='github' if tag not provided, e.g., 'main', 'develop')"
+        description = "Branch name for source_type='github' (e.g., 'main', 'develop'). If neither branch nor tag is given, the repository's default branch is detected and used"
     )]
     pub branch: Option<String>,
     #[schemars(
-        description = "Tag name (REQUIRED for source_type='github' if branch not provided, e.g., 'v1.0.0', '0.2.1')"
+        description = "Tag name for source_type='github' (e.g., 'v1.0.0', '0.2.1'). Only one of branch or tag can be specified"
     )]
     pub tag: Option<String>,
 
-    // Local parameters
+    // Local and archive parameters
     #[schemars(
-        description = "Local file system path (REQUIRED for source_type='local', supports absolute paths (/path), home paths (~/path), and relative paths (./path, ../path))"
+        description = "Local file system path (REQUIRED for source_type='local' and source_type='archive', supports absolute paths (/path), home paths (~/path), and relative paths (./path, ../path)). For 'archive', the path of a .crate, .tar.gz or .tgz file."
     )]
     pub path: Option<String>,
 
     // Common parameters
     #[schemars(
-        description = "Optional list of workspace members to cache. If the crate is a workspace and this is not provided, the tool will return a list of available members. Specify member paths relative to the workspace root (e.g., [\"crates/rmcp\", \"crates/rmcp-macros\"])."
+        description = "Optional list of workspace members to cache. If the crate is a workspace and this is not provided, the tool will return a list of available members. Specify member paths relative to the workspace root (e.g., [\"crates/rmcp\", \"crates/rmcp-macros\"]), or \"default\" to cache the workspace's default-members (all members if none are declared).",
+        with = "Option<MembersSelection>"
+    )]
+    #[serde(default, deserialize_with = "deserialize_members")]
+    pub members: Option<Vec<String>>,
+    #[schemars(
+        description = "Force re-download and re-cache the crate even if it already exists. Defaults to false. The existing cache is preserved until the update succeeds."
+    )]
+    pub update: Option<bool>,
+    #[schemars(
+        description = "Optional target triple to also document the crate for (e.g., 'x86_64-pc-windows-msvc'), exposing platform-specific APIs. The target must be installed with rustup. Query the docs by passing the same 'target' to list_crate_items, search_items, get_item_details, etc."
+    )]
+    pub target: Option<String>,
+    #[schemars(
+        description = "Optional module paths to restrict the cached docs to (e.g., ['regex::bytes'] or ['bytes']). After the full docs are generated, only the items under these modules, the local items their signatures use, and the modules leading to them are kept. Makes queries on very large crates cheaper; items outside the scope are no longer found. Re-cache with update: true to change the scope."
+    )]
+    pub scope: Option<Vec<String>>,
+    #[schemars(
+        description = "With scope, also keep the unfiltered docs next to the scoped ones (docs.full.json in the cache directory). Defaults to false."
+    )]
+    pub keep_full_docs: Option<bool>,
+}
+
+impl CacheCrateParams {
+    /// The source the request fetches from, or `None` for an unknown source type
+    fn requested_source(&self) -> Option<SourceType> {
+        let path = || self.path.clone().unwrap_or_default();
+        match self.source_type.as_str() {
+            "cratesio" => Some(SourceType::CratesIo),
+            "github" => Some(SourceType::GitHub {
+                url: self.github_url.clone().unwrap_or_default(),
+                repo_path: None,
+                reference: GitReference::Default,
+            }),
+            "local" => Some(SourceType::Local { path: path() }),
+            "archive" => Some(SourceType::Archive { path: path() }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CacheLatestParams {
+    #[schemars(description = "The name of the crate on crates.io")]
+    pub crate_name: String,
+    #[schemars(
+        description = "Consider pre-release versions (e.g., '2.0.0-beta.1') when picking the latest version. Defaults to false."
     )]
+    pub allow_prerelease: Option<bool>,
+    #[schemars(
+        description = "Optional list of workspace members to cache. If the crate is a workspace and this is not provided, the tool will return a list of available members. Specify member paths relative to the workspace root (e.g., [\"crates/rmcp\", \"crates/rmcp-macros\"]), or \"default\" to cache the workspace's default-members (all members if none are declared).",
+        with = "Option<MembersSelection>"
+    )]
+    #[serde(default, deserialize_with = "deserialize_members")]
     pub members: Option<Vec<String>>,
     #[schemars(
         description = "Force re-download and re-cache the crate even if it already exists. Defaults to false. The existing cache is preserved until the update succeeds."
     )]
     pub update: Option<bool>,
+    #[schemars(
+        description = "Optional target triple to also document the crate for (e.g., 'x86_64-pc-windows-msvc'). Query the docs by passing the same 'target' to the documentation tools."
+    )]
+    pub target: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -74,8 +166,10 @@ pub struct CacheCrateFromCratesIOParams {
     #[schemars(description = "The version of the crate")]
     pub version: String,
     #[schemars(
-        description = "Optional list of workspace members to cache. If the crate is a workspace and this is not provided, the tool will return a list of available members. Specify member paths relative to the workspace root (e.g., [\"crates/rmcp\", \"crates/rmcp-macros\"])."
+        description = "Optional list of workspace members to cache. If the crate is a workspace and this is not provided, the tool will return a list of available members. Specify member paths relative to the workspace root (e.g., [\"crates/rmcp\", \"crates/rmcp-macros\"]), or \"default\" to cache the workspace's default-members (all members if none are declared).",
+        with = "Option<MembersSelection>"
     )]
+    #[serde(default, deserialize_with = "deserialize_members")]
     pub members: Option<Vec<String>>,
     #[schemars(
         description = "Force re-download and re-cache the crate even if it already exists. Defaults to false. The existing cache is preserved until the update succeeds."
@@ -90,7 +184,7 @@ pub struct CacheCrateFromGitHubParams {
     #[schemars(description = "GitHub repository URL (e.g., https://github.com/user/repo)")]
     pub github_url: String,
     #[schemars(
-        description = "Branch to use (e.g., 'main', 'develop'). Only one of branch or tag can be specified."
+        description = "Branch to use (e.g., 'main', 'develop'). Only one of branch or tag can be specified; the default branch is used if neither is."
     )]
     pub branch: Option<String>,
     #[schemars(
@@ -98,8 +192,10 @@ pub struct CacheCrateFromGitHubParams {
     )]
     pub tag: Option<String>,
     #[schemars(
-        description = "Optional list of workspace members to cache. If the crate is a workspace and this is not provided, the tool will return a list of available members. Specify member paths relative to the workspace root (e.g., [\"crates/rmcp\", \"crates/rmcp-macros\"])."
+        description = "Optional list of workspace members to cache. If the crate is a workspace and this is not provided, the tool will return a list of available members. Specify member paths relative to the workspace root (e.g., [\"crates/rmcp\", \"crates/rmcp-macros\"]), or \"default\" to cache the workspace's default-members (all members if none are declared).",
+        with = "Option<MembersSelection>"
     )]
+    #[serde(default, deserialize_with = "deserialize_members")]
     pub members: Option<Vec<String>>,
     #[schemars(
         description = "Force re-download and re-cache the crate even if it already exists. Defaults to false. The existing cache is preserved until the update succeeds."
@@ -120,8 +216,10 @@ pub struct CacheCrateFromLocalParams {
     )]
     pub path: String,
     #[schemars(
-        description = "Optional list of workspace members to cache. If the crate is a workspace and this is not provided, the tool will return a list of available members. Specify member paths relative to the workspace root (e.g., [\"crates/rmcp\", \"crates/rmcp-macros\"])."
+        description = "Optional list of workspace members to cache. If the crate is a workspace and this is not provided, the tool will return a list of available members. Specify member paths relative to the workspace root (e.g., [\"crates/rmcp\", \"crates/rmcp-macros\"]), or \"default\" to cache the workspace's default-members (all members if none are declared).",
+        with = "Option<MembersSelection>"
     )]
+    #[serde(default, deserialize_with = "deserialize_members")]
     pub members: Option<Vec<String>>,
     #[schemars(
         description = "Force re-download and re-cache the crate even if it already exists. Defaults to false. The existing cache is preserved until the update succeeds."
@@ -129,6 +227,24 @@ pub struct CacheCrateFromLocalParams {
     pub update: Option<bool>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CacheCrateFromArchiveParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+    #[schemars(
+        description = "Optional version to use for caching. If not provided, the version from the Cargo.toml inside the archive will be used. If provided, it will be validated against the actual version."
+    )]
+    pub version: Option<String>,
+    #[schemars(
+        description = "Path of a .crate, .tar.gz or .tgz file, e.g. a vendored dependency or a file from a private registry"
+    )]
+    pub path: String,
+    #[schemars(
+        description = "Force re-download and re-cache the crate even if it already exists. Defaults to false. The existing cache is preserved until the update succeeds."
+    )]
+    pub update: Option<bool>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CrateMetadataQuery {
     #[schemars(description = "The name of the crate")]
@@ -155,6 +271,30 @@ pub struct RemoveCrateParams {
     pub version: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EnsureReadyParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+    #[schemars(description = "The version of the crate")]
+    pub version: String,
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ClearAllCachesParams {
+    #[schemars(
+        description = "Must be set to true to confirm that every cached crate should be removed"
+    )]
+    #[serde(
+        default,
+        deserialize_with = "crate::util::deserialize_bool_from_anything"
+    )]
+    pub confirm: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ListCrateVersionsParams {
     #[schemars(description = "The name of the crate")]
@@ -183,12 +323,20 @@ pub struct CacheOperationsParams {
     )]
     #[serde(default)]
     pub clear: bool,
+
+    #[schemars(
+        description = "Set to true to start failed tasks again with the parameters they were started with (retries the specified task or all failed tasks). Each retried task is replaced by a new one"
+    )]
+    #[serde(default)]
+    pub retry_failed: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct CacheTools {
     cache: Arc<RwLock<CrateCache>>,
     task_manager: Arc<TaskManager>,
+    cache_size: CacheSizeGauge,
+    tool_metrics: ToolMetrics,
 }
 
 impl CacheTools {
@@ -197,9 +345,31 @@ impl CacheTools {
         Self {
             cache,
             task_manager,
+            cache_size: CacheSizeGauge::default(),
+            tool_metrics: ToolMetrics::default(),
         }
     }
 
+    /// Report the cache size measured by the watchdog updating `gauge`
+    pub fn with_cache_size_gauge(mut self, gauge: CacheSizeGauge) -> Self {
+        self.cache_size = gauge;
+        self
+    }
+
+    /// Report the calls counted by `metrics` in server_info
+    pub fn with_tool_metrics(mut self, metrics: ToolMetrics) -> Self {
+        self.tool_metrics = metrics;
+        self
+    }
+
+    /// Refuse to modify the cache when the server is read-only
+    async fn ensure_not_read_only(&self) -> Result<(), ErrorOutput> {
+        if self.cache.read().await.is_read_only() {
+            return Err(ErrorOutput::new(READ_ONLY_ERROR));
+        }
+        Ok(())
+    }
+
     pub async fn cache_crate_from_cratesio(
         &self,
         params: CacheCrateFromCratesIOParams,
@@ -216,19 +386,11 @@ impl CacheTools {
         &self,
         params: CacheCrateFromGitHubParams,
     ) -> CacheCrateOutput {
-        // Validate that only one of branch or tag is provided
-        match (&params.branch, &params.tag) {
-            (Some(_), Some(_)) => {
-                return CacheCrateOutput::Error {
-                    error: "Only one of 'branch' or 'tag' can be specified, not both".to_string(),
-                };
-            }
-            (None, None) => {
-                return CacheCrateOutput::Error {
-                    error: "Either 'branch' or 'tag' must be specified".to_string(),
-                };
-            }
-            _ => {} // Valid: exactly one is provided
+        // Validate that at most one of branch or tag is provided, the default branch is used otherwise
+        if params.branch.is_some() && params.tag.is_some() {
+            return CacheCrateOutput::Error {
+                error: "Only one of 'branch' or 'tag' can be specified, not both".to_string(),
+            };
         }
 
         let cache = self.cache.write().await;
@@ -251,10 +413,23 @@ impl CacheTools {
         })
     }
 
+    pub async fn cache_crate_from_archive(
+        &self,
+        params: CacheCrateFromArchiveParams,
+    ) -> CacheCrateOutput {
+        let cache = self.cache.write().await;
+        let source = CrateSource::Archive(params);
+        let json_response = cache.cache_crate_with_source(source, None, None).await;
+        serde_json::from_str(&json_response).unwrap_or_else(|_| CacheCrateOutput::Error {
+            error: "Failed to parse cache response".to_string(),
+        })
+    }
+
     pub async fn remove_crate(
         &self,
         params: RemoveCrateParams,
     ) -> Result<RemoveCrateOutput, ErrorOutput> {
+        self.ensure_not_read_only().await?;
         let cache = self.cache.write().await;
         match cache
             .remove_crate(&params.crate_name, &params.version)
@@ -273,6 +448,199 @@ impl CacheTools {
         }
     }
 
+    pub async fn clear_all_caches(
+        &self,
+        params: ClearAllCachesParams,
+    ) -> Result<ClearAllCachesOutput, ErrorOutput> {
+        self.ensure_not_read_only().await?;
+        if !params.confirm {
+            return Err(ErrorOutput::new(
+                "Refusing to clear all caches without confirmation. Call again with confirm: true.",
+            ));
+        }
+
+        // Don't pull the cache out from under a running caching task
+        let active = self
+            .task_manager
+            .list_tasks(None)
+            .await
+            .into_iter()
+            .filter(|task| !task.is_terminal())
+            .count();
+        if active > 0 {
+            return Err(ErrorOutput::new(format!(
+                "Cannot clear caches while {active} caching task(s) are running. Cancel them or wait for completion."
+            )));
+        }
+
+        let cache = self.cache.write().await;
+        let crates_removed = match cache.storage.list_cached_crates() {
+            Ok(crates) => crates.len(),
+            Err(_) => 0,
+        };
+
+        match cache.clear_all_caches().await {
+            Ok(bytes) => Ok(ClearAllCachesOutput {
+                status: "success".to_string(),
+                message: format!(
+                    "Removed {crates_removed} cached crate version(s), reclaiming {}",
+                    format_bytes(bytes)
+                ),
+                crates_removed,
+                reclaimed: SizeInfo {
+                    bytes,
+                    human: format_bytes(bytes),
+                },
+            }),
+            Err(e) => Err(ErrorOutput::new(format!("Failed to clear caches: {e}"))),
+        }
+    }
+
+    /// Rebuild the search index of every cached crate and workspace member
+    ///
+    /// Only the indexes are recreated, from the docs already in the cache.
+    pub async fn reindex(&self) -> Result<ReindexOutput, ErrorOutput> {
+        self.ensure_not_read_only().await?;
+        // A caching task may be writing docs or an index we are about to rebuild
+        let active = self
+            .task_manager
+            .list_tasks(None)
+            .await
+            .into_iter()
+            .filter(|task| !task.is_terminal())
+            .count();
+        if active > 0 {
+            return Err(ErrorOutput::new(format!(
+                "Cannot reindex while {active} caching task(s) are running. Cancel them or wait for completion."
+            )));
+        }
+
+        let cache = self.cache.write().await;
+        match cache
+            .reindex_all(self.task_manager.max_concurrent_tasks())
+            .await
+        {
+            Ok((rebuilt, failed)) => Ok(ReindexOutput {
+                status: if failed.is_empty() {
+                    "success".to_string()
+                } else {
+                    "partial_success".to_string()
+                },
+                message: format!(
+                    "Rebuilt {rebuilt} search index(es), {} failed",
+                    failed.len()
+                ),
+                rebuilt,
+                failed,
+            }),
+            Err(e) => Err(ErrorOutput::new(format!(
+                "Failed to rebuild search indexes: {e}"
+            ))),
+        }
+    }
+
+    /// Remove search indexes whose crate or member documentation is no longer cached
+    pub async fn prune_indexes(&self) -> Result<PruneIndexesOutput, ErrorOutput> {
+        self.ensure_not_read_only().await?;
+        // A caching task writes its docs before creating the index
+        let active = self
+            .task_manager
+            .list_tasks(None)
+            .await
+            .into_iter()
+            .filter(|task| !task.is_terminal())
+            .count();
+        if active > 0 {
+            return Err(ErrorOutput::new(format!(
+                "Cannot prune indexes while {active} caching task(s) are running. Cancel them or wait for completion."
+            )));
+        }
+
+        let cache = self.cache.write().await;
+        match cache.prune_indexes().await {
+            Ok(removed) => {
+                let bytes = removed.iter().map(|index| index.bytes).sum();
+                Ok(PruneIndexesOutput {
+                    status: "success".to_string(),
+                    message: format!(
+                        "Removed {} orphaned search index(es), reclaiming {}",
+                        removed.len(),
+                        format_bytes(bytes)
+                    ),
+                    removed,
+                    reclaimed: SizeInfo {
+                        bytes,
+                        human: format_bytes(bytes),
+                    },
+                })
+            }
+            Err(e) => Err(ErrorOutput::new(format!(
+                "Failed to prune search indexes: {e}"
+            ))),
+        }
+    }
+
+    /// Report the server's version, cache location and runtime configuration
+    pub async fn server_info(
+        &self,
+        config: &ServiceConfig,
+    ) -> Result<ServerInfoOutput, ErrorOutput> {
+        let active_tasks = self
+            .task_manager
+            .list_tasks(None)
+            .await
+            .into_iter()
+            .filter(|task| !task.is_terminal())
+            .count();
+
+        let cache = self.cache.read().await;
+        let crates = cache
+            .list_all_cached_crates()
+            .await
+            .map_err(|e| ErrorOutput::new(format!("Failed to list cached crates: {e}")))?;
+        let cached_crates = crates
+            .iter()
+            .map(|meta| meta.name.as_str())
+            .collect::<std::collections::HashSet<_>>()
+            .len();
+
+        Ok(ServerInfoOutput {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            protocol_version: rmcp::model::ProtocolVersion::LATEST.to_string(),
+            cache_dir: cache.cache_dir().display().to_string(),
+            cache_writable: cache.storage.is_writable(),
+            read_only: config.read_only,
+            cache_permissions: config.cache_permissions.map(|mode| format!("{mode:04o}")),
+            cached_crates,
+            cached_versions: crates.len(),
+            active_tasks,
+            docgen_toolchain: config.docgen_toolchain.clone(),
+            rustdoc_format_version: rustdoc_types::FORMAT_VERSION,
+            rustdoc_args: config.rustdoc_args.clone(),
+            registry_download_url: config.registry.download_template().to_string(),
+            registry_index_url: config.registry.index_url().to_string(),
+            docsrs_url: config.registry.docsrs_url().to_string(),
+            git_depth: config.git_depth,
+            keep_git_history: config.keep_git_history,
+            allowed_sources: config.source_policy.allowed_sources(),
+            eviction_policy: config.eviction_policy.to_string(),
+            evict_local_crates: config.evict_local,
+            max_cache_bytes: config.max_cache_bytes,
+            cache_size_bytes: self.cache_size.get(),
+            docgen_failures: cache.docgen_failures().snapshot(),
+            tool_calls: self.tool_metrics.snapshot(),
+            query_timeout_secs: config.query_timeout.as_secs(),
+            cache_timeout_secs: config.cache_timeout.as_secs(),
+            max_concurrent_tasks: config.max_concurrent_tasks,
+            max_response_bytes: config.max_response_bytes,
+            search_time_budget_ms: config
+                .search_time_budget
+                .map(|budget| budget.as_millis() as u64),
+            auto_regenerate_incompatible: config.auto_regenerate_incompatible,
+        })
+    }
+
     pub async fn list_cached_crates(&self) -> Result<ListCachedCratesOutput, ErrorOutput> {
         let cache = self.cache.read().await;
         match cache.list_all_cached_crates().await {
@@ -306,6 +674,11 @@ impl CacheTools {
                         size_bytes: crate_meta.size_bytes,
                         size_human: format_bytes(crate_meta.size_bytes),
                         members,
+                        yanked: crate_meta.yanked,
+                        last_accessed: cache
+                            .storage
+                            .last_accessed(&crate_name, &version, None)
+                            .map(|time| time.to_string()),
                     };
 
                     grouped.entry(crate_name).or_default().push(version_info);
@@ -327,6 +700,38 @@ impl CacheTools {
         }
     }
 
+    /// Make sure the docs of a crate version are generated, without returning them
+    ///
+    /// Downloads and documents the crate if needed, like the first query on it
+    /// would. The parsed docs are discarded.
+    pub async fn ensure_ready(&self, params: EnsureReadyParams) -> EnsureReadyOutput {
+        let start = Instant::now();
+        let cache = self.cache.write().await;
+        let member = params.member.as_deref();
+        let had_docs = cache
+            .storage
+            .has_docs(&params.crate_name, &params.version, member);
+        let result = cache
+            .ensure_crate_or_member_docs(&params.crate_name, &params.version, member)
+            .await;
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+
+        match result {
+            Ok(_) => EnsureReadyOutput {
+                ready: true,
+                generated: !had_docs,
+                elapsed_ms,
+                error: None,
+            },
+            Err(e) => EnsureReadyOutput {
+                ready: false,
+                generated: false,
+                elapsed_ms,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
     pub async fn list_crate_versions(
         &self,
         params: ListCrateVersionsParams,
@@ -349,6 +754,10 @@ impl CacheTools {
                             Ok(members) if !members.is_empty() => Some(members),
                             _ => None,
                         };
+                        let last_accessed = cache
+                            .storage
+                            .last_accessed(&meta.name, &meta.version, None)
+                            .map(|time| time.to_string());
 
                         VersionInfo {
                             version: meta.version,
@@ -357,6 +766,8 @@ impl CacheTools {
                             size_bytes: meta.size_bytes,
                             size_human: format_bytes(meta.size_bytes),
                             members,
+                            yanked: meta.yanked,
+                            last_accessed,
                         }
                     })
                     .collect();
@@ -407,9 +818,11 @@ impl CacheTools {
                             cache_size_human: Some(format_bytes(metadata.size_bytes)),
                             member: None,
                             workspace_members: None,
+                            yanked: Some(metadata.yanked),
+                            error: None,
                         }
                     }
-                    Err(_) => CrateMetadata {
+                    Err(e) => CrateMetadata {
                         crate_name: crate_name.clone(),
                         version: version.clone(),
                         cached: true,
@@ -418,6 +831,8 @@ impl CacheTools {
                         cache_size_human: None,
                         member: None,
                         workspace_members: None,
+                        yanked: None,
+                        error: Some(format!("Failed to load metadata: {e:#}")),
                     },
                 };
                 metadata_list.push(main_metadata);
@@ -431,6 +846,8 @@ impl CacheTools {
                     cache_size_human: None,
                     member: None,
                     workspace_members: None,
+                    yanked: None,
+                    error: None,
                 });
             }
 
@@ -463,9 +880,11 @@ impl CacheTools {
                                     cache_size_human: Some(format_bytes(metadata.size_bytes)),
                                     member: Some(member_path),
                                     workspace_members: None,
+                                    yanked: None,
+                                    error: None,
                                 }
                             }
-                            Err(_) => CrateMetadata {
+                            Err(e) => CrateMetadata {
                                 crate_name: crate_name.clone(),
                                 version: version.clone(),
                                 cached: true,
@@ -474,6 +893,8 @@ impl CacheTools {
                                 cache_size_human: None,
                                 member: Some(member_path),
                                 workspace_members: None,
+                                yanked: None,
+                                error: Some(format!("Failed to load metadata: {e:#}")),
                             },
                         };
                         metadata_list.push(member_metadata);
@@ -487,6 +908,8 @@ impl CacheTools {
                             cache_size_human: None,
                             member: Some(member_path),
                             workspace_members: None,
+                            yanked: None,
+                            error: None,
                         });
                     }
                 }
@@ -500,16 +923,8 @@ impl CacheTools {
         }
     }
 
-    /// Resolve version from local Cargo.toml synchronously
-    ///
-    /// Returns `(version, auto_detected)` tuple or error message.
-    /// This helper reads the Cargo.toml to get the real version before creating a task,
-    /// ensuring task metadata is accurate from the start.
-    fn resolve_local_version(
-        path: &str,
-        provided_version: Option<&str>,
-    ) -> Result<(String, bool), String> {
-        use crate::cache::workspace::WorkspaceHandler;
+    /// The Cargo.toml of a local crate path, expanding `~` and checking it exists
+    fn local_manifest(path: &str) -> Result<std::path::PathBuf, String> {
         use std::path::Path;
 
         // Expand path (handles ~ and relative paths)
@@ -534,6 +949,50 @@ impl CacheTools {
                 local_path.display()
             ));
         }
+        Ok(cargo_toml)
+    }
+
+    /// Resolve the crate name from local Cargo.toml synchronously
+    ///
+    /// Returns `(name, auto_detected)` tuple or error message. An empty
+    /// `provided_name` is read from the package manifest; a workspace has no
+    /// package name, so its name must be provided to key the cache.
+    fn resolve_local_name(path: &str, provided_name: &str) -> Result<(String, bool), String> {
+        use crate::cache::workspace::WorkspaceHandler;
+
+        let cargo_toml = Self::local_manifest(path)?;
+        let provided_name = provided_name.trim();
+
+        match WorkspaceHandler::is_workspace(&cargo_toml) {
+            Ok(true) if provided_name.is_empty() => Err(format!(
+                "The path '{path}' contains a workspace manifest. Please provide a crate_name to cache it under."
+            )),
+            Ok(true) => Ok((provided_name.to_string(), false)),
+            Ok(false) => match WorkspaceHandler::get_package_name(&cargo_toml) {
+                Ok(actual_name) if provided_name.is_empty() => Ok((actual_name, true)),
+                Ok(actual_name) if actual_name != provided_name => Err(format!(
+                    "Name mismatch: provided '{provided_name}' does not match package name '{actual_name}' in Cargo.toml"
+                )),
+                Ok(actual_name) => Ok((actual_name, false)),
+                Err(e) => Err(format!("Failed to read package name from Cargo.toml: {e}")),
+            },
+            Err(e) => Err(format!("Failed to check workspace status: {e}")),
+        }
+    }
+
+    /// Resolve version from local Cargo.toml synchronously
+    ///
+    /// Returns `(version, auto_detected)` tuple or error message.
+    /// This helper reads the Cargo.toml to get the real version before creating a task,
+    /// ensuring task metadata is accurate from the start.
+    fn resolve_local_version(
+        path: &str,
+        provided_version: Option<&str>,
+    ) -> Result<(String, bool), String> {
+        use crate::cache::workspace::WorkspaceHandler;
+
+        let cargo_toml = Self::local_manifest(path)?;
+        let local_path = cargo_toml.parent().unwrap_or(&cargo_toml);
 
         // Check if workspace
         match WorkspaceHandler::is_workspace(&cargo_toml) {
@@ -574,15 +1033,41 @@ impl CacheTools {
     /// Unified cache_crate method that accepts all source types
     ///
     /// Validates parameters, spawns async task, and returns immediately with task ID.
-    /// Returns JSON-formatted [`CacheTaskStartedOutput`] for structured monitoring.
-    pub async fn cache_crate(&self, params: CacheCrateParams) -> String {
+    /// Returns JSON-formatted [`CacheTaskStartedOutput`] for structured monitoring, or
+    /// a markdown error message as `Err`.
+    pub async fn cache_crate(&self, mut params: CacheCrateParams) -> Result<String, String> {
+        if let Err(error) = self.ensure_not_read_only().await {
+            return Err(format!("# Error\n\n{}", error.error));
+        }
+
+        // Only local crates can name themselves, from their Cargo.toml
+        if params.crate_name.trim().is_empty() && params.source_type != "local" {
+            return Err(format!(
+                "# Error\n\nMissing required parameter 'crate_name' for source_type='{}'",
+                params.source_type
+            ));
+        }
+
+        if let Some(target) = &params.target
+            && let Err(e) = rustdoc::validate_target_triple(target)
+        {
+            return Err(format!("# Error\n\n{e}"));
+        }
+
+        // Refuse sources the policy does not allow before touching them
+        if let Some(source) = params.requested_source()
+            && let Err(e) = self.cache.read().await.source_policy().check(&source)
+        {
+            return Err(format!("# Error\n\n{e}"));
+        }
+
         // Validate and extract source details for task creation
         let (crate_name, version, source_details) = match params.source_type.as_str() {
             "cratesio" => {
                 let version = match &params.version {
                     Some(v) => v.clone(),
                     None => {
-                        return "# Error\n\nMissing required parameter 'version' for source_type='cratesio'".to_string();
+                        return Err("# Error\n\nMissing required parameter 'version' for source_type='cratesio'".to_string());
                     }
                 };
                 (params.crate_name.clone(), version, None)
@@ -591,17 +1076,27 @@ impl CacheTools {
                 let github_url = match &params.github_url {
                     Some(url) => url.clone(),
                     None => {
-                        return "# Error\n\nMissing required parameter 'github_url' for source_type='github'".to_string();
+                        return Err("# Error\n\nMissing required parameter 'github_url' for source_type='github'".to_string());
                     }
                 };
 
+                // Resolve the default branch before creating the task, so it is cached under that name
+                let mut branch_detected = false;
                 match (&params.branch, &params.tag) {
                     (Some(_), Some(_)) => {
-                        return "# Error\n\nOnly one of 'branch' or 'tag' can be specified for source_type='github', not both".to_string();
-                    }
-                    (None, None) => {
-                        return "# Error\n\nEither 'branch' or 'tag' must be specified for source_type='github'".to_string();
+                        return Err("# Error\n\nOnly one of 'branch' or 'tag' can be specified for source_type='github', not both".to_string());
                     }
+                    (None, None) => match resolve_default_branch(&github_url).await {
+                        Ok(branch) => {
+                            params.branch = Some(branch);
+                            branch_detected = true;
+                        }
+                        Err(e) => {
+                            return Err(format!(
+                                "# Error\n\nNo branch or tag given and the default branch could not be detected: {e:#}"
+                            ));
+                        }
+                    },
                     _ => {}
                 }
 
@@ -610,34 +1105,72 @@ impl CacheTools {
                     .clone()
                     .or_else(|| params.tag.clone())
                     .unwrap();
-                let ref_type = if params.branch.is_some() {
-                    "branch"
-                } else {
-                    "tag"
+                let details = match (&params.branch, branch_detected) {
+                    (Some(_), true) => format!("{github_url}, branch: {version} (default branch)"),
+                    (Some(_), false) => format!("{github_url}, branch: {version}"),
+                    (None, _) => format!("{github_url}, tag: {version}"),
                 };
-                let details = format!("{github_url}, {ref_type}: {version}");
                 (params.crate_name.clone(), version, Some(details))
             }
             "local" => {
                 let path = match &params.path {
                     Some(p) => p.clone(),
                     None => {
-                        return "# Error\n\nMissing required parameter 'path' for source_type='local'".to_string();
+                        return Err(
+                            "# Error\n\nMissing required parameter 'path' for source_type='local'"
+                                .to_string(),
+                        );
                     }
                 };
 
+                let (crate_name, name_detected) =
+                    match Self::resolve_local_name(&path, &params.crate_name) {
+                        Ok(result) => result,
+                        Err(error_msg) => {
+                            return Err(format!("# Error\n\n{error_msg}"));
+                        }
+                    };
+                params.crate_name = crate_name;
+
                 // Resolve version synchronously before creating task (fixes bug #2)
                 let (version, auto_detected) =
                     match Self::resolve_local_version(&path, params.version.as_deref()) {
                         Ok(result) => result,
                         Err(error_msg) => {
-                            return format!("# Error\n\n{error_msg}");
+                            return Err(format!("# Error\n\n{error_msg}"));
                         }
                     };
 
                 // Add auto-detection note to source details
+                let details = match (name_detected, auto_detected) {
+                    (true, true) => {
+                        format!("{path} (name and version auto-detected from Cargo.toml)")
+                    }
+                    (true, false) => format!("{path} (name auto-detected from Cargo.toml)"),
+                    (false, true) => format!("{path} (version auto-detected from Cargo.toml)"),
+                    (false, false) => path,
+                };
+
+                (params.crate_name.clone(), version, Some(details))
+            }
+            "archive" => {
+                let path = match &params.path {
+                    Some(p) => p.clone(),
+                    None => {
+                        return Err("# Error\n\nMissing required parameter 'path' for source_type='archive'".to_string());
+                    }
+                };
+
+                let (version, auto_detected) =
+                    match resolve_archive_version(&path, params.version.as_deref()) {
+                        Ok(result) => result,
+                        Err(e) => {
+                            return Err(format!("# Error\n\n{e}"));
+                        }
+                    };
+
                 let details = if auto_detected {
-                    format!("{path} (version auto-detected from Cargo.toml)")
+                    format!("{path} (version auto-detected from the archive's Cargo.toml)")
                 } else {
                     path
                 };
@@ -645,28 +1178,51 @@ impl CacheTools {
                 (params.crate_name.clone(), version, Some(details))
             }
             _ => {
-                return format!(
-                    "# Error\n\nInvalid source_type '{}'. Must be one of: 'cratesio', 'github', 'local'",
+                return Err(format!(
+                    "# Error\n\nInvalid source_type '{}'. Must be one of: 'cratesio', 'github', 'local', 'archive'",
                     params.source_type
-                );
+                ));
             }
         };
 
-        // Create task
-        let task = self
+        // Create task, or join the one already caching the same crate
+        let (task, created) = self
             .task_manager
-            .create_task(
+            .find_or_create_task(
                 crate_name,
                 version,
                 params.source_type.clone(),
                 source_details,
+                Some(params.clone()),
             )
             .await;
+        if !created {
+            return Ok(CacheTaskStartedOutput {
+                task_id: task.task_id.clone(),
+                crate_name: task.crate_name.clone(),
+                version: task.version.clone(),
+                source_type: task.source_type.clone(),
+                source_details: task.source_details.clone(),
+                status: task.status.as_str().to_string(),
+                message: format!(
+                    "A caching task for {}-{} from the same source is already running; returning its task id. Use cache_operations to monitor progress.",
+                    task.crate_name, task.version
+                ),
+                existing_task: true,
+            }
+            .to_json());
+        }
 
-        // Update status to InProgress before returning (fixes race condition bug #1)
-        self.task_manager
-            .update_status(&task.task_id, TaskStatus::InProgress)
-            .await;
+        // Take a concurrency slot if one is free; otherwise the task stays queued as Pending
+        let permit = self.task_manager.try_acquire_permit();
+        let queued = permit.is_none();
+
+        if !queued {
+            // Update status to InProgress before returning (fixes race condition bug #1)
+            self.task_manager
+                .update_status(&task.task_id, TaskStatus::InProgress)
+                .await;
+        }
 
         // Spawn background task
         let cache = self.cache.clone();
@@ -674,8 +1230,19 @@ impl CacheTools {
         let task_id = task.task_id.clone();
         let cancellation_token = task.cancellation_token.clone();
         let params = params.clone(); // Clone params for the spawned task
+        let version = task.version.clone();
 
         tokio::spawn(async move {
+            // Wait for a free slot before downloading; the permit is held until the task ends
+            let _permit = match permit {
+                Some(permit) => permit,
+                None => tokio::select! {
+                    permit = task_manager.acquire_permit() => permit,
+                    // cancel_task already marked the task as cancelled
+                    _ = cancellation_token.cancelled() => return,
+                },
+            };
+
             // Build CrateSource from params
             let crate_source = Self::params_to_source(&params);
 
@@ -705,6 +1272,69 @@ impl CacheTools {
                 .await;
             drop(cache_guard); // Release lock
 
+            // Document the requested target platform once the host docs are cached
+            let json_response = match &params.target {
+                Some(target) => {
+                    Self::cache_target_docs(
+                        &cache,
+                        &task_manager,
+                        &task_id,
+                        &params.crate_name,
+                        &version,
+                        target,
+                        json_response,
+                    )
+                    .await
+                }
+                None => json_response,
+            };
+
+            // Restrict the cached docs to the requested modules
+            let json_response = match params.scope.as_deref() {
+                Some(scope) if !scope.is_empty() => {
+                    Self::cache_scoped_docs(
+                        &cache,
+                        &task_manager,
+                        &task_id,
+                        &params,
+                        &version,
+                        json_response,
+                    )
+                    .await
+                }
+                _ => json_response,
+            };
+
+            // Downloads and rustdoc write files with the process umask
+            if let Ok(
+                CacheCrateOutput::Success {
+                    crate_name,
+                    version,
+                    ..
+                }
+                | CacheCrateOutput::PartialSuccess {
+                    crate_name,
+                    version,
+                    ..
+                }
+                | CacheCrateOutput::WorkspaceDetected {
+                    crate_name,
+                    version,
+                    ..
+                },
+            ) = serde_json::from_str::<CacheCrateOutput>(&json_response)
+            {
+                let cache_guard = cache.read().await;
+                if let Err(e) = cache_guard
+                    .storage
+                    .restrict_crate_permissions(&crate_name, &version)
+                {
+                    tracing::warn!(
+                        "Failed to apply cache permissions to {crate_name}-{version}: {e}"
+                    );
+                }
+            }
+
             // Check for cancellation after caching
             if cancellation_token.is_cancelled() {
                 task_manager
@@ -749,13 +1379,202 @@ impl CacheTools {
             version: task.version.clone(),
             source_type: task.source_type.clone(),
             source_details: task.source_details.clone(),
-            status: "in_progress".to_string(),
-            message: format!(
-                "Caching task started for {}-{}. Use cache_operations to monitor progress.",
-                task.crate_name, task.version
-            ),
+            status: if queued { "pending" } else { "in_progress" }.to_string(),
+            message: if queued {
+                format!(
+                    "Caching task queued for {}-{}: all {} caching slots are busy. Use cache_operations to monitor progress.",
+                    task.crate_name,
+                    task.version,
+                    self.task_manager.max_concurrent_tasks()
+                )
+            } else {
+                format!(
+                    "Caching task started for {}-{}. Use cache_operations to monitor progress.",
+                    task.crate_name, task.version
+                )
+            },
+            existing_task: false,
         };
-        output.to_json()
+        Ok(output.to_json())
+    }
+
+    /// Resolve the newest published version of a crate and cache it
+    pub async fn cache_latest(&self, params: CacheLatestParams) -> Result<String, String> {
+        if let Err(error) = self.ensure_not_read_only().await {
+            return Err(format!("# Error\n\n{}", error.error));
+        }
+
+        let versions = {
+            let cache = self.cache.read().await;
+            cache.get_available_versions(&params.crate_name).await
+        };
+        let versions = match versions {
+            Ok(versions) => versions,
+            Err(e) => {
+                return Err(format!(
+                    "# Error\n\nFailed to look up versions of '{}' on crates.io: {e}",
+                    params.crate_name
+                ));
+            }
+        };
+
+        let allow_prerelease = params.allow_prerelease.unwrap_or(false);
+        let Some(version) = downloader::latest_version(&versions, allow_prerelease) else {
+            let kind = if allow_prerelease {
+                "non-yanked"
+            } else {
+                "non-yanked, non-prerelease"
+            };
+            return Err(format!(
+                "# Error\n\nNo {kind} version of '{}' is published on crates.io",
+                params.crate_name
+            ));
+        };
+
+        let response = self
+            .cache_crate(CacheCrateParams {
+                crate_name: params.crate_name,
+                source_type: "cratesio".to_string(),
+                version: Some(version.clone()),
+                github_url: None,
+                branch: None,
+                tag: None,
+                path: None,
+                members: params.members,
+                update: params.update,
+                target: params.target,
+                scope: None,
+                keep_full_docs: None,
+            })
+            .await?;
+
+        match serde_json::from_str::<CacheTaskStartedOutput>(&response) {
+            Ok(mut output) => {
+                output.message = format!(
+                    "Resolved latest version of {} to {version}. {}",
+                    output.crate_name, output.message
+                );
+                Ok(output.to_json())
+            }
+            Err(_) => Ok(response),
+        }
+    }
+
+    /// Generate docs for `target` for everything a successful caching run produced
+    ///
+    /// Returns the original response, or an error response if documenting for the
+    /// target failed.
+    async fn cache_target_docs(
+        cache: &RwLock<CrateCache>,
+        task_manager: &TaskManager,
+        task_id: &str,
+        crate_name: &str,
+        version: &str,
+        target: &str,
+        json_response: String,
+    ) -> String {
+        let members = match serde_json::from_str::<CacheCrateOutput>(&json_response) {
+            Ok(CacheCrateOutput::Success { members, .. }) => members,
+            Ok(CacheCrateOutput::PartialSuccess { members, .. }) => Some(members),
+            _ => return json_response,
+        };
+
+        task_manager
+            .update_stage(task_id, CachingStage::GeneratingDocs)
+            .await;
+        task_manager
+            .update_step(
+                task_id,
+                1,
+                format!("Running cargo rustdoc for target {target}"),
+            )
+            .await;
+
+        let cache_guard = cache.write().await;
+        let members: Vec<Option<&str>> = match &members {
+            Some(members) => members.iter().map(|m| Some(m.as_str())).collect(),
+            None => vec![None],
+        };
+        for member in members {
+            // Skip workspace members whose host docs failed to generate
+            if !cache_guard.has_docs(crate_name, version, member) {
+                continue;
+            }
+
+            if let Err(e) = cache_guard
+                .ensure_crate_or_member_target_docs(crate_name, version, member, target)
+                .await
+            {
+                let name = match member {
+                    Some(member) => format!("{crate_name}-{version} (member: {member})"),
+                    None => format!("{crate_name}-{version}"),
+                };
+                return CacheCrateOutput::Error {
+                    error: format!(
+                        "Cached {name}, but documenting it for target {target} failed: {e}"
+                    ),
+                }
+                .to_json();
+            }
+        }
+
+        json_response
+    }
+
+    /// Restrict the docs of everything a successful caching run produced to the requested scope
+    ///
+    /// Returns the original response, or an error response if scoping failed.
+    async fn cache_scoped_docs(
+        cache: &RwLock<CrateCache>,
+        task_manager: &TaskManager,
+        task_id: &str,
+        params: &CacheCrateParams,
+        version: &str,
+        json_response: String,
+    ) -> String {
+        let crate_name = params.crate_name.as_str();
+        let scope = params.scope.as_deref().unwrap_or_default();
+        let keep_full = params.keep_full_docs.unwrap_or(false);
+        let members = match serde_json::from_str::<CacheCrateOutput>(&json_response) {
+            Ok(CacheCrateOutput::Success { members, .. }) => members,
+            Ok(CacheCrateOutput::PartialSuccess { members, .. }) => Some(members),
+            _ => return json_response,
+        };
+
+        task_manager
+            .update_stage(task_id, CachingStage::Indexing)
+            .await;
+        task_manager
+            .update_step(task_id, 1, format!("Scoping docs to {}", scope.join(", ")))
+            .await;
+
+        let cache_guard = cache.write().await;
+        let members: Vec<Option<&str>> = match &members {
+            Some(members) => members.iter().map(|m| Some(m.as_str())).collect(),
+            None => vec![None],
+        };
+        for member in members {
+            // Skip workspace members whose docs failed to generate
+            if !cache_guard.has_docs(crate_name, version, member) {
+                continue;
+            }
+
+            if let Err(e) = cache_guard
+                .scope_docs(crate_name, version, member, scope, keep_full)
+                .await
+            {
+                let name = match member {
+                    Some(member) => format!("{crate_name}-{version} (member: {member})"),
+                    None => format!("{crate_name}-{version}"),
+                };
+                return CacheCrateOutput::Error {
+                    error: format!("Cached {name}, but scoping its docs failed: {e}"),
+                }
+                .to_json();
+            }
+        }
+
+        json_response
     }
 
     /// Helper to convert CacheCrateParams to CrateSource
@@ -782,23 +1601,80 @@ impl CacheTools {
                 members: params.members.clone(),
                 update: params.update,
             }),
+            "archive" => CrateSource::Archive(CacheCrateFromArchiveParams {
+                crate_name: params.crate_name.clone(),
+                version: params.version.clone(),
+                path: params.path.clone().unwrap(),
+                update: params.update,
+            }),
             _ => unreachable!("Invalid source type should have been caught earlier"),
         }
     }
 
+    /// Start failed tasks again with the parameters they were started with
+    ///
+    /// Retries the task `task_id`, or every failed task. A retried task is
+    /// replaced by the new one, so retrying twice does not start it twice.
+    async fn retry_failed_tasks(&self, task_id: Option<&str>) -> Result<String, String> {
+        let mut failed = match task_id {
+            Some(task_id) => match self.task_manager.get_task(task_id).await {
+                Some(task) if task.status == TaskStatus::Failed => vec![task],
+                Some(task) => {
+                    return Err(format!(
+                        "# Error\n\nCannot retry task `{task_id}` because it is {}. Only failed tasks can be retried.",
+                        task.status.as_str()
+                    ));
+                }
+                None => return Err(format!("# Error\n\nTask `{task_id}` not found.")),
+            },
+            None => {
+                self.task_manager
+                    .list_tasks(Some(&TaskStatus::Failed))
+                    .await
+            }
+        };
+        // Retry in the order the tasks were first started
+        failed.reverse();
+
+        let mut retried = Vec::new();
+        let mut not_retried = Vec::new();
+        for task in failed {
+            let Some(request) = task.request.clone() else {
+                not_retried.push((task, "Its parameters were not recorded".to_string()));
+                continue;
+            };
+            match self.cache_crate(request).await {
+                Ok(response) => match serde_json::from_str::<CacheTaskStartedOutput>(&response) {
+                    Ok(started) => {
+                        self.task_manager.remove_task(&task.task_id).await;
+                        retried.push((task, started.task_id));
+                    }
+                    Err(_) => not_retried.push((task, response)),
+                },
+                Err(response) => {
+                    let error = response.trim_start_matches("# Error\n\n").to_string();
+                    not_retried.push((task, error));
+                }
+            }
+        }
+
+        Ok(task_formatter::format_retry_result(retried, not_retried))
+    }
+
     /// Unified cache_operations method for managing and monitoring caching tasks
     ///
-    /// Returns markdown-formatted text optimized for LLM consumption
-    pub async fn cache_operations(&self, params: CacheOperationsParams) -> String {
+    /// Returns markdown-formatted text optimized for LLM consumption, as `Err` when the
+    /// operation failed
+    pub async fn cache_operations(&self, params: CacheOperationsParams) -> Result<String, String> {
         // Handle cancel action
         if params.cancel {
             let Some(task_id) = &params.task_id else {
-                return "# Error\n\nCannot cancel without specifying a task_id.".to_string();
+                return Err("# Error\n\nCannot cancel without specifying a task_id.".to_string());
             };
 
             return match self.task_manager.cancel_task(task_id).await {
-                Some(task) => task_formatter::format_cancel_result(&task),
-                None => format!("# Error\n\nTask `{task_id}` not found."),
+                Some(task) => Ok(task_formatter::format_cancel_result(&task)),
+                None => Err(format!("# Error\n\nTask `{task_id}` not found.")),
             };
         }
 
@@ -809,26 +1685,31 @@ impl CacheTools {
                 match self.task_manager.get_task(task_id).await {
                     Some(task) if task.is_terminal() => {
                         self.task_manager.remove_task(task_id).await;
-                        task_formatter::format_clear_result(vec![task])
+                        Ok(task_formatter::format_clear_result(vec![task]))
                     }
-                    Some(_) => format!(
+                    Some(_) => Err(format!(
                         "# Error\n\nCannot clear task `{task_id}` because it is still in progress. Cancel it first or wait for completion."
-                    ),
-                    None => format!("# Error\n\nTask `{task_id}` not found."),
+                    )),
+                    None => Err(format!("# Error\n\nTask `{task_id}` not found.")),
                 }
             } else {
                 // Clear all terminal tasks
                 let cleared = self.task_manager.clear_terminal_tasks().await;
-                task_formatter::format_clear_result(cleared)
+                Ok(task_formatter::format_clear_result(cleared))
             };
         }
 
+        // Handle retry action
+        if params.retry_failed {
+            return self.retry_failed_tasks(params.task_id.as_deref()).await;
+        }
+
         // Handle query operations
         if let Some(task_id) = &params.task_id {
             // Get specific task
             match self.task_manager.get_task(task_id).await {
-                Some(task) => task_formatter::format_single_task(&task),
-                None => format!("# Error\n\nTask `{task_id}` not found."),
+                Some(task) => Ok(task_formatter::format_single_task(&task)),
+                None => Err(format!("# Error\n\nTask `{task_id}` not found.")),
             }
         } else {
             // List all tasks with optional filter
@@ -845,7 +1726,69 @@ impl CacheTools {
                 });
 
             let tasks = self.task_manager.list_tasks(status_filter.as_ref()).await;
-            task_formatter::format_task_list(tasks)
+            Ok(task_formatter::format_task_list(tasks))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use tempfile::TempDir;
+
+    fn create_test_tools(temp_dir: &TempDir) -> Result<CacheTools> {
+        let cache = CrateCache::new(Some(temp_dir.path().to_path_buf()))?;
+        Ok(CacheTools::new(
+            Arc::new(RwLock::new(cache)),
+            Arc::new(TaskManager::new()),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_get_crates_metadata_reports_corrupt_metadata() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let tools = create_test_tools(&temp_dir)?;
+
+        // A cached crate whose metadata file cannot be parsed
+        {
+            let cache = tools.cache.read().await;
+            let metadata_path = cache.storage.metadata_path("broken", "1.0.0", None)?;
+            cache.storage.ensure_dir(metadata_path.parent().unwrap())?;
+            std::fs::write(&metadata_path, "{ not json")?;
+        }
+
+        let output = tools
+            .get_crates_metadata(GetCratesMetadataParams {
+                queries: vec![
+                    CrateMetadataQuery {
+                        crate_name: "broken".to_string(),
+                        version: "1.0.0".to_string(),
+                        members: None,
+                    },
+                    CrateMetadataQuery {
+                        crate_name: "missing".to_string(),
+                        version: "1.0.0".to_string(),
+                        members: None,
+                    },
+                ],
+            })
+            .await;
+
+        let broken = &output.metadata[0];
+        assert!(broken.cached);
+        assert!(!broken.analyzed);
+        assert!(
+            broken
+                .error
+                .as_deref()
+                .is_some_and(|e| e.contains("Failed to load metadata"))
+        );
+
+        // Crates that simply aren't cached carry no error
+        let missing = &output.metadata[1];
+        assert!(!missing.cached);
+        assert!(missing.error.is_none());
+        Ok(())
+    }
+}
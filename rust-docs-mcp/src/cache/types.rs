@@ -9,6 +9,10 @@ use std::fmt;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
+use crate::cache::constants::{
+    HIDDEN_DOCS_FILE, HIDDEN_TARGET_DIR, PRIVATE_DOCS_FILE, PRIVATE_TARGET_DIR,
+};
+
 /// Validate that a crate name is safe for use in file paths
 fn validate_crate_name(name: &str) -> Result<()> {
     // Check for path traversal attempts
@@ -151,6 +155,44 @@ impl AsRef<Path> for MemberPath {
     }
 }
 
+/// Documentation generated on demand with more items than the regular docs
+///
+/// Each variant is cached in its own file next to the regular docs, and shares
+/// their dependency information and search index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocsVariant {
+    /// Also documents private items, with `--document-private-items`
+    Private,
+    /// Also documents `#[doc(hidden)]` items, with `--document-hidden-items`
+    Hidden,
+}
+
+impl DocsVariant {
+    /// Name of the cached documentation file
+    pub fn docs_file(self) -> &'static str {
+        match self {
+            Self::Private => PRIVATE_DOCS_FILE,
+            Self::Hidden => HIDDEN_DOCS_FILE,
+        }
+    }
+
+    /// Name of the cargo target directory the variant is built in
+    pub fn target_dir(self) -> &'static str {
+        match self {
+            Self::Private => PRIVATE_TARGET_DIR,
+            Self::Hidden => HIDDEN_TARGET_DIR,
+        }
+    }
+
+    /// The extra items, as in "documentation with private items"
+    pub fn items(self) -> &'static str {
+        match self {
+            Self::Private => "private items",
+            Self::Hidden => "hidden items",
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
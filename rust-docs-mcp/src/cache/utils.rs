@@ -3,16 +3,86 @@
 //! This module contains shared utilities used across the cache implementation,
 //! including file operations, error handling, and response formatting.
 
+use super::downloader::ProgressCallback;
 use super::outputs::CacheCrateOutput;
 use anyhow::{Context, Result, bail};
+use std::ffi::OsStr;
 use std::fs;
 use std::path::Path;
 
+/// Number of copied files between two progress reports
+const COPY_PROGRESS_INTERVAL: u64 = 50;
+
 /// Recursively copy directory contents from source to destination
 ///
 /// This function copies all files and subdirectories from the source path to the destination,
 /// excluding version control directories like .git, .svn, and .hg.
 pub fn copy_directory_contents(src: &Path, dest: &Path) -> Result<()> {
+    copy_directory_recursive(src, dest, &mut || {})
+}
+
+/// Recursively copy directory contents, reporting progress as a percentage of files copied
+///
+/// The files are counted up front, then the callback is invoked every
+/// `COPY_PROGRESS_INTERVAL` files and once more when the copy completes.
+pub fn copy_directory_contents_with_progress(
+    src: &Path,
+    dest: &Path,
+    progress_callback: Option<&ProgressCallback>,
+) -> Result<()> {
+    let Some(callback) = progress_callback else {
+        return copy_directory_contents(src, dest);
+    };
+
+    let total_files = count_files(src)?;
+    let mut copied_files = 0u64;
+    copy_directory_recursive(src, dest, &mut || {
+        copied_files += 1;
+        if copied_files % COPY_PROGRESS_INTERVAL == 0 || copied_files == total_files {
+            let percent = ((copied_files * 100) / total_files).min(100) as u8;
+            callback(percent);
+        }
+    })?;
+
+    // Empty directories never trigger the per-file report
+    if total_files == 0 {
+        callback(100);
+    }
+
+    Ok(())
+}
+
+/// Count the files `copy_directory_contents` would copy from `src`
+pub fn count_files(src: &Path) -> Result<u64> {
+    let mut count = 0;
+    for entry in
+        fs::read_dir(src).with_context(|| format!("Failed to read directory: {}", src.display()))?
+    {
+        let entry = entry?;
+        let path = entry.path();
+
+        if path.is_dir() {
+            if !is_skipped_dir(&entry.file_name()) {
+                count += count_files(&path)?;
+            }
+        } else {
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+/// Version control directories and the target directory are never copied
+fn is_skipped_dir(name: &OsStr) -> bool {
+    name == ".git" || name == ".svn" || name == ".hg" || name == "target"
+}
+
+fn copy_directory_recursive(
+    src: &Path,
+    dest: &Path,
+    on_file_copied: &mut dyn FnMut(),
+) -> Result<()> {
     if !src.exists() {
         bail!("Source directory does not exist: {}", src.display());
     }
@@ -32,10 +102,10 @@ pub fn copy_directory_contents(src: &Path, dest: &Path) -> Result<()> {
 
         if path.is_dir() {
             // Skip version control directories and target directory
-            if name == ".git" || name == ".svn" || name == ".hg" || name == "target" {
+            if is_skipped_dir(&name) {
                 continue;
             }
-            copy_directory_contents(&path, &dest_path)?;
+            copy_directory_recursive(&path, &dest_path, on_file_copied)?;
         } else {
             fs::copy(&path, &dest_path).with_context(|| {
                 format!(
@@ -44,6 +114,7 @@ pub fn copy_directory_contents(src: &Path, dest: &Path) -> Result<()> {
                     dest_path.display()
                 )
             })?;
+            on_file_copied();
         }
     }
 
@@ -86,6 +157,8 @@ impl CacheResponse {
             members: None,
             results: None,
             updated: None,
+            yanked: None,
+            doc_source: None,
         }
     }
 
@@ -100,7 +173,30 @@ impl CacheResponse {
             members: None,
             results: None,
             updated: Some(true),
+            yanked: None,
+            doc_source: None,
+        }
+    }
+
+    /// Flag a success response for a version that has been yanked from crates.io
+    pub fn with_yanked(mut self, is_yanked: bool) -> Self {
+        if is_yanked
+            && let Self::Success {
+                message, yanked, ..
+            } = &mut self
+        {
+            message.push_str(". Warning: this version has been yanked from crates.io");
+            *yanked = Some(true);
         }
+        self
+    }
+
+    /// Report where the documentation of a success response came from
+    pub fn with_doc_source(mut self, source: impl Into<String>) -> Self {
+        if let Self::Success { doc_source, .. } = &mut self {
+            *doc_source = Some(source.into());
+        }
+        self
     }
 
     /// Create a workspace members success response
@@ -125,6 +221,8 @@ impl CacheResponse {
             members: Some(members),
             results: Some(results),
             updated: if updated { Some(true) } else { None },
+            yanked: None,
+            doc_source: None,
         }
     }
 
@@ -175,7 +273,7 @@ impl CacheResponse {
         let example_members = members.get(0..2.min(members.len())).unwrap_or(&[]).to_vec();
 
         Self::WorkspaceDetected {
-            message: "This is a workspace crate. Please specify which members to cache using the 'members' parameter.".to_string(),
+            message: "This is a workspace crate. Please specify which members to cache using the 'members' parameter, or set members to \"default\" to cache the workspace's default members.".to_string(),
             crate_name: crate_name.clone(),
             version: version.clone(),
             workspace_members: members,
@@ -240,6 +338,44 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_copy_directory_contents_reports_progress() -> Result<()> {
+        use std::sync::{Arc, Mutex};
+
+        let temp_dir = TempDir::new()?;
+        let src_dir = temp_dir.path().join("src");
+        let dest_dir = temp_dir.path().join("dest");
+
+        fs::create_dir_all(src_dir.join("nested"))?;
+        for i in 0..120 {
+            fs::write(src_dir.join(format!("file{i}.rs")), "content")?;
+            fs::write(
+                src_dir.join("nested").join(format!("file{i}.rs")),
+                "content",
+            )?;
+        }
+        fs::create_dir_all(src_dir.join("target"))?;
+        fs::write(src_dir.join("target").join("artifact"), "skipped")?;
+
+        assert_eq!(count_files(&src_dir)?, 240);
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let reports_clone = Arc::clone(&reports);
+        let callback: ProgressCallback = Arc::new(move |percent| {
+            reports_clone.lock().unwrap().push(percent);
+        });
+
+        copy_directory_contents_with_progress(&src_dir, &dest_dir, Some(&callback))?;
+
+        let reports = reports.lock().unwrap();
+        // Every 50 files out of 240, plus the final report
+        assert_eq!(*reports, vec![20, 41, 62, 83, 100]);
+        assert!(dest_dir.join("nested/file119.rs").exists());
+        assert!(!dest_dir.join("target").exists());
+
+        Ok(())
+    }
+
     #[test]
     fn test_cache_response() {
         // Test success response
@@ -250,6 +386,18 @@ mod tests {
         assert_eq!(json["message"], "Successfully cached test-crate-1.0.0");
         assert_eq!(json["crate"], "test-crate");
         assert_eq!(json["version"], "1.0.0");
+        assert!(json.get("yanked").is_none());
+
+        // Test yanked success response
+        let yanked = CacheResponse::success("test-crate", "1.0.0").with_yanked(true);
+        let json: serde_json::Value = serde_json::from_str(&yanked.to_json()).unwrap();
+        assert_eq!(json["yanked"], true);
+        assert!(json["message"].as_str().unwrap().contains("yanked"));
+
+        // Test success response reporting its documentation source
+        let docsrs = CacheResponse::success("test-crate", "1.0.0").with_doc_source("docsrs");
+        let json: serde_json::Value = serde_json::from_str(&docsrs.to_json()).unwrap();
+        assert_eq!(json["doc_source"], "docsrs");
 
         // Test error response
         let error = CacheResponse::error("Something went wrong");
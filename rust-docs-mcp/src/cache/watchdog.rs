@@ -0,0 +1,237 @@
+//! Background watchdog keeping the cache within its size budget
+//!
+//! When a budget is configured with `--max-cache-bytes`, the watchdog wakes up
+//! every `--cache-watchdog-interval` seconds, measures the crates in the cache
+//! and records the total in the `rust_docs_mcp_cache_size_bytes` gauge. If the
+//! total exceeds the budget, it evicts crate versions with the configured
+//! eviction policy until the cache is back under the low-water mark, so the
+//! cache is not trimmed again by the next few crates cached.
+
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+use crate::cache::CrateCache;
+use crate::cache::constants::CRATES_DIR;
+use crate::cache::eviction::EvictionCandidate;
+
+/// Name of the gauge holding the size of the cached crates
+pub const CACHE_SIZE_METRIC: &str = "rust_docs_mcp_cache_size_bytes";
+
+/// Default interval between two checks of the cache size, in seconds
+pub const DEFAULT_WATCHDOG_INTERVAL_SECS: u64 = 300;
+
+/// Default low-water mark, as a percentage of the budget
+pub const DEFAULT_LOW_WATER_PERCENT: u8 = 80;
+
+/// Gauge of the cache size in bytes, shared between the watchdog and readers
+#[derive(Debug, Clone, Default)]
+pub struct CacheSizeGauge {
+    /// Size in bytes plus one, zero until the first measurement
+    value: Arc<AtomicU64>,
+}
+
+impl CacheSizeGauge {
+    pub fn name(&self) -> &'static str {
+        CACHE_SIZE_METRIC
+    }
+
+    /// The last measured size, or `None` before the watchdog first ran
+    pub fn get(&self) -> Option<u64> {
+        self.value.load(Ordering::Relaxed).checked_sub(1)
+    }
+
+    fn set(&self, bytes: u64) {
+        self.value.store(bytes.saturating_add(1), Ordering::Relaxed);
+    }
+}
+
+/// Size the cache may grow to, and the size eviction brings it back down to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheBudget {
+    pub max_bytes: u64,
+    pub low_water_bytes: u64,
+}
+
+impl CacheBudget {
+    /// A budget of `max_bytes`, evicting down to `low_water_percent` of it
+    pub fn new(max_bytes: u64, low_water_percent: u8) -> Self {
+        let percent = u64::from(low_water_percent.min(100));
+        Self {
+            max_bytes,
+            low_water_bytes: (u128::from(max_bytes) * u128::from(percent) / 100) as u64,
+        }
+    }
+}
+
+/// Result of one check of the cache size
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchdogReport {
+    /// Size of the cached crates before eviction
+    pub size_bytes: u64,
+    /// Versions evicted to get back under the low-water mark
+    pub evicted: Vec<EvictionCandidate>,
+}
+
+/// Periodically measures the cache and evicts crates when it exceeds its budget
+#[derive(Debug, Clone)]
+pub struct CacheWatchdog {
+    cache: Arc<RwLock<CrateCache>>,
+    budget: CacheBudget,
+    interval: Duration,
+    gauge: CacheSizeGauge,
+}
+
+impl CacheWatchdog {
+    pub fn new(cache: Arc<RwLock<CrateCache>>, budget: CacheBudget, interval: Duration) -> Self {
+        Self {
+            cache,
+            budget,
+            interval,
+            gauge: CacheSizeGauge::default(),
+        }
+    }
+
+    /// Gauge updated with the cache size on every check
+    pub fn gauge(&self) -> CacheSizeGauge {
+        self.gauge.clone()
+    }
+
+    /// Measure the cache, update the gauge and evict crates if it is over budget
+    ///
+    /// A read-only cache is measured but never evicted from. The cache is
+    /// measured without holding its lock, so tools keep running meanwhile;
+    /// only eviction takes the write lock.
+    pub async fn check(&self) -> Result<WatchdogReport> {
+        let (storage, read_only) = {
+            let cache = self.cache.read().await;
+            (cache.storage.clone(), cache.is_read_only())
+        };
+        let size_bytes = tokio::task::spawn_blocking(move || {
+            storage.calculate_dir_size(&storage.cache_dir().join(CRATES_DIR))
+        })
+        .await
+        .context("Measuring the cache size panicked")??;
+
+        let mut evicted = Vec::new();
+        if size_bytes > self.budget.max_bytes && !read_only {
+            let cache = self.cache.write().await;
+            let bytes_to_free = size_bytes - self.budget.low_water_bytes;
+            tracing::info!(
+                "Cache size {} bytes exceeds the budget of {} bytes, evicting {} bytes",
+                size_bytes,
+                self.budget.max_bytes,
+                bytes_to_free
+            );
+            evicted = cache.evict(bytes_to_free).await?;
+        }
+
+        let freed: u64 = evicted.iter().map(|victim| victim.size_bytes).sum();
+        self.gauge.set(size_bytes.saturating_sub(freed));
+        Ok(WatchdogReport {
+            size_bytes,
+            evicted,
+        })
+    }
+
+    /// Run [`Self::check`] every interval in a background task, the first time right away
+    ///
+    /// Must be called from within a Tokio runtime. The task stops when the
+    /// returned guard is dropped.
+    pub fn spawn(self) -> Result<WatchdogGuard> {
+        let runtime = tokio::runtime::Handle::try_current()
+            .context("The cache watchdog needs a Tokio runtime")?;
+        let handle = runtime.spawn(async move {
+            let mut interval = tokio::time::interval(self.interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.check().await {
+                    tracing::warn!("Cache watchdog check failed: {}", e);
+                }
+            }
+        });
+        Ok(WatchdogGuard(handle))
+    }
+}
+
+/// Stops the watchdog task when dropped
+#[derive(Debug)]
+pub struct WatchdogGuard(JoinHandle<()>);
+
+impl Drop for WatchdogGuard {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_budget_low_water_mark() {
+        assert_eq!(CacheBudget::new(1000, 80).low_water_bytes, 800);
+        assert_eq!(CacheBudget::new(1000, 150).low_water_bytes, 1000);
+        assert_eq!(CacheBudget::new(u64::MAX, 100).low_water_bytes, u64::MAX);
+    }
+
+    #[tokio::test]
+    async fn test_watchdog_evicts_over_budget() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cache = CrateCache::new(Some(temp_dir.path().to_path_buf()))?;
+        for (name, source) in [("a", "crates.io"), ("b", "crates.io"), ("c", "local")] {
+            let source_path = cache.storage.source_path(name, "1.0.0")?;
+            std::fs::create_dir_all(&source_path)?;
+            std::fs::write(source_path.join("lib.rs"), "pub fn f() {}\n")?;
+            cache
+                .storage
+                .save_metadata_with_source(name, "1.0.0", source, None, None)?;
+        }
+        let cache = Arc::new(RwLock::new(cache));
+
+        // A generous budget only measures the cache
+        let watchdog = CacheWatchdog::new(
+            cache.clone(),
+            CacheBudget::new(u64::MAX, 80),
+            Duration::from_secs(60),
+        );
+        assert_eq!(watchdog.gauge().get(), None);
+        // Measuring does not wait for tools holding the cache
+        let report = {
+            let _reader = cache.read().await;
+            tokio::time::timeout(Duration::from_secs(5), watchdog.check())
+                .await
+                .context("check should not need the write lock to measure")??
+        };
+        assert!(report.size_bytes > 0);
+        assert!(report.evicted.is_empty());
+        assert_eq!(watchdog.gauge().get(), Some(report.size_bytes));
+
+        // A tiny budget evicts everything but the exempt local crate
+        let watchdog = CacheWatchdog::new(
+            cache.clone(),
+            CacheBudget::new(1, 0),
+            Duration::from_secs(60),
+        );
+        let report = watchdog.check().await?;
+        let mut names: Vec<_> = report
+            .evicted
+            .iter()
+            .map(|victim| victim.name.as_str())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["a", "b"]);
+        assert!(watchdog.gauge().get().unwrap() < report.size_bytes);
+
+        let cache = cache.read().await;
+        assert!(!cache.storage.crate_path("a", "1.0.0")?.exists());
+        assert!(cache.storage.crate_path("c", "1.0.0")?.exists());
+
+        Ok(())
+    }
+}
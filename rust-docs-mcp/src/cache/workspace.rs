@@ -79,6 +79,39 @@ impl WorkspaceHandler {
         Ok(member_list)
     }
 
+    /// Get the default members of a workspace from a workspace Cargo.toml
+    ///
+    /// Returns `workspace.default-members` when declared, falling back to all
+    /// workspace members otherwise.
+    pub fn get_default_members(cargo_toml_path: &Path) -> Result<Vec<String>> {
+        let content = fs::read_to_string(cargo_toml_path).with_context(|| {
+            format!("Failed to read Cargo.toml at {}", cargo_toml_path.display())
+        })?;
+
+        let parsed: Value = toml::from_str(&content).with_context(|| {
+            format!(
+                "Failed to parse Cargo.toml at {}",
+                cargo_toml_path.display()
+            )
+        })?;
+
+        let default_members = parsed
+            .get("workspace")
+            .and_then(|w| w.get("default-members"))
+            .and_then(|m| m.as_array());
+
+        match default_members {
+            Some(default_members) => Ok(default_members
+                .iter()
+                .filter_map(|m| m.as_str())
+                // Glob patterns are not expanded, matching get_workspace_members
+                .filter(|m| !m.contains('*'))
+                .map(str::to_string)
+                .collect()),
+            None => Self::get_workspace_members(cargo_toml_path),
+        }
+    }
+
     /// Get the package name from a Cargo.toml file
     pub fn get_package_name(cargo_toml_path: &Path) -> Result<String> {
         let content = fs::read_to_string(cargo_toml_path).with_context(|| {
@@ -104,6 +137,33 @@ impl WorkspaceHandler {
         Ok(name.to_string())
     }
 
+    /// Get the name of the library target from a Cargo.toml file
+    ///
+    /// Uses `[lib] name` when set, and the package name with hyphens replaced by
+    /// underscores otherwise, matching the name rustdoc gives its output.
+    pub fn get_lib_name(cargo_toml_path: &Path) -> Result<String> {
+        let content = fs::read_to_string(cargo_toml_path).with_context(|| {
+            format!("Failed to read Cargo.toml at {}", cargo_toml_path.display())
+        })?;
+
+        let parsed: Value = toml::from_str(&content).with_context(|| {
+            format!(
+                "Failed to parse Cargo.toml at {}",
+                cargo_toml_path.display()
+            )
+        })?;
+
+        if let Some(name) = parsed
+            .get("lib")
+            .and_then(|lib| lib.get("name"))
+            .and_then(|n| n.as_str())
+        {
+            return Ok(name.replace('-', "_"));
+        }
+
+        Ok(Self::get_package_name(cargo_toml_path)?.replace('-', "_"))
+    }
+
     /// Get the package version from a Cargo.toml file
     pub fn get_package_version(cargo_toml_path: &Path) -> Result<String> {
         let content = fs::read_to_string(cargo_toml_path).with_context(|| {
@@ -175,6 +235,38 @@ name = "test-crate"
         Ok(())
     }
 
+    #[test]
+    fn test_get_lib_name() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+
+        let renamed_toml = temp_dir.path().join("renamed.toml");
+        fs::write(
+            &renamed_toml,
+            r#"
+[package]
+name = "foo"
+version = "0.1.0"
+
+[lib]
+name = "foo_core"
+"#,
+        )?;
+        assert_eq!(WorkspaceHandler::get_lib_name(&renamed_toml)?, "foo_core");
+
+        let default_toml = temp_dir.path().join("default.toml");
+        fs::write(
+            &default_toml,
+            r#"
+[package]
+name = "foo-bar"
+version = "0.1.0"
+"#,
+        )?;
+        assert_eq!(WorkspaceHandler::get_lib_name(&default_toml)?, "foo_bar");
+
+        Ok(())
+    }
+
     #[test]
     fn test_workspace_detection() -> Result<()> {
         let temp_dir = TempDir::new()?;
@@ -219,4 +311,39 @@ members = ["sub-crate"]
 
         Ok(())
     }
+
+    #[test]
+    fn test_get_default_members() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+
+        let with_defaults = temp_dir.path().join("defaults.toml");
+        fs::write(
+            &with_defaults,
+            r#"
+[workspace]
+members = ["crates/core", "crates/cli", "crates/bench"]
+default-members = ["crates/core", "crates/cli"]
+"#,
+        )?;
+        assert_eq!(
+            WorkspaceHandler::get_default_members(&with_defaults)?,
+            vec!["crates/core".to_string(), "crates/cli".to_string()]
+        );
+
+        // Without default-members, all members are returned
+        let without_defaults = temp_dir.path().join("no-defaults.toml");
+        fs::write(
+            &without_defaults,
+            r#"
+[workspace]
+members = ["crates/core", "crates/cli"]
+"#,
+        )?;
+        assert_eq!(
+            WorkspaceHandler::get_default_members(&without_defaults)?,
+            WorkspaceHandler::get_workspace_members(&without_defaults)?
+        );
+
+        Ok(())
+    }
 }
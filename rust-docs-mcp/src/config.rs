@@ -0,0 +1,247 @@
+//! Runtime configuration for the MCP service
+//!
+//! Holds the settings that can be tuned from the command line or a
+//! configuration file and are shared by the tool implementations.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::cache::CrateCache;
+use crate::cache::downloader::DEFAULT_GIT_DEPTH;
+use crate::cache::eviction::EvictionPolicy;
+use crate::cache::policy::SourcePolicy;
+use crate::cache::registry::Registry;
+use crate::cache::task_manager::default_max_concurrent_tasks;
+use crate::cache::watchdog::{DEFAULT_LOW_WATER_PERCENT, DEFAULT_WATCHDOG_INTERVAL_SECS};
+use crate::format::ResponseFormat;
+use crate::rustdoc::REQUIRED_TOOLCHAIN;
+
+/// Default timeout for read-only query tools, in seconds
+pub const DEFAULT_QUERY_TIMEOUT_SECS: u64 = 60;
+
+/// Default timeout for tools that modify the cache, in seconds
+pub const DEFAULT_CACHE_TIMEOUT_SECS: u64 = 300;
+
+/// Default maximum size of a tool response, in bytes (roughly 25k tokens * 4 bytes/token)
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 100_000;
+
+/// Default time a name search may scan a crate's items for, in milliseconds
+pub const DEFAULT_SEARCH_TIME_BUDGET_MS: u64 = 2_000;
+
+/// Configuration for [`crate::RustDocsService`]
+#[derive(Debug, Clone)]
+pub struct ServiceConfig {
+    /// Timeout applied to read-only query tools
+    pub query_timeout: Duration,
+    /// Timeout applied to tools that modify the cache
+    pub cache_timeout: Duration,
+    /// Maximum number of caching tasks running at the same time
+    pub max_concurrent_tasks: usize,
+    /// Maximum size of a tool response in bytes before results are truncated
+    pub max_response_bytes: usize,
+    /// Time search_items may scan items for before returning partial results, if limited
+    pub search_time_budget: Option<Duration>,
+    /// Rustup toolchain used to generate rustdoc JSON
+    pub docgen_toolchain: String,
+    /// Regenerate cached docs with an incompatible rustdoc JSON format on load
+    pub auto_regenerate_incompatible: bool,
+    /// Extra arguments passed to rustdoc, such as `--cfg docsrs`
+    pub rustdoc_args: Vec<String>,
+    /// Registry crates.io crates are downloaded from, e.g. a mirror
+    pub registry: Registry,
+    /// Number of commits fetched when cloning git repositories, zero for the full history
+    pub git_depth: u32,
+    /// Keep the `.git` directory of cloned repositories in the cache
+    pub keep_git_history: bool,
+    /// Sources crates may be cached from
+    pub source_policy: SourcePolicy,
+    /// Policy choosing which cached crates to evict first
+    pub eviction_policy: EvictionPolicy,
+    /// Size the cached crates may take up before the watchdog evicts some, if limited
+    pub max_cache_bytes: Option<u64>,
+    /// Percentage of `max_cache_bytes` the watchdog evicts down to
+    pub cache_low_water_percent: u8,
+    /// Interval between two checks of the cache size by the watchdog
+    pub cache_watchdog_interval: Duration,
+    /// Whether crates cached from local paths may be evicted
+    pub evict_local: bool,
+    /// Only serve the already cached crates, refusing every modification of the cache
+    pub read_only: bool,
+    /// Unix mode given to cache directories, e.g. `0o700`, if any
+    pub cache_permissions: Option<u32>,
+    /// File every tool call is logged to as a JSON line, if any
+    pub access_log: Option<PathBuf>,
+    /// Format of tool results that support several, unless a call asks for another
+    pub default_format: ResponseFormat,
+}
+
+impl Default for ServiceConfig {
+    fn default() -> Self {
+        Self {
+            query_timeout: Duration::from_secs(DEFAULT_QUERY_TIMEOUT_SECS),
+            cache_timeout: Duration::from_secs(DEFAULT_CACHE_TIMEOUT_SECS),
+            max_concurrent_tasks: default_max_concurrent_tasks(),
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            search_time_budget: Some(Duration::from_millis(DEFAULT_SEARCH_TIME_BUDGET_MS)),
+            docgen_toolchain: REQUIRED_TOOLCHAIN.to_string(),
+            auto_regenerate_incompatible: false,
+            rustdoc_args: Vec::new(),
+            registry: Registry::default(),
+            git_depth: DEFAULT_GIT_DEPTH,
+            keep_git_history: false,
+            source_policy: SourcePolicy::default(),
+            eviction_policy: EvictionPolicy::default(),
+            max_cache_bytes: None,
+            cache_low_water_percent: DEFAULT_LOW_WATER_PERCENT,
+            cache_watchdog_interval: Duration::from_secs(DEFAULT_WATCHDOG_INTERVAL_SECS),
+            evict_local: false,
+            read_only: false,
+            cache_permissions: None,
+            access_log: None,
+            default_format: ResponseFormat::default(),
+        }
+    }
+}
+
+impl ServiceConfig {
+    /// Open the crate cache in `cache_dir` with the cache settings of this configuration
+    pub fn open_cache(&self, cache_dir: Option<PathBuf>) -> Result<CrateCache> {
+        CrateCache::open(cache_dir, &self.docgen_toolchain, self.read_only)?
+            .with_auto_regenerate_incompatible(self.auto_regenerate_incompatible)
+            .with_rustdoc_args(self.rustdoc_args.clone())
+            .with_registry(self.registry.clone())
+            .with_git_depth(self.git_depth)
+            .with_git_history(self.keep_git_history)
+            .with_source_policy(self.source_policy.clone())
+            .with_eviction_policy(self.eviction_policy)
+            .with_evict_local(self.evict_local)
+            .with_cache_permissions(self.cache_permissions)
+    }
+}
+
+/// Options read from a TOML configuration file
+///
+/// Keys are named like the command line flags, e.g. `max-response-bytes`.
+/// Every option is optional: command line flags and environment variables
+/// override the file, and options missing from both use the defaults.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct FileConfig {
+    pub cache_dir: Option<PathBuf>,
+    pub tool_timeout: Option<u64>,
+    pub cache_timeout: Option<u64>,
+    pub max_concurrent_tasks: Option<usize>,
+    pub max_response_bytes: Option<usize>,
+    pub search_time_budget_ms: Option<u64>,
+    pub docgen_toolchain: Option<String>,
+    pub auto_regenerate_incompatible: Option<bool>,
+    pub rustdoc_args: Option<String>,
+    pub registry_url: Option<String>,
+    pub registry_index_url: Option<String>,
+    pub docsrs_url: Option<String>,
+    pub git_depth: Option<u32>,
+    pub keep_git_history: Option<bool>,
+    pub allowed_sources: Option<String>,
+    pub cache_eviction_policy: Option<String>,
+    pub max_cache_bytes: Option<u64>,
+    pub cache_low_water_percent: Option<u8>,
+    pub cache_watchdog_interval: Option<u64>,
+    pub evict_local_crates: Option<bool>,
+    pub read_only: Option<bool>,
+    pub cache_perms: Option<String>,
+    pub access_log: Option<PathBuf>,
+    pub default_format: Option<String>,
+}
+
+impl FileConfig {
+    /// Default location of the configuration file, `~/.config/rust-docs-mcp/config.toml`
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".config/rust-docs-mcp/config.toml"))
+    }
+
+    /// Read the configuration file at `path`
+    ///
+    /// A `~` at the start of `cache-dir` and `access-log` is expanded to the home directory.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        let mut config: Self = toml::from_str(&contents)
+            .with_context(|| format!("Invalid config file {}", path.display()))?;
+
+        for path in [&mut config.cache_dir, &mut config.access_log]
+            .into_iter()
+            .flatten()
+        {
+            let expanded = shellexpand::tilde(&path.to_string_lossy()).into_owned();
+            *path = PathBuf::from(expanded);
+        }
+        Ok(config)
+    }
+
+    /// Read the configuration file at `path`, or at [`Self::default_path`] if none is given
+    ///
+    /// A missing file at the default location is not an error and yields an
+    /// empty configuration, but an explicitly given file must exist.
+    pub fn load_or_default(path: Option<&Path>) -> Result<Self> {
+        match path {
+            Some(path) => Self::load(path),
+            None => match Self::default_path() {
+                Some(path) if path.exists() => Self::load(&path),
+                _ => Ok(Self::default()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_file_config() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "cache-dir = \"~/docs-cache\"\nmax-response-bytes = 400000\nauto-regenerate-incompatible = true\n",
+        )?;
+
+        let config = FileConfig::load(&path)?;
+        assert_eq!(config.max_response_bytes, Some(400_000));
+        assert_eq!(config.auto_regenerate_incompatible, Some(true));
+        assert_eq!(config.tool_timeout, None);
+        let cache_dir = config.cache_dir.unwrap();
+        assert!(cache_dir.ends_with("docs-cache"));
+        assert!(!cache_dir.starts_with("~"));
+
+        // Typos are reported instead of silently ignored
+        std::fs::write(&path, "max-respones-bytes = 1\n")?;
+        assert!(FileConfig::load(&path).is_err());
+        assert!(FileConfig::load(&temp_dir.path().join("missing.toml")).is_err());
+
+        Ok(())
+    }
+    #[cfg(unix)]
+    #[test]
+    fn test_open_cache_applies_cache_settings() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new()?;
+        let cache_dir = temp_dir.path().join("cache");
+        let config = ServiceConfig {
+            cache_permissions: Some(0o700),
+            eviction_policy: EvictionPolicy::Lfu,
+            ..ServiceConfig::default()
+        };
+
+        let cache = config.open_cache(Some(cache_dir.clone()))?;
+        assert_eq!(cache.eviction_policy(), EvictionPolicy::Lfu);
+        let mode = std::fs::metadata(&cache_dir)?.permissions().mode() & 0o777;
+        assert_eq!(mode, 0o700);
+        Ok(())
+    }
+}
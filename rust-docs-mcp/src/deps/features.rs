@@ -0,0 +1,208 @@
+//! Interpretation of a package's `[features]` table
+//!
+//! Feature lists can contain other features (`std`), optional dependencies
+//! (`dep:serde`), features of a dependency that also enable it when optional
+//! (`serde/derive`), and weak activations that only apply if the dependency is
+//! enabled some other way (`serde?/derive`). `cargo metadata` reports the table
+//! with the implicit feature of each optional dependency already spelled out as
+//! `name = ["dep:name"]`.
+
+use rmcp::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// A feature of the crate that turns on a feature of one of its dependencies
+#[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq, Clone)]
+pub struct FeatureActivation {
+    /// Feature of the crate containing the activation
+    pub feature: String,
+    /// Feature of the dependency that gets enabled
+    pub dependency_feature: String,
+    /// Weak activation (`dep?/feature`): only applies if the dependency is
+    /// enabled through another feature, and does not enable it by itself
+    pub weak: bool,
+}
+
+/// A single entry of a feature's list
+#[derive(Debug, PartialEq)]
+enum FeatureValue<'a> {
+    /// Another feature of the same crate
+    Feature(&'a str),
+    /// `dep:name`, enables an optional dependency without a feature of its own
+    Dep(&'a str),
+    /// `name/feature` or `name?/feature`
+    DepFeature {
+        dep: &'a str,
+        feature: &'a str,
+        weak: bool,
+    },
+}
+
+impl<'a> FeatureValue<'a> {
+    fn parse(value: &'a str) -> Self {
+        if let Some(dep) = value.strip_prefix("dep:") {
+            return Self::Dep(dep);
+        }
+        match value.split_once('/') {
+            Some((dep, feature)) => match dep.strip_suffix('?') {
+                Some(dep) => Self::DepFeature {
+                    dep,
+                    feature,
+                    weak: true,
+                },
+                None => Self::DepFeature {
+                    dep,
+                    feature,
+                    weak: false,
+                },
+            },
+            None => Self::Feature(value),
+        }
+    }
+
+    /// Whether this value enables the dependency with manifest key `dep`
+    fn enables(&self, dep: &str) -> bool {
+        match self {
+            Self::Dep(name) => *name == dep,
+            Self::DepFeature {
+                dep: name, weak, ..
+            } => *name == dep && !weak,
+            Self::Feature(_) => false,
+        }
+    }
+}
+
+/// The `[features]` table of a package
+#[derive(Debug, Default)]
+pub struct FeatureTable {
+    features: BTreeMap<String, Vec<String>>,
+}
+
+impl FeatureTable {
+    /// Read the features of a package from its `cargo metadata` entry
+    pub fn from_metadata(package: &serde_json::Value) -> Self {
+        let features = package["features"]
+            .as_object()
+            .map(|features| {
+                features
+                    .iter()
+                    .map(|(name, values)| {
+                        let values = values
+                            .as_array()
+                            .map(|values| {
+                                values
+                                    .iter()
+                                    .filter_map(|v| v.as_str().map(String::from))
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        (name.clone(), values)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { features }
+    }
+
+    /// Features that enable the optional dependency `dep`, directly or through other features
+    ///
+    /// `dep` is the dependency's manifest key, i.e. its rename if it has one.
+    pub fn features_enabling(&self, dep: &str) -> Vec<String> {
+        self.features
+            .keys()
+            .filter(|feature| self.enables(feature, dep, &mut HashSet::new()))
+            .cloned()
+            .collect()
+    }
+
+    /// Features of the crate that turn on features of the dependency `dep`
+    pub fn activations_of(&self, dep: &str) -> Vec<FeatureActivation> {
+        let mut activations = Vec::new();
+        for (feature, values) in &self.features {
+            for value in values {
+                if let FeatureValue::DepFeature {
+                    dep: name,
+                    feature: dependency_feature,
+                    weak,
+                } = FeatureValue::parse(value)
+                    && name == dep
+                {
+                    activations.push(FeatureActivation {
+                        feature: feature.clone(),
+                        dependency_feature: dependency_feature.to_string(),
+                        weak,
+                    });
+                }
+            }
+        }
+        activations
+    }
+
+    /// Whether enabling `feature` enables `dep`, following the features it enables
+    fn enables<'a>(&'a self, feature: &'a str, dep: &str, visited: &mut HashSet<&'a str>) -> bool {
+        if !visited.insert(feature) {
+            return false;
+        }
+        let Some(values) = self.features.get(feature) else {
+            return false;
+        };
+        values.iter().any(|value| match FeatureValue::parse(value) {
+            FeatureValue::Feature(other) => self.enables(other, dep, visited),
+            value => value.enables(dep),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_feature_values() {
+        assert_eq!(FeatureValue::parse("std"), FeatureValue::Feature("std"));
+        assert_eq!(FeatureValue::parse("dep:serde"), FeatureValue::Dep("serde"));
+        assert_eq!(
+            FeatureValue::parse("serde/derive"),
+            FeatureValue::DepFeature {
+                dep: "serde",
+                feature: "derive",
+                weak: false
+            }
+        );
+        assert_eq!(
+            FeatureValue::parse("chrono?/serde"),
+            FeatureValue::DepFeature {
+                dep: "chrono",
+                feature: "serde",
+                weak: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_features_enabling_follow_feature_chains() {
+        let table = FeatureTable::from_metadata(&json!({
+            "features": {
+                "default": ["std"],
+                "std": [],
+                "serde": ["dep:serde", "chrono?/serde"],
+                "full": ["serde", "derive"],
+                "derive": ["serde_derive/default"],
+                "serde_derive": ["dep:serde_derive"],
+                "cycle-a": ["cycle-b"],
+                "cycle-b": ["cycle-a"],
+            }
+        }));
+
+        assert_eq!(table.features_enabling("serde"), vec!["full", "serde"]);
+        assert_eq!(
+            table.features_enabling("serde_derive"),
+            vec!["derive", "full", "serde_derive"]
+        );
+        // A weak activation alone never enables the dependency
+        assert!(table.features_enabling("chrono").is_empty());
+        assert!(table.features_enabling("missing").is_empty());
+    }
+}
@@ -1,9 +1,17 @@
+pub mod features;
 pub mod outputs;
 pub mod tools;
 
 use rmcp::schemars;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use features::{FeatureActivation, FeatureTable};
+
+/// Dependency kinds as named in cargo metadata, normal dependencies having no kind
+const DEPENDENCY_KINDS: [&str; 3] = ["normal", "dev", "build"];
 
 /// Response for dependency information
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -51,6 +59,59 @@ pub struct Dependency {
 
     /// Target platform (if dependency is platform-specific)
     pub target: Option<String>,
+
+    /// Whether the requirement is inherited from the workspace (`workspace = true`)
+    pub inherited: bool,
+
+    /// Patch source if the dependency is overridden by a `[patch]` section
+    pub patched: Option<String>,
+
+    /// Features actually enabled after feature unification (only included if requested)
+    pub resolved_features: Option<Vec<String>>,
+
+    /// Features of the crate that enable this optional dependency, directly or
+    /// through other features
+    pub enabled_by: Vec<String>,
+
+    /// Features of the crate that turn on features of this dependency
+    pub feature_activations: Vec<FeatureActivation>,
+}
+
+/// Manifest information that `cargo metadata` does not expose directly
+#[derive(Debug, Default)]
+struct ManifestOverrides {
+    /// Dependencies declared with `workspace = true` in the package manifest
+    inherited: HashSet<String>,
+    /// Requirements declared in `[workspace.dependencies]` of the workspace root manifest
+    workspace_reqs: HashMap<String, String>,
+    /// Patched dependencies mapped to a description of their patch source
+    patches: HashMap<String, String>,
+}
+
+impl ManifestOverrides {
+    /// Load overrides from the package manifest and the workspace root manifest
+    ///
+    /// Missing or unreadable manifests are treated as having no overrides.
+    fn load(metadata: &serde_json::Value, package: &serde_json::Value) -> Self {
+        let mut overrides = Self::default();
+
+        if let Some(manifest) = package["manifest_path"]
+            .as_str()
+            .and_then(|path| read_manifest(Path::new(path)))
+        {
+            overrides.inherited = collect_inherited_deps(&manifest);
+        }
+
+        if let Some(root) = metadata["workspace_root"]
+            .as_str()
+            .and_then(|root| read_manifest(&Path::new(root).join("Cargo.toml")))
+        {
+            overrides.workspace_reqs = collect_workspace_reqs(&root);
+            overrides.patches = collect_patches(&root);
+        }
+
+        overrides
+    }
 }
 
 /// Process cargo metadata output to extract dependency information
@@ -60,7 +121,19 @@ pub fn process_cargo_metadata(
     crate_version: &str,
     include_tree: bool,
     filter: Option<&str>,
+    kind_filter: Option<&str>,
+    include_resolved_features: bool,
 ) -> anyhow::Result<DependencyInfo> {
+    let kind_filter = kind_filter.map(str::to_lowercase);
+    if let Some(kind) = &kind_filter
+        && !DEPENDENCY_KINDS.contains(&kind.as_str())
+    {
+        anyhow::bail!(
+            "Invalid kind_filter '{kind}'. Must be one of: {}",
+            DEPENDENCY_KINDS.join(", ")
+        );
+    }
+
     // Find the package in the metadata
     let packages = metadata["packages"]
         .as_array()
@@ -75,6 +148,9 @@ pub fn process_cargo_metadata(
             anyhow::anyhow!("Package {crate_name}-{crate_version} not found in metadata")
         })?;
 
+    let overrides = ManifestOverrides::load(metadata, package);
+    let feature_table = FeatureTable::from_metadata(package);
+
     // Extract direct dependencies
     let mut direct_dependencies = Vec::new();
 
@@ -89,15 +165,47 @@ pub fn process_cargo_metadata(
                 continue;
             }
 
-            // Find resolved version from the resolve section
-            let resolved_version = find_resolved_version(metadata, crate_name, crate_version, name);
+            let kind = dep["kind"].as_str().unwrap_or("normal");
+            if let Some(kind_filter) = &kind_filter
+                && kind != kind_filter
+            {
+                continue;
+            }
+
+            // Find the resolved package from the resolve section
+            let resolved_pkg = find_resolved_pkg(metadata, crate_name, crate_version, name);
+            let resolved_version = resolved_pkg.and_then(parse_pkg_version);
+            let resolved_features = if include_resolved_features {
+                resolved_pkg.and_then(|pkg| find_resolved_features(metadata, pkg))
+            } else {
+                None
+            };
+
+            // Dependencies may be renamed in the manifest, so match on the manifest key
+            let manifest_key = dep["rename"].as_str().unwrap_or(name);
+            let inherited = overrides.inherited.contains(manifest_key);
+
+            let mut version_req = dep["req"].as_str().unwrap_or_default().to_string();
+            if inherited
+                && (version_req.is_empty() || version_req == "*")
+                && let Some(workspace_req) = overrides.workspace_reqs.get(manifest_key)
+            {
+                version_req = workspace_req.clone();
+            }
+
+            let optional = dep["optional"].as_bool().unwrap_or(false);
+            let enabled_by = if optional {
+                feature_table.features_enabling(manifest_key)
+            } else {
+                Vec::new()
+            };
 
             direct_dependencies.push(Dependency {
                 name: name.to_string(),
-                version_req: dep["req"].as_str().unwrap_or_default().to_string(),
+                version_req,
                 resolved_version,
-                kind: dep["kind"].as_str().unwrap_or("normal").to_string(),
-                optional: dep["optional"].as_bool().unwrap_or(false),
+                kind: kind.to_string(),
+                optional,
                 features: dep["features"]
                     .as_array()
                     .map(|arr| {
@@ -107,6 +215,11 @@ pub fn process_cargo_metadata(
                     })
                     .unwrap_or_default(),
                 target: dep["target"].as_str().map(String::from),
+                inherited,
+                patched: overrides.patches.get(name).cloned(),
+                resolved_features,
+                enabled_by,
+                feature_activations: feature_table.activations_of(manifest_key),
             });
         }
     }
@@ -148,13 +261,273 @@ pub fn process_cargo_metadata(
     })
 }
 
-/// Find the resolved version of a dependency from the resolve section
-fn find_resolved_version(
+/// A dependency whose version requirement differs between two versions of a crate
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DependencyChange {
+    pub name: String,
+    pub kind: String,
+    pub target: Option<String>,
+    pub from_req: String,
+    pub to_req: String,
+}
+
+/// Differences between the direct dependencies of two versions of a crate
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DependencyDiff {
+    /// Dependencies only the newer version has
+    pub added: Vec<Dependency>,
+    /// Dependencies only the older version has
+    pub removed: Vec<Dependency>,
+    /// Dependencies whose version requirement changed
+    pub changed: Vec<DependencyChange>,
+}
+
+/// Diff the direct dependencies of two versions of a crate
+///
+/// Dependencies are matched by name, kind and target platform, so moving a
+/// dependency from `[dev-dependencies]` to `[dependencies]` shows up as a
+/// removal and an addition. Results are sorted by name.
+pub fn diff_dependencies(from: Vec<Dependency>, to: Vec<Dependency>) -> DependencyDiff {
+    fn key(dep: &Dependency) -> (String, String, Option<String>) {
+        (dep.name.clone(), dep.kind.clone(), dep.target.clone())
+    }
+
+    let mut from: HashMap<_, _> = from.into_iter().map(|dep| (key(&dep), dep)).collect();
+    let mut diff = DependencyDiff::default();
+
+    for dep in to {
+        match from.remove(&key(&dep)) {
+            Some(old) if old.version_req != dep.version_req => {
+                diff.changed.push(DependencyChange {
+                    name: dep.name,
+                    kind: dep.kind,
+                    target: dep.target,
+                    from_req: old.version_req,
+                    to_req: dep.version_req,
+                });
+            }
+            Some(_) => {}
+            None => diff.added.push(dep),
+        }
+    }
+    diff.removed = from.into_values().collect();
+
+    diff.added.sort_by_key(key);
+    diff.removed.sort_by_key(key);
+    diff.changed
+        .sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.kind.cmp(&b.kind)));
+    diff
+}
+
+/// Name and version of the package `cargo metadata` was run for
+///
+/// `None` for the metadata of a virtual workspace, which has no root package.
+pub fn root_package(metadata: &serde_json::Value) -> Option<(String, String)> {
+    let root = metadata["resolve"]["root"].as_str()?;
+    let package = metadata["packages"]
+        .as_array()?
+        .iter()
+        .find(|p| p["id"].as_str() == Some(root))?;
+    Some((
+        package["name"].as_str()?.to_string(),
+        package["version"].as_str()?.to_string(),
+    ))
+}
+
+/// Direct dependencies of the root package of `metadata` on the crate `target`
+///
+/// When `target_version` is given, requirements it does not satisfy are left
+/// out. A package can depend on the same crate more than once, e.g. as a normal
+/// and a dev dependency, so several entries may be returned.
+pub fn requirements_on(
     metadata: &serde_json::Value,
+    target: &str,
+    target_version: Option<&semver::Version>,
+) -> anyhow::Result<Vec<Dependency>> {
+    let (name, version) = root_package(metadata)
+        .ok_or_else(|| anyhow::anyhow!("No root package found in metadata"))?;
+    let info = process_cargo_metadata(metadata, &name, &version, false, None, None, false)?;
+
+    Ok(info
+        .direct_dependencies
+        .into_iter()
+        .filter(|dep| dep.name == target)
+        .filter(|dep| {
+            let Some(target_version) = target_version else {
+                return true;
+            };
+            // An empty requirement accepts any version, and one cargo wrote
+            // but semver cannot parse is kept rather than hidden
+            dep.version_req.is_empty()
+                || semver::VersionReq::parse(&dep.version_req)
+                    .ok()
+                    .is_none_or(|req| req.matches(target_version))
+        })
+        .collect())
+}
+
+/// A package in the dependency tree built by [`dependency_tree`]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct DependencyTreeNode {
+    pub name: String,
+    pub version: String,
+
+    /// Kinds of the dependency edge from the parent (normal, dev, build), empty for the root
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kinds: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<DependencyTreeNode>,
+
+    /// The package is one of its own ancestors, so its dependencies are not repeated
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cycle: bool,
+
+    /// The package has dependencies below the maximum depth that were left out
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+/// Build the dependency tree of a package from the resolve graph of `metadata`
+///
+/// Unlike the raw resolve section, the tree only holds the packages reachable
+/// from the crate within `max_depth` edges, the crate itself being at depth 0.
+/// Edges back to an ancestor, such as a dev-dependency on the crate, end the
+/// branch with a node marked as a cycle, so the tree is always finite.
+pub fn dependency_tree(
+    metadata: &serde_json::Value,
+    crate_name: &str,
+    crate_version: &str,
+    max_depth: usize,
+) -> anyhow::Result<DependencyTreeNode> {
+    let nodes = metadata["resolve"]["nodes"]
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("No resolve graph found in metadata"))?;
+    let nodes: HashMap<&str, &serde_json::Value> = nodes
+        .iter()
+        .filter_map(|node| Some((node["id"].as_str()?, node)))
+        .collect();
+
+    // Package IDs are opaque, so names and versions come from the packages
+    let packages: HashMap<&str, (&str, &str)> = metadata["packages"]
+        .as_array()
+        .map(|packages| {
+            packages
+                .iter()
+                .filter_map(|p| {
+                    Some((
+                        p["id"].as_str()?,
+                        (p["name"].as_str()?, p["version"].as_str()?),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let root_id = packages
+        .iter()
+        .find(|(_, package)| **package == (crate_name, crate_version))
+        .map(|(id, _)| *id)
+        .or_else(|| {
+            nodes
+                .keys()
+                .find(|id| id.starts_with(&format!("{crate_name} {crate_version}")))
+                .copied()
+        })
+        .ok_or_else(|| {
+            anyhow::anyhow!("Package {crate_name}-{crate_version} not found in the resolve graph")
+        })?;
+
+    let mut ancestors = HashSet::new();
+    Ok(build_tree_node(
+        &nodes,
+        &packages,
+        root_id,
+        Vec::new(),
+        max_depth,
+        &mut ancestors,
+    ))
+}
+
+fn build_tree_node<'a>(
+    nodes: &HashMap<&'a str, &'a serde_json::Value>,
+    packages: &HashMap<&str, (&str, &str)>,
+    id: &'a str,
+    kinds: Vec<String>,
+    remaining_depth: usize,
+    ancestors: &mut HashSet<&'a str>,
+) -> DependencyTreeNode {
+    let (name, version) = match packages.get(id) {
+        Some((name, version)) => (name.to_string(), version.to_string()),
+        None => (
+            id.split(' ').next().unwrap_or(id).to_string(),
+            parse_pkg_version(id).unwrap_or_default(),
+        ),
+    };
+    let mut node = DependencyTreeNode {
+        name,
+        version,
+        kinds,
+        dependencies: Vec::new(),
+        cycle: false,
+        truncated: false,
+    };
+
+    let deps = nodes
+        .get(id)
+        .copied()
+        .and_then(|n| n["deps"].as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    if deps.is_empty() {
+        return node;
+    }
+    if ancestors.contains(id) {
+        node.cycle = true;
+        return node;
+    }
+    if remaining_depth == 0 {
+        node.truncated = true;
+        return node;
+    }
+
+    ancestors.insert(id);
+    for dep in deps {
+        let Some(pkg) = dep["pkg"].as_str() else {
+            continue;
+        };
+        let mut kinds: Vec<String> = dep["dep_kinds"]
+            .as_array()
+            .map(|dep_kinds| {
+                dep_kinds
+                    .iter()
+                    .map(|k| k["kind"].as_str().unwrap_or("normal").to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        kinds.sort();
+        kinds.dedup();
+        node.dependencies.push(build_tree_node(
+            nodes,
+            packages,
+            pkg,
+            kinds,
+            remaining_depth - 1,
+            ancestors,
+        ));
+    }
+    ancestors.remove(id);
+
+    node
+}
+
+/// Find the package ID a dependency resolved to from the resolve section
+fn find_resolved_pkg<'a>(
+    metadata: &'a serde_json::Value,
     parent_name: &str,
     parent_version: &str,
     dep_name: &str,
-) -> Option<String> {
+) -> Option<&'a str> {
     let resolve = metadata["resolve"].as_object()?;
     let nodes = resolve["nodes"].as_array()?;
 
@@ -167,19 +540,560 @@ fn find_resolved_version(
     })?;
 
     // Find the dependency in the parent's deps
-    let deps = parent_node["deps"].as_array()?;
-    for dep in deps {
-        if dep["name"].as_str() == Some(dep_name) {
-            // Extract version from the pkg field
-            if let Some(pkg) = dep["pkg"].as_str() {
-                // pkg format is "name version (source)"
-                let parts: Vec<&str> = pkg.split(' ').collect();
-                if parts.len() >= 2 {
-                    return Some(parts[1].to_string());
-                }
-            }
+    parent_node["deps"]
+        .as_array()?
+        .iter()
+        .find(|dep| dep["name"].as_str() == Some(dep_name))
+        .and_then(|dep| dep["pkg"].as_str())
+}
+
+/// Extract the version from a package ID
+fn parse_pkg_version(pkg: &str) -> Option<String> {
+    // pkg format is "name version (source)"
+    let parts: Vec<&str> = pkg.split(' ').collect();
+    if parts.len() >= 2 {
+        Some(parts[1].to_string())
+    } else {
+        None
+    }
+}
+
+/// Find the features enabled for a resolved package
+///
+/// Cargo unifies features across every dependent of a package, so this can
+/// include features the queried crate never asked for.
+fn find_resolved_features(metadata: &serde_json::Value, pkg: &str) -> Option<Vec<String>> {
+    let nodes = metadata["resolve"]["nodes"].as_array()?;
+    let node = nodes.iter().find(|n| n["id"].as_str() == Some(pkg))?;
+
+    let mut features: Vec<String> = node["features"]
+        .as_array()?
+        .iter()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect();
+    features.sort();
+    Some(features)
+}
+
+/// Read and parse a Cargo.toml manifest
+fn read_manifest(path: &Path) -> Option<toml::Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    toml::from_str(&content).ok()
+}
+
+/// Collect the manifest keys of dependencies declared with `workspace = true`
+fn collect_inherited_deps(manifest: &toml::Value) -> HashSet<String> {
+    const DEP_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+    let mut tables: Vec<&toml::value::Table> = DEP_TABLES
+        .iter()
+        .filter_map(|key| manifest.get(key).and_then(|v| v.as_table()))
+        .collect();
+
+    // Platform-specific dependencies live under [target.<cfg>.dependencies]
+    if let Some(targets) = manifest.get("target").and_then(|t| t.as_table()) {
+        for target in targets.values() {
+            tables.extend(
+                DEP_TABLES
+                    .iter()
+                    .filter_map(|key| target.get(key).and_then(|v| v.as_table())),
+            );
         }
     }
 
-    None
+    tables
+        .into_iter()
+        .flat_map(|table| table.iter())
+        .filter(|(_, spec)| spec.get("workspace").and_then(|w| w.as_bool()) == Some(true))
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+/// Collect version requirements from `[workspace.dependencies]`
+fn collect_workspace_reqs(root: &toml::Value) -> HashMap<String, String> {
+    let Some(deps) = root
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(|d| d.as_table())
+    else {
+        return HashMap::new();
+    };
+
+    deps.iter()
+        .map(|(key, spec)| {
+            let req = match spec {
+                toml::Value::String(version) => version.clone(),
+                _ => spec
+                    .get("version")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("*")
+                    .to_string(),
+            };
+            (key.clone(), req)
+        })
+        .collect()
+}
+
+/// Collect patched crates from `[patch.<registry>]` sections, keyed by package name
+fn collect_patches(root: &toml::Value) -> HashMap<String, String> {
+    let Some(registries) = root.get("patch").and_then(|p| p.as_table()) else {
+        return HashMap::new();
+    };
+
+    let mut patches = HashMap::new();
+    for (registry, entries) in registries {
+        let Some(entries) = entries.as_table() else {
+            continue;
+        };
+
+        for (key, spec) in entries {
+            let package = spec.get("package").and_then(|p| p.as_str()).unwrap_or(key);
+            patches.insert(package.to_string(), describe_patch_source(registry, spec));
+        }
+    }
+    patches
+}
+
+/// Describe where a patched dependency is sourced from
+fn describe_patch_source(registry: &str, spec: &toml::Value) -> String {
+    let field = |name: &str| spec.get(name).and_then(|v| v.as_str());
+
+    if let Some(git) = field("git") {
+        let reference = field("rev")
+            .or_else(|| field("tag"))
+            .or_else(|| field("branch"));
+        match reference {
+            Some(reference) => format!("git+{git}#{reference}"),
+            None => format!("git+{git}"),
+        }
+    } else if let Some(path) = field("path") {
+        format!("path+{path}")
+    } else if let Some(version) = field("version") {
+        format!("{registry}@{version}")
+    } else {
+        registry.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Build minimal `cargo metadata` output for a workspace member
+    fn metadata_for(
+        workspace_root: &Path,
+        manifest_path: &Path,
+        deps: serde_json::Value,
+    ) -> serde_json::Value {
+        json!({
+            "packages": [{
+                "name": "app",
+                "version": "0.1.0",
+                "manifest_path": manifest_path.to_string_lossy(),
+                "dependencies": deps,
+            }],
+            "workspace_root": workspace_root.to_string_lossy(),
+        })
+    }
+
+    #[test]
+    fn test_workspace_inherited_dependency() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        fs::write(
+            root.join("Cargo.toml"),
+            r#"
+[workspace]
+members = ["app"]
+
+[workspace.dependencies]
+serde = { version = "1.0.200", features = ["derive"] }
+"#,
+        )?;
+        fs::create_dir_all(root.join("app"))?;
+        let manifest_path = root.join("app").join("Cargo.toml");
+        fs::write(
+            &manifest_path,
+            r#"
+[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+serde = { workspace = true }
+anyhow = "1.0"
+"#,
+        )?;
+
+        let metadata = metadata_for(
+            root,
+            &manifest_path,
+            json!([
+                {"name": "serde", "req": "", "kind": null, "optional": false, "features": []},
+                {"name": "anyhow", "req": "^1.0", "kind": null, "optional": false, "features": []},
+            ]),
+        );
+
+        let info = process_cargo_metadata(&metadata, "app", "0.1.0", false, None, None, false)?;
+        let serde_dep = &info.direct_dependencies[0];
+        assert!(serde_dep.inherited);
+        assert_eq!(serde_dep.version_req, "1.0.200");
+        assert!(serde_dep.patched.is_none());
+
+        let anyhow_dep = &info.direct_dependencies[1];
+        assert!(!anyhow_dep.inherited);
+        assert_eq!(anyhow_dep.version_req, "^1.0");
+        Ok(())
+    }
+
+    #[test]
+    fn test_patched_dependency() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        let manifest_path = root.join("Cargo.toml");
+        fs::write(
+            &manifest_path,
+            r#"
+[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+log = "0.4"
+regex = "1"
+
+[patch.crates-io]
+log = { git = "https://github.com/rust-lang/log", branch = "master" }
+"#,
+        )?;
+
+        let metadata = metadata_for(
+            root,
+            &manifest_path,
+            json!([
+                {"name": "log", "req": "^0.4", "kind": null, "optional": false, "features": []},
+                {"name": "regex", "req": "^1", "kind": null, "optional": false, "features": []},
+            ]),
+        );
+
+        let info = process_cargo_metadata(&metadata, "app", "0.1.0", false, None, None, false)?;
+        assert_eq!(
+            info.direct_dependencies[0].patched.as_deref(),
+            Some("git+https://github.com/rust-lang/log#master")
+        );
+        assert!(!info.direct_dependencies[0].inherited);
+        assert!(info.direct_dependencies[1].patched.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolved_features_include_unified_features() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        let manifest_path = root.join("Cargo.toml");
+
+        // app asks for serde with "derive", but another dependent (toml) enables "std"
+        let mut metadata = metadata_for(
+            root,
+            &manifest_path,
+            json!([
+                {"name": "serde", "req": "^1.0", "kind": null, "optional": false, "features": ["derive"]},
+                {"name": "toml", "req": "^0.8", "kind": null, "optional": false, "features": []},
+            ]),
+        );
+        metadata["resolve"] = json!({
+            "nodes": [
+                {
+                    "id": "app 0.1.0 (path+file:///app)",
+                    "deps": [
+                        {"name": "serde", "pkg": "serde 1.0.200 (registry+https://github.com/rust-lang/crates.io-index)"},
+                        {"name": "toml", "pkg": "toml 0.8.0 (registry+https://github.com/rust-lang/crates.io-index)"},
+                    ],
+                    "dependencies": [],
+                    "features": [],
+                },
+                {
+                    "id": "serde 1.0.200 (registry+https://github.com/rust-lang/crates.io-index)",
+                    "deps": [],
+                    "features": ["std", "derive", "serde_derive", "default"],
+                },
+                {
+                    "id": "toml 0.8.0 (registry+https://github.com/rust-lang/crates.io-index)",
+                    "deps": [
+                        {"name": "serde", "pkg": "serde 1.0.200 (registry+https://github.com/rust-lang/crates.io-index)"},
+                    ],
+                    "features": [],
+                },
+            ],
+        });
+
+        let info = process_cargo_metadata(&metadata, "app", "0.1.0", false, None, None, true)?;
+        let serde_dep = &info.direct_dependencies[0];
+        assert_eq!(serde_dep.features, vec!["derive"]);
+        assert_eq!(serde_dep.resolved_version.as_deref(), Some("1.0.200"));
+        assert_eq!(
+            serde_dep.resolved_features,
+            Some(vec![
+                "default".to_string(),
+                "derive".to_string(),
+                "serde_derive".to_string(),
+                "std".to_string(),
+            ])
+        );
+        assert_eq!(info.direct_dependencies[1].resolved_features, Some(vec![]));
+
+        // Resolved features are only reported when requested
+        let info = process_cargo_metadata(&metadata, "app", "0.1.0", false, None, None, false)?;
+        assert!(info.direct_dependencies[0].resolved_features.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_optional_and_weak_dependency_features() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        let manifest_path = root.join("Cargo.toml");
+
+        // Metadata for a manifest with:
+        //   [features]
+        //   serde = ["dep:serde", "chrono?/serde"]
+        //   derive = ["serde", "serde/derive"]
+        //   clock = ["chrono/clock"]
+        // plus the implicit `chrono = ["dep:chrono"]` feature cargo reports for
+        // an optional dependency that is never named with `dep:`
+        let mut metadata = metadata_for(
+            root,
+            &manifest_path,
+            json!([
+                {"name": "serde", "req": "^1.0", "kind": null, "optional": true, "features": []},
+                {"name": "chrono", "req": "^0.4", "kind": null, "optional": true, "features": []},
+                {"name": "log", "req": "^0.4", "kind": null, "optional": false, "features": []},
+            ]),
+        );
+        metadata["packages"][0]["features"] = json!({
+            "serde": ["dep:serde", "chrono?/serde"],
+            "derive": ["serde", "serde/derive"],
+            "clock": ["chrono/clock"],
+            "chrono": ["dep:chrono"],
+        });
+
+        let info = process_cargo_metadata(&metadata, "app", "0.1.0", false, None, None, false)?;
+        let [serde_dep, chrono_dep, log_dep] = &info.direct_dependencies[..] else {
+            panic!("Expected three dependencies");
+        };
+
+        assert_eq!(serde_dep.enabled_by, vec!["derive", "serde"]);
+        assert_eq!(
+            serde_dep.feature_activations,
+            vec![FeatureActivation {
+                feature: "derive".to_string(),
+                dependency_feature: "derive".to_string(),
+                weak: false,
+            }]
+        );
+
+        // The weak activation in "serde" does not enable chrono
+        assert_eq!(chrono_dep.enabled_by, vec!["chrono", "clock"]);
+        assert_eq!(
+            chrono_dep.feature_activations,
+            vec![
+                FeatureActivation {
+                    feature: "clock".to_string(),
+                    dependency_feature: "clock".to_string(),
+                    weak: false,
+                },
+                FeatureActivation {
+                    feature: "serde".to_string(),
+                    dependency_feature: "serde".to_string(),
+                    weak: true,
+                },
+            ]
+        );
+
+        assert!(log_dep.enabled_by.is_empty());
+        assert!(log_dep.feature_activations.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_kind_filter() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let manifest_path = temp_dir.path().join("Cargo.toml");
+        fs::write(
+            &manifest_path,
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n",
+        )?;
+        let metadata = metadata_for(
+            temp_dir.path(),
+            &manifest_path,
+            json!([
+                {"name": "serde", "req": "^1.0", "kind": null, "optional": false, "features": []},
+                {"name": "proptest", "req": "^1.0", "kind": "dev", "optional": false, "features": []},
+                {"name": "cc", "req": "^1.0", "kind": "build", "optional": false, "features": []},
+            ]),
+        );
+
+        let names = |kind_filter| -> Result<Vec<String>> {
+            let info =
+                process_cargo_metadata(&metadata, "app", "0.1.0", false, None, kind_filter, false)?;
+            Ok(info
+                .direct_dependencies
+                .into_iter()
+                .map(|dep| dep.name)
+                .collect())
+        };
+        assert_eq!(names(Some("dev"))?, ["proptest"]);
+        assert_eq!(names(Some("build"))?, ["cc"]);
+        assert_eq!(names(Some("Normal"))?, ["serde"]);
+        assert_eq!(names(None)?.len(), 3);
+        assert!(names(Some("optional")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_dependencies() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let manifest_path = temp_dir.path().join("Cargo.toml");
+        let direct_dependencies = |deps: serde_json::Value| -> Result<Vec<Dependency>> {
+            let metadata = metadata_for(temp_dir.path(), &manifest_path, deps);
+            Ok(
+                process_cargo_metadata(&metadata, "app", "0.1.0", false, None, None, false)?
+                    .direct_dependencies,
+            )
+        };
+
+        let old = direct_dependencies(json!([
+            {"name": "serde", "req": "^1.0", "kind": null, "optional": false, "features": []},
+            {"name": "log", "req": "^0.4", "kind": null, "optional": false, "features": []},
+            {"name": "tempfile", "req": "^3", "kind": "dev", "optional": false, "features": []},
+        ]))?;
+        let new = direct_dependencies(json!([
+            {"name": "serde", "req": "^1.0.200", "kind": null, "optional": false, "features": []},
+            {"name": "tracing", "req": "^0.1", "kind": null, "optional": false, "features": []},
+            {"name": "tempfile", "req": "^3", "kind": "dev", "optional": false, "features": []},
+        ]))?;
+
+        let diff = diff_dependencies(old, new);
+        let names = |deps: &[Dependency]| deps.iter().map(|d| d.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&diff.added), vec!["tracing"]);
+        assert_eq!(names(&diff.removed), vec!["log"]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].name, "serde");
+        assert_eq!(diff.changed[0].from_req, "^1.0");
+        assert_eq!(diff.changed[0].to_req, "^1.0.200");
+        Ok(())
+    }
+
+    #[test]
+    fn test_requirements_on() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let manifest_path = temp_dir.path().join("Cargo.toml");
+        let mut metadata = metadata_for(
+            temp_dir.path(),
+            &manifest_path,
+            json!([
+                {"name": "semver", "req": "^1.0", "kind": null, "optional": false, "features": []},
+                {"name": "semver", "req": "^0.9", "kind": "dev", "optional": false, "features": []},
+                {"name": "serde", "req": "^1.0", "kind": null, "optional": false, "features": []},
+            ]),
+        );
+        metadata["packages"][0]["id"] = json!("path+file:///app#0.1.0");
+        metadata["resolve"] = json!({"root": "path+file:///app#0.1.0", "nodes": []});
+
+        assert_eq!(
+            root_package(&metadata),
+            Some(("app".to_string(), "0.1.0".to_string()))
+        );
+
+        let reqs = |version: Option<&str>| -> Result<Vec<String>> {
+            let version = version.map(semver::Version::parse).transpose()?;
+            Ok(requirements_on(&metadata, "semver", version.as_ref())?
+                .into_iter()
+                .map(|dep| format!("{} {}", dep.kind, dep.version_req))
+                .collect())
+        };
+        assert_eq!(reqs(None)?, ["normal ^1.0", "dev ^0.9"]);
+        assert_eq!(reqs(Some("1.0.23"))?, ["normal ^1.0"]);
+        assert!(reqs(Some("2.0.0"))?.is_empty());
+
+        // A virtual workspace has no root package to read requirements from
+        metadata["resolve"]["root"] = serde_json::Value::Null;
+        assert!(requirements_on(&metadata, "semver", None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_dependency_tree_depth_and_cycles() -> Result<()> {
+        // app -> lib -> util, and util dev-depends back on app
+        let metadata = json!({
+            "packages": [
+                {"id": "app 0.1.0 (path+file:///app)", "name": "app", "version": "0.1.0"},
+                {"id": "lib 1.0.0 (registry)", "name": "lib", "version": "1.0.0"},
+                {"id": "util 2.0.0 (registry)", "name": "util", "version": "2.0.0"},
+            ],
+            "resolve": {
+                "root": "app 0.1.0 (path+file:///app)",
+                "nodes": [
+                    {"id": "app 0.1.0 (path+file:///app)", "deps": [
+                        {"name": "lib", "pkg": "lib 1.0.0 (registry)", "dep_kinds": [{"kind": null}]},
+                    ]},
+                    {"id": "lib 1.0.0 (registry)", "deps": [
+                        {"name": "util", "pkg": "util 2.0.0 (registry)", "dep_kinds": [{"kind": "build"}]},
+                    ]},
+                    {"id": "util 2.0.0 (registry)", "deps": [
+                        {"name": "app", "pkg": "app 0.1.0 (path+file:///app)", "dep_kinds": [{"kind": "dev"}]},
+                    ]},
+                ],
+            },
+        });
+
+        let tree = dependency_tree(&metadata, "app", "0.1.0", 10)?;
+        let lib = &tree.dependencies[0];
+        assert_eq!(
+            (lib.name.as_str(), lib.kinds.as_slice()),
+            ("lib", &["normal".to_string()][..])
+        );
+        let util = &lib.dependencies[0];
+        assert_eq!(util.kinds, ["build"]);
+        // The back edge ends the branch instead of expanding app again
+        let app = &util.dependencies[0];
+        assert_eq!(
+            (app.name.as_str(), app.kinds.as_slice()),
+            ("app", &["dev".to_string()][..])
+        );
+        assert!(app.cycle);
+        assert!(app.dependencies.is_empty());
+
+        // Depth 1 keeps the direct dependencies only
+        let tree = dependency_tree(&metadata, "app", "0.1.0", 1)?;
+        let lib = &tree.dependencies[0];
+        assert!(lib.truncated);
+        assert!(lib.dependencies.is_empty());
+        assert!(!tree.truncated);
+
+        let tree = dependency_tree(&metadata, "app", "0.1.0", 0)?;
+        assert!(tree.truncated && tree.dependencies.is_empty());
+
+        assert!(dependency_tree(&metadata, "missing", "0.1.0", 1).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_describe_patch_source() {
+        let path: toml::Value = toml::from_str(r#"path = "../local-log""#).unwrap();
+        assert_eq!(
+            describe_patch_source("crates-io", &path),
+            "path+../local-log"
+        );
+
+        let version: toml::Value = toml::from_str(r#"version = "0.4.20""#).unwrap();
+        assert_eq!(
+            describe_patch_source("crates-io", &version),
+            "crates-io@0.4.20"
+        );
+    }
 }
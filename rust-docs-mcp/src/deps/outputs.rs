@@ -6,6 +6,8 @@
 
 use serde::{Deserialize, Serialize};
 
+pub use crate::deps::features::FeatureActivation;
+
 /// Identifies a crate with name and version
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct CrateIdentifier {
@@ -36,6 +38,29 @@ pub struct Dependency {
 
     /// Target platform (if dependency is platform-specific)
     pub target: Option<String>,
+
+    /// Whether the requirement is inherited from the workspace (`workspace = true`)
+    #[serde(default)]
+    pub inherited: bool,
+
+    /// Patch source if the dependency is overridden by a `[patch]` section
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patched: Option<String>,
+
+    /// Features actually enabled after feature unification across the dependency graph
+    /// (only included if requested)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_features: Option<Vec<String>>,
+
+    /// Features of the crate that enable this optional dependency, directly or
+    /// through other features
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enabled_by: Vec<String>,
+
+    /// Features of the crate that turn on features of this dependency, including
+    /// weak (`dep?/feature`) activations
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub feature_activations: Vec<FeatureActivation>,
 }
 
 /// Output from get_dependencies operation
@@ -62,6 +87,77 @@ impl GetDependenciesOutput {
     }
 }
 
+/// A dependency whose version requirement differs between two versions of a crate
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct DependencyChange {
+    pub name: String,
+    /// Kind of dependency (normal, dev, build)
+    pub kind: String,
+    /// Target platform (if dependency is platform-specific)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Version requirement in `version_a`
+    pub from_req: String,
+    /// Version requirement in `version_b`
+    pub to_req: String,
+}
+
+/// Output from diff_dependencies operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct DiffDependenciesOutput {
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    pub version_a: String,
+    pub version_b: String,
+    /// Dependencies of `version_b` that `version_a` does not have
+    pub added: Vec<Dependency>,
+    /// Dependencies of `version_a` that `version_b` no longer has
+    pub removed: Vec<Dependency>,
+    /// Dependencies whose version requirement changed
+    pub changed: Vec<DependencyChange>,
+}
+
+impl DiffDependenciesOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// A cached crate, or workspace member, that depends on the queried crate
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct DependentInCache {
+    pub name: String,
+    pub version: String,
+    /// Workspace member path, for dependents cached as part of a workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member: Option<String>,
+    /// Version requirement on the queried crate
+    pub req: String,
+    /// Kind of dependency (normal, dev, build)
+    pub kind: String,
+}
+
+/// Output from get_dependents_in_cache operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct DependentsInCacheOutput {
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    /// Version the requirements were matched against, if one was given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub dependents: Vec<DependentInCache>,
+}
+
+impl DependentsInCacheOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
 /// Error output for dependency tools
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct DepsErrorOutput {
@@ -102,6 +198,19 @@ mod tests {
                 optional: false,
                 features: vec!["derive".to_string()],
                 target: None,
+                inherited: true,
+                patched: Some("git+https://github.com/serde-rs/serde#master".to_string()),
+                resolved_features: Some(vec![
+                    "default".to_string(),
+                    "derive".to_string(),
+                    "std".to_string(),
+                ]),
+                enabled_by: vec![],
+                feature_activations: vec![FeatureActivation {
+                    feature: "derive".to_string(),
+                    dependency_feature: "derive".to_string(),
+                    weak: false,
+                }],
             }],
             dependency_tree: None,
             total_dependencies: 1,
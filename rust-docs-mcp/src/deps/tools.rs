@@ -7,8 +7,12 @@ use serde::{Deserialize, Serialize};
 
 use crate::cache::CrateCache;
 use crate::deps::{
-    outputs::{CrateIdentifier, Dependency, DepsErrorOutput, GetDependenciesOutput},
-    process_cargo_metadata,
+    self, dependency_tree, diff_dependencies,
+    outputs::{
+        CrateIdentifier, Dependency, DependencyChange, DependentInCache, DependentsInCacheOutput,
+        DepsErrorOutput, DiffDependenciesOutput, GetDependenciesOutput,
+    },
+    process_cargo_metadata, requirements_on,
 };
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -21,14 +25,68 @@ pub struct GetDependenciesParams {
         description = "Include the full dependency tree (default: false, only shows direct dependencies)"
     )]
     pub include_tree: Option<bool>,
+    #[schemars(
+        description = "With include_tree, return a tree of the packages reachable within this many dependency edges of the crate (1 = direct dependencies only) instead of the raw resolve graph. Cycles, such as dev-dependencies back on the crate, are cut and marked with cycle: true; packages whose dependencies were cut off are marked truncated: true"
+    )]
+    pub max_depth: Option<i64>,
     #[schemars(description = "Filter dependencies by name (partial match)")]
     pub filter: Option<String>,
+    #[schemars(
+        description = "Only return dependencies of this kind: 'normal', 'dev' or 'build' (default: all kinds)"
+    )]
+    pub kind_filter: Option<String>,
+    #[schemars(
+        description = "Also report the features each dependency ends up with after feature unification across the dependency graph (default: false)"
+    )]
+    pub include_resolved_features: Option<bool>,
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DiffDependenciesParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+    #[schemars(description = "The older version to compare from")]
+    pub version_a: String,
+    #[schemars(description = "The newer version to compare to")]
+    pub version_b: String,
     #[schemars(
         description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
     )]
     pub member: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetDependentsInCacheParams {
+    #[schemars(description = "The name of the crate to find dependents of")]
+    pub crate_name: String,
+    #[schemars(
+        description = "Optional version of the crate; only dependents whose requirement this version satisfies are returned (default: any version)"
+    )]
+    pub version: Option<String>,
+}
+
+/// Convert a dependency to its output representation
+fn to_output_dependency(d: deps::Dependency) -> Dependency {
+    Dependency {
+        name: d.name,
+        version_req: d.version_req,
+        resolved_version: d.resolved_version,
+        kind: d.kind,
+        optional: d.optional,
+        features: d.features,
+        target: d.target,
+        inherited: d.inherited,
+        patched: d.patched,
+        resolved_features: d.resolved_features,
+        enabled_by: d.enabled_by,
+        feature_activations: d.feature_activations,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DepsTools {
     cache: Arc<RwLock<CrateCache>>,
@@ -57,7 +115,11 @@ impl DepsTools {
             Ok(_) => {
                 // Load the dependency metadata
                 match cache
-                    .load_dependencies(&params.crate_name, &params.version)
+                    .load_dependencies(
+                        &params.crate_name,
+                        &params.version,
+                        params.member.as_deref(),
+                    )
                     .await
                 {
                     Ok(metadata) => {
@@ -68,28 +130,41 @@ impl DepsTools {
                             &params.version,
                             params.include_tree.unwrap_or(false),
                             params.filter.as_deref(),
+                            params.kind_filter.as_deref(),
+                            params.include_resolved_features.unwrap_or(false),
                         ) {
-                            Ok(dep_info) => Ok(GetDependenciesOutput {
-                                crate_info: CrateIdentifier {
-                                    name: dep_info.crate_info.name,
-                                    version: dep_info.crate_info.version,
-                                },
-                                direct_dependencies: dep_info
-                                    .direct_dependencies
-                                    .into_iter()
-                                    .map(|d| Dependency {
-                                        name: d.name,
-                                        version_req: d.version_req,
-                                        resolved_version: d.resolved_version,
-                                        kind: d.kind,
-                                        optional: d.optional,
-                                        features: d.features,
-                                        target: d.target,
-                                    })
-                                    .collect(),
-                                dependency_tree: dep_info.dependency_tree,
-                                total_dependencies: dep_info.total_dependencies,
-                            }),
+                            Ok(mut dep_info) => {
+                                if let (Some(_), Some(max_depth)) =
+                                    (&dep_info.dependency_tree, params.max_depth)
+                                {
+                                    let tree = dependency_tree(
+                                        &metadata,
+                                        &params.crate_name,
+                                        &params.version,
+                                        max_depth.max(0) as usize,
+                                    )
+                                    .and_then(|tree| Ok(serde_json::to_value(tree)?))
+                                    .map_err(|e| {
+                                        DepsErrorOutput::new(format!(
+                                            "Failed to build dependency tree: {e}"
+                                        ))
+                                    })?;
+                                    dep_info.dependency_tree = Some(tree);
+                                }
+                                Ok(GetDependenciesOutput {
+                                    crate_info: CrateIdentifier {
+                                        name: dep_info.crate_info.name,
+                                        version: dep_info.crate_info.version,
+                                    },
+                                    direct_dependencies: dep_info
+                                        .direct_dependencies
+                                        .into_iter()
+                                        .map(to_output_dependency)
+                                        .collect(),
+                                    dependency_tree: dep_info.dependency_tree,
+                                    total_dependencies: dep_info.total_dependencies,
+                                })
+                            }
                             Err(e) => Err(DepsErrorOutput::new(format!(
                                 "Failed to process dependency metadata: {e}"
                             ))),
@@ -104,4 +179,154 @@ impl DepsTools {
             Err(e) => Err(DepsErrorOutput::new(format!("Failed to cache crate: {e}"))),
         }
     }
+
+    /// Compare the direct dependencies of two cached versions of a crate
+    pub async fn diff_dependencies(
+        &self,
+        params: DiffDependenciesParams,
+    ) -> Result<DiffDependenciesOutput, DepsErrorOutput> {
+        let from = self
+            .direct_dependencies(
+                &params.crate_name,
+                &params.version_a,
+                params.member.as_deref(),
+            )
+            .await?;
+        let to = self
+            .direct_dependencies(
+                &params.crate_name,
+                &params.version_b,
+                params.member.as_deref(),
+            )
+            .await?;
+
+        let diff = diff_dependencies(from, to);
+        Ok(DiffDependenciesOutput {
+            crate_name: params.crate_name,
+            version_a: params.version_a,
+            version_b: params.version_b,
+            added: diff.added.into_iter().map(to_output_dependency).collect(),
+            removed: diff.removed.into_iter().map(to_output_dependency).collect(),
+            changed: diff
+                .changed
+                .into_iter()
+                .map(|change| DependencyChange {
+                    name: change.name,
+                    kind: change.kind,
+                    target: change.target,
+                    from_req: change.from_req,
+                    to_req: change.to_req,
+                })
+                .collect(),
+        })
+    }
+
+    /// Cached crates and workspace members that depend on a crate
+    ///
+    /// Reads the `cargo metadata` saved with each cached crate, so it needs no
+    /// network. Crates whose metadata is not saved, such as those cached by older
+    /// versions, are skipped rather than regenerated.
+    pub async fn get_dependents_in_cache(
+        &self,
+        params: GetDependentsInCacheParams,
+    ) -> Result<DependentsInCacheOutput, DepsErrorOutput> {
+        let target_version =
+            match params.version.as_deref() {
+                Some(version) => Some(semver::Version::parse(version).map_err(|e| {
+                    DepsErrorOutput::new(format!("Invalid version '{version}': {e}"))
+                })?),
+                None => None,
+            };
+
+        let cache = self.cache.read().await;
+        let cached_crates = cache
+            .storage
+            .list_cached_crates()
+            .map_err(|e| DepsErrorOutput::new(format!("Failed to list cached crates: {e}")))?;
+
+        let mut dependents = Vec::new();
+        for cached in cached_crates {
+            let members = cache
+                .storage
+                .list_workspace_members(&cached.name, &cached.version)
+                .unwrap_or_default();
+            let targets = std::iter::once(None).chain(members.iter().map(|m| Some(m.as_str())));
+            for member in targets {
+                let Some(metadata) =
+                    read_saved_dependencies(&cache, &cached.name, &cached.version, member).await
+                else {
+                    continue;
+                };
+                // Virtual workspace roots have no package of their own
+                let Ok(requirements) =
+                    requirements_on(&metadata, &params.crate_name, target_version.as_ref())
+                else {
+                    continue;
+                };
+                dependents.extend(requirements.into_iter().map(|dep| DependentInCache {
+                    name: cached.name.clone(),
+                    version: cached.version.clone(),
+                    member: member.map(str::to_string),
+                    req: dep.version_req,
+                    kind: dep.kind,
+                }));
+            }
+        }
+        dependents.sort_by(|a, b| {
+            (&a.name, &a.version, &a.member, &a.kind)
+                .cmp(&(&b.name, &b.version, &b.member, &b.kind))
+        });
+
+        Ok(DependentsInCacheOutput {
+            crate_name: params.crate_name,
+            version: params.version,
+            dependents,
+        })
+    }
+
+    /// Direct dependencies of a crate version, caching it first if needed
+    async fn direct_dependencies(
+        &self,
+        crate_name: &str,
+        version: &str,
+        member: Option<&str>,
+    ) -> Result<Vec<deps::Dependency>, DepsErrorOutput> {
+        let cache = self.cache.write().await;
+        cache
+            .ensure_crate_or_member_docs(crate_name, version, member)
+            .await
+            .map_err(|e| DepsErrorOutput::new(format!("Failed to cache crate: {e}")))?;
+
+        let metadata = cache
+            .load_dependencies(crate_name, version, member)
+            .await
+            .map_err(|e| {
+                DepsErrorOutput::new(format!(
+                    "Dependencies not available for {crate_name}-{version}. Error: {e}"
+                ))
+            })?;
+
+        process_cargo_metadata(&metadata, crate_name, version, false, None, None, false)
+            .map(|info| info.direct_dependencies)
+            .map_err(|e| {
+                DepsErrorOutput::new(format!(
+                    "Failed to process dependency metadata for {crate_name}-{version}: {e}"
+                ))
+            })
+    }
+}
+
+/// The `cargo metadata` saved with a cached crate or member, if there is one
+async fn read_saved_dependencies(
+    cache: &CrateCache,
+    name: &str,
+    version: &str,
+    member: Option<&str>,
+) -> Option<serde_json::Value> {
+    let path = cache
+        .storage
+        .dependencies_path(name, version, member)
+        .ok()?;
+    let content = tokio::fs::read_to_string(path).await.ok()?;
+    serde_json::from_str(&content).ok()
 }
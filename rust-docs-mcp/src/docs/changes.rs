@@ -0,0 +1,137 @@
+//! Files changed between two commits of a crate cloned from a repository
+//!
+//! Reads the git history kept in the cache with `--keep-git-history` and
+//! diffs the trees of two refs. Changes are tracked per file: an item counts
+//! as changed when the file its span points to changed, so the result is an
+//! approximation suited to reviewing a branch rather than an exact API diff.
+
+use anyhow::{Context, Result};
+use git2::{Repository, Tree};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Paths of the files added, modified, renamed or deleted between `since` and `until`
+///
+/// `git_dir` is the `.git` directory of the clone. The refs can be anything
+/// git accepts, such as commit hashes, tags or `HEAD~3`. Paths are relative to
+/// `subdir` of the repository, the directory the crate was cached from, and
+/// files outside of it are left out.
+pub fn changed_files(
+    git_dir: &Path,
+    since: &str,
+    until: &str,
+    subdir: Option<&str>,
+) -> Result<BTreeSet<String>> {
+    let repo = Repository::open(git_dir)
+        .with_context(|| format!("Failed to open git history at {}", git_dir.display()))?;
+    let old_tree = ref_tree(&repo, since)?;
+    let new_tree = ref_tree(&repo, until)?;
+    let diff = repo
+        .diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)
+        .context("Failed to diff the commits")?;
+
+    let prefix = subdir
+        .map(|subdir| subdir.trim_matches('/'))
+        .filter(|subdir| !subdir.is_empty())
+        .map(|subdir| format!("{subdir}/"));
+    let mut files = BTreeSet::new();
+    for delta in diff.deltas() {
+        for file in [delta.old_file(), delta.new_file()] {
+            let Some(path) = file.path() else {
+                continue;
+            };
+            let path = path.to_string_lossy().replace('\\', "/");
+            let relative = match &prefix {
+                Some(prefix) => path.strip_prefix(prefix.as_str()),
+                None => Some(path.as_str()),
+            };
+            if let Some(relative) = relative {
+                files.insert(relative.to_string());
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// The tree of the commit `spec` refers to
+fn ref_tree<'repo>(repo: &'repo Repository, spec: &str) -> Result<Tree<'repo>> {
+    repo.revparse_single(spec)
+        .and_then(|object| object.peel_to_tree())
+        .with_context(|| {
+            format!(
+                "Commit '{spec}' not found in the kept git history. Shallow clones only hold the cached commit, cache with --git-depth 0 to diff against older ones"
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn commit(repo: &Repository, files: &[(&str, &str)], message: &str) -> Result<git2::Oid> {
+        let workdir = repo.workdir().context("Repository has no working tree")?;
+        let mut index = repo.index()?;
+        for (path, content) in files {
+            let full_path = workdir.join(path);
+            std::fs::create_dir_all(full_path.parent().unwrap())?;
+            std::fs::write(&full_path, content)?;
+            index.add_path(Path::new(path))?;
+        }
+        index.write()?;
+        let tree = repo.find_tree(index.write_tree()?)?;
+        let signature = git2::Signature::now("Test", "test@example.com")?;
+        let parents = match repo.head() {
+            Ok(head) => vec![head.peel_to_commit()?],
+            Err(_) => Vec::new(),
+        };
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        Ok(repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )?)
+    }
+
+    #[test]
+    fn test_changed_files() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let repo = Repository::init(temp_dir.path())?;
+        let first = commit(
+            &repo,
+            &[
+                ("crates/demo/src/lib.rs", "pub mod parser;\n"),
+                ("crates/demo/src/parser.rs", "pub struct Parser;\n"),
+                ("README.md", "demo\n"),
+            ],
+            "Initial commit",
+        )?;
+        commit(
+            &repo,
+            &[
+                ("crates/demo/src/parser.rs", "pub struct Parser(u8);\n"),
+                ("README.md", "demo crate\n"),
+            ],
+            "Change the parser",
+        )?;
+        let git_dir = temp_dir.path().join(".git");
+
+        let files = changed_files(&git_dir, &first.to_string(), "HEAD", None)?;
+        assert_eq!(
+            files.into_iter().collect::<Vec<_>>(),
+            ["README.md", "crates/demo/src/parser.rs"]
+        );
+
+        // Paths are relative to the directory the crate was cached from
+        let files = changed_files(&git_dir, "HEAD~1", "HEAD", Some("crates/demo"))?;
+        assert_eq!(files.into_iter().collect::<Vec<_>>(), ["src/parser.rs"]);
+
+        assert!(changed_files(&git_dir, "HEAD", "HEAD", None)?.is_empty());
+        assert!(changed_files(&git_dir, "missing-ref", "HEAD", None).is_err());
+
+        Ok(())
+    }
+}
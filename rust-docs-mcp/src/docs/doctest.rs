@@ -0,0 +1,232 @@
+//! Removal of doc-test scaffolding from examples
+//!
+//! Rustdoc hides the lines of a Rust code block that start with `# `, such as
+//! `# use std::io;` or the `# fn main() {` and `# }` wrappers, and renders `##`
+//! as a literal `#`. These helpers apply the same rules so examples read like
+//! the rendered documentation. Code blocks in other languages are untouched.
+
+use std::borrow::Cow;
+
+/// Code block attributes that keep a block a Rust doc-test
+const RUST_ATTRIBUTES: [&str; 7] = [
+    "rust",
+    "ignore",
+    "should_panic",
+    "no_run",
+    "compile_fail",
+    "test_harness",
+    "standalone_crate",
+];
+
+/// Remove rustdoc's hidden lines from the Rust code blocks of the Markdown `docs`
+pub fn strip_doctest_scaffolding(docs: &str) -> String {
+    let mut filter = DoctestFilter::default();
+    docs.lines()
+        .filter_map(|line| filter.apply(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Remove rustdoc's hidden lines from the examples in the `///` and `//!` doc comments of `code`
+///
+/// Lines outside doc comments are kept as they are, so the code itself is unchanged.
+pub fn strip_source_doctest_scaffolding(code: &str) -> String {
+    let mut filter = DoctestFilter::default();
+    code.lines()
+        .filter_map(|line| {
+            let Some((prefix, content)) = split_doc_comment(line) else {
+                // A doc comment ends at the first line that is not part of it
+                filter = DoctestFilter::default();
+                return Some(Cow::Borrowed(line));
+            };
+            match filter.apply(content)? {
+                Cow::Borrowed(_) => Some(Cow::Borrowed(line)),
+                Cow::Owned(content) => Some(Cow::Owned(format!("{prefix}{content}"))),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Split a `///` or `//!` doc comment line into its prefix and Markdown content
+fn split_doc_comment(line: &str) -> Option<(&str, &str)> {
+    let indent = line.len() - line.trim_start().len();
+    let rest = &line[indent..];
+    // `////` starts a regular comment
+    let is_doc = (rest.starts_with("///") && !rest.starts_with("////")) || rest.starts_with("//!");
+    is_doc.then(|| line.split_at(indent + 3))
+}
+
+/// Tracks the code fence a Markdown line is in
+#[derive(Debug, Default)]
+struct DoctestFilter {
+    /// Fence character, fence length and whether the open block is Rust
+    fence: Option<(char, usize, bool)>,
+}
+
+impl DoctestFilter {
+    /// The line to show in place of `line`, or `None` if rustdoc hides it
+    fn apply<'a>(&mut self, line: &'a str) -> Option<Cow<'a, str>> {
+        let trimmed = line.trim_start();
+        match self.fence {
+            None => {
+                if let Some((fence_char, fence_len)) = fence(trimmed) {
+                    let info = trimmed[fence_len..].trim();
+                    self.fence = Some((fence_char, fence_len, is_rust_block(info)));
+                }
+                Some(Cow::Borrowed(line))
+            }
+            Some((fence_char, fence_len, is_rust)) => {
+                if let Some((closing_char, closing_len)) = fence(trimmed)
+                    && closing_char == fence_char
+                    && closing_len >= fence_len
+                    && trimmed[closing_len..].trim().is_empty()
+                {
+                    self.fence = None;
+                    return Some(Cow::Borrowed(line));
+                }
+                if is_rust {
+                    map_code_line(line)
+                } else {
+                    Some(Cow::Borrowed(line))
+                }
+            }
+        }
+    }
+}
+
+/// Fence character and length if `line` starts with a ``` or ~~~ code fence
+fn fence(line: &str) -> Option<(char, usize)> {
+    let fence_char = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = line.chars().take_while(|c| *c == fence_char).count();
+    (len >= 3).then_some((fence_char, len))
+}
+
+/// Whether a code block with the info string `info` is a Rust doc-test, as rustdoc decides
+///
+/// Blocks without an info string are Rust, as are blocks naming `rust` or only
+/// doc-test attributes such as `no_run` or `edition2021`.
+fn is_rust_block(info: &str) -> bool {
+    let tokens: Vec<&str> = info
+        .split(|c: char| c == ',' || c.is_whitespace() || c == '{' || c == '}')
+        .map(|token| token.trim_start_matches('.'))
+        .filter(|token| !token.is_empty())
+        .collect();
+    tokens.contains(&"rust")
+        || tokens.iter().all(|token| {
+            RUST_ATTRIBUTES.contains(token)
+                || token.starts_with("edition")
+                || token.starts_with("ignore-")
+                || is_error_code(token)
+        })
+}
+
+/// Whether `token` is a compiler error code such as `E0382`, given with `compile_fail`
+fn is_error_code(token: &str) -> bool {
+    token
+        .strip_prefix('E')
+        .is_some_and(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Rustdoc's rule for a line of a Rust code block: `# ` lines and lone `#` are hidden,
+/// and a leading `##` stands for a literal `#`
+fn map_code_line(line: &str) -> Option<Cow<'_, str>> {
+    let trimmed = line.trim();
+    if trimmed.starts_with("##") {
+        Some(Cow::Owned(line.replacen("##", "#", 1)))
+    } else if trimmed == "#" || trimmed.starts_with("# ") || trimmed.starts_with("#\t") {
+        None
+    } else {
+        Some(Cow::Borrowed(line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_doctest_scaffolding() {
+        let docs = "\
+# Examples
+
+```
+# use semver::Version;
+# fn main() -> Result<(), semver::Error> {
+let version = Version::parse(\"1.2.3\")?;
+#[cfg(feature = \"serde\")]
+let json = serde_json::to_string(&version);
+## not hidden
+#
+# Ok(())
+# }
+```
+
+```text
+# shown in text blocks
+```
+
+```rust,no_run
+#     let hidden = true;
+let shown = true;
+```";
+        assert_eq!(
+            strip_doctest_scaffolding(docs),
+            "\
+# Examples
+
+```
+let version = Version::parse(\"1.2.3\")?;
+#[cfg(feature = \"serde\")]
+let json = serde_json::to_string(&version);
+# not hidden
+```
+
+```text
+# shown in text blocks
+```
+
+```rust,no_run
+let shown = true;
+```"
+        );
+    }
+
+    #[test]
+    fn test_is_rust_block() {
+        assert!(is_rust_block(""));
+        assert!(is_rust_block("rust"));
+        assert!(is_rust_block("no_run, edition2021"));
+        assert!(is_rust_block("compile_fail,E0382"));
+        assert!(!is_rust_block("text"));
+        assert!(!is_rust_block("toml"));
+        assert!(!is_rust_block("sh"));
+    }
+
+    #[test]
+    fn test_strip_source_doctest_scaffolding() {
+        let code = "\
+    /// Parse a version
+    ///
+    /// ```
+    /// # use semver::Version;
+    /// let version = Version::parse(\"1.0.0\").unwrap();
+    /// # assert_eq!(version.major, 1);
+    /// ```
+    pub fn parse(text: &str) -> Result<Version, Error> {
+        # not a doc comment, so kept
+    }";
+        assert_eq!(
+            strip_source_doctest_scaffolding(code),
+            "\
+    /// Parse a version
+    ///
+    /// ```
+    /// let version = Version::parse(\"1.0.0\").unwrap();
+    /// ```
+    pub fn parse(text: &str) -> Result<Version, Error> {
+        # not a doc comment, so kept
+    }"
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! Item kind vocabulary for `kind_filter` parameters
+//!
+//! Kind filters are matched exactly against the kind names reported for each
+//! item, so they are validated and normalized up front: a typo such as `fn` or
+//! `Struct` would otherwise silently match nothing. A filter can name several
+//! kinds, comma-separated or as a list, and matches items of any of them.
+
+use anyhow::{Result, bail};
+use rmcp::schemars;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Every item kind reported by the documentation and search tools
+pub const ITEM_KINDS: [&str; 21] = [
+    "module",
+    "struct",
+    "enum",
+    "union",
+    "function",
+    "trait",
+    "trait_alias",
+    "impl",
+    "type_alias",
+    "constant",
+    "static",
+    "macro",
+    "proc_macro",
+    "extern_crate",
+    "use",
+    "field",
+    "variant",
+    "primitive",
+    "assoc_const",
+    "assoc_type",
+    "extern_type",
+];
+
+/// Common alternative spellings mapped to their item kind
+const KIND_ALIASES: [(&str, &str); 11] = [
+    ("fn", "function"),
+    ("func", "function"),
+    ("method", "function"),
+    ("mod", "module"),
+    ("type", "type_alias"),
+    ("typealias", "type_alias"),
+    ("const", "constant"),
+    ("derive", "proc_macro"),
+    ("attribute", "proc_macro"),
+    ("struct_field", "field"),
+    ("enum_variant", "variant"),
+];
+
+/// Normalize a kind filter to the kind name used by items
+///
+/// Matching is case-insensitive, treats `-` and spaces like `_`, and accepts
+/// the aliases in [`KIND_ALIASES`]. Unknown kinds are rejected with an error
+/// listing the valid ones.
+pub fn normalize_kind(kind: &str) -> Result<&'static str> {
+    let normalized = kind.trim().to_lowercase().replace(['-', ' '], "_");
+
+    if let Some(known) = ITEM_KINDS.iter().find(|known| **known == normalized) {
+        return Ok(known);
+    }
+    if let Some((_, known)) = KIND_ALIASES.iter().find(|(alias, _)| *alias == normalized) {
+        return Ok(known);
+    }
+
+    bail!(
+        "Unknown kind_filter '{kind}'. Valid kinds: {}",
+        ITEM_KINDS.join(", ")
+    )
+}
+
+/// Normalize an optional kind filter of comma-separated kinds, see [`normalize_kind`]
+///
+/// Returns the distinct kinds in the order given.
+pub fn normalize_kind_filter(filter: Option<&str>) -> Result<Option<Vec<&'static str>>> {
+    let Some(filter) = filter else {
+        return Ok(None);
+    };
+    let mut kinds = Vec::new();
+    for kind in filter.split(',') {
+        let kind = normalize_kind(kind)?;
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+    }
+    Ok(Some(kinds))
+}
+
+/// A kind filter as given to a tool: one string, possibly comma-separated, or a list of kinds
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum KindFilterInput {
+    Text(String),
+    List(Vec<String>),
+}
+
+/// Deserialize an optional kind filter given as a string or a list, joining lists with commas
+pub fn deserialize_kind_filter<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(
+        Option::<KindFilterInput>::deserialize(deserializer)?.map(|input| match input {
+            KindFilterInput::Text(text) => text,
+            KindFilterInput::List(kinds) => kinds.join(","),
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_kind_aliases() {
+        assert_eq!(normalize_kind("struct").unwrap(), "struct");
+        assert_eq!(normalize_kind("Struct").unwrap(), "struct");
+        assert_eq!(normalize_kind(" FN ").unwrap(), "function");
+        assert_eq!(normalize_kind("type").unwrap(), "type_alias");
+        assert_eq!(normalize_kind("Type-Alias").unwrap(), "type_alias");
+        assert_eq!(normalize_kind("const").unwrap(), "constant");
+        assert_eq!(normalize_kind("mod").unwrap(), "module");
+        assert_eq!(normalize_kind_filter(None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_normalize_multiple_kinds() {
+        assert_eq!(
+            normalize_kind_filter(Some("struct, Enum,fn")).unwrap(),
+            Some(vec!["struct", "enum", "function"])
+        );
+        assert_eq!(
+            normalize_kind_filter(Some("fn,function")).unwrap(),
+            Some(vec!["function"])
+        );
+        let error = normalize_kind_filter(Some("struct,structure"))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Unknown kind_filter 'structure'"));
+        assert!(normalize_kind_filter(Some("struct,")).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_kind_filter() {
+        #[derive(Deserialize)]
+        struct Params {
+            #[serde(default, deserialize_with = "deserialize_kind_filter")]
+            kind_filter: Option<String>,
+        }
+
+        let parse = |json: &str| serde_json::from_str::<Params>(json).unwrap().kind_filter;
+        assert_eq!(
+            parse(r#"{"kind_filter": "struct,enum"}"#).as_deref(),
+            Some("struct,enum")
+        );
+        assert_eq!(
+            parse(r#"{"kind_filter": ["struct", "enum"]}"#).as_deref(),
+            Some("struct,enum")
+        );
+        assert_eq!(parse("{}"), None);
+    }
+
+    #[test]
+    fn test_normalize_kind_rejects_unknown() {
+        let error = normalize_kind("structure").unwrap_err().to_string();
+        assert!(error.contains("Unknown kind_filter 'structure'"));
+        assert!(error.contains("function, trait"));
+        assert!(normalize_kind_filter(Some("")).is_err());
+    }
+}
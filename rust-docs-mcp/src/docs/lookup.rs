@@ -0,0 +1,142 @@
+//! Single-item lookups in rustdoc JSON files
+//!
+//! Parsing a whole `docs.json` builds every item of the crate in memory, which
+//! takes gigabytes for crates such as `aws-sdk-*`. Tools that need one item
+//! can use [`find_item`] instead: it streams through the file and deserializes
+//! only the requested entry of the `index` map, skipping everything else.
+
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rustdoc_types::{Id, Item};
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, Visitor};
+
+/// Result of looking up an item in a rustdoc JSON file
+#[derive(Debug, Default)]
+pub struct ItemLookup {
+    /// The file's `format_version`, if present
+    pub format_version: Option<u32>,
+    /// The item, if the file contains it
+    pub item: Option<Item>,
+}
+
+/// Read the item with `id` from the rustdoc JSON file at `path`
+///
+/// The item is deserialized only if it is found, so callers should check
+/// [`ItemLookup::format_version`] before relying on it.
+pub fn find_item(path: &Path, id: Id) -> Result<ItemLookup> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open documentation file: {}", path.display()))?;
+    let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(file));
+    let lookup = CrateSeed { id }
+        .deserialize(&mut deserializer)
+        .context("Failed to parse documentation JSON")?;
+    deserializer
+        .end()
+        .context("Failed to parse documentation JSON")?;
+    Ok(lookup)
+}
+
+/// Visits the top-level crate object, keeping only `format_version` and the wanted item
+struct CrateSeed {
+    id: Id,
+}
+
+impl<'de> DeserializeSeed<'de> for CrateSeed {
+    type Value = ItemLookup;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for CrateSeed {
+    type Value = ItemLookup;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a rustdoc JSON crate object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut lookup = ItemLookup::default();
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "index" => lookup.item = map.next_value_seed(IndexSeed { id: self.id })?,
+                "format_version" => lookup.format_version = map.next_value()?,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(lookup)
+    }
+}
+
+/// Visits the `index` map, deserializing only the entry with the wanted id
+struct IndexSeed {
+    id: Id,
+}
+
+impl<'de> DeserializeSeed<'de> for IndexSeed {
+    type Value = Option<Item>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for IndexSeed {
+    type Value = Option<Item>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map of item ids to items")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let wanted = self.id.0.to_string();
+        let mut item = None;
+        while let Some(key) = map.next_key::<String>()? {
+            if item.is_none() && key == wanted {
+                item = Some(map.next_value::<Item>()?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_item_skips_other_entries() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("docs.json");
+        // Entries other than the requested one are never deserialized as items
+        let docs = serde_json::json!({
+            "root": 0,
+            "index": {
+                "0": {"not": "an item"},
+                "1": [1, 2, 3],
+            },
+            "paths": {},
+            "format_version": 53,
+        });
+        std::fs::write(&path, docs.to_string())?;
+
+        let lookup = find_item(&path, Id(7))?;
+        assert_eq!(lookup.format_version, Some(53));
+        assert!(lookup.item.is_none());
+
+        std::fs::write(&path, "{\"index\": {")?;
+        assert!(find_item(&path, Id(7)).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+//! Markdown export of a crate's public API
+//!
+//! Renders the public items of a crate as one Markdown document, grouped by
+//! module, with a table of contents linking to each module section.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::docs::query::{DocQuery, ItemInfo};
+
+/// Item kinds rendered as entries under their module heading
+const EXPORTED_KINDS: [&str; 11] = [
+    "struct",
+    "enum",
+    "union",
+    "trait",
+    "trait_alias",
+    "function",
+    "type_alias",
+    "constant",
+    "static",
+    "macro",
+    "proc_macro",
+];
+
+/// Options controlling which items are exported
+#[derive(Debug, Default)]
+pub struct MarkdownOptions<'a> {
+    /// Only export items of these kinds (e.g. "struct")
+    pub kind_filter: Option<&'a [&'a str]>,
+    /// Stop after this many items
+    pub max_items: Option<usize>,
+}
+
+/// A rendered Markdown document
+#[derive(Debug)]
+pub struct MarkdownExport {
+    pub markdown: String,
+    /// Number of items rendered
+    pub item_count: usize,
+    /// True if items were left out because of `max_items`
+    pub truncated: bool,
+}
+
+/// Render the public items of a crate as a single Markdown document
+pub fn render_crate_markdown(
+    query: &DocQuery,
+    crate_name: &str,
+    version: &str,
+    options: &MarkdownOptions,
+) -> MarkdownExport {
+    let module_docs: BTreeMap<String, Option<String>> = query
+        .list_items(Some(&["module"]))
+        .into_iter()
+        .map(|module| (module.path.join("::"), module.docs))
+        .collect();
+
+    let mut items: Vec<ItemInfo> = query
+        .list_items(options.kind_filter)
+        .into_iter()
+        .filter(|item| {
+            item.visibility == "public"
+                && !item.path.is_empty()
+                && EXPORTED_KINDS.contains(&item.kind.as_str())
+        })
+        .collect();
+
+    let max_items = options.max_items.unwrap_or(usize::MAX);
+    let truncated = items.len() > max_items;
+    items.truncate(max_items);
+    let item_count = items.len();
+
+    // Group items by their parent module path
+    let mut modules: BTreeMap<String, Vec<ItemInfo>> = BTreeMap::new();
+    for item in items {
+        let module_path = item.path[..item.path.len() - 1].join("::");
+        modules.entry(module_path).or_default().push(item);
+    }
+
+    let mut markdown = String::new();
+    let _ = writeln!(markdown, "# {crate_name} {version}\n");
+    if let Some(Some(docs)) = module_docs.get(crate_name.replace('-', "_").as_str()) {
+        let _ = writeln!(markdown, "{}\n", demote_headings(docs, 2));
+    }
+
+    let _ = writeln!(markdown, "## Contents\n");
+    for (module_path, module_items) in &modules {
+        let _ = writeln!(
+            markdown,
+            "- [`{module_path}`](#{}) ({} items)",
+            heading_anchor(module_path),
+            module_items.len()
+        );
+    }
+    markdown.push('\n');
+
+    for (module_path, module_items) in &modules {
+        let _ = writeln!(markdown, "## `{module_path}`\n");
+        if module_path.contains("::")
+            && let Some(Some(docs)) = module_docs.get(module_path)
+        {
+            let _ = writeln!(markdown, "{}\n", demote_headings(docs, 2));
+        }
+
+        for item in module_items {
+            render_item(&mut markdown, query, item);
+        }
+    }
+
+    if truncated {
+        let _ = writeln!(
+            markdown,
+            "---\n\n_Output limited to {item_count} items. Increase `max_items` to include more._"
+        );
+    }
+
+    MarkdownExport {
+        markdown,
+        item_count,
+        truncated,
+    }
+}
+
+/// Render a single item as a level 3 section
+fn render_item(markdown: &mut String, query: &DocQuery, item: &ItemInfo) {
+    let _ = writeln!(markdown, "### `{}` ({})\n", item.name, item.kind);
+
+    if let Ok(item_id) = item.id.parse::<u32>()
+        && let Some(signature) = query.get_item_signature_by_id(item_id)
+    {
+        let _ = writeln!(markdown, "```rust\n{signature}\n```\n");
+    }
+
+    match &item.docs {
+        Some(docs) if !docs.trim().is_empty() => {
+            let _ = writeln!(markdown, "{}\n", demote_headings(docs.trim(), 3));
+        }
+        _ => {}
+    }
+}
+
+/// Push the headings inside item docs below the document's own heading levels
+///
+/// Lines inside fenced code blocks are left untouched, since `#` there is
+/// usually a comment or attribute rather than a heading.
+fn demote_headings(docs: &str, levels: usize) -> String {
+    let prefix = "#".repeat(levels);
+    let mut in_code_block = false;
+
+    docs.lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_code_block = !in_code_block;
+            }
+            if !in_code_block && trimmed.starts_with('#') {
+                format!("{prefix}{trimmed}")
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Anchor generated by GitHub-flavored Markdown for a `` `module::path` `` heading
+fn heading_anchor(module_path: &str) -> String {
+    module_path
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '_' || *c == '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_demote_headings_skips_code_blocks() {
+        let docs = "# Examples\n\n```\n# use semver::Version;\n```\n## Notes";
+        assert_eq!(
+            demote_headings(docs, 3),
+            "#### Examples\n\n```\n# use semver::Version;\n```\n##### Notes"
+        );
+    }
+
+    #[test]
+    fn test_heading_anchor() {
+        assert_eq!(heading_anchor("semver"), "semver");
+        assert_eq!(heading_anchor("my_crate::Io"), "my_crateio");
+    }
+}
@@ -1,5 +1,13 @@
+pub mod changes;
+pub mod doctest;
+pub mod kinds;
+pub mod lookup;
+pub mod markdown;
+pub mod ndjson;
 pub mod outputs;
 pub mod query;
+pub mod text;
 pub mod tools;
+pub mod visibility;
 
 pub use query::DocQuery;
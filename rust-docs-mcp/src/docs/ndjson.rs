@@ -0,0 +1,87 @@
+//! Newline-delimited JSON export of a crate's items
+//!
+//! Writes a header line naming the crate, then one JSON object per item in
+//! the same shape as the items of list_crate_items. The export is written to
+//! a file rather than returned, so it covers the whole crate regardless of the
+//! response size limit.
+
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+
+use crate::docs::query::ItemInfo;
+
+/// First line of an export, describing the items that follow
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NdjsonHeader {
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    pub version: String,
+    /// Number of item lines after the header
+    pub item_count: usize,
+}
+
+/// Write the header and one line per item, returning the number of bytes written
+pub fn write_items_ndjson(
+    writer: &mut impl Write,
+    header: &NdjsonHeader,
+    items: &[ItemInfo],
+) -> io::Result<u64> {
+    let mut bytes = write_line(writer, header)?;
+    for item in items {
+        bytes += write_line(writer, item)?;
+    }
+    writer.flush()?;
+    Ok(bytes)
+}
+
+fn write_line(writer: &mut impl Write, value: &impl Serialize) -> io::Result<u64> {
+    let mut line = serde_json::to_vec(value)?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    Ok(line.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, name: &str) -> ItemInfo {
+        ItemInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            kind: "struct".to_string(),
+            path: vec!["demo".to_string(), name.to_string()],
+            docs: Some("Line one.\nLine two.".to_string()),
+            visibility: "public".to_string(),
+            hidden: false,
+        }
+    }
+
+    #[test]
+    fn test_write_items_ndjson() -> io::Result<()> {
+        let items = vec![item("1", "Parser"), item("2", "Token")];
+        let header = NdjsonHeader {
+            crate_name: "demo".to_string(),
+            version: "0.1.0".to_string(),
+            item_count: items.len(),
+        };
+
+        let mut output = Vec::new();
+        let bytes = write_items_ndjson(&mut output, &header, &items)?;
+        assert_eq!(bytes, output.len() as u64);
+
+        let text = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            r#"{"crate":"demo","version":"0.1.0","item_count":2}"#
+        );
+        // Newlines in docs are escaped, keeping one item per line
+        let second: serde_json::Value = serde_json::from_str(lines[2])?;
+        assert_eq!(second["name"], "Token");
+        assert_eq!(second["docs"], "Line one.\nLine two.");
+
+        Ok(())
+    }
+}
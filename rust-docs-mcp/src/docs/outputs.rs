@@ -5,6 +5,7 @@
 //! deserialized in tests for type-safe validation.
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Simplified item information for API responses
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -15,6 +16,15 @@ pub struct ItemInfo {
     pub path: Vec<String>,
     pub docs: Option<String>,
     pub visibility: String,
+    /// Id of the crate item a field's type names, when field types are resolved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field_type_id: Option<String>,
+    /// Full path of a field's type defined in another crate, when field types are resolved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field_type_path: Option<String>,
+    /// Whether the item is marked `#[doc(hidden)]`, only listed with `include_hidden`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hidden: bool,
 }
 
 /// Preview item info for lightweight responses
@@ -35,11 +45,24 @@ pub struct PaginationInfo {
     pub has_more: bool,
 }
 
+/// Items of one module, listed by list_crate_items with `group_by_module`
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ModuleItems {
+    /// Module path such as `serde::de`, empty for items without a module path
+    pub path: String,
+    pub items: Vec<ItemInfo>,
+}
+
 /// Output from list_crate_items operation
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct ListCrateItemsOutput {
     pub items: Vec<ItemInfo>,
+    /// Items nested under their module, present only when `group_by_module` is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modules: Option<Vec<ModuleItems>>,
     pub pagination: PaginationInfo,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
 }
 
 impl ListCrateItemsOutput {
@@ -50,11 +73,62 @@ impl ListCrateItemsOutput {
     }
 }
 
+/// Search results sharing a name or kind, collapsed into one entry
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ItemGroup {
+    /// The shared name or kind
+    pub key: String,
+    pub count: usize,
+    pub items: Vec<ItemPreview>,
+}
+
+/// A chunk of items emitted while streaming a crate listing
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ItemChunk {
+    pub chunk_index: usize,
+    pub total_chunks: usize,
+    /// Total number of items across all chunks
+    pub total: usize,
+    pub items: Vec<ItemInfo>,
+}
+
+impl ItemChunk {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// Summary returned once a streamed crate listing has finished
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct StreamCrateItemsOutput {
+    pub total: usize,
+    pub chunk_size: usize,
+    pub chunks_sent: usize,
+    /// False if streaming stopped before every chunk was delivered
+    pub complete: bool,
+}
+
+impl StreamCrateItemsOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
 /// Output from search_items operation (full details)
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct SearchItemsOutput {
     pub items: Vec<ItemInfo>,
+    /// Grouped results, present only when `group_by` is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<ItemGroup>>,
     pub pagination: PaginationInfo,
+    /// Whether the search stopped at its time budget, leaving later items unscanned
+    #[serde(default)]
+    pub timed_out: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub warning: Option<String>,
 }
@@ -72,6 +146,11 @@ impl SearchItemsOutput {
 pub struct SearchItemsPreviewOutput {
     pub items: Vec<ItemPreview>,
     pub pagination: PaginationInfo,
+    /// Whether the search stopped at its time budget, leaving later items unscanned
+    #[serde(default)]
+    pub timed_out: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
 }
 
 impl SearchItemsPreviewOutput {
@@ -80,6 +159,42 @@ impl SearchItemsPreviewOutput {
         serde_json::to_string(self)
             .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
     }
+
+    /// Render one `id  kind  path::name` line per item
+    ///
+    /// Pagination and warnings follow as lines starting with `# `, only when
+    /// there are more results or something to warn about.
+    pub fn to_compact_text(&self) -> String {
+        let mut lines: Vec<String> = self
+            .items
+            .iter()
+            .map(|item| {
+                let path = if item.path.last() == Some(&item.name) {
+                    item.path.join("::")
+                } else {
+                    item.path
+                        .iter()
+                        .chain(std::iter::once(&item.name))
+                        .map(String::as_str)
+                        .collect::<Vec<_>>()
+                        .join("::")
+                };
+                format!("{}  {}  {path}", item.id, item.kind)
+            })
+            .collect();
+        if self.pagination.has_more {
+            lines.push(format!(
+                "# {} of {} items shown, next offset {}",
+                self.items.len(),
+                self.pagination.total,
+                self.pagination.offset + self.items.len()
+            ));
+        }
+        if let Some(warning) = &self.warning {
+            lines.push(format!("# {warning}"));
+        }
+        lines.join("\n")
+    }
 }
 
 /// Source location information
@@ -92,16 +207,89 @@ pub struct SourceLocation {
     pub column_end: usize,
 }
 
+/// A generic parameter of an item, with its default and bounds rendered as Rust syntax
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct GenericParam {
+    pub name: String,
+    /// "lifetime", "type" or "const"
+    pub kind: String,
+    /// Type of a const parameter
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// Bounds from the parameter list and the where clause
+    pub bounds: Vec<String>,
+}
+
 /// Detailed item information including signatures
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct DetailedItem {
     pub info: ItemInfo,
     pub signature: Option<String>,
     pub generics: Option<serde_json::Value>,
+    /// Generic parameters with their kind, default and bounds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generic_params: Option<Vec<GenericParam>>,
     pub fields: Option<Vec<ItemInfo>>,
     pub variants: Option<Vec<ItemInfo>>,
     pub methods: Option<Vec<ItemInfo>>,
     pub source_location: Option<SourceLocation>,
+    /// Page of the item on docs.rs, for crates cached from crates.io
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docsrs_url: Option<String>,
+    /// Whether a trait looks sealed, so it cannot be implemented outside its crate
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sealed: bool,
+    /// Why the trait is considered sealed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sealed_note: Option<String>,
+}
+
+/// Warning returned when a name search stops at its time budget
+pub const SEARCH_TIMED_OUT_WARNING: &str = "Search stopped at its time budget, so the results are partial. Narrow the pattern, add a kind_filter or path_filter, or use search_items_fuzzy, which queries an index instead of scanning every item.";
+
+/// Parts of a `DetailedItem` that can be selected with the `fields` parameter of get_item_details
+pub const DETAIL_FIELDS: [&str; 7] = [
+    "signature",
+    "docs",
+    "generics",
+    "fields",
+    "variants",
+    "methods",
+    "source_location",
+];
+
+impl DetailedItem {
+    /// Drop every part not listed in `selected`
+    ///
+    /// The item's id, name, kind, path and visibility are always kept.
+    pub fn retain_fields(&mut self, selected: &[String]) {
+        let keep = |name: &str| selected.iter().any(|field| field == name);
+
+        if !keep("signature") {
+            self.signature = None;
+        }
+        if !keep("docs") {
+            self.info.docs = None;
+        }
+        if !keep("generics") {
+            self.generics = None;
+            self.generic_params = None;
+        }
+        if !keep("fields") {
+            self.fields = None;
+        }
+        if !keep("variants") {
+            self.variants = None;
+        }
+        if !keep("methods") {
+            self.methods = None;
+        }
+        if !keep("source_location") {
+            self.source_location = None;
+        }
+    }
 }
 
 /// Output from get_item_details operation
@@ -109,7 +297,14 @@ pub struct DetailedItem {
 #[serde(untagged)]
 pub enum GetItemDetailsOutput {
     Success(Box<DetailedItem>),
-    Error { error: String },
+    /// The requested path matched several items; pick one of the candidates by id
+    Ambiguous {
+        error: String,
+        candidates: Vec<ItemPreview>,
+    },
+    Error {
+        error: String,
+    },
 }
 
 impl GetItemDetailsOutput {
@@ -128,12 +323,20 @@ impl GetItemDetailsOutput {
     pub fn is_error(&self) -> bool {
         matches!(self, GetItemDetailsOutput::Error { .. })
     }
+
+    /// Check if the requested path matched several items
+    pub fn is_ambiguous(&self) -> bool {
+        matches!(self, GetItemDetailsOutput::Ambiguous { .. })
+    }
 }
 
 /// Output from get_item_docs operation
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct GetItemDocsOutput {
     pub documentation: Option<String>,
+    /// `trait_default` when the docs were inherited from the implemented trait method
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
 }
@@ -146,12 +349,71 @@ impl GetItemDocsOutput {
     }
 }
 
+/// Documentation of one of the names asked for in get_docs_for_names
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct NameDocs {
+    /// ID of the item the name resolved to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub documentation: Option<String>,
+    /// `trait_default` when the docs were inherited from the implemented trait method
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Why the name could not be resolved to a single item
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl NameDocs {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            documentation: None,
+            source: None,
+            error: Some(message.into()),
+        }
+    }
+}
+
+/// Output from get_docs_for_names operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct GetDocsForNamesOutput {
+    /// Documentation keyed by the requested name
+    pub docs: BTreeMap<String, NameDocs>,
+    /// Names left out to stay within the response size limit
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub omitted: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+impl GetDocsForNamesOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// Where a type named in an item's signature is defined in the crate
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ReferencedDefinition {
+    pub id: String,
+    pub name: String,
+    pub filename: String,
+    pub line: usize,
+}
+
 /// Source code information for an item
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct SourceInfo {
     pub location: SourceLocation,
     pub code: String,
     pub context_lines: Option<usize>,
+    /// Definitions of the types in the item's signature, when requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub references: Option<Vec<ReferencedDefinition>>,
 }
 
 /// Output from get_item_source operation
@@ -180,6 +442,246 @@ impl GetItemSourceOutput {
     }
 }
 
+/// A blanket trait implementation that applies to a type
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct BlanketImpl {
+    pub impl_id: String,
+    pub trait_name: String,
+    pub trait_path: String,
+    /// The generic type the impl is written for (e.g. `T`)
+    pub for_type: String,
+}
+
+/// Output from get_blanket_impls operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct GetBlanketImplsOutput {
+    pub item: ItemPreview,
+    /// Traits the type implements through blanket impls such as `impl<T: Display> ToString for T`
+    pub blanket_traits: Vec<BlanketImpl>,
+    /// Auto traits rustdoc determined the type implements (e.g. `Send`, `Sync`)
+    pub auto_traits: Vec<String>,
+}
+
+impl GetBlanketImplsOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// Output from get_repr_info operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct GetReprInfoOutput {
+    pub item: ItemPreview,
+    /// The `repr` attribute, e.g. `#[repr(C)]`, if the type has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repr: Option<String>,
+    /// Traits implemented through `#[derive(...)]`
+    pub derives: Vec<String>,
+    /// Which of `Copy`, `Clone`, `Send` and `Sync` the type implements
+    pub auto_traits: Vec<String>,
+    /// Field names in declaration order, or variant names for enums
+    pub fields: Vec<String>,
+}
+
+impl GetReprInfoOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// A `cfg` predicate and how many items it gates
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct CfgAttribute {
+    /// The predicate as written, e.g. `feature = "std"` or `all(unix, not(target_os = "macos"))`
+    pub predicate: String,
+    pub item_count: usize,
+}
+
+/// Output from list_cfg_attributes operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ListCfgAttributesOutput {
+    /// Distinct predicates, most common first
+    pub cfgs: Vec<CfgAttribute>,
+}
+
+impl ListCfgAttributesOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// A method callable on a type
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct MethodEntry {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// `inherent`, or `trait <path>` for methods from a trait impl
+    pub from: String,
+}
+
+/// Output from get_all_methods operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct GetAllMethodsOutput {
+    pub item: ItemPreview,
+    pub methods: Vec<MethodEntry>,
+}
+
+impl GetAllMethodsOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// A supertrait of a trait
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct SupertraitEntry {
+    pub id: String,
+    pub path: String,
+    /// Path of the supertrait whose bounds name it, absent for a direct supertrait
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub via: Option<String>,
+}
+
+/// Output from get_supertraits operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct GetSupertraitsOutput {
+    pub item: ItemPreview,
+    /// Supertraits declared by the trait itself
+    pub direct: Vec<SupertraitEntry>,
+    /// Every supertrait, the direct ones included, nearest first
+    pub transitive: Vec<SupertraitEntry>,
+}
+
+impl GetSupertraitsOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// Output from get_item_raw operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct GetItemRawOutput {
+    /// rustdoc JSON format version the items are encoded with
+    pub format_version: u32,
+    pub item_id: String,
+    pub depth: usize,
+    /// Raw `rustdoc_types::Item` objects keyed by ID, including the requested item
+    pub items: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+impl GetItemRawOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// Output from export_docs_markdown operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ExportDocsMarkdownOutput {
+    /// The rendered document, omitted when it was written to `output_path`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub markdown: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_path: Option<String>,
+    pub item_count: usize,
+    /// True if items were left out because of `max_items`
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+impl ExportDocsMarkdownOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// Output from export_items_ndjson operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ExportItemsNdjsonOutput {
+    pub output_path: String,
+    /// Number of item lines written after the header line
+    pub item_count: usize,
+    pub bytes_written: u64,
+}
+
+impl ExportItemsNdjsonOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// Output from get_changed_items_since operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ChangedItemsOutput {
+    pub since: String,
+    pub until: String,
+    /// Files changed between the commits, relative to the crate sources
+    pub changed_files: Vec<String>,
+    /// Items defined in the changed files
+    pub changed_items: Vec<ItemInfo>,
+}
+
+impl ChangedItemsOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// An item whose visibility changed between two versions of a crate
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct VisibilityChange {
+    /// `::`-separated path of the item
+    pub path: String,
+    pub kind: String,
+    /// Visibility in `version_a`
+    pub from: String,
+    /// Visibility in `version_b`
+    pub to: String,
+}
+
+/// Output from diff_visibility operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct DiffVisibilityOutput {
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    pub version_a: String,
+    pub version_b: String,
+    /// Items that are public in `version_b` but were not in `version_a`
+    pub newly_public: Vec<VisibilityChange>,
+    /// Items that were public in `version_a` but are not in `version_b`
+    pub newly_private: Vec<VisibilityChange>,
+}
+
+impl DiffVisibilityOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
 /// Generic error output for docs tools
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct DocsErrorOutput {
@@ -215,13 +717,18 @@ mod tests {
                 path: vec!["test".to_string()],
                 docs: Some("Test function".to_string()),
                 visibility: "public".to_string(),
+                field_type_id: None,
+                field_type_path: None,
+                hidden: false,
             }],
+            modules: None,
             pagination: PaginationInfo {
                 total: 1,
                 limit: 100,
                 offset: 0,
                 has_more: false,
             },
+            warning: None,
         };
 
         let json = output.to_json();
@@ -244,11 +751,18 @@ mod tests {
                 offset: 0,
                 has_more: false,
             },
+            timed_out: true,
+            warning: Some(SEARCH_TIMED_OUT_WARNING.to_string()),
         };
 
         let json = output.to_json();
         let deserialized: SearchItemsPreviewOutput = serde_json::from_str(&json).unwrap();
         assert_eq!(output, deserialized);
+
+        assert_eq!(
+            output.to_compact_text(),
+            format!("42  struct  my_mod::MyStruct\n# {SEARCH_TIMED_OUT_WARNING}")
+        );
     }
 
     #[test]
@@ -261,13 +775,20 @@ mod tests {
                 path: vec![],
                 docs: None,
                 visibility: "public".to_string(),
+                field_type_id: None,
+                field_type_path: None,
+                hidden: false,
             },
             signature: Some("fn test()".to_string()),
             generics: None,
+            generic_params: None,
             fields: None,
             variants: None,
             methods: None,
             source_location: None,
+            docsrs_url: None,
+            sealed: false,
+            sealed_note: None,
         }));
 
         assert!(success.is_success());
@@ -280,4 +801,52 @@ mod tests {
         assert!(!error.is_success());
         assert!(error.is_error());
     }
+
+    #[test]
+    fn test_detailed_item_retain_fields() {
+        let field = ItemInfo {
+            id: "2".to_string(),
+            name: "major".to_string(),
+            kind: "struct_field".to_string(),
+            path: vec![],
+            docs: None,
+            visibility: "public".to_string(),
+            field_type_id: None,
+            field_type_path: None,
+            hidden: false,
+        };
+        let mut item = DetailedItem {
+            info: ItemInfo {
+                id: "1".to_string(),
+                name: "Version".to_string(),
+                kind: "struct".to_string(),
+                path: vec!["semver".to_string()],
+                docs: Some("A version".to_string()),
+                visibility: "public".to_string(),
+                field_type_id: None,
+                field_type_path: None,
+                hidden: false,
+            },
+            signature: Some("pub struct Version".to_string()),
+            generics: Some(serde_json::json!({"params": []})),
+            generic_params: Some(Vec::new()),
+            fields: Some(vec![field.clone()]),
+            variants: None,
+            methods: Some(vec![field]),
+            source_location: None,
+            docsrs_url: None,
+            sealed: false,
+            sealed_note: None,
+        };
+
+        item.retain_fields(&["signature".to_string(), "docs".to_string()]);
+
+        assert_eq!(item.info.name, "Version");
+        assert_eq!(item.info.docs.as_deref(), Some("A version"));
+        assert_eq!(item.signature.as_deref(), Some("pub struct Version"));
+        assert!(item.generics.is_none());
+        assert!(item.generic_params.is_none());
+        assert!(item.fields.is_none());
+        assert!(item.methods.is_none());
+    }
 }
@@ -3,6 +3,12 @@ use rmcp::schemars;
 use rustdoc_types::{Crate, Id, Item, ItemEnum};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::docs::visibility::ItemVisibility;
 
 /// Query interface for rustdoc JSON data
 #[derive(Debug)]
@@ -19,6 +25,9 @@ pub struct ItemInfo {
     pub path: Vec<String>,
     pub docs: Option<String>,
     pub visibility: String,
+    /// Whether the item is marked `#[doc(hidden)]`, only listed in docs generated with hidden items
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hidden: bool,
 }
 
 /// Source location information
@@ -39,16 +48,109 @@ pub struct SourceInfo {
     pub context_lines: Option<usize>,
 }
 
+/// Where a type named in an item's signature is defined in the crate
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ReferencedDefinition {
+    pub id: String,
+    pub name: String,
+    pub filename: String,
+    pub line: usize,
+}
+
+/// A generic parameter of an item, with its default and bounds rendered as Rust syntax
+#[derive(Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct GenericParam {
+    pub name: String,
+    /// "lifetime", "type" or "const"
+    pub kind: String,
+    /// Type of a const parameter
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub default: Option<String>,
+    /// Bounds from the parameter list and the where clause
+    pub bounds: Vec<String>,
+}
+
 /// Detailed item information including signatures
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct DetailedItem {
     pub info: ItemInfo,
     pub signature: Option<String>,
     pub generics: Option<serde_json::Value>,
+    pub generic_params: Option<Vec<GenericParam>>,
     pub fields: Option<Vec<ItemInfo>>,
     pub variants: Option<Vec<ItemInfo>>,
     pub methods: Option<Vec<ItemInfo>>,
     pub source_location: Option<SourceLocation>,
+    /// Why a trait looks sealed, see [`DocQuery::sealed_reason`]
+    pub sealed_note: Option<String>,
+}
+
+/// A blanket trait implementation (e.g. `impl<T: Display> ToString for T`) that applies to a type
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct BlanketImplInfo {
+    /// ID of the impl block
+    pub impl_id: String,
+    pub trait_name: String,
+    /// Full path of the trait (e.g. `alloc::string::ToString`)
+    pub trait_path: String,
+    /// The generic type the impl is written for (e.g. `T`)
+    pub for_type: String,
+}
+
+/// Layout-relevant facts about a struct, enum or union
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ReprInfo {
+    /// The `repr` attribute, e.g. `#[repr(C)]`
+    pub repr: Option<String>,
+    /// Traits implemented through `#[derive(...)]`
+    pub derives: Vec<String>,
+    /// Which of `Copy`, `Clone`, `Send` and `Sync` the type implements
+    pub auto_traits: Vec<String>,
+    /// Field names in declaration order, or variant names for enums
+    pub fields: Vec<String>,
+}
+
+/// Traits reported by [`DocQuery::get_repr_info`], in reporting order
+const LAYOUT_TRAITS: [&str; 4] = ["Copy", "Clone", "Send", "Sync"];
+
+/// Where the type held by a field or newtype variant is defined
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldTypeLink {
+    /// An item of this crate, with its id
+    Local(u32),
+    /// A type from another crate, with its full path (e.g. `alloc::string::String`)
+    External(String),
+}
+
+/// A method callable on a type, tagged with the impl it comes from
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct MethodInfo {
+    pub name: String,
+    pub signature: Option<String>,
+    /// `inherent` or `trait <path>`
+    pub from: String,
+}
+
+/// Items matching a search pattern
+#[derive(Debug)]
+pub struct ItemSearch {
+    pub items: Vec<ItemInfo>,
+    /// Whether the time budget ran out before every item was scanned, so that
+    /// `items` holds only part of the matches
+    pub timed_out: bool,
+}
+
+/// Number of items scanned between two checks of the search time budget
+const BUDGET_CHECK_INTERVAL: usize = 1024;
+
+/// A supertrait of a trait
+#[derive(Debug, Clone, PartialEq)]
+pub struct SupertraitInfo {
+    pub id: u32,
+    pub path: String,
+    /// Path of the supertrait whose bounds name it, `None` for a direct supertrait
+    pub via: Option<String>,
 }
 
 impl DocQuery {
@@ -58,12 +160,12 @@ impl DocQuery {
     }
 
     /// List all items in the crate, optionally filtered by kind
-    pub fn list_items(&self, kind_filter: Option<&str>) -> Vec<ItemInfo> {
+    pub fn list_items(&self, kind_filter: Option<&[&str]>) -> Vec<ItemInfo> {
         let mut items = Vec::new();
 
         for (id, item) in &self.crate_data.index {
-            if let Some(filter) = &kind_filter
-                && self.get_item_kind_string(&item.inner) != *filter
+            if let Some(kinds) = kind_filter
+                && !kinds.contains(&self.get_item_kind_string(&item.inner).as_str())
             {
                 continue;
             }
@@ -78,12 +180,110 @@ impl DocQuery {
         items
     }
 
+    /// List the items whose span is in one of `files`, paths relative to the crate sources
+    pub fn items_in_files(&self, files: &std::collections::BTreeSet<String>) -> Vec<ItemInfo> {
+        let mut items: Vec<ItemInfo> = self
+            .crate_data
+            .index
+            .iter()
+            .filter(|(_, item)| {
+                item.span.as_ref().is_some_and(|span| {
+                    files.contains(&span.filename.to_string_lossy().replace('\\', "/"))
+                })
+            })
+            .filter_map(|(id, item)| self.item_to_info(id, item))
+            .collect();
+
+        items.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.name.cmp(&b.name)));
+        items
+    }
+
+    /// The visibility of every local item with a path, and of inherent methods as `Type::method`
+    ///
+    /// Items with the default visibility of trait items and enum variants are
+    /// left out, as their visibility follows their parent.
+    pub fn item_visibilities(&self) -> Vec<ItemVisibility> {
+        let mut items = Vec::new();
+        let mut push = |path: String, item: &Item| {
+            if !matches!(item.visibility, rustdoc_types::Visibility::Default) {
+                items.push(ItemVisibility {
+                    path,
+                    kind: self.get_item_kind_string(&item.inner),
+                    visibility: self.get_visibility_string(&item.visibility),
+                });
+            }
+        };
+
+        for (id, summary) in &self.crate_data.paths {
+            if summary.crate_id != 0 {
+                continue;
+            }
+            if let Some(item) = self.crate_data.index.get(id) {
+                push(summary.path.join("::"), item);
+            }
+        }
+
+        for item in self.crate_data.index.values() {
+            let ItemEnum::Impl(imp) = &item.inner else {
+                continue;
+            };
+            let rustdoc_types::Type::ResolvedPath(for_) = &imp.for_ else {
+                continue;
+            };
+            if imp.trait_.is_some() || imp.is_synthetic {
+                continue;
+            }
+            let Some(type_path) = self
+                .crate_data
+                .paths
+                .get(&for_.id)
+                .filter(|summary| summary.crate_id == 0)
+            else {
+                continue;
+            };
+            for method in imp
+                .items
+                .iter()
+                .filter_map(|id| self.crate_data.index.get(id))
+            {
+                if let Some(name) = &method.name {
+                    push(format!("{}::{name}", type_path.path.join("::")), method);
+                }
+            }
+        }
+
+        items
+    }
+
     /// Search for items by name pattern
+    ///
+    /// Results are ordered by relevance: exact name matches first, then prefix and
+    /// substring matches, with shorter names and shallower paths ranked higher.
     pub fn search_items(&self, pattern: &str) -> Vec<ItemInfo> {
+        self.search_items_within(pattern, None).items
+    }
+
+    /// Search for items by name pattern, giving up once `budget` has elapsed
+    ///
+    /// The scan is linear in the number of items, so a broad pattern on a huge
+    /// crate can take a while. When the budget runs out, the matches found so
+    /// far are returned, ranked like [`Self::search_items`], and `timed_out` is set.
+    pub fn search_items_within(&self, pattern: &str, budget: Option<Duration>) -> ItemSearch {
+        let start = Instant::now();
         let pattern_lower = pattern.to_lowercase();
         let mut items = Vec::new();
+        let mut timed_out = false;
+
+        for (scanned, (id, item)) in self.crate_data.index.iter().enumerate() {
+            if let Some(budget) = budget
+                && scanned > 0
+                && scanned % BUDGET_CHECK_INTERVAL == 0
+                && start.elapsed() >= budget
+            {
+                timed_out = true;
+                break;
+            }
 
-        for (id, item) in &self.crate_data.index {
             // First check if item has a direct name
             let item_name = if let Some(name) = &item.name {
                 Some(name.clone())
@@ -102,21 +302,29 @@ impl DocQuery {
             }
         }
 
-        items.sort_by(|a, b| {
-            // Sort by relevance (exact match first, then prefix match, then contains)
-            let a_exact = a.name.to_lowercase() == pattern_lower;
-            let b_exact = b.name.to_lowercase() == pattern_lower;
-            let a_prefix = a.name.to_lowercase().starts_with(&pattern_lower);
-            let b_prefix = b.name.to_lowercase().starts_with(&pattern_lower);
+        items.sort_by(|a, b| compare_relevance(a, b, pattern));
 
-            b_exact
-                .cmp(&a_exact)
-                .then_with(|| b_prefix.cmp(&a_prefix))
-                .then_with(|| a.name.len().cmp(&b.name.len()))
-                .then_with(|| a.name.cmp(&b.name))
-        });
+        ItemSearch { items, timed_out }
+    }
 
-        items
+    /// Collapse items that resolve to the same underlying item, keeping the shortest path
+    ///
+    /// Re-exports (`pub use`) resolve to the item they name, so a type and its
+    /// re-exports are reduced to a single entry.
+    pub fn dedupe_reexports(&self, items: Vec<ItemInfo>) -> Vec<ItemInfo> {
+        dedupe_by_target(items, |id| {
+            id.parse()
+                .map(|id| self.reexport_target(Id(id)).0.to_string())
+                .unwrap_or_else(|_| id.to_string())
+        })
+    }
+
+    /// The ID of the item a re-export names, or the ID itself for other items
+    fn reexport_target(&self, id: Id) -> Id {
+        match self.crate_data.index.get(&id).map(|item| &item.inner) {
+            Some(ItemEnum::Use(use_)) if !use_.is_glob => use_.id.unwrap_or(id),
+            _ => id,
+        }
     }
 
     /// Get detailed information about a specific item by ID
@@ -132,39 +340,571 @@ impl DocQuery {
             info,
             signature: self.get_item_signature(item),
             generics: None,
+            generic_params: None,
             fields: None,
             variants: None,
             methods: None,
             source_location: self.get_item_source_location(item),
+            sealed_note: None,
         };
 
         // Add type-specific information
-        match &item.inner {
+        let generics = match &item.inner {
             ItemEnum::Struct(s) => {
-                details.generics = serde_json::to_value(&s.generics).ok();
                 details.fields = Some(self.get_struct_fields(s));
+                Some(&s.generics)
             }
             ItemEnum::Enum(e) => {
-                details.generics = serde_json::to_value(&e.generics).ok();
                 details.variants = Some(self.get_enum_variants(e));
+                Some(&e.generics)
             }
             ItemEnum::Trait(t) => {
-                details.generics = serde_json::to_value(&t.generics).ok();
                 details.methods = Some(self.get_trait_items(&t.items));
+                details.sealed_note = self.sealed_reason(id);
+                Some(&t.generics)
             }
             ItemEnum::Impl(i) => {
-                details.generics = serde_json::to_value(&i.generics).ok();
                 details.methods = Some(self.get_impl_items(&i.items));
+                Some(&i.generics)
             }
-            ItemEnum::Function(f) => {
-                details.generics = serde_json::to_value(&f.generics).ok();
-            }
-            _ => {}
+            ItemEnum::Function(f) => Some(&f.generics),
+            _ => None,
+        };
+        if let Some(generics) = generics {
+            details.generics = serde_json::to_value(generics).ok();
+            details.generic_params = Some(describe_generic_params(generics));
         }
 
         Ok(details)
     }
 
+    /// Get the blanket trait impls that apply to a struct, enum or union
+    pub fn get_blanket_impls(&self, item_id: u32) -> Result<Vec<BlanketImplInfo>> {
+        let mut blanket_impls: Vec<BlanketImplInfo> = self
+            .get_type_impls(item_id)?
+            .into_iter()
+            .filter_map(|(impl_id, imp)| {
+                let blanket_type = imp.blanket_impl.as_ref()?;
+                let trait_ = imp.trait_.as_ref()?;
+                Some(BlanketImplInfo {
+                    impl_id: impl_id.0.to_string(),
+                    trait_name: trait_.path.rsplit("::").next()?.to_string(),
+                    trait_path: self.get_trait_path(trait_),
+                    for_type: match blanket_type {
+                        rustdoc_types::Type::Generic(name) => name.clone(),
+                        _ => "...".to_string(),
+                    },
+                })
+            })
+            .collect();
+
+        blanket_impls.sort_by(|a, b| a.trait_path.cmp(&b.trait_path));
+        Ok(blanket_impls)
+    }
+
+    /// Get the auto traits (e.g. `Send`, `Sync`) rustdoc synthesized impls for on a type
+    pub fn get_auto_trait_impls(&self, item_id: u32) -> Result<Vec<String>> {
+        let mut auto_traits: Vec<String> = self
+            .get_type_impls(item_id)?
+            .into_iter()
+            .filter(|(_, imp)| imp.is_synthetic && !imp.is_negative)
+            .filter_map(|(_, imp)| imp.trait_.as_ref().map(|t| self.get_trait_path(t)))
+            .collect();
+
+        auto_traits.sort();
+        Ok(auto_traits)
+    }
+
+    /// Get the `repr`, derives and layout-relevant traits of a struct, enum or union
+    ///
+    /// Derives are recognized by the `#[automatically_derived]` attribute
+    /// rustdoc keeps on derived impls. `Send` and `Sync` come from the auto
+    /// trait impls rustdoc synthesizes, `Copy` and `Clone` from any impl.
+    pub fn get_repr_info(&self, item_id: u32) -> Result<ReprInfo> {
+        let impls = self.get_type_impls(item_id)?;
+        let item = self
+            .crate_data
+            .index
+            .get(&Id(item_id))
+            .context("Item not found")?;
+
+        let mut derives = Vec::new();
+        let mut auto_traits = Vec::new();
+        for (impl_id, imp) in impls {
+            if imp.is_negative || imp.blanket_impl.is_some() {
+                continue;
+            }
+            let Some(trait_) = &imp.trait_ else {
+                continue;
+            };
+            let name = trait_.path.rsplit("::").next().unwrap_or(&trait_.path);
+            if LAYOUT_TRAITS.contains(&name) {
+                auto_traits.push(name.to_string());
+            }
+            // Deriving PartialEq also derives this compiler-internal marker
+            if !imp.is_synthetic
+                && name != "StructuralPartialEq"
+                && self
+                    .crate_data
+                    .index
+                    .get(impl_id)
+                    .is_some_and(is_automatically_derived)
+            {
+                derives.push(name.to_string());
+            }
+        }
+        auto_traits.sort_by_key(|name| LAYOUT_TRAITS.iter().position(|t| t == name));
+        auto_traits.dedup();
+        derives.sort();
+        derives.dedup();
+
+        let names = |ids: &[Id]| -> Vec<String> {
+            ids.iter()
+                .filter_map(|id| self.crate_data.index.get(id)?.name.clone())
+                .collect()
+        };
+        let fields = match &item.inner {
+            ItemEnum::Struct(s) => self
+                .get_struct_fields(s)
+                .into_iter()
+                .map(|field| field.name)
+                .collect(),
+            ItemEnum::Union(u) => names(&u.fields),
+            ItemEnum::Enum(e) => names(&e.variants),
+            _ => Vec::new(),
+        };
+
+        Ok(ReprInfo {
+            repr: repr_attribute(item),
+            derives,
+            auto_traits,
+            fields,
+        })
+    }
+
+    /// Get the raw rustdoc JSON of an item and of the items it references
+    ///
+    /// Child items (module items, fields, variants, impls, and trait or impl
+    /// members) are followed breadth-first up to `depth` levels below the item.
+    /// Entries are returned in visiting order, starting with the item itself.
+    pub fn get_item_raw(&self, item_id: u32, depth: usize) -> Result<Vec<(Id, serde_json::Value)>> {
+        let root = Id(item_id);
+        self.crate_data.index.get(&root).context("Item not found")?;
+
+        let mut visited = HashSet::from([root]);
+        let mut queue = VecDeque::from([(root, 0)]);
+        let mut entries = Vec::new();
+
+        while let Some((id, level)) = queue.pop_front() {
+            // Ids of items from other crates have no entry in the index
+            let Some(item) = self.crate_data.index.get(&id) else {
+                continue;
+            };
+            entries.push((id, serde_json::to_value(item)?));
+
+            if level < depth {
+                for child in child_ids(&item.inner) {
+                    if visited.insert(child) {
+                        queue.push_back((child, level + 1));
+                    }
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Get every method callable on a struct, enum or union
+    ///
+    /// Combines inherent methods with the methods of every trait the type
+    /// implements, including provided trait methods that the impl does not
+    /// override. Blanket impls are only included if `include_blanket` is set.
+    pub fn get_all_methods(&self, item_id: u32, include_blanket: bool) -> Result<Vec<MethodInfo>> {
+        let mut seen = HashSet::new();
+        let mut methods = Vec::new();
+
+        for (_, imp) in self.get_type_impls(item_id)? {
+            if imp.is_synthetic || imp.is_negative {
+                continue;
+            }
+            if imp.blanket_impl.is_some() && !include_blanket {
+                continue;
+            }
+
+            let from = match &imp.trait_ {
+                Some(trait_) => format!("trait {}", self.get_trait_path(trait_)),
+                None => "inherent".to_string(),
+            };
+
+            for method_id in &imp.items {
+                let Some(method) = self.crate_data.index.get(method_id) else {
+                    continue;
+                };
+                if let (Some(name), ItemEnum::Function(_)) = (&method.name, &method.inner)
+                    && seen.insert((name.clone(), from.clone()))
+                {
+                    methods.push(MethodInfo {
+                        name: name.clone(),
+                        signature: self.get_item_signature(method),
+                        from: from.clone(),
+                    });
+                }
+            }
+
+            // Default methods the impl does not override are only listed by name
+            if let Some(trait_) = &imp.trait_ {
+                for name in &imp.provided_trait_methods {
+                    if seen.insert((name.clone(), from.clone())) {
+                        methods.push(MethodInfo {
+                            name: name.clone(),
+                            signature: self.get_provided_method_signature(&trait_.id, name),
+                            from: from.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        methods.sort_by(|a, b| {
+            (a.from != "inherent")
+                .cmp(&(b.from != "inherent"))
+                .then_with(|| a.from.cmp(&b.from))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        Ok(methods)
+    }
+
+    /// Get the signature of a provided method from its trait, if the trait is documented locally
+    fn get_provided_method_signature(&self, trait_id: &Id, name: &str) -> Option<String> {
+        let ItemEnum::Trait(trait_) = &self.crate_data.index.get(trait_id)?.inner else {
+            return None;
+        };
+        trait_
+            .items
+            .iter()
+            .filter_map(|id| self.crate_data.index.get(id))
+            .find(|item| item.name.as_deref() == Some(name))
+            .and_then(|item| self.get_item_signature(item))
+    }
+
+    /// Get the impl blocks attached to a struct, enum or union
+    fn get_type_impls(&self, item_id: u32) -> Result<Vec<(&Id, &rustdoc_types::Impl)>> {
+        let id = Id(item_id);
+        let item = self.crate_data.index.get(&id).context("Item not found")?;
+
+        let impl_ids = match &item.inner {
+            ItemEnum::Struct(s) => &s.impls,
+            ItemEnum::Enum(e) => &e.impls,
+            ItemEnum::Union(u) => &u.impls,
+            _ => anyhow::bail!(
+                "Item is a {}, expected a struct, enum or union",
+                self.get_item_kind_string(&item.inner)
+            ),
+        };
+
+        Ok(impl_ids
+            .iter()
+            .filter_map(|impl_id| match &self.crate_data.index.get(impl_id)?.inner {
+                ItemEnum::Impl(imp) => Some((impl_id, imp)),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Get the full path of a trait, falling back to the path as written in the impl
+    fn get_trait_path(&self, trait_: &rustdoc_types::Path) -> String {
+        self.crate_data
+            .paths
+            .get(&trait_.id)
+            .map(|summary| summary.path.join("::"))
+            .unwrap_or_else(|| trait_.path.clone())
+    }
+
+    /// Get the supertraits of a trait, following each supertrait's own bounds
+    ///
+    /// Supertraits are the trait bounds on `Self`, written `trait Foo: Bar` or
+    /// `where Self: Bar`. Each is returned once, nearest first. Traits from
+    /// other crates are listed but their bounds are not in the docs, so they
+    /// are not followed.
+    pub fn get_supertraits(&self, item_id: u32) -> Result<Vec<SupertraitInfo>> {
+        let item = self
+            .crate_data
+            .index
+            .get(&Id(item_id))
+            .context("Item not found")?;
+        if !matches!(item.inner, ItemEnum::Trait(_)) {
+            anyhow::bail!(
+                "Item is a {}, expected a trait",
+                self.get_item_kind_string(&item.inner)
+            );
+        }
+
+        Ok(walk_supertraits(Id(item_id), |id| {
+            self.direct_supertraits(id)
+        }))
+    }
+
+    /// Why the trait `trait_id` looks sealed, i.e. cannot be implemented outside its crate
+    ///
+    /// A heuristic for the sealed-trait pattern: the trait requires, directly or
+    /// through its supertraits, a trait of this crate that other crates cannot
+    /// name because it is private, `#[doc(hidden)]` or in a private module.
+    /// Returns `None` if every supertrait is nameable.
+    pub fn sealed_reason(&self, trait_id: Id) -> Option<String> {
+        walk_supertraits(trait_id, |id| self.direct_supertraits(id))
+            .into_iter()
+            .find_map(|supertrait| {
+                let reason = self.unnameable_reason(Id(supertrait.id))?;
+                let via = supertrait
+                    .via
+                    .map(|via| format!(" through `{via}`"))
+                    .unwrap_or_default();
+                Some(format!(
+                    "Requires the supertrait `{}`{via}, which is {reason}, so other crates \
+                    cannot implement this trait (detected from the sealed-trait pattern)",
+                    supertrait.path
+                ))
+            })
+    }
+
+    /// Why other crates cannot name the trait `id` of this crate, or `None` if they can
+    fn unnameable_reason(&self, id: Id) -> Option<&'static str> {
+        if self
+            .crate_data
+            .paths
+            .get(&id)
+            .is_some_and(|summary| summary.crate_id != 0)
+        {
+            // Traits of other crates are not sealed by this one
+            return None;
+        }
+        match self.crate_data.index.get(&id) {
+            // Private items and items of private modules are left out of the docs
+            None => Some("not part of the public API"),
+            Some(item) if is_doc_hidden(item) => Some("#[doc(hidden)]"),
+            Some(item) if !matches!(item.visibility, rustdoc_types::Visibility::Public) => {
+                Some("private")
+            }
+            Some(_) => None,
+        }
+    }
+
+    /// The traits bounding `Self` in the declaration of a locally documented trait
+    fn direct_supertraits(&self, trait_id: Id) -> Vec<(Id, String)> {
+        use rustdoc_types::{GenericBound, Type, WherePredicate};
+        let Some(ItemEnum::Trait(trait_)) =
+            self.crate_data.index.get(&trait_id).map(|item| &item.inner)
+        else {
+            return Vec::new();
+        };
+
+        let self_bounds = trait_
+            .generics
+            .where_predicates
+            .iter()
+            .filter_map(|predicate| match predicate {
+                WherePredicate::BoundPredicate {
+                    type_: Type::Generic(name),
+                    bounds,
+                    ..
+                } if name == "Self" => Some(bounds),
+                _ => None,
+            });
+        std::iter::once(&trait_.bounds)
+            .chain(self_bounds)
+            .flatten()
+            .filter_map(|bound| match bound {
+                GenericBound::TraitBound { trait_, .. } => {
+                    Some((trait_.id, self.get_trait_path(trait_)))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Count the items gated by each distinct `cfg` predicate
+    ///
+    /// Predicates are read from the attributes rustdoc records on items, such
+    /// as `#[cfg(...)]` and `#[doc(cfg(...))]`. Items whose cfg was false when
+    /// the docs were generated are not in the docs at all. Results are sorted
+    /// by item count, most common first.
+    pub fn cfg_predicates(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for item in self.crate_data.index.values() {
+            let Ok(attrs) = serde_json::to_value(&item.attrs) else {
+                continue;
+            };
+            let mut strings = Vec::new();
+            collect_strings(&attrs, &mut strings);
+
+            let predicates: HashSet<String> = strings
+                .into_iter()
+                .flat_map(extract_cfg_predicates)
+                .collect();
+            for predicate in predicates {
+                *counts.entry(predicate).or_default() += 1;
+            }
+        }
+
+        let mut counts: Vec<_> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+
+    /// Restrict the crate to the items under the given modules
+    ///
+    /// `modules` are module paths with or without the crate name, e.g.
+    /// `regex::bytes` or `bytes`. Kept are the items under those modules with
+    /// their fields, variants, impls and members, the local items named in
+    /// their signatures, and the modules leading to them from the crate root.
+    /// Fails if a path names no module of the crate.
+    pub fn scoped_to_modules(&self, modules: &[String]) -> Result<Crate> {
+        let root_name = self
+            .crate_data
+            .index
+            .get(&self.crate_data.root)
+            .and_then(|root| root.name.clone())
+            .unwrap_or_default();
+
+        let mut seeds = Vec::new();
+        for module in modules {
+            let mut segments: Vec<&str> = module
+                .trim()
+                .trim_start_matches("::")
+                .split("::")
+                .filter(|segment| !segment.is_empty())
+                .collect();
+            if segments.first() != Some(&root_name.as_str()) {
+                segments.insert(0, &root_name);
+            }
+
+            let matched: Vec<Id> = self
+                .crate_data
+                .paths
+                .iter()
+                .filter(|(id, summary)| {
+                    summary.crate_id == 0
+                        && summary.path == segments
+                        && matches!(
+                            self.crate_data.index.get(id).map(|item| &item.inner),
+                            Some(ItemEnum::Module(_))
+                        )
+                })
+                .map(|(id, _)| *id)
+                .collect();
+            if matched.is_empty() {
+                anyhow::bail!("No module '{}' in the crate", segments.join("::"));
+            }
+            seeds.extend(matched);
+        }
+
+        // Everything owned by the selected modules
+        let mut kept: HashSet<Id> = HashSet::new();
+        let mut queue: VecDeque<Id> = seeds.into_iter().collect();
+        while let Some(id) = queue.pop_front() {
+            if !kept.insert(id) {
+                continue;
+            }
+            if let Some(item) = self.crate_data.index.get(&id) {
+                queue.extend(child_ids(&item.inner));
+            }
+        }
+
+        // Local items their signatures depend on
+        let mut dependencies = Vec::new();
+        for id in &kept {
+            if let Some(item) = self.crate_data.index.get(id) {
+                self.collect_signature_ids(item, &mut dependencies);
+            }
+        }
+        kept.extend(
+            dependencies
+                .into_iter()
+                .filter(|id| self.crate_data.index.contains_key(id)),
+        );
+
+        // Modules leading from the crate root to the kept items
+        let mut parents: HashMap<Id, Id> = HashMap::new();
+        for (id, item) in &self.crate_data.index {
+            if let ItemEnum::Module(module) = &item.inner {
+                for child in &module.items {
+                    parents.entry(*child).or_insert(*id);
+                }
+            }
+        }
+        let mut ancestors = HashSet::from([self.crate_data.root]);
+        for id in &kept {
+            let mut current = *id;
+            while let Some(&parent) = parents.get(&current)
+                && ancestors.insert(parent)
+            {
+                current = parent;
+            }
+        }
+        kept.extend(ancestors);
+
+        let mut scoped = self.crate_data.clone();
+        scoped.index.retain(|id, _| kept.contains(id));
+        scoped
+            .paths
+            .retain(|id, summary| summary.crate_id != 0 || kept.contains(id));
+        for item in scoped.index.values_mut() {
+            if let ItemEnum::Module(module) = &mut item.inner {
+                module.items.retain(|child| kept.contains(child));
+            }
+        }
+        Ok(scoped)
+    }
+
+    /// Resolve an item name or `::`-separated path to the IDs of matching items
+    ///
+    /// The path is matched against the trailing segments of each local item path, so
+    /// `semver::Version`, `Version` and `::semver::Version` all resolve the same item.
+    pub fn resolve_path(&self, path: &str) -> Vec<u32> {
+        let segments: Vec<&str> = path
+            .trim()
+            .trim_start_matches("::")
+            .split("::")
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        if segments.is_empty() {
+            return Vec::new();
+        }
+
+        let mut ids: Vec<u32> = self
+            .crate_data
+            .paths
+            .iter()
+            .filter(|(id, summary)| {
+                // Only items defined in this crate have entries in the index
+                summary.crate_id == 0
+                    && self.crate_data.index.contains_key(*id)
+                    && summary.path.len() >= segments.len()
+                    && summary.path[summary.path.len() - segments.len()..]
+                        .iter()
+                        .zip(&segments)
+                        .all(|(a, b)| a == b)
+            })
+            .map(|(id, _)| id.0)
+            .collect();
+
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Get summary information for a specific item
+    pub fn get_item_info(&self, item_id: u32) -> Option<ItemInfo> {
+        let id = Id(item_id);
+        let item = self.crate_data.index.get(&id)?;
+        self.item_to_info(&id, item)
+    }
+
+    /// Get the signature of an item, if one can be rendered for its kind
+    pub fn get_item_signature_by_id(&self, item_id: u32) -> Option<String> {
+        let item = self.crate_data.index.get(&Id(item_id))?;
+        self.get_item_signature(item)
+    }
+
     /// Get documentation for a specific item
     pub fn get_item_docs(&self, item_id: u32) -> Result<Option<String>> {
         let id = Id(item_id);
@@ -173,6 +913,38 @@ impl DocQuery {
         Ok(item.docs.clone())
     }
 
+    /// Get the docs of the trait method that an undocumented impl method implements
+    ///
+    /// Mirrors rustdoc HTML, which shows the trait's docs for trait impl methods
+    /// that have none of their own. Only traits documented in this crate can be
+    /// resolved.
+    pub fn get_trait_default_docs(&self, item_id: u32) -> Option<String> {
+        let id = Id(item_id);
+        let name = self.crate_data.index.get(&id)?.name.as_deref()?;
+
+        let trait_id = self
+            .crate_data
+            .index
+            .values()
+            .find_map(|item| match &item.inner {
+                ItemEnum::Impl(imp) if imp.items.contains(&id) => {
+                    imp.trait_.as_ref().map(|t| &t.id)
+                }
+                _ => None,
+            })?;
+        let ItemEnum::Trait(trait_) = &self.crate_data.index.get(trait_id)?.inner else {
+            return None;
+        };
+
+        trait_
+            .items
+            .iter()
+            .filter_map(|id| self.crate_data.index.get(id))
+            .find(|item| item.name.as_deref() == Some(name))
+            .and_then(|item| item.docs.clone())
+            .filter(|docs| !docs.trim().is_empty())
+    }
+
     /// Helper to convert an Item to ItemInfo
     fn item_to_info(&self, id: &Id, item: &Item) -> Option<ItemInfo> {
         // Get name from item or from paths
@@ -195,6 +967,7 @@ impl DocQuery {
             path,
             docs: item.docs.clone(),
             visibility,
+            hidden: is_doc_hidden(item),
         })
     }
 
@@ -310,6 +1083,7 @@ impl DocQuery {
                             path: Vec::new(),
                             docs: None,
                             visibility: "private".to_string(),
+                            hidden: false,
                         })
                     }
                 })
@@ -334,6 +1108,7 @@ impl DocQuery {
                         path: Vec::new(),
                         docs: None,
                         visibility: "private".to_string(),
+                        hidden: false,
                     });
                 }
 
@@ -361,6 +1136,7 @@ impl DocQuery {
                 path: Vec::new(),
                 docs: None,
                 visibility: "private".to_string(),
+                hidden: false,
             });
         }
 
@@ -389,6 +1165,37 @@ impl DocQuery {
             .collect()
     }
 
+    /// Where the type of a field, or of the single field of a tuple variant, is defined
+    ///
+    /// References, pointers, slices and arrays are looked through, so a field
+    /// of type `&[Config]` links to `Config`. Generic and primitive types, and
+    /// variants without exactly one field, link to nothing.
+    pub fn get_field_type_link(&self, item_id: u32) -> Option<FieldTypeLink> {
+        use rustdoc_types::VariantKind;
+        let ty = match &self.crate_data.index.get(&Id(item_id))?.inner {
+            ItemEnum::StructField(ty) => ty,
+            ItemEnum::Variant(variant) => match &variant.kind {
+                VariantKind::Tuple(fields) => match fields.as_slice() {
+                    [Some(field_id)] => match &self.crate_data.index.get(field_id)?.inner {
+                        ItemEnum::StructField(ty) => ty,
+                        _ => return None,
+                    },
+                    _ => return None,
+                },
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        let id = named_type_id(ty)?;
+        if self.crate_data.index.contains_key(&id) {
+            Some(FieldTypeLink::Local(id.0))
+        } else {
+            let summary = self.crate_data.paths.get(&id)?;
+            Some(FieldTypeLink::External(summary.path.join("::")))
+        }
+    }
+
     /// Get source location information for an item
     fn get_item_source_location(&self, item: &Item) -> Option<SourceLocation> {
         let span = item.span.as_ref()?;
@@ -401,48 +1208,1210 @@ impl DocQuery {
         })
     }
 
-    /// Get source code for a specific item by ID
-    pub fn get_item_source(
-        &self,
-        item_id: u32,
-        base_path: &std::path::Path,
-        context_lines: usize,
+    /// Find the in-crate definitions of the types named in an item's signature
+    ///
+    /// Covers function parameters and return types, generic bounds, field and
+    /// variant types, aliased types and the types of an impl. Types defined in
+    /// other crates have no span in this crate's docs and are left out.
+    pub fn get_item_references(&self, item_id: u32) -> Vec<ReferencedDefinition> {
+        let id = Id(item_id);
+        let Some(item) = self.crate_data.index.get(&id) else {
+            return Vec::new();
+        };
+
+        let mut ids = Vec::new();
+        self.collect_signature_ids(item, &mut ids);
+
+        let mut seen = HashSet::from([id]);
+        ids.into_iter()
+            .filter(|id| seen.insert(*id))
+            .filter_map(|id| {
+                let item = self.crate_data.index.get(&id)?;
+                let span = item.span.as_ref()?;
+                let name = item
+                    .name
+                    .clone()
+                    .or_else(|| self.crate_data.paths.get(&id)?.path.last().cloned())?;
+                Some(ReferencedDefinition {
+                    id: id.0.to_string(),
+                    name,
+                    filename: span.filename.to_string_lossy().to_string(),
+                    line: span.begin.0,
+                })
+            })
+            .collect()
+    }
+
+    /// Collect the ids of the types named in an item's signature, in order
+    fn collect_signature_ids(&self, item: &Item, ids: &mut Vec<Id>) {
+        use rustdoc_types::{StructKind, VariantKind};
+
+        // Fields and variants are items of their own, referenced by id
+        let collect_field_ids = |fields: &[Id], ids: &mut Vec<Id>| {
+            for field_id in fields {
+                if let Some(ItemEnum::StructField(ty)) = self
+                    .crate_data
+                    .index
+                    .get(field_id)
+                    .map(|field| &field.inner)
+                {
+                    collect_type_ids(ty, ids);
+                }
+            }
+        };
+
+        match &item.inner {
+            ItemEnum::Function(f) => {
+                collect_generics_ids(&f.generics, ids);
+                for (_, ty) in &f.sig.inputs {
+                    collect_type_ids(ty, ids);
+                }
+                if let Some(output) = &f.sig.output {
+                    collect_type_ids(output, ids);
+                }
+            }
+            ItemEnum::Struct(s) => {
+                collect_generics_ids(&s.generics, ids);
+                match &s.kind {
+                    StructKind::Unit => {}
+                    StructKind::Tuple(fields) => {
+                        let fields: Vec<Id> = fields.iter().flatten().copied().collect();
+                        collect_field_ids(&fields, ids);
+                    }
+                    StructKind::Plain { fields, .. } => collect_field_ids(fields, ids),
+                }
+            }
+            ItemEnum::Union(u) => {
+                collect_generics_ids(&u.generics, ids);
+                collect_field_ids(&u.fields, ids);
+            }
+            ItemEnum::Enum(e) => {
+                collect_generics_ids(&e.generics, ids);
+                for variant_id in &e.variants {
+                    let Some(ItemEnum::Variant(variant)) =
+                        self.crate_data.index.get(variant_id).map(|v| &v.inner)
+                    else {
+                        continue;
+                    };
+                    match &variant.kind {
+                        VariantKind::Plain => {}
+                        VariantKind::Tuple(fields) => {
+                            let fields: Vec<Id> = fields.iter().flatten().copied().collect();
+                            collect_field_ids(&fields, ids);
+                        }
+                        VariantKind::Struct { fields, .. } => collect_field_ids(fields, ids),
+                    }
+                }
+            }
+            ItemEnum::StructField(ty) => collect_type_ids(ty, ids),
+            ItemEnum::TypeAlias(alias) => {
+                collect_generics_ids(&alias.generics, ids);
+                collect_type_ids(&alias.type_, ids);
+            }
+            ItemEnum::Constant { type_, .. } => collect_type_ids(type_, ids),
+            ItemEnum::Static(s) => collect_type_ids(&s.type_, ids),
+            ItemEnum::Trait(t) => {
+                collect_generics_ids(&t.generics, ids);
+                collect_bound_ids(&t.bounds, ids);
+            }
+            ItemEnum::Impl(imp) => {
+                collect_generics_ids(&imp.generics, ids);
+                if let Some(trait_) = &imp.trait_ {
+                    collect_path_ids(trait_, ids);
+                }
+                collect_type_ids(&imp.for_, ids);
+            }
+            _ => {}
+        }
+    }
+
+    /// Get source code for a specific item by ID
+    pub fn get_item_source(
+        &self,
+        item_id: u32,
+        base_path: &std::path::Path,
+        context_lines: usize,
     ) -> Result<SourceInfo> {
         let id = Id(item_id);
         let item = self.crate_data.index.get(&id).context("Item not found")?;
+        item_source(item, base_path, context_lines)
+    }
+}
+
+/// Read the source code of an item from the crate sources at `base_path`
+pub fn item_source(
+    item: &Item,
+    base_path: &std::path::Path,
+    context_lines: usize,
+) -> Result<SourceInfo> {
+    let span = item.span.as_ref().context("Item has no source span")?;
+    let source_path = resolve_source_path(base_path, &span.filename)?;
+
+    if !source_path.exists() {
+        anyhow::bail!("Source file not found: {}", source_path.display());
+    }
+
+    let content = std::fs::read_to_string(&source_path)
+        .with_context(|| format!("Failed to read source file: {}", source_path.display()))?;
+
+    // `lines` strips the `\r` of CRLF line endings, so spans count lines the
+    // same way for files authored on Windows
+    let lines: Vec<&str> = content.lines().collect();
+    let code = source_window(&lines, span.begin.0, span.end.0, context_lines);
+
+    Ok(SourceInfo {
+        location: SourceLocation {
+            filename: span.filename.to_string_lossy().to_string(),
+            line_start: span.begin.0,
+            column_start: span.begin.1,
+            line_end: span.end.0,
+            column_end: span.end.1,
+        },
+        code,
+        context_lines: Some(context_lines),
+    })
+}
+
+/// Path of a span's source file under the crate sources at `base_path`
+///
+/// Spans keep the separators of the machine the docs were built on, so both `/`
+/// and `\` separate components. The path may not leave `base_path`: `..` is
+/// rejected, and an absolute path is only accepted if it points inside it.
+pub fn resolve_source_path(base_path: &Path, filename: &Path) -> Result<PathBuf> {
+    let filename = if filename.is_absolute() {
+        filename.strip_prefix(base_path).map_err(|_| {
+            anyhow::anyhow!(
+                "Source path {} is outside of the crate sources",
+                filename.display()
+            )
+        })?
+    } else {
+        filename
+    };
+
+    let raw = filename.to_string_lossy();
+    let has_drive = raw.len() >= 2 && raw.as_bytes()[1] == b':';
+    if raw.starts_with(['/', '\\']) || has_drive {
+        anyhow::bail!("Source path {raw} is outside of the crate sources");
+    }
+
+    let mut path = base_path.to_path_buf();
+    for part in raw.split(['/', '\\']) {
+        match Path::new(part).components().next() {
+            None | Some(Component::CurDir) => {}
+            Some(Component::Normal(_)) => path.push(part),
+            Some(_) => anyhow::bail!("Source path {raw} is outside of the crate sources"),
+        }
+    }
+    Ok(path)
+}
+
+/// The 1-based lines `line_start..=line_end` of a file with `context_lines` around them
+pub fn source_window(
+    lines: &[&str],
+    line_start: usize,
+    line_end: usize,
+    context_lines: usize,
+) -> String {
+    let start = line_start.saturating_sub(1).saturating_sub(context_lines);
+    let end = std::cmp::min(line_end + context_lines, lines.len());
+    lines.get(start..end).unwrap_or_default().join("\n")
+}
+
+/// Collect the ids of the items a type refers to, including its generic arguments
+fn collect_type_ids(ty: &rustdoc_types::Type, ids: &mut Vec<Id>) {
+    use rustdoc_types::Type;
+    match ty {
+        Type::ResolvedPath(path) => collect_path_ids(path, ids),
+        Type::DynTrait(dyn_trait) => {
+            for poly_trait in &dyn_trait.traits {
+                collect_path_ids(&poly_trait.trait_, ids);
+            }
+        }
+        Type::FunctionPointer(fn_ptr) => {
+            for (_, input) in &fn_ptr.sig.inputs {
+                collect_type_ids(input, ids);
+            }
+            if let Some(output) = &fn_ptr.sig.output {
+                collect_type_ids(output, ids);
+            }
+        }
+        Type::Tuple(types) => {
+            for ty in types {
+                collect_type_ids(ty, ids);
+            }
+        }
+        Type::Slice(ty)
+        | Type::Array { type_: ty, .. }
+        | Type::RawPointer { type_: ty, .. }
+        | Type::BorrowedRef { type_: ty, .. } => collect_type_ids(ty, ids),
+        Type::ImplTrait(bounds) => collect_bound_ids(bounds, ids),
+        Type::QualifiedPath {
+            self_type, trait_, ..
+        } => {
+            collect_type_ids(self_type, ids);
+            if let Some(trait_) = trait_ {
+                collect_path_ids(trait_, ids);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The id of the type a type names, looking through references, pointers, slices and arrays
+fn named_type_id(ty: &rustdoc_types::Type) -> Option<Id> {
+    use rustdoc_types::Type;
+    match ty {
+        Type::ResolvedPath(path) => Some(path.id),
+        Type::Slice(ty)
+        | Type::Array { type_: ty, .. }
+        | Type::RawPointer { type_: ty, .. }
+        | Type::BorrowedRef { type_: ty, .. } => named_type_id(ty),
+        _ => None,
+    }
+}
+
+/// Collect the id of a path and of the types in its generic arguments
+fn collect_path_ids(path: &rustdoc_types::Path, ids: &mut Vec<Id>) {
+    use rustdoc_types::{GenericArg, GenericArgs};
+    ids.push(path.id);
+    match path.args.as_deref() {
+        Some(GenericArgs::AngleBracketed { args, .. }) => {
+            for arg in args {
+                if let GenericArg::Type(ty) = arg {
+                    collect_type_ids(ty, ids);
+                }
+            }
+        }
+        Some(GenericArgs::Parenthesized { inputs, output }) => {
+            for input in inputs {
+                collect_type_ids(input, ids);
+            }
+            if let Some(output) = output {
+                collect_type_ids(output, ids);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Breadth-first walk of the supertraits of `root`, visiting each trait once
+///
+/// `direct_supertraits` returns the id and path of the supertraits a trait
+/// declares. Trait cycles are rejected by the compiler, but the walk guards
+/// against them anyway, and against revisiting shared supertraits.
+fn walk_supertraits(
+    root: Id,
+    direct_supertraits: impl Fn(Id) -> Vec<(Id, String)>,
+) -> Vec<SupertraitInfo> {
+    let mut visited = HashSet::from([root]);
+    let mut queue = VecDeque::from([(root, None)]);
+    let mut supertraits = Vec::new();
+
+    while let Some((id, via)) = queue.pop_front() {
+        for (supertrait, path) in direct_supertraits(id) {
+            if !visited.insert(supertrait) {
+                continue;
+            }
+            supertraits.push(SupertraitInfo {
+                id: supertrait.0,
+                path: path.clone(),
+                via: via.clone(),
+            });
+            queue.push_back((supertrait, Some(path)));
+        }
+    }
+    supertraits
+}
+
+/// Collect the ids of the traits in a list of bounds
+fn collect_bound_ids(bounds: &[rustdoc_types::GenericBound], ids: &mut Vec<Id>) {
+    for bound in bounds {
+        if let rustdoc_types::GenericBound::TraitBound { trait_, .. } = bound {
+            collect_path_ids(trait_, ids);
+        }
+    }
+}
+
+/// Collect the ids of the traits and types in generic parameters and where clauses
+fn collect_generics_ids(generics: &rustdoc_types::Generics, ids: &mut Vec<Id>) {
+    use rustdoc_types::{GenericParamDefKind, WherePredicate};
+    for param in &generics.params {
+        if let GenericParamDefKind::Type {
+            bounds, default, ..
+        } = &param.kind
+        {
+            collect_bound_ids(bounds, ids);
+            if let Some(default) = default {
+                collect_type_ids(default, ids);
+            }
+        }
+    }
+    for predicate in &generics.where_predicates {
+        if let WherePredicate::BoundPredicate { type_, bounds, .. } = predicate {
+            collect_type_ids(type_, ids);
+            collect_bound_ids(bounds, ids);
+        }
+    }
+}
+
+/// Describe each generic parameter with its kind, default and bounds
+///
+/// Bounds from the where clause are merged into the parameter they constrain.
+/// Synthetic parameters created for `impl Trait` arguments are left out.
+fn describe_generic_params(generics: &rustdoc_types::Generics) -> Vec<GenericParam> {
+    use rustdoc_types::{GenericParamDefKind, Type, WherePredicate};
+
+    let mut params: Vec<GenericParam> = generics
+        .params
+        .iter()
+        .filter_map(|param| {
+            let (kind, type_, default, bounds) = match &param.kind {
+                GenericParamDefKind::Lifetime { outlives } => {
+                    ("lifetime", None, None, outlives.clone())
+                }
+                GenericParamDefKind::Type {
+                    is_synthetic: true, ..
+                } => return None,
+                GenericParamDefKind::Type {
+                    bounds, default, ..
+                } => (
+                    "type",
+                    None,
+                    default.as_ref().map(render_type),
+                    bounds.iter().map(render_bound).collect(),
+                ),
+                GenericParamDefKind::Const { type_, default } => (
+                    "const",
+                    Some(render_type(type_)),
+                    default.clone(),
+                    Vec::new(),
+                ),
+            };
+            Some(GenericParam {
+                name: param.name.clone(),
+                kind: kind.to_string(),
+                type_,
+                default,
+                bounds,
+            })
+        })
+        .collect();
+
+    for predicate in &generics.where_predicates {
+        let (name, bounds) = match predicate {
+            WherePredicate::BoundPredicate {
+                type_: Type::Generic(name),
+                bounds,
+                ..
+            } => (name, bounds.iter().map(render_bound).collect::<Vec<_>>()),
+            WherePredicate::LifetimePredicate { lifetime, outlives } => {
+                (lifetime, outlives.clone())
+            }
+            _ => continue,
+        };
+        if let Some(param) = params.iter_mut().find(|param| &param.name == name) {
+            param.bounds.extend(bounds);
+        }
+    }
+
+    params
+}
+
+/// Render a type as Rust syntax, e.g. `Option<&'a str>`
+fn render_type(ty: &rustdoc_types::Type) -> String {
+    use rustdoc_types::Type;
+    match ty {
+        Type::ResolvedPath(path) => render_path(path),
+        Type::DynTrait(dyn_trait) => {
+            let mut bounds: Vec<String> = dyn_trait
+                .traits
+                .iter()
+                .map(|poly_trait| render_path(&poly_trait.trait_))
+                .collect();
+            bounds.extend(dyn_trait.lifetime.clone());
+            format!("dyn {}", bounds.join(" + "))
+        }
+        Type::Generic(name) | Type::Primitive(name) => name.clone(),
+        Type::FunctionPointer(fn_ptr) => {
+            let inputs: Vec<String> = fn_ptr
+                .sig
+                .inputs
+                .iter()
+                .map(|(_, input)| render_type(input))
+                .collect();
+            let output = fn_ptr
+                .sig
+                .output
+                .as_ref()
+                .map(|output| format!(" -> {}", render_type(output)))
+                .unwrap_or_default();
+            format!("fn({}){output}", inputs.join(", "))
+        }
+        Type::Tuple(types) if types.len() == 1 => format!("({},)", render_type(&types[0])),
+        Type::Tuple(types) => {
+            let types: Vec<String> = types.iter().map(render_type).collect();
+            format!("({})", types.join(", "))
+        }
+        Type::Slice(ty) => format!("[{}]", render_type(ty)),
+        Type::Array { type_, len } => format!("[{}; {len}]", render_type(type_)),
+        Type::Pat { type_, .. } => render_type(type_),
+        Type::ImplTrait(bounds) => format!("impl {}", render_bounds(bounds)),
+        Type::Infer => "_".to_string(),
+        Type::RawPointer { is_mutable, type_ } => {
+            let mutability = if *is_mutable { "mut" } else { "const" };
+            format!("*{mutability} {}", render_type(type_))
+        }
+        Type::BorrowedRef {
+            lifetime,
+            is_mutable,
+            type_,
+        } => {
+            let lifetime = lifetime
+                .as_ref()
+                .map(|lifetime| format!("{lifetime} "))
+                .unwrap_or_default();
+            let mutability = if *is_mutable { "mut " } else { "" };
+            format!("&{lifetime}{mutability}{}", render_type(type_))
+        }
+        Type::QualifiedPath {
+            name,
+            self_type,
+            trait_,
+            ..
+        } => match trait_ {
+            Some(trait_) => format!(
+                "<{} as {}>::{name}",
+                render_type(self_type),
+                render_path(trait_)
+            ),
+            None => format!("{}::{name}", render_type(self_type)),
+        },
+    }
+}
+
+/// Render a path with its generic arguments, e.g. `Vec<u8>`
+fn render_path(path: &rustdoc_types::Path) -> String {
+    use rustdoc_types::{AssocItemConstraintKind, GenericArg, GenericArgs, Term};
 
-        let span = item.span.as_ref().context("Item has no source span")?;
-        let source_path = base_path.join(&span.filename);
+    let args = match path.args.as_deref() {
+        Some(GenericArgs::AngleBracketed { args, constraints }) => {
+            let args = args.iter().map(|arg| match arg {
+                GenericArg::Lifetime(lifetime) => lifetime.clone(),
+                GenericArg::Type(ty) => render_type(ty),
+                GenericArg::Const(constant) => constant.expr.clone(),
+                GenericArg::Infer => "_".to_string(),
+            });
+            let constraints = constraints
+                .iter()
+                .map(|constraint| match &constraint.binding {
+                    AssocItemConstraintKind::Equality(Term::Type(ty)) => {
+                        format!("{} = {}", constraint.name, render_type(ty))
+                    }
+                    AssocItemConstraintKind::Equality(Term::Constant(constant)) => {
+                        format!("{} = {}", constraint.name, constant.expr)
+                    }
+                    AssocItemConstraintKind::Constraint(bounds) => {
+                        format!("{}: {}", constraint.name, render_bounds(bounds))
+                    }
+                });
+            let args: Vec<String> = args.chain(constraints).collect();
+            if args.is_empty() {
+                String::new()
+            } else {
+                format!("<{}>", args.join(", "))
+            }
+        }
+        Some(GenericArgs::Parenthesized { inputs, output }) => {
+            let inputs: Vec<String> = inputs.iter().map(render_type).collect();
+            let output = output
+                .as_ref()
+                .map(|output| format!(" -> {}", render_type(output)))
+                .unwrap_or_default();
+            format!("({}){output}", inputs.join(", "))
+        }
+        Some(GenericArgs::ReturnTypeNotation) => "(..)".to_string(),
+        None => String::new(),
+    };
+    format!("{}{args}", path.path)
+}
+
+/// Render a bound, e.g. `?Sized`, `'a` or `for<'a> Fn(&'a str)`
+fn render_bound(bound: &rustdoc_types::GenericBound) -> String {
+    use rustdoc_types::{GenericBound, TraitBoundModifier};
+    match bound {
+        GenericBound::TraitBound {
+            trait_,
+            generic_params,
+            modifier,
+        } => {
+            let binder = if generic_params.is_empty() {
+                String::new()
+            } else {
+                let names: Vec<&str> = generic_params
+                    .iter()
+                    .map(|param| param.name.as_str())
+                    .collect();
+                format!("for<{}> ", names.join(", "))
+            };
+            let modifier = match modifier {
+                TraitBoundModifier::None => "",
+                TraitBoundModifier::Maybe => "?",
+                TraitBoundModifier::MaybeConst => "~const ",
+            };
+            format!("{binder}{modifier}{}", render_path(trait_))
+        }
+        GenericBound::Outlives(lifetime) => lifetime.clone(),
+        GenericBound::Use(_) => "use<..>".to_string(),
+    }
+}
+
+/// Render bounds joined with `+`
+fn render_bounds(bounds: &[rustdoc_types::GenericBound]) -> String {
+    let bounds: Vec<String> = bounds.iter().map(render_bound).collect();
+    bounds.join(" + ")
+}
+
+/// Collect every string in a JSON value
+fn collect_strings<'a>(value: &'a serde_json::Value, strings: &mut Vec<&'a str>) {
+    match value {
+        serde_json::Value::String(string) => strings.push(string),
+        serde_json::Value::Array(values) => {
+            for value in values {
+                collect_strings(value, strings);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for value in map.values() {
+                collect_strings(value, strings);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Whether an item is marked `#[doc(hidden)]`
+///
+/// Such items are only in docs generated with `--document-hidden-items`.
+pub fn is_doc_hidden(item: &Item) -> bool {
+    let Ok(attrs) = serde_json::to_value(&item.attrs) else {
+        return false;
+    };
+    let mut strings = Vec::new();
+    collect_strings(&attrs, &mut strings);
+    strings.into_iter().any(|attr| {
+        let attr: String = attr.chars().filter(|c| !c.is_whitespace()).collect();
+        attr.contains("doc(hidden)")
+    })
+}
+
+/// Whether an impl was generated by `#[derive(...)]`
+fn is_automatically_derived(item: &Item) -> bool {
+    let Ok(attrs) = serde_json::to_value(&item.attrs) else {
+        return false;
+    };
+    let mut strings = Vec::new();
+    collect_strings(&attrs, &mut strings);
+    strings
+        .into_iter()
+        .any(|attr| attr.contains("automatically_derived"))
+}
+
+/// The `repr` attribute of an item, e.g. `#[repr(C, u8)]`
+fn repr_attribute(item: &Item) -> Option<String> {
+    let attrs = serde_json::to_value(&item.attrs).ok()?;
+    attrs.as_array()?.iter().find_map(describe_repr)
+}
 
-        if !source_path.exists() {
-            anyhow::bail!("Source file not found: {}", source_path.display());
+/// Render one rustdoc attribute as a `repr` attribute, if it is one
+///
+/// Older format versions record attributes as written, e.g. `#[repr(C)]`;
+/// newer ones as `{"repr": {"kind": "c", "int": "u8", "align": 8, ...}}`.
+fn describe_repr(attr: &serde_json::Value) -> Option<String> {
+    if let Some(attr) = attr.as_str() {
+        let start = attr.find("repr(")?;
+        let end = attr.rfind(')')?;
+        return (end > start).then(|| format!("#[{}]", &attr[start..=end]));
+    }
+
+    let repr = attr.get("repr")?;
+    let mut parts = Vec::new();
+    match repr.get("kind").and_then(|kind| kind.as_str()) {
+        Some("c") => parts.push("C".to_string()),
+        Some("rust") | None => {}
+        Some(kind) => parts.push(kind.to_string()),
+    }
+    if let Some(int) = repr.get("int").and_then(|int| int.as_str()) {
+        parts.push(int.to_string());
+    }
+    if let Some(align) = repr.get("align").and_then(|align| align.as_u64()) {
+        parts.push(format!("align({align})"));
+    }
+    match repr.get("packed").and_then(|packed| packed.as_u64()) {
+        Some(1) => parts.push("packed".to_string()),
+        Some(packed) => parts.push(format!("packed({packed})")),
+        None => {}
+    }
+    if parts.is_empty() {
+        parts.push("Rust".to_string());
+    }
+    Some(format!("#[repr({})]", parts.join(", ")))
+}
+
+/// Extract the predicates of the `cfg(...)` clauses in an attribute
+///
+/// Matches `#[cfg(...)]` as well as `cfg` nested in other attributes such as
+/// `#[doc(cfg(...))]`, but not `cfg_attr`, which does not gate the item.
+/// Whitespace in predicates is normalized.
+fn extract_cfg_predicates(attr: &str) -> Vec<String> {
+    let mut predicates = Vec::new();
+    let mut rest = attr;
+    while let Some(start) = rest.find("cfg(") {
+        let preceded_by_ident = rest[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_');
+        let after = &rest[start + "cfg(".len()..];
+        if preceded_by_ident {
+            rest = after;
+            continue;
+        }
+
+        let mut depth = 1;
+        let mut in_string = false;
+        let mut end = None;
+        for (i, c) in after.char_indices() {
+            match c {
+                '"' => in_string = !in_string,
+                '(' if !in_string => depth += 1,
+                ')' if !in_string => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = Some(i);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let Some(end) = end else {
+            break;
+        };
+
+        let predicate = after[..end]
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !predicate.is_empty() {
+            predicates.push(predicate);
         }
+        rest = &after[end + 1..];
+    }
+    predicates
+}
 
-        let content = std::fs::read_to_string(&source_path)
-            .with_context(|| format!("Failed to read source file: {}", source_path.display()))?;
+/// How closely an item name matches a search pattern, lower is better
+fn match_rank(name: &str, pattern: &str) -> u8 {
+    let name_lower = name.to_lowercase();
+    let pattern_lower = pattern.to_lowercase();
+    if name == pattern {
+        0
+    } else if name_lower == pattern_lower {
+        1
+    } else if name_lower.starts_with(&pattern_lower) {
+        2
+    } else {
+        3
+    }
+}
 
-        let lines: Vec<&str> = content.lines().collect();
+/// Order two search results by relevance to `pattern`
+///
+/// Ties are broken by name length, path depth, name, path and finally id, so
+/// results are returned in the same order on every call.
+fn compare_relevance(a: &ItemInfo, b: &ItemInfo, pattern: &str) -> std::cmp::Ordering {
+    match_rank(&a.name, pattern)
+        .cmp(&match_rank(&b.name, pattern))
+        .then_with(|| a.name.len().cmp(&b.name.len()))
+        .then_with(|| a.path.len().cmp(&b.path.len()))
+        .then_with(|| a.name.cmp(&b.name))
+        .then_with(|| a.path.cmp(&b.path))
+        .then_with(|| {
+            let a_id = a.id.parse::<u32>().unwrap_or(u32::MAX);
+            let b_id = b.id.parse::<u32>().unwrap_or(u32::MAX);
+            a_id.cmp(&b_id)
+        })
+}
 
-        // Calculate line range with context
-        let start_line = span.begin.0.saturating_sub(1).saturating_sub(context_lines);
-        let end_line = std::cmp::min(span.end.0 + context_lines, lines.len());
+/// Ids of the items directly owned by an item: module items, fields,
+/// variants, impls, and trait or impl members
+fn child_ids(inner: &ItemEnum) -> Vec<Id> {
+    use rustdoc_types::{StructKind, VariantKind};
 
-        // Extract the relevant lines
-        let code_lines: Vec<String> = lines[start_line..end_line]
+    match inner {
+        ItemEnum::Module(m) => m.items.clone(),
+        ItemEnum::Struct(s) => {
+            let mut ids = match &s.kind {
+                StructKind::Unit => Vec::new(),
+                StructKind::Tuple(fields) => fields.iter().flatten().cloned().collect(),
+                StructKind::Plain { fields, .. } => fields.clone(),
+            };
+            ids.extend(s.impls.iter().cloned());
+            ids
+        }
+        ItemEnum::Union(u) => u.fields.iter().chain(&u.impls).cloned().collect(),
+        ItemEnum::Enum(e) => e.variants.iter().chain(&e.impls).cloned().collect(),
+        ItemEnum::Variant(v) => match &v.kind {
+            VariantKind::Plain => Vec::new(),
+            VariantKind::Tuple(fields) => fields.iter().flatten().cloned().collect(),
+            VariantKind::Struct { fields, .. } => fields.clone(),
+        },
+        ItemEnum::Trait(t) => t.items.iter().chain(&t.implementations).cloned().collect(),
+        ItemEnum::Impl(i) => i.items.clone(),
+        _ => Vec::new(),
+    }
+}
+
+/// Keep one item per target, as resolved by `target_of`, preferring the shortest path
+///
+/// Items without a path are only kept when no item with the same target has one.
+/// The kept item takes the position of the first item with its target.
+fn dedupe_by_target(items: Vec<ItemInfo>, target_of: impl Fn(&str) -> String) -> Vec<ItemInfo> {
+    let path_rank = |item: &ItemInfo| match item.path.len() {
+        0 => usize::MAX,
+        len => len,
+    };
+
+    let mut positions = HashMap::new();
+    let mut deduped: Vec<ItemInfo> = Vec::new();
+    for item in items {
+        match positions.entry(target_of(&item.id)) {
+            Entry::Occupied(entry) => {
+                let kept = &mut deduped[*entry.get()];
+                if path_rank(&item) < path_rank(kept) {
+                    *kept = item;
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(deduped.len());
+                deduped.push(item);
+            }
+        }
+    }
+    deduped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, name: &str, path: &[&str]) -> ItemInfo {
+        ItemInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            kind: "struct".to_string(),
+            path: path.iter().map(|s| s.to_string()).collect(),
+            docs: None,
+            visibility: "public".to_string(),
+            hidden: false,
+        }
+    }
+
+    fn ranked_ids(mut items: Vec<ItemInfo>, pattern: &str) -> Vec<String> {
+        items.sort_by(|a, b| compare_relevance(a, b, pattern));
+        items.into_iter().map(|i| i.id).collect()
+    }
+
+    #[test]
+    fn test_exact_match_ranks_first() {
+        let items = vec![
+            item("1", "InternalErrorKind", &["mycrate", "InternalErrorKind"]),
+            item("2", "ErrorKind", &["mycrate", "ErrorKind"]),
+            item("3", "Error", &["mycrate", "Error"]),
+            item("4", "error", &["mycrate", "error"]),
+        ];
+
+        assert_eq!(ranked_ids(items, "Error"), vec!["3", "4", "2", "1"]);
+    }
+
+    #[test]
+    fn test_shallower_paths_break_ties() {
+        let items = vec![
+            item("7", "Error", &["mycrate", "parse", "Error"]),
+            item("5", "Error", &["mycrate", "Error"]),
+            item("6", "Error", &["mycrate", "io", "Error"]),
+        ];
+
+        assert_eq!(ranked_ids(items, "Error"), vec!["5", "6", "7"]);
+    }
+
+    #[test]
+    fn test_ordering_is_deterministic() {
+        let forward = vec![
+            item("8", "Error", &["mycrate", "Error"]),
+            item("9", "Error", &["mycrate", "Error"]),
+        ];
+        let backward = vec![
+            item("9", "Error", &["mycrate", "Error"]),
+            item("8", "Error", &["mycrate", "Error"]),
+        ];
+
+        assert_eq!(ranked_ids(forward, "error"), vec!["8", "9"]);
+        assert_eq!(ranked_ids(backward, "error"), vec!["8", "9"]);
+    }
+
+    #[test]
+    fn test_dedupe_keeps_shortest_path_of_reexported_type() {
+        // "2" is a `pub use` of "1" without a path of its own, "3" re-exports it at the root
+        let items = || {
+            vec![
+                item("1", "Config", &["mycrate", "config", "types", "Config"]),
+                item("2", "Config", &[]),
+                item("4", "Config", &["mycrate", "other", "Config"]),
+                item("3", "Config", &["mycrate", "Config"]),
+            ]
+        };
+        let target_of = |id: &str| match id {
+            "2" | "3" => "1".to_string(),
+            id => id.to_string(),
+        };
+
+        let deduped = dedupe_by_target(items(), target_of);
+        let kept: Vec<_> = deduped.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(kept, vec!["3", "4"]);
+
+        // Without a pathed re-export the canonical item wins over the bare `use`
+        let deduped = dedupe_by_target(items().into_iter().take(2).collect(), target_of);
+        assert_eq!(deduped.len(), 1);
+        assert_eq!(deduped[0].id, "1");
+    }
+
+    /// A crate whose root holds `count` public modules named `item_<n>`
+    fn synthetic_crate(count: u32) -> Crate {
+        let module = |id: u32, name: String, items: Vec<u32>| {
+            serde_json::json!({
+                "id": id,
+                "crate_id": 0,
+                "name": name,
+                "span": null,
+                "visibility": "public",
+                "docs": null,
+                "links": {},
+                "attrs": [],
+                "deprecation": null,
+                "inner": { "module": { "is_crate": id == 0, "items": items, "is_stripped": false } },
+            })
+        };
+        let mut index = serde_json::Map::new();
+        index.insert(
+            "0".to_string(),
+            module(0, "demo".to_string(), (1..=count).collect()),
+        );
+        for id in 1..=count {
+            index.insert(id.to_string(), module(id, format!("item_{id}"), Vec::new()));
+        }
+        serde_json::from_value(serde_json::json!({
+            "root": 0,
+            "crate_version": "0.1.0",
+            "includes_private": false,
+            "index": index,
+            "paths": {},
+            "external_crates": {},
+            "target": { "triple": "x86_64-unknown-linux-gnu", "target_features": [] },
+            "format_version": rustdoc_types::FORMAT_VERSION,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_describe_repr() {
+        use serde_json::json;
+
+        assert_eq!(
+            describe_repr(&json!("#[repr(C)]")).as_deref(),
+            Some("#[repr(C)]")
+        );
+        assert_eq!(describe_repr(&json!("#[non_exhaustive]")), None);
+        assert_eq!(
+            describe_repr(
+                &json!({ "repr": { "kind": "c", "int": "u8", "align": null, "packed": null } })
+            )
+            .as_deref(),
+            Some("#[repr(C, u8)]")
+        );
+        assert_eq!(
+            describe_repr(&json!({ "repr": { "kind": "transparent", "int": null, "align": null, "packed": null } }))
+                .as_deref(),
+            Some("#[repr(transparent)]")
+        );
+        assert_eq!(
+            describe_repr(
+                &json!({ "repr": { "kind": "rust", "int": null, "align": 16, "packed": null } })
+            )
+            .as_deref(),
+            Some("#[repr(align(16))]")
+        );
+        assert_eq!(
+            describe_repr(
+                &json!({ "repr": { "kind": "rust", "int": null, "align": null, "packed": 1 } })
+            )
+            .as_deref(),
+            Some("#[repr(packed)]")
+        );
+        assert_eq!(describe_repr(&json!("automatically_derived")), None);
+    }
+
+    #[test]
+    fn test_resolve_source_path() -> Result<()> {
+        // A real directory, so the path is absolute on every platform
+        let temp_dir = tempfile::TempDir::new()?;
+        let base = temp_dir.path();
+
+        assert_eq!(
+            resolve_source_path(base, Path::new("src/lib.rs"))?,
+            base.join("src").join("lib.rs")
+        );
+        // Spans written on Windows use backslashes
+        assert_eq!(
+            resolve_source_path(base, Path::new(r"src\parser\mod.rs"))?,
+            base.join("src").join("parser").join("mod.rs")
+        );
+        assert_eq!(
+            resolve_source_path(base, Path::new("./src//lib.rs"))?,
+            base.join("src").join("lib.rs")
+        );
+        // Absolute paths inside the sources are made relative to them
+        assert_eq!(
+            resolve_source_path(base, &base.join("src/lib.rs"))?,
+            base.join("src").join("lib.rs")
+        );
+
+        for outside in [
+            "../other/src/lib.rs",
+            r"src\..\..\secret.rs",
+            "/etc/passwd",
+            r"\\server\share\lib.rs",
+            r"C:\Users\demo\src\lib.rs",
+        ] {
+            assert!(
+                resolve_source_path(base, Path::new(outside)).is_err(),
+                "{outside} should be rejected"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_item_source_crlf() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        std::fs::create_dir_all(temp_dir.path().join("src"))?;
+        std::fs::write(
+            temp_dir.path().join("src").join("lib.rs"),
+            "//! Demo\r\n\r\n/// A point\r\npub struct Point {\r\n    pub x: i32,\r\n}\r\n\r\npub fn origin() {}\r\n",
+        )?;
+        let item: Item = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "crate_id": 0,
+            "name": "Point",
+            "span": { "filename": r"src\lib.rs", "begin": [4, 0], "end": [6, 1] },
+            "visibility": "public",
+            "docs": "A point",
+            "links": {},
+            "attrs": [],
+            "deprecation": null,
+            "inner": { "module": { "is_crate": false, "items": [], "is_stripped": false } },
+        }))?;
+
+        let source = item_source(&item, temp_dir.path(), 0)?;
+        assert_eq!(source.code, "pub struct Point {\n    pub x: i32,\n}");
+
+        // The context window counts CRLF lines like LF ones
+        let source = item_source(&item, temp_dir.path(), 1)?;
+        assert_eq!(
+            source.code,
+            "/// A point\npub struct Point {\n    pub x: i32,\n}\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_time_budget() {
+        let query = DocQuery::new(synthetic_crate(10 * BUDGET_CHECK_INTERVAL as u32));
+
+        // A generous budget scans every item
+        let search = query.search_items_within("item", Some(Duration::from_secs(3600)));
+        assert!(!search.timed_out);
+        assert_eq!(search.items.len(), 10 * BUDGET_CHECK_INTERVAL);
+
+        // An exhausted budget stops at the first check with the matches found so far
+        let search = query.search_items_within("item", Some(Duration::ZERO));
+        assert!(search.timed_out);
+        assert!(!search.items.is_empty());
+        assert!(search.items.len() <= BUDGET_CHECK_INTERVAL);
+
+        assert_eq!(query.search_items("item").len(), 10 * BUDGET_CHECK_INTERVAL);
+    }
+
+    #[test]
+    fn test_describe_generic_params() {
+        use rustdoc_types::{
+            GenericArg, GenericArgs, GenericBound, GenericParamDef, GenericParamDefKind, Generics,
+            Path, TraitBoundModifier, Type, WherePredicate,
+        };
+
+        let path = |name: &str, id: u32, args: Vec<GenericArg>| Path {
+            path: name.to_string(),
+            id: Id(id),
+            args: (!args.is_empty()).then(|| {
+                Box::new(GenericArgs::AngleBracketed {
+                    args,
+                    constraints: Vec::new(),
+                })
+            }),
+        };
+        let trait_bound = |name: &str, id: u32| GenericBound::TraitBound {
+            trait_: path(name, id, Vec::new()),
+            generic_params: Vec::new(),
+            modifier: TraitBoundModifier::None,
+        };
+
+        // struct Buffer<'a, T: Clone = Vec<u8>, const N: usize = 16> where T: 'a + Send
+        let generics = Generics {
+            params: vec![
+                GenericParamDef {
+                    name: "'a".to_string(),
+                    kind: GenericParamDefKind::Lifetime {
+                        outlives: Vec::new(),
+                    },
+                },
+                GenericParamDef {
+                    name: "T".to_string(),
+                    kind: GenericParamDefKind::Type {
+                        bounds: vec![trait_bound("Clone", 1)],
+                        default: Some(Type::ResolvedPath(path(
+                            "Vec",
+                            2,
+                            vec![GenericArg::Type(Type::Primitive("u8".to_string()))],
+                        ))),
+                        is_synthetic: false,
+                    },
+                },
+                GenericParamDef {
+                    name: "N".to_string(),
+                    kind: GenericParamDefKind::Const {
+                        type_: Type::Primitive("usize".to_string()),
+                        default: Some("16".to_string()),
+                    },
+                },
+            ],
+            where_predicates: vec![WherePredicate::BoundPredicate {
+                type_: Type::Generic("T".to_string()),
+                bounds: vec![
+                    GenericBound::Outlives("'a".to_string()),
+                    trait_bound("Send", 3),
+                ],
+                generic_params: Vec::new(),
+            }],
+        };
+
+        let params = describe_generic_params(&generics);
+        assert_eq!(
+            params,
+            vec![
+                GenericParam {
+                    name: "'a".to_string(),
+                    kind: "lifetime".to_string(),
+                    type_: None,
+                    default: None,
+                    bounds: Vec::new(),
+                },
+                GenericParam {
+                    name: "T".to_string(),
+                    kind: "type".to_string(),
+                    type_: None,
+                    default: Some("Vec<u8>".to_string()),
+                    bounds: vec!["Clone".to_string(), "'a".to_string(), "Send".to_string()],
+                },
+                GenericParam {
+                    name: "N".to_string(),
+                    kind: "const".to_string(),
+                    type_: Some("usize".to_string()),
+                    default: Some("16".to_string()),
+                    bounds: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_named_type_id() {
+        use rustdoc_types::{Path, Type};
+
+        let config = Type::ResolvedPath(Path {
+            path: "Config".to_string(),
+            id: Id(7),
+            args: None,
+        });
+        assert_eq!(named_type_id(&config), Some(Id(7)));
+
+        // &[Config]
+        let borrowed_slice = Type::BorrowedRef {
+            lifetime: None,
+            is_mutable: false,
+            type_: Box::new(Type::Slice(Box::new(config))),
+        };
+        assert_eq!(named_type_id(&borrowed_slice), Some(Id(7)));
+
+        assert_eq!(named_type_id(&Type::Primitive("u32".to_string())), None);
+        assert_eq!(named_type_id(&Type::Generic("T".to_string())), None);
+    }
+
+    #[test]
+    fn test_walk_supertraits() {
+        // trait Top: Left + Right, trait Left: Base, trait Right: Base + Top, trait Base: Clone
+        let declared: HashMap<u32, Vec<u32>> =
+            HashMap::from([(1, vec![2, 3]), (2, vec![4]), (3, vec![4, 1]), (4, vec![5])]);
+        let name = |id: u32| ["", "Top", "Left", "Right", "Base", "Clone"][id as usize].to_string();
+        let supertraits = walk_supertraits(Id(1), |id| {
+            declared
+                .get(&id.0)
+                .into_iter()
+                .flatten()
+                .map(|&supertrait| (Id(supertrait), name(supertrait)))
+                .collect()
+        });
+
+        let found: Vec<(&str, Option<&str>)> = supertraits
             .iter()
-            .map(|line| line.to_string())
+            .map(|info| (info.path.as_str(), info.via.as_deref()))
             .collect();
+        assert_eq!(
+            found,
+            [
+                ("Left", None),
+                ("Right", None),
+                ("Base", Some("Left")),
+                ("Clone", Some("Base")),
+            ]
+        );
+    }
 
-        Ok(SourceInfo {
-            location: SourceLocation {
-                filename: span.filename.to_string_lossy().to_string(),
-                line_start: span.begin.0,
-                column_start: span.begin.1,
-                line_end: span.end.0,
-                column_end: span.end.1,
-            },
-            code: code_lines.join("\n"),
-            context_lines: Some(context_lines),
-        })
+    #[test]
+    fn test_extract_cfg_predicates() {
+        assert_eq!(
+            extract_cfg_predicates(r#"#[cfg(feature = "std")]"#),
+            [r#"feature = "std""#]
+        );
+        assert_eq!(
+            extract_cfg_predicates("#[doc(cfg(all(unix,\n    target_pointer_width = \"64\")))]"),
+            [r#"all(unix, target_pointer_width = "64")"#]
+        );
+        // A parenthesis inside a string does not end the predicate
+        assert_eq!(
+            extract_cfg_predicates(r#"#[cfg(feature = "a)")]"#),
+            [r#"feature = "a)""#]
+        );
+        assert!(extract_cfg_predicates(r#"#[cfg_attr(docsrs, doc(alias = "x"))]"#).is_empty());
+        assert!(extract_cfg_predicates("#[inline]").is_empty());
     }
 }
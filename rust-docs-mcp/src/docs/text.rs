@@ -0,0 +1,232 @@
+//! Markdown rendering of documentation tool outputs
+//!
+//! Used when a call asks for the `text` response format. An item renders as a
+//! card: a heading with its kind and path, the signature in a code block, the
+//! documentation, then lists of its fields, variants and methods.
+
+use std::fmt::Write;
+
+use crate::docs::outputs::{
+    DetailedItem, DocsErrorOutput, GetItemDetailsOutput, GetItemDocsOutput, ItemInfo,
+};
+use crate::format::ToText;
+
+impl ToText for GetItemDetailsOutput {
+    fn to_text(&self) -> String {
+        match self {
+            GetItemDetailsOutput::Success(item) => render_item_card(item),
+            GetItemDetailsOutput::Ambiguous { error, candidates } => {
+                let mut text = format!("# Ambiguous path\n\n{error}\n\n");
+                for candidate in candidates {
+                    let _ = writeln!(
+                        text,
+                        "- `{}` ({}, id {})",
+                        candidate.path.join("::"),
+                        candidate.kind,
+                        candidate.id
+                    );
+                }
+                text
+            }
+            GetItemDetailsOutput::Error { error } => format!("# Error\n\n{error}"),
+        }
+    }
+}
+
+impl ToText for GetItemDocsOutput {
+    fn to_text(&self) -> String {
+        let mut text = match &self.documentation {
+            Some(documentation) => documentation.trim_end().to_string(),
+            None => self
+                .message
+                .clone()
+                .unwrap_or_else(|| "No documentation available for this item".to_string()),
+        };
+        if self.source.as_deref() == Some("trait_default") {
+            text.push_str("\n\n*Inherited from the documentation of the trait method.*");
+        }
+        text
+    }
+}
+
+impl ToText for DocsErrorOutput {
+    fn to_text(&self) -> String {
+        format!("# Error\n\n{}", self.error)
+    }
+}
+
+/// Render an item as a Markdown card
+fn render_item_card(item: &DetailedItem) -> String {
+    let info = &item.info;
+    let mut text = format!("# {} `{}`\n\n", info.kind, info.path.join("::"));
+    let _ = writeln!(text, "*id {} · {}*", info.id, info.visibility);
+
+    if let Some(signature) = &item.signature {
+        let _ = write!(text, "\n```rust\n{}\n```\n", signature.trim_end());
+    }
+    if let Some(docs) = info.docs.as_deref().filter(|docs| !docs.trim().is_empty()) {
+        let _ = write!(text, "\n{}\n", docs.trim_end());
+    }
+    if let Some(note) = &item.sealed_note {
+        let _ = write!(text, "\n**Sealed:** {note}\n");
+    }
+
+    if let Some(params) = item
+        .generic_params
+        .as_ref()
+        .filter(|params| !params.is_empty())
+    {
+        text.push_str("\n## Generic parameters\n\n");
+        for param in params {
+            let mut line = match &param.type_ {
+                Some(type_) => format!("`const {}: {type_}`", param.name),
+                None => format!("`{}`", param.name),
+            };
+            if !param.bounds.is_empty() {
+                let _ = write!(line, ": `{}`", param.bounds.join(" + "));
+            }
+            if let Some(default) = &param.default {
+                let _ = write!(line, " (default `{default}`)");
+            }
+            let _ = writeln!(text, "- {line}");
+        }
+    }
+
+    for (heading, members) in [
+        ("Fields", &item.fields),
+        ("Variants", &item.variants),
+        ("Methods", &item.methods),
+    ] {
+        if let Some(members) = members.as_ref().filter(|members| !members.is_empty()) {
+            let _ = write!(text, "\n## {heading}\n\n");
+            for member in members {
+                render_member(&mut text, member);
+            }
+        }
+    }
+
+    if let Some(location) = &item.source_location {
+        let _ = write!(
+            text,
+            "\nDefined in `{}:{}-{}`\n",
+            location.filename, location.line_start, location.line_end
+        );
+    }
+    if let Some(url) = &item.docsrs_url {
+        let _ = write!(text, "\nOnline: [docs.rs]({url})\n");
+    }
+
+    text
+}
+
+/// A list entry for a field, variant or method with the first line of its docs
+fn render_member(text: &mut String, member: &ItemInfo) {
+    let summary = member
+        .docs
+        .as_deref()
+        .and_then(|docs| docs.lines().map(str::trim).find(|line| !line.is_empty()));
+    match summary {
+        Some(summary) => {
+            let _ = writeln!(text, "- `{}` (id {}): {summary}", member.name, member.id);
+        }
+        None => {
+            let _ = writeln!(text, "- `{}` (id {})", member.name, member.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docs::outputs::{ItemPreview, SourceLocation};
+    use crate::format::ResponseFormat;
+
+    fn info(id: &str, name: &str, kind: &str, docs: Option<&str>) -> ItemInfo {
+        ItemInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            kind: kind.to_string(),
+            path: vec!["demo".to_string(), name.to_string()],
+            docs: docs.map(str::to_string),
+            visibility: "public".to_string(),
+            field_type_id: None,
+            field_type_path: None,
+            hidden: false,
+        }
+    }
+
+    #[test]
+    fn test_item_card() {
+        let output = GetItemDetailsOutput::Success(Box::new(DetailedItem {
+            info: info("1", "Point", "struct", Some("A point.\n\nWith details.")),
+            signature: Some("pub struct Point { pub x: i32 }".to_string()),
+            generics: None,
+            generic_params: None,
+            fields: Some(vec![info("2", "x", "struct_field", Some("The x axis."))]),
+            variants: None,
+            methods: Some(Vec::new()),
+            source_location: Some(SourceLocation {
+                filename: "src/lib.rs".to_string(),
+                line_start: 3,
+                line_end: 5,
+                column_start: 0,
+                column_end: 1,
+            }),
+            docsrs_url: Some("https://docs.rs/demo/0.1.0/demo/struct.Point.html".to_string()),
+            sealed: false,
+            sealed_note: None,
+        }));
+
+        let text = output.render(ResponseFormat::Text);
+        assert!(text.starts_with("# struct `demo::Point`\n"));
+        assert!(text.contains("```rust\npub struct Point { pub x: i32 }\n```"));
+        assert!(text.contains("With details."));
+        assert!(text.contains("## Fields\n\n- `x` (id 2): The x axis."));
+        assert!(!text.contains("## Methods"));
+        assert!(text.contains("Defined in `src/lib.rs:3-5`"));
+        assert!(text.contains("[docs.rs](https://docs.rs/demo/0.1.0/demo/struct.Point.html)"));
+
+        // JSON stays the structured output
+        assert_eq!(output.render(ResponseFormat::Json), output.to_json());
+    }
+
+    #[test]
+    fn test_ambiguous_and_error_text() {
+        let output = GetItemDetailsOutput::Ambiguous {
+            error: "Path 'Error' matches 2 items".to_string(),
+            candidates: vec![ItemPreview {
+                id: "7".to_string(),
+                name: "Error".to_string(),
+                kind: "struct".to_string(),
+                path: vec!["demo".to_string(), "Error".to_string()],
+            }],
+        };
+        assert!(output.to_text().contains("- `demo::Error` (struct, id 7)"));
+
+        let error = DocsErrorOutput::new("Item not found");
+        assert_eq!(error.to_text(), "# Error\n\nItem not found");
+    }
+
+    #[test]
+    fn test_item_docs_text() {
+        let inherited = GetItemDocsOutput {
+            documentation: Some("Clones the value.\n".to_string()),
+            source: Some("trait_default".to_string()),
+            message: None,
+        };
+        assert_eq!(
+            inherited.to_text(),
+            "Clones the value.\n\n*Inherited from the documentation of the trait method.*"
+        );
+
+        let missing = GetItemDocsOutput {
+            documentation: None,
+            source: None,
+            message: Some("No documentation available for this item".to_string()),
+        };
+        assert_eq!(
+            missing.to_text(),
+            "No documentation available for this item"
+        );
+    }
+}
@@ -1,22 +1,49 @@
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{RwLock, mpsc};
 
 use rmcp::schemars;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::cache::CrateCache;
+use crate::cache::docsrs;
+use crate::cache::types::DocsVariant;
+use crate::config::{DEFAULT_MAX_RESPONSE_BYTES, DEFAULT_SEARCH_TIME_BUDGET_MS};
 use crate::docs::{
     DocQuery,
+    changes::changed_files,
+    doctest::{strip_doctest_scaffolding, strip_source_doctest_scaffolding},
+    kinds::{KindFilterInput, deserialize_kind_filter, normalize_kind_filter},
+    markdown::{MarkdownOptions, render_crate_markdown},
+    ndjson::{NdjsonHeader, write_items_ndjson},
     outputs::{
-        DetailedItem, DocsErrorOutput, GetItemDetailsOutput, GetItemDocsOutput,
-        GetItemSourceOutput, ItemInfo, ItemPreview, ListCrateItemsOutput, PaginationInfo,
-        SearchItemsOutput, SearchItemsPreviewOutput, SourceInfo, SourceLocation,
+        BlanketImpl, CfgAttribute, ChangedItemsOutput, DETAIL_FIELDS, DetailedItem,
+        DiffVisibilityOutput, DocsErrorOutput, ExportDocsMarkdownOutput, ExportItemsNdjsonOutput,
+        GenericParam, GetAllMethodsOutput, GetBlanketImplsOutput, GetDocsForNamesOutput,
+        GetItemDetailsOutput, GetItemDocsOutput, GetItemRawOutput, GetItemSourceOutput,
+        GetReprInfoOutput, GetSupertraitsOutput, ItemChunk, ItemGroup, ItemInfo, ItemPreview,
+        ListCfgAttributesOutput, ListCrateItemsOutput, MethodEntry, ModuleItems, NameDocs,
+        PaginationInfo, ReferencedDefinition, SEARCH_TIMED_OUT_WARNING, SearchItemsOutput,
+        SearchItemsPreviewOutput, SourceInfo, SourceLocation, StreamCrateItemsOutput,
+        SupertraitEntry, VisibilityChange,
     },
+    query::{FieldTypeLink, ItemSearch, item_source},
+    visibility::{self, diff_visibility},
 };
 
-/// Maximum size for response in bytes (roughly 25k tokens * 4 bytes/token)
-const MAX_RESPONSE_SIZE: usize = 100_000;
+/// Default number of items per chunk when streaming crate items
+const DEFAULT_STREAM_CHUNK_SIZE: usize = 500;
+
+/// Maximum number of items per chunk when streaming crate items
+const MAX_STREAM_CHUNK_SIZE: usize = 5_000;
+
+/// Default number of levels of referenced items returned by get_item_raw
+const DEFAULT_RAW_DEPTH: usize = 1;
+
+/// Maximum number of levels of referenced items returned by get_item_raw
+const MAX_RAW_DEPTH: usize = 5;
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ListItemsParams {
@@ -24,7 +51,11 @@ pub struct ListItemsParams {
     pub crate_name: String,
     #[schemars(description = "The version of the crate")]
     pub version: String,
-    #[schemars(description = "Optional filter by item kind (e.g., 'function', 'struct', 'enum')")]
+    #[schemars(
+        description = "Optional filter by item kind (e.g., 'function', 'struct', 'enum'). Several kinds can be given comma-separated or as a list (e.g., 'struct,enum'), matching items of any of them. Case-insensitive; aliases such as 'fn' are accepted",
+        with = "Option<KindFilterInput>"
+    )]
+    #[serde(default, deserialize_with = "deserialize_kind_filter")]
     pub kind_filter: Option<String>,
     #[schemars(description = "Maximum number of items to return (default: 100)")]
     pub limit: Option<i64>,
@@ -34,6 +65,72 @@ pub struct ListItemsParams {
         description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
     )]
     pub member: Option<String>,
+    #[schemars(
+        description = "Include private items (requires generating docs with --document-private-items, done on first use and cached separately). Useful when documenting your own crate. Defaults to false"
+    )]
+    pub include_private: Option<bool>,
+    #[schemars(
+        description = "Include items marked #[doc(hidden)] (requires generating docs with the nightly --document-hidden-items flag, done on first use and cached separately); they are returned with hidden: true. Set to false to exclude hidden items even if the regular docs contain them. Cannot be combined with include_private"
+    )]
+    pub include_hidden: Option<bool>,
+    #[schemars(
+        description = "Optional target triple to read docs for (e.g., 'x86_64-pc-windows-msvc'), to see platform-specific APIs. Docs for the target are generated on first use and cached separately; the target must be installed with rustup"
+    )]
+    pub target: Option<String>,
+    #[schemars(
+        description = "Return the items nested under their module path in 'modules' instead of as a flat list, for an outline of the crate in one call. limit and offset still apply to the items, and the response is cut to stay within the response size limit. Defaults to false"
+    )]
+    pub group_by_module: Option<bool>,
+    #[schemars(
+        description = "With group_by_module, the deepest module level to nest items under, counted from the crate root (0 puts every item under the crate root). Items of deeper modules are listed under their ancestor at that level. Unlimited by default"
+    )]
+    pub max_depth: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct StreamItemsParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+    #[schemars(description = "The version of the crate")]
+    pub version: String,
+    #[schemars(
+        description = "Optional filter by item kind (e.g., 'function', 'struct', 'enum'). Several kinds can be given comma-separated or as a list (e.g., 'struct,enum'), matching items of any of them. Case-insensitive; aliases such as 'fn' are accepted",
+        with = "Option<KindFilterInput>"
+    )]
+    #[serde(default, deserialize_with = "deserialize_kind_filter")]
+    pub kind_filter: Option<String>,
+    #[schemars(description = "Number of items per streamed chunk (default: 500, max: 5000)")]
+    pub chunk_size: Option<i64>,
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+}
+
+impl StreamItemsParams {
+    /// Number of items per chunk, clamped to a sane range
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+            .unwrap_or(DEFAULT_STREAM_CHUNK_SIZE as i64)
+            .clamp(1, MAX_STREAM_CHUNK_SIZE as i64) as usize
+    }
+
+    /// Equivalent paginated request returning the first chunk
+    pub fn to_list_params(&self) -> ListItemsParams {
+        ListItemsParams {
+            crate_name: self.crate_name.clone(),
+            version: self.version.clone(),
+            kind_filter: self.kind_filter.clone(),
+            limit: Some(self.chunk_size() as i64),
+            offset: None,
+            member: self.member.clone(),
+            include_private: None,
+            include_hidden: None,
+            target: None,
+            group_by_module: None,
+            max_depth: None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -50,7 +147,11 @@ pub struct SearchItemsParams {
     pub limit: Option<i64>,
     #[schemars(description = "Starting position for pagination (default: 0)")]
     pub offset: Option<i64>,
-    #[schemars(description = "Optional filter by item kind (e.g., 'function', 'struct', 'enum')")]
+    #[schemars(
+        description = "Optional filter by item kind (e.g., 'function', 'struct', 'enum'). Several kinds can be given comma-separated or as a list (e.g., 'struct,enum'), matching items of any of them. Case-insensitive; aliases such as 'fn' are accepted",
+        with = "Option<KindFilterInput>"
+    )]
+    #[serde(default, deserialize_with = "deserialize_kind_filter")]
     pub kind_filter: Option<String>,
     #[schemars(description = "Optional filter by module path prefix")]
     pub path_filter: Option<String>,
@@ -58,6 +159,26 @@ pub struct SearchItemsParams {
         description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
     )]
     pub member: Option<String>,
+    #[schemars(
+        description = "Collapse results into groups: 'none' (default), 'name' or 'kind'. When grouping, results are returned in 'groups' with the ids and paths of each occurrence, and limit/offset paginate over groups"
+    )]
+    pub group_by: Option<String>,
+    #[schemars(
+        description = "Include private items (requires generating docs with --document-private-items, done on first use and cached separately). Useful when documenting your own crate. Defaults to false"
+    )]
+    pub include_private: Option<bool>,
+    #[schemars(
+        description = "Include items marked #[doc(hidden)] (requires generating docs with the nightly --document-hidden-items flag, done on first use and cached separately); they are returned with hidden: true. Set to false to exclude hidden items even if the regular docs contain them. Cannot be combined with include_private"
+    )]
+    pub include_hidden: Option<bool>,
+    #[schemars(
+        description = "Optional target triple to read docs for (e.g., 'x86_64-pc-windows-msvc'), to see platform-specific APIs. Docs for the target are generated on first use and cached separately; the target must be installed with rustup"
+    )]
+    pub target: Option<String>,
+    #[schemars(
+        description = "Collapse results that name the same underlying item, such as a type and its re-exports, keeping the entry with the shortest path. Defaults to false, which lists every public path"
+    )]
+    pub dedupe_by_id: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -74,7 +195,11 @@ pub struct SearchItemsPreviewParams {
     pub limit: Option<i64>,
     #[schemars(description = "Starting position for pagination (default: 0)")]
     pub offset: Option<i64>,
-    #[schemars(description = "Optional filter by item kind (e.g., 'function', 'struct', 'enum')")]
+    #[schemars(
+        description = "Optional filter by item kind (e.g., 'function', 'struct', 'enum'). Several kinds can be given comma-separated or as a list (e.g., 'struct,enum'), matching items of any of them. Case-insensitive; aliases such as 'fn' are accepted",
+        with = "Option<KindFilterInput>"
+    )]
+    #[serde(default, deserialize_with = "deserialize_kind_filter")]
     pub kind_filter: Option<String>,
     #[schemars(description = "Optional filter by module path prefix")]
     pub path_filter: Option<String>,
@@ -82,6 +207,69 @@ pub struct SearchItemsPreviewParams {
         description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
     )]
     pub member: Option<String>,
+    #[schemars(
+        description = "Optional target triple to read docs for (e.g., 'x86_64-pc-windows-msvc'), to see platform-specific APIs. Docs for the target are generated on first use and cached separately; the target must be installed with rustup"
+    )]
+    pub target: Option<String>,
+    #[schemars(
+        description = "Collapse results that name the same underlying item, such as a type and its re-exports, keeping the entry with the shortest path. Defaults to false, which lists every public path"
+    )]
+    pub dedupe_by_id: Option<bool>,
+    #[schemars(
+        description = "Return one 'id  kind  path' line per item instead of JSON, which is cheaper to read. Lines starting with '# ' report more results or warnings. Defaults to false"
+    )]
+    pub compact: Option<bool>,
+}
+
+/// An item ID as given to a tool: a number, or the string returned in `ItemInfo.id`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum ItemIdInput {
+    Number(i64),
+    Text(String),
+}
+
+impl ItemIdInput {
+    /// Convert to the crate's native item ID, rejecting IDs it cannot hold
+    fn into_id(self) -> Result<u32, String> {
+        let id = match self {
+            ItemIdInput::Number(id) => id,
+            ItemIdInput::Text(text) => {
+                let text = text.trim();
+                text.parse::<i64>().map_err(|_| {
+                    if text.contains(':') {
+                        format!(
+                            "Item ID '{text}' uses the ID format of older rustdoc versions. Use the IDs returned by the search and list tools"
+                        )
+                    } else {
+                        format!("Invalid item ID '{text}', expected a number such as \"123\"")
+                    }
+                })?
+            }
+        };
+        u32::try_from(id).map_err(|_| format!("Item ID {id} is out of range"))
+    }
+}
+
+/// Deserialize an item ID given as a number or a string
+fn deserialize_item_id<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    ItemIdInput::deserialize(deserializer)?
+        .into_id()
+        .map_err(serde::de::Error::custom)
+}
+
+/// Deserialize an optional item ID given as a number or a string
+fn deserialize_optional_item_id<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Option::<ItemIdInput>::deserialize(deserializer)?
+        .map(ItemIdInput::into_id)
+        .transpose()
+        .map_err(serde::de::Error::custom)
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -90,12 +278,56 @@ pub struct GetItemDetailsParams {
     pub crate_name: String,
     #[schemars(description = "The version of the crate")]
     pub version: String,
-    #[schemars(description = "The numeric ID of the item")]
-    pub item_id: i32,
+    #[schemars(
+        description = "The ID of the item as returned in the 'id' field of search and list results, as a number or string (mutually exclusive with 'path')",
+        with = "Option<ItemIdInput>"
+    )]
+    #[serde(default, deserialize_with = "deserialize_optional_item_id")]
+    pub item_id: Option<u32>,
+    #[schemars(
+        description = "Item name or path to look up instead of an ID (e.g., 'Version' or 'semver::Version'). Mutually exclusive with 'item_id'. If several items match, their IDs are returned as candidates"
+    )]
+    pub path: Option<String>,
     #[schemars(
         description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
     )]
     pub member: Option<String>,
+    #[schemars(
+        description = "Only include these parts of the item to reduce response size. Available: 'signature', 'docs', 'generics', 'fields', 'variants', 'methods', 'source_location'. 'generics' includes 'generic_params', each generic parameter with its kind, default and bounds. The item's id, name, kind, path and visibility are always included. Defaults to all parts"
+    )]
+    pub fields: Option<Vec<String>>,
+    #[schemars(
+        description = "Optional target triple to read docs for (e.g., 'x86_64-pc-windows-msvc'), to see platform-specific APIs. Docs for the target are generated on first use and cached separately; the target must be installed with rustup"
+    )]
+    pub target: Option<String>,
+    #[schemars(
+        description = "Link each field, and each variant holding a single value, to the definition of its type: 'field_type_id' is the id of a type from this crate to pass to get_item_details, 'field_type_path' the full path of a type from another crate. References, slices and arrays are looked through. Defaults to false"
+    )]
+    pub resolve_field_types: Option<bool>,
+    #[schemars(
+        description = "Response format: 'json' for the structured output or 'text' for a Markdown rendering. Defaults to the server's default format (json unless configured otherwise)"
+    )]
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetDocsForNamesParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+    #[schemars(description = "The version of the crate")]
+    pub version: String,
+    #[schemars(
+        description = "Item paths to get the documentation of (e.g., ['semver::Version', 'semver::Version::parse']). Each is looked up like the 'path' of get_item_details"
+    )]
+    pub names: Vec<String>,
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+    #[schemars(
+        description = "Optional target triple to read docs for (e.g., 'x86_64-pc-windows-msvc'), to see platform-specific APIs. Docs for the target are generated on first use and cached separately; the target must be installed with rustup"
+    )]
+    pub target: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -104,8 +336,252 @@ pub struct GetItemDocsParams {
     pub crate_name: String,
     #[schemars(description = "The version of the crate")]
     pub version: String,
-    #[schemars(description = "The numeric ID of the item")]
-    pub item_id: i32,
+    #[schemars(
+        description = "The ID of the item as returned in the 'id' field of search and list results, as a number or string",
+        with = "ItemIdInput"
+    )]
+    #[serde(deserialize_with = "deserialize_item_id")]
+    pub item_id: u32,
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+    #[schemars(
+        description = "Optional target triple to read docs for (e.g., 'x86_64-pc-windows-msvc'), to see platform-specific APIs. Docs for the target are generated on first use and cached separately; the target must be installed with rustup"
+    )]
+    pub target: Option<String>,
+    #[schemars(
+        description = "Remove rustdoc's hidden lines from the Rust examples, such as '# use ...' imports and '# fn main() {' wrappers, to show them as rendered (default: false)"
+    )]
+    pub strip_doctest_scaffolding: Option<bool>,
+    #[schemars(
+        description = "Response format: 'json' for the structured output or 'text' for a Markdown rendering. Defaults to the server's default format (json unless configured otherwise)"
+    )]
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetBlanketImplsParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+    #[schemars(description = "The version of the crate")]
+    pub version: String,
+    #[schemars(
+        description = "The ID of the struct, enum or union as returned in the 'id' field of search and list results, as a number or string",
+        with = "ItemIdInput"
+    )]
+    #[serde(deserialize_with = "deserialize_item_id")]
+    pub item_id: u32,
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+    #[schemars(
+        description = "Optional target triple to read docs for (e.g., 'x86_64-pc-windows-msvc'), to see platform-specific APIs. Docs for the target are generated on first use and cached separately; the target must be installed with rustup"
+    )]
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetReprInfoParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+    #[schemars(description = "The version of the crate")]
+    pub version: String,
+    #[schemars(
+        description = "The ID of the struct, enum or union as returned in the 'id' field of search and list results, as a number or string",
+        with = "ItemIdInput"
+    )]
+    #[serde(deserialize_with = "deserialize_item_id")]
+    pub item_id: u32,
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+    #[schemars(
+        description = "Optional target triple to read docs for (e.g., 'x86_64-pc-windows-msvc'), to see platform-specific APIs. Docs for the target are generated on first use and cached separately; the target must be installed with rustup"
+    )]
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListCfgAttributesParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+    #[schemars(description = "The version of the crate")]
+    pub version: String,
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+    #[schemars(
+        description = "Optional target triple to read docs for (e.g., 'x86_64-pc-windows-msvc'), to see platform-specific APIs. Docs for the target are generated on first use and cached separately; the target must be installed with rustup"
+    )]
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetSupertraitsParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+    #[schemars(description = "The version of the crate")]
+    pub version: String,
+    #[schemars(
+        description = "The ID of the trait as returned in the 'id' field of search and list results, as a number or string",
+        with = "ItemIdInput"
+    )]
+    #[serde(deserialize_with = "deserialize_item_id")]
+    pub item_id: u32,
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+    #[schemars(
+        description = "Optional target triple to read docs for (e.g., 'x86_64-pc-windows-msvc'), to see platform-specific APIs. Docs for the target are generated on first use and cached separately; the target must be installed with rustup"
+    )]
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetAllMethodsParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+    #[schemars(description = "The version of the crate")]
+    pub version: String,
+    #[schemars(
+        description = "The ID of the struct, enum or union as returned in the 'id' field of search and list results, as a number or string",
+        with = "ItemIdInput"
+    )]
+    #[serde(deserialize_with = "deserialize_item_id")]
+    pub item_id: u32,
+    #[schemars(
+        description = "Also include methods from blanket impls such as `impl<T: Display> ToString for T` (default: false)"
+    )]
+    pub include_blanket: Option<bool>,
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+    #[schemars(
+        description = "Optional target triple to read docs for (e.g., 'x86_64-pc-windows-msvc'), to see platform-specific APIs. Docs for the target are generated on first use and cached separately; the target must be installed with rustup"
+    )]
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetItemRawParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+    #[schemars(description = "The version of the crate")]
+    pub version: String,
+    #[schemars(
+        description = "The ID of the item as returned in the 'id' field of search and list results, as a number or string",
+        with = "ItemIdInput"
+    )]
+    #[serde(deserialize_with = "deserialize_item_id")]
+    pub item_id: u32,
+    #[schemars(
+        description = "How many levels of referenced items (fields, variants, impls, module and trait items) to include: 0 returns only the item (default: 1, max: 5)"
+    )]
+    pub depth: Option<i64>,
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+    #[schemars(
+        description = "Optional target triple to read docs for (e.g., 'x86_64-pc-windows-msvc'), to see platform-specific APIs. Docs for the target are generated on first use and cached separately; the target must be installed with rustup"
+    )]
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ExportDocsMarkdownParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+    #[schemars(description = "The version of the crate")]
+    pub version: String,
+    #[schemars(
+        description = "Optional filter by item kind (e.g., 'function', 'struct', 'enum'). Several kinds can be given comma-separated or as a list (e.g., 'struct,enum'), matching items of any of them. Case-insensitive; aliases such as 'fn' are accepted",
+        with = "Option<KindFilterInput>"
+    )]
+    #[serde(default, deserialize_with = "deserialize_kind_filter")]
+    pub kind_filter: Option<String>,
+    #[schemars(description = "Maximum number of items to include (default: all public items)")]
+    pub max_items: Option<i64>,
+    #[schemars(
+        description = "Absolute file path to write the Markdown to. When set, the document is written there instead of being returned"
+    )]
+    pub output_path: Option<String>,
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+    #[schemars(
+        description = "Optional target triple to read docs for (e.g., 'x86_64-pc-windows-msvc'), to see platform-specific APIs. Docs for the target are generated on first use and cached separately; the target must be installed with rustup"
+    )]
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ExportItemsNdjsonParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+    #[schemars(description = "The version of the crate")]
+    pub version: String,
+    #[schemars(
+        description = "Absolute file path to write the export to. An existing file is overwritten"
+    )]
+    pub output_path: String,
+    #[schemars(
+        description = "Optional filter by item kind (e.g., 'function', 'struct', 'enum'). Several kinds can be given comma-separated or as a list (e.g., 'struct,enum'), matching items of any of them. Case-insensitive; aliases such as 'fn' are accepted",
+        with = "Option<KindFilterInput>"
+    )]
+    #[serde(default, deserialize_with = "deserialize_kind_filter")]
+    pub kind_filter: Option<String>,
+    #[schemars(
+        description = "Include private items (defaults to false). Private docs are generated on first use"
+    )]
+    pub include_private: Option<bool>,
+    #[schemars(
+        description = "Include items marked #[doc(hidden)] with hidden: true (defaults to the regular docs). Hidden docs are generated on first use with the nightly --document-hidden-items flag; false excludes hidden items"
+    )]
+    pub include_hidden: Option<bool>,
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+    #[schemars(
+        description = "Optional target triple to read docs for (e.g., 'x86_64-pc-windows-msvc'), to see platform-specific APIs. Docs for the target are generated on first use and cached separately; the target must be installed with rustup"
+    )]
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetChangedItemsSinceParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+    #[schemars(description = "The version of the crate, the branch or tag it was cached from")]
+    pub version: String,
+    #[schemars(
+        description = "Commit to compare from: a commit hash, tag, or expression such as 'HEAD~5'"
+    )]
+    pub since: String,
+    #[schemars(description = "Commit to compare to (default: 'HEAD', the cached commit)")]
+    pub until: Option<String>,
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DiffVisibilityParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+    #[schemars(description = "The older version to compare from")]
+    pub version_a: String,
+    #[schemars(description = "The newer version to compare to")]
+    pub version_b: String,
     #[schemars(
         description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
     )]
@@ -118,8 +594,12 @@ pub struct GetItemSourceParams {
     pub crate_name: String,
     #[schemars(description = "The version of the crate")]
     pub version: String,
-    #[schemars(description = "The numeric ID of the item")]
-    pub item_id: i32,
+    #[schemars(
+        description = "The ID of the item as returned in the 'id' field of search and list results, as a number or string",
+        with = "ItemIdInput"
+    )]
+    #[serde(deserialize_with = "deserialize_item_id")]
+    pub item_id: u32,
     #[schemars(
         description = "Number of context lines to include before and after the item (default: 3)"
     )]
@@ -128,16 +608,233 @@ pub struct GetItemSourceParams {
         description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
     )]
     pub member: Option<String>,
+    #[schemars(
+        description = "Optional target triple to read docs for (e.g., 'x86_64-pc-windows-msvc'), to see platform-specific APIs. Docs for the target are generated on first use and cached separately; the target must be installed with rustup"
+    )]
+    pub target: Option<String>,
+    #[schemars(
+        description = "Also list where the crate's own types named in the item's signature are defined, as {id, name, filename, line}, to navigate from a function to its argument types' sources (default: false). Loads the whole crate's docs, so it is slower"
+    )]
+    pub resolve_references: Option<bool>,
+    #[schemars(
+        description = "Remove rustdoc's hidden lines ('# ...') from the examples in the doc comments shown with the source (default: false). The code itself is unchanged, but the returned lines no longer match the file's line numbers one to one"
+    )]
+    pub strip_doctest_scaffolding: Option<bool>,
+}
+
+/// How search results are grouped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GroupBy {
+    None,
+    Name,
+    Kind,
+}
+
+impl GroupBy {
+    fn parse(value: Option<&str>) -> Result<Self, DocsErrorOutput> {
+        match value.unwrap_or("none") {
+            "none" => Ok(GroupBy::None),
+            "name" => Ok(GroupBy::Name),
+            "kind" => Ok(GroupBy::Kind),
+            other => Err(DocsErrorOutput::new(format!(
+                "Invalid group_by '{other}'. Must be one of: 'none', 'name', 'kind'"
+            ))),
+        }
+    }
+}
+
+/// Nest items under their module path, cut to `max_depth` levels below the crate root
+///
+/// Modules are sorted by path and keep the listing order of their items.
+fn group_by_module(items: Vec<ItemInfo>, max_depth: Option<usize>) -> Vec<ModuleItems> {
+    let mut modules: BTreeMap<Vec<String>, Vec<ItemInfo>> = BTreeMap::new();
+    for item in items {
+        let mut module = item.path[..item.path.len().saturating_sub(1)].to_vec();
+        if let Some(depth) = max_depth {
+            // The first segment is the crate itself
+            module.truncate(depth + 1);
+        }
+        modules.entry(module).or_default().push(item);
+    }
+
+    modules
+        .into_iter()
+        .map(|(path, items)| ModuleItems {
+            path: path.join("::"),
+            items,
+        })
+        .collect()
+}
+
+/// Collapse items sharing a name or kind into groups, keeping first-occurrence order
+fn group_items(items: Vec<ItemPreview>, group_by: GroupBy) -> Vec<ItemGroup> {
+    let mut groups: Vec<ItemGroup> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for item in items {
+        let key = match group_by {
+            GroupBy::Kind => item.kind.clone(),
+            GroupBy::Name | GroupBy::None => item.name.clone(),
+        };
+
+        match index.get(&key) {
+            Some(&position) => {
+                let group = &mut groups[position];
+                group.count += 1;
+                group.items.push(item);
+            }
+            None => {
+                index.insert(key.clone(), groups.len());
+                groups.push(ItemGroup {
+                    key,
+                    count: 1,
+                    items: vec![item],
+                });
+            }
+        }
+    }
+
+    groups
+}
+
+/// The docs variant selected by the `include_private` and `include_hidden` parameters
+fn docs_variant(
+    include_private: Option<bool>,
+    include_hidden: Option<bool>,
+) -> anyhow::Result<Option<DocsVariant>> {
+    match (
+        include_private.unwrap_or(false),
+        include_hidden.unwrap_or(false),
+    ) {
+        (true, true) => anyhow::bail!("'include_private' and 'include_hidden' cannot be combined"),
+        (true, false) => Ok(Some(DocsVariant::Private)),
+        (false, true) => Ok(Some(DocsVariant::Hidden)),
+        (false, false) => Ok(None),
+    }
+}
+
+/// Load a crate's docs, using the variant with private or hidden items or for
+/// another target platform when requested
+async fn load_crate_docs(
+    cache: &CrateCache,
+    crate_name: &str,
+    version: &str,
+    member: Option<&str>,
+    variant: Option<DocsVariant>,
+    target: Option<&str>,
+) -> anyhow::Result<rustdoc_types::Crate> {
+    match (variant, target) {
+        (Some(DocsVariant::Private), Some(_)) => {
+            anyhow::bail!("'include_private' and 'target' cannot be combined")
+        }
+        (Some(DocsVariant::Hidden), Some(_)) => {
+            anyhow::bail!("'include_hidden' and 'target' cannot be combined")
+        }
+        (Some(variant), None) => {
+            cache
+                .ensure_crate_or_member_variant_docs(crate_name, version, member, variant)
+                .await
+        }
+        (None, Some(target)) => {
+            cache
+                .ensure_crate_or_member_target_docs(crate_name, version, member, target)
+                .await
+        }
+        (None, None) => {
+            cache
+                .ensure_crate_or_member_docs(crate_name, version, member)
+                .await
+        }
+    }
+}
+
+/// An item's docs, falling back to the docs of the trait method it implements
+///
+/// Returns the docs and their source, `trait_default` when inherited. Trait
+/// impl methods without docs of their own inherit those of the trait method.
+fn item_docs(query: &DocQuery, item_id: u32) -> anyhow::Result<(Option<String>, Option<String>)> {
+    let docs = query.get_item_docs(item_id)?;
+    Ok(match docs.filter(|docs| !docs.trim().is_empty()) {
+        Some(docs) => (Some(docs), None),
+        None => match query.get_trait_default_docs(item_id) {
+            Some(docs) => (Some(docs), Some("trait_default".to_string())),
+            None => (None, None),
+        },
+    })
+}
+
+/// Whether a cached crate version was downloaded from crates.io, and so is on docs.rs
+fn is_from_crates_io(cache: &CrateCache, crate_name: &str, version: &str) -> bool {
+    cache
+        .storage
+        .load_metadata(crate_name, version, None)
+        .is_ok_and(|metadata| metadata.source == "crates.io")
+}
+
+/// Look up one item in cached docs without loading the whole crate
+///
+/// Returns `None` whenever the full load through [`load_crate_docs`] is needed
+/// instead, see [`CrateCache::find_cached_item`].
+async fn find_cached_item(
+    cache: &CrateCache,
+    crate_name: &str,
+    version: &str,
+    member: Option<&str>,
+    target: Option<&str>,
+    item_id: u32,
+) -> Option<rustdoc_types::Item> {
+    match cache
+        .find_cached_item(crate_name, version, member, target, item_id)
+        .await
+    {
+        Ok(item) => item,
+        Err(e) => {
+            tracing::debug!("Item lookup in {crate_name}-{version} failed, loading all docs: {e}");
+            None
+        }
+    }
+}
+
+/// Fill in where the type of a field or single-value variant is defined
+fn link_field_type(query: &DocQuery, member: &mut ItemInfo) {
+    let Ok(id) = member.id.parse::<u32>() else {
+        return;
+    };
+    match query.get_field_type_link(id) {
+        Some(FieldTypeLink::Local(type_id)) => member.field_type_id = Some(type_id.to_string()),
+        Some(FieldTypeLink::External(path)) => member.field_type_path = Some(path),
+        None => {}
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct DocsTools {
     cache: Arc<RwLock<CrateCache>>,
+    /// Maximum size of a response in bytes before results are truncated
+    max_response_bytes: usize,
+    /// Time a name search may scan items for before returning partial results
+    search_time_budget: Option<Duration>,
 }
 
 impl DocsTools {
     pub fn new(cache: Arc<RwLock<CrateCache>>) -> Self {
-        Self { cache }
+        Self {
+            cache,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            search_time_budget: Some(Duration::from_millis(DEFAULT_SEARCH_TIME_BUDGET_MS)),
+        }
+    }
+
+    /// Set the maximum size of a response in bytes
+    pub fn with_max_response_bytes(mut self, max_response_bytes: usize) -> Self {
+        self.max_response_bytes = max_response_bytes;
+        self
+    }
+
+    /// Limit the time a name search scans items for, or lift the limit with `None`
+    pub fn with_search_time_budget(mut self, budget: Option<Duration>) -> Self {
+        self.search_time_budget = budget;
+        self
     }
 
     /// Helper to check if a response might exceed size limits
@@ -149,25 +846,34 @@ impl DocsTools {
         &self,
         params: ListItemsParams,
     ) -> Result<ListCrateItemsOutput, DocsErrorOutput> {
+        let kind_filter = normalize_kind_filter(params.kind_filter.as_deref())
+            .map_err(|e| DocsErrorOutput::new(e.to_string()))?;
+        let variant = docs_variant(params.include_private, params.include_hidden)
+            .map_err(|e| DocsErrorOutput::new(e.to_string()))?;
         let cache = self.cache.write().await;
-        match cache
-            .ensure_crate_or_member_docs(
-                &params.crate_name,
-                &params.version,
-                params.member.as_deref(),
-            )
-            .await
+        match load_crate_docs(
+            &cache,
+            &params.crate_name,
+            &params.version,
+            params.member.as_deref(),
+            variant,
+            params.target.as_deref(),
+        )
+        .await
         {
             Ok(crate_data) => {
                 let query = DocQuery::new(crate_data);
-                let items = query.list_items(params.kind_filter.as_deref());
+                let mut items = query.list_items(kind_filter.as_deref());
+                if params.include_hidden == Some(false) {
+                    items.retain(|item| !item.hidden);
+                }
 
                 let total_count = items.len();
                 let limit = params.limit.unwrap_or(100).max(0) as usize;
                 let offset = params.offset.unwrap_or(0).max(0) as usize;
 
                 // Apply pagination
-                let paginated_items: Vec<_> = items
+                let mut paginated_items: Vec<_> = items
                     .into_iter()
                     .skip(offset)
                     .take(limit)
@@ -178,17 +884,57 @@ impl DocsTools {
                         path: item.path.clone(),
                         docs: item.docs.clone(),
                         visibility: item.visibility.clone(),
+                        field_type_id: None,
+                        field_type_path: None,
+                        hidden: item.hidden,
                     })
                     .collect();
 
+                if params.group_by_module == Some(true) {
+                    // Halve the page until the outline fits within the response size limit
+                    let mut actual_limit = limit;
+                    let mut truncated = false;
+                    let modules = loop {
+                        let modules = group_by_module(paginated_items.clone(), params.max_depth);
+                        if Self::estimate_response_size(&modules) <= self.max_response_bytes {
+                            break modules;
+                        }
+                        let new_len = paginated_items.len() / 2;
+                        if new_len == 0 {
+                            break modules;
+                        }
+                        paginated_items.truncate(new_len);
+                        actual_limit = new_len;
+                        truncated = true;
+                    };
+
+                    return Ok(ListCrateItemsOutput {
+                        items: Vec::new(),
+                        modules: Some(modules),
+                        pagination: PaginationInfo {
+                            total: total_count,
+                            limit: actual_limit,
+                            offset,
+                            has_more: offset + actual_limit < total_count,
+                        },
+                        warning: truncated.then(|| {
+                            "Response was truncated to stay within size limits. Use a smaller \
+                            limit or max_depth, or page through with offset."
+                                .to_string()
+                        }),
+                    });
+                }
+
                 Ok(ListCrateItemsOutput {
                     items: paginated_items,
+                    modules: None,
                     pagination: PaginationInfo {
                         total: total_count,
                         limit,
                         offset,
                         has_more: offset + limit < total_count,
                     },
+                    warning: None,
                 })
             }
             Err(e) => Err(DocsErrorOutput::new(format!(
@@ -197,26 +943,114 @@ impl DocsTools {
         }
     }
 
+    /// List every item of a crate, sending them in chunks through `sender`
+    ///
+    /// The cache lock is released before the first chunk is sent, so a slow consumer
+    /// does not block other tools. Streaming stops early if the receiver is dropped.
+    pub async fn stream_crate_items(
+        &self,
+        params: StreamItemsParams,
+        sender: mpsc::Sender<ItemChunk>,
+    ) -> Result<StreamCrateItemsOutput, DocsErrorOutput> {
+        let chunk_size = params.chunk_size();
+        let kind_filter = normalize_kind_filter(params.kind_filter.as_deref())
+            .map_err(|e| DocsErrorOutput::new(e.to_string()))?;
+
+        let items = {
+            let cache = self.cache.write().await;
+            match cache
+                .ensure_crate_or_member_docs(
+                    &params.crate_name,
+                    &params.version,
+                    params.member.as_deref(),
+                )
+                .await
+            {
+                Ok(crate_data) => DocQuery::new(crate_data).list_items(kind_filter.as_deref()),
+                Err(e) => {
+                    return Err(DocsErrorOutput::new(format!(
+                        "Failed to get crate docs: {e}"
+                    )));
+                }
+            }
+        };
+
+        let total = items.len();
+        let total_chunks = total.div_ceil(chunk_size);
+        let mut chunks_sent = 0;
+
+        for (chunk_index, chunk) in items.chunks(chunk_size).enumerate() {
+            let chunk = ItemChunk {
+                chunk_index,
+                total_chunks,
+                total,
+                items: chunk
+                    .iter()
+                    .map(|item| ItemInfo {
+                        id: item.id.clone(),
+                        name: item.name.clone(),
+                        kind: item.kind.clone(),
+                        path: item.path.clone(),
+                        docs: item.docs.clone(),
+                        visibility: item.visibility.clone(),
+                        field_type_id: None,
+                        field_type_path: None,
+                        hidden: item.hidden,
+                    })
+                    .collect(),
+            };
+
+            if sender.send(chunk).await.is_err() {
+                // Receiver gone, e.g. the client disconnected
+                break;
+            }
+            chunks_sent += 1;
+        }
+
+        Ok(StreamCrateItemsOutput {
+            total,
+            chunk_size,
+            chunks_sent,
+            complete: chunks_sent == total_chunks,
+        })
+    }
+
     pub async fn search_items(
         &self,
         params: SearchItemsParams,
     ) -> Result<SearchItemsOutput, DocsErrorOutput> {
+        let group_by = GroupBy::parse(params.group_by.as_deref())?;
+        let kind_filter = normalize_kind_filter(params.kind_filter.as_deref())
+            .map_err(|e| DocsErrorOutput::new(e.to_string()))?;
+
+        let variant = docs_variant(params.include_private, params.include_hidden)
+            .map_err(|e| DocsErrorOutput::new(e.to_string()))?;
         let cache = self.cache.write().await;
-        match cache
-            .ensure_crate_or_member_docs(
-                &params.crate_name,
-                &params.version,
-                params.member.as_deref(),
-            )
-            .await
+        match load_crate_docs(
+            &cache,
+            &params.crate_name,
+            &params.version,
+            params.member.as_deref(),
+            variant,
+            params.target.as_deref(),
+        )
+        .await
         {
             Ok(crate_data) => {
                 let query = DocQuery::new(crate_data);
-                let mut items = query.search_items(&params.pattern);
+                let ItemSearch {
+                    mut items,
+                    timed_out,
+                } = query.search_items_within(&params.pattern, self.search_time_budget);
 
                 // Apply kind filter if provided
-                if let Some(kind_filter) = &params.kind_filter {
-                    items.retain(|item| item.kind == *kind_filter);
+                if let Some(kinds) = kind_filter {
+                    items.retain(|item| kinds.contains(&item.kind.as_str()));
+                }
+
+                // Exclude #[doc(hidden)] items, present when configured rustdoc args document them
+                if params.include_hidden == Some(false) {
+                    items.retain(|item| !item.hidden);
                 }
 
                 // Apply path filter if provided
@@ -227,10 +1061,45 @@ impl DocsTools {
                     });
                 }
 
-                let total_count = items.len();
+                if params.dedupe_by_id.unwrap_or(false) {
+                    items = query.dedupe_reexports(items);
+                }
+
                 let limit = params.limit.unwrap_or(100).max(0) as usize;
                 let offset = params.offset.unwrap_or(0).max(0) as usize;
 
+                // Group after filtering but before pagination, so pages are made of groups
+                if group_by != GroupBy::None {
+                    let previews = items
+                        .into_iter()
+                        .map(|item| ItemPreview {
+                            id: item.id,
+                            name: item.name,
+                            kind: item.kind,
+                            path: item.path,
+                        })
+                        .collect();
+                    let groups = group_items(previews, group_by);
+                    let total_count = groups.len();
+                    let paginated_groups: Vec<_> =
+                        groups.into_iter().skip(offset).take(limit).collect();
+
+                    return Ok(SearchItemsOutput {
+                        items: Vec::new(),
+                        groups: Some(paginated_groups),
+                        pagination: PaginationInfo {
+                            total: total_count,
+                            limit,
+                            offset,
+                            has_more: offset + limit < total_count,
+                        },
+                        timed_out,
+                        warning: timed_out.then(|| SEARCH_TIMED_OUT_WARNING.to_string()),
+                    });
+                }
+
+                let total_count = items.len();
+
                 // Apply pagination
                 let mut paginated_items: Vec<_> =
                     items.into_iter().skip(offset).take(limit).collect();
@@ -250,7 +1119,7 @@ impl DocsTools {
                         }
                     });
 
-                    if Self::estimate_response_size(&test_response) <= MAX_RESPONSE_SIZE {
+                    if Self::estimate_response_size(&test_response) <= self.max_response_bytes {
                         break;
                     }
 
@@ -264,10 +1133,13 @@ impl DocsTools {
                     truncated = true;
                 }
 
-                let warning = if truncated {
-                    Some("Response was truncated to stay within size limits. Use smaller limit or preview mode.".to_string())
-                } else {
-                    None
+                let warning = match (truncated, timed_out) {
+                    (true, true) => Some(format!(
+                        "Response was truncated to stay within size limits. Use smaller limit or preview mode. {SEARCH_TIMED_OUT_WARNING}"
+                    )),
+                    (true, false) => Some("Response was truncated to stay within size limits. Use smaller limit or preview mode.".to_string()),
+                    (false, true) => Some(SEARCH_TIMED_OUT_WARNING.to_string()),
+                    (false, false) => None,
                 };
 
                 Ok(SearchItemsOutput {
@@ -280,14 +1152,19 @@ impl DocsTools {
                             path: item.path.clone(),
                             docs: item.docs.clone(),
                             visibility: item.visibility.clone(),
+                            field_type_id: None,
+                            field_type_path: None,
+                            hidden: item.hidden,
                         })
                         .collect(),
+                    groups: None,
                     pagination: PaginationInfo {
                         total: total_count,
                         limit: actual_limit,
                         offset,
                         has_more: offset + actual_limit < total_count,
                     },
+                    timed_out,
                     warning,
                 })
             }
@@ -301,22 +1178,29 @@ impl DocsTools {
         &self,
         params: SearchItemsPreviewParams,
     ) -> Result<SearchItemsPreviewOutput, DocsErrorOutput> {
+        let kind_filter = normalize_kind_filter(params.kind_filter.as_deref())
+            .map_err(|e| DocsErrorOutput::new(e.to_string()))?;
         let cache = self.cache.write().await;
-        match cache
-            .ensure_crate_or_member_docs(
-                &params.crate_name,
-                &params.version,
-                params.member.as_deref(),
-            )
-            .await
+        match load_crate_docs(
+            &cache,
+            &params.crate_name,
+            &params.version,
+            params.member.as_deref(),
+            None,
+            params.target.as_deref(),
+        )
+        .await
         {
             Ok(crate_data) => {
                 let query = DocQuery::new(crate_data);
-                let mut items = query.search_items(&params.pattern);
+                let ItemSearch {
+                    mut items,
+                    timed_out,
+                } = query.search_items_within(&params.pattern, self.search_time_budget);
 
                 // Apply kind filter if provided
-                if let Some(kind_filter) = &params.kind_filter {
-                    items.retain(|item| item.kind == *kind_filter);
+                if let Some(kinds) = kind_filter {
+                    items.retain(|item| kinds.contains(&item.kind.as_str()));
                 }
 
                 // Apply path filter if provided
@@ -327,6 +1211,10 @@ impl DocsTools {
                     });
                 }
 
+                if params.dedupe_by_id.unwrap_or(false) {
+                    items = query.dedupe_reexports(items);
+                }
+
                 let total_count = items.len();
                 let limit = params.limit.unwrap_or(100).max(0) as usize;
                 let offset = params.offset.unwrap_or(0).max(0) as usize;
@@ -369,6 +1257,8 @@ impl DocsTools {
                         offset,
                         has_more: offset + limit < total_count,
                     },
+                    timed_out,
+                    warning: timed_out.then(|| SEARCH_TIMED_OUT_WARNING.to_string()),
                 })
             }
             Err(e) => Err(DocsErrorOutput::new(format!(
@@ -378,21 +1268,77 @@ impl DocsTools {
     }
 
     pub async fn get_item_details(&self, params: GetItemDetailsParams) -> GetItemDetailsOutput {
+        if let Some(selected) = &params.fields
+            && let Some(unknown) = selected
+                .iter()
+                .find(|field| !DETAIL_FIELDS.contains(&field.as_str()))
+        {
+            return GetItemDetailsOutput::Error {
+                error: format!(
+                    "Unknown field '{unknown}'. Available fields: {}",
+                    DETAIL_FIELDS.join(", ")
+                ),
+            };
+        }
+
         let cache = self.cache.write().await;
-        match cache
-            .ensure_crate_or_member_docs(
-                &params.crate_name,
-                &params.version,
-                params.member.as_deref(),
-            )
-            .await
+        match load_crate_docs(
+            &cache,
+            &params.crate_name,
+            &params.version,
+            params.member.as_deref(),
+            None,
+            params.target.as_deref(),
+        )
+        .await
         {
             Ok(crate_data) => {
                 let query = DocQuery::new(crate_data);
-                match query.get_item_details(params.item_id.max(0) as u32) {
+                let item_id = match (params.item_id, params.path.as_deref()) {
+                    (Some(item_id), None) => item_id,
+                    (None, Some(path)) => match query.resolve_path(path).as_slice() {
+                        [] => {
+                            return GetItemDetailsOutput::Error {
+                                error: format!("No item found matching path '{path}'"),
+                            };
+                        }
+                        [item_id] => *item_id,
+                        item_ids => {
+                            return GetItemDetailsOutput::Ambiguous {
+                                error: format!(
+                                    "Path '{path}' matches {} items. Call again with the item_id of one of the candidates",
+                                    item_ids.len()
+                                ),
+                                candidates: item_ids
+                                    .iter()
+                                    .filter_map(|&id| query.get_item_info(id))
+                                    .map(|info| ItemPreview {
+                                        id: info.id,
+                                        name: info.name,
+                                        kind: info.kind,
+                                        path: info.path,
+                                    })
+                                    .collect(),
+                            };
+                        }
+                    },
+                    (Some(_), Some(_)) => {
+                        return GetItemDetailsOutput::Error {
+                            error: "Only one of 'item_id' or 'path' can be specified, not both"
+                                .to_string(),
+                        };
+                    }
+                    (None, None) => {
+                        return GetItemDetailsOutput::Error {
+                            error: "Either 'item_id' or 'path' must be specified".to_string(),
+                        };
+                    }
+                };
+
+                match query.get_item_details(item_id) {
                     Ok(details) => {
                         // Convert the details to our output format
-                        GetItemDetailsOutput::Success(Box::new(DetailedItem {
+                        let mut item = DetailedItem {
                             info: ItemInfo {
                                 id: details.info.id.clone(),
                                 name: details.info.name.clone(),
@@ -400,9 +1346,24 @@ impl DocsTools {
                                 path: details.info.path.clone(),
                                 docs: details.info.docs.clone(),
                                 visibility: details.info.visibility.clone(),
+                                field_type_id: None,
+                                field_type_path: None,
+                                hidden: details.info.hidden,
                             },
                             signature: details.signature.clone(),
                             generics: details.generics.clone(),
+                            generic_params: details.generic_params.map(|params| {
+                                params
+                                    .into_iter()
+                                    .map(|p| GenericParam {
+                                        name: p.name,
+                                        kind: p.kind,
+                                        type_: p.type_,
+                                        default: p.default,
+                                        bounds: p.bounds,
+                                    })
+                                    .collect()
+                            }),
                             fields: details.fields.map(|fields| {
                                 fields
                                     .into_iter()
@@ -413,6 +1374,9 @@ impl DocsTools {
                                         path: f.path,
                                         docs: f.docs,
                                         visibility: f.visibility,
+                                        field_type_id: None,
+                                        field_type_path: None,
+                                        hidden: f.hidden,
                                     })
                                     .collect()
                             }),
@@ -426,6 +1390,9 @@ impl DocsTools {
                                         path: v.path,
                                         docs: v.docs,
                                         visibility: v.visibility,
+                                        field_type_id: None,
+                                        field_type_path: None,
+                                        hidden: v.hidden,
                                     })
                                     .collect()
                             }),
@@ -439,6 +1406,9 @@ impl DocsTools {
                                         path: m.path,
                                         docs: m.docs,
                                         visibility: m.visibility,
+                                        field_type_id: None,
+                                        field_type_path: None,
+                                        hidden: m.hidden,
                                     })
                                     .collect()
                             }),
@@ -449,7 +1419,33 @@ impl DocsTools {
                                 line_end: loc.line_end,
                                 column_end: loc.column_end,
                             }),
-                        }))
+                            docsrs_url: None,
+                            sealed: details.sealed_note.is_some(),
+                            sealed_note: details.sealed_note,
+                        };
+                        if is_from_crates_io(&cache, &params.crate_name, &params.version) {
+                            item.docsrs_url = docsrs::item_url(
+                                cache.docsrs_url(),
+                                &params.crate_name,
+                                &params.version,
+                                &item.info.kind,
+                                &item.info.path,
+                            );
+                        }
+                        if params.resolve_field_types.unwrap_or(false) {
+                            for member in item
+                                .fields
+                                .iter_mut()
+                                .chain(item.variants.iter_mut())
+                                .flatten()
+                            {
+                                link_field_type(&query, member);
+                            }
+                        }
+                        if let Some(selected) = &params.fields {
+                            item.retain_fields(selected);
+                        }
+                        GetItemDetailsOutput::Success(Box::new(item))
                     }
                     Err(e) => GetItemDetailsOutput::Error {
                         error: format!("Item not found: {e}"),
@@ -466,26 +1462,75 @@ impl DocsTools {
         &self,
         params: GetItemDocsParams,
     ) -> Result<GetItemDocsOutput, DocsErrorOutput> {
-        let cache = self.cache.write().await;
-        match cache
-            .ensure_crate_or_member_docs(
+        let item_id = params.item_id;
+        let strip = |docs: String| {
+            if params.strip_doctest_scaffolding.unwrap_or(false) {
+                strip_doctest_scaffolding(&docs)
+            } else {
+                docs
+            }
+        };
+
+        // The lookup only reads cached docs, so it does not block other readers
+        let item = {
+            let cache = self.cache.read().await;
+            find_cached_item(
+                &cache,
                 &params.crate_name,
                 &params.version,
                 params.member.as_deref(),
+                params.target.as_deref(),
+                item_id,
             )
             .await
+        };
+
+        // Documented items are answered without loading the whole crate, and so
+        // are undocumented ones unless they are trait impl items, which may
+        // inherit the docs of the trait
+        if let Some(item) = item {
+            match item.docs.filter(|docs| !docs.trim().is_empty()) {
+                Some(docs) => {
+                    return Ok(GetItemDocsOutput {
+                        documentation: Some(strip(docs)),
+                        source: None,
+                        message: None,
+                    });
+                }
+                None if item.visibility != rustdoc_types::Visibility::Default => {
+                    return Ok(GetItemDocsOutput {
+                        documentation: None,
+                        source: None,
+                        message: Some("No documentation available for this item".to_string()),
+                    });
+                }
+                None => {}
+            }
+        }
+
+        let cache = self.cache.write().await;
+        match load_crate_docs(
+            &cache,
+            &params.crate_name,
+            &params.version,
+            params.member.as_deref(),
+            None,
+            params.target.as_deref(),
+        )
+        .await
         {
             Ok(crate_data) => {
                 let query = DocQuery::new(crate_data);
-                match query.get_item_docs(params.item_id.max(0) as u32) {
-                    Ok(docs) => {
+                match item_docs(&query, item_id) {
+                    Ok((docs, source)) => {
                         let message = if docs.is_none() {
                             Some("No documentation available for this item".to_string())
                         } else {
                             None
                         };
                         Ok(GetItemDocsOutput {
-                            documentation: docs,
+                            documentation: docs.map(strip),
+                            source,
                             message,
                         })
                     }
@@ -498,6 +1543,587 @@ impl DocsTools {
         }
     }
 
+    /// Get the documentation of several items, looked up by path
+    ///
+    /// Names that match no item or several items are reported with an error
+    /// rather than failing the whole call. Once the response would exceed the
+    /// size limit, the remaining names are listed in `omitted`.
+    pub async fn get_docs_for_names(
+        &self,
+        params: GetDocsForNamesParams,
+    ) -> Result<GetDocsForNamesOutput, DocsErrorOutput> {
+        if params.names.is_empty() {
+            return Err(DocsErrorOutput::new(
+                "'names' must list at least one item path",
+            ));
+        }
+
+        let cache = self.cache.write().await;
+        let crate_data = load_crate_docs(
+            &cache,
+            &params.crate_name,
+            &params.version,
+            params.member.as_deref(),
+            None,
+            params.target.as_deref(),
+        )
+        .await
+        .map_err(|e| DocsErrorOutput::new(format!("Failed to get crate docs: {e}")))?;
+        drop(cache);
+
+        let query = DocQuery::new(crate_data);
+        let mut docs = BTreeMap::new();
+        let mut omitted = Vec::new();
+        let mut size = 0;
+        for name in params.names {
+            if docs.contains_key(&name) {
+                continue;
+            }
+            if !omitted.is_empty() {
+                omitted.push(name);
+                continue;
+            }
+
+            let entry = match query.resolve_path(&name).as_slice() {
+                [] => NameDocs::error(format!("No item found matching path '{name}'")),
+                [item_id] => match item_docs(&query, *item_id) {
+                    Ok((documentation, source)) => NameDocs {
+                        id: Some(item_id.to_string()),
+                        documentation,
+                        source,
+                        error: None,
+                    },
+                    Err(e) => NameDocs::error(format!("Failed to get docs: {e}")),
+                },
+                item_ids => NameDocs::error(format!(
+                    "Path '{name}' matches {} items (ids {}). Use get_item_docs with one of them",
+                    item_ids.len(),
+                    item_ids
+                        .iter()
+                        .map(u32::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )),
+            };
+
+            // The first entry is always kept
+            let entry_size = Self::estimate_response_size(&entry) + name.len();
+            if !docs.is_empty() && size + entry_size > self.max_response_bytes {
+                omitted.push(name);
+                continue;
+            }
+            size += entry_size;
+            docs.insert(name, entry);
+        }
+
+        let warning = (!omitted.is_empty()).then(|| {
+            format!(
+                "Docs of {} names were omitted to stay within size limits. Request them in a separate call.",
+                omitted.len()
+            )
+        });
+
+        Ok(GetDocsForNamesOutput {
+            docs,
+            omitted,
+            warning,
+        })
+    }
+
+    pub async fn get_blanket_impls(
+        &self,
+        params: GetBlanketImplsParams,
+    ) -> Result<GetBlanketImplsOutput, DocsErrorOutput> {
+        let cache = self.cache.write().await;
+        let crate_data = load_crate_docs(
+            &cache,
+            &params.crate_name,
+            &params.version,
+            params.member.as_deref(),
+            None,
+            params.target.as_deref(),
+        )
+        .await
+        .map_err(|e| DocsErrorOutput::new(format!("Failed to get crate docs: {e}")))?;
+
+        let query = DocQuery::new(crate_data);
+        let item_id = params.item_id;
+        let info = query
+            .get_item_info(item_id)
+            .ok_or_else(|| DocsErrorOutput::new(format!("Item {item_id} not found")))?;
+
+        let blanket_traits = query
+            .get_blanket_impls(item_id)
+            .map_err(|e| DocsErrorOutput::new(format!("Failed to get blanket impls: {e}")))?
+            .into_iter()
+            .map(|b| BlanketImpl {
+                impl_id: b.impl_id,
+                trait_name: b.trait_name,
+                trait_path: b.trait_path,
+                for_type: b.for_type,
+            })
+            .collect();
+        let auto_traits = query
+            .get_auto_trait_impls(item_id)
+            .map_err(|e| DocsErrorOutput::new(format!("Failed to get auto trait impls: {e}")))?;
+
+        Ok(GetBlanketImplsOutput {
+            item: ItemPreview {
+                id: info.id,
+                name: info.name,
+                kind: info.kind,
+                path: info.path,
+            },
+            blanket_traits,
+            auto_traits,
+        })
+    }
+
+    pub async fn get_repr_info(
+        &self,
+        params: GetReprInfoParams,
+    ) -> Result<GetReprInfoOutput, DocsErrorOutput> {
+        let cache = self.cache.write().await;
+        let crate_data = load_crate_docs(
+            &cache,
+            &params.crate_name,
+            &params.version,
+            params.member.as_deref(),
+            None,
+            params.target.as_deref(),
+        )
+        .await
+        .map_err(|e| DocsErrorOutput::new(format!("Failed to get crate docs: {e}")))?;
+
+        let query = DocQuery::new(crate_data);
+        let item_id = params.item_id;
+        let info = query
+            .get_item_info(item_id)
+            .ok_or_else(|| DocsErrorOutput::new(format!("Item {item_id} not found")))?;
+        let repr_info = query
+            .get_repr_info(item_id)
+            .map_err(|e| DocsErrorOutput::new(format!("Failed to get repr info: {e}")))?;
+
+        Ok(GetReprInfoOutput {
+            item: ItemPreview {
+                id: info.id,
+                name: info.name,
+                kind: info.kind,
+                path: info.path,
+            },
+            repr: repr_info.repr,
+            derives: repr_info.derives,
+            auto_traits: repr_info.auto_traits,
+            fields: repr_info.fields,
+        })
+    }
+
+    pub async fn list_cfg_attributes(
+        &self,
+        params: ListCfgAttributesParams,
+    ) -> Result<ListCfgAttributesOutput, DocsErrorOutput> {
+        let cache = self.cache.write().await;
+        let crate_data = load_crate_docs(
+            &cache,
+            &params.crate_name,
+            &params.version,
+            params.member.as_deref(),
+            None,
+            params.target.as_deref(),
+        )
+        .await
+        .map_err(|e| DocsErrorOutput::new(format!("Failed to get crate docs: {e}")))?;
+
+        let cfgs = DocQuery::new(crate_data)
+            .cfg_predicates()
+            .into_iter()
+            .map(|(predicate, item_count)| CfgAttribute {
+                predicate,
+                item_count,
+            })
+            .collect();
+
+        Ok(ListCfgAttributesOutput { cfgs })
+    }
+
+    pub async fn get_all_methods(
+        &self,
+        params: GetAllMethodsParams,
+    ) -> Result<GetAllMethodsOutput, DocsErrorOutput> {
+        let cache = self.cache.write().await;
+        let crate_data = load_crate_docs(
+            &cache,
+            &params.crate_name,
+            &params.version,
+            params.member.as_deref(),
+            None,
+            params.target.as_deref(),
+        )
+        .await
+        .map_err(|e| DocsErrorOutput::new(format!("Failed to get crate docs: {e}")))?;
+
+        let query = DocQuery::new(crate_data);
+        let item_id = params.item_id;
+        let info = query
+            .get_item_info(item_id)
+            .ok_or_else(|| DocsErrorOutput::new(format!("Item {item_id} not found")))?;
+
+        let methods = query
+            .get_all_methods(item_id, params.include_blanket.unwrap_or(false))
+            .map_err(|e| DocsErrorOutput::new(format!("Failed to get methods: {e}")))?
+            .into_iter()
+            .map(|m| MethodEntry {
+                name: m.name,
+                signature: m.signature,
+                from: m.from,
+            })
+            .collect();
+
+        Ok(GetAllMethodsOutput {
+            item: ItemPreview {
+                id: info.id,
+                name: info.name,
+                kind: info.kind,
+                path: info.path,
+            },
+            methods,
+        })
+    }
+
+    pub async fn get_supertraits(
+        &self,
+        params: GetSupertraitsParams,
+    ) -> Result<GetSupertraitsOutput, DocsErrorOutput> {
+        let cache = self.cache.write().await;
+        let crate_data = load_crate_docs(
+            &cache,
+            &params.crate_name,
+            &params.version,
+            params.member.as_deref(),
+            None,
+            params.target.as_deref(),
+        )
+        .await
+        .map_err(|e| DocsErrorOutput::new(format!("Failed to get crate docs: {e}")))?;
+
+        let query = DocQuery::new(crate_data);
+        let item_id = params.item_id;
+        let info = query
+            .get_item_info(item_id)
+            .ok_or_else(|| DocsErrorOutput::new(format!("Item {item_id} not found")))?;
+
+        let transitive: Vec<SupertraitEntry> = query
+            .get_supertraits(item_id)
+            .map_err(|e| DocsErrorOutput::new(format!("Failed to get supertraits: {e}")))?
+            .into_iter()
+            .map(|supertrait| SupertraitEntry {
+                id: supertrait.id.to_string(),
+                path: supertrait.path,
+                via: supertrait.via,
+            })
+            .collect();
+        let direct = transitive
+            .iter()
+            .filter(|supertrait| supertrait.via.is_none())
+            .cloned()
+            .collect();
+
+        Ok(GetSupertraitsOutput {
+            item: ItemPreview {
+                id: info.id,
+                name: info.name,
+                kind: info.kind,
+                path: info.path,
+            },
+            direct,
+            transitive,
+        })
+    }
+
+    pub async fn get_item_raw(
+        &self,
+        params: GetItemRawParams,
+    ) -> Result<GetItemRawOutput, DocsErrorOutput> {
+        let cache = self.cache.write().await;
+        let crate_data = load_crate_docs(
+            &cache,
+            &params.crate_name,
+            &params.version,
+            params.member.as_deref(),
+            None,
+            params.target.as_deref(),
+        )
+        .await
+        .map_err(|e| DocsErrorOutput::new(format!("Failed to get crate docs: {e}")))?;
+
+        let format_version = crate_data.format_version;
+        let query = DocQuery::new(crate_data);
+        let item_id = params.item_id;
+        let depth = params
+            .depth
+            .map(|d| d.clamp(0, MAX_RAW_DEPTH as i64) as usize)
+            .unwrap_or(DEFAULT_RAW_DEPTH);
+
+        let entries = query
+            .get_item_raw(item_id, depth)
+            .map_err(|e| DocsErrorOutput::new(format!("Failed to get item {item_id}: {e}")))?;
+
+        // Entries are ordered by depth, so the ones left out to stay within the
+        // size limit are the deepest. The item itself is always kept.
+        let total = entries.len();
+        let mut items = serde_json::Map::new();
+        let mut size = 0;
+        for (id, value) in entries {
+            let entry_size = Self::estimate_response_size(&value);
+            if !items.is_empty() && size + entry_size > self.max_response_bytes {
+                break;
+            }
+            size += entry_size;
+            items.insert(id.0.to_string(), value);
+        }
+        let omitted = total - items.len();
+
+        let warning = (omitted > 0).then(|| {
+            format!(
+                "{omitted} referenced items were omitted to stay within size limits. Use a smaller depth or request them by ID."
+            )
+        });
+
+        Ok(GetItemRawOutput {
+            format_version,
+            item_id: item_id.to_string(),
+            depth,
+            items,
+            warning,
+        })
+    }
+
+    pub async fn export_docs_markdown(
+        &self,
+        params: ExportDocsMarkdownParams,
+    ) -> Result<ExportDocsMarkdownOutput, DocsErrorOutput> {
+        let kind_filter = normalize_kind_filter(params.kind_filter.as_deref())
+            .map_err(|e| DocsErrorOutput::new(e.to_string()))?;
+        let output_path = params.output_path.as_deref().map(std::path::Path::new);
+        if let Some(path) = output_path
+            && !path.is_absolute()
+        {
+            return Err(DocsErrorOutput::new(format!(
+                "output_path must be an absolute path, got '{}'",
+                path.display()
+            )));
+        }
+
+        let cache = self.cache.write().await;
+        let crate_data = load_crate_docs(
+            &cache,
+            &params.crate_name,
+            &params.version,
+            params.member.as_deref(),
+            None,
+            params.target.as_deref(),
+        )
+        .await
+        .map_err(|e| DocsErrorOutput::new(format!("Failed to get crate docs: {e}")))?;
+
+        let query = DocQuery::new(crate_data);
+        let options = MarkdownOptions {
+            kind_filter: kind_filter.as_deref(),
+            max_items: params.max_items.map(|max| max.max(0) as usize),
+        };
+        let export = render_crate_markdown(&query, &params.crate_name, &params.version, &options);
+
+        if let Some(path) = output_path {
+            std::fs::write(path, &export.markdown).map_err(|e| {
+                DocsErrorOutput::new(format!("Failed to write {}: {e}", path.display()))
+            })?;
+            return Ok(ExportDocsMarkdownOutput {
+                markdown: None,
+                output_path: Some(path.display().to_string()),
+                item_count: export.item_count,
+                truncated: export.truncated,
+                warning: None,
+            });
+        }
+
+        let warning = (export.markdown.len() > self.max_response_bytes).then(|| {
+            "Document is large and may exceed token limits. Set 'output_path' to write it to a file, or use 'max_items' or 'kind_filter' to shrink it".to_string()
+        });
+        Ok(ExportDocsMarkdownOutput {
+            markdown: Some(export.markdown),
+            output_path: None,
+            item_count: export.item_count,
+            truncated: export.truncated,
+            warning,
+        })
+    }
+
+    pub async fn export_items_ndjson(
+        &self,
+        params: ExportItemsNdjsonParams,
+    ) -> Result<ExportItemsNdjsonOutput, DocsErrorOutput> {
+        let kind_filter = normalize_kind_filter(params.kind_filter.as_deref())
+            .map_err(|e| DocsErrorOutput::new(e.to_string()))?;
+        let output_path = std::path::Path::new(&params.output_path);
+        if !output_path.is_absolute() {
+            return Err(DocsErrorOutput::new(format!(
+                "output_path must be an absolute path, got '{}'",
+                output_path.display()
+            )));
+        }
+
+        let variant = docs_variant(params.include_private, params.include_hidden)
+            .map_err(|e| DocsErrorOutput::new(e.to_string()))?;
+        let cache = self.cache.write().await;
+        let crate_data = load_crate_docs(
+            &cache,
+            &params.crate_name,
+            &params.version,
+            params.member.as_deref(),
+            variant,
+            params.target.as_deref(),
+        )
+        .await
+        .map_err(|e| DocsErrorOutput::new(format!("Failed to get crate docs: {e}")))?;
+        drop(cache);
+
+        let query = DocQuery::new(crate_data);
+        let mut items = query.list_items(kind_filter.as_deref());
+        if params.include_hidden == Some(false) {
+            items.retain(|item| !item.hidden);
+        }
+        items.sort_by_key(|item| item.id.parse::<u32>().unwrap_or(u32::MAX));
+
+        let header = NdjsonHeader {
+            crate_name: params.crate_name,
+            version: params.version,
+            item_count: items.len(),
+        };
+        let write_error = |e: std::io::Error| {
+            DocsErrorOutput::new(format!("Failed to write {}: {e}", output_path.display()))
+        };
+        let file = std::fs::File::create(output_path).map_err(write_error)?;
+        let bytes_written = write_items_ndjson(&mut std::io::BufWriter::new(file), &header, &items)
+            .map_err(write_error)?;
+
+        Ok(ExportItemsNdjsonOutput {
+            output_path: output_path.display().to_string(),
+            item_count: header.item_count,
+            bytes_written,
+        })
+    }
+
+    /// List the items defined in files changed between two commits of a crate cloned from git
+    pub async fn get_changed_items_since(
+        &self,
+        params: GetChangedItemsSinceParams,
+    ) -> Result<ChangedItemsOutput, DocsErrorOutput> {
+        let until = params.until.unwrap_or_else(|| "HEAD".to_string());
+
+        let cache = self.cache.write().await;
+        let git_path = cache
+            .storage
+            .git_path(&params.crate_name, &params.version)
+            .map_err(|e| DocsErrorOutput::new(e.to_string()))?;
+        if !git_path.exists() {
+            return Err(DocsErrorOutput::new(format!(
+                "No git history is kept for {}-{}. Only GitHub sources cached with --keep-git-history have one",
+                params.crate_name, params.version
+            )));
+        }
+
+        // Sources cached from a directory of the repository are saved as "url#path"
+        let subdir = cache
+            .storage
+            .load_metadata(&params.crate_name, &params.version, None)
+            .ok()
+            .and_then(|metadata| metadata.source_path)
+            .and_then(|source| source.split_once('#').map(|(_, path)| path.to_string()));
+        let files = changed_files(&git_path, &params.since, &until, subdir.as_deref())
+            .map_err(|e| DocsErrorOutput::new(format!("{e:#}")))?;
+
+        let crate_data = load_crate_docs(
+            &cache,
+            &params.crate_name,
+            &params.version,
+            params.member.as_deref(),
+            None,
+            None,
+        )
+        .await
+        .map_err(|e| DocsErrorOutput::new(format!("Failed to get crate docs: {e}")))?;
+        drop(cache);
+
+        let changed_items = DocQuery::new(crate_data)
+            .items_in_files(&files)
+            .into_iter()
+            .map(|item| ItemInfo {
+                id: item.id,
+                name: item.name,
+                kind: item.kind,
+                path: item.path,
+                docs: item.docs,
+                visibility: item.visibility,
+                field_type_id: None,
+                field_type_path: None,
+                hidden: item.hidden,
+            })
+            .collect();
+
+        Ok(ChangedItemsOutput {
+            since: params.since,
+            until,
+            changed_files: files.into_iter().collect(),
+            changed_items,
+        })
+    }
+
+    /// Report the items that became public or stopped being public between two versions
+    pub async fn diff_visibility(
+        &self,
+        params: DiffVisibilityParams,
+    ) -> Result<DiffVisibilityOutput, DocsErrorOutput> {
+        let mut versions = Vec::new();
+        for version in [&params.version_a, &params.version_b] {
+            // Private docs are needed to see the items that are not public
+            let cache = self.cache.write().await;
+            let crate_data = load_crate_docs(
+                &cache,
+                &params.crate_name,
+                version,
+                params.member.as_deref(),
+                Some(DocsVariant::Private),
+                None,
+            )
+            .await
+            .map_err(|e| {
+                DocsErrorOutput::new(format!(
+                    "Failed to get crate docs for {}-{version}: {e}",
+                    params.crate_name
+                ))
+            })?;
+            drop(cache);
+            versions.push(DocQuery::new(crate_data).item_visibilities());
+        }
+        let to = versions.pop().unwrap_or_default();
+        let from = versions.pop().unwrap_or_default();
+
+        let to_output = |change: visibility::VisibilityChange| VisibilityChange {
+            path: change.path,
+            kind: change.kind,
+            from: change.from,
+            to: change.to,
+        };
+        let diff = diff_visibility(from, to);
+        Ok(DiffVisibilityOutput {
+            crate_name: params.crate_name,
+            version_a: params.version_a,
+            version_b: params.version_b,
+            newly_public: diff.newly_public.into_iter().map(to_output).collect(),
+            newly_private: diff.newly_private.into_iter().map(to_output).collect(),
+        })
+    }
+
     pub async fn get_item_source(&self, params: GetItemSourceParams) -> GetItemSourceOutput {
         let cache = self.cache.write().await;
         let source_base_path = match cache.get_source_path(&params.crate_name, &params.version) {
@@ -509,42 +2135,245 @@ impl DocsTools {
             }
         };
 
-        match cache
-            .ensure_crate_or_member_docs(
+        let item_id = params.item_id;
+        let context_lines = params.context_lines.unwrap_or(3).max(0) as usize;
+        let resolve_references = params.resolve_references.unwrap_or(false);
+
+        // Only the item's span is needed, so avoid loading the whole crate if possible.
+        // Resolving references needs the whole crate anyway.
+        let cached_item = if resolve_references {
+            None
+        } else {
+            find_cached_item(
+                &cache,
                 &params.crate_name,
                 &params.version,
                 params.member.as_deref(),
+                params.target.as_deref(),
+                item_id,
             )
             .await
-        {
-            Ok(crate_data) => {
-                let query = DocQuery::new(crate_data);
-                let context_lines = params.context_lines.unwrap_or(3).max(0) as usize;
-
-                match query.get_item_source(
-                    params.item_id.max(0) as u32,
-                    &source_base_path,
-                    context_lines,
-                ) {
-                    Ok(source_info) => GetItemSourceOutput::Success(SourceInfo {
-                        location: SourceLocation {
-                            filename: source_info.location.filename,
-                            line_start: source_info.location.line_start,
-                            column_start: source_info.location.column_start,
-                            line_end: source_info.location.line_end,
-                            column_end: source_info.location.column_end,
-                        },
-                        code: source_info.code,
-                        context_lines: source_info.context_lines,
-                    }),
-                    Err(e) => GetItemSourceOutput::Error {
-                        error: format!("Failed to get source: {e}"),
-                    },
+        };
+        let (source_info, references) = match cached_item {
+            Some(item) => (item_source(&item, &source_base_path, context_lines), None),
+            None => match load_crate_docs(
+                &cache,
+                &params.crate_name,
+                &params.version,
+                params.member.as_deref(),
+                None,
+                params.target.as_deref(),
+            )
+            .await
+            {
+                Ok(crate_data) => {
+                    let query = DocQuery::new(crate_data);
+                    let references = resolve_references.then(|| {
+                        query
+                            .get_item_references(item_id)
+                            .into_iter()
+                            .map(|reference| ReferencedDefinition {
+                                id: reference.id,
+                                name: reference.name,
+                                filename: reference.filename,
+                                line: reference.line,
+                            })
+                            .collect()
+                    });
+                    (
+                        query.get_item_source(item_id, &source_base_path, context_lines),
+                        references,
+                    )
                 }
-            }
+                Err(e) => {
+                    return GetItemSourceOutput::Error {
+                        error: format!("Failed to get crate docs: {e}"),
+                    };
+                }
+            },
+        };
+
+        match source_info {
+            Ok(source_info) => GetItemSourceOutput::Success(SourceInfo {
+                location: SourceLocation {
+                    filename: source_info.location.filename,
+                    line_start: source_info.location.line_start,
+                    column_start: source_info.location.column_start,
+                    line_end: source_info.location.line_end,
+                    column_end: source_info.location.column_end,
+                },
+                code: if params.strip_doctest_scaffolding.unwrap_or(false) {
+                    strip_source_doctest_scaffolding(&source_info.code)
+                } else {
+                    source_info.code
+                },
+                context_lines: source_info.context_lines,
+                references,
+            }),
             Err(e) => GetItemSourceOutput::Error {
-                error: format!("Failed to get crate docs: {e}"),
+                error: format!("Failed to get source: {e}"),
             },
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preview(id: &str, name: &str, kind: &str, path: &[&str]) -> ItemPreview {
+        ItemPreview {
+            id: id.to_string(),
+            name: name.to_string(),
+            kind: kind.to_string(),
+            path: path.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_stream_chunk_size_is_clamped() {
+        let mut params = StreamItemsParams {
+            crate_name: "serde".to_string(),
+            version: "1.0.0".to_string(),
+            kind_filter: None,
+            chunk_size: None,
+            member: None,
+        };
+        assert_eq!(params.chunk_size(), DEFAULT_STREAM_CHUNK_SIZE);
+
+        params.chunk_size = Some(0);
+        assert_eq!(params.chunk_size(), 1);
+
+        params.chunk_size = Some(1_000_000);
+        assert_eq!(params.chunk_size(), MAX_STREAM_CHUNK_SIZE);
+        assert_eq!(
+            params.to_list_params().limit,
+            Some(MAX_STREAM_CHUNK_SIZE as i64)
+        );
+    }
+
+    #[test]
+    fn test_item_id_accepts_numbers_and_strings() {
+        let params = |item_id: serde_json::Value| {
+            serde_json::from_value::<GetItemDocsParams>(serde_json::json!({
+                "crate_name": "serde",
+                "version": "1.0.0",
+                "item_id": item_id,
+            }))
+        };
+        assert_eq!(params(serde_json::json!(42)).unwrap().item_id, 42);
+        assert_eq!(params(serde_json::json!("42")).unwrap().item_id, 42);
+        assert_eq!(
+            params(serde_json::json!(u32::MAX)).unwrap().item_id,
+            u32::MAX
+        );
+        assert!(params(serde_json::json!(-1)).is_err());
+        assert!(params(serde_json::json!(u64::from(u32::MAX) + 1)).is_err());
+
+        let error = params(serde_json::json!("0:123:456")).unwrap_err();
+        assert!(error.to_string().contains("older rustdoc versions"));
+
+        let details: GetItemDetailsParams = serde_json::from_value(serde_json::json!({
+            "crate_name": "serde",
+            "version": "1.0.0",
+            "path": "serde::Serialize",
+        }))
+        .unwrap();
+        assert_eq!(details.item_id, None);
+    }
+
+    #[test]
+    fn test_group_by_parse() {
+        assert_eq!(GroupBy::parse(None).unwrap(), GroupBy::None);
+        assert_eq!(GroupBy::parse(Some("name")).unwrap(), GroupBy::Name);
+        assert_eq!(GroupBy::parse(Some("kind")).unwrap(), GroupBy::Kind);
+        assert!(GroupBy::parse(Some("signature")).is_err());
+    }
+
+    #[test]
+    fn test_group_items_by_name() {
+        let items = vec![
+            preview("1", "new", "function", &["crate", "Foo", "new"]),
+            preview("2", "New", "struct", &["crate", "New"]),
+            preview("3", "new", "function", &["crate", "Bar", "new"]),
+        ];
+
+        let groups = group_items(items, GroupBy::Name);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].key, "new");
+        assert_eq!(groups[0].count, 2);
+        assert_eq!(groups[0].items[1].id, "3");
+        assert_eq!(groups[1].key, "New");
+        assert_eq!(groups[1].count, 1);
+    }
+
+    #[test]
+    fn test_group_items_by_kind() {
+        let items = vec![
+            preview("1", "new", "function", &["crate", "Foo", "new"]),
+            preview("2", "Foo", "struct", &["crate", "Foo"]),
+            preview("3", "parse", "function", &["crate", "parse"]),
+        ];
+
+        let groups = group_items(items, GroupBy::Kind);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].key, "function");
+        assert_eq!(groups[0].count, 2);
+        assert_eq!(groups[1].key, "struct");
+    }
+
+    #[test]
+    fn test_group_by_module() {
+        let item = |id: &str, path: &[&str]| ItemInfo {
+            id: id.to_string(),
+            name: path.last().unwrap_or(&"").to_string(),
+            kind: "struct".to_string(),
+            path: path.iter().map(|p| p.to_string()).collect(),
+            docs: None,
+            visibility: "public".to_string(),
+            field_type_id: None,
+            field_type_path: None,
+            hidden: false,
+        };
+        let items = || {
+            vec![
+                item("1", &["demo", "Root"]),
+                item("2", &["demo", "de", "value", "Value"]),
+                item("3", &["demo", "de", "Error"]),
+                item("4", &["demo", "de", "value", "Map"]),
+            ]
+        };
+        let outline = |modules: Vec<ModuleItems>| -> Vec<(String, Vec<String>)> {
+            modules
+                .into_iter()
+                .map(|module| {
+                    let ids = module.items.into_iter().map(|item| item.id).collect();
+                    (module.path, ids)
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            outline(group_by_module(items(), None)),
+            [
+                ("demo".to_string(), vec!["1".to_string()]),
+                ("demo::de".to_string(), vec!["3".to_string()]),
+                (
+                    "demo::de::value".to_string(),
+                    vec!["2".to_string(), "4".to_string()]
+                ),
+            ]
+        );
+        assert_eq!(
+            outline(group_by_module(items(), Some(1))),
+            [
+                ("demo".to_string(), vec!["1".to_string()]),
+                (
+                    "demo::de".to_string(),
+                    vec!["2".to_string(), "3".to_string(), "4".to_string()]
+                ),
+            ]
+        );
+        assert_eq!(group_by_module(items(), Some(0)).len(), 1);
+    }
+}
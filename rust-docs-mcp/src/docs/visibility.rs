@@ -0,0 +1,119 @@
+//! Visibility changes between two versions of a crate
+//!
+//! Items are matched across versions by path and kind, since item IDs are not
+//! stable between builds. Inherent methods are included as `Type::method`.
+//! Only changes into and out of `pub` are reported: a move between `pub(crate)`
+//! and `pub(super)` does not change the public API.
+
+use std::collections::HashMap;
+
+/// The visibility of an item in one version of a crate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemVisibility {
+    /// `::`-separated path of the item, e.g. `semver::Version::parse`
+    pub path: String,
+    pub kind: String,
+    /// Visibility as reported in `ItemInfo.visibility`, e.g. "public" or "crate"
+    pub visibility: String,
+}
+
+/// An item whose visibility changed between two versions
+#[derive(Debug, Clone, PartialEq)]
+pub struct VisibilityChange {
+    pub path: String,
+    pub kind: String,
+    /// Visibility in the older version
+    pub from: String,
+    /// Visibility in the newer version
+    pub to: String,
+}
+
+/// Items that became public or stopped being public
+#[derive(Debug, Default, PartialEq)]
+pub struct VisibilityDiff {
+    pub newly_public: Vec<VisibilityChange>,
+    pub newly_private: Vec<VisibilityChange>,
+}
+
+/// Diff the visibility of the items present in both versions of a crate
+///
+/// Items added or removed between the versions are not reported. Results are
+/// sorted by path.
+pub fn diff_visibility(from: Vec<ItemVisibility>, to: Vec<ItemVisibility>) -> VisibilityDiff {
+    let mut from: HashMap<_, _> = from
+        .into_iter()
+        .map(|item| ((item.path, item.kind), item.visibility))
+        .collect();
+    let mut diff = VisibilityDiff::default();
+
+    for item in to {
+        let Some(old) = from.remove(&(item.path.clone(), item.kind.clone())) else {
+            continue;
+        };
+        let change = VisibilityChange {
+            path: item.path,
+            kind: item.kind,
+            from: old,
+            to: item.visibility,
+        };
+        match (change.from == "public", change.to == "public") {
+            (false, true) => diff.newly_public.push(change),
+            (true, false) => diff.newly_private.push(change),
+            _ => {}
+        }
+    }
+
+    for changes in [&mut diff.newly_public, &mut diff.newly_private] {
+        changes.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.kind.cmp(&b.kind)));
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(path: &str, kind: &str, visibility: &str) -> ItemVisibility {
+        ItemVisibility {
+            path: path.to_string(),
+            kind: kind.to_string(),
+            visibility: visibility.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_diff_visibility() {
+        let from = vec![
+            item("demo::Parser", "struct", "public"),
+            item("demo::Parser::new", "function", "crate"),
+            item("demo::helper", "function", "public"),
+            item("demo::internal", "module", "crate"),
+            item("demo::Removed", "struct", "crate"),
+        ];
+        let to = vec![
+            item("demo::Parser", "struct", "public"),
+            item("demo::Parser::new", "function", "public"),
+            item("demo::helper", "function", "restricted(0)"),
+            item("demo::internal", "module", "restricted(0)"),
+            item("demo::Added", "struct", "public"),
+        ];
+
+        let diff = diff_visibility(from, to);
+        assert_eq!(
+            diff.newly_public,
+            [VisibilityChange {
+                path: "demo::Parser::new".to_string(),
+                kind: "function".to_string(),
+                from: "crate".to_string(),
+                to: "public".to_string(),
+            }]
+        );
+        let private: Vec<&str> = diff
+            .newly_private
+            .iter()
+            .map(|change| change.path.as_str())
+            .collect();
+        // Moving between private visibilities is not a change of the public API
+        assert_eq!(private, ["demo::helper"]);
+    }
+}
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use rust_docs_mcp::cache::registry::Registry;
 use rust_docs_mcp::rustdoc;
 use serde::Serialize;
 use std::fs;
@@ -25,6 +26,8 @@ impl DiagnosticResult {
 
 pub async fn run_diagnostics(
     cache_dir: Option<std::path::PathBuf>,
+    toolchain: &str,
+    registry: &Registry,
 ) -> Result<Vec<DiagnosticResult>> {
     let mut results = Vec::new();
 
@@ -32,16 +35,16 @@ pub async fn run_diagnostics(
     results.push(check_rust_toolchain().await);
 
     // Check nightly toolchain
-    results.push(check_nightly_toolchain().await);
+    results.push(check_nightly_toolchain(toolchain).await);
 
     // Check rustdoc JSON capability
-    results.push(check_rustdoc_json().await);
+    results.push(check_rustdoc_json(toolchain).await);
 
     // Check Git installation
     results.push(check_git_installation().await);
 
     // Check network connectivity
-    results.push(check_network_connectivity().await);
+    results.push(check_network_connectivity(registry).await);
 
     // Check cache directory
     results.push(check_cache_directory(cache_dir).await);
@@ -73,14 +76,14 @@ async fn check_rust_toolchain() -> DiagnosticResult {
     }
 }
 
-async fn check_nightly_toolchain() -> DiagnosticResult {
+async fn check_nightly_toolchain(toolchain: &str) -> DiagnosticResult {
     match Command::new("rustup").args(["toolchain", "list"]).output() {
         Ok(output) if output.status.success() => {
             let toolchains = String::from_utf8_lossy(&output.stdout);
-            if toolchains.contains("nightly") {
-                // Try to get nightly version
+            if rustdoc::toolchain_installed(&toolchains, toolchain) {
+                // Try to get the toolchain version
                 match Command::new("rustc")
-                    .args(["+nightly", "--version"])
+                    .args([&format!("+{toolchain}"), "--version"])
                     .output()
                 {
                     Ok(nightly_output) if nightly_output.status.success() => {
@@ -92,7 +95,7 @@ async fn check_nightly_toolchain() -> DiagnosticResult {
                     _ => DiagnosticResult::new(
                         "Nightly toolchain".to_string(),
                         false,
-                        "nightly toolchain installed but not functional".to_string(),
+                        format!("{toolchain} installed but not functional"),
                         true,
                     ),
                 }
@@ -100,7 +103,9 @@ async fn check_nightly_toolchain() -> DiagnosticResult {
                 DiagnosticResult::new(
                     "Nightly toolchain".to_string(),
                     false,
-                    "nightly toolchain not installed".to_string(),
+                    format!(
+                        "{toolchain} not installed (run: rustup toolchain install {toolchain})"
+                    ),
                     true,
                 )
             }
@@ -120,20 +125,16 @@ async fn check_nightly_toolchain() -> DiagnosticResult {
     }
 }
 
-async fn check_rustdoc_json() -> DiagnosticResult {
+async fn check_rustdoc_json(toolchain: &str) -> DiagnosticResult {
     // First check if rustdoc is available
-    match rustdoc::get_rustdoc_version().await {
+    match rustdoc::get_rustdoc_version(toolchain).await {
         Ok(version) => {
             // Try to test JSON generation using the unified function
-            match rustdoc::test_rustdoc_json().await {
+            match rustdoc::test_rustdoc_json(toolchain).await {
                 Ok(_) => DiagnosticResult::new(
                     "Rustdoc JSON".to_string(),
                     true,
-                    format!(
-                        "{} with JSON support (toolchain: {})",
-                        version,
-                        rustdoc::REQUIRED_TOOLCHAIN
-                    ),
+                    format!("{version} with JSON support (toolchain: {toolchain})"),
                     false,
                 ),
                 Err(e) => {
@@ -177,7 +178,7 @@ async fn check_git_installation() -> DiagnosticResult {
     }
 }
 
-async fn check_network_connectivity() -> DiagnosticResult {
+async fn check_network_connectivity(registry: &Registry) -> DiagnosticResult {
     let client = match reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(10))
         .user_agent("rust-docs-mcp-doctor/1.0")
@@ -194,22 +195,23 @@ async fn check_network_connectivity() -> DiagnosticResult {
         }
     };
 
-    // Test crates.io API
-    tracing::debug!("Testing crates.io connectivity...");
-    match client
-        .get("https://crates.io/api/v1/crates/serde")
-        .send()
-        .await
-    {
+    // Test the registry index crates are resolved through
+    let url = registry.index_entry_url("serde");
+    let host = reqwest::Url::parse(&url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.clone());
+    tracing::debug!("Testing {host} connectivity...");
+    match client.get(&url).send().await {
         Ok(response) => {
             let status = response.status();
-            tracing::debug!("crates.io response status: {}", status);
+            tracing::debug!("{host} response status: {}", status);
 
             if status.is_success() {
                 // Try to read a small portion of the response to ensure it's valid
                 match response.text().await {
                     Ok(body) => {
-                        tracing::debug!("crates.io response body length: {}", body.len());
+                        tracing::debug!("{host} response body length: {}", body.len());
 
                         // Also test GitHub connectivity
                         tracing::debug!("Testing GitHub connectivity...");
@@ -223,7 +225,7 @@ async fn check_network_connectivity() -> DiagnosticResult {
                                         "Network".to_string(),
                                         true,
                                         format!(
-                                            "crates.io ({status}) and GitHub ({gh_status}) reachable"
+                                            "{host} ({status}) and GitHub ({gh_status}) reachable"
                                         ),
                                         false,
                                     )
@@ -232,7 +234,7 @@ async fn check_network_connectivity() -> DiagnosticResult {
                                         "Network".to_string(),
                                         false,
                                         format!(
-                                            "crates.io reachable ({status}) but GitHub unreachable ({gh_status})"
+                                            "{host} reachable ({status}) but GitHub unreachable ({gh_status})"
                                         ),
                                         false,
                                     )
@@ -243,20 +245,18 @@ async fn check_network_connectivity() -> DiagnosticResult {
                                 DiagnosticResult::new(
                                     "Network".to_string(),
                                     false,
-                                    format!("crates.io reachable ({status}) but GitHub error: {e}"),
+                                    format!("{host} reachable ({status}) but GitHub error: {e}"),
                                     false,
                                 )
                             }
                         }
                     }
                     Err(e) => {
-                        eprintln!("DEBUG: Failed to read crates.io response body: {e}");
+                        eprintln!("DEBUG: Failed to read {host} response body: {e}");
                         DiagnosticResult::new(
                             "Network".to_string(),
                             false,
-                            format!(
-                                "crates.io responded ({status}) but failed to read response: {e}"
-                            ),
+                            format!("{host} responded ({status}) but failed to read response: {e}"),
                             false,
                         )
                     }
@@ -265,17 +265,17 @@ async fn check_network_connectivity() -> DiagnosticResult {
                 DiagnosticResult::new(
                     "Network".to_string(),
                     false,
-                    format!("crates.io returned error status: {status}"),
+                    format!("{host} returned error status: {status}"),
                     false,
                 )
             }
         }
         Err(e) => {
-            eprintln!("DEBUG: crates.io request error: {e}");
+            eprintln!("DEBUG: {host} request error: {e}");
             DiagnosticResult::new(
                 "Network".to_string(),
                 false,
-                format!("Unable to reach crates.io: {e}"),
+                format!("Unable to reach {host}: {e}"),
                 false,
             )
         }
@@ -462,6 +462,9 @@ pub fn print_results(results: &[DiagnosticResult]) {
                             "\nNightly toolchain is required for rustdoc JSON generation. Install with:"
                         );
                         println!("  rustup toolchain install nightly");
+                        println!(
+                            "Without it, crates.io crates fall back to prebuilt docs from docs.rs where available."
+                        );
                     }
                     "Git" => {
                         println!(
@@ -542,9 +545,9 @@ pub fn print_results_json(results: &[DiagnosticResult]) -> Result<()> {
 
 /// Run diagnostics and print results with status message
 /// This is a convenience function used after install/update operations
-pub async fn run_and_print_diagnostics() -> Result<()> {
+pub async fn run_and_print_diagnostics(toolchain: &str, registry: &Registry) -> Result<()> {
     println!("\n🔍 Running system diagnostics...\n");
-    let results = run_diagnostics(None).await?;
+    let results = run_diagnostics(None, toolchain, registry).await?;
     print_results(&results);
 
     let exit_code = exit_code(&results);
@@ -0,0 +1,94 @@
+//! Response formats of tool results
+//!
+//! Tools answer with JSON, which programmatic clients parse. Tools whose
+//! results read well as a document can also render them as Markdown text, for
+//! clients that show responses to a person. The format is chosen per call with
+//! a `format` parameter, falling back to the server's `--default-format`.
+
+use anyhow::{Result, bail};
+use serde::Serialize;
+use std::fmt;
+
+/// Names accepted by [`ResponseFormat::parse`]
+const FORMAT_NAMES: [&str; 2] = ["json", "text"];
+
+/// Format a tool result is returned in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResponseFormat {
+    /// The structured output serialized as JSON
+    #[default]
+    Json,
+    /// The output rendered as Markdown
+    Text,
+}
+
+impl ResponseFormat {
+    /// Parse a format name: `json`, or `text` (also accepted as `markdown`)
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "text" | "markdown" => Ok(Self::Text),
+            other => bail!(
+                "Unknown response format '{other}'. Must be one of: {}",
+                FORMAT_NAMES.join(", ")
+            ),
+        }
+    }
+
+    /// The format requested for a call, or `default` when none was requested
+    pub fn resolve(requested: Option<&str>, default: Self) -> Result<Self> {
+        requested.map_or(Ok(default), Self::parse)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Text => "text",
+        }
+    }
+}
+
+impl fmt::Display for ResponseFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Tool output that can also be rendered as Markdown
+pub trait ToText: Serialize {
+    /// Render the output as a Markdown document
+    fn to_text(&self) -> String;
+
+    /// Render the output in `format`
+    fn render(&self, format: ResponseFormat) -> String {
+        match format {
+            ResponseFormat::Json => serde_json::to_string(self)
+                .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string()),
+            ResponseFormat::Text => self.to_text(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_format() -> Result<()> {
+        assert_eq!(ResponseFormat::parse("JSON")?, ResponseFormat::Json);
+        assert_eq!(ResponseFormat::parse(" text ")?, ResponseFormat::Text);
+        assert_eq!(ResponseFormat::parse("markdown")?, ResponseFormat::Text);
+        assert!(ResponseFormat::parse("yaml").is_err());
+        assert_eq!(ResponseFormat::default().to_string(), "json");
+
+        assert_eq!(
+            ResponseFormat::resolve(None, ResponseFormat::Text)?,
+            ResponseFormat::Text
+        );
+        assert_eq!(
+            ResponseFormat::resolve(Some("json"), ResponseFormat::Text)?,
+            ResponseFormat::Json
+        );
+        Ok(())
+    }
+}
@@ -1,10 +1,15 @@
+pub mod access_log;
 pub mod analysis;
 pub mod cache;
+pub mod config;
 pub mod deps;
 pub mod docs;
+pub mod format;
 pub mod rustdoc;
 pub mod search;
 pub mod service;
+pub mod timeout;
 pub mod util;
+pub mod validation;
 
 pub use service::RustDocsService;
@@ -3,24 +3,305 @@ use clap::{Parser, Subcommand};
 use rmcp::{ServiceExt, transport::stdio};
 use std::path::PathBuf;
 use std::process;
+use std::time::Duration;
 use tracing_subscriber::EnvFilter;
 
 mod doctor;
 mod update;
 use rust_docs_mcp::RustDocsService;
+use rust_docs_mcp::cache::downloader::DEFAULT_GIT_DEPTH;
+use rust_docs_mcp::cache::eviction::EvictionPolicy;
+use rust_docs_mcp::cache::policy::SourcePolicy;
+use rust_docs_mcp::cache::registry::Registry;
+use rust_docs_mcp::cache::storage::{parse_cache_permissions, restrict_umask};
+use rust_docs_mcp::cache::task_manager::default_max_concurrent_tasks;
+use rust_docs_mcp::cache::watchdog::{DEFAULT_LOW_WATER_PERCENT, DEFAULT_WATCHDOG_INTERVAL_SECS};
+use rust_docs_mcp::config::{
+    DEFAULT_CACHE_TIMEOUT_SECS, DEFAULT_MAX_RESPONSE_BYTES, DEFAULT_QUERY_TIMEOUT_SECS,
+    DEFAULT_SEARCH_TIME_BUDGET_MS, FileConfig, ServiceConfig,
+};
+use rust_docs_mcp::format::ResponseFormat;
+use rust_docs_mcp::rustdoc::{REQUIRED_TOOLCHAIN, parse_rustdoc_args};
 
 /// MCP server for querying Rust crate documentation with offline caching
+///
+/// Options can also be set in a TOML config file, read from
+/// ~/.config/rust-docs-mcp/config.toml or --config. Command line flags and
+/// environment variables override the file, which overrides the defaults.
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
+    /// Config file to read options from (defaults to ~/.config/rust-docs-mcp/config.toml)
+    #[arg(long, env = "RUST_DOCS_MCP_CONFIG")]
+    config: Option<PathBuf>,
+
     /// Custom cache directory path (defaults to ~/.rust-docs-mcp/cache)
     #[arg(long, env = "RUST_DOCS_MCP_CACHE_DIR")]
     cache_dir: Option<PathBuf>,
 
+    /// Timeout in seconds for read-only query tools [default: 60]
+    #[arg(long, env = "RUST_DOCS_MCP_TOOL_TIMEOUT")]
+    tool_timeout: Option<u64>,
+
+    /// Timeout in seconds for tools that modify the cache [default: 300]
+    #[arg(long, env = "RUST_DOCS_MCP_CACHE_TIMEOUT")]
+    cache_timeout: Option<u64>,
+
+    /// Maximum number of caching tasks running concurrently (defaults to the CPU count)
+    #[arg(long, env = "RUST_DOCS_MCP_MAX_CONCURRENT_TASKS")]
+    max_concurrent_tasks: Option<usize>,
+
+    /// Maximum size in bytes of a tool response before results are truncated [default: 100000]
+    #[arg(long, env = "RUST_DOCS_MCP_MAX_RESPONSE_BYTES")]
+    max_response_bytes: Option<usize>,
+
+    /// Milliseconds search_items may scan a crate's items for before returning
+    /// partial results, 0 for no limit [default: 2000]
+    #[arg(long, env = "RUST_DOCS_MCP_SEARCH_TIME_BUDGET_MS")]
+    search_time_budget_ms: Option<u64>,
+
+    /// Rustup toolchain used to generate rustdoc JSON (e.g. nightly-2024-10-01)
+    #[arg(long, env = "RUST_DOCS_MCP_TOOLCHAIN")]
+    docgen_toolchain: Option<String>,
+
+    /// Regenerate cached docs whose rustdoc JSON format is incompatible instead of failing
+    #[arg(
+        long,
+        env = "RUST_DOCS_MCP_AUTO_REGENERATE_INCOMPATIBLE",
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_parser = clap::builder::BoolishValueParser::new()
+    )]
+    auto_regenerate_incompatible: Option<bool>,
+
+    /// Extra rustdoc arguments, whitespace-separated: --cfg <spec> (e.g. --cfg docsrs
+    /// to document items gated for docs.rs), --document-hidden-items or -Z unstable-options
+    #[arg(long, env = "RUST_DOCS_MCP_RUSTDOC_ARGS", allow_hyphen_values = true)]
+    rustdoc_args: Option<String>,
+
+    /// Download crates from a mirror instead of crates.io, as a URL template with
+    /// {name} and {version} placeholders or a base URL
+    #[arg(long, env = "RUST_DOCS_MCP_REGISTRY")]
+    registry_url: Option<String>,
+
+    /// Sparse index base URL of the mirror (defaults to https://index.crates.io)
+    #[arg(long, env = "RUST_DOCS_MCP_REGISTRY_INDEX")]
+    registry_index_url: Option<String>,
+
+    /// Base URL of docs.rs, used for the docs fallback and item links (defaults to https://docs.rs)
+    #[arg(long, env = "RUST_DOCS_MCP_DOCSRS_URL")]
+    docsrs_url: Option<String>,
+
+    /// Number of commits fetched when cloning GitHub sources, 0 for the full history [default: 1]
+    #[arg(long, env = "RUST_DOCS_MCP_GIT_DEPTH")]
+    git_depth: Option<u32>,
+
+    /// Keep the git history of GitHub sources in the cache, for get_changed_items_since
+    #[arg(
+        long,
+        env = "RUST_DOCS_MCP_KEEP_GIT_HISTORY",
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_parser = clap::builder::BoolishValueParser::new()
+    )]
+    keep_git_history: Option<bool>,
+
+    /// Only cache crates from these sources, comma-separated: cratesio, github, local,
+    /// archive or git hosts such as github.com/my-org (defaults to every source)
+    #[arg(long, env = "RUST_DOCS_MCP_ALLOWED_SOURCES")]
+    allowed_sources: Option<String>,
+
+    /// Policy choosing which cached crates to evict first: lru (least recently
+    /// accessed), lfu (least frequently accessed) or age (oldest cached) [default: lru]
+    #[arg(long, env = "RUST_DOCS_MCP_CACHE_EVICTION_POLICY")]
+    cache_eviction_policy: Option<String>,
+
+    /// Size in bytes the cached crates may take up; when exceeded, a background
+    /// watchdog evicts crates with the eviction policy (defaults to no limit)
+    #[arg(long, env = "RUST_DOCS_MCP_MAX_CACHE_BYTES")]
+    max_cache_bytes: Option<u64>,
+
+    /// Percentage of --max-cache-bytes the watchdog evicts down to [default: 80]
+    #[arg(long, env = "RUST_DOCS_MCP_CACHE_LOW_WATER_PERCENT", value_parser = clap::value_parser!(u8).range(0..=100))]
+    cache_low_water_percent: Option<u8>,
+
+    /// Seconds between two checks of the cache size by the watchdog [default: 300]
+    #[arg(long, env = "RUST_DOCS_MCP_CACHE_WATCHDOG_INTERVAL")]
+    cache_watchdog_interval: Option<u64>,
+
+    /// Allow evicting crates cached from local paths, which are exempt by default
+    #[arg(
+        long,
+        env = "RUST_DOCS_MCP_EVICT_LOCAL_CRATES",
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_parser = clap::builder::BoolishValueParser::new()
+    )]
+    evict_local_crates: Option<bool>,
+
+    /// Only serve crates that are already cached: tools that cache, remove or
+    /// reindex crates are disabled and uncached crates are not documented
+    #[arg(
+        long,
+        env = "RUST_DOCS_MCP_READ_ONLY",
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_parser = clap::builder::BoolishValueParser::new()
+    )]
+    read_only: Option<bool>,
+
+    /// Octal mode given to cache directories on Unix, e.g. 0700 to keep other users out
+    #[arg(long, env = "RUST_DOCS_MCP_CACHE_PERMS")]
+    cache_perms: Option<String>,
+
+    /// Append a JSON line per tool call with the tool, crate, version and item id to this file
+    #[arg(long, env = "RUST_DOCS_MCP_ACCESS_LOG")]
+    access_log: Option<PathBuf>,
+
+    /// Format of tool results that can be rendered as Markdown: json or text [default: json]
+    #[arg(long, env = "RUST_DOCS_MCP_DEFAULT_FORMAT")]
+    default_format: Option<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
 
+impl Args {
+    /// Fill in options not given on the command line or in the environment from `file`
+    fn with_file_config(self, file: FileConfig) -> Self {
+        Self {
+            cache_dir: self.cache_dir.or(file.cache_dir),
+            tool_timeout: self.tool_timeout.or(file.tool_timeout),
+            cache_timeout: self.cache_timeout.or(file.cache_timeout),
+            max_concurrent_tasks: self.max_concurrent_tasks.or(file.max_concurrent_tasks),
+            max_response_bytes: self.max_response_bytes.or(file.max_response_bytes),
+            search_time_budget_ms: self.search_time_budget_ms.or(file.search_time_budget_ms),
+            docgen_toolchain: self.docgen_toolchain.or(file.docgen_toolchain),
+            auto_regenerate_incompatible: self
+                .auto_regenerate_incompatible
+                .or(file.auto_regenerate_incompatible),
+            rustdoc_args: self.rustdoc_args.or(file.rustdoc_args),
+            registry_url: self.registry_url.or(file.registry_url),
+            registry_index_url: self.registry_index_url.or(file.registry_index_url),
+            docsrs_url: self.docsrs_url.or(file.docsrs_url),
+            git_depth: self.git_depth.or(file.git_depth),
+            keep_git_history: self.keep_git_history.or(file.keep_git_history),
+            allowed_sources: self.allowed_sources.or(file.allowed_sources),
+            cache_eviction_policy: self.cache_eviction_policy.or(file.cache_eviction_policy),
+            max_cache_bytes: self.max_cache_bytes.or(file.max_cache_bytes),
+            cache_low_water_percent: self
+                .cache_low_water_percent
+                .or(file.cache_low_water_percent),
+            cache_watchdog_interval: self
+                .cache_watchdog_interval
+                .or(file.cache_watchdog_interval),
+            evict_local_crates: self.evict_local_crates.or(file.evict_local_crates),
+            read_only: self.read_only.or(file.read_only),
+            cache_perms: self.cache_perms.or(file.cache_perms),
+            access_log: self.access_log.or(file.access_log),
+            default_format: self.default_format.or(file.default_format),
+            ..self
+        }
+    }
+
+    /// Toolchain used to generate rustdoc JSON
+    fn docgen_toolchain(&self) -> &str {
+        self.docgen_toolchain
+            .as_deref()
+            .unwrap_or(REQUIRED_TOOLCHAIN)
+    }
+
+    /// Registry endpoints, defaulting to crates.io and docs.rs
+    fn registry(&self) -> Result<Registry> {
+        let mut registry = Registry::default();
+        if let Some(url) = &self.registry_url {
+            registry = registry.with_download_url(url)?;
+        }
+        if let Some(url) = &self.registry_index_url {
+            registry = registry.with_index_url(url)?;
+        }
+        if let Some(url) = &self.docsrs_url {
+            registry = registry.with_docsrs_url(url)?;
+        }
+        Ok(registry)
+    }
+
+    /// Build the service configuration, using defaults for options that were not set
+    fn service_config(&self) -> Result<ServiceConfig> {
+        let registry = self.registry()?;
+
+        let source_policy = match &self.allowed_sources {
+            Some(spec) => SourcePolicy::parse(spec)?,
+            None => SourcePolicy::default(),
+        };
+
+        let eviction_policy = match &self.cache_eviction_policy {
+            Some(name) => EvictionPolicy::parse(name)?,
+            None => EvictionPolicy::default(),
+        };
+
+        let rustdoc_args = match &self.rustdoc_args {
+            Some(args) => parse_rustdoc_args(args)?,
+            None => Vec::new(),
+        };
+
+        let default_format = match &self.default_format {
+            Some(name) => ResponseFormat::parse(name)?,
+            None => ResponseFormat::default(),
+        };
+
+        let cache_permissions = self
+            .cache_perms
+            .as_deref()
+            .map(parse_cache_permissions)
+            .transpose()?;
+
+        Ok(ServiceConfig {
+            query_timeout: Duration::from_secs(
+                self.tool_timeout.unwrap_or(DEFAULT_QUERY_TIMEOUT_SECS),
+            ),
+            cache_timeout: Duration::from_secs(
+                self.cache_timeout.unwrap_or(DEFAULT_CACHE_TIMEOUT_SECS),
+            ),
+            max_concurrent_tasks: self
+                .max_concurrent_tasks
+                .unwrap_or_else(default_max_concurrent_tasks),
+            max_response_bytes: self
+                .max_response_bytes
+                .unwrap_or(DEFAULT_MAX_RESPONSE_BYTES),
+            search_time_budget: match self
+                .search_time_budget_ms
+                .unwrap_or(DEFAULT_SEARCH_TIME_BUDGET_MS)
+            {
+                0 => None,
+                millis => Some(Duration::from_millis(millis)),
+            },
+            docgen_toolchain: self.docgen_toolchain().to_string(),
+            auto_regenerate_incompatible: self.auto_regenerate_incompatible.unwrap_or(false),
+            rustdoc_args,
+            registry,
+            git_depth: self.git_depth.unwrap_or(DEFAULT_GIT_DEPTH),
+            keep_git_history: self.keep_git_history.unwrap_or(false),
+            source_policy,
+            eviction_policy,
+            max_cache_bytes: self.max_cache_bytes,
+            cache_low_water_percent: self
+                .cache_low_water_percent
+                .unwrap_or(DEFAULT_LOW_WATER_PERCENT),
+            cache_watchdog_interval: Duration::from_secs(
+                self.cache_watchdog_interval
+                    .unwrap_or(DEFAULT_WATCHDOG_INTERVAL_SECS)
+                    .max(1),
+            ),
+            evict_local: self.evict_local_crates.unwrap_or(false),
+            read_only: self.read_only.unwrap_or(false),
+            cache_permissions,
+            access_log: self.access_log.clone(),
+            default_format,
+        })
+    }
+}
+
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Install the current executable to a directory in PATH
@@ -44,6 +325,16 @@ enum Commands {
         #[arg(long)]
         branch: Option<String>,
     },
+    /// Remove all cached crates, documentation and search indexes
+    Clean {
+        /// Skip the confirmation prompt
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Upgrade the cache directory to the current on-disk layout
+    Migrate,
+    /// Rebuild the search indexes of all cached crates from their existing docs
+    Reindex,
     /// Verify system environment and dependencies
     Doctor {
         /// Output results in JSON format for programmatic consumption
@@ -52,14 +343,37 @@ enum Commands {
     },
 }
 
+impl Commands {
+    /// Whether the command modifies the cache
+    fn modifies_cache(&self) -> bool {
+        matches!(self, Self::Clean { .. } | Self::Migrate | Self::Reindex)
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Parse command line arguments
+    // Parse command line arguments, then fill in the rest from the config file
     let args = Args::parse();
+    let file_config = FileConfig::load_or_default(args.config.as_deref())?;
+    let mut args = args.with_file_config(file_config);
+
+    let config = args.service_config()?;
+
+    // Files written by downloads, git and rustdoc get the cache permissions as
+    // they are created, not only once a caching task finishes
+    if let Some(mode) = config.cache_permissions
+        && !config.read_only
+        && args.command.as_ref().is_none_or(Commands::modifies_cache)
+    {
+        restrict_umask(mode);
+    }
 
     // Handle subcommands
-    if let Some(command) = args.command {
-        return handle_command(command, args.cache_dir).await;
+    if let Some(command) = args.command.take() {
+        if command.modifies_cache() && config.read_only {
+            anyhow::bail!("The cache cannot be modified in read-only mode");
+        }
+        return handle_command(command, args.cache_dir, &config).await;
     }
 
     // Initialize tracing to stderr to avoid conflicts with stdio transport
@@ -73,33 +387,139 @@ async fn main() -> Result<()> {
     if let Some(ref cache_dir) = args.cache_dir {
         tracing::info!("Using custom cache directory: {}", cache_dir.display());
     }
+    if let Some(url) = &args.registry_url {
+        tracing::info!("Downloading crates from registry mirror: {url}");
+    }
 
     // Create the service with optional cache directory
-    let rust_docs_service = RustDocsService::new(args.cache_dir)?;
+    let rust_docs_service = RustDocsService::with_config(args.cache_dir, config)?;
 
     // Serve using stdio transport
-    let service = rust_docs_service.serve(stdio()).await.inspect_err(|e| {
-        tracing::error!("serving error: {:?}", e);
-    })?;
+    let service = rust_docs_service
+        .clone()
+        .serve(stdio())
+        .await
+        .inspect_err(|e| {
+            tracing::error!("serving error: {:?}", e);
+        })?;
 
     // Wait for the service to complete
     service.waiting().await?;
+    rust_docs_service.flush_access_log().await;
     Ok(())
 }
 
-async fn handle_command(command: Commands, cache_dir: Option<PathBuf>) -> Result<()> {
+async fn handle_command(
+    command: Commands,
+    cache_dir: Option<PathBuf>,
+    config: &ServiceConfig,
+) -> Result<()> {
+    let docgen_toolchain = config.docgen_toolchain.as_str();
+    let registry = &config.registry;
     match command {
-        Commands::Install { target_dir, force } => install_executable(target_dir, force).await,
+        Commands::Install { target_dir, force } => {
+            install_executable(target_dir, force, docgen_toolchain, registry).await
+        }
         Commands::Update {
             target_dir,
             repo_url,
             branch,
-        } => update::update_executable(target_dir, repo_url, branch).await,
-        Commands::Doctor { json } => handle_doctor_command(cache_dir, json).await,
+        } => {
+            update::update_executable(target_dir, repo_url, branch, docgen_toolchain, registry)
+                .await
+        }
+        Commands::Doctor { json } => {
+            handle_doctor_command(cache_dir, json, docgen_toolchain, registry).await
+        }
+        Commands::Clean { yes } => clean_cache(cache_dir, config, yes).await,
+        Commands::Migrate => migrate_cache(cache_dir, config),
+        Commands::Reindex => reindex_cache(cache_dir, config).await,
     }
 }
 
-async fn install_executable(target_dir: Option<PathBuf>, force: bool) -> Result<()> {
+async fn clean_cache(cache_dir: Option<PathBuf>, config: &ServiceConfig, yes: bool) -> Result<()> {
+    use rust_docs_mcp::cache::utils::format_bytes;
+    use std::io::{self, BufRead, Write};
+
+    let cache = config.open_cache(cache_dir)?;
+    let cache_path = cache.cache_dir().display().to_string();
+
+    if !yes {
+        print!("This will remove every cached crate in {cache_path}. Continue? [y/N] ");
+        io::stdout().flush()?;
+
+        let mut answer = String::new();
+        io::stdin().lock().read_line(&mut answer)?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            println!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    let reclaimed = cache.clear_all_caches().await?;
+    println!(
+        "Cleared cache at {cache_path}, reclaimed {}",
+        format_bytes(reclaimed)
+    );
+    Ok(())
+}
+
+fn migrate_cache(cache_dir: Option<PathBuf>, config: &ServiceConfig) -> Result<()> {
+    use rust_docs_mcp::cache::layout;
+
+    let cache = config.open_cache(cache_dir)?;
+    let report = layout::migrate(cache.cache_dir())?;
+
+    if report.from_version == report.to_version {
+        println!(
+            "Cache at {} already uses layout version {}",
+            cache.cache_dir().display(),
+            report.to_version
+        );
+    } else {
+        println!(
+            "Migrated cache at {} from layout version {} to {}: updated {} metadata files, normalized {} member directories",
+            cache.cache_dir().display(),
+            report.from_version,
+            report.to_version,
+            report.metadata_updated,
+            report.members_normalized
+        );
+    }
+    Ok(())
+}
+
+async fn reindex_cache(cache_dir: Option<PathBuf>, config: &ServiceConfig) -> Result<()> {
+    let cache = config.open_cache(cache_dir)?;
+    let (rebuilt, failed) = cache.reindex_all(config.max_concurrent_tasks).await?;
+
+    println!(
+        "Rebuilt {rebuilt} search index(es) in {}",
+        cache.cache_dir().display()
+    );
+    for failure in &failed {
+        let member = failure
+            .member
+            .as_deref()
+            .map(|member| format!(" (member {member})"))
+            .unwrap_or_default();
+        println!(
+            "Failed to rebuild {}-{}{member}: {}",
+            failure.crate_name, failure.version, failure.error
+        );
+    }
+    if !failed.is_empty() {
+        anyhow::bail!("{} search index(es) could not be rebuilt", failed.len());
+    }
+    Ok(())
+}
+
+async fn install_executable(
+    target_dir: Option<PathBuf>,
+    force: bool,
+    docgen_toolchain: &str,
+    registry: &Registry,
+) -> Result<()> {
     use std::env;
     use std::fs;
 
@@ -167,13 +587,18 @@ async fn install_executable(target_dir: Option<PathBuf>, force: bool) -> Result<
     }
 
     // Run doctor command to verify the installation
-    doctor::run_and_print_diagnostics().await?;
+    doctor::run_and_print_diagnostics(docgen_toolchain, registry).await?;
 
     Ok(())
 }
 
-async fn handle_doctor_command(cache_dir: Option<PathBuf>, json_output: bool) -> Result<()> {
-    let results = doctor::run_diagnostics(cache_dir).await?;
+async fn handle_doctor_command(
+    cache_dir: Option<PathBuf>,
+    json_output: bool,
+    docgen_toolchain: &str,
+    registry: &Registry,
+) -> Result<()> {
+    let results = doctor::run_diagnostics(cache_dir, docgen_toolchain, registry).await?;
 
     if json_output {
         doctor::print_results_json(&results)?;
@@ -183,3 +608,86 @@ async fn handle_doctor_command(cache_dir: Option<PathBuf>, json_output: bool) ->
 
     process::exit(doctor::exit_code(&results));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_config_file_options_and_cli_override() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config_path = temp_dir.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            r#"
+cache-dir = "/tmp/rust-docs-cache"
+tool-timeout = 30
+max-response-bytes = 400000
+search-time-budget-ms = 0
+docgen-toolchain = "nightly-2024-10-01"
+auto-regenerate-incompatible = true
+rustdoc-args = "--cfg docsrs --document-hidden-items"
+registry-url = "https://mirror.example.com/crates"
+docsrs-url = "https://docs.example.com/"
+git-depth = 0
+keep-git-history = true
+allowed-sources = "cratesio,github.com/my-org"
+cache-eviction-policy = "lfu"
+max-cache-bytes = 1000000000
+cache-watchdog-interval = 60
+cache-perms = "0700"
+default-format = "text"
+"#,
+        )?;
+
+        let args = Args::try_parse_from([
+            "rust-docs-mcp",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--max-response-bytes",
+            "5000",
+        ])?;
+        let file_config = FileConfig::load_or_default(args.config.as_deref())?;
+        let args = args.with_file_config(file_config);
+        let config = args.service_config()?;
+
+        // Values from the file take effect
+        assert_eq!(args.cache_dir, Some(PathBuf::from("/tmp/rust-docs-cache")));
+        assert_eq!(config.query_timeout, Duration::from_secs(30));
+        assert_eq!(config.search_time_budget, None);
+        assert_eq!(config.docgen_toolchain, "nightly-2024-10-01");
+        assert!(config.auto_regenerate_incompatible);
+        assert_eq!(
+            config.rustdoc_args,
+            ["--cfg", "docsrs", "--document-hidden-items"]
+        );
+        assert_eq!(config.git_depth, 0);
+        assert!(config.keep_git_history);
+        assert_eq!(
+            config.source_policy.allowed_sources(),
+            ["cratesio", "github.com/my-org"]
+        );
+        assert_eq!(config.eviction_policy, EvictionPolicy::Lfu);
+        assert_eq!(config.max_cache_bytes, Some(1_000_000_000));
+        assert_eq!(config.cache_watchdog_interval, Duration::from_secs(60));
+        assert_eq!(config.default_format, ResponseFormat::Text);
+        assert_eq!(config.cache_permissions, Some(0o700));
+        assert_eq!(
+            config.registry.download_url("serde", "1.0.0"),
+            "https://mirror.example.com/crates/serde/1.0.0/download"
+        );
+        assert_eq!(config.registry.docsrs_url(), "https://docs.example.com");
+        // The command line overrides the file
+        assert_eq!(config.max_response_bytes, 5000);
+        // Options set nowhere use the defaults
+        assert_eq!(
+            config.cache_timeout,
+            Duration::from_secs(DEFAULT_CACHE_TIMEOUT_SECS)
+        );
+        assert!(!config.evict_local);
+        assert_eq!(config.cache_low_water_percent, DEFAULT_LOW_WATER_PERCENT);
+
+        Ok(())
+    }
+}
@@ -12,6 +12,50 @@ use tokio::process::Command as TokioCommand;
 /// The pinned nightly toolchain version compatible with rustdoc-types 0.53.0
 pub const REQUIRED_TOOLCHAIN: &str = "nightly-2025-06-23";
 
+/// Error returned when cached rustdoc JSON uses a different format version than
+/// the one [`rustdoc_types`] can parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatVersionMismatch {
+    /// The `format_version` found in the JSON, if present
+    pub found: Option<u64>,
+    /// The `format_version` expected by [`rustdoc_types::FORMAT_VERSION`]
+    pub expected: u32,
+}
+
+impl std::fmt::Display for FormatVersionMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.found {
+            Some(found) => write!(
+                f,
+                "rustdoc JSON format version {found} is not compatible with the supported version {}",
+                self.expected
+            ),
+            None => write!(
+                f,
+                "rustdoc JSON has no format_version field (supported version {})",
+                self.expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FormatVersionMismatch {}
+
+/// Check that rustdoc JSON was produced in the format expected by [`rustdoc_types`]
+///
+/// This reads only the top-level `format_version` field, so it can run before the
+/// full deserialization that would otherwise fail with an unhelpful serde error.
+pub fn check_format_version(docs: &serde_json::Value) -> Result<(), FormatVersionMismatch> {
+    let expected = rustdoc_types::FORMAT_VERSION;
+    let found = docs.get("format_version").and_then(|v| v.as_u64());
+
+    if found == Some(u64::from(expected)) {
+        Ok(())
+    } else {
+        Err(FormatVersionMismatch { found, expected })
+    }
+}
+
 /// Number of lines to preview from error messages in diagnostic output
 const ERROR_MESSAGE_PREVIEW_LINES: usize = 10;
 
@@ -19,10 +63,23 @@ const ERROR_MESSAGE_PREVIEW_LINES: usize = 10;
 const MAX_ERROR_MESSAGE_CHARS: usize = 4096;
 
 /// Timeout for individual rustdoc execution attempts (in seconds)
-const RUSTDOC_TIMEOUT_SECS: u64 = 1800;
+pub(crate) const RUSTDOC_TIMEOUT_SECS: u64 = 1800;
+
+/// Check whether `toolchain` appears in the output of `rustup toolchain list`
+///
+/// Installed toolchains are listed with their host triple appended
+/// (e.g. `nightly-2025-06-23-x86_64-unknown-linux-gnu`), so a name also matches
+/// when it is followed by a `-` suffix.
+pub fn toolchain_installed(toolchain_list: &str, toolchain: &str) -> bool {
+    let prefix = format!("{toolchain}-");
+    toolchain_list
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .any(|name| name == toolchain || name.starts_with(&prefix))
+}
 
-/// Check if the required nightly toolchain is available
-pub async fn validate_toolchain() -> Result<()> {
+/// Check if the toolchain used for JSON generation is available
+pub async fn validate_toolchain(toolchain: &str) -> Result<()> {
     let output = Command::new("rustup")
         .args(["toolchain", "list"])
         .output()
@@ -33,20 +90,170 @@ pub async fn validate_toolchain() -> Result<()> {
     }
 
     let toolchains = String::from_utf8_lossy(&output.stdout);
-    if !toolchains.contains(REQUIRED_TOOLCHAIN) {
+    if !toolchain_installed(&toolchains, toolchain) {
+        if toolchain == REQUIRED_TOOLCHAIN {
+            bail!(
+                "Required toolchain {toolchain} is not installed. Please run: rustup toolchain install {toolchain}"
+            );
+        }
+        bail!(
+            "Toolchain {toolchain} (set via --docgen-toolchain or RUST_DOCS_MCP_TOOLCHAIN) is not installed. Please run: rustup toolchain install {toolchain}, or unset the override to use {REQUIRED_TOOLCHAIN}"
+        );
+    }
+
+    tracing::debug!("Validated toolchain {} is available", toolchain);
+    Ok(())
+}
+
+/// Check that `target` looks like a target triple (e.g. `x86_64-pc-windows-msvc`)
+///
+/// The triple is used as a directory name in the cache, so only ASCII letters,
+/// digits, `-`, `_` and `.` are accepted.
+pub fn validate_target_triple(target: &str) -> Result<()> {
+    if target.is_empty()
+        || target.starts_with('.')
+        || !target
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        bail!("Invalid target '{target}': expected a target triple such as x86_64-pc-windows-msvc");
+    }
+    Ok(())
+}
+
+/// Parse extra rustdoc arguments given as a whitespace-separated string, e.g. `--cfg docsrs`
+///
+/// The arguments are passed to rustdoc directly, never through a shell, but
+/// rustdoc itself has flags that load code or write files (`-Z codegen-backend`,
+/// `--extern`, `-o`). Only flags that change what gets documented are accepted:
+/// - `--cfg <spec>` or `--cfg=<spec>`, where the spec is `name` or `name="value"`
+/// - `--document-hidden-items`
+/// - `-Z unstable-options`, which is always passed already
+pub fn parse_rustdoc_args(input: &str) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut tokens = input.split_whitespace();
+    while let Some(token) = tokens.next() {
+        args.extend(next_rustdoc_arg(token, &mut tokens)?);
+    }
+    Ok(args)
+}
+
+/// Keep the arguments of `args` that [`parse_rustdoc_args`] accepts, dropping the rest
+///
+/// Used for the `rustdoc-args` crates declare for docs.rs, which often include
+/// flags such as `--html-in-header` that only matter for HTML output.
+pub fn filter_rustdoc_args(args: &[String]) -> Vec<String> {
+    let mut accepted = Vec::new();
+    let mut tokens = args.iter().map(String::as_str);
+    while let Some(token) = tokens.next() {
+        match next_rustdoc_arg(token, &mut tokens) {
+            Ok(arg) => accepted.extend(arg),
+            Err(e) => tracing::debug!("Ignoring rustdoc argument: {}", e),
+        }
+    }
+    accepted
+}
+
+/// Validate the argument starting with `token`, taking its value from `tokens`
+///
+/// Returns the argument in normalized form, or nothing if it is always passed anyway.
+fn next_rustdoc_arg<'a>(
+    token: &'a str,
+    tokens: &mut impl Iterator<Item = &'a str>,
+) -> Result<Vec<String>> {
+    match token {
+        "--cfg" => {
+            let Some(spec) = tokens.next() else {
+                bail!("Missing value for rustdoc argument --cfg");
+            };
+            validate_cfg_spec(spec)?;
+            Ok(vec!["--cfg".to_string(), spec.to_string()])
+        }
+        "--document-hidden-items" => Ok(vec![token.to_string()]),
+        "-Zunstable-options" => Ok(Vec::new()),
+        "-Z" => match tokens.next() {
+            Some("unstable-options") => Ok(Vec::new()),
+            option => bail!(unsupported_rustdoc_arg(&format!(
+                "-Z {}",
+                option.unwrap_or_default()
+            ))),
+        },
+        _ => match token.strip_prefix("--cfg=") {
+            Some(spec) => {
+                validate_cfg_spec(spec)?;
+                Ok(vec!["--cfg".to_string(), spec.to_string()])
+            }
+            None => bail!(unsupported_rustdoc_arg(token)),
+        },
+    }
+}
+
+fn unsupported_rustdoc_arg(arg: &str) -> String {
+    format!(
+        "Unsupported rustdoc argument '{arg}': only --cfg, --document-hidden-items and -Z unstable-options are allowed"
+    )
+}
+
+/// Check that `spec` is a cfg option such as `docsrs` or `feature="serde"`
+fn validate_cfg_spec(spec: &str) -> Result<()> {
+    let (name, value) = match spec.split_once('=') {
+        Some((name, value)) => (name, Some(value)),
+        None => (spec, None),
+    };
+    let valid_name = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let valid_value = value.is_none_or(|value| {
+        value.len() >= 2
+            && value.starts_with('"')
+            && value.ends_with('"')
+            && value[1..value.len() - 1]
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    });
+    if !valid_name || !valid_value {
+        bail!("Invalid --cfg value '{spec}': expected a name such as docsrs or name=\"value\"");
+    }
+    Ok(())
+}
+
+/// Check whether `target` appears in the output of `rustup target list --installed`
+pub fn target_installed(target_list: &str, target: &str) -> bool {
+    target_list.lines().any(|line| line.trim() == target)
+}
+
+/// Check that the standard library for `target` is installed for `toolchain`
+///
+/// Documenting a crate for a non-host target needs that target's standard library.
+pub async fn validate_target(toolchain: &str, target: &str) -> Result<()> {
+    validate_target_triple(target)?;
+
+    let output = Command::new("rustup")
+        .args(["target", "list", "--installed", "--toolchain", toolchain])
+        .output()
+        .context("Failed to run rustup target list")?;
+
+    if !output.status.success() {
+        bail!("Failed to check installed targets for toolchain {toolchain}");
+    }
+
+    let targets = String::from_utf8_lossy(&output.stdout);
+    if !target_installed(&targets, target) {
         bail!(
-            "Required toolchain {REQUIRED_TOOLCHAIN} is not installed. Please run: rustup toolchain install {REQUIRED_TOOLCHAIN}"
+            "Target {target} is not installed for toolchain {toolchain}. Please run: rustup target add {target} --toolchain {toolchain}"
         );
     }
 
-    tracing::debug!("Validated toolchain {} is available", REQUIRED_TOOLCHAIN);
+    tracing::debug!("Validated target {} is installed for {}", target, toolchain);
     Ok(())
 }
 
 /// Test rustdoc JSON functionality with a simple test file
-pub async fn test_rustdoc_json() -> Result<()> {
+pub async fn test_rustdoc_json(toolchain: &str) -> Result<()> {
     // First validate the toolchain
-    validate_toolchain().await?;
+    validate_toolchain(toolchain).await?;
 
     // Create a temporary directory and test file
     let temp_dir =
@@ -60,15 +267,12 @@ pub async fn test_rustdoc_json() -> Result<()> {
         .to_str()
         .ok_or_else(|| anyhow::anyhow!("Test file path contains invalid UTF-8"))?;
 
-    tracing::debug!(
-        "Testing rustdoc JSON generation with {}",
-        REQUIRED_TOOLCHAIN
-    );
+    tracing::debug!("Testing rustdoc JSON generation with {}", toolchain);
 
-    // Try to generate JSON documentation using the pinned toolchain
+    // Try to generate JSON documentation using the configured toolchain
     let output = Command::new("rustdoc")
         .args([
-            &format!("+{REQUIRED_TOOLCHAIN}"),
+            &format!("+{toolchain}"),
             "-Z",
             "unstable-options",
             "--output-format",
@@ -90,9 +294,9 @@ pub async fn test_rustdoc_json() -> Result<()> {
 }
 
 /// Get rustdoc version information
-pub async fn get_rustdoc_version() -> Result<String> {
+pub async fn get_rustdoc_version(toolchain: &str) -> Result<String> {
     let output = Command::new("rustdoc")
-        .arg(format!("+{REQUIRED_TOOLCHAIN}"))
+        .arg(format!("+{toolchain}"))
         .arg("--version")
         .output()
         .context("Failed to run rustdoc --version")?;
@@ -104,6 +308,46 @@ pub async fn get_rustdoc_version() -> Result<String> {
     Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
+/// Build settings a package declares for docs.rs in `[package.metadata.docs.rs]`
+///
+/// Generating docs with them gives the same items docs.rs shows, such as the
+/// ones gated on `#[cfg(docsrs)]` or on features that are off by default.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocsRsSettings {
+    /// Features enabled in addition to the default ones
+    pub features: Vec<String>,
+    pub all_features: bool,
+    pub no_default_features: bool,
+    /// rustdoc arguments, restricted to the ones [`parse_rustdoc_args`] accepts
+    pub rustdoc_args: Vec<String>,
+}
+
+impl DocsRsSettings {
+    /// The cargo arguments selecting the declared features
+    fn feature_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.all_features {
+            args.push("--all-features".to_string());
+        }
+        if self.no_default_features {
+            args.push("--no-default-features".to_string());
+        }
+        if !self.features.is_empty() {
+            args.push(format!("--features={}", self.features.join(",")));
+        }
+        args
+    }
+}
+
+/// Settings of a `cargo rustdoc` run besides the package and output location
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RustdocOptions {
+    /// rustdoc arguments passed on every attempt, validated by [`parse_rustdoc_args`]
+    pub extra_args: Vec<String>,
+    /// docs.rs settings of the package, tried before the default feature strategies
+    pub docs_rs: Option<DocsRsSettings>,
+}
+
 /// Strategy for selecting feature flags when generating rustdoc JSON output.
 ///
 /// Provides a fallback mechanism to handle crates that fail to compile with
@@ -113,10 +357,12 @@ pub async fn get_rustdoc_version() -> Result<String> {
 /// - Features requiring specific system libraries
 ///
 /// The recommended order is: [`AllFeatures`](Self::AllFeatures) →
-/// [`DefaultFeatures`](Self::DefaultFeatures) → [`NoDefaultFeatures`](Self::NoDefaultFeatures)
-#[derive(Debug, Clone, Copy)]
-#[allow(clippy::enum_variant_names)]
+/// [`DefaultFeatures`](Self::DefaultFeatures) → [`NoDefaultFeatures`](Self::NoDefaultFeatures),
+/// preceded by [`DocsRs`](Self::DocsRs) when the package declares docs.rs settings
+#[derive(Debug, Clone)]
 enum FeatureStrategy {
+    /// Use the features and rustdoc arguments declared for docs.rs
+    DocsRs(DocsRsSettings),
     /// Use --all-features (enables all feature flags)
     AllFeatures,
     /// Use default features only
@@ -129,6 +375,7 @@ impl FeatureStrategy {
     /// Get the command line arguments for this strategy
     fn args(&self) -> Vec<String> {
         match self {
+            Self::DocsRs(settings) => settings.feature_args(),
             Self::AllFeatures => vec!["--all-features".to_string()],
             Self::DefaultFeatures => vec![],
             Self::NoDefaultFeatures => vec!["--no-default-features".to_string()],
@@ -138,11 +385,20 @@ impl FeatureStrategy {
     /// Get a description of this strategy for logging
     fn description(&self) -> &str {
         match self {
+            Self::DocsRs(_) => "docs.rs metadata features",
             Self::AllFeatures => "all features enabled",
             Self::DefaultFeatures => "default features only",
             Self::NoDefaultFeatures => "no default features",
         }
     }
+
+    /// rustdoc arguments specific to this strategy
+    fn rustdoc_args(&self) -> &[String] {
+        match self {
+            Self::DocsRs(settings) => &settings.rustdoc_args,
+            _ => &[],
+        }
+    }
 }
 
 /// Check if an error is a compilation error
@@ -215,9 +471,40 @@ async fn execute_rustdoc(
         .context("Failed to run cargo rustdoc")
 }
 
+/// Build the leading `cargo` arguments selecting the toolchain and package
+///
+/// `cargo rustdoc` runs rustdoc on the selected package only. Unlike `cargo doc`
+/// it never documents dependencies, so no `--no-deps` flag is needed: they are
+/// compiled as required, and only the package's own JSON is produced.
+fn cargo_rustdoc_base_args(
+    toolchain: &str,
+    package: Option<&str>,
+    target_triple: Option<&str>,
+) -> Vec<String> {
+    let mut args = vec![format!("+{toolchain}"), "rustdoc".to_string()];
+
+    // Add package-specific arguments if provided
+    if let Some(pkg) = package {
+        args.push("-p".to_string());
+        args.push(pkg.to_string());
+    }
+
+    // Document for another platform instead of the host
+    if let Some(triple) = target_triple {
+        args.push("--target".to_string());
+        args.push(triple.to_string());
+    }
+
+    args
+}
+
 /// Run cargo rustdoc with JSON output for a crate or specific package
 ///
+/// Only the package itself is documented. Items of its dependencies are
+/// referenced by path in the JSON but their docs are not generated.
+///
 /// # Parameters
+/// - `toolchain`: The rustup toolchain to invoke cargo with (e.g. [`REQUIRED_TOOLCHAIN`])
 /// - `source_path`: The root directory containing Cargo.toml
 /// - `package`: Optional package name for workspace members
 /// - `target_dir`: Optional custom target directory to avoid conflicts when building
@@ -225,12 +512,25 @@ async fn execute_rustdoc(
 ///   in parallel, each must use a unique target directory to prevent cargo from
 ///   conflicting with itself. See [`DocGenerator::generate_workspace_member_docs`](crate::cache::docgen::DocGenerator::generate_workspace_member_docs)
 ///   for the implementation pattern.
+/// - `document_private_items`: Also document private items (`--document-private-items`)
+/// - `target_triple`: Optional platform to document for (`--target`). Cargo then writes
+///   the docs to `<target_dir>/<triple>/doc` instead of `<target_dir>/doc`
+/// - `options`: Further rustdoc arguments and the package's docs.rs settings. The
+///   docs.rs settings are tried first; if the crate fails to compile with them,
+///   the usual feature strategies are tried without the docs.rs rustdoc arguments
 pub async fn run_cargo_rustdoc_json(
+    toolchain: &str,
     source_path: &Path,
     package: Option<&str>,
     target_dir: Option<&Path>,
+    document_private_items: bool,
+    target_triple: Option<&str>,
+    options: &RustdocOptions,
 ) -> Result<()> {
-    validate_toolchain().await?;
+    validate_toolchain(toolchain).await?;
+    if let Some(triple) = target_triple {
+        validate_target(toolchain, triple).await?;
+    }
 
     // Logging strategy:
     // - debug: Strategy attempts and retries
@@ -261,20 +561,26 @@ pub async fn run_cargo_rustdoc_json(
     };
     tracing::debug!("{}", log_msg);
 
-    let mut base_args = vec![format!("+{}", REQUIRED_TOOLCHAIN), "rustdoc".to_string()];
+    let base_args = cargo_rustdoc_base_args(toolchain, package, target_triple);
 
-    // Add package-specific arguments if provided
-    if let Some(pkg) = package {
-        base_args.push("-p".to_string());
-        base_args.push(pkg.to_string());
+    // Try different feature strategies in order, starting like docs.rs would
+    let mut strategies = Vec::new();
+    if let Some(settings) = &options.docs_rs {
+        strategies.push(FeatureStrategy::DocsRs(settings.clone()));
     }
-
-    // Try different feature strategies in order
-    let strategies = [
+    for fallback in [
         FeatureStrategy::AllFeatures,
         FeatureStrategy::DefaultFeatures,
         FeatureStrategy::NoDefaultFeatures,
-    ];
+    ] {
+        // Skip a fallback that would repeat the docs.rs attempt exactly
+        let repeats_docs_rs = options.docs_rs.as_ref().is_some_and(|settings| {
+            settings.rustdoc_args.is_empty() && settings.feature_args() == fallback.args()
+        });
+        if !repeats_docs_rs {
+            strategies.push(fallback);
+        }
+    }
 
     let mut failed_attempts = Vec::new();
 
@@ -286,13 +592,18 @@ pub async fn run_cargo_rustdoc_json(
 
         // Build args with current feature strategy
         let feature_args = strategy.args();
-        let rustdoc_args = vec![
+        let mut rustdoc_args = vec![
             "--".to_string(),
             "--output-format".to_string(),
             "json".to_string(),
             "-Z".to_string(),
             "unstable-options".to_string(),
         ];
+        if document_private_items {
+            rustdoc_args.push("--document-private-items".to_string());
+        }
+        rustdoc_args.extend_from_slice(&options.extra_args);
+        rustdoc_args.extend_from_slice(strategy.rustdoc_args());
 
         // First try without --lib to support crates that have a single target
         let mut args = base_args.clone();
@@ -428,7 +739,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_rustdoc_version() {
         // This test will pass if rustdoc is installed
-        let result = get_rustdoc_version().await;
+        let result = get_rustdoc_version(REQUIRED_TOOLCHAIN).await;
         // We can't guarantee the success state in all environments
         // but we can verify it returns a valid result
         assert!(result.is_ok() || result.is_err());
@@ -437,12 +748,113 @@ mod tests {
     #[tokio::test]
     async fn test_validate_toolchain() {
         // This test will pass if rustup is installed
-        let result = validate_toolchain().await;
+        let result = validate_toolchain(REQUIRED_TOOLCHAIN).await;
         // We can't guarantee the toolchain is installed in all environments
         // but we can verify it returns a valid result
         assert!(result.is_ok() || result.is_err());
     }
 
+    #[test]
+    fn test_check_format_version() {
+        let expected = rustdoc_types::FORMAT_VERSION;
+
+        let docs = serde_json::json!({ "format_version": expected });
+        assert!(check_format_version(&docs).is_ok());
+
+        let docs = serde_json::json!({ "format_version": expected + 1 });
+        assert_eq!(
+            check_format_version(&docs),
+            Err(FormatVersionMismatch {
+                found: Some(u64::from(expected + 1)),
+                expected,
+            })
+        );
+
+        let docs = serde_json::json!({ "index": {} });
+        assert_eq!(check_format_version(&docs).unwrap_err().found, None);
+    }
+
+    #[test]
+    fn test_toolchain_installed() {
+        let list = "stable-x86_64-unknown-linux-gnu (default)\n\
+                    nightly-2025-06-23-x86_64-unknown-linux-gnu\n\
+                    my-custom\n";
+        assert!(toolchain_installed(list, "nightly-2025-06-23"));
+        assert!(toolchain_installed(list, "stable"));
+        assert!(toolchain_installed(list, "my-custom"));
+        assert!(!toolchain_installed(list, "nightly-2024-10-01"));
+        assert!(!toolchain_installed(list, "beta"));
+    }
+
+    #[test]
+    fn test_parse_rustdoc_args() {
+        assert_eq!(
+            parse_rustdoc_args("--cfg docsrs --document-hidden-items").unwrap(),
+            ["--cfg", "docsrs", "--document-hidden-items"]
+        );
+        assert_eq!(
+            parse_rustdoc_args("--cfg=feature=\"serde\" -Z unstable-options").unwrap(),
+            ["--cfg", "feature=\"serde\""]
+        );
+        assert!(parse_rustdoc_args("").unwrap().is_empty());
+
+        assert!(parse_rustdoc_args("--cfg").is_err());
+        assert!(parse_rustdoc_args("--cfg 'docsrs'").is_err());
+        assert!(parse_rustdoc_args("--cfg feature=\"a b\"").is_err());
+        assert!(parse_rustdoc_args("--cfg feature=serde").is_err());
+        assert!(parse_rustdoc_args("-Z codegen-backend=/tmp/evil.so").is_err());
+        assert!(parse_rustdoc_args("--extern evil=/tmp/libevil.rlib").is_err());
+        assert!(parse_rustdoc_args("--document-private-items").is_err());
+        assert!(parse_rustdoc_args("--cfg docsrs; rm -rf /").is_err());
+    }
+
+    #[test]
+    fn test_cargo_rustdoc_base_args_uses_toolchain_override() {
+        assert_eq!(
+            cargo_rustdoc_base_args("nightly-2024-10-01", None, None),
+            vec!["+nightly-2024-10-01".to_string(), "rustdoc".to_string()]
+        );
+        assert_eq!(
+            cargo_rustdoc_base_args(REQUIRED_TOOLCHAIN, Some("rmcp"), None),
+            vec![
+                format!("+{REQUIRED_TOOLCHAIN}"),
+                "rustdoc".to_string(),
+                "-p".to_string(),
+                "rmcp".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cargo_rustdoc_base_args_with_target() {
+        assert_eq!(
+            cargo_rustdoc_base_args(REQUIRED_TOOLCHAIN, None, Some("x86_64-pc-windows-msvc")),
+            vec![
+                format!("+{REQUIRED_TOOLCHAIN}"),
+                "rustdoc".to_string(),
+                "--target".to_string(),
+                "x86_64-pc-windows-msvc".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_target_triple() {
+        assert!(validate_target_triple("x86_64-pc-windows-msvc").is_ok());
+        assert!(validate_target_triple("thumbv7em-none-eabihf").is_ok());
+        assert!(validate_target_triple("").is_err());
+        assert!(validate_target_triple("../escape").is_err());
+        assert!(validate_target_triple("x86_64 linux").is_err());
+    }
+
+    #[test]
+    fn test_target_installed() {
+        let list = "x86_64-unknown-linux-gnu\nwasm32-unknown-unknown\n";
+        assert!(target_installed(list, "wasm32-unknown-unknown"));
+        assert!(!target_installed(list, "x86_64-pc-windows-msvc"));
+        assert!(!target_installed(list, "wasm32"));
+    }
+
     #[test]
     fn test_feature_strategy_args() {
         assert_eq!(
@@ -32,3 +32,9 @@ pub const MAX_FUZZY_DISTANCE: u8 = 2;
 /// Whether transpositions cost 1 edit instead of 2 in fuzzy matching
 /// This makes fuzzy search more forgiving for common typos like "teh" -> "the"
 pub const FUZZY_TRANSPOSE_COST_ONE: bool = true;
+
+/// Default number of suggestions returned by find_similar_items
+pub const DEFAULT_SIMILAR_LIMIT: usize = 5;
+
+/// Number of fuzzy matches re-ranked by edit distance when looking for similar names
+pub const SIMILAR_CANDIDATE_LIMIT: usize = 200;
@@ -6,6 +6,7 @@
 //! - [`FuzzySearcher`] - Main searcher with fuzzy and standard search modes
 //! - [`FuzzySearchOptions`] - Configuration for search behavior
 //! - [`SearchResult`] - Structure containing search result information
+//! - [`FuzzySearcher::find_similar`] - "Did you mean" suggestions ranked by edit distance
 //!
 //! ## Example
 //! ```no_run
@@ -28,13 +29,15 @@
 //! ```
 
 use crate::search::config::{
-    DEFAULT_FUZZY_DISTANCE, DEFAULT_SEARCH_LIMIT, FUZZY_TRANSPOSE_COST_ONE, MAX_QUERY_LENGTH,
+    DEFAULT_FUZZY_DISTANCE, DEFAULT_SEARCH_LIMIT, FUZZY_TRANSPOSE_COST_ONE, MAX_FUZZY_DISTANCE,
+    MAX_QUERY_LENGTH, SIMILAR_CANDIDATE_LIMIT,
 };
 use crate::search::indexer::SearchIndexer;
 use anyhow::{Context, Result};
 use rmcp::schemars;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use tantivy::{
     Index, TantivyDocument, Term,
     collector::TopDocs,
@@ -70,12 +73,14 @@ pub struct FuzzySearchOptions {
     pub fuzzy_distance: u8,
     #[schemars(description = "Maximum number of results to return")]
     pub limit: usize,
-    #[schemars(description = "Filter by item kind")]
-    pub kind_filter: Option<String>,
+    #[schemars(description = "Filter by item kinds, matching any of them")]
+    pub kind_filter: Option<Vec<String>>,
     #[schemars(description = "Filter by crate name")]
     pub crate_filter: Option<String>,
     #[schemars(description = "Filter by workspace member")]
     pub member_filter: Option<String>,
+    #[schemars(description = "Filter by module path prefix")]
+    pub path_filter: Option<String>,
 }
 
 impl Default for FuzzySearchOptions {
@@ -87,6 +92,7 @@ impl Default for FuzzySearchOptions {
             kind_filter: None,
             crate_filter: None,
             member_filter: None,
+            path_filter: None,
         }
     }
 }
@@ -181,6 +187,102 @@ impl FuzzySearcher {
         Ok(results)
     }
 
+    /// Find the item names closest to `name`, for "did you mean" suggestions
+    ///
+    /// Candidates are gathered with a fuzzy query on the name field, then ranked by
+    /// case-insensitive edit distance to `name`. The returned score is a similarity
+    /// between 0 and 1, where 1 means the names are equal ignoring case.
+    pub fn find_similar(
+        &self,
+        name: &str,
+        limit: usize,
+        options: &FuzzySearchOptions,
+    ) -> Result<Vec<SearchResult>> {
+        if name.len() > MAX_QUERY_LENGTH {
+            return Err(anyhow::anyhow!(
+                "Query too long (max {MAX_QUERY_LENGTH} characters)"
+            ));
+        }
+
+        let name_lower = name.to_lowercase();
+
+        // The name field is tokenized, so match each word of the name separately
+        let term_clauses: Vec<(Occur, Box<dyn Query>)> = name_lower
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|token| !token.is_empty())
+            .map(|token| {
+                let fuzzy_query = FuzzyTermQuery::new(
+                    Term::from_field_text(self.fields.name, token),
+                    MAX_FUZZY_DISTANCE,
+                    FUZZY_TRANSPOSE_COST_ONE,
+                );
+                (Occur::Should, Box::new(fuzzy_query) as Box<dyn Query>)
+            })
+            .collect();
+        if term_clauses.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut clauses = vec![(
+            Occur::Must,
+            Box::new(BooleanQuery::new(term_clauses)) as Box<dyn Query>,
+        )];
+
+        if let Some(crate_name) = &options.crate_filter {
+            let crate_term = Term::from_field_text(self.fields.crate_name, crate_name);
+            let crate_query = TermQuery::new(crate_term, tantivy::schema::IndexRecordOption::Basic);
+            clauses.push((Occur::Must, Box::new(crate_query) as Box<dyn Query>));
+        }
+
+        if let Some(member_name) = &options.member_filter {
+            let member_term = Term::from_field_text(self.fields.member, member_name);
+            let member_query =
+                TermQuery::new(member_term, tantivy::schema::IndexRecordOption::Basic);
+            clauses.push((Occur::Must, Box::new(member_query) as Box<dyn Query>));
+        }
+
+        let reader = self.index.reader()?;
+        let searcher = reader.searcher();
+        let top_docs = searcher.search(
+            &BooleanQuery::new(clauses),
+            &TopDocs::with_limit(SIMILAR_CANDIDATE_LIMIT),
+        )?;
+
+        let mut seen = HashSet::new();
+        let mut ranked = Vec::new();
+        for (_, doc_address) in top_docs {
+            let doc = searcher.doc(doc_address)?;
+            let Some(result) = self.doc_to_search_result(&doc, 0.0)? else {
+                continue;
+            };
+            if !self.matches_filters(&result, options)
+                || !seen.insert((result.name.clone(), result.path.clone()))
+            {
+                continue;
+            }
+
+            let candidate = result.name.to_lowercase();
+            let distance = levenshtein(&name_lower, &candidate);
+            let longest = name_lower.chars().count().max(candidate.chars().count());
+            ranked.push((distance, longest, result));
+        }
+
+        ranked.sort_by(|a, b| {
+            a.0.cmp(&b.0)
+                .then_with(|| a.2.name.cmp(&b.2.name))
+                .then_with(|| a.2.path.cmp(&b.2.path))
+        });
+
+        Ok(ranked
+            .into_iter()
+            .take(limit)
+            .map(|(distance, longest, mut result)| {
+                result.score = 1.0 - distance as f32 / longest.max(1) as f32;
+                result
+            })
+            .collect())
+    }
+
     /// Build fuzzy query with typo tolerance
     fn build_fuzzy_query(
         &self,
@@ -307,7 +409,13 @@ impl FuzzySearcher {
     /// Check if result matches additional filters
     fn matches_filters(&self, result: &SearchResult, options: &FuzzySearchOptions) -> bool {
         if let Some(kind_filter) = &options.kind_filter
-            && result.kind != *kind_filter
+            && !kind_filter.contains(&result.kind)
+        {
+            return false;
+        }
+
+        if let Some(path_filter) = &options.path_filter
+            && !result.path.starts_with(path_filter.as_str())
         {
             return false;
         }
@@ -330,6 +438,24 @@ impl FuzzySearcher {
     }
 }
 
+/// Levenshtein edit distance between two strings, counted in characters
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut current = vec![0; b_chars.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b_chars.len()]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -351,6 +477,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("version", "version"), 0);
+        assert_eq!(levenshtein("verson", "version"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+    }
+
     #[test]
     fn test_fuzzy_search_options_default() {
         let options = FuzzySearchOptions::default();
@@ -360,6 +495,7 @@ mod tests {
         assert!(options.kind_filter.is_none());
         assert!(options.crate_filter.is_none());
         assert!(options.member_filter.is_none());
+        assert!(options.path_filter.is_none());
     }
 
     #[test]
@@ -12,7 +12,7 @@ pub struct SearchResult {
     /// Relevance score
     pub score: f32,
     /// Item ID
-    pub item_id: u32,
+    pub item_id: String,
     /// Item name
     pub name: String,
     /// Item path
@@ -59,6 +59,37 @@ impl SearchItemsFuzzyOutput {
     }
 }
 
+/// A suggested item whose name is close to the requested one
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SimilarItem {
+    /// Item name
+    pub name: String,
+    /// Item path
+    pub path: String,
+    /// Item kind
+    pub kind: String,
+    /// Item ID, usable with get_item_details
+    pub item_id: String,
+    /// Name similarity between 0 and 1 (1 means equal ignoring case)
+    pub score: f32,
+}
+
+/// Output from find_similar_items operation
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct FindSimilarItemsOutput {
+    pub query: String,
+    /// Suggestions sorted by edit distance, closest first
+    pub suggestions: Vec<SimilarItem>,
+}
+
+impl FindSimilarItemsOutput {
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
 /// Error output for search tools
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct SearchErrorOutput {
@@ -89,7 +120,7 @@ mod tests {
         let output = SearchItemsFuzzyOutput {
             results: vec![SearchResult {
                 score: 0.95,
-                item_id: 42,
+                item_id: "42".to_string(),
                 name: "deserialize".to_string(),
                 path: "serde::de".to_string(),
                 kind: "function".to_string(),
@@ -5,6 +5,7 @@
 //! ## Key Components
 //! - [`SearchTools`] - MCP tool handler for search operations
 //! - [`SearchItemsFuzzyParams`] - Parameters for fuzzy search requests
+//! - [`FindSimilarItemsParams`] - Parameters for "did you mean" name suggestions
 //!
 //! ## Features
 //! - Automatic crate indexing on first search
@@ -29,6 +30,7 @@
 //!     fuzzy_distance: Some(1),
 //!     limit: Some(10),
 //!     kind_filter: None,
+//!     path_filter: None,
 //!     member: None,
 //! };
 //!
@@ -45,10 +47,14 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::cache::{CrateCache, storage::CacheStorage};
+use crate::docs::kinds::{KindFilterInput, deserialize_kind_filter, normalize_kind_filter};
 use crate::search::config::{
-    DEFAULT_FUZZY_DISTANCE, DEFAULT_SEARCH_LIMIT, MAX_FUZZY_DISTANCE, MAX_SEARCH_LIMIT,
+    DEFAULT_FUZZY_DISTANCE, DEFAULT_SEARCH_LIMIT, DEFAULT_SIMILAR_LIMIT, MAX_FUZZY_DISTANCE,
+    MAX_SEARCH_LIMIT,
+};
+use crate::search::outputs::{
+    FindSimilarItemsOutput, SearchErrorOutput, SearchItemsFuzzyOutput, SimilarItem,
 };
-use crate::search::outputs::{SearchErrorOutput, SearchItemsFuzzyOutput};
 use crate::search::{FuzzySearchOptions, FuzzySearcher, SearchIndexer, SearchResult};
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -65,7 +71,35 @@ pub struct SearchItemsFuzzyParams {
     pub fuzzy_distance: Option<u8>,
     #[schemars(description = "Maximum number of results to return")]
     pub limit: Option<usize>,
-    #[schemars(description = "Filter by item kind")]
+    #[schemars(
+        description = "Filter by item kind (e.g., 'function', 'struct', 'enum'). Several kinds can be given comma-separated or as a list (e.g., 'struct,enum'), matching items of any of them. Case-insensitive; aliases such as 'fn' are accepted",
+        with = "Option<KindFilterInput>"
+    )]
+    #[serde(default, deserialize_with = "deserialize_kind_filter")]
+    pub kind_filter: Option<String>,
+    #[schemars(description = "Optional filter by module path prefix")]
+    pub path_filter: Option<String>,
+    #[schemars(
+        description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
+    )]
+    pub member: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FindSimilarItemsParams {
+    #[schemars(description = "The name of the crate")]
+    pub crate_name: String,
+    #[schemars(description = "The version of the crate")]
+    pub version: String,
+    #[schemars(description = "The item name to find close matches for (e.g., 'HashMpa')")]
+    pub name: String,
+    #[schemars(description = "Maximum number of suggestions to return (default: 5)")]
+    pub limit: Option<usize>,
+    #[schemars(
+        description = "Filter by item kind (e.g., 'function', 'struct', 'enum'). Several kinds can be given comma-separated or as a list (e.g., 'struct,enum'), matching items of any of them. Case-insensitive; aliases such as 'fn' are accepted",
+        with = "Option<KindFilterInput>"
+    )]
+    #[serde(default, deserialize_with = "deserialize_kind_filter")]
     pub kind_filter: Option<String>,
     #[schemars(
         description = "For workspace crates, specify the member path (e.g., 'crates/rmcp')"
@@ -94,6 +128,56 @@ impl SearchTools {
         cache.storage.has_search_index(crate_name, version, member)
     }
 
+    /// Make sure docs and a search index exist for the crate, generating them if needed
+    ///
+    /// Returns a copy of the cache storage so the search itself can run without
+    /// holding any locks.
+    async fn ensure_search_index(
+        &self,
+        crate_name: &str,
+        version: &str,
+        member: Option<&str>,
+    ) -> Result<CacheStorage, anyhow::Error> {
+        // First check with read lock if docs already exist
+        {
+            let cache = self.cache.read().await;
+            let has_docs = cache.has_docs(crate_name, version, member);
+
+            if has_docs && self.has_search_index(crate_name, version, member).await {
+                // Docs and index exist, proceed with search using read lock only
+                return Ok(cache.storage.clone());
+            }
+        }
+
+        // Need to generate docs/index, acquire write lock
+        {
+            let cache = self.cache.write().await;
+            // Double-check in case another task generated it
+            let has_docs = cache.has_docs(crate_name, version, member);
+
+            if !has_docs {
+                cache
+                    .ensure_crate_or_member_docs(crate_name, version, member)
+                    .await?;
+            }
+        }
+
+        let cache = self.cache.read().await;
+        let storage = cache.storage.clone();
+        drop(cache);
+
+        // Check if search index exists after ensuring docs
+        if !self.has_search_index(crate_name, version, member).await {
+            // Docs exist but search index is missing - regenerate it
+            let cache = self.cache.write().await;
+            cache
+                .create_search_index(crate_name, version, member)
+                .await?;
+        }
+
+        Ok(storage)
+    }
+
     /// Perform the actual search without holding any locks
     async fn perform_search(
         &self,
@@ -107,6 +191,11 @@ impl SearchTools {
             &storage,
             params.member.as_deref(),
         )?;
+        storage.touch(
+            &params.crate_name,
+            &params.version,
+            params.member.as_deref(),
+        );
 
         // Create fuzzy searcher
         let fuzzy_searcher = FuzzySearcher::from_indexer(&indexer)?;
@@ -130,9 +219,11 @@ impl SearchTools {
             fuzzy_enabled: params.fuzzy_enabled.unwrap_or(true),
             fuzzy_distance,
             limit,
-            kind_filter: params.kind_filter.clone(),
+            kind_filter: normalize_kind_filter(params.kind_filter.as_deref())?
+                .map(|kinds| kinds.into_iter().map(String::from).collect()),
             crate_filter: Some(params.crate_name.clone()),
             member_filter: params.member.clone(),
+            path_filter: params.path_filter.clone(),
         };
 
         // Perform search
@@ -149,79 +240,20 @@ impl SearchTools {
         let crate_name = params.crate_name.clone();
         let version = params.version.clone();
         let member = params.member.clone();
+        // Keep the crate from being removed while its index is being read
+        let _pin = self
+            .cache
+            .read()
+            .await
+            .pin(&params.crate_name, &params.version);
         let result = async {
-            // First check with read lock if docs already exist
-            {
-                let cache = self.cache.read().await;
-                let has_docs = cache.has_docs(
-                    &params.crate_name,
-                    &params.version,
-                    params.member.as_deref(),
-                );
-
-                if has_docs
-                    && self
-                        .has_search_index(
-                            &params.crate_name,
-                            &params.version,
-                            params.member.as_deref(),
-                        )
-                        .await
-                {
-                    // Docs and index exist, proceed with search using read lock only
-                    let storage = cache.storage.clone();
-                    drop(cache); // Release read lock early
-
-                    return self.perform_search(params, storage).await;
-                }
-            }
-
-            // Need to generate docs/index, acquire write lock
-            {
-                let cache = self.cache.write().await;
-                // Double-check in case another task generated it
-                let has_docs = cache.has_docs(
-                    &params.crate_name,
-                    &params.version,
-                    params.member.as_deref(),
-                );
-
-                if !has_docs {
-                    cache
-                        .ensure_crate_or_member_docs(
-                            &params.crate_name,
-                            &params.version,
-                            params.member.as_deref(),
-                        )
-                        .await?;
-                }
-            }
-
-            // Now perform search with read lock
-            let cache = self.cache.read().await;
-            let storage = cache.storage.clone();
-            drop(cache);
-
-            // Check if search index exists after ensuring docs
-            if !self
-                .has_search_index(
+            let storage = self
+                .ensure_search_index(
                     &params.crate_name,
                     &params.version,
                     params.member.as_deref(),
                 )
-                .await
-            {
-                // Docs exist but search index is missing - regenerate it
-                let cache = self.cache.write().await;
-                cache
-                    .create_search_index(
-                        &params.crate_name,
-                        &params.version,
-                        params.member.as_deref(),
-                    )
-                    .await?;
-            }
-
+                .await?;
             self.perform_search(params, storage).await
         }
         .await;
@@ -234,7 +266,7 @@ impl SearchTools {
                         .into_iter()
                         .map(|r| crate::search::outputs::SearchResult {
                             score: r.score,
-                            item_id: r.item_id,
+                            item_id: r.item_id.to_string(),
                             name: r.name,
                             path: r.path,
                             kind: r.kind,
@@ -256,4 +288,70 @@ impl SearchTools {
             Err(e) => Err(SearchErrorOutput::new(format!("Search failed: {e}"))),
         }
     }
+
+    /// Suggest item names close to a possibly misspelled name
+    pub async fn find_similar_items(
+        &self,
+        params: FindSimilarItemsParams,
+    ) -> Result<FindSimilarItemsOutput, SearchErrorOutput> {
+        let limit = params.limit.unwrap_or(DEFAULT_SIMILAR_LIMIT);
+        if limit > MAX_SEARCH_LIMIT {
+            return Err(SearchErrorOutput::new(format!(
+                "Limit must not exceed {MAX_SEARCH_LIMIT}"
+            )));
+        }
+
+        // Keep the crate from being removed while its index is being read
+        let _pin = self
+            .cache
+            .read()
+            .await
+            .pin(&params.crate_name, &params.version);
+        let result = async {
+            let storage = self
+                .ensure_search_index(
+                    &params.crate_name,
+                    &params.version,
+                    params.member.as_deref(),
+                )
+                .await?;
+
+            let indexer = SearchIndexer::new_for_crate(
+                &params.crate_name,
+                &params.version,
+                &storage,
+                params.member.as_deref(),
+            )?;
+            let fuzzy_searcher = FuzzySearcher::from_indexer(&indexer)?;
+
+            let options = FuzzySearchOptions {
+                kind_filter: normalize_kind_filter(params.kind_filter.as_deref())?
+                    .map(|kinds| kinds.into_iter().map(String::from).collect()),
+                crate_filter: Some(params.crate_name.clone()),
+                member_filter: params.member.clone(),
+                ..Default::default()
+            };
+            fuzzy_searcher.find_similar(&params.name, limit, &options)
+        }
+        .await;
+
+        match result {
+            Ok(results) => Ok(FindSimilarItemsOutput {
+                query: params.name,
+                suggestions: results
+                    .into_iter()
+                    .map(|r| SimilarItem {
+                        name: r.name,
+                        path: r.path,
+                        kind: r.kind,
+                        item_id: r.item_id.to_string(),
+                        score: r.score,
+                    })
+                    .collect(),
+            }),
+            Err(e) => Err(SearchErrorOutput::new(format!(
+                "Finding similar items failed: {e}"
+            ))),
+        }
+    }
 }
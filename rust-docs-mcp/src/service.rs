@@ -1,7 +1,8 @@
 use rmcp::handler::server::wrapper::Parameters;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Instant;
+use tokio::sync::{RwLock, mpsc};
 
 use anyhow::Result;
 use rmcp::schemars::{self, JsonSchema};
@@ -10,7 +11,8 @@ use rmcp::{
     handler::server::{router::prompt::PromptRouter, router::tool::ToolRouter},
     model::{
         GetPromptRequestParam, GetPromptResult, ListPromptsResult, PaginatedRequestParam,
-        PromptMessage, PromptMessageRole, ServerCapabilities, ServerInfo,
+        ProgressNotificationParam, PromptMessage, PromptMessageRole, ServerCapabilities,
+        ServerInfo,
     },
     prompt, prompt_handler, prompt_router,
     service::RequestContext,
@@ -19,21 +21,46 @@ use rmcp::{
 
 use serde::{Deserialize, Serialize};
 
-use crate::analysis::tools::{AnalysisTools, AnalyzeCrateStructureParams};
+use crate::access_log::AccessLog;
+use crate::analysis::tools::{
+    AnalysisTools, AnalyzeCrateStructureParams, GetCrateEditionParams, GetCrateSourceTreeParams,
+    GetUsageExamplesParams, ListCrateTargetsParams,
+};
 use crate::cache::{
-    CrateCache,
     task_manager::TaskManager,
     tools::{
-        CacheCrateParams, CacheOperationsParams, CacheTools, GetCratesMetadataParams,
-        ListCrateVersionsParams, RemoveCrateParams,
+        CacheCrateParams, CacheLatestParams, CacheOperationsParams, CacheTools,
+        ClearAllCachesParams, EnsureReadyParams, GetCratesMetadataParams, ListCrateVersionsParams,
+        RemoveCrateParams,
     },
+    watchdog::{CacheBudget, CacheWatchdog, WatchdogGuard},
+};
+use crate::config::ServiceConfig;
+use crate::deps::tools::{
+    DepsTools, DiffDependenciesParams, GetDependenciesParams, GetDependentsInCacheParams,
 };
-use crate::deps::tools::{DepsTools, GetDependenciesParams};
+use crate::docs::outputs::DocsErrorOutput;
 use crate::docs::tools::{
-    DocsTools, GetItemDetailsParams, GetItemDocsParams, GetItemSourceParams, ListItemsParams,
-    SearchItemsParams, SearchItemsPreviewParams,
+    DiffVisibilityParams, DocsTools, ExportDocsMarkdownParams, ExportItemsNdjsonParams,
+    GetAllMethodsParams, GetBlanketImplsParams, GetChangedItemsSinceParams, GetDocsForNamesParams,
+    GetItemDetailsParams, GetItemDocsParams, GetItemRawParams, GetItemSourceParams,
+    GetReprInfoParams, GetSupertraitsParams, ListCfgAttributesParams, ListItemsParams,
+    SearchItemsParams, SearchItemsPreviewParams, StreamItemsParams,
 };
-use crate::search::tools::{SearchItemsFuzzyParams, SearchTools};
+use crate::format::{ResponseFormat, ToText};
+use crate::search::tools::{FindSimilarItemsParams, SearchItemsFuzzyParams, SearchTools};
+use crate::timeout::{ToolMetrics, ToolOutcome, ToolTimeoutOutput, run_with_timeout};
+use crate::validation::validate_params;
+
+/// Tools that modify the cache, hidden when the server is read-only
+const MUTATING_TOOLS: [&str; 6] = [
+    "cache_crate",
+    "cache_latest",
+    "remove_crate",
+    "clear_all_caches",
+    "reindex",
+    "prune_indexes",
+];
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 struct CacheDependenciesArgs {
@@ -62,30 +89,114 @@ pub struct RustDocsService {
     deps_tools: DepsTools,
     analysis_tools: AnalysisTools,
     search_tools: SearchTools,
+    task_manager: Arc<TaskManager>,
+    access_log: AccessLog,
+    tool_metrics: ToolMetrics,
+    /// Stops the cache size watchdog once the last clone of the service is dropped
+    _watchdog: Option<Arc<WatchdogGuard>>,
+    config: ServiceConfig,
 }
 
 #[tool_router]
 impl RustDocsService {
     pub fn new(cache_dir: Option<PathBuf>) -> Result<Self> {
-        let cache = Arc::new(RwLock::new(CrateCache::new(cache_dir)?));
-        let task_manager = Arc::new(TaskManager::new());
+        Self::with_config(cache_dir, ServiceConfig::default())
+    }
+
+    /// Create a new service with custom configuration
+    pub fn with_config(cache_dir: Option<PathBuf>, config: ServiceConfig) -> Result<Self> {
+        let cache = config.open_cache(cache_dir)?;
+        let cache = Arc::new(RwLock::new(cache));
+
+        // Keep the cache within its budget, if it has one
+        let watchdog = config.max_cache_bytes.map(|max_bytes| {
+            CacheWatchdog::new(
+                cache.clone(),
+                CacheBudget::new(max_bytes, config.cache_low_water_percent),
+                config.cache_watchdog_interval,
+            )
+        });
+        let cache_size = watchdog
+            .as_ref()
+            .map(CacheWatchdog::gauge)
+            .unwrap_or_default();
+        let watchdog = watchdog
+            .map(|watchdog| watchdog.spawn().map(Arc::new))
+            .transpose()?;
+
+        let task_manager = Arc::new(TaskManager::with_max_concurrent_tasks(
+            config.max_concurrent_tasks,
+        ));
+
+        let access_log = match &config.access_log {
+            Some(path) => AccessLog::open(path)?,
+            None => AccessLog::default(),
+        };
+
+        let tool_metrics = ToolMetrics::default();
+
+        let mut tool_router = Self::tool_router();
+        if config.read_only {
+            // Clients never see the tools that would fail anyway
+            for tool in MUTATING_TOOLS {
+                tool_router.remove_route(tool);
+            }
+        }
 
         Ok(Self {
-            tool_router: Self::tool_router(),
+            tool_router,
             prompt_router: Self::prompt_router(),
-            cache_tools: CacheTools::new(cache.clone(), task_manager),
-            docs_tools: DocsTools::new(cache.clone()),
+            cache_tools: CacheTools::new(cache.clone(), task_manager.clone())
+                .with_cache_size_gauge(cache_size)
+                .with_tool_metrics(tool_metrics.clone()),
+            docs_tools: DocsTools::new(cache.clone())
+                .with_max_response_bytes(config.max_response_bytes)
+                .with_search_time_budget(config.search_time_budget),
             deps_tools: DepsTools::new(cache.clone()),
             analysis_tools: AnalysisTools::new(cache.clone()),
             search_tools: SearchTools::new(cache),
+            task_manager,
+            access_log,
+            tool_metrics,
+            _watchdog: watchdog,
+            config,
         })
     }
 
+    /// Wait until every tool call logged so far is written to the access log
+    pub async fn flush_access_log(&self) {
+        self.access_log.flush().await;
+    }
+
+    /// Format requested by a call's `format` parameter, or the configured default
+    fn response_format(&self, requested: Option<&str>) -> Result<ResponseFormat, DocsErrorOutput> {
+        ResponseFormat::resolve(requested, self.config.default_format)
+            .map_err(|e| DocsErrorOutput::new(e.to_string()))
+    }
+
+    /// Run a read-only query tool bounded by the configured query timeout
+    async fn with_query_timeout(
+        &self,
+        tool: &str,
+        future: impl Future<Output = Result<String, String>>,
+    ) -> String {
+        run_with_timeout(tool, self.config.query_timeout, &self.tool_metrics, future).await
+    }
+
+    /// Run a cache-modifying tool bounded by the configured cache timeout
+    async fn with_cache_timeout(
+        &self,
+        tool: &str,
+        future: impl Future<Output = Result<String, String>>,
+    ) -> String {
+        run_with_timeout(tool, self.config.cache_timeout, &self.tool_metrics, future).await
+    }
+
     // Cache tools
     #[tool(
         description = "Download and cache a crate from various sources for offline use. This operation runs asynchronously in the background and returns immediately with a task ID for monitoring progress.
 
-SOURCE TYPE: Set 'source_type' to one of: 'cratesio', 'github', or 'local'
+SOURCE TYPE: Set 'source_type' to one of: 'cratesio', 'github', 'local', or 'archive'
 
 REQUIRED PARAMETERS BY SOURCE TYPE:
 
@@ -103,36 +214,173 @@ REQUIRED PARAMETERS BY SOURCE TYPE:
 3. For source_type='local':
    - path: Local file system path (supports absolute paths, ~/home paths, and relative paths)
    - version: Optional, will be read from Cargo.toml if not provided
-   Example: {crate_name: 'my-crate', source_type: 'local', path: '~/projects/my-crate'}
+   - crate_name: Optional, will be read from Cargo.toml if not provided (required for workspaces)
+   Example: {source_type: 'local', path: '~/projects/my-crate'}
+
+4. For source_type='archive':
+   - path: Path of a .crate, .tar.gz or .tgz file, e.g. a vendored dependency or a file from a private registry
+   - version: Optional, will be read from the archive's Cargo.toml if not provided
+   Example: {crate_name: 'serde', source_type: 'archive', path: './vendor/serde-1.0.215.crate'}
 
 OPTIONAL PARAMETERS (all source types):
-- members: List of workspace members to cache (e.g., ['crates/core', 'crates/macros'])
+- members: List of workspace members to cache (e.g., ['crates/core', 'crates/macros']), or 'default' to cache the workspace's default-members (all members if none are declared)
 - update: Force re-cache even if already cached (default: false)
+- target: Also document the crate for another target triple (e.g., 'x86_64-pc-windows-msvc') to expose platform-specific APIs; pass the same 'target' to the documentation tools to query them
 
 MONITORING: Use cache_operations tool to monitor progress, cancel, or check status of caching operations."
     )]
     pub async fn cache_crate(&self, Parameters(params): Parameters<CacheCrateParams>) -> String {
-        self.cache_tools.cache_crate(params).await
+        self.access_log.record("cache_crate", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        let request = params.clone();
+        let timeout = self.config.cache_timeout;
+        let started = Instant::now();
+
+        match tokio::time::timeout(timeout, self.cache_tools.cache_crate(params)).await {
+            Ok(response) => {
+                let outcome = ToolOutcome::of_response(&response);
+                self.tool_metrics
+                    .record("cache_crate", started.elapsed(), outcome);
+                response.unwrap_or_else(|error| error)
+            }
+            Err(_) => {
+                tracing::warn!("Tool 'cache_crate' timed out after {:?}", timeout);
+                self.tool_metrics
+                    .record("cache_crate", started.elapsed(), ToolOutcome::Timeout);
+                // Point the client at the background task so it polls instead of retrying
+                let task_id = self
+                    .task_manager
+                    .find_active_task(&request)
+                    .await
+                    .map(|task| task.task_id);
+                ToolTimeoutOutput::new("cache_crate", timeout, task_id).to_json()
+            }
+        }
+    }
+
+    #[tool(
+        description = "Cache the latest published version of a crate from crates.io without knowing its version. Resolves the newest non-yanked release (set allow_prerelease: true to include pre-releases) and starts caching it in the background. Returns the resolved version and a task ID; use cache_operations to monitor progress, then pass the resolved version to the documentation tools."
+    )]
+    pub async fn cache_latest(&self, Parameters(params): Parameters<CacheLatestParams>) -> String {
+        self.access_log.record("cache_latest", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_cache_timeout("cache_latest", self.cache_tools.cache_latest(params))
+            .await
+    }
+
+    #[tool(
+        description = "Make sure a crate version's docs are downloaded and generated, without returning any of them. Use it to warm the cache before a burst of queries: it blocks until the docs are ready and reports only whether they are (ready), whether they had to be generated (generated) and how long it took (elapsed_ms). Cheaper than a listing query and unlike cache_crate it needs no polling. Fails in read-only mode for crates that are not cached."
+    )]
+    pub async fn ensure_ready(&self, Parameters(params): Parameters<EnsureReadyParams>) -> String {
+        self.access_log.record("ensure_ready", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_cache_timeout("ensure_ready", async {
+            let output = self.cache_tools.ensure_ready(params).await;
+            match output.error {
+                None => Ok(output.to_json()),
+                Some(_) => Err(output.to_json()),
+            }
+        })
+        .await
     }
 
     #[tool(
         description = "Remove a cached crate version from local storage. Use to free up disk space or remove outdated versions. This only affects the local cache - the crate can be re-downloaded later if needed."
     )]
     pub async fn remove_crate(&self, Parameters(params): Parameters<RemoveCrateParams>) -> String {
-        match self.cache_tools.remove_crate(params).await {
-            Ok(output) => output.to_json(),
-            Err(error) => error.to_json(),
+        self.access_log.record("remove_crate", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
         }
+        self.with_cache_timeout("remove_crate", async {
+            match self.cache_tools.remove_crate(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Remove ALL cached crates, documentation and search indexes to start from a clean slate. This is destructive: every crate will need to be cached again. Requires confirm: true and fails while caching tasks are running. Reports the disk space reclaimed."
+    )]
+    pub async fn clear_all_caches(
+        &self,
+        Parameters(params): Parameters<ClearAllCachesParams>,
+    ) -> String {
+        self.access_log.record("clear_all_caches", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_cache_timeout("clear_all_caches", async {
+            match self.cache_tools.clear_all_caches(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Rebuild the search index of every cached crate and workspace member from its already generated documentation. Use after a search index upgrade or when search results look corrupted; source and docs are not touched and nothing is downloaded. Fails while caching tasks are running. Reports how many indexes were rebuilt and which failed."
+    )]
+    pub async fn reindex(&self) -> String {
+        self.access_log.record("reindex", None::<&()>);
+        self.with_cache_timeout("reindex", async {
+            match self.cache_tools.reindex().await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Remove search indexes whose documentation is no longer cached, such as indexes left behind by a removed crate, an interrupted update or a member that was dropped from a workspace. Cached crates and their working indexes are not touched. Fails while caching tasks are running. Reports each removed index and the disk space reclaimed."
+    )]
+    pub async fn prune_indexes(&self) -> String {
+        self.access_log.record("prune_indexes", None::<&()>);
+        self.with_cache_timeout("prune_indexes", async {
+            match self.cache_tools.prune_indexes().await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Report the running server's configuration: its version and MCP protocol version, the cache directory and how many crates it holds, active caching tasks, the docgen toolchain and rustdoc JSON format version, the registry, timeouts and response limits. Read-only and cheap; use it to debug a setup. Use the doctor CLI command to check the environment instead."
+    )]
+    pub async fn server_info(&self) -> String {
+        self.access_log.record("server_info", None::<&()>);
+        self.with_query_timeout("server_info", async {
+            match self.cache_tools.server_info(&self.config).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
     }
 
     #[tool(
         description = "List all locally cached crates with their versions and sizes. Use to see what crates are available offline and how much disk space they use. Shows cache metadata including when each crate was cached."
     )]
     pub async fn list_cached_crates(&self) -> String {
-        match self.cache_tools.list_cached_crates().await {
-            Ok(output) => output.to_json(),
-            Err(error) => error.to_json(),
-        }
+        self.access_log.record("list_cached_crates", None::<&()>);
+        self.with_query_timeout("list_cached_crates", async {
+            match self.cache_tools.list_cached_crates().await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
     }
 
     #[tool(
@@ -142,10 +390,17 @@ MONITORING: Use cache_operations tool to monitor progress, cancel, or check stat
         &self,
         Parameters(params): Parameters<ListCrateVersionsParams>,
     ) -> String {
-        match self.cache_tools.list_crate_versions(params).await {
-            Ok(output) => output.to_json(),
-            Err(error) => error.to_json(),
+        self.access_log.record("list_crate_versions", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
         }
+        self.with_query_timeout("list_crate_versions", async {
+            match self.cache_tools.list_crate_versions(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
     }
 
     #[tool(
@@ -155,18 +410,27 @@ MONITORING: Use cache_operations tool to monitor progress, cancel, or check stat
         &self,
         Parameters(params): Parameters<GetCratesMetadataParams>,
     ) -> String {
-        let output = self.cache_tools.get_crates_metadata(params).await;
-        output.to_json()
+        self.access_log.record("get_crates_metadata", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("get_crates_metadata", async {
+            let output = self.cache_tools.get_crates_metadata(params).await;
+            Ok(output.to_json())
+        })
+        .await
     }
 
     #[tool(
-        description = "Manage and monitor background caching operations. This unified tool handles all task-related operations: list all tasks, query specific task status, cancel in-progress tasks, and clear completed/failed tasks. Returns rich markdown-formatted output optimized for AI agents.
+        description = "Manage and monitor background caching operations. This unified tool handles all task-related operations: list all tasks, query specific task status, cancel in-progress tasks, retry failed tasks, and clear completed/failed tasks. Returns rich markdown-formatted output optimized for AI agents.
 
 Usage:
 - List all tasks: cache_operations({})
 - Filter by status: cache_operations({status_filter: \"in_progress\"})
 - Check specific task: cache_operations({task_id: \"abc-123-def\"})
 - Cancel task: cache_operations({task_id: \"abc-123-def\", cancel: true})
+- Retry failed tasks: cache_operations({retry_failed: true})
+- Retry specific: cache_operations({task_id: \"abc-123-def\", retry_failed: true})
 - Clear completed: cache_operations({clear: true})
 - Clear specific: cache_operations({task_id: \"abc-123-def\", clear: true})"
     )]
@@ -174,88 +438,482 @@ Usage:
         &self,
         Parameters(params): Parameters<CacheOperationsParams>,
     ) -> String {
-        self.cache_tools.cache_operations(params).await
+        self.access_log.record("cache_operations", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("cache_operations", async {
+            self.cache_tools.cache_operations(params).await
+        })
+        .await
     }
 
     // Docs tools
     #[tool(
-        description = "List all items in a crate's documentation. Use when browsing a crate's contents without a specific search term. Returns full item details including documentation. For large crates, consider using search_items_preview for a lighter response that only includes names and types. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+        description = "List all items in a crate's documentation. Use when browsing a crate's contents without a specific search term. Returns full item details including documentation. For large crates, consider using search_items_preview for a lighter response that only includes names and types. Set include_private to true to also list private items (docs are regenerated with private items on first use), or include_hidden to true to also list #[doc(hidden)] items, marked hidden: true. Set group_by_module to true to get the items nested under their module path in 'modules', an outline of the crate in one call; max_depth limits the nesting. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
     )]
     pub async fn list_crate_items(
         &self,
         Parameters(params): Parameters<ListItemsParams>,
     ) -> String {
-        match self.docs_tools.list_crate_items(params).await {
-            Ok(output) => output.to_json(),
-            Err(error) => error.to_json(),
+        self.access_log.record("list_crate_items", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
         }
+        self.with_query_timeout("list_crate_items", async {
+            match self.docs_tools.list_crate_items(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
     }
 
     #[tool(
-        description = "Search for items by name pattern in a crate. Use when looking for specific functions, types, or modules. Returns FULL details including documentation. WARNING: May exceed token limits for large results. Use search_items_preview first for exploration, then get_item_details for specific items. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+        description = "Stream all items in a crate's documentation in chunks. Use for very large crates where list_crate_items would need many paginated calls. When the request carries a progress token, each chunk of chunk_size items is delivered as a progress notification and the final result is a summary. Without a progress token this falls back to returning the first chunk_size items with pagination info, like list_crate_items. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn stream_crate_items(
+        &self,
+        Parameters(params): Parameters<StreamItemsParams>,
+        context: RequestContext<RoleServer>,
+    ) -> String {
+        self.access_log.record("stream_crate_items", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        let Some(progress_token) = context.meta.get_progress_token() else {
+            // The client cannot receive progress notifications, fall back to a paginated listing
+            return self
+                .with_query_timeout("stream_crate_items", async {
+                    match self
+                        .docs_tools
+                        .list_crate_items(params.to_list_params())
+                        .await
+                    {
+                        Ok(output) => Ok(output.to_json()),
+                        Err(error) => Err(error.to_json()),
+                    }
+                })
+                .await;
+        };
+
+        self.with_query_timeout("stream_crate_items", async {
+            let (tx, mut rx) = mpsc::channel(4);
+
+            let producer = self.docs_tools.stream_crate_items(params, tx);
+            let consumer = async {
+                let mut sent = 0;
+                while let Some(chunk) = rx.recv().await {
+                    sent += chunk.items.len();
+                    let notification = ProgressNotificationParam {
+                        progress_token: progress_token.clone(),
+                        progress: sent as f64,
+                        total: Some(chunk.total as f64),
+                        message: Some(chunk.to_json()),
+                    };
+                    if let Err(e) = context.peer.notify_progress(notification).await {
+                        tracing::warn!("Failed to send item chunk notification: {e}");
+                        break;
+                    }
+                }
+                // Dropping the receiver stops the producer early on failure
+                drop(rx);
+            };
+
+            let (result, ()) = tokio::join!(producer, consumer);
+            match result {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Search for items by name pattern in a crate. Use when looking for specific functions, types, or modules. Returns FULL details including documentation. WARNING: May exceed token limits for large results. Use search_items_preview first for exploration, then get_item_details for specific items. For broad patterns like 'new', set group_by to 'name' or 'kind' to collapse repeated results into groups. Set include_hidden to true to also search #[doc(hidden)] items. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
     )]
     pub async fn search_items(&self, Parameters(params): Parameters<SearchItemsParams>) -> String {
-        match self.docs_tools.search_items(params).await {
-            Ok(output) => output.to_json(),
-            Err(error) => error.to_json(),
+        self.access_log.record("search_items", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
         }
+        self.with_query_timeout("search_items", async {
+            match self.docs_tools.search_items(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
     }
 
     #[tool(
-        description = "Search for items by name pattern in a crate - PREVIEW MODE. Use this FIRST when searching to avoid token limits. Returns only id, name, kind, and path. Once you find items of interest, use get_item_details to fetch full documentation. This is the recommended search method for exploration. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+        description = "Search for items by name pattern in a crate - PREVIEW MODE. Use this FIRST when searching to avoid token limits. Returns only id, name, kind, and path; set compact to true to get them as one 'id  kind  path' line per item instead of JSON. Once you find items of interest, use get_item_details to fetch full documentation. This is the recommended search method for exploration. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
     )]
     pub async fn search_items_preview(
         &self,
         Parameters(params): Parameters<SearchItemsPreviewParams>,
     ) -> String {
-        match self.docs_tools.search_items_preview(params).await {
-            Ok(output) => output.to_json(),
-            Err(error) => error.to_json(),
+        self.access_log
+            .record("search_items_preview", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
         }
+        let compact = params.compact.unwrap_or(false);
+        self.with_query_timeout("search_items_preview", async {
+            match self.docs_tools.search_items_preview(params).await {
+                Ok(output) if compact => Ok(output.to_compact_text()),
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
     }
 
     #[tool(
-        description = "Get detailed information about a specific item by ID. Use after search_items_preview to fetch full details including documentation, signatures, fields, methods, etc. The item_id comes from search results. This is the recommended way to get complete information about a specific item. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+        description = "Get detailed information about a specific item by ID. Use after search_items_preview to fetch full details including documentation, signatures, fields, methods, etc. The item_id comes from search results; alternatively pass 'path' with a known item name or path (e.g., 'semver::Version') instead of an item_id. This is the recommended way to get complete information about a specific item. For large types, pass 'fields' (e.g., ['signature', 'docs']) to include only those parts. Traits that cannot be implemented outside their crate because they require a private or hidden supertrait are marked sealed: true, with a sealed_note explaining why. Set format to 'text' for a Markdown card instead of JSON. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
     )]
     pub async fn get_item_details(
         &self,
         Parameters(params): Parameters<GetItemDetailsParams>,
     ) -> String {
-        self.docs_tools.get_item_details(params).await.to_json()
+        self.access_log.record("get_item_details", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        let format = match self.response_format(params.format.as_deref()) {
+            Ok(format) => format,
+            Err(error) => return error.to_json(),
+        };
+        self.with_query_timeout("get_item_details", async {
+            let output = self.docs_tools.get_item_details(params).await;
+            if output.is_success() {
+                Ok(output.render(format))
+            } else {
+                Err(output.render(format))
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Export a crate's public API as a single Markdown document: a table of contents, then one section per module with each item's signature and documentation. Useful for offline reading or loading a whole small crate into context at once. Narrow large crates with kind_filter and max_items, or set output_path to write the document to a file instead of returning it. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn export_docs_markdown(
+        &self,
+        Parameters(params): Parameters<ExportDocsMarkdownParams>,
+    ) -> String {
+        self.access_log
+            .record("export_docs_markdown", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("export_docs_markdown", async {
+            match self.docs_tools.export_docs_markdown(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Export every item of a crate to a file as newline-delimited JSON, for bulk processing or offline indexing. The first line is a header with the crate, version and item count; each following line is one item with its id, name, kind, path, docs and visibility, as in list_crate_items. Unlike the listing tools, the export covers the whole crate regardless of the response size limit; only a summary is returned. output_path must be absolute. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn export_items_ndjson(
+        &self,
+        Parameters(params): Parameters<ExportItemsNdjsonParams>,
+    ) -> String {
+        self.access_log.record("export_items_ndjson", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("export_items_ndjson", async {
+            match self.docs_tools.export_items_ndjson(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
     }
 
     #[tool(
-        description = "Get ONLY the documentation string for a specific item. Use when you need just the docs without other details. More efficient than get_item_details if you only need the documentation text. Returns null if no documentation exists. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+        description = "List the items of a crate cached from GitHub that are defined in files changed between two commits, e.g. to review what a branch touched. Changes are tracked per file, so every item in a changed file is listed even if only part of the file changed. Needs the git history kept with --keep-git-history; shallow clones only hold the cached commit, so diffing against older commits also needs --git-depth 0. since and until accept commit hashes, tags or expressions like 'HEAD~5'; until defaults to the cached commit. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn get_changed_items_since(
+        &self,
+        Parameters(params): Parameters<GetChangedItemsSinceParams>,
+    ) -> String {
+        self.access_log
+            .record("get_changed_items_since", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("get_changed_items_since", async {
+            match self.docs_tools.get_changed_items_since(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "List the traits a struct, enum or union gets for free through blanket impls (e.g., `impl<T: Display> ToString for T`), plus the auto traits such as Send and Sync it implements. Use this to find out why a type has methods that are not in its own impls. The item_id comes from search results. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn get_blanket_impls(
+        &self,
+        Parameters(params): Parameters<GetBlanketImplsParams>,
+    ) -> String {
+        self.access_log.record("get_blanket_impls", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("get_blanket_impls", async {
+            match self.docs_tools.get_blanket_impls(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Get the memory-layout-relevant facts of a struct, enum or union for FFI and layout reasoning: its repr attribute (e.g., `#[repr(C)]`, `#[repr(transparent)]`), the traits it derives, which of Copy, Clone, Send and Sync it implements, and its field names in declaration order (variant names for enums). The item_id comes from search results. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn get_repr_info(&self, Parameters(params): Parameters<GetReprInfoParams>) -> String {
+        self.access_log.record("get_repr_info", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("get_repr_info", async {
+            match self.docs_tools.get_repr_info(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "List the distinct cfg predicates gating items in a crate (e.g., `feature = \"std\"`, `unix`, `all(unix, not(target_os = \"macos\"))`) with how many items each gates, most common first. Use this to learn which features and platforms a crate's API depends on. Only the cfg information rustdoc recorded is available: items whose cfg was false when the docs were generated are absent, so platform-specific predicates may only show up with the target parameter. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn list_cfg_attributes(
+        &self,
+        Parameters(params): Parameters<ListCfgAttributesParams>,
+    ) -> String {
+        self.access_log.record("list_cfg_attributes", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("list_cfg_attributes", async {
+            match self.docs_tools.list_cfg_attributes(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "List every method callable on a struct, enum or union: inherent methods plus the methods of all traits it implements, including trait methods with default implementations. Each method is tagged with where it comes from ('inherent' or 'trait <path>'). Set include_blanket: true to also include methods from blanket impls (e.g., to_string from `impl<T: Display> ToString for T`). Use this to answer 'what can I call on this value?'. The item_id comes from search results. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn get_all_methods(
+        &self,
+        Parameters(params): Parameters<GetAllMethodsParams>,
+    ) -> String {
+        self.access_log.record("get_all_methods", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("get_all_methods", async {
+            match self.docs_tools.get_all_methods(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "List the supertraits of a trait: the traits it requires of its implementors (`trait Foo: Bar + Baz` or `where Self: Bar`), followed transitively through each supertrait's own bounds. Returns the direct supertraits and the full transitive set, each with its item_id and path; transitive entries name the supertrait that introduced them in 'via'. Use this to understand the complete contract of a trait. Bounds of traits from other crates are not followed. The item_id comes from search results. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn get_supertraits(
+        &self,
+        Parameters(params): Parameters<GetSupertraitsParams>,
+    ) -> String {
+        self.access_log.record("get_supertraits", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("get_supertraits", async {
+            match self.docs_tools.get_supertraits(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Get the raw rustdoc JSON (rustdoc_types::Item) for an item, plus the raw entries of the items it references (fields, variants, impls, module and trait items) so the subtree is self-contained. Items are keyed by ID. Use depth to control how many levels of referenced items are included (default: 1, max: 5). An escape hatch for processing the rustdoc data directly when the curated outputs are too lossy; prefer get_item_details otherwise. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn get_item_raw(&self, Parameters(params): Parameters<GetItemRawParams>) -> String {
+        self.access_log.record("get_item_raw", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("get_item_raw", async {
+            match self.docs_tools.get_item_raw(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Get ONLY the documentation string for a specific item. Use when you need just the docs without other details. More efficient than get_item_details if you only need the documentation text. Returns null if no documentation exists. Set strip_doctest_scaffolding to drop rustdoc's hidden example lines ('# use ...', '# fn main() {'). Set format to 'text' for the plain Markdown documentation instead of JSON. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
     )]
     pub async fn get_item_docs(&self, Parameters(params): Parameters<GetItemDocsParams>) -> String {
-        match self.docs_tools.get_item_docs(params).await {
-            Ok(output) => output.to_json(),
-            Err(error) => error.to_json(),
+        self.access_log.record("get_item_docs", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
         }
+        let format = match self.response_format(params.format.as_deref()) {
+            Ok(format) => format,
+            Err(error) => return error.to_json(),
+        };
+        self.with_query_timeout("get_item_docs", async {
+            match self.docs_tools.get_item_docs(params).await {
+                Ok(output) => Ok(output.render(format)),
+                Err(error) => Err(error.render(format)),
+            }
+        })
+        .await
     }
 
     #[tool(
-        description = "Get the source code for a specific item. Returns the actual source code with optional context lines. Use after finding items of interest to view their implementation. The source location is also included in get_item_details responses. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+        description = "Get the documentation of several items at once by path (e.g., ['semver::Version', 'semver::Version::parse']). Use when you already know which symbols to document, instead of a search and get_item_details per symbol. Returns the docs keyed by name; names matching no item or several items carry an error instead. Names that do not fit in the response are listed in 'omitted'. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn get_docs_for_names(
+        &self,
+        Parameters(params): Parameters<GetDocsForNamesParams>,
+    ) -> String {
+        self.access_log.record("get_docs_for_names", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("get_docs_for_names", async {
+            match self.docs_tools.get_docs_for_names(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Get the source code for a specific item. Returns the actual source code with optional context lines. Use after finding items of interest to view their implementation. The source location is also included in get_item_details responses. Set resolve_references to also list where the crate's own types named in the item's signature are defined (file and line), for go-to-definition style navigation. Set strip_doctest_scaffolding to drop rustdoc's hidden lines from the examples in doc comments. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
     )]
     pub async fn get_item_source(
         &self,
         Parameters(params): Parameters<GetItemSourceParams>,
     ) -> String {
-        self.docs_tools.get_item_source(params).await.to_json()
+        self.access_log.record("get_item_source", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("get_item_source", async {
+            let output = self.docs_tools.get_item_source(params).await;
+            if output.is_success() {
+                Ok(output.to_json())
+            } else {
+                Err(output.to_json())
+            }
+        })
+        .await
     }
 
     // Deps tools
     #[tool(
-        description = "Get dependency information for a crate. Returns direct dependencies by default, with option to include full dependency tree. Use this to understand what a crate depends on, check for version conflicts, or explore the dependency graph. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+        description = "Get dependency information for a crate. Returns direct dependencies by default, with option to include full dependency tree. Use this to understand what a crate depends on, check for version conflicts, or explore the dependency graph. Use kind_filter to list only normal, dev or build dependencies, e.g. to audit build-time dependencies. Optional dependencies list the crate features that enable them (enabled_by), and feature_activations shows which crate features turn on features of a dependency, marking weak `dep?/feature` activations. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
     )]
     pub async fn get_dependencies(
         &self,
         Parameters(params): Parameters<GetDependenciesParams>,
     ) -> String {
-        match self.deps_tools.get_dependencies(params).await {
-            Ok(output) => output.to_json(),
-            Err(error) => error.to_json(),
+        self.access_log.record("get_dependencies", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("get_dependencies", async {
+            match self.deps_tools.get_dependencies(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Compare the direct dependencies of two versions of a crate. Returns the dependencies added in version_b, the ones removed since version_a, and the ones whose version requirement changed. Both versions are cached if needed. Use this to review what an upgrade pulls in. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn diff_dependencies(
+        &self,
+        Parameters(params): Parameters<DiffDependenciesParams>,
+    ) -> String {
+        self.access_log.record("diff_dependencies", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("diff_dependencies", async {
+            match self.deps_tools.diff_dependencies(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "List the locally cached crates and workspace members that depend on a crate, with their version requirement on it and the dependency kind. Use before remove_crate to see what else in the cache relies on a crate. Pass version to only keep dependents whose requirement that version satisfies. Reads the dependency metadata saved in the cache and needs no network; crates cached without saved metadata are skipped."
+    )]
+    pub async fn get_dependents_in_cache(
+        &self,
+        Parameters(params): Parameters<GetDependentsInCacheParams>,
+    ) -> String {
+        self.access_log
+            .record("get_dependents_in_cache", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
         }
+        self.with_query_timeout("get_dependents_in_cache", async {
+            match self.deps_tools.get_dependents_in_cache(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Compare the visibility of a crate's items between two versions and list the ones that became public and the ones that stopped being public, with their paths. Use this to catch items accidentally made `pub`, or to review API stability before a release. Items are matched by path, inherent methods as `Type::method`; items added or removed between the versions are not listed. Private docs of both versions are generated on first use. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn diff_visibility(
+        &self,
+        Parameters(params): Parameters<DiffVisibilityParams>,
+    ) -> String {
+        self.access_log.record("diff_visibility", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("diff_visibility", async {
+            match self.docs_tools.diff_visibility(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
     }
 
     // Analysis tools
@@ -266,24 +924,139 @@ Usage:
         &self,
         Parameters(params): Parameters<AnalyzeCrateStructureParams>,
     ) -> String {
-        match self.analysis_tools.structure(params).await {
-            Ok(output) => output.to_json(),
-            Err(error) => error.to_json(),
+        self.access_log.record("structure", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("structure", async {
+            match self.analysis_tools.structure(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "List a crate's Cargo targets: the library, binaries, examples, tests and benches, with each target's name, kind and root source path. Reads explicit [lib]/[[bin]]/[[example]]/[[test]]/[[bench]] sections of Cargo.toml and auto-detects targets in src/bin/, examples/, tests/ and benches/. Only needs the crate source, so it also works for binary-only packages that cannot be documented. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn list_crate_targets(
+        &self,
+        Parameters(params): Parameters<ListCrateTargetsParams>,
+    ) -> String {
+        self.access_log.record("list_crate_targets", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("list_crate_targets", async {
+            match self.analysis_tools.list_crate_targets(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Get a crate's Rust edition (2015, 2018, 2021 or 2024) and its minimum supported Rust version (rust-version) from Cargo.toml, resolving values inherited from the workspace. A cheap check before suggesting code, to know which syntax and language features the crate can use. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn get_crate_edition(
+        &self,
+        Parameters(params): Parameters<GetCrateEditionParams>,
+    ) -> String {
+        self.access_log.record("get_crate_edition", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
         }
+        self.with_query_timeout("get_crate_edition", async {
+            match self.analysis_tools.get_crate_edition(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "List the source files of a cached crate: Cargo.toml, build.rs and every file under src/ and examples/, with paths relative to the package directory. Set include_stats to also get each file's size in bytes and line count. A file system view complementing the module tree of structure, to decide which files to read with get_item_source. Only needs the crate source. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn get_crate_source_tree(
+        &self,
+        Parameters(params): Parameters<GetCrateSourceTreeParams>,
+    ) -> String {
+        self.access_log
+            .record("get_crate_source_tree", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("get_crate_source_tree", async {
+            match self.analysis_tools.get_crate_source_tree(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Find how an item is used in the crate's own examples/ and tests/ directories and its #[test] functions and #[cfg(test)] modules. Returns short snippets with the file and line of each whole-word reference to the item name, examples first, bounded in number and total size. Complements the doc-comment examples with concrete usage patterns. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn get_usage_examples(
+        &self,
+        Parameters(params): Parameters<GetUsageExamplesParams>,
+    ) -> String {
+        self.access_log.record("get_usage_examples", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("get_usage_examples", async {
+            match self.analysis_tools.get_usage_examples(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
     }
 
     // Search tools
     #[tool(
-        description = "Perform fuzzy search on crate items with typo tolerance and semantic similarity. This provides more flexible searching compared to exact pattern matching, allowing you to find items even with typos or partial matches. The search indexes item names, documentation, and metadata using Tantivy full-text search engine. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+        description = "Perform fuzzy search on crate items with typo tolerance and semantic similarity. This provides more flexible searching compared to exact pattern matching, allowing you to find items even with typos or partial matches. The search indexes item names, documentation, and metadata using Tantivy full-text search engine. Use path_filter to scope results to a module path prefix (e.g., 'tokio::sync'). For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
     )]
     pub async fn search_items_fuzzy(
         &self,
         Parameters(params): Parameters<SearchItemsFuzzyParams>,
     ) -> String {
-        match self.search_tools.search_items_fuzzy(params).await {
-            Ok(output) => output.to_json(),
-            Err(error) => error.to_json(),
+        self.access_log.record("search_items_fuzzy", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
+        }
+        self.with_query_timeout("search_items_fuzzy", async {
+            match self.search_tools.search_items_fuzzy(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
+    }
+
+    #[tool(
+        description = "Suggest items whose names are closest to the given name, for \"did you mean\" corrections when a name is misremembered or a lookup returns no results. Returns up to 5 suggestions (configurable with limit) sorted by edit distance, each with its path, item_id and a similarity score between 0 and 1. For workspace crates, specify the member parameter with the member path (e.g., 'crates/rmcp')."
+    )]
+    pub async fn find_similar_items(
+        &self,
+        Parameters(params): Parameters<FindSimilarItemsParams>,
+    ) -> String {
+        self.access_log.record("find_similar_items", Some(&params));
+        if let Err(error) = validate_params(&params) {
+            return error.to_json();
         }
+        self.with_query_timeout("find_similar_items", async {
+            match self.search_tools.find_similar_items(params).await {
+                Ok(output) => Ok(output.to_json()),
+                Err(error) => Err(error.to_json()),
+            }
+        })
+        .await
     }
 }
 
@@ -356,7 +1129,7 @@ impl ServerHandler for RustDocsService {
         ServerInfo {
             server_info: rmcp::model::Implementation {
                 name: "rust-docs-mcp".to_string(),
-                version: "0.1.1".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
                 title: None,
                 website_url: None,
                 icons: None,
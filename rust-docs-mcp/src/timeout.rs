@@ -0,0 +1,352 @@
+//! Timeout handling for tool calls
+//!
+//! Long-running tool calls (for example a query waiting on the cache lock while
+//! a large crate is being documented) are bounded by a timeout so that clients
+//! receive a structured response instead of blocking indefinitely.
+//!
+//! Every call is also timed and counted by outcome in the
+//! `rust_docs_mcp_tool_duration_seconds` histogram and the
+//! `rust_docs_mcp_tool_calls_total` counter, both labeled by tool.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Name of the histogram of tool call durations, labeled by tool
+pub const TOOL_DURATION_METRIC: &str = "rust_docs_mcp_tool_duration_seconds";
+
+/// Name of the counter of tool calls, labeled by tool and outcome
+pub const TOOL_CALLS_METRIC: &str = "rust_docs_mcp_tool_calls_total";
+
+/// Upper bounds of the duration histogram buckets, in seconds
+pub const TOOL_DURATION_BUCKETS: [f64; 10] =
+    [0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 60.0, 300.0];
+
+/// How a tool call ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolOutcome {
+    Ok,
+    /// The tool returned an error response
+    Error,
+    Timeout,
+}
+
+impl ToolOutcome {
+    pub const ALL: [ToolOutcome; 3] = [Self::Ok, Self::Error, Self::Timeout];
+
+    /// Value of the `outcome` label
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Error => "error",
+            Self::Timeout => "timeout",
+        }
+    }
+
+    /// Outcome of a call that completed with `response`
+    pub fn of_response<T, E>(response: &Result<T, E>) -> Self {
+        match response {
+            Ok(_) => Self::Ok,
+            Err(_) => Self::Error,
+        }
+    }
+}
+
+/// Calls and durations of one tool
+#[derive(Debug, Default)]
+struct ToolStats {
+    calls: [AtomicU64; ToolOutcome::ALL.len()],
+    /// Calls per bucket, the last one counting calls slower than every bound
+    buckets: [AtomicU64; TOOL_DURATION_BUCKETS.len() + 1],
+    sum_micros: AtomicU64,
+}
+
+/// Per-tool call counter and duration histogram, shared by every clone
+#[derive(Debug, Clone, Default)]
+pub struct ToolMetrics {
+    tools: Arc<Mutex<BTreeMap<String, Arc<ToolStats>>>>,
+}
+
+impl ToolMetrics {
+    /// Count one call of `tool` that took `elapsed` and ended with `outcome`
+    pub fn record(&self, tool: &str, elapsed: Duration, outcome: ToolOutcome) {
+        let stats = {
+            let mut tools = self.tools.lock().unwrap_or_else(|e| e.into_inner());
+            tools.entry(tool.to_string()).or_default().clone()
+        };
+
+        stats.calls[outcome as usize].fetch_add(1, Ordering::Relaxed);
+        let seconds = elapsed.as_secs_f64();
+        let bucket = TOOL_DURATION_BUCKETS
+            .iter()
+            .position(|bound| seconds <= *bound)
+            .unwrap_or(TOOL_DURATION_BUCKETS.len());
+        stats.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        stats
+            .sum_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Calls of `tool` that ended with `outcome` since the server started
+    pub fn calls(&self, tool: &str, outcome: ToolOutcome) -> u64 {
+        let tools = self.tools.lock().unwrap_or_else(|e| e.into_inner());
+        tools.get(tool).map_or(0, |stats| {
+            stats.calls[outcome as usize].load(Ordering::Relaxed)
+        })
+    }
+
+    /// Counts and durations by tool name, leaving out tools never called
+    pub fn snapshot(&self) -> BTreeMap<String, ToolCallStats> {
+        let tools = self.tools.lock().unwrap_or_else(|e| e.into_inner());
+        tools
+            .iter()
+            .map(|(tool, stats)| (tool.clone(), ToolCallStats::from_stats(stats)))
+            .collect()
+    }
+}
+
+/// Snapshot of one tool's call counter and duration histogram
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ToolCallStats {
+    /// Calls by outcome (`ok`, `error` or `timeout`), leaving out outcomes without calls
+    pub calls: BTreeMap<String, u64>,
+    /// Number of calls timed
+    pub duration_count: u64,
+    /// Total time spent in calls, in seconds
+    pub duration_sum_seconds: f64,
+    /// Calls that took at most `le` seconds, one entry per bucket bound
+    pub duration_buckets: Vec<DurationBucket>,
+}
+
+/// Cumulative histogram bucket
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct DurationBucket {
+    pub le: f64,
+    pub count: u64,
+}
+
+impl ToolCallStats {
+    fn from_stats(stats: &ToolStats) -> Self {
+        let calls = ToolOutcome::ALL
+            .iter()
+            .map(|outcome| {
+                let count = stats.calls[*outcome as usize].load(Ordering::Relaxed);
+                (outcome.name().to_string(), count)
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect();
+
+        let mut cumulative = 0;
+        let duration_buckets = TOOL_DURATION_BUCKETS
+            .iter()
+            .zip(&stats.buckets)
+            .map(|(bound, count)| {
+                cumulative += count.load(Ordering::Relaxed);
+                DurationBucket {
+                    le: *bound,
+                    count: cumulative,
+                }
+            })
+            .collect();
+        let duration_count = stats
+            .buckets
+            .iter()
+            .map(|count| count.load(Ordering::Relaxed))
+            .sum();
+
+        Self {
+            calls,
+            duration_count,
+            duration_sum_seconds: stats.sum_micros.load(Ordering::Relaxed) as f64 / 1e6,
+            duration_buckets,
+        }
+    }
+}
+
+/// Response returned when a tool call exceeds its timeout
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ToolTimeoutOutput {
+    pub error: String,
+    pub tool: String,
+    pub timeout_secs: u64,
+    /// Background caching task that is still running, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    pub hint: String,
+}
+
+impl ToolTimeoutOutput {
+    /// Create a timeout response for the given tool
+    pub fn new(tool: impl Into<String>, timeout: Duration, task_id: Option<String>) -> Self {
+        let tool = tool.into();
+        let hint = match &task_id {
+            Some(task_id) => format!(
+                "The operation is still running in the background. Use cache_operations({{task_id: \"{task_id}\"}}) to check its progress instead of retrying."
+            ),
+            None => "The operation may still be running. Use cache_operations to check active caching tasks before retrying.".to_string(),
+        };
+
+        Self {
+            error: format!(
+                "Operation '{tool}' timed out after {} seconds",
+                timeout.as_secs()
+            ),
+            tool,
+            timeout_secs: timeout.as_secs(),
+            task_id,
+            hint,
+        }
+    }
+
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// Run a tool future, returning a [`ToolTimeoutOutput`] response if it exceeds `timeout`
+///
+/// The future resolves to the tool's response, as `Err` if it reports an error.
+/// The call is recorded in `metrics` with its duration and outcome.
+pub async fn run_with_timeout<F>(
+    tool: &str,
+    timeout: Duration,
+    metrics: &ToolMetrics,
+    future: F,
+) -> String
+where
+    F: Future<Output = Result<String, String>>,
+{
+    let started = Instant::now();
+    match tokio::time::timeout(timeout, future).await {
+        Ok(response) => {
+            let elapsed = started.elapsed();
+            tracing::debug!("Tool '{}' completed in {:?}", tool, elapsed);
+            metrics.record(tool, elapsed, ToolOutcome::of_response(&response));
+            response.unwrap_or_else(|error| error)
+        }
+        Err(_) => {
+            tracing::warn!("Tool '{}' timed out after {:?}", tool, timeout);
+            metrics.record(tool, started.elapsed(), ToolOutcome::Timeout);
+            ToolTimeoutOutput::new(tool, timeout, None).to_json()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fast_query_returns_response() {
+        let metrics = ToolMetrics::default();
+        let response = run_with_timeout("search_items", Duration::from_secs(5), &metrics, async {
+            Ok("ok".to_string())
+        })
+        .await;
+        assert_eq!(response, "ok");
+        assert_eq!(metrics.calls("search_items", ToolOutcome::Ok), 1);
+    }
+
+    #[tokio::test]
+    async fn test_slow_query_returns_timeout_response() {
+        let metrics = ToolMetrics::default();
+        let response =
+            run_with_timeout("search_items", Duration::from_millis(10), &metrics, async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok("too late".to_string())
+            })
+            .await;
+        assert_eq!(metrics.calls("search_items", ToolOutcome::Timeout), 1);
+        assert_eq!(metrics.calls("search_items", ToolOutcome::Ok), 0);
+
+        let output: ToolTimeoutOutput = serde_json::from_str(&response).unwrap();
+        assert_eq!(output.tool, "search_items");
+        assert!(output.error.contains("timed out"));
+        assert!(output.task_id.is_none());
+    }
+
+    #[test]
+    fn test_timeout_output_includes_task_id() {
+        let output = ToolTimeoutOutput::new(
+            "cache_crate",
+            Duration::from_secs(300),
+            Some("abc-123".to_string()),
+        );
+        assert_eq!(output.timeout_secs, 300);
+        assert_eq!(output.task_id.as_deref(), Some("abc-123"));
+        assert!(output.hint.contains("abc-123"));
+
+        let json = output.to_json();
+        assert!(json.contains("\"task_id\":\"abc-123\""));
+    }
+
+    #[tokio::test]
+    async fn test_error_response_counted_as_error() {
+        let metrics = ToolMetrics::default();
+        let response = run_with_timeout("cache_crate", Duration::from_secs(5), &metrics, async {
+            Err("# Error\n\nMissing required parameter 'version'".to_string())
+        })
+        .await;
+        assert!(response.starts_with("# Error"));
+        assert_eq!(metrics.calls("cache_crate", ToolOutcome::Error), 1);
+        assert_eq!(metrics.calls("cache_crate", ToolOutcome::Ok), 0);
+    }
+
+    #[test]
+    fn test_tool_metrics_snapshot() {
+        let metrics = ToolMetrics::default();
+        assert!(metrics.snapshot().is_empty());
+
+        let shared = metrics.clone();
+        shared.record("search_items", Duration::from_millis(2), ToolOutcome::Ok);
+        shared.record("search_items", Duration::from_millis(200), ToolOutcome::Ok);
+        metrics.record(
+            "search_items",
+            Duration::from_secs(400),
+            ToolOutcome::Timeout,
+        );
+        metrics.record(
+            "list_crate_items",
+            Duration::from_millis(20),
+            ToolOutcome::Error,
+        );
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(
+            snapshot.keys().collect::<Vec<_>>(),
+            ["list_crate_items", "search_items"]
+        );
+
+        let search = &snapshot["search_items"];
+        assert_eq!(
+            search.calls,
+            [("ok".to_string(), 2), ("timeout".to_string(), 1)]
+                .into_iter()
+                .collect()
+        );
+        assert_eq!(search.duration_count, 3);
+        assert!((search.duration_sum_seconds - 400.202).abs() < 1e-6);
+
+        // Buckets are cumulative, the slowest call is above every bound
+        let counts: Vec<(f64, u64)> = search
+            .duration_buckets
+            .iter()
+            .map(|bucket| (bucket.le, bucket.count))
+            .collect();
+        assert_eq!(counts.len(), TOOL_DURATION_BUCKETS.len());
+        assert_eq!(counts[0], (0.005, 1));
+        assert_eq!(counts[3], (0.1, 1));
+        assert_eq!(counts[4], (0.5, 2));
+        assert_eq!(counts[9], (300.0, 2));
+
+        assert_eq!(
+            snapshot["list_crate_items"].calls,
+            [("error".to_string(), 1)].into_iter().collect()
+        );
+    }
+}
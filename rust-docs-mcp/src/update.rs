@@ -5,6 +5,7 @@
 
 use crate::doctor;
 use anyhow::{Context, Result};
+use rust_docs_mcp::cache::registry::Registry;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -13,6 +14,8 @@ pub async fn update_executable(
     target_dir: Option<PathBuf>,
     repo_url: Option<String>,
     branch: Option<String>,
+    docgen_toolchain: &str,
+    registry: &Registry,
 ) -> Result<()> {
     // Configuration
     let repo_url = repo_url.unwrap_or_else(|| {
@@ -108,7 +111,7 @@ pub async fn update_executable(
     println!("  rust-docs-mcp --help         # Show help");
 
     // Run doctor command to verify the update
-    doctor::run_and_print_diagnostics().await?;
+    doctor::run_and_print_diagnostics(docgen_toolchain, registry).await?;
 
     Ok(())
 }
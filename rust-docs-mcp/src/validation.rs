@@ -0,0 +1,239 @@
+//! Up-front validation of tool parameters
+//!
+//! Parameters are checked before a tool runs, so a malformed crate name or a
+//! negative offset is reported against the field it came from instead of
+//! failing deep in the cache or docs code with a confusing error. Checks are
+//! keyed by field name on the serialized parameters, the same way the access
+//! log picks out the crate and version, so every tool gets them for free.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::cache::downloader::CrateDownloader;
+use crate::cache::member_utils::validate_member_path;
+
+/// Largest page size accepted by the `limit` parameter
+pub const MAX_LIMIT: i64 = 10_000;
+
+/// Longest crate name accepted by crates.io
+const MAX_CRATE_NAME_LEN: usize = 64;
+
+/// Response returned when a tool parameter is invalid
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ValidationErrorOutput {
+    pub error: String,
+    /// Name of the offending parameter, e.g. `crate_name` or `queries[1].version`
+    pub field: String,
+}
+
+impl ValidationErrorOutput {
+    /// Create an error for `field`
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        let field = field.into();
+        Self {
+            error: format!("Invalid parameter '{field}': {}", message.into()),
+            field,
+        }
+    }
+
+    /// Convert to JSON string for MCP response
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .unwrap_or_else(|_| r#"{"error":"Failed to serialize response"}"#.to_string())
+    }
+}
+
+/// Check a crate name against the crates.io naming rules
+pub fn validate_crate_name(name: &str) -> Result<(), String> {
+    let Some(first) = name.chars().next() else {
+        return Err("crate name must not be empty".to_string());
+    };
+    if name.len() > MAX_CRATE_NAME_LEN {
+        return Err(format!(
+            "crate name '{name}' is longer than {MAX_CRATE_NAME_LEN} characters"
+        ));
+    }
+    if !first.is_ascii_alphabetic() {
+        return Err(format!(
+            "crate name '{name}' must start with an ASCII letter"
+        ));
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && *c != '-' && *c != '_')
+    {
+        return Err(format!(
+            "crate name '{name}' contains '{c}'; only ASCII letters, digits, '-' and '_' are allowed"
+        ));
+    }
+    Ok(())
+}
+
+/// Check that a version is a semver version or, for crates cached from git, a branch or tag name
+pub fn validate_version(version: &str) -> Result<(), String> {
+    if version.is_empty() {
+        return Err("version must not be empty".to_string());
+    }
+    if semver::Version::parse(version).is_ok() || CrateDownloader::is_valid_git_ref(version) {
+        return Ok(());
+    }
+    Err(format!(
+        "'{version}' is neither a semver version (e.g. '1.0.215') nor a git branch or tag name"
+    ))
+}
+
+/// Validate the parameters of a tool call, reporting the first invalid field
+pub fn validate_params(params: &impl Serialize) -> Result<(), ValidationErrorOutput> {
+    match serde_json::to_value(params) {
+        Ok(Value::Object(fields)) => validate_fields(&fields, ""),
+        _ => Ok(()),
+    }
+}
+
+fn validate_fields(fields: &Map<String, Value>, prefix: &str) -> Result<(), ValidationErrorOutput> {
+    let error = |name: &str, message: String| {
+        ValidationErrorOutput::new(format!("{prefix}{name}"), message)
+    };
+    // cache_crate reports a missing name itself, since local crates may leave it to Cargo.toml
+    let name_optional = fields.contains_key("source_type");
+
+    for (name, value) in fields {
+        match (name.as_str(), value) {
+            ("crate_name", Value::String(crate_name)) => {
+                if !(crate_name.is_empty() && name_optional) {
+                    validate_crate_name(crate_name).map_err(|e| error(name, e))?;
+                }
+            }
+            ("version" | "version_a" | "version_b", Value::String(version)) => {
+                validate_version(version).map_err(|e| error(name, e))?;
+            }
+            ("offset", Value::Number(number)) => {
+                if number.as_i64().is_some_and(|n| n < 0) {
+                    return Err(error(name, format!("must not be negative, got {number}")));
+                }
+            }
+            ("limit", Value::Number(number)) => {
+                if number
+                    .as_i64()
+                    .is_some_and(|n| !(0..=MAX_LIMIT).contains(&n))
+                    || number.as_u64().is_some_and(|n| n > MAX_LIMIT as u64)
+                {
+                    return Err(error(
+                        name,
+                        format!("must be between 0 and {MAX_LIMIT}, got {number}"),
+                    ));
+                }
+            }
+            ("member", Value::String(member)) => {
+                validate_member_path(member).map_err(|e| error(name, e.to_string()))?;
+            }
+            ("members", Value::Array(members)) => {
+                for (index, member) in members.iter().enumerate() {
+                    if let Some(member) = member.as_str() {
+                        validate_member_path(member)
+                            .map_err(|e| error(&format!("{name}[{index}]"), e.to_string()))?;
+                    }
+                }
+            }
+            // Batched queries, as taken by get_crates_metadata
+            ("queries", Value::Array(queries)) => {
+                for (index, query) in queries.iter().enumerate() {
+                    if let Some(query) = query.as_object() {
+                        validate_fields(query, &format!("{prefix}{name}[{index}]."))?;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn field_error(params: Value) -> Option<String> {
+        validate_params(&params).err().map(|error| error.field)
+    }
+
+    #[test]
+    fn test_validate_crate_name() {
+        assert!(validate_crate_name("serde").is_ok());
+        assert!(validate_crate_name("tokio-util").is_ok());
+        assert!(validate_crate_name("rust_docs_mcp2").is_ok());
+
+        assert!(validate_crate_name("").is_err());
+        assert!(validate_crate_name("2fast").is_err());
+        assert!(validate_crate_name("_private").is_err());
+        assert!(validate_crate_name("../etc").is_err());
+        assert!(validate_crate_name("serde json").is_err());
+        assert!(validate_crate_name("crème").is_err());
+        assert!(validate_crate_name(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_validate_version() {
+        assert!(validate_version("1.0.215").is_ok());
+        assert!(validate_version("0.1.0-alpha.1+build.5").is_ok());
+        // Crates cached from git use their branch or tag as version
+        assert!(validate_version("main").is_ok());
+        assert!(validate_version("feature/new-thing").is_ok());
+        assert!(validate_version("v1.0.0").is_ok());
+
+        assert!(validate_version("").is_err());
+        assert!(validate_version("../1.0.0").is_err());
+        assert!(validate_version("1.0 beta").is_err());
+        assert!(validate_version("/absolute").is_err());
+    }
+
+    #[test]
+    fn test_validate_params() {
+        let valid = json!({
+            "crate_name": "serde",
+            "version": "1.0.0",
+            "item_id": 42,
+            "limit": 100,
+            "offset": 0,
+            "member": "crates/core",
+        });
+        assert_eq!(field_error(valid), None);
+
+        for (params, field) in [
+            (
+                json!({"crate_name": "bad name", "version": "1.0.0"}),
+                "crate_name",
+            ),
+            (json!({"crate_name": "serde", "version": "1..0"}), "version"),
+            (json!({"crate_name": "serde", "version_b": ""}), "version_b"),
+            (json!({"limit": -5}), "limit"),
+            (json!({"limit": MAX_LIMIT + 1}), "limit"),
+            (json!({"offset": -1}), "offset"),
+            (json!({"member": "../outside"}), "member"),
+            (json!({"members": ["crates/core", "/etc"]}), "members[1]"),
+            (
+                json!({"queries": [{"crate_name": "serde", "version": "1.0.0"}, {"crate_name": "", "version": "1.0.0"}]}),
+                "queries[1].crate_name",
+            ),
+        ] {
+            assert_eq!(field_error(params).as_deref(), Some(field));
+        }
+
+        // A missing name is left to cache_crate, which knows which sources need one
+        assert_eq!(
+            field_error(json!({"crate_name": "", "source_type": "local"})),
+            None
+        );
+        assert_eq!(
+            field_error(json!({"crate_name": "bad name", "source_type": "local"})).as_deref(),
+            Some("crate_name")
+        );
+
+        let error = validate_params(&json!({"offset": -3})).unwrap_err();
+        assert_eq!(
+            error.error,
+            "Invalid parameter 'offset': must not be negative, got -3"
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Peak memory of a single-item lookup compared to a full docs load
+//!
+//! Lives in its own test binary because it installs a counting global allocator.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::Result;
+use rust_docs_mcp::docs::lookup::find_item;
+use rustdoc_types::Id;
+use tempfile::TempDir;
+
+/// Allocator that tracks the current and peak number of allocated bytes
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(allocated, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Bytes allocated at the peak of `f`, above what was allocated before it ran
+fn peak_memory<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let baseline = ALLOCATED.load(Ordering::SeqCst);
+    PEAK.store(baseline, Ordering::SeqCst);
+    let result = f();
+    (result, PEAK.load(Ordering::SeqCst) - baseline)
+}
+
+#[test]
+fn test_single_item_lookup_peak_memory() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let docs_path = temp_dir.path().join("docs.json");
+
+    // A large index whose entries are only skipped by the lookup
+    let index: serde_json::Map<String, serde_json::Value> = (0..20_000)
+        .map(|id| {
+            let entry = serde_json::json!({
+                "id": id,
+                "name": format!("item_{id}"),
+                "docs": "Lorem ipsum dolor sit amet, consectetur adipiscing elit.",
+                "links": {},
+                "attrs": ["#[inline]", "#[must_use]"],
+            });
+            (id.to_string(), entry)
+        })
+        .collect();
+    let docs = serde_json::json!({
+        "root": 0,
+        "index": index,
+        "paths": {},
+        "format_version": rustdoc_types::FORMAT_VERSION,
+    });
+    std::fs::write(&docs_path, docs.to_string())?;
+    drop(docs);
+
+    let (lookup, lookup_peak) = peak_memory(|| find_item(&docs_path, Id(u32::MAX)));
+    let lookup = lookup?;
+    assert_eq!(lookup.format_version, Some(rustdoc_types::FORMAT_VERSION));
+    assert!(lookup.item.is_none());
+
+    // The full load reads the file and parses it into a JSON value
+    let (full, full_peak) = peak_memory(|| -> Result<serde_json::Value> {
+        let json = std::fs::read_to_string(&docs_path)?;
+        Ok(serde_json::from_str(&json)?)
+    });
+    drop(full?);
+
+    println!("Peak memory: lookup {lookup_peak} bytes, full load {full_peak} bytes");
+    assert!(
+        lookup_peak * 20 < full_peak,
+        "Lookup peaked at {lookup_peak} bytes, full load at {full_peak} bytes"
+    );
+
+    Ok(())
+}
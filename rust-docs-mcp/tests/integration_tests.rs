@@ -1,34 +1,57 @@
 //! Integration tests for rust-docs-mcp caching functionality
 //!
-//! These tests verify that caching works correctly for all three sources:
+//! These tests verify that caching works correctly for all sources:
 //! - crates.io
 //! - GitHub
 //! - Local paths
+//! - Local `.crate` archives
 
 use anyhow::{Context, Result};
 use rmcp::handler::server::wrapper::Parameters;
 use rust_docs_mcp::RustDocsService;
-use rust_docs_mcp::analysis::outputs::StructureOutput;
-use rust_docs_mcp::analysis::tools::AnalyzeCrateStructureParams;
+use rust_docs_mcp::access_log::AccessLogEntry;
+use rust_docs_mcp::analysis::outputs::{
+    GetCrateEditionOutput, GetSourceTreeOutput, GetUsageExamplesOutput, ListCrateTargetsOutput,
+    StructureOutput,
+};
+use rust_docs_mcp::analysis::tools::{
+    AnalyzeCrateStructureParams, GetCrateEditionParams, GetCrateSourceTreeParams,
+    GetUsageExamplesParams, ListCrateTargetsParams,
+};
 use rust_docs_mcp::cache::outputs::{
-    CacheTaskStartedOutput, GetCratesMetadataOutput, ListCrateVersionsOutput,
+    CacheTaskStartedOutput, EnsureReadyOutput, GetCratesMetadataOutput, ListCrateVersionsOutput,
+    PruneIndexesOutput, ReindexOutput, ServerInfoOutput,
 };
+use rust_docs_mcp::cache::policy::SourcePolicy;
+use rust_docs_mcp::cache::storage::READ_ONLY_ERROR;
 use rust_docs_mcp::cache::tools::{
-    CacheCrateParams, CacheOperationsParams, CrateMetadataQuery, GetCratesMetadataParams,
-    ListCrateVersionsParams,
+    CacheCrateParams, CacheLatestParams, CacheOperationsParams, ClearAllCachesParams,
+    CrateMetadataQuery, EnsureReadyParams, GetCratesMetadataParams, ListCrateVersionsParams,
+    RemoveCrateParams,
 };
-use rust_docs_mcp::deps::outputs::GetDependenciesOutput;
-use rust_docs_mcp::deps::tools::GetDependenciesParams;
+use rust_docs_mcp::config::ServiceConfig;
+use rust_docs_mcp::deps::outputs::{DependentsInCacheOutput, GetDependenciesOutput};
+use rust_docs_mcp::deps::tools::{GetDependenciesParams, GetDependentsInCacheParams};
 use rust_docs_mcp::docs::outputs::{
-    GetItemDetailsOutput, GetItemDocsOutput, GetItemSourceOutput, ListCrateItemsOutput,
-    SearchItemsOutput, SearchItemsPreviewOutput,
+    ChangedItemsOutput, DetailedItem, DiffVisibilityOutput, DocsErrorOutput,
+    ExportDocsMarkdownOutput, ExportItemsNdjsonOutput, GetAllMethodsOutput, GetBlanketImplsOutput,
+    GetDocsForNamesOutput, GetItemDetailsOutput, GetItemDocsOutput, GetItemRawOutput,
+    GetItemSourceOutput, GetReprInfoOutput, GetSupertraitsOutput, ItemInfo, ItemPreview,
+    ListCfgAttributesOutput, ListCrateItemsOutput, SearchItemsOutput, SearchItemsPreviewOutput,
+    SupertraitEntry,
 };
 use rust_docs_mcp::docs::tools::{
-    GetItemDetailsParams, GetItemDocsParams, GetItemSourceParams, ListItemsParams,
-    SearchItemsParams, SearchItemsPreviewParams,
+    DiffVisibilityParams, ExportDocsMarkdownParams, ExportItemsNdjsonParams, GetAllMethodsParams,
+    GetBlanketImplsParams, GetChangedItemsSinceParams, GetDocsForNamesParams, GetItemDetailsParams,
+    GetItemDocsParams, GetItemRawParams, GetItemSourceParams, GetReprInfoParams,
+    GetSupertraitsParams, ListCfgAttributesParams, ListItemsParams, SearchItemsParams,
+    SearchItemsPreviewParams,
 };
-use rust_docs_mcp::search::outputs::SearchItemsFuzzyOutput;
-use rust_docs_mcp::search::tools::SearchItemsFuzzyParams;
+use rust_docs_mcp::format::ResponseFormat;
+use rust_docs_mcp::search::outputs::{FindSimilarItemsOutput, SearchItemsFuzzyOutput};
+use rust_docs_mcp::search::tools::{FindSimilarItemsParams, SearchItemsFuzzyParams};
+use rust_docs_mcp::validation::ValidationErrorOutput;
+use std::path::Path;
 use std::time::Duration;
 use tempfile::TempDir;
 
@@ -125,6 +148,7 @@ async fn wait_for_task_completion(
             status_filter: None,
             cancel: false,
             clear: false,
+            retry_failed: false,
         };
 
         let response = service.cache_operations(Parameters(params)).await;
@@ -162,6 +186,9 @@ async fn setup_test_crate(service: &RustDocsService) -> Result<()> {
         path: None,
         members: None,
         update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
     };
 
     // Start the async caching operation
@@ -180,7 +207,7 @@ async fn setup_test_crate(service: &RustDocsService) -> Result<()> {
 }
 
 /// Helper to get a test item ID from the semver crate
-async fn get_test_item_id(service: &RustDocsService) -> Result<i32> {
+async fn get_test_item_id(service: &RustDocsService) -> Result<u32> {
     let params = SearchItemsPreviewParams {
         crate_name: "semver".to_string(),
         version: SEMVER_VERSION.to_string(),
@@ -190,18 +217,85 @@ async fn get_test_item_id(service: &RustDocsService) -> Result<i32> {
         kind_filter: Some("struct".to_string()),
         path_filter: None,
         member: None,
+        target: None,
+        dedupe_by_id: None,
+        compact: None,
     };
 
     let response = service.search_items_preview(Parameters(params)).await;
     let output: SearchItemsPreviewOutput = serde_json::from_str(&response)?;
 
     if let Some(item) = output.items.first() {
-        return Ok(item.id.parse::<i32>()?);
+        return Ok(item.id.parse::<u32>()?);
     }
 
     Err(anyhow::anyhow!("Could not find test item ID in response"))
 }
 
+/// Call get_item_details and unwrap a success response
+async fn get_item_details_success(
+    service: &RustDocsService,
+    params: GetItemDetailsParams,
+) -> Result<DetailedItem> {
+    let response = service.get_item_details(Parameters(params)).await;
+    match serde_json::from_str::<GetItemDetailsOutput>(&response)? {
+        GetItemDetailsOutput::Success(item) => Ok(*item),
+        other => Err(anyhow::anyhow!("Expected success response, got: {other:?}")),
+    }
+}
+
+/// Cache the local crate `name` 0.1.0 with `lib_rs` as its source
+async fn cache_local_crate(service: &RustDocsService, name: &str, lib_rs: &str) -> Result<()> {
+    let manifest = format!(
+        r#"
+[package]
+name = "{name}"
+version = "0.1.0"
+edition = "2021"
+"#
+    );
+    cache_local_crate_with_manifest(service, name, &manifest, lib_rs).await
+}
+
+/// Cache the local crate `name` with `manifest` as its Cargo.toml and `lib_rs` as its source
+async fn cache_local_crate_with_manifest(
+    service: &RustDocsService,
+    name: &str,
+    manifest: &str,
+    lib_rs: &str,
+) -> Result<()> {
+    let test_crate_dir = TempDir::new()?;
+    std::fs::write(test_crate_dir.path().join("Cargo.toml"), manifest)?;
+    std::fs::create_dir(test_crate_dir.path().join("src"))?;
+    std::fs::write(test_crate_dir.path().join("src/lib.rs"), lib_rs)?;
+    cache_local_crate_at(service, name, test_crate_dir.path()).await
+}
+
+/// Cache the local crate `name` from the crate directory `path`
+async fn cache_local_crate_at(service: &RustDocsService, name: &str, path: &Path) -> Result<()> {
+    let params = CacheCrateParams {
+        crate_name: name.to_string(),
+        source_type: "local".to_string(),
+        // Read from the manifest
+        version: None,
+        github_url: None,
+        branch: None,
+        tag: None,
+        path: Some(path.to_str().unwrap().to_string()),
+        members: None,
+        update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
+    };
+    let response = service.cache_crate(Parameters(params)).await;
+    let task_output = parse_cache_task_started(&response)?;
+    match wait_for_task_completion(service, &task_output.task_id, TEST_TIMEOUT).await? {
+        TaskResult::Success => Ok(()),
+        result => Err(anyhow::anyhow!("Failed to cache {name}: {result:?}")),
+    }
+}
+
 #[tokio::test]
 async fn test_cache_from_crates_io() -> Result<()> {
     let (service, _temp_dir) = create_test_service()?;
@@ -217,6 +311,9 @@ async fn test_cache_from_crates_io() -> Result<()> {
         path: None,
         members: None,
         update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
     };
 
     // Start async caching operation
@@ -252,6 +349,39 @@ async fn test_cache_from_crates_io() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_cache_latest() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    let params = CacheLatestParams {
+        crate_name: "semver".to_string(),
+        allow_prerelease: None,
+        members: None,
+        update: None,
+        target: None,
+    };
+
+    let response = service.cache_latest(Parameters(params)).await;
+    let task_output = parse_cache_task_started(&response)?;
+
+    assert_eq!(task_output.crate_name, "semver");
+    let version = semver::Version::parse(&task_output.version)?;
+    assert!(version.pre.is_empty(), "Should skip pre-releases");
+    assert!(
+        version >= semver::Version::parse("1.0.26")?,
+        "Should resolve a recent release, got {version}"
+    );
+    assert!(task_output.message.contains(&task_output.version));
+
+    let result = wait_for_task_completion(&service, &task_output.task_id, TEST_TIMEOUT).await?;
+    assert!(
+        matches!(result, TaskResult::Success),
+        "Expected success, got: {result:?}"
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_cache_from_github() -> Result<()> {
     let (service, _temp_dir) = create_test_service()?;
@@ -267,6 +397,9 @@ async fn test_cache_from_github() -> Result<()> {
         path: None,
         members: None,
         update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
     };
 
     let response = service.cache_crate(Parameters(params)).await;
@@ -319,6 +452,9 @@ async fn test_cache_from_github_branch() -> Result<()> {
         path: None,
         members: None,
         update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
     };
 
     let response = service.cache_crate(Parameters(params)).await;
@@ -380,6 +516,9 @@ edition = "2021"
         path: Some(test_crate_dir.path().to_str().unwrap().to_string()),
         members: None,
         update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
     };
 
     let response = service.cache_crate(Parameters(params)).await;
@@ -407,142 +546,144 @@ edition = "2021"
 }
 
 #[tokio::test]
-async fn test_workspace_crate_detection() -> Result<()> {
+async fn test_identical_cache_requests_share_a_task() -> Result<()> {
     let (service, _temp_dir) = create_test_service()?;
 
-    // Create a workspace crate
-    let workspace_dir = TempDir::new()?;
-    let workspace_toml = workspace_dir.path().join("Cargo.toml");
+    let test_crate_dir = TempDir::new()?;
     std::fs::write(
-        &workspace_toml,
+        test_crate_dir.path().join("Cargo.toml"),
         r#"
-[workspace]
-members = ["crate-a", "crate-b"]
-resolver = "2"
-
-[workspace.dependencies]
-serde = "1.0"
-    "#,
-    )?;
-
-    // Create member crates
-    for member in &["crate-a", "crate-b"] {
-        let member_dir = workspace_dir.path().join(member);
-        std::fs::create_dir_all(&member_dir)?;
-        std::fs::write(
-            member_dir.join("Cargo.toml"),
-            format!(
-                r#"
 [package]
-name = "{member}"
+name = "test-idempotent"
 version = "0.1.0"
 edition = "2021"
+    "#,
+    )?;
+    let src_dir = test_crate_dir.path().join("src");
+    std::fs::create_dir(&src_dir)?;
+    std::fs::write(src_dir.join("lib.rs"), "pub fn test() {}")?;
 
-[dependencies]
-serde = {{ workspace = true }}
-        "#
-            ),
-        )?;
-
-        let src_dir = member_dir.join("src");
-        std::fs::create_dir(&src_dir)?;
-        std::fs::write(src_dir.join("lib.rs"), format!("//! {member} crate"))?;
-    }
-
-    // Cache the workspace - should detect it's a workspace
-    let params = CacheCrateParams {
-        crate_name: "test-workspace".to_string(),
+    let params = || CacheCrateParams {
+        crate_name: "test-idempotent".to_string(),
         source_type: "local".to_string(),
         version: Some("0.1.0".to_string()),
         github_url: None,
         branch: None,
         tag: None,
-        path: Some(workspace_dir.path().to_str().unwrap().to_string()),
-        members: None, // Should detect workspace and return member list
+        path: Some(test_crate_dir.path().to_str().unwrap().to_string()),
+        members: None,
         update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
     };
 
-    let response = service.cache_crate(Parameters(params)).await;
-
-    // Parse async task response
-    let task_output = parse_cache_task_started(&response)?;
-
-    // Wait for completion - should fail with workspace detection
-    let result = wait_for_task_completion(&service, &task_output.task_id, TEST_TIMEOUT).await?;
+    // Two identical requests fired together start a single task
+    let (first, second) = tokio::join!(
+        service.cache_crate(Parameters(params())),
+        service.cache_crate(Parameters(params()))
+    );
+    let first = parse_cache_task_started(&first)?;
+    let second = parse_cache_task_started(&second)?;
+    assert_eq!(first.task_id, second.task_id);
+    assert!(first.existing_task != second.existing_task);
 
-    // Response should indicate workspace detection
+    let result = wait_for_task_completion(&service, &first.task_id, TEST_TIMEOUT).await?;
     assert!(
-        matches!(result, TaskResult::WorkspaceDetected(_)),
-        "Response should indicate workspace detection: {result:?}"
+        matches!(result, TaskResult::Success),
+        "Failed to cache from local path: {result:?}"
     );
 
-    // Verify the response mentions workspace detection
-    if let TaskResult::WorkspaceDetected(msg) = result {
-        assert!(
-            msg.contains("Workspace detected"),
-            "Should mention workspace: {msg}"
-        );
-    }
+    // Once the task has finished, the same request starts a new one
+    let third = parse_cache_task_started(&service.cache_crate(Parameters(params())).await)?;
+    assert_ne!(third.task_id, first.task_id);
+    assert!(!third.existing_task);
+    wait_for_task_completion(&service, &third.task_id, TEST_TIMEOUT).await?;
 
     Ok(())
 }
 
 #[tokio::test]
-async fn test_cache_update() -> Result<()> {
+async fn test_cache_local_crate_without_name() -> Result<()> {
     let (service, _temp_dir) = create_test_service()?;
 
-    // Cache initially
-    let params1 = CacheCrateParams {
-        crate_name: "once_cell".to_string(),
-        source_type: "cratesio".to_string(),
-        version: Some("1.17.0".to_string()),
+    let test_crate_dir = TempDir::new()?;
+    std::fs::write(
+        test_crate_dir.path().join("Cargo.toml"),
+        r#"
+[package]
+name = "test-unnamed"
+version = "0.2.0"
+edition = "2021"
+    "#,
+    )?;
+    std::fs::create_dir(test_crate_dir.path().join("src"))?;
+    std::fs::write(
+        test_crate_dir.path().join("src/lib.rs"),
+        "//! Crate cached without a name\npub fn test() {}",
+    )?;
+    let path = test_crate_dir.path().to_str().unwrap().to_string();
+
+    // A name that does not match the manifest is rejected
+    let params = CacheCrateParams {
+        crate_name: "wrong-name".to_string(),
+        source_type: "local".to_string(),
+        version: None,
         github_url: None,
         branch: None,
         tag: None,
-        path: None,
+        path: Some(path.clone()),
         members: None,
         update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
     };
-
-    let response1 = service.cache_crate(Parameters(params1)).await;
-    let task1 = parse_cache_task_started(&response1)?;
-    let result1 = wait_for_task_completion(&service, &task1.task_id, TEST_TIMEOUT).await?;
+    let response = service.cache_crate(Parameters(params)).await;
     assert!(
-        matches!(result1, TaskResult::Success),
-        "Initial cache failed: {result1:?}"
+        response.contains("Name mismatch"),
+        "Expected name mismatch error: {response}"
     );
 
-    // Cache again with update flag
-    let params2 = CacheCrateParams {
-        crate_name: "once_cell".to_string(),
-        source_type: "cratesio".to_string(),
-        version: Some("1.17.0".to_string()),
+    // Without a name, the name and version are read from Cargo.toml
+    let params = CacheCrateParams {
+        crate_name: String::new(),
+        source_type: "local".to_string(),
+        version: None,
         github_url: None,
         branch: None,
         tag: None,
-        path: None,
+        path: Some(path),
         members: None,
-        update: Some(true),
+        update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
     };
+    let response = service.cache_crate(Parameters(params)).await;
+    let task_output = parse_cache_task_started(&response)?;
+    assert_eq!(task_output.crate_name, "test-unnamed");
+    assert_eq!(task_output.version, "0.2.0");
 
-    let response2 = service.cache_crate(Parameters(params2)).await;
-    let task2 = parse_cache_task_started(&response2)?;
-    let result2 = wait_for_task_completion(&service, &task2.task_id, TEST_TIMEOUT).await?;
+    let result = wait_for_task_completion(&service, &task_output.task_id, TEST_TIMEOUT).await?;
     assert!(
-        matches!(result2, TaskResult::Success),
-        "Update cache failed: {result2:?}"
+        matches!(result, TaskResult::Success),
+        "Failed to cache local crate without a name: {result:?}"
     );
 
-    Ok(())
-}
-
-#[tokio::test]
-async fn test_invalid_inputs() -> Result<()> {
-    let (service, _temp_dir) = create_test_service()?;
+    let versions_response = service
+        .list_crate_versions(Parameters(ListCrateVersionsParams {
+            crate_name: "test-unnamed".to_string(),
+        }))
+        .await;
+    assert!(
+        versions_response.contains("0.2.0"),
+        "Version not found: {versions_response}"
+    );
 
-    // Test non-existent crate from crates.io
+    // Other sources still need a name
     let params = CacheCrateParams {
-        crate_name: "this-crate-definitely-does-not-exist-123456".to_string(),
+        crate_name: String::new(),
         source_type: "cratesio".to_string(),
         version: Some("1.0.0".to_string()),
         github_url: None,
@@ -551,193 +692,238 @@ async fn test_invalid_inputs() -> Result<()> {
         path: None,
         members: None,
         update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
     };
-
     let response = service.cache_crate(Parameters(params)).await;
-
-    // crates.io returns 403 Forbidden for non-existent crates - this will be async
-    let task = parse_cache_task_started(&response)?;
-    let result = wait_for_task_completion(&service, &task.task_id, TEST_TIMEOUT).await?;
     assert!(
-        matches!(result, TaskResult::Failed(_)),
-        "Expected error response, got: {result:?}"
+        response.contains("Missing required parameter 'crate_name'"),
+        "Expected missing name error: {response}"
     );
 
-    // Test invalid GitHub URL - this might fail synchronously or asynchronously
-    let params = CacheCrateParams {
-        crate_name: "invalid".to_string(),
-        source_type: "github".to_string(),
-        version: None,
-        github_url: Some("not-a-valid-url".to_string()),
-        branch: None,
-        tag: Some("v1.0.0".to_string()),
-        path: None,
-        members: None,
-        update: None,
-    };
+    Ok(())
+}
 
-    let response = service.cache_crate(Parameters(params)).await;
-    // Try parsing as error first, then as async task
-    if response.contains("# Error") {
-        // Synchronous error
-        assert!(
-            response.contains("Error"),
-            "Expected error in response: {response}"
-        );
-    } else {
-        // Async task
-        let task = parse_cache_task_started(&response)?;
-        let result = wait_for_task_completion(&service, &task.task_id, TEST_TIMEOUT).await?;
-        assert!(
-            matches!(result, TaskResult::Failed(_)),
-            "Expected error response, got: {result:?}"
-        );
-    }
+#[tokio::test]
+async fn test_cache_with_module_scope() -> Result<()> {
+    let (service, temp_dir) = create_test_service()?;
+
+    let test_crate_dir = TempDir::new()?;
+    std::fs::write(
+        test_crate_dir.path().join("Cargo.toml"),
+        r#"
+[package]
+name = "test-scope"
+version = "0.1.0"
+edition = "2021"
+    "#,
+    )?;
+    let src_dir = test_crate_dir.path().join("src");
+    std::fs::create_dir(&src_dir)?;
+    std::fs::write(
+        src_dir.join("lib.rs"),
+        r#"
+pub mod wanted {
+    pub struct Kept;
+    pub fn make() -> crate::shared::Dependency { crate::shared::Dependency }
+}
+pub mod unwanted {
+    pub struct Dropped;
+}
+pub mod shared {
+    pub struct Dependency;
+}
+"#,
+    )?;
 
-    // Test non-existent local path - this will fail synchronously
     let params = CacheCrateParams {
-        crate_name: "invalid".to_string(),
+        crate_name: "test-scope".to_string(),
         source_type: "local".to_string(),
-        version: Some("1.0.0".to_string()),
+        version: Some("0.1.0".to_string()),
         github_url: None,
         branch: None,
         tag: None,
-        path: Some("/this/path/does/not/exist".to_string()),
+        path: Some(test_crate_dir.path().to_str().unwrap().to_string()),
         members: None,
         update: None,
+        target: None,
+        scope: Some(vec!["wanted".to_string()]),
+        keep_full_docs: Some(true),
     };
-
     let response = service.cache_crate(Parameters(params)).await;
-    // Local path validation happens synchronously before spawning
+    let task_output = parse_cache_task_started(&response)?;
+    let result = wait_for_task_completion(&service, &task_output.task_id, TEST_TIMEOUT).await?;
     assert!(
-        response.contains("Error") || response.contains("does not exist"),
-        "Expected error for non-existent path: {response}"
+        matches!(result, TaskResult::Success),
+        "Failed to cache with scope: {result:?}"
+    );
+
+    let params = ListItemsParams {
+        crate_name: "test-scope".to_string(),
+        version: "0.1.0".to_string(),
+        kind_filter: None,
+        limit: None,
+        offset: None,
+        member: None,
+        include_private: None,
+        include_hidden: None,
+        target: None,
+        group_by_module: None,
+        max_depth: None,
+    };
+    let response = service.list_crate_items(Parameters(params)).await;
+    let output: ListCrateItemsOutput = serde_json::from_str(&response)?;
+    let names: Vec<&str> = output.items.iter().map(|item| item.name.as_str()).collect();
+
+    // The scoped module and the type its signature uses are kept, the rest is dropped
+    assert!(names.contains(&"Kept"), "Missing scoped item: {names:?}");
+    assert!(names.contains(&"make"), "Missing scoped item: {names:?}");
+    assert!(
+        names.contains(&"Dependency"),
+        "Missing dependency: {names:?}"
+    );
+    assert!(
+        !names.contains(&"Dropped"),
+        "Out-of-scope item kept: {names:?}"
     );
 
+    // The unfiltered docs were kept alongside
+    let full_docs = temp_dir
+        .path()
+        .join("crates/test-scope/0.1.0/docs.full.json");
+    let full_docs = std::fs::read_to_string(full_docs)?;
+    assert!(full_docs.contains("Dropped"));
+
     Ok(())
 }
 
 #[tokio::test]
-async fn test_concurrent_caching() -> Result<()> {
+async fn test_cache_from_crate_archive() -> Result<()> {
     let (service, _temp_dir) = create_test_service()?;
-    let service = std::sync::Arc::new(service);
-
-    // Define test crates with expected metadata
-    let test_crates = vec![
-        ("semver", "1.0.0"),
-        ("once_cell", "1.0.0"),
-        ("regex", "1.11.1"), // Use the latest version compatible with nightly toolchain
-    ];
-
-    // Start caching multiple crates concurrently
-    let mut task_ids = vec![];
-
-    for (name, version) in &test_crates {
-        let params = CacheCrateParams {
-            crate_name: name.to_string(),
-            source_type: "cratesio".to_string(),
-            version: Some(version.to_string()),
-            github_url: None,
-            branch: None,
-            tag: None,
-            path: None,
-            members: None,
-            update: None,
-        };
-        let start = std::time::Instant::now();
-        let response = service.cache_crate(Parameters(params)).await;
-        let duration = start.elapsed();
-        println!("Started caching {name} {version} in {duration:?}");
-
-        let task = parse_cache_task_started(&response)?;
-        task_ids.push((name.to_string(), version.to_string(), task.task_id));
-    }
-
-    // Wait for all tasks to complete
-    let mut results = vec![];
-    for (name, version, task_id) in task_ids {
-        let result = wait_for_task_completion(&service, &task_id, TEST_TIMEOUT).await?;
-        println!("Completed caching {name} {version}");
 
-        if !matches!(result, TaskResult::Success) {
-            eprintln!("Concurrent cache failed for {name}: {result:?}");
-        }
-        results.push((name.clone(), version.clone(), result));
+    // Build a .crate tarball by hand, as found in a vendor directory
+    let archive_dir = TempDir::new()?;
+    let archive_path = archive_dir.path().join("test-archive-0.3.0.crate");
+    let encoder = flate2::write::GzEncoder::new(
+        std::fs::File::create(&archive_path)?,
+        flate2::Compression::default(),
+    );
+    let mut builder = tar::Builder::new(encoder);
+    for (name, content) in [
+        (
+            "Cargo.toml",
+            "[package]\nname = \"test-archive\"\nversion = \"0.3.0\"\nedition = \"2021\"\n",
+        ),
+        (
+            "src/lib.rs",
+            "//! Test archived crate\n\n/// Greets from the archive\npub fn from_archive() {}\n",
+        ),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(
+            &mut header,
+            format!("test-archive-0.3.0/{name}"),
+            content.as_bytes(),
+        )?;
     }
+    builder.into_inner()?.finish()?;
 
-    // Verify all operations succeeded
-    for (name, version, result) in &results {
-        assert!(
-            matches!(result, TaskResult::Success),
-            "Failed to cache {name} {version}: {result:?}"
-        );
-    }
+    // The version is read from the archive's Cargo.toml
+    let params = CacheCrateParams {
+        crate_name: "test-archive".to_string(),
+        source_type: "archive".to_string(),
+        version: None,
+        github_url: None,
+        branch: None,
+        tag: None,
+        path: Some(archive_path.to_str().unwrap().to_string()),
+        members: None,
+        update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
+    };
 
-    // Verify cache consistency - all crates should be present
-    let cached_crates_response = service.list_cached_crates().await;
-    for (name, version) in &test_crates {
-        assert!(
-            cached_crates_response.contains(name),
-            "{name} not found in cache listing"
-        );
+    let response = service.cache_crate(Parameters(params)).await;
+    let task_output = parse_cache_task_started(&response)?;
+    assert_eq!(task_output.version, "0.3.0");
 
-        // Also verify specific version is cached
-        let list_params = ListCrateVersionsParams {
-            crate_name: name.to_string(),
-        };
-        let versions_response = service.list_crate_versions(Parameters(list_params)).await;
-        assert!(
-            versions_response.contains(version),
-            "Version {version} of {name} not found in cache"
-        );
-    }
+    let result = wait_for_task_completion(&service, &task_output.task_id, TEST_TIMEOUT).await?;
+    assert!(
+        matches!(result, TaskResult::Success),
+        "Failed to cache from crate archive: {result:?}"
+    );
 
-    // Verify no corruption by attempting to re-cache (should be idempotent)
-    // This would fail if the cache was corrupted during concurrent access
-    for (name, version) in &test_crates {
-        let params = CacheCrateParams {
-            crate_name: name.to_string(),
-            source_type: "cratesio".to_string(),
-            version: Some(version.to_string()),
-            github_url: None,
-            branch: None,
-            tag: None,
-            path: None,
-            members: None,
-            update: Some(false), // Should not re-download if already cached
-        };
-        let response = service.cache_crate(Parameters(params)).await;
-        let task = parse_cache_task_started(&response)?;
-        let result = wait_for_task_completion(&service, &task.task_id, TEST_TIMEOUT).await?;
+    let search_params = SearchItemsPreviewParams {
+        crate_name: "test-archive".to_string(),
+        version: "0.3.0".to_string(),
+        pattern: "from_archive".to_string(),
+        limit: None,
+        offset: None,
+        kind_filter: None,
+        path_filter: None,
+        member: None,
+        target: None,
+        dedupe_by_id: None,
+        compact: None,
+    };
+    let search_response = service
+        .search_items_preview(Parameters(search_params))
+        .await;
+    assert!(
+        search_response.contains("from_archive"),
+        "Archived item not found: {search_response}"
+    );
 
-        // Should complete successfully even if already cached
-        assert!(
-            matches!(result, TaskResult::Success),
-            "Cache integrity check failed for {name} {version}: {result:?}"
-        );
-    }
+    // A version that does not match the archive is rejected up front
+    let params = CacheCrateParams {
+        crate_name: "test-archive".to_string(),
+        source_type: "archive".to_string(),
+        version: Some("1.0.0".to_string()),
+        github_url: None,
+        branch: None,
+        tag: None,
+        path: Some(archive_path.to_str().unwrap().to_string()),
+        members: None,
+        update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
+    };
+    let response = service.cache_crate(Parameters(params)).await;
+    assert!(
+        response.contains("Version mismatch"),
+        "Expected version mismatch error: {response}"
+    );
 
     Ok(())
 }
 
 #[tokio::test]
-async fn test_workspace_member_caching() -> Result<()> {
+async fn test_workspace_crate_detection() -> Result<()> {
     let (service, _temp_dir) = create_test_service()?;
 
-    // Create a workspace with members
+    // Create a workspace crate
     let workspace_dir = TempDir::new()?;
     let workspace_toml = workspace_dir.path().join("Cargo.toml");
     std::fs::write(
         &workspace_toml,
         r#"
 [workspace]
-members = ["lib-a", "lib-b"]
+members = ["crate-a", "crate-b"]
 resolver = "2"
+
+[workspace.dependencies]
+serde = "1.0"
     "#,
     )?;
 
     // Create member crates
-    for (member, version) in &[("lib-a", "0.1.0"), ("lib-b", "0.2.0")] {
+    for member in &["crate-a", "crate-b"] {
         let member_dir = workspace_dir.path().join(member);
         std::fs::create_dir_all(&member_dir)?;
         std::fs::write(
@@ -746,239 +932,2669 @@ resolver = "2"
                 r#"
 [package]
 name = "{member}"
-version = "{version}"
+version = "0.1.0"
 edition = "2021"
+
+[dependencies]
+serde = {{ workspace = true }}
         "#
             ),
         )?;
 
         let src_dir = member_dir.join("src");
         std::fs::create_dir(&src_dir)?;
-        std::fs::write(src_dir.join("lib.rs"), format!("//! {member} library"))?;
+        std::fs::write(src_dir.join("lib.rs"), format!("//! {member} crate"))?;
     }
 
-    // First attempt without specifying members - should get workspace detection
-    let params1 = CacheCrateParams {
-        crate_name: "my-workspace".to_string(),
+    // Cache the workspace - should detect it's a workspace
+    let params = CacheCrateParams {
+        crate_name: "test-workspace".to_string(),
         source_type: "local".to_string(),
-        version: Some("1.0.0".to_string()),
+        version: Some("0.1.0".to_string()),
         github_url: None,
         branch: None,
         tag: None,
         path: Some(workspace_dir.path().to_str().unwrap().to_string()),
-        members: None,
+        members: None, // Should detect workspace and return member list
         update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
     };
 
-    let response1 = service.cache_crate(Parameters(params1)).await;
-    let task1 = parse_cache_task_started(&response1)?;
-    let result1 = wait_for_task_completion(&service, &task1.task_id, TEST_TIMEOUT).await?;
+    let response = service.cache_crate(Parameters(params)).await;
 
-    // Should detect workspace
+    // Parse async task response
+    let task_output = parse_cache_task_started(&response)?;
+
+    // Wait for completion - should fail with workspace detection
+    let result = wait_for_task_completion(&service, &task_output.task_id, TEST_TIMEOUT).await?;
+
+    // Response should indicate workspace detection
     assert!(
-        matches!(result1, TaskResult::WorkspaceDetected(_)),
-        "Should detect workspace: {result1:?}"
+        matches!(result, TaskResult::WorkspaceDetected(_)),
+        "Response should indicate workspace detection: {result:?}"
     );
 
-    if let TaskResult::WorkspaceDetected(msg) = result1 {
+    // Verify the response mentions workspace detection
+    if let TaskResult::WorkspaceDetected(msg) = result {
         assert!(
             msg.contains("Workspace detected"),
             "Should mention workspace: {msg}"
         );
     }
 
-    // Now cache with specific members
-    let params2 = CacheCrateParams {
-        crate_name: "my-workspace".to_string(),
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_workspace_member_reports_recorded_error() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    // A workspace with one library member and one binary-only member
+    let workspace_dir = TempDir::new()?;
+    std::fs::write(
+        workspace_dir.path().join("Cargo.toml"),
+        r#"
+[workspace]
+members = ["crate-lib", "crate-bin"]
+resolver = "2"
+    "#,
+    )?;
+    for (member, file, contents) in [
+        (
+            "crate-lib",
+            "lib.rs",
+            "//! Library member\npub fn hello() {}",
+        ),
+        ("crate-bin", "main.rs", "fn main() {}"),
+    ] {
+        let member_dir = workspace_dir.path().join(member);
+        std::fs::create_dir_all(member_dir.join("src"))?;
+        std::fs::write(
+            member_dir.join("Cargo.toml"),
+            format!(
+                r#"
+[package]
+name = "{member}"
+version = "0.1.0"
+edition = "2021"
+        "#
+            ),
+        )?;
+        std::fs::write(member_dir.join("src").join(file), contents)?;
+    }
+
+    let params = CacheCrateParams {
+        crate_name: "test-partial".to_string(),
         source_type: "local".to_string(),
-        version: Some("1.0.0".to_string()),
+        version: Some("0.1.0".to_string()),
         github_url: None,
         branch: None,
         tag: None,
         path: Some(workspace_dir.path().to_str().unwrap().to_string()),
-        members: Some(vec!["lib-a".to_string(), "lib-b".to_string()]),
+        members: Some(vec!["crate-lib".to_string(), "crate-bin".to_string()]),
         update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
     };
+    let response = service.cache_crate(Parameters(params)).await;
+    let task_output = parse_cache_task_started(&response)?;
+    let result = wait_for_task_completion(&service, &task_output.task_id, TEST_TIMEOUT).await?;
+    assert!(
+        matches!(result, TaskResult::Success),
+        "Partial success should complete the task: {result:?}"
+    );
 
-    let response2 = service.cache_crate(Parameters(params2)).await;
-    let task2 = parse_cache_task_started(&response2)?;
-    let result2 = wait_for_task_completion(&service, &task2.task_id, TEST_TIMEOUT).await?;
+    let list = |member: &str| ListItemsParams {
+        crate_name: "test-partial".to_string(),
+        version: "0.1.0".to_string(),
+        kind_filter: None,
+        limit: None,
+        offset: None,
+        member: Some(member.to_string()),
+        include_private: None,
+        include_hidden: None,
+        target: None,
+        group_by_module: None,
+        max_depth: None,
+    };
 
+    // The library member is queryable
+    let response = service
+        .list_crate_items(Parameters(list("crate-lib")))
+        .await;
+    let output: ListCrateItemsOutput = serde_json::from_str(&response)?;
+    assert!(output.items.iter().any(|item| item.name == "hello"));
+
+    // The binary-only member reports the error recorded while caching
+    let response = service
+        .list_crate_items(Parameters(list("crate-bin")))
+        .await;
+    let output: DocsErrorOutput = serde_json::from_str(&response)?;
     assert!(
-        matches!(result2, TaskResult::Success),
-        "Should successfully cache workspace members: {result2:?}"
+        output
+            .error
+            .contains("Member crate-bin of test-partial-0.1.0 failed to build")
+            && output.error.contains("binary-only"),
+        "Should report the recorded failure: {}",
+        output.error
     );
 
     Ok(())
 }
 
-// ===== DOCUMENTATION TOOLS TESTS =====
-
 #[tokio::test]
-async fn test_list_crate_items() -> Result<()> {
+async fn test_cache_update() -> Result<()> {
     let (service, _temp_dir) = create_test_service()?;
-    setup_test_crate(&service).await?;
 
-    // Test basic listing
-    let params = ListItemsParams {
-        crate_name: "semver".to_string(),
+    // Cache initially
+    let params1 = CacheCrateParams {
+        crate_name: "once_cell".to_string(),
+        source_type: "cratesio".to_string(),
+        version: Some("1.17.0".to_string()),
+        github_url: None,
+        branch: None,
+        tag: None,
+        path: None,
+        members: None,
+        update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
+    };
+
+    let response1 = service.cache_crate(Parameters(params1)).await;
+    let task1 = parse_cache_task_started(&response1)?;
+    let result1 = wait_for_task_completion(&service, &task1.task_id, TEST_TIMEOUT).await?;
+    assert!(
+        matches!(result1, TaskResult::Success),
+        "Initial cache failed: {result1:?}"
+    );
+
+    // Cache again with update flag
+    let params2 = CacheCrateParams {
+        crate_name: "once_cell".to_string(),
+        source_type: "cratesio".to_string(),
+        version: Some("1.17.0".to_string()),
+        github_url: None,
+        branch: None,
+        tag: None,
+        path: None,
+        members: None,
+        update: Some(true),
+        target: None,
+        scope: None,
+        keep_full_docs: None,
+    };
+
+    let response2 = service.cache_crate(Parameters(params2)).await;
+    let task2 = parse_cache_task_started(&response2)?;
+    let result2 = wait_for_task_completion(&service, &task2.task_id, TEST_TIMEOUT).await?;
+    assert!(
+        matches!(result2, TaskResult::Success),
+        "Update cache failed: {result2:?}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cache_nonexistent_version() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    let params = CacheCrateParams {
+        crate_name: "semver".to_string(),
+        source_type: "cratesio".to_string(),
+        version: Some("999.0.0".to_string()),
+        github_url: None,
+        branch: None,
+        tag: None,
+        path: None,
+        members: None,
+        update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
+    };
+
+    let response = service.cache_crate(Parameters(params)).await;
+    let task = parse_cache_task_started(&response)?;
+    let result = wait_for_task_completion(&service, &task.task_id, TEST_TIMEOUT).await?;
+
+    // The crate exists, so the error names the missing version and the latest one
+    match result {
+        TaskResult::Failed(response) => {
+            assert!(
+                response.contains("Version '999.0.0' of crate 'semver' not found on crates.io"),
+                "Expected a version not found error, got: {response}"
+            );
+            assert!(
+                response.contains("latest version is"),
+                "Expected the latest version, got: {response}"
+            );
+        }
+        other => panic!("Expected error response, got: {other:?}"),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_inputs() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    // Test non-existent crate from crates.io
+    let params = CacheCrateParams {
+        crate_name: "this-crate-definitely-does-not-exist-123456".to_string(),
+        source_type: "cratesio".to_string(),
+        version: Some("1.0.0".to_string()),
+        github_url: None,
+        branch: None,
+        tag: None,
+        path: None,
+        members: None,
+        update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
+    };
+
+    let response = service.cache_crate(Parameters(params)).await;
+
+    // crates.io returns 403 Forbidden for non-existent crates - this will be async
+    let task = parse_cache_task_started(&response)?;
+    let result = wait_for_task_completion(&service, &task.task_id, TEST_TIMEOUT).await?;
+    match result {
+        TaskResult::Failed(response) => assert!(
+            response.contains("not found on crates.io") && !response.contains("403"),
+            "Expected a not found error, got: {response}"
+        ),
+        other => panic!("Expected error response, got: {other:?}"),
+    }
+
+    // Test invalid GitHub URL - this might fail synchronously or asynchronously
+    let params = CacheCrateParams {
+        crate_name: "invalid".to_string(),
+        source_type: "github".to_string(),
+        version: None,
+        github_url: Some("not-a-valid-url".to_string()),
+        branch: None,
+        tag: Some("v1.0.0".to_string()),
+        path: None,
+        members: None,
+        update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
+    };
+
+    let response = service.cache_crate(Parameters(params)).await;
+    // Try parsing as error first, then as async task
+    if response.contains("# Error") {
+        // Synchronous error
+        assert!(
+            response.contains("Error"),
+            "Expected error in response: {response}"
+        );
+    } else {
+        // Async task
+        let task = parse_cache_task_started(&response)?;
+        let result = wait_for_task_completion(&service, &task.task_id, TEST_TIMEOUT).await?;
+        assert!(
+            matches!(result, TaskResult::Failed(_)),
+            "Expected error response, got: {result:?}"
+        );
+    }
+
+    // Test non-existent local path - this will fail synchronously
+    let params = CacheCrateParams {
+        crate_name: "invalid".to_string(),
+        source_type: "local".to_string(),
+        version: Some("1.0.0".to_string()),
+        github_url: None,
+        branch: None,
+        tag: None,
+        path: Some("/this/path/does/not/exist".to_string()),
+        members: None,
+        update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
+    };
+
+    let response = service.cache_crate(Parameters(params)).await;
+    // Local path validation happens synchronously before spawning
+    assert!(
+        response.contains("Error") || response.contains("does not exist"),
+        "Expected error for non-existent path: {response}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_concurrent_caching() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    let service = std::sync::Arc::new(service);
+
+    // Define test crates with expected metadata
+    let test_crates = vec![
+        ("semver", "1.0.0"),
+        ("once_cell", "1.0.0"),
+        ("regex", "1.11.1"), // Use the latest version compatible with nightly toolchain
+    ];
+
+    // Start caching multiple crates concurrently
+    let mut task_ids = vec![];
+
+    for (name, version) in &test_crates {
+        let params = CacheCrateParams {
+            crate_name: name.to_string(),
+            source_type: "cratesio".to_string(),
+            version: Some(version.to_string()),
+            github_url: None,
+            branch: None,
+            tag: None,
+            path: None,
+            members: None,
+            update: None,
+            target: None,
+            scope: None,
+            keep_full_docs: None,
+        };
+        let start = std::time::Instant::now();
+        let response = service.cache_crate(Parameters(params)).await;
+        let duration = start.elapsed();
+        println!("Started caching {name} {version} in {duration:?}");
+
+        let task = parse_cache_task_started(&response)?;
+        task_ids.push((name.to_string(), version.to_string(), task.task_id));
+    }
+
+    // Wait for all tasks to complete
+    let mut results = vec![];
+    for (name, version, task_id) in task_ids {
+        let result = wait_for_task_completion(&service, &task_id, TEST_TIMEOUT).await?;
+        println!("Completed caching {name} {version}");
+
+        if !matches!(result, TaskResult::Success) {
+            eprintln!("Concurrent cache failed for {name}: {result:?}");
+        }
+        results.push((name.clone(), version.clone(), result));
+    }
+
+    // Verify all operations succeeded
+    for (name, version, result) in &results {
+        assert!(
+            matches!(result, TaskResult::Success),
+            "Failed to cache {name} {version}: {result:?}"
+        );
+    }
+
+    // Verify cache consistency - all crates should be present
+    let cached_crates_response = service.list_cached_crates().await;
+    for (name, version) in &test_crates {
+        assert!(
+            cached_crates_response.contains(name),
+            "{name} not found in cache listing"
+        );
+
+        // Also verify specific version is cached
+        let list_params = ListCrateVersionsParams {
+            crate_name: name.to_string(),
+        };
+        let versions_response = service.list_crate_versions(Parameters(list_params)).await;
+        assert!(
+            versions_response.contains(version),
+            "Version {version} of {name} not found in cache"
+        );
+    }
+
+    // Verify no corruption by attempting to re-cache (should be idempotent)
+    // This would fail if the cache was corrupted during concurrent access
+    for (name, version) in &test_crates {
+        let params = CacheCrateParams {
+            crate_name: name.to_string(),
+            source_type: "cratesio".to_string(),
+            version: Some(version.to_string()),
+            github_url: None,
+            branch: None,
+            tag: None,
+            path: None,
+            members: None,
+            update: Some(false), // Should not re-download if already cached
+            target: None,
+            scope: None,
+            keep_full_docs: None,
+        };
+        let response = service.cache_crate(Parameters(params)).await;
+        let task = parse_cache_task_started(&response)?;
+        let result = wait_for_task_completion(&service, &task.task_id, TEST_TIMEOUT).await?;
+
+        // Should complete successfully even if already cached
+        assert!(
+            matches!(result, TaskResult::Success),
+            "Cache integrity check failed for {name} {version}: {result:?}"
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_retry_failed_tasks() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    // A local crate that does not compile yet
+    let test_crate_dir = TempDir::new()?;
+    std::fs::write(
+        test_crate_dir.path().join("Cargo.toml"),
+        r#"
+[package]
+name = "test-retry"
+version = "0.1.0"
+edition = "2021"
+    "#,
+    )?;
+    let src_dir = test_crate_dir.path().join("src");
+    std::fs::create_dir(&src_dir)?;
+    std::fs::write(src_dir.join("lib.rs"), "pub fn broken( {}")?;
+
+    let params = CacheCrateParams {
+        crate_name: "test-retry".to_string(),
+        source_type: "local".to_string(),
+        version: Some("0.1.0".to_string()),
+        github_url: None,
+        branch: None,
+        tag: None,
+        path: Some(test_crate_dir.path().to_str().unwrap().to_string()),
+        members: None,
+        update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
+    };
+    let response = service.cache_crate(Parameters(params)).await;
+    let failed_task = parse_cache_task_started(&response)?;
+    let result = wait_for_task_completion(&service, &failed_task.task_id, TEST_TIMEOUT).await?;
+    assert!(
+        matches!(result, TaskResult::Failed(_)),
+        "Caching a crate that does not compile should fail: {result:?}"
+    );
+
+    // Fix the crate, then retry every failed task with its original parameters
+    std::fs::write(src_dir.join("lib.rs"), "pub fn fixed() {}")?;
+    let response = service
+        .cache_operations(Parameters(CacheOperationsParams {
+            task_id: None,
+            status_filter: None,
+            cancel: false,
+            clear: false,
+            retry_failed: true,
+        }))
+        .await;
+    assert!(response.starts_with("# Tasks Retried"), "{response}");
+
+    // The new task ID is listed next to the one it replaces
+    let line = response
+        .lines()
+        .find(|line| line.contains(&failed_task.task_id))
+        .context("Retried task should be listed")?;
+    let new_task_id = line
+        .split('`')
+        .nth(3)
+        .context("New task ID should be listed")?;
+    assert_ne!(new_task_id, failed_task.task_id);
+
+    let result = wait_for_task_completion(&service, new_task_id, TEST_TIMEOUT).await?;
+    assert!(
+        matches!(result, TaskResult::Success),
+        "Retried task should succeed: {result:?}"
+    );
+
+    // The failed task was replaced, so there is nothing left to retry
+    let response = service
+        .cache_operations(Parameters(CacheOperationsParams {
+            task_id: Some(failed_task.task_id.clone()),
+            status_filter: None,
+            cancel: false,
+            clear: false,
+            retry_failed: true,
+        }))
+        .await;
+    assert!(response.contains("not found"), "{response}");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_workspace_member_caching() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    // Create a workspace with members
+    let workspace_dir = TempDir::new()?;
+    let workspace_toml = workspace_dir.path().join("Cargo.toml");
+    std::fs::write(
+        &workspace_toml,
+        r#"
+[workspace]
+members = ["lib-a", "lib-b"]
+resolver = "2"
+    "#,
+    )?;
+
+    // Create member crates
+    for (member, version) in &[("lib-a", "0.1.0"), ("lib-b", "0.2.0")] {
+        let member_dir = workspace_dir.path().join(member);
+        std::fs::create_dir_all(&member_dir)?;
+        std::fs::write(
+            member_dir.join("Cargo.toml"),
+            format!(
+                r#"
+[package]
+name = "{member}"
+version = "{version}"
+edition = "2021"
+        "#
+            ),
+        )?;
+
+        let src_dir = member_dir.join("src");
+        std::fs::create_dir(&src_dir)?;
+        std::fs::write(src_dir.join("lib.rs"), format!("//! {member} library"))?;
+    }
+
+    // First attempt without specifying members - should get workspace detection
+    let params1 = CacheCrateParams {
+        crate_name: "my-workspace".to_string(),
+        source_type: "local".to_string(),
+        version: Some("1.0.0".to_string()),
+        github_url: None,
+        branch: None,
+        tag: None,
+        path: Some(workspace_dir.path().to_str().unwrap().to_string()),
+        members: None,
+        update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
+    };
+
+    let response1 = service.cache_crate(Parameters(params1)).await;
+    let task1 = parse_cache_task_started(&response1)?;
+    let result1 = wait_for_task_completion(&service, &task1.task_id, TEST_TIMEOUT).await?;
+
+    // Should detect workspace
+    assert!(
+        matches!(result1, TaskResult::WorkspaceDetected(_)),
+        "Should detect workspace: {result1:?}"
+    );
+
+    if let TaskResult::WorkspaceDetected(msg) = result1 {
+        assert!(
+            msg.contains("Workspace detected"),
+            "Should mention workspace: {msg}"
+        );
+    }
+
+    // Now cache with specific members
+    let params2 = CacheCrateParams {
+        crate_name: "my-workspace".to_string(),
+        source_type: "local".to_string(),
+        version: Some("1.0.0".to_string()),
+        github_url: None,
+        branch: None,
+        tag: None,
+        path: Some(workspace_dir.path().to_str().unwrap().to_string()),
+        members: Some(vec!["lib-a".to_string(), "lib-b".to_string()]),
+        update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
+    };
+
+    let response2 = service.cache_crate(Parameters(params2)).await;
+    let task2 = parse_cache_task_started(&response2)?;
+    let result2 = wait_for_task_completion(&service, &task2.task_id, TEST_TIMEOUT).await?;
+
+    assert!(
+        matches!(result2, TaskResult::Success),
+        "Should successfully cache workspace members: {result2:?}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_docgen_documents_only_the_crate() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    // regex depends on regex-syntax, regex-automata, aho-corasick and memchr
+    let params = CacheCrateParams {
+        crate_name: "regex".to_string(),
+        source_type: "cratesio".to_string(),
+        version: Some("1.11.1".to_string()),
+        github_url: None,
+        branch: None,
+        tag: None,
+        path: None,
+        members: None,
+        update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
+    };
+    let start = std::time::Instant::now();
+    let response = service.cache_crate(Parameters(params)).await;
+    let task_output = parse_cache_task_started(&response)?;
+    let result =
+        wait_for_task_completion(&service, &task_output.task_id, LARGE_CRATE_TEST_TIMEOUT).await?;
+    println!("Cached regex 1.11.1 in {:?}", start.elapsed());
+    assert!(
+        matches!(result, TaskResult::Success),
+        "Failed to cache regex: {result:?}"
+    );
+
+    // Items of dependencies are not documented alongside the crate
+    let params = SearchItemsPreviewParams {
+        crate_name: "regex".to_string(),
+        version: "1.11.1".to_string(),
+        pattern: "Hir".to_string(),
+        limit: None,
+        offset: None,
+        kind_filter: None,
+        path_filter: Some("regex_syntax".to_string()),
+        member: None,
+        target: None,
+        dedupe_by_id: None,
+        compact: None,
+    };
+    let response = service.search_items_preview(Parameters(params)).await;
+    let output: SearchItemsPreviewOutput = serde_json::from_str(&response)?;
+    assert!(
+        output.items.is_empty(),
+        "Dependency items should not be documented: {:?}",
+        output.items
+    );
+
+    Ok(())
+}
+
+// ===== DOCUMENTATION TOOLS TESTS =====
+
+#[tokio::test]
+async fn test_list_crate_items() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    // Test basic listing
+    let params = ListItemsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        kind_filter: None,
+        limit: Some(50),
+        offset: Some(0),
+        member: None,
+        include_private: None,
+        include_hidden: None,
+        target: None,
+        group_by_module: None,
+        max_depth: None,
+    };
+
+    let response = service.list_crate_items(Parameters(params)).await;
+    let output: ListCrateItemsOutput = serde_json::from_str(&response)?;
+
+    assert!(!output.items.is_empty(), "Should have items");
+    assert_eq!(output.pagination.limit, 50, "Limit should match request");
+    assert_eq!(output.pagination.offset, 0, "Offset should match request");
+
+    // Test with kind filter
+    let params = ListItemsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        kind_filter: Some("struct".to_string()),
+        limit: Some(10),
+        offset: None,
+        member: None,
+        include_private: None,
+        include_hidden: None,
+        target: None,
+        group_by_module: None,
+        max_depth: None,
+    };
+
+    let response = service.list_crate_items(Parameters(params)).await;
+    let output: ListCrateItemsOutput = serde_json::from_str(&response)?;
+
+    // Check all items are structs
+    for item in &output.items {
+        assert_eq!(item.kind, "struct", "All items should be structs");
+    }
+
+    // Aliases and other casings are normalized to the item kind
+    let list_kind = |kind: &str| ListItemsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        kind_filter: Some(kind.to_string()),
+        limit: Some(10),
+        offset: None,
+        member: None,
+        include_private: None,
+        include_hidden: None,
+        target: None,
+        group_by_module: None,
+        max_depth: None,
+    };
+    let response = service.list_crate_items(Parameters(list_kind("fn"))).await;
+    let output: ListCrateItemsOutput = serde_json::from_str(&response)?;
+    assert!(!output.items.is_empty(), "'fn' should match functions");
+    assert!(output.items.iter().all(|item| item.kind == "function"));
+
+    // Several kinds match items of any of them, given as a list or comma-separated
+    let params: ListItemsParams = serde_json::from_value(serde_json::json!({
+        "crate_name": "semver",
+        "version": SEMVER_VERSION,
+        "kind_filter": ["struct", "enum"],
+        "limit": 1000,
+    }))?;
+    assert_eq!(params.kind_filter.as_deref(), Some("struct,enum"));
+    let response = service.list_crate_items(Parameters(params)).await;
+    let output: ListCrateItemsOutput = serde_json::from_str(&response)?;
+    let kinds: std::collections::BTreeSet<&str> =
+        output.items.iter().map(|item| item.kind.as_str()).collect();
+    assert_eq!(
+        kinds.into_iter().collect::<Vec<_>>(),
+        ["enum", "struct"],
+        "Only structs and enums should be listed"
+    );
+
+    // Unknown kinds are rejected instead of silently matching nothing
+    let response = service
+        .list_crate_items(Parameters(list_kind("structure")))
+        .await;
+    let output: DocsErrorOutput = serde_json::from_str(&response)?;
+    assert!(
+        output.error.contains("Valid kinds:"),
+        "Should list valid kinds: {}",
+        output.error
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_crate_items_group_by_module() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let list = |max_depth: Option<usize>| ListItemsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        kind_filter: Some("struct,enum,function".to_string()),
+        limit: Some(1000),
+        offset: None,
+        member: None,
+        include_private: None,
+        include_hidden: None,
+        target: None,
+        group_by_module: Some(true),
+        max_depth,
+    };
+
+    let response = service.list_crate_items(Parameters(list(None))).await;
+    let output: ListCrateItemsOutput = serde_json::from_str(&response)?;
+    assert!(output.items.is_empty(), "Items should be nested in modules");
+    let modules = output.modules.expect("Should group items by module");
+    assert!(!modules.is_empty());
+    let grouped: usize = modules.iter().map(|module| module.items.len()).sum();
+    assert_eq!(grouped, output.pagination.total.min(1000));
+    for module in &modules {
+        for item in &module.items {
+            assert_eq!(
+                item.path[..item.path.len().saturating_sub(1)].join("::"),
+                module.path,
+                "{} should be listed under its module",
+                item.name
+            );
+        }
+    }
+
+    // At depth 0 every item with a path is nested under the crate root
+    let response = service.list_crate_items(Parameters(list(Some(0)))).await;
+    let output: ListCrateItemsOutput = serde_json::from_str(&response)?;
+    let modules = output.modules.expect("Should group items by module");
+    assert!(
+        modules
+            .iter()
+            .all(|module| module.path == "semver" || module.path.is_empty()),
+        "Modules should be cut to the crate root: {:?}",
+        modules
+            .iter()
+            .map(|module| &module.path)
+            .collect::<Vec<_>>()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_search_items_preview() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    // Test basic preview search
+    let params = SearchItemsPreviewParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        pattern: "Version".to_string(),
+        limit: Some(10),
+        offset: None,
+        kind_filter: None,
+        path_filter: None,
+        member: None,
+        target: None,
+        dedupe_by_id: None,
+        compact: None,
+    };
+
+    let response = service.search_items_preview(Parameters(params)).await;
+    let output: SearchItemsPreviewOutput = serde_json::from_str(&response)?;
+
+    assert!(
+        !output.items.is_empty(),
+        "Should find items matching 'Version'"
+    );
+
+    // Verify preview format (only id, name, kind, path)
+    if let Some(item) = output.items.first() {
+        assert!(!item.id.is_empty(), "Item should have id");
+        assert!(!item.name.is_empty(), "Item should have name");
+        assert!(!item.kind.is_empty(), "Item should have kind");
+        assert!(!item.path.is_empty(), "Item should have path");
+    }
+
+    // Test with filters
+    let params = SearchItemsPreviewParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        pattern: "new".to_string(),
+        limit: Some(5),
+        offset: None,
+        kind_filter: Some("function".to_string()),
+        path_filter: None,
+        member: None,
+        target: None,
+        dedupe_by_id: None,
+        compact: None,
+    };
+
+    let response = service.search_items_preview(Parameters(params)).await;
+    let output: SearchItemsPreviewOutput = serde_json::from_str(&response)?;
+
+    // Check all items are functions
+    for item in &output.items {
+        assert_eq!(item.kind, "function", "All items should be functions");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_search_items_preview_compact() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let params = |compact| SearchItemsPreviewParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        pattern: "Version".to_string(),
+        limit: Some(10),
+        offset: None,
+        kind_filter: None,
+        path_filter: None,
+        member: None,
+        target: None,
+        dedupe_by_id: None,
+        compact,
+    };
+
+    let response = service.search_items_preview(Parameters(params(None))).await;
+    let output: SearchItemsPreviewOutput = serde_json::from_str(&response)?;
+    assert!(
+        !output.items.is_empty(),
+        "Should find items matching 'Version'"
+    );
+
+    let compact = service
+        .search_items_preview(Parameters(params(Some(true))))
+        .await;
+    assert!(
+        serde_json::from_str::<serde_json::Value>(&compact).is_err(),
+        "Compact results should not be JSON: {compact}"
+    );
+
+    // Every line parses back into the item the JSON response lists at the same position
+    let items: Vec<ItemPreview> = compact
+        .lines()
+        .filter(|line| !line.starts_with("# "))
+        .map(|line| {
+            let fields: Vec<&str> = line.split("  ").collect();
+            let [id, kind, path] = fields[..] else {
+                panic!("Malformed compact line: {line}");
+            };
+            let path: Vec<String> = path.split("::").map(String::from).collect();
+            ItemPreview {
+                id: id.to_string(),
+                name: path.last().cloned().unwrap_or_default(),
+                kind: kind.to_string(),
+                path,
+            }
+        })
+        .collect();
+    assert_eq!(items, output.items);
+
+    // More results than the limit are announced after the items
+    if output.pagination.has_more {
+        assert!(
+            compact
+                .lines()
+                .last()
+                .is_some_and(|line| line.starts_with("# ")),
+            "Missing pagination line: {compact}"
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_search_items_full() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    // Test full search with complete documentation
+    let params = SearchItemsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        pattern: "Version".to_string(),
+        limit: Some(5),
+        offset: None,
+        kind_filter: Some("struct".to_string()),
+        path_filter: None,
+        member: None,
+        group_by: None,
+        include_private: None,
+        include_hidden: None,
+        target: None,
+        dedupe_by_id: None,
+    };
+
+    let response = service.search_items(Parameters(params)).await;
+    let output: SearchItemsOutput = serde_json::from_str(&response)?;
+
+    assert!(!output.items.is_empty(), "Should find items");
+
+    // Verify full format includes documentation
+    if let Some(item) = output.items.first() {
+        assert!(!item.id.is_empty(), "Item should have id");
+        assert!(!item.name.is_empty(), "Item should have name");
+        assert_eq!(item.kind, "struct", "Item should be a struct");
+        // Full search may include documentation
+        // Note: docs field is Optional, so it's OK if it's None
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_search_items_respects_max_response_bytes() -> Result<()> {
+    let (service, temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let search = || SearchItemsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        pattern: "Version".to_string(),
+        limit: Some(50),
+        offset: None,
+        kind_filter: None,
+        path_filter: None,
+        member: None,
+        group_by: None,
+        include_private: None,
+        include_hidden: None,
+        target: None,
+        dedupe_by_id: None,
+    };
+
+    let response = service.search_items(Parameters(search())).await;
+    let output: SearchItemsOutput = serde_json::from_str(&response)?;
+    assert!(
+        output.warning.is_none(),
+        "Default limit should not truncate"
+    );
+    let default_count = output.items.len();
+    assert!(default_count > 1, "Should find several items");
+
+    // A service sharing the same cache but with a tiny response limit
+    let config = ServiceConfig {
+        max_response_bytes: 1_000,
+        ..ServiceConfig::default()
+    };
+    let small_service = RustDocsService::with_config(Some(temp_dir.path().to_path_buf()), config)?;
+
+    let response = small_service.search_items(Parameters(search())).await;
+    let output: SearchItemsOutput = serde_json::from_str(&response)?;
+    assert!(
+        output.items.len() < default_count,
+        "Tiny limit should return fewer items"
+    );
+    assert!(
+        output
+            .warning
+            .as_deref()
+            .is_some_and(|warning| warning.contains("truncated")),
+        "Truncation should be reported: {:?}",
+        output.warning
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_include_private_items() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    // Create a local crate with both public and private items
+    cache_local_crate(
+        &service,
+        "test-private",
+        "//! Crate with private items\npub fn visible() { hidden_helper() }\nfn hidden_helper() {}",
+    )
+    .await?;
+
+    let search = |include_private| SearchItemsParams {
+        crate_name: "test-private".to_string(),
+        version: "0.1.0".to_string(),
+        pattern: "hidden_helper".to_string(),
+        limit: None,
+        offset: None,
+        kind_filter: None,
+        path_filter: None,
+        member: None,
+        group_by: None,
+        include_private,
+        include_hidden: None,
+        target: None,
+        dedupe_by_id: None,
+    };
+
+    // Private items are not documented by default
+    let response = service.search_items(Parameters(search(None))).await;
+    let output: SearchItemsOutput = serde_json::from_str(&response)?;
+    assert!(output.items.is_empty(), "Private item should be hidden");
+
+    // With include_private, docs are regenerated with private items
+    let response = service.search_items(Parameters(search(Some(true)))).await;
+    let output: SearchItemsOutput = serde_json::from_str(&response)?;
+    let item = output
+        .items
+        .iter()
+        .find(|item| item.name == "hidden_helper")
+        .context("Private item should be included")?;
+    assert_ne!(item.visibility, "public");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_include_hidden_items() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    // Create a local crate with a public item hidden from the docs
+    cache_local_crate(
+        &service,
+        "test-hidden",
+        "//! Crate with hidden items\npub fn visible() {}\n#[doc(hidden)]\npub fn internal_hook() {}",
+    )
+    .await?;
+
+    let list = |include_hidden| ListItemsParams {
+        crate_name: "test-hidden".to_string(),
+        version: "0.1.0".to_string(),
+        kind_filter: Some("function".to_string()),
+        limit: None,
+        offset: None,
+        member: None,
+        include_private: None,
+        include_hidden,
+        target: None,
+        group_by_module: None,
+        max_depth: None,
+    };
+
+    // Hidden items are not documented by default
+    let response = service.list_crate_items(Parameters(list(None))).await;
+    let output: ListCrateItemsOutput = serde_json::from_str(&response)?;
+    let names: Vec<_> = output.items.iter().map(|item| item.name.as_str()).collect();
+    assert_eq!(names, ["visible"]);
+    assert!(!output.items[0].hidden);
+
+    // With include_hidden, docs are regenerated with hidden items
+    let response = service.list_crate_items(Parameters(list(Some(true)))).await;
+    let output: ListCrateItemsOutput = serde_json::from_str(&response)?;
+    let item = output
+        .items
+        .iter()
+        .find(|item| item.name == "internal_hook")
+        .context("Hidden item should be included")?;
+    assert!(item.hidden);
+    assert!(response.contains(r#""hidden":true"#));
+
+    // include_private and include_hidden select different docs
+    let mut params = list(Some(true));
+    params.include_private = Some(true);
+    let response = service.list_crate_items(Parameters(params)).await;
+    assert!(response.contains("cannot be combined"), "{response}");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_search_items_dedupe_by_id() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    // Create a local crate that re-exports a type at its root
+    cache_local_crate(
+        &service,
+        "test-reexport",
+        "//! Crate with a re-export\npub mod config {\n    pub struct Settings;\n}\npub use config::Settings;",
+    )
+    .await?;
+
+    let search = |dedupe_by_id| SearchItemsParams {
+        crate_name: "test-reexport".to_string(),
+        version: "0.1.0".to_string(),
+        pattern: "Settings".to_string(),
+        limit: None,
+        offset: None,
+        kind_filter: None,
+        path_filter: None,
+        member: None,
+        group_by: None,
+        include_private: None,
+        include_hidden: None,
+        target: None,
+        dedupe_by_id,
+    };
+
+    let response = service.search_items(Parameters(search(None))).await;
+    let all: SearchItemsOutput = serde_json::from_str(&response)?;
+    assert!(!all.items.is_empty(), "Should find Settings");
+
+    // The struct and its re-export collapse into the struct itself
+    let response = service.search_items(Parameters(search(Some(true)))).await;
+    let deduped: SearchItemsOutput = serde_json::from_str(&response)?;
+    assert_eq!(deduped.items.len(), 1, "Got {:?}", deduped.items);
+    assert_eq!(deduped.items[0].kind, "struct");
+    assert_eq!(deduped.items[0].name, "Settings");
+    assert!(deduped.items.len() <= all.items.len());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_item_details() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let item_id = get_test_item_id(&service).await?;
+
+    // Test getting complete item details
+    let params = GetItemDetailsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        item_id: Some(item_id),
+        path: None,
+        member: None,
+        fields: None,
+        target: None,
+        resolve_field_types: None,
+        format: None,
+    };
+
+    let response = service.get_item_details(Parameters(params)).await;
+    let output: GetItemDetailsOutput = serde_json::from_str(&response)?;
+
+    assert!(output.is_success(), "Should be a success response");
+
+    if let GetItemDetailsOutput::Success(detailed_item) = output {
+        // Should contain detailed information about the item
+        assert!(!detailed_item.info.id.is_empty(), "Details should have id");
+        assert!(
+            !detailed_item.info.name.is_empty(),
+            "Details should have name"
+        );
+        assert!(
+            !detailed_item.info.kind.is_empty(),
+            "Details should have kind"
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_item_details_formats() -> Result<()> {
+    let (service, temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let item_id = get_test_item_id(&service).await?;
+    let params = |format: Option<&str>| GetItemDetailsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        item_id: Some(item_id),
+        path: None,
+        member: None,
+        fields: None,
+        target: None,
+        resolve_field_types: None,
+        format: format.map(str::to_string),
+    };
+
+    // JSON by default
+    let response = service.get_item_details(Parameters(params(None))).await;
+    let output: GetItemDetailsOutput = serde_json::from_str(&response)?;
+    assert!(output.is_success());
+
+    // A Markdown card on request
+    let response = service
+        .get_item_details(Parameters(params(Some("text"))))
+        .await;
+    assert!(
+        response.starts_with("# struct `semver::"),
+        "Expected a Markdown card, got: {response}"
+    );
+    assert!(response.contains(
+        "```rust
+"
+    ));
+    assert!(serde_json::from_str::<serde_json::Value>(&response).is_err());
+
+    // The server default applies unless a call asks for another format
+    let config = ServiceConfig {
+        default_format: ResponseFormat::Text,
+        ..ServiceConfig::default()
+    };
+    let text_service = RustDocsService::with_config(Some(temp_dir.path().to_path_buf()), config)?;
+    let response = text_service
+        .get_item_details(Parameters(params(None)))
+        .await;
+    assert!(response.starts_with("# struct `semver::"));
+    let response = text_service
+        .get_item_details(Parameters(params(Some("json"))))
+        .await;
+    assert!(serde_json::from_str::<GetItemDetailsOutput>(&response)?.is_success());
+
+    let response = service
+        .get_item_details(Parameters(params(Some("yaml"))))
+        .await;
+    let error: DocsErrorOutput = serde_json::from_str(&response)?;
+    assert!(error.error.contains("Unknown response format"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_item_details_resolve_field_types() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    cache_local_crate(
+        &service,
+        "test-field-types",
+        r#"
+pub struct Config {
+    pub name: String,
+}
+
+pub struct Server {
+    pub config: Config,
+    pub peers: [Config; 2],
+    pub host: String,
+    pub port: u16,
+}
+
+pub enum Event {
+    Configured(Config),
+    Renamed(String),
+    Stopped,
+    Moved { from: u16, to: u16 },
+}
+"#,
+    )
+    .await?;
+
+    let details = |path: &str, resolve_field_types: Option<bool>| GetItemDetailsParams {
+        crate_name: "test-field-types".to_string(),
+        version: "0.1.0".to_string(),
+        item_id: None,
+        path: Some(path.to_string()),
+        member: None,
+        fields: None,
+        target: None,
+        resolve_field_types,
+        format: None,
+    };
+
+    let config_id = get_item_details_success(&service, details("test_field_types::Config", None))
+        .await?
+        .info
+        .id;
+
+    // Field types are only resolved on request
+    let server =
+        get_item_details_success(&service, details("test_field_types::Server", None)).await?;
+    let fields = server.fields.unwrap_or_default();
+    assert!(fields.iter().all(|field| field.field_type_id.is_none()));
+
+    let server =
+        get_item_details_success(&service, details("test_field_types::Server", Some(true))).await?;
+    let fields = server.fields.unwrap_or_default();
+    let field = |name: &str| fields.iter().find(|field| field.name == name).unwrap();
+    assert_eq!(
+        field("config").field_type_id.as_deref(),
+        Some(config_id.as_str())
+    );
+    // Arrays are looked through to their element type
+    assert_eq!(
+        field("peers").field_type_id.as_deref(),
+        Some(config_id.as_str())
+    );
+    // Types from other crates link to their path
+    assert!(field("host").field_type_id.is_none());
+    assert!(
+        field("host")
+            .field_type_path
+            .as_deref()
+            .is_some_and(|path| path.ends_with("::String"))
+    );
+    // Primitives link to nothing
+    assert!(field("port").field_type_id.is_none() && field("port").field_type_path.is_none());
+
+    let event =
+        get_item_details_success(&service, details("test_field_types::Event", Some(true))).await?;
+    let variants = event.variants.unwrap_or_default();
+    let variant = |name: &str| {
+        variants
+            .iter()
+            .find(|variant| variant.name == name)
+            .unwrap()
+    };
+    assert_eq!(
+        variant("Configured").field_type_id.as_deref(),
+        Some(config_id.as_str())
+    );
+    assert!(variant("Renamed").field_type_path.is_some());
+    assert!(variant("Stopped").field_type_id.is_none());
+    assert!(variant("Moved").field_type_id.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_item_details_by_path() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    // A fully qualified path resolves to a single item
+    let params = GetItemDetailsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        item_id: None,
+        path: Some("semver::Version".to_string()),
+        member: None,
+        fields: None,
+        target: None,
+        resolve_field_types: None,
+        format: None,
+    };
+
+    let response = service.get_item_details(Parameters(params)).await;
+    let output: GetItemDetailsOutput = serde_json::from_str(&response)?;
+
+    match output {
+        GetItemDetailsOutput::Success(detailed_item) => {
+            assert_eq!(detailed_item.info.name, "Version");
+            assert_eq!(detailed_item.info.kind, "struct");
+            // Crates from crates.io link to their page on docs.rs
+            assert_eq!(
+                detailed_item.docsrs_url,
+                Some(format!(
+                    "https://docs.rs/semver/{SEMVER_VERSION}/semver/struct.Version.html"
+                ))
+            );
+        }
+        other => panic!("Expected success response, got: {other:?}"),
+    }
+
+    // Unknown paths are reported as errors
+    let params = GetItemDetailsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        item_id: None,
+        path: Some("semver::DoesNotExist".to_string()),
+        member: None,
+        fields: None,
+        target: None,
+        resolve_field_types: None,
+        format: None,
+    };
+
+    let response = service.get_item_details(Parameters(params)).await;
+    let output: GetItemDetailsOutput = serde_json::from_str(&response)?;
+    assert!(output.is_error(), "Unknown path should return an error");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_item_details_field_selection() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let params = GetItemDetailsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        item_id: None,
+        path: Some("semver::Version".to_string()),
+        member: None,
+        fields: Some(vec!["signature".to_string(), "docs".to_string()]),
+        target: None,
+        resolve_field_types: None,
+        format: None,
+    };
+
+    let response = service.get_item_details(Parameters(params)).await;
+    let output: GetItemDetailsOutput = serde_json::from_str(&response)?;
+
+    match output {
+        GetItemDetailsOutput::Success(detailed_item) => {
+            assert_eq!(detailed_item.info.name, "Version");
+            assert!(detailed_item.info.docs.is_some());
+            assert!(detailed_item.fields.is_none());
+            assert!(detailed_item.methods.is_none());
+            assert!(detailed_item.source_location.is_none());
+        }
+        other => panic!("Expected success response, got: {other:?}"),
+    }
+
+    // Unknown field names are rejected
+    let params = GetItemDetailsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        item_id: None,
+        path: Some("semver::Version".to_string()),
+        member: None,
+        fields: Some(vec!["everything".to_string()]),
+        target: None,
+        resolve_field_types: None,
+        format: None,
+    };
+
+    let response = service.get_item_details(Parameters(params)).await;
+    let output: GetItemDetailsOutput = serde_json::from_str(&response)?;
+    assert!(output.is_error(), "Unknown field should return an error");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_blanket_impls() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let params = GetItemDetailsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        item_id: None,
+        path: Some("semver::Version".to_string()),
+        member: None,
+        fields: Some(vec!["signature".to_string()]),
+        target: None,
+        resolve_field_types: None,
+        format: None,
+    };
+    let response = service.get_item_details(Parameters(params)).await;
+    let item_id = match serde_json::from_str::<GetItemDetailsOutput>(&response)? {
+        GetItemDetailsOutput::Success(detailed_item) => detailed_item.info.id.parse::<u32>()?,
+        other => panic!("Expected success response, got: {other:?}"),
+    };
+
+    let params = GetBlanketImplsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        item_id,
+        member: None,
+        target: None,
+    };
+    let response = service.get_blanket_impls(Parameters(params)).await;
+    let output: GetBlanketImplsOutput = serde_json::from_str(&response)?;
+
+    assert_eq!(output.item.name, "Version");
+    // Version implements Display, so it gets ToString through the blanket impl
+    assert!(
+        output
+            .blanket_traits
+            .iter()
+            .any(|b| b.trait_name == "ToString"),
+        "Expected a ToString blanket impl, got: {:?}",
+        output.blanket_traits
+    );
+    assert!(output.auto_traits.iter().any(|t| t.ends_with("Send")));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_repr_info() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    cache_local_crate(
+        &service,
+        "test-repr-info",
+        r#"
+/// A point shared with C code.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub y: i32,
+    pub x: i32,
+}
+
+/// An opaque handle owned by C code.
+pub struct Handle(pub *mut u8);
+"#,
+    )
+    .await?;
+
+    let repr_info = |path: &str| {
+        let service = &service;
+        let path = path.to_string();
+        async move {
+            let item = get_item_details_success(
+                service,
+                GetItemDetailsParams {
+                    crate_name: "test-repr-info".to_string(),
+                    version: "0.1.0".to_string(),
+                    item_id: None,
+                    path: Some(path),
+                    member: None,
+                    fields: None,
+                    target: None,
+                    resolve_field_types: None,
+                    format: None,
+                },
+            )
+            .await?;
+            let params = GetReprInfoParams {
+                crate_name: "test-repr-info".to_string(),
+                version: "0.1.0".to_string(),
+                item_id: item.info.id.parse()?,
+                member: None,
+                target: None,
+            };
+            let response = service.get_repr_info(Parameters(params)).await;
+            Ok::<_, anyhow::Error>(serde_json::from_str::<GetReprInfoOutput>(&response)?)
+        }
+    };
+
+    let point = repr_info("test_repr_info::Point").await?;
+    assert_eq!(point.repr.as_deref(), Some("#[repr(C)]"));
+    assert_eq!(point.derives, ["Clone", "Copy", "Debug", "PartialEq"]);
+    assert_eq!(point.auto_traits, ["Copy", "Clone", "Send", "Sync"]);
+    // Fields keep their declaration order
+    assert_eq!(point.fields, ["y", "x"]);
+
+    // Raw pointers are neither Send nor Sync
+    let handle = repr_info("test_repr_info::Handle").await?;
+    assert_eq!(handle.repr, None);
+    assert!(handle.derives.is_empty());
+    assert!(
+        handle.auto_traits.is_empty(),
+        "Unexpected traits: {:?}",
+        handle.auto_traits
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_cfg_attributes() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let params = ListCfgAttributesParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        member: None,
+        target: None,
+    };
+    let response = service.list_cfg_attributes(Parameters(params)).await;
+    let output: ListCfgAttributesOutput = serde_json::from_str(&response)?;
+
+    // Predicates are distinct, each gates at least one item, and the most common come first
+    let mut predicates: Vec<_> = output.cfgs.iter().map(|c| &c.predicate).collect();
+    predicates.sort();
+    predicates.dedup();
+    assert_eq!(predicates.len(), output.cfgs.len());
+    assert!(output.cfgs.iter().all(|c| c.item_count > 0));
+    assert!(
+        output
+            .cfgs
+            .windows(2)
+            .all(|pair| pair[0].item_count >= pair[1].item_count)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_all_methods() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let params = GetItemDetailsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        item_id: None,
+        path: Some("semver::Version".to_string()),
+        member: None,
+        fields: Some(vec!["signature".to_string()]),
+        target: None,
+        resolve_field_types: None,
+        format: None,
+    };
+    let response = service.get_item_details(Parameters(params)).await;
+    let item_id = match serde_json::from_str::<GetItemDetailsOutput>(&response)? {
+        GetItemDetailsOutput::Success(detailed_item) => detailed_item.info.id.parse::<u32>()?,
+        other => panic!("Expected success response, got: {other:?}"),
+    };
+
+    let all_methods = |include_blanket| GetAllMethodsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        item_id,
+        include_blanket,
+        member: None,
+        target: None,
+    };
+    let response = service.get_all_methods(Parameters(all_methods(None))).await;
+    let output: GetAllMethodsOutput = serde_json::from_str(&response)?;
+    assert_eq!(output.item.name, "Version");
+
+    let parse = output
+        .methods
+        .iter()
+        .find(|m| m.name == "parse")
+        .context("Version::parse should be listed")?;
+    assert_eq!(parse.from, "inherent");
+    assert!(parse.signature.is_some());
+
+    // Methods from trait impls, including provided methods such as Ord::max
+    let from_trait = |name: &str, trait_name: &str| {
+        output
+            .methods
+            .iter()
+            .any(|m| m.name == name && m.from.starts_with("trait ") && m.from.ends_with(trait_name))
+    };
+    assert!(from_trait("from_str", "FromStr"), "{:?}", output.methods);
+    assert!(from_trait("cmp", "Ord"), "{:?}", output.methods);
+    assert!(from_trait("max", "Ord"), "{:?}", output.methods);
+    assert!(!output.methods.iter().any(|m| m.name == "to_string"));
+
+    // Blanket impl methods are opt-in
+    let response = service
+        .get_all_methods(Parameters(all_methods(Some(true))))
+        .await;
+    let output: GetAllMethodsOutput = serde_json::from_str(&response)?;
+    assert!(
+        output
+            .methods
+            .iter()
+            .any(|m| m.name == "to_string" && m.from.ends_with("ToString")),
+        "{:?}",
+        output.methods
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_supertraits() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    cache_local_crate(
+        &service,
+        "test-supertraits",
+        r#"
+pub trait Base: Clone {}
+pub trait Left: Base {}
+pub trait Right: Base + std::fmt::Debug {}
+pub trait Top: Left + Right where Self: Send {}
+"#,
+    )
+    .await?;
+
+    let params = GetItemDetailsParams {
+        crate_name: "test-supertraits".to_string(),
+        version: "0.1.0".to_string(),
+        item_id: None,
+        path: Some("test_supertraits::Top".to_string()),
+        member: None,
+        fields: None,
+        target: None,
+        resolve_field_types: None,
+        format: None,
+    };
+    let response = service.get_item_details(Parameters(params)).await;
+    let item_id = match serde_json::from_str::<GetItemDetailsOutput>(&response)? {
+        GetItemDetailsOutput::Success(detailed_item) => detailed_item.info.id.parse::<u32>()?,
+        other => panic!("Expected success response, got: {other:?}"),
+    };
+
+    let params = GetSupertraitsParams {
+        crate_name: "test-supertraits".to_string(),
+        version: "0.1.0".to_string(),
+        item_id,
+        member: None,
+        target: None,
+    };
+    let response = service.get_supertraits(Parameters(params)).await;
+    let output: GetSupertraitsOutput = serde_json::from_str(&response)?;
+    assert_eq!(output.item.name, "Top");
+
+    let names = |entries: &[SupertraitEntry]| -> Vec<String> {
+        entries
+            .iter()
+            .map(|entry| {
+                entry
+                    .path
+                    .rsplit("::")
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect()
+    };
+    assert_eq!(names(&output.direct), ["Left", "Right", "Send"]);
+
+    // Base is shared by Left and Right but listed once
+    let transitive = names(&output.transitive);
+    assert_eq!(
+        transitive,
+        ["Left", "Right", "Send", "Base", "Debug", "Clone"]
+    );
+    let base = &output.transitive[3];
+    assert!(base.via.as_deref().is_some_and(|via| via.ends_with("Left")));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_item_raw() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let params = GetItemDetailsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        item_id: None,
+        path: Some("semver::Version".to_string()),
+        member: None,
+        fields: Some(vec!["signature".to_string()]),
+        target: None,
+        resolve_field_types: None,
+        format: None,
+    };
+    let response = service.get_item_details(Parameters(params)).await;
+    let item_id = match serde_json::from_str::<GetItemDetailsOutput>(&response)? {
+        GetItemDetailsOutput::Success(detailed_item) => detailed_item.info.id.parse::<u32>()?,
+        other => panic!("Expected success response, got: {other:?}"),
+    };
+
+    let raw = |depth| GetItemRawParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        item_id,
+        depth,
+        member: None,
+        target: None,
+    };
+
+    // Depth 0 returns only the item itself, as raw rustdoc JSON
+    let response = service.get_item_raw(Parameters(raw(Some(0)))).await;
+    let output: GetItemRawOutput = serde_json::from_str(&response)?;
+    assert_eq!(output.items.len(), 1);
+    let version = &output.items[&item_id.to_string()];
+    assert_eq!(version["name"], "Version");
+    assert!(version["inner"]["struct"].is_object(), "{version}");
+
+    // The default depth includes the fields and impls of the struct
+    let response = service.get_item_raw(Parameters(raw(None))).await;
+    let output: GetItemRawOutput = serde_json::from_str(&response)?;
+    assert_eq!(output.depth, 1);
+    assert!(
+        output
+            .items
+            .values()
+            .any(|item| item["name"] == "major" && item["inner"]["struct_field"].is_object()),
+        "Fields should be included"
+    );
+    assert!(
+        output
+            .items
+            .values()
+            .any(|item| item["inner"]["impl"].is_object()),
+        "Impls should be included"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_export_docs_markdown() -> Result<()> {
+    let (service, temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let params = ExportDocsMarkdownParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        kind_filter: Some("struct".to_string()),
+        max_items: None,
+        output_path: None,
+        member: None,
+        target: None,
+    };
+    let response = service.export_docs_markdown(Parameters(params)).await;
+    let output: ExportDocsMarkdownOutput = serde_json::from_str(&response)?;
+
+    let markdown = output.markdown.context("Expected inline markdown")?;
+    assert!(markdown.starts_with(&format!("# semver {SEMVER_VERSION}")));
+    assert!(markdown.contains("## `semver`"));
+    assert!(markdown.contains("### `Version` (struct)"));
+    assert!(!markdown.contains("(function)"));
+    assert!(!output.truncated);
+
+    // Write a truncated export to a file
+    let output_path = temp_dir.path().join("semver.md");
+    let params = ExportDocsMarkdownParams {
+        crate_name: "semver".to_string(),
         version: SEMVER_VERSION.to_string(),
         kind_filter: None,
-        limit: Some(50),
-        offset: Some(0),
+        max_items: Some(2),
+        output_path: Some(output_path.display().to_string()),
+        member: None,
+        target: None,
+    };
+    let response = service.export_docs_markdown(Parameters(params)).await;
+    let output: ExportDocsMarkdownOutput = serde_json::from_str(&response)?;
+
+    assert!(output.markdown.is_none());
+    assert_eq!(output.item_count, 2);
+    assert!(output.truncated);
+    let written = std::fs::read_to_string(&output_path)?;
+    assert!(written.contains("Output limited to 2 items"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_export_items_ndjson() -> Result<()> {
+    let (service, temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let output_path = temp_dir.path().join("semver.ndjson");
+    let params = ExportItemsNdjsonParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        output_path: output_path.to_str().unwrap().to_string(),
+        kind_filter: None,
+        include_private: None,
+        include_hidden: None,
+        member: None,
+        target: None,
+    };
+    let response = service.export_items_ndjson(Parameters(params)).await;
+    let output: ExportItemsNdjsonOutput = serde_json::from_str(&response)?;
+
+    // The export has every item, where listing them is paginated
+    let params = ListItemsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        kind_filter: None,
+        limit: Some(1),
+        offset: None,
+        member: None,
+        include_private: None,
+        include_hidden: None,
+        target: None,
+        group_by_module: None,
+        max_depth: None,
+    };
+    let response = service.list_crate_items(Parameters(params)).await;
+    let listing: ListCrateItemsOutput = serde_json::from_str(&response)?;
+    assert_eq!(output.item_count, listing.pagination.total);
+
+    let contents = std::fs::read_to_string(&output_path)?;
+    assert_eq!(output.bytes_written, contents.len() as u64);
+    let mut lines = contents.lines();
+    let header: serde_json::Value = serde_json::from_str(lines.next().unwrap())?;
+    assert_eq!(header["crate"], "semver");
+    assert_eq!(header["version"], SEMVER_VERSION);
+    assert_eq!(header["item_count"], output.item_count);
+
+    let items: Vec<ItemInfo> = lines.map(serde_json::from_str).collect::<Result<_, _>>()?;
+    assert_eq!(items.len(), output.item_count);
+    assert!(items.iter().any(|item| item.name == "Version"));
+
+    // Relative paths are rejected
+    let params = ExportItemsNdjsonParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        output_path: "semver.ndjson".to_string(),
+        kind_filter: None,
+        include_private: None,
+        include_hidden: None,
+        member: None,
+        target: None,
+    };
+    let response = service.export_items_ndjson(Parameters(params)).await;
+    let error: DocsErrorOutput = serde_json::from_str(&response)?;
+    assert!(error.error.contains("absolute"));
+
+    Ok(())
+}
+
+/// Commit the files in the working tree of `repo`
+fn git_commit(repo: &git2::Repository, message: &str) -> Result<()> {
+    let mut index = repo.index()?;
+    index.add_all(["*"], git2::IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let signature = git2::Signature::now("Test", "test@example.com")?;
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parent.iter().collect::<Vec<_>>(),
+    )?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_changed_items_since() -> Result<()> {
+    let (service, temp_dir) = create_test_service()?;
+
+    let test_crate_dir = TempDir::new()?;
+    let root = test_crate_dir.path();
+    std::fs::write(
+        root.join("Cargo.toml"),
+        r#"
+[package]
+name = "test-changes"
+version = "0.1.0"
+edition = "2021"
+    "#,
+    )?;
+    std::fs::create_dir(root.join("src"))?;
+    std::fs::write(
+        root.join("src/lib.rs"),
+        "pub mod parser;\npub struct Root;\n",
+    )?;
+    std::fs::write(root.join("src/parser.rs"), "pub struct Parser;\n")?;
+    let repo = git2::Repository::init(root)?;
+    git_commit(&repo, "Initial commit")?;
+    std::fs::write(
+        root.join("src/parser.rs"),
+        "pub struct Parser;\npub fn parse() -> Parser { Parser }\n",
+    )?;
+    git_commit(&repo, "Add parse")?;
+
+    cache_local_crate_at(&service, "test-changes", root).await?;
+
+    let params = || GetChangedItemsSinceParams {
+        crate_name: "test-changes".to_string(),
+        version: "0.1.0".to_string(),
+        since: "HEAD~1".to_string(),
+        until: None,
         member: None,
     };
 
-    let response = service.list_crate_items(Parameters(params)).await;
-    let output: ListCrateItemsOutput = serde_json::from_str(&response)?;
+    // Crates cached without their git history cannot be diffed
+    let response = service.get_changed_items_since(Parameters(params())).await;
+    let error: DocsErrorOutput = serde_json::from_str(&response)?;
+    assert!(
+        error.error.contains("--keep-git-history"),
+        "{}",
+        error.error
+    );
 
-    assert!(!output.items.is_empty(), "Should have items");
-    assert_eq!(output.pagination.limit, 50, "Limit should match request");
-    assert_eq!(output.pagination.offset, 0, "Offset should match request");
+    // Keep the history where a clone of a GitHub source keeps it
+    let version_dir = temp_dir.path().join("crates/test-changes/0.1.0");
+    std::fs::rename(root.join(".git"), version_dir.join("git"))?;
+
+    let response = service.get_changed_items_since(Parameters(params())).await;
+    let output: ChangedItemsOutput = serde_json::from_str(&response)?;
+    assert_eq!(output.until, "HEAD");
+    assert_eq!(output.changed_files, ["src/parser.rs"]);
+    let names: Vec<&str> = output
+        .changed_items
+        .iter()
+        .map(|item| item.name.as_str())
+        .collect();
+    assert!(names.contains(&"Parser"), "Missing changed item: {names:?}");
+    assert!(names.contains(&"parse"), "Missing changed item: {names:?}");
+    assert!(!names.contains(&"Root"), "Unchanged item listed: {names:?}");
+
+    Ok(())
+}
+
+/// Cache a local crate `test-visibility` at `version` with `lib_rs` as its source
+async fn cache_visibility_crate(
+    service: &RustDocsService,
+    version: &str,
+    lib_rs: &str,
+) -> Result<()> {
+    let manifest = format!(
+        r#"
+[package]
+name = "test-visibility"
+version = "{version}"
+edition = "2021"
+"#
+    );
+    cache_local_crate_with_manifest(service, "test-visibility", &manifest, lib_rs).await
+}
+
+#[tokio::test]
+async fn test_diff_visibility() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    cache_visibility_crate(
+        &service,
+        "0.1.0",
+        r#"
+pub struct Kept;
+pub struct Hidden;
+struct Exposed;
+pub struct Widget;
+impl Widget {
+    fn build() {}
+    pub fn old() {}
+}
+"#,
+    )
+    .await?;
+    cache_visibility_crate(
+        &service,
+        "0.2.0",
+        r#"
+pub struct Kept;
+pub(crate) struct Hidden;
+pub struct Exposed;
+pub struct Widget;
+impl Widget {
+    pub fn build() {}
+    fn old() {}
+}
+pub struct Added;
+"#,
+    )
+    .await?;
+
+    let params = DiffVisibilityParams {
+        crate_name: "test-visibility".to_string(),
+        version_a: "0.1.0".to_string(),
+        version_b: "0.2.0".to_string(),
+        member: None,
+    };
+    let response = service.diff_visibility(Parameters(params)).await;
+    let output: DiffVisibilityOutput = serde_json::from_str(&response)
+        .with_context(|| format!("Unexpected response: {response}"))?;
+
+    let paths = |changes: &[rust_docs_mcp::docs::outputs::VisibilityChange]| {
+        changes
+            .iter()
+            .map(|change| change.path.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        paths(&output.newly_public),
+        ["test_visibility::Exposed", "test_visibility::Widget::build"]
+    );
+    assert_eq!(
+        paths(&output.newly_private),
+        ["test_visibility::Hidden", "test_visibility::Widget::old"]
+    );
+    assert_eq!(output.newly_private[0].to, "crate");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rustdoc_args_cfg_docsrs() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let config = ServiceConfig {
+        rustdoc_args: rust_docs_mcp::rustdoc::parse_rustdoc_args("--cfg docsrs")?,
+        ..ServiceConfig::default()
+    };
+    let service = RustDocsService::with_config(Some(temp_dir.path().to_path_buf()), config)?;
+
+    cache_local_crate(
+        &service,
+        "test-docsrs",
+        r#"
+pub struct Always;
+
+/// Only documented on docs.rs
+#[cfg(docsrs)]
+pub struct DocsOnly;
+
+#[cfg(not(docsrs))]
+pub struct BuildOnly;
+"#,
+    )
+    .await?;
 
-    // Test with kind filter
     let params = ListItemsParams {
-        crate_name: "semver".to_string(),
-        version: SEMVER_VERSION.to_string(),
-        kind_filter: Some("struct".to_string()),
-        limit: Some(10),
+        crate_name: "test-docsrs".to_string(),
+        version: "0.1.0".to_string(),
+        kind_filter: None,
+        limit: None,
         offset: None,
         member: None,
+        include_private: None,
+        include_hidden: None,
+        target: None,
+        group_by_module: None,
+        max_depth: None,
     };
-
     let response = service.list_crate_items(Parameters(params)).await;
     let output: ListCrateItemsOutput = serde_json::from_str(&response)?;
+    let names: Vec<&str> = output.items.iter().map(|item| item.name.as_str()).collect();
 
-    // Check all items are structs
-    for item in &output.items {
-        assert_eq!(item.kind, "struct", "All items should be structs");
-    }
+    // Items gated on docsrs are documented, the ones excluded from docs.rs are not
+    assert!(names.contains(&"Always"), "Missing item: {names:?}");
+    assert!(
+        names.contains(&"DocsOnly"),
+        "Missing docsrs item: {names:?}"
+    );
+    assert!(
+        !names.contains(&"BuildOnly"),
+        "Item excluded by --cfg docsrs documented: {names:?}"
+    );
 
     Ok(())
 }
 
 #[tokio::test]
-async fn test_search_items_preview() -> Result<()> {
+async fn test_docs_rs_metadata_applied() -> Result<()> {
     let (service, _temp_dir) = create_test_service()?;
-    setup_test_crate(&service).await?;
 
-    // Test basic preview search
-    let params = SearchItemsPreviewParams {
-        crate_name: "semver".to_string(),
-        version: SEMVER_VERSION.to_string(),
-        pattern: "Version".to_string(),
-        limit: Some(10),
-        offset: None,
+    cache_local_crate_with_manifest(
+        &service,
+        "test-docsrs-metadata",
+        r#"
+[package]
+name = "test-docsrs-metadata"
+version = "0.1.0"
+edition = "2021"
+
+[features]
+default = ["std"]
+std = []
+extra = []
+
+[package.metadata.docs.rs]
+features = ["extra"]
+no-default-features = true
+rustdoc-args = ["--cfg", "docsrs", "--html-in-header", "header.html"]
+"#,
+        r#"
+pub struct Always;
+
+#[cfg(docsrs)]
+pub struct DocsOnly;
+
+#[cfg(feature = "extra")]
+pub struct Extra;
+
+#[cfg(feature = "std")]
+pub struct WithStd;
+"#,
+    )
+    .await?;
+
+    let params = ListItemsParams {
+        crate_name: "test-docsrs-metadata".to_string(),
+        version: "0.1.0".to_string(),
         kind_filter: None,
-        path_filter: None,
+        limit: None,
+        offset: None,
         member: None,
+        include_private: None,
+        include_hidden: None,
+        target: None,
+        group_by_module: None,
+        max_depth: None,
     };
+    let response = service.list_crate_items(Parameters(params)).await;
+    let output: ListCrateItemsOutput = serde_json::from_str(&response)?;
+    let names: Vec<&str> = output.items.iter().map(|item| item.name.as_str()).collect();
 
-    let response = service.search_items_preview(Parameters(params)).await;
-    let output: SearchItemsPreviewOutput = serde_json::from_str(&response)?;
-
+    // The crate is documented the way docs.rs builds it
+    assert!(names.contains(&"Always"), "Missing item: {names:?}");
     assert!(
-        !output.items.is_empty(),
-        "Should find items matching 'Version'"
+        names.contains(&"DocsOnly"),
+        "Missing docsrs item: {names:?}"
+    );
+    assert!(names.contains(&"Extra"), "Missing feature item: {names:?}");
+    assert!(
+        !names.contains(&"WithStd"),
+        "Default feature enabled despite no-default-features: {names:?}"
     );
 
-    // Verify preview format (only id, name, kind, path)
-    if let Some(item) = output.items.first() {
-        assert!(!item.id.is_empty(), "Item should have id");
-        assert!(!item.name.is_empty(), "Item should have name");
-        assert!(!item.kind.is_empty(), "Item should have kind");
-        assert!(!item.path.is_empty(), "Item should have path");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_item_docs_trait_default() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    // A trait method with docs, implemented by a type without its own docs
+    cache_local_crate(
+        &service,
+        "test-trait-docs",
+        r#"
+pub trait Describe {
+    /// Returns a human readable description.
+    fn describe(&self) -> String;
+}
+
+pub struct Widget;
+
+impl Describe for Widget {
+    fn describe(&self) -> String {
+        "widget".to_string()
     }
+}
+"#,
+    )
+    .await?;
 
-    // Test with filters
-    let params = SearchItemsPreviewParams {
-        crate_name: "semver".to_string(),
-        version: SEMVER_VERSION.to_string(),
-        pattern: "new".to_string(),
-        limit: Some(5),
+    let params = SearchItemsParams {
+        crate_name: "test-trait-docs".to_string(),
+        version: "0.1.0".to_string(),
+        pattern: "describe".to_string(),
+        limit: None,
         offset: None,
         kind_filter: Some("function".to_string()),
         path_filter: None,
         member: None,
+        group_by: None,
+        include_private: None,
+        include_hidden: None,
+        target: None,
+        dedupe_by_id: None,
+    };
+    let response = service.search_items(Parameters(params)).await;
+    let output: SearchItemsOutput = serde_json::from_str(&response)?;
+    let impl_method = output
+        .items
+        .iter()
+        .find(|item| item.name == "describe" && item.docs.is_none())
+        .context("Undocumented impl method should be found")?;
+
+    let params = GetItemDocsParams {
+        crate_name: "test-trait-docs".to_string(),
+        version: "0.1.0".to_string(),
+        item_id: impl_method.id.parse()?,
+        member: None,
+        target: None,
+        strip_doctest_scaffolding: None,
+        format: None,
     };
+    let response = service.get_item_docs(Parameters(params)).await;
+    let output: GetItemDocsOutput = serde_json::from_str(&response)?;
+    assert_eq!(
+        output.documentation.as_deref(),
+        Some("Returns a human readable description.")
+    );
+    assert_eq!(output.source.as_deref(), Some("trait_default"));
 
+    // Other undocumented items have no docs to inherit
+    let params = SearchItemsPreviewParams {
+        crate_name: "test-trait-docs".to_string(),
+        version: "0.1.0".to_string(),
+        pattern: "Widget".to_string(),
+        limit: None,
+        offset: None,
+        kind_filter: Some("struct".to_string()),
+        path_filter: None,
+        member: None,
+        target: None,
+        dedupe_by_id: None,
+        compact: None,
+    };
     let response = service.search_items_preview(Parameters(params)).await;
     let output: SearchItemsPreviewOutput = serde_json::from_str(&response)?;
+    let widget = output.items.first().context("Widget should be found")?;
 
-    // Check all items are functions
-    for item in &output.items {
-        assert_eq!(item.kind, "function", "All items should be functions");
-    }
+    let params = GetItemDocsParams {
+        crate_name: "test-trait-docs".to_string(),
+        version: "0.1.0".to_string(),
+        item_id: widget.id.parse()?,
+        member: None,
+        target: None,
+        strip_doctest_scaffolding: None,
+        format: None,
+    };
+    let response = service.get_item_docs(Parameters(params)).await;
+    let output: GetItemDocsOutput = serde_json::from_str(&response)?;
+    assert_eq!(output.documentation, None);
+    assert_eq!(output.source, None);
+    assert_eq!(
+        output.message.as_deref(),
+        Some("No documentation available for this item")
+    );
 
     Ok(())
 }
 
 #[tokio::test]
-async fn test_search_items_full() -> Result<()> {
+async fn test_strip_doctest_scaffolding() -> Result<()> {
     let (service, _temp_dir) = create_test_service()?;
-    setup_test_crate(&service).await?;
 
-    // Test full search with complete documentation
+    // A function whose example is wrapped in hidden imports and a hidden main
+    cache_local_crate(
+        &service,
+        "test-doctest-docs",
+        r#"
+/// Adds two numbers.
+///
+/// ```
+/// # use test_doctest_docs::add;
+/// # fn main() {
+/// let sum = add(1, 2);
+/// assert_eq!(sum, 3);
+/// # }
+/// ```
+pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+"#,
+    )
+    .await?;
+
     let params = SearchItemsParams {
-        crate_name: "semver".to_string(),
-        version: SEMVER_VERSION.to_string(),
-        pattern: "Version".to_string(),
-        limit: Some(5),
+        crate_name: "test-doctest-docs".to_string(),
+        version: "0.1.0".to_string(),
+        pattern: "add".to_string(),
+        limit: None,
         offset: None,
-        kind_filter: Some("struct".to_string()),
+        kind_filter: Some("function".to_string()),
         path_filter: None,
         member: None,
+        group_by: None,
+        include_private: None,
+        include_hidden: None,
+        target: None,
+        dedupe_by_id: None,
     };
-
     let response = service.search_items(Parameters(params)).await;
     let output: SearchItemsOutput = serde_json::from_str(&response)?;
+    let item_id = output
+        .items
+        .first()
+        .context("add should be found")?
+        .id
+        .parse()?;
+
+    let docs_params = |strip_doctest_scaffolding| GetItemDocsParams {
+        crate_name: "test-doctest-docs".to_string(),
+        version: "0.1.0".to_string(),
+        item_id,
+        member: None,
+        target: None,
+        strip_doctest_scaffolding,
+        format: None,
+    };
+    let response = service.get_item_docs(Parameters(docs_params(None))).await;
+    let output: GetItemDocsOutput = serde_json::from_str(&response)?;
+    let docs = output.documentation.context("add should be documented")?;
+    assert!(docs.contains("# use test_doctest_docs::add;"));
+
+    let response = service
+        .get_item_docs(Parameters(docs_params(Some(true))))
+        .await;
+    let output: GetItemDocsOutput = serde_json::from_str(&response)?;
+    assert_eq!(
+        output.documentation.as_deref(),
+        Some("Adds two numbers.\n\n```\nlet sum = add(1, 2);\nassert_eq!(sum, 3);\n```")
+    );
 
-    assert!(!output.items.is_empty(), "Should find items");
+    // The doc comment above the function is part of the context lines
+    let params = GetItemSourceParams {
+        crate_name: "test-doctest-docs".to_string(),
+        version: "0.1.0".to_string(),
+        item_id,
+        context_lines: Some(10),
+        member: None,
+        target: None,
+        resolve_references: None,
+        strip_doctest_scaffolding: Some(true),
+    };
+    let response = service.get_item_source(Parameters(params)).await;
+    let output: GetItemSourceOutput = serde_json::from_str(&response)?;
+    let GetItemSourceOutput::Success(source) = output else {
+        anyhow::bail!("Failed to get source: {response}");
+    };
+    assert!(source.code.contains("/// let sum = add(1, 2);"));
+    assert!(source.code.contains("pub fn add(a: u32, b: u32) -> u32 {"));
+    assert!(!source.code.contains("# fn main()"));
+    assert!(!source.code.contains("# use"));
 
-    // Verify full format includes documentation
-    if let Some(item) = output.items.first() {
-        assert!(!item.id.is_empty(), "Item should have id");
-        assert!(!item.name.is_empty(), "Item should have name");
-        assert_eq!(item.kind, "struct", "Item should be a struct");
-        // Full search may include documentation
-        // Note: docs field is Optional, so it's OK if it's None
-    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_item_details_sealed_trait() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    // Format is sealed by a supertrait in a private module, Plugin is not
+    cache_local_crate(
+        &service,
+        "test-sealed-trait",
+        r#"
+mod private {
+    pub trait Sealed {}
+}
+
+/// An output format, implemented only by this crate.
+pub trait Format: private::Sealed {}
+
+/// A binary format, sealed through Format.
+pub trait BinaryFormat: Format {}
+
+/// A plugin that other crates can implement.
+pub trait Plugin: Clone {}
+
+pub struct Json;
+
+impl private::Sealed for Json {}
+impl Format for Json {}
+"#,
+    )
+    .await?;
+
+    let details = |path: &str| GetItemDetailsParams {
+        crate_name: "test-sealed-trait".to_string(),
+        version: "0.1.0".to_string(),
+        item_id: None,
+        path: Some(path.to_string()),
+        member: None,
+        fields: None,
+        target: None,
+        resolve_field_types: None,
+        format: None,
+    };
+
+    let format = get_item_details_success(&service, details("test_sealed_trait::Format")).await?;
+    assert!(format.sealed, "Format should be detected as sealed");
+    let note = format
+        .sealed_note
+        .context("Sealed traits should explain why")?;
+    assert!(
+        note.contains("Sealed"),
+        "Note should name the supertrait: {note}"
+    );
+
+    // Sealing carries over to subtraits
+    let binary =
+        get_item_details_success(&service, details("test_sealed_trait::BinaryFormat")).await?;
+    assert!(
+        binary.sealed,
+        "BinaryFormat should be sealed through Format"
+    );
+    assert!(
+        binary
+            .sealed_note
+            .is_some_and(|note| note.contains("through `"))
+    );
+
+    // Supertraits from other crates do not seal a trait
+    let plugin = get_item_details_success(&service, details("test_sealed_trait::Plugin")).await?;
+    assert!(!plugin.sealed);
+    assert!(plugin.sealed_note.is_none());
 
     Ok(())
 }
 
 #[tokio::test]
-async fn test_get_item_details() -> Result<()> {
+async fn test_cache_crate_with_renamed_lib_target() -> Result<()> {
     let (service, _temp_dir) = create_test_service()?;
-    setup_test_crate(&service).await?;
 
-    let item_id = get_test_item_id(&service).await?;
+    // The lib target is not named after the package
+    cache_local_crate_with_manifest(
+        &service,
+        "test-renamed-lib",
+        r#"
+[package]
+name = "test-renamed-lib"
+version = "0.1.0"
+edition = "2021"
+
+[lib]
+name = "renamed_core"
+"#,
+        "/// Adds one.\npub fn add_one(x: u32) -> u32 {\n    x + 1\n}\n",
+    )
+    .await?;
+
+    let params = SearchItemsParams {
+        crate_name: "test-renamed-lib".to_string(),
+        version: "0.1.0".to_string(),
+        pattern: "add_one".to_string(),
+        limit: None,
+        offset: None,
+        kind_filter: Some("function".to_string()),
+        path_filter: None,
+        member: None,
+        group_by: None,
+        include_private: None,
+        include_hidden: None,
+        target: None,
+        dedupe_by_id: None,
+    };
+    let response = service.search_items(Parameters(params)).await;
+    let output: SearchItemsOutput = serde_json::from_str(&response)?;
+    let item = output
+        .items
+        .iter()
+        .find(|item| item.name == "add_one")
+        .context("Function from the renamed lib should be found")?;
+    assert_eq!(item.path.first().map(String::as_str), Some("renamed_core"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cache_proc_macro_crate() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    cache_local_crate_with_manifest(
+        &service,
+        "test-hello-derive",
+        r#"
+[package]
+name = "test-hello-derive"
+version = "0.1.0"
+edition = "2021"
+
+[lib]
+proc-macro = true
+"#,
+        r#"
+use proc_macro::TokenStream;
 
-    // Test getting complete item details
-    let params = GetItemDetailsParams {
-        crate_name: "semver".to_string(),
-        version: SEMVER_VERSION.to_string(),
-        item_id,
+/// Derives nothing.
+#[proc_macro_derive(Hello)]
+pub fn derive_hello(_input: TokenStream) -> TokenStream {
+    TokenStream::new()
+}
+"#,
+    )
+    .await?;
+
+    let search = |target: Option<&str>| SearchItemsParams {
+        crate_name: "test-hello-derive".to_string(),
+        version: "0.1.0".to_string(),
+        pattern: "Hello".to_string(),
+        limit: None,
+        offset: None,
+        kind_filter: Some("proc_macro".to_string()),
+        path_filter: None,
         member: None,
+        group_by: None,
+        include_private: None,
+        include_hidden: None,
+        target: target.map(str::to_string),
+        dedupe_by_id: None,
     };
+    let response = service.search_items(Parameters(search(None))).await;
+    let output: SearchItemsOutput = serde_json::from_str(&response)?;
+    assert!(
+        output.items.iter().any(|item| item.name == "Hello"),
+        "Derive macro should be documented: {response}"
+    );
 
-    let response = service.get_item_details(Parameters(params)).await;
-    let output: GetItemDetailsOutput = serde_json::from_str(&response)?;
-
-    assert!(output.is_success(), "Should be a success response");
-
-    if let GetItemDetailsOutput::Success(detailed_item) = output {
-        // Should contain detailed information about the item
-        assert!(!detailed_item.info.id.is_empty(), "Details should have id");
-        assert!(
-            !detailed_item.info.name.is_empty(),
-            "Details should have name"
-        );
-        assert!(
-            !detailed_item.info.kind.is_empty(),
-            "Details should have kind"
-        );
-    }
+    // Proc macros only exist for the host, so other targets are explained
+    let response = service
+        .search_items(Parameters(search(Some("wasm32-unknown-unknown"))))
+        .await;
+    let output: DocsErrorOutput = serde_json::from_str(&response)?;
+    assert!(
+        output
+            .error
+            .contains("proc-macro crate 'test-hello-derive'")
+    );
+    assert!(output.error.contains("derive(Hello)"));
 
     Ok(())
 }
@@ -996,6 +3612,9 @@ async fn test_get_item_docs_and_source() -> Result<()> {
         version: SEMVER_VERSION.to_string(),
         item_id,
         member: None,
+        target: None,
+        strip_doctest_scaffolding: None,
+        format: None,
     };
 
     let docs_response = service.get_item_docs(Parameters(docs_params)).await;
@@ -1017,6 +3636,9 @@ async fn test_get_item_docs_and_source() -> Result<()> {
         item_id,
         context_lines: Some(5),
         member: None,
+        target: None,
+        resolve_references: None,
+        strip_doctest_scaffolding: None,
     };
 
     let source_response = service.get_item_source(Parameters(source_params)).await;
@@ -1040,6 +3662,104 @@ async fn test_get_item_docs_and_source() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_get_docs_for_names() -> Result<()> {
+    let (service, temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let params = || GetDocsForNamesParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        names: vec![
+            "semver::Version".to_string(),
+            "semver::Version::parse".to_string(),
+            "semver::DoesNotExist".to_string(),
+        ],
+        member: None,
+        target: None,
+    };
+
+    let response = service.get_docs_for_names(Parameters(params())).await;
+    let output: GetDocsForNamesOutput = serde_json::from_str(&response)?;
+    assert!(output.omitted.is_empty());
+
+    let version = &output.docs["semver::Version"];
+    assert!(version.id.is_some());
+    assert!(version.documentation.is_some(), "{version:?}");
+    assert!(output.docs["semver::Version::parse"].error.is_none());
+    let missing = &output.docs["semver::DoesNotExist"];
+    assert!(missing.documentation.is_none());
+    assert!(
+        missing
+            .error
+            .as_deref()
+            .is_some_and(|error| error.contains("No item found"))
+    );
+
+    // With a tiny response limit only the first name fits, the rest are listed
+    let config = ServiceConfig {
+        max_response_bytes: 1,
+        ..ServiceConfig::default()
+    };
+    let small_service = RustDocsService::with_config(Some(temp_dir.path().to_path_buf()), config)?;
+    let response = small_service.get_docs_for_names(Parameters(params())).await;
+    let output: GetDocsForNamesOutput = serde_json::from_str(&response)?;
+    assert_eq!(output.docs.len(), 1);
+    assert!(output.docs.contains_key("semver::Version"));
+    assert_eq!(
+        output.omitted,
+        ["semver::Version::parse", "semver::DoesNotExist"]
+    );
+    assert!(output.warning.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_item_source_resolves_references() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    // `Version` has `pre: Prerelease` and `build: BuildMetadata` fields
+    let item_id = get_test_item_id(&service).await?;
+    let params = |resolve_references| GetItemSourceParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        item_id,
+        context_lines: Some(0),
+        member: None,
+        target: None,
+        resolve_references,
+        strip_doctest_scaffolding: None,
+    };
+
+    let response = service.get_item_source(Parameters(params(None))).await;
+    let GetItemSourceOutput::Success(source_info) = serde_json::from_str(&response)? else {
+        panic!("Expected source: {response}");
+    };
+    assert!(source_info.references.is_none(), "Off by default");
+
+    let response = service
+        .get_item_source(Parameters(params(Some(true))))
+        .await;
+    let GetItemSourceOutput::Success(source_info) = serde_json::from_str(&response)? else {
+        panic!("Expected source: {response}");
+    };
+    let references = source_info.references.expect("references requested");
+    for name in ["Prerelease", "BuildMetadata"] {
+        let reference = references
+            .iter()
+            .find(|reference| reference.name == name)
+            .unwrap_or_else(|| panic!("{name} should be referenced: {response}"));
+        assert!(reference.filename.ends_with(".rs"));
+        assert!(reference.line > 0);
+    }
+    // Primitive field types have no definition to navigate to
+    assert!(!references.iter().any(|reference| reference.name == "u64"));
+
+    Ok(())
+}
+
 // ===== SEARCH TOOLS TESTS =====
 
 #[tokio::test]
@@ -1056,6 +3776,7 @@ async fn test_search_items_fuzzy() -> Result<()> {
         fuzzy_distance: Some(1),
         limit: Some(10),
         kind_filter: None,
+        path_filter: None,
         member: None,
     };
 
@@ -1076,6 +3797,7 @@ async fn test_search_items_fuzzy() -> Result<()> {
         fuzzy_distance: Some(0),
         limit: Some(5),
         kind_filter: Some("struct".to_string()),
+        path_filter: None,
         member: None,
     };
 
@@ -1092,6 +3814,178 @@ async fn test_search_items_fuzzy() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_search_items_fuzzy_path_filter() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let search = |path_filter: Option<&str>| SearchItemsFuzzyParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        query: "Versoin".to_string(),
+        fuzzy_enabled: Some(true),
+        fuzzy_distance: Some(1),
+        limit: Some(100),
+        kind_filter: None,
+        path_filter: path_filter.map(str::to_string),
+        member: None,
+    };
+
+    let response = service.search_items_fuzzy(Parameters(search(None))).await;
+    let unscoped: SearchItemsFuzzyOutput = serde_json::from_str(&response)?;
+    assert!(
+        unscoped
+            .results
+            .iter()
+            .any(|result| result.path == "semver::Version"),
+        "Unscoped search should find semver::Version: {:?}",
+        unscoped.results
+    );
+
+    let response = service
+        .search_items_fuzzy(Parameters(search(Some("semver::VersionReq"))))
+        .await;
+    let scoped: SearchItemsFuzzyOutput = serde_json::from_str(&response)?;
+    assert!(
+        !scoped.results.is_empty(),
+        "Scoped search should still find VersionReq items despite the typo"
+    );
+    assert!(
+        scoped
+            .results
+            .iter()
+            .all(|result| result.path.starts_with("semver::VersionReq")),
+        "Every result should be under the path prefix: {:?}",
+        scoped.results
+    );
+    assert!(scoped.results.len() < unscoped.results.len());
+    assert_eq!(scoped.total_results, scoped.results.len());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reindex() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let search = || SearchItemsFuzzyParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        query: "Version".to_string(),
+        fuzzy_enabled: Some(false),
+        fuzzy_distance: None,
+        limit: Some(100),
+        kind_filter: None,
+        path_filter: None,
+        member: None,
+    };
+
+    let response = service.search_items_fuzzy(Parameters(search())).await;
+    let before: SearchItemsFuzzyOutput = serde_json::from_str(&response)?;
+    assert!(
+        !before.results.is_empty(),
+        "Should find items before reindex"
+    );
+
+    let response = service.reindex().await;
+    let output: ReindexOutput = serde_json::from_str(&response)?;
+    assert_eq!(output.rebuilt, 1, "Should rebuild the semver index");
+    assert!(
+        output.failed.is_empty(),
+        "Unexpected failures: {:?}",
+        output.failed
+    );
+
+    // The index is rebuilt from scratch rather than appended to
+    let response = service.search_items_fuzzy(Parameters(search())).await;
+    let after: SearchItemsFuzzyOutput = serde_json::from_str(&response)?;
+    assert_eq!(
+        after.results.len(),
+        before.results.len(),
+        "Reindexing should not duplicate documents"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_prune_indexes() -> Result<()> {
+    let (service, temp_dir) = create_test_service()?;
+    let crates_dir = temp_dir.path().join("crates");
+
+    // A cached crate whose index is still in use
+    let kept = crates_dir.join("kept").join("1.0.0");
+    std::fs::create_dir_all(kept.join("search_index"))?;
+    std::fs::write(kept.join("docs.json"), "{}")?;
+    std::fs::write(kept.join("search_index").join("meta.json"), "{}")?;
+
+    // An index left behind by a removed crate
+    let ghost = crates_dir.join("ghost").join("0.1.0").join("search_index");
+    std::fs::create_dir_all(&ghost)?;
+    std::fs::write(ghost.join("meta.json"), "{}")?;
+
+    // An index left behind by a member dropped from the workspace
+    let member = kept.join("members").join("gone").join("search_index");
+    std::fs::create_dir_all(&member)?;
+    std::fs::write(member.join("meta.json"), "{}")?;
+
+    let response = service.prune_indexes().await;
+    let output: PruneIndexesOutput = serde_json::from_str(&response)?;
+    assert_eq!(output.status, "success");
+    assert_eq!(output.removed.len(), 2, "Unexpected removals: {response}");
+    assert!(output.reclaimed.bytes > 0);
+
+    assert!(kept.join("search_index").join("meta.json").exists());
+    assert!(!crates_dir.join("ghost").exists());
+    assert!(!kept.join("members").exists());
+
+    // Nothing is left to prune
+    let response = service.prune_indexes().await;
+    let output: PruneIndexesOutput = serde_json::from_str(&response)?;
+    assert!(output.removed.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_find_similar_items() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let params = FindSimilarItemsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        name: "Verson".to_string(), // Missing letter in "Version"
+        limit: None,
+        kind_filter: Some("struct".to_string()),
+        member: None,
+    };
+
+    let response = service.find_similar_items(Parameters(params)).await;
+    let output: FindSimilarItemsOutput = serde_json::from_str(&response)?;
+
+    assert_eq!(output.query, "Verson");
+    assert!(
+        output.suggestions.len() <= 5,
+        "Should return at most 5 suggestions"
+    );
+    let best = output
+        .suggestions
+        .first()
+        .context("Expected at least one suggestion")?;
+    assert_eq!(best.name, "Version");
+    assert!(
+        output
+            .suggestions
+            .windows(2)
+            .all(|pair| pair[0].score >= pair[1].score),
+        "Suggestions should be sorted closest first"
+    );
+
+    Ok(())
+}
+
 // ===== ANALYSIS TOOLS TESTS =====
 
 #[tokio::test]
@@ -1120,43 +4014,248 @@ async fn test_structure() -> Result<()> {
         max_depth: Some(3),
     };
 
-    let response = service.structure(Parameters(params)).await;
-    let output: StructureOutput = serde_json::from_str(&response)?;
+    let response = service.structure(Parameters(params)).await;
+    let output: StructureOutput = serde_json::from_str(&response)?;
+
+    assert!(output.is_success(), "Structure analysis should succeed");
+    assert_eq!(output.status, "success", "Status should be success");
+    assert!(!output.message.is_empty(), "Should have a message");
+    assert!(!output.tree.name.is_empty(), "Tree should have a name");
+    assert!(!output.tree.kind.is_empty(), "Tree should have a kind");
+
+    // Items defined in the crate point at their source file
+    let version_node = output
+        .tree
+        .children
+        .iter()
+        .flatten()
+        .find(|node| node.name == "Version")
+        .context("Expected a Version node")?;
+    let source = version_node
+        .source
+        .as_ref()
+        .context("Version should have a source location")?;
+    assert!(source.filename.starts_with("src/"));
+    assert!(source.line_start > 0 && source.line_start <= source.line_end);
+
+    // Test with filtering options
+    let params = AnalyzeCrateStructureParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        member: None,
+        lib: Some(true),
+        bin: None,
+        no_default_features: None,
+        all_features: None,
+        features: None,
+        target: None,
+        cfg_test: None,
+        no_fns: Some(true), // Filter out functions
+        no_traits: None,
+        no_types: None,
+        sort_by: Some("name".to_string()),
+        sort_reversed: None,
+        focus_on: None,
+        max_depth: Some(2),
+    };
+
+    let response = service.structure(Parameters(params)).await;
+    let output: StructureOutput = serde_json::from_str(&response)?;
+
+    assert!(
+        output.is_success(),
+        "Filtered structure analysis should succeed"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_structure_with_features() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    // A module that only exists with the "extra" feature
+    cache_local_crate_with_manifest(
+        &service,
+        "test-features",
+        r#"
+[package]
+name = "test-features"
+version = "0.1.0"
+edition = "2021"
+
+[features]
+extra = []
+"#,
+        "pub mod always {}\n\n#[cfg(feature = \"extra\")]\npub mod gated {}\n",
+    )
+    .await?;
+
+    let module_names = |features: Option<Vec<String>>| {
+        let service = &service;
+        async move {
+            let params = AnalyzeCrateStructureParams {
+                crate_name: "test-features".to_string(),
+                version: "0.1.0".to_string(),
+                member: None,
+                lib: Some(true),
+                bin: None,
+                no_default_features: None,
+                all_features: None,
+                features,
+                target: None,
+                cfg_test: None,
+                no_fns: None,
+                no_traits: None,
+                no_types: None,
+                sort_by: None,
+                sort_reversed: None,
+                focus_on: None,
+                max_depth: None,
+            };
+            let response = service.structure(Parameters(params)).await;
+            let output: StructureOutput = serde_json::from_str(&response)?;
+            assert!(output.is_success(), "Structure analysis should succeed");
+            Ok::<_, anyhow::Error>(
+                output
+                    .tree
+                    .children
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|node| node.name)
+                    .collect::<Vec<_>>(),
+            )
+        }
+    };
+
+    let without_feature = module_names(None).await?;
+    assert!(without_feature.contains(&"always".to_string()));
+    assert!(
+        !without_feature.contains(&"gated".to_string()),
+        "Feature-gated module should be hidden: {without_feature:?}"
+    );
+
+    let with_feature = module_names(Some(vec!["extra".to_string()])).await?;
+    assert!(with_feature.contains(&"always".to_string()));
+    assert!(
+        with_feature.contains(&"gated".to_string()),
+        "Feature-gated module should appear with the feature: {with_feature:?}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_crate_targets() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let params = ListCrateTargetsParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        member: None,
+    };
+
+    let response = service.list_crate_targets(Parameters(params)).await;
+    let output: ListCrateTargetsOutput = serde_json::from_str(&response)?;
+
+    let lib = output
+        .targets
+        .iter()
+        .find(|t| t.kind == "lib")
+        .context("semver should have a lib target")?;
+    assert_eq!(lib.name, "semver");
+    assert_eq!(lib.path, "src/lib.rs");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_crate_edition() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let params = GetCrateEditionParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        member: None,
+    };
+
+    let response = service.get_crate_edition(Parameters(params)).await;
+    let output: GetCrateEditionOutput = serde_json::from_str(&response)?;
+
+    assert_eq!(output.edition, "2018");
+    assert!(!output.edition_2024);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_crate_source_tree() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let params = GetCrateSourceTreeParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        member: None,
+        include_stats: Some(true),
+    };
+
+    let response = service.get_crate_source_tree(Parameters(params)).await;
+    let output: GetSourceTreeOutput = serde_json::from_str(&response)?;
+
+    assert_eq!(output.files[0].path, "Cargo.toml");
+    let lib = output
+        .files
+        .iter()
+        .find(|file| file.path == "src/lib.rs")
+        .context("semver should have src/lib.rs")?;
+    assert!(lib.size.is_some_and(|size| size > 0));
+    assert!(lib.lines.is_some_and(|lines| lines > 0));
+    // Only Cargo.toml, build.rs, src/ and examples/ are listed
+    assert!(output.files.iter().all(|file| {
+        file.path == "Cargo.toml"
+            || file.path == "build.rs"
+            || file.path.starts_with("src/")
+            || file.path.starts_with("examples/")
+    }));
+    assert!(!output.truncated);
 
-    assert!(output.is_success(), "Structure analysis should succeed");
-    assert_eq!(output.status, "success", "Status should be success");
-    assert!(!output.message.is_empty(), "Should have a message");
-    assert!(!output.tree.name.is_empty(), "Tree should have a name");
-    assert!(!output.tree.kind.is_empty(), "Tree should have a kind");
+    Ok(())
+}
 
-    // Test with filtering options
-    let params = AnalyzeCrateStructureParams {
+#[tokio::test]
+async fn test_get_usage_examples() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let params = GetUsageExamplesParams {
         crate_name: "semver".to_string(),
         version: SEMVER_VERSION.to_string(),
+        item_name: "Version".to_string(),
         member: None,
-        lib: Some(true),
-        bin: None,
-        no_default_features: None,
-        all_features: None,
-        features: None,
-        target: None,
-        cfg_test: None,
-        no_fns: Some(true), // Filter out functions
-        no_traits: None,
-        no_types: None,
-        sort_by: Some("name".to_string()),
-        sort_reversed: None,
-        focus_on: None,
-        max_depth: Some(2),
+        context_lines: Some(1),
+        limit: Some(3),
     };
 
-    let response = service.structure(Parameters(params)).await;
-    let output: StructureOutput = serde_json::from_str(&response)?;
+    let response = service.get_usage_examples(Parameters(params)).await;
+    let output: GetUsageExamplesOutput = serde_json::from_str(&response)?;
 
-    assert!(
-        output.is_success(),
-        "Filtered structure analysis should succeed"
-    );
+    // semver's integration tests construct versions
+    assert!(!output.usages.is_empty(), "No usages found: {response}");
+    assert!(output.usages.len() <= 3);
+    for usage in &output.usages {
+        assert!(
+            ["examples/", "tests/", "src/"]
+                .iter()
+                .any(|dir| usage.file.starts_with(dir)),
+            "Unexpected file: {}",
+            usage.file
+        );
+        assert!(usage.snippet.contains("Version"));
+        assert!(usage.snippet.lines().count() <= 3);
+    }
 
     Ok(())
 }
@@ -1173,7 +4272,10 @@ async fn test_get_dependencies() -> Result<()> {
         crate_name: "semver".to_string(),
         version: SEMVER_VERSION.to_string(),
         include_tree: Some(false),
+        max_depth: None,
         filter: None,
+        kind_filter: None,
+        include_resolved_features: None,
         member: None,
     };
 
@@ -1193,7 +4295,10 @@ async fn test_get_dependencies() -> Result<()> {
         crate_name: "semver".to_string(),
         version: SEMVER_VERSION.to_string(),
         include_tree: Some(true),
+        max_depth: None,
         filter: None,
+        kind_filter: None,
+        include_resolved_features: None,
         member: None,
     };
 
@@ -1206,12 +4311,35 @@ async fn test_get_dependencies() -> Result<()> {
         "Should include dependency tree when requested"
     );
 
+    // With max_depth, the tree is trimmed to the direct dependencies
+    let params = GetDependenciesParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        include_tree: Some(true),
+        max_depth: Some(1),
+        filter: None,
+        kind_filter: None,
+        include_resolved_features: None,
+        member: None,
+    };
+
+    let response = service.get_dependencies(Parameters(params)).await;
+    let output: GetDependenciesOutput = serde_json::from_str(&response)?;
+    let tree = output.dependency_tree.context("Missing dependency tree")?;
+    assert_eq!(tree["name"], "semver");
+    for dependency in tree["dependencies"].as_array().into_iter().flatten() {
+        assert!(dependency.get("dependencies").is_none(), "{dependency}");
+    }
+
     // Test with filter
     let params = GetDependenciesParams {
         crate_name: "semver".to_string(),
         version: SEMVER_VERSION.to_string(),
         include_tree: Some(false),
+        max_depth: None,
         filter: Some("serde".to_string()),
+        kind_filter: None,
+        include_resolved_features: Some(true),
         member: None,
     };
 
@@ -1228,6 +4356,40 @@ async fn test_get_dependencies() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_get_dependencies_served_from_saved_metadata() -> Result<()> {
+    let (service, temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let params = || GetDependenciesParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        include_tree: Some(false),
+        max_depth: None,
+        filter: None,
+        kind_filter: None,
+        include_resolved_features: None,
+        member: None,
+    };
+    let crate_dir = temp_dir.path().join("crates/semver").join(SEMVER_VERSION);
+    let metadata_path = crate_dir.join("dependencies.json");
+
+    // Metadata lost from the cache is computed again and saved
+    std::fs::remove_file(&metadata_path)?;
+    let response = service.get_dependencies(Parameters(params())).await;
+    let first: GetDependenciesOutput = serde_json::from_str(&response)?;
+    assert!(metadata_path.exists());
+
+    // Later queries read the saved metadata without running cargo, which
+    // would fail without the manifest
+    std::fs::remove_file(crate_dir.join("source/Cargo.toml"))?;
+    let response = service.get_dependencies(Parameters(params())).await;
+    let second: GetDependenciesOutput = serde_json::from_str(&response)?;
+    assert_eq!(first, second);
+
+    Ok(())
+}
+
 // ===== METADATA TOOLS TESTS =====
 
 #[tokio::test]
@@ -1279,8 +4441,13 @@ async fn test_invalid_item_ids() -> Result<()> {
     let params = GetItemDetailsParams {
         crate_name: "semver".to_string(),
         version: SEMVER_VERSION.to_string(),
-        item_id: 999999, // Invalid ID
+        item_id: Some(999999), // Invalid ID
+        path: None,
         member: None,
+        fields: None,
+        target: None,
+        resolve_field_types: None,
+        format: None,
     };
 
     let response = service.get_item_details(Parameters(params)).await;
@@ -1295,6 +4462,9 @@ async fn test_invalid_item_ids() -> Result<()> {
         version: SEMVER_VERSION.to_string(),
         item_id: 999999,
         member: None,
+        target: None,
+        strip_doctest_scaffolding: None,
+        format: None,
     };
 
     let response = service.get_item_docs(Parameters(params)).await;
@@ -1310,6 +4480,9 @@ async fn test_invalid_item_ids() -> Result<()> {
         item_id: 999999,
         context_lines: Some(3),
         member: None,
+        target: None,
+        resolve_references: None,
+        strip_doctest_scaffolding: None,
     };
 
     let response = service.get_item_source(Parameters(params)).await;
@@ -1336,6 +4509,9 @@ async fn test_empty_search_results() -> Result<()> {
         kind_filter: None,
         path_filter: None,
         member: None,
+        target: None,
+        dedupe_by_id: None,
+        compact: None,
     };
 
     let response = service.search_items_preview(Parameters(params)).await;
@@ -1359,6 +4535,7 @@ async fn test_empty_search_results() -> Result<()> {
         fuzzy_distance: Some(1),
         limit: Some(10),
         kind_filter: None,
+        path_filter: None,
         member: None,
     };
 
@@ -1415,6 +4592,9 @@ async fn test_cache_bevy_with_feature_fallback() -> Result<()> {
         path: None,
         members: None,
         update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
     };
 
     // Use a longer timeout for bevy as it's a large crate
@@ -1467,6 +4647,9 @@ async fn test_step_tracking() -> Result<()> {
         path: None,
         members: None,
         update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
     };
 
     let response = service.cache_crate(Parameters(params)).await;
@@ -1492,6 +4675,7 @@ async fn test_step_tracking() -> Result<()> {
             status_filter: None,
             cancel: false,
             clear: false,
+            retry_failed: false,
         };
 
         let response = service.cache_operations(Parameters(check_params)).await;
@@ -1553,3 +4737,568 @@ async fn test_step_tracking() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_server_info() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let config = ServiceConfig {
+        max_response_bytes: 50_000,
+        ..ServiceConfig::default()
+    };
+    let service = RustDocsService::with_config(Some(temp_dir.path().to_path_buf()), config)?;
+
+    let response = service.server_info().await;
+    let output: ServerInfoOutput = serde_json::from_str(&response)?;
+    assert_eq!(output.name, "rust-docs-mcp");
+    assert_eq!(output.version, env!("CARGO_PKG_VERSION"));
+    assert!(!output.protocol_version.is_empty());
+    assert!(
+        output
+            .cache_dir
+            .starts_with(&temp_dir.path().display().to_string())
+    );
+    assert!(output.cache_writable);
+    assert_eq!(output.cached_crates, 0);
+    assert_eq!(output.active_tasks, 0);
+    assert_eq!(output.max_response_bytes, 50_000);
+    assert_eq!(output.rustdoc_format_version, rustdoc_types::FORMAT_VERSION);
+    assert!(output.registry_download_url.contains("{name}"));
+    assert!(output.allowed_sources.is_empty(), "Every source is allowed");
+    assert!(output.tool_calls.is_empty(), "No call finished yet");
+
+    // The first call is counted once it finished
+    let response = service.server_info().await;
+    let output: ServerInfoOutput = serde_json::from_str(&response)?;
+    let stats = &output.tool_calls["server_info"];
+    assert_eq!(stats.calls.get("ok"), Some(&1));
+    assert_eq!(stats.duration_count, 1);
+    assert_eq!(
+        stats.duration_buckets.last().map(|bucket| bucket.count),
+        Some(1)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ensure_ready() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+    let params = |version: &str| EnsureReadyParams {
+        crate_name: "semver".to_string(),
+        version: version.to_string(),
+        member: None,
+    };
+
+    // The first call downloads and documents the crate
+    let response = service
+        .ensure_ready(Parameters(params(SEMVER_VERSION)))
+        .await;
+    let output: EnsureReadyOutput = serde_json::from_str(&response)?;
+    assert!(output.ready, "Not ready: {:?}", output.error);
+    assert!(output.generated);
+
+    // Later calls find the docs in the cache
+    let response = service
+        .ensure_ready(Parameters(params(SEMVER_VERSION)))
+        .await;
+    let output: EnsureReadyOutput = serde_json::from_str(&response)?;
+    assert!(output.ready);
+    assert!(!output.generated);
+
+    let response = service
+        .ensure_ready(Parameters(params("0.0.0-missing")))
+        .await;
+    let output: EnsureReadyOutput = serde_json::from_str(&response)?;
+    assert!(!output.ready);
+    assert!(output.error.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_allowed_sources_policy() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let config = ServiceConfig {
+        source_policy: SourcePolicy::parse("cratesio")?,
+        ..ServiceConfig::default()
+    };
+    let service = RustDocsService::with_config(Some(temp_dir.path().to_path_buf()), config)?;
+
+    // A GitHub source is refused before any task is started
+    let params = CacheCrateParams {
+        crate_name: "semver".to_string(),
+        source_type: "github".to_string(),
+        version: None,
+        github_url: Some("https://github.com/dtolnay/semver".to_string()),
+        branch: None,
+        tag: Some(SEMVER_VERSION.to_string()),
+        path: None,
+        members: None,
+        update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
+    };
+    let response = service.cache_crate(Parameters(params)).await;
+    assert!(
+        response.contains("Source not allowed by policy: GitHub repository"),
+        "Expected a policy error, got: {response}"
+    );
+    assert!(response.contains("Allowed sources: cratesio"));
+
+    // crates.io is still allowed
+    setup_test_crate(&service).await?;
+
+    let response = service.server_info().await;
+    let output: ServerInfoOutput = serde_json::from_str(&response)?;
+    assert_eq!(output.allowed_sources, ["cratesio"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_access_log() -> Result<()> {
+    let (service, temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    let log_path = temp_dir.path().join("access.jsonl");
+    let config = ServiceConfig {
+        access_log: Some(log_path.clone()),
+        ..ServiceConfig::default()
+    };
+    let service = RustDocsService::with_config(Some(temp_dir.path().to_path_buf()), config)?;
+
+    // Item lookups also run a search, which is logged as its own call
+    let item_id = get_test_item_id(&service).await?;
+    let params = GetItemSourceParams {
+        crate_name: "semver".to_string(),
+        version: SEMVER_VERSION.to_string(),
+        item_id,
+        context_lines: Some(0),
+        member: None,
+        target: None,
+        resolve_references: None,
+        strip_doctest_scaffolding: None,
+    };
+    service.get_item_source(Parameters(params)).await;
+    service.list_cached_crates().await;
+    service.flush_access_log().await;
+
+    let entries: Vec<AccessLogEntry> = std::fs::read_to_string(&log_path)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    let tools: Vec<&str> = entries.iter().map(|entry| entry.tool.as_str()).collect();
+    assert_eq!(
+        tools,
+        [
+            "search_items_preview",
+            "get_item_source",
+            "list_cached_crates"
+        ]
+    );
+
+    let source = &entries[1];
+    assert_eq!(source.crate_name.as_deref(), Some("semver"));
+    assert_eq!(source.version.as_deref(), Some(SEMVER_VERSION));
+    assert_eq!(source.item_id, Some(u64::from(item_id)));
+    assert!(chrono::DateTime::parse_from_rfc3339(&source.timestamp).is_ok());
+    assert_eq!(entries[2].crate_name, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_read_only_mode() -> Result<()> {
+    let (service, temp_dir) = create_test_service()?;
+    setup_test_crate(&service).await?;
+
+    // A read-only server over the warmed cache
+    let config = ServiceConfig {
+        read_only: true,
+        ..ServiceConfig::default()
+    };
+    let service = RustDocsService::with_config(Some(temp_dir.path().to_path_buf()), config)?;
+
+    let params = CacheCrateParams {
+        crate_name: "semver".to_string(),
+        source_type: "cratesio".to_string(),
+        version: Some(SEMVER_VERSION.to_string()),
+        github_url: None,
+        branch: None,
+        tag: None,
+        path: None,
+        members: None,
+        update: Some(true),
+        target: None,
+        scope: None,
+        keep_full_docs: None,
+    };
+    let responses = [
+        service.cache_crate(Parameters(params)).await,
+        service
+            .remove_crate(Parameters(RemoveCrateParams {
+                crate_name: "semver".to_string(),
+                version: SEMVER_VERSION.to_string(),
+            }))
+            .await,
+        service
+            .clear_all_caches(Parameters(ClearAllCachesParams { confirm: true }))
+            .await,
+        service.reindex().await,
+        service.prune_indexes().await,
+    ];
+    for response in responses {
+        assert!(
+            response.contains(READ_ONLY_ERROR),
+            "Expected a read-only error, got: {response}"
+        );
+    }
+
+    // Cached crates can still be queried
+    let list = |crate_name: &str, version: &str| ListItemsParams {
+        crate_name: crate_name.to_string(),
+        version: version.to_string(),
+        kind_filter: None,
+        limit: None,
+        offset: None,
+        member: None,
+        include_private: None,
+        include_hidden: None,
+        target: None,
+        group_by_module: None,
+        max_depth: None,
+    };
+    let response = service
+        .list_crate_items(Parameters(list("semver", SEMVER_VERSION)))
+        .await;
+    let output: ListCrateItemsOutput = serde_json::from_str(&response)?;
+    assert!(!output.items.is_empty());
+
+    // Uncached crates are not downloaded or documented
+    let response = service
+        .list_crate_items(Parameters(list("itoa", "1.0.0")))
+        .await;
+    assert!(
+        response.contains("itoa-1.0.0 is not cached and the server is read-only"),
+        "Expected a read-only error, got: {response}"
+    );
+    assert!(!temp_dir.path().join("crates/itoa").exists());
+
+    let response = service.server_info().await;
+    let output: ServerInfoOutput = serde_json::from_str(&response)?;
+    assert!(output.read_only);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_params_rejected_up_front() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    let list = |crate_name: &str, version: &str| ListItemsParams {
+        crate_name: crate_name.to_string(),
+        version: version.to_string(),
+        kind_filter: None,
+        limit: None,
+        offset: None,
+        member: None,
+        include_private: None,
+        include_hidden: None,
+        target: None,
+        group_by_module: None,
+        max_depth: None,
+    };
+    let search =
+        |limit: Option<i64>, offset: Option<i64>, member: Option<&str>| SearchItemsPreviewParams {
+            crate_name: "semver".to_string(),
+            version: SEMVER_VERSION.to_string(),
+            pattern: "Version".to_string(),
+            limit,
+            offset,
+            kind_filter: None,
+            path_filter: None,
+            member: member.map(str::to_string),
+            target: None,
+            dedupe_by_id: None,
+            compact: None,
+        };
+
+    let cases = [
+        (
+            service
+                .list_crate_items(Parameters(list("../semver", SEMVER_VERSION)))
+                .await,
+            "crate_name",
+        ),
+        (
+            service
+                .list_crate_items(Parameters(list("semver", "1.0 beta")))
+                .await,
+            "version",
+        ),
+        (
+            service
+                .search_items_preview(Parameters(search(Some(-1), None, None)))
+                .await,
+            "limit",
+        ),
+        (
+            service
+                .search_items_preview(Parameters(search(Some(1_000_000), None, None)))
+                .await,
+            "limit",
+        ),
+        (
+            service
+                .search_items_preview(Parameters(search(None, Some(-10), None)))
+                .await,
+            "offset",
+        ),
+        (
+            service
+                .search_items_preview(Parameters(search(None, None, Some("/etc"))))
+                .await,
+            "member",
+        ),
+        (
+            service
+                .get_crates_metadata(Parameters(GetCratesMetadataParams {
+                    queries: vec![CrateMetadataQuery {
+                        crate_name: "semver".to_string(),
+                        version: SEMVER_VERSION.to_string(),
+                        members: Some(vec!["../outside".to_string()]),
+                    }],
+                }))
+                .await,
+            "queries[0].members[0]",
+        ),
+        (
+            service
+                .export_docs_markdown(Parameters(ExportDocsMarkdownParams {
+                    crate_name: "semver".to_string(),
+                    version: "1.0 beta".to_string(),
+                    kind_filter: None,
+                    max_items: None,
+                    output_path: None,
+                    member: None,
+                    target: None,
+                }))
+                .await,
+            "version",
+        ),
+        (
+            service
+                .get_changed_items_since(Parameters(GetChangedItemsSinceParams {
+                    crate_name: "../semver".to_string(),
+                    version: SEMVER_VERSION.to_string(),
+                    since: "HEAD~1".to_string(),
+                    until: None,
+                    member: None,
+                }))
+                .await,
+            "crate_name",
+        ),
+    ];
+
+    for (response, field) in cases {
+        let output: ValidationErrorOutput = serde_json::from_str(&response)
+            .with_context(|| format!("Expected a validation error: {response}"))?;
+        assert_eq!(output.field, field);
+        assert!(
+            output.error.contains(field),
+            "Error should name the field: {}",
+            output.error
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_dependents_in_cache() -> Result<()> {
+    let (service, _temp_dir) = create_test_service()?;
+
+    // An app depending on a base crate by path, so no network is needed
+    let workspace_dir = TempDir::new()?;
+    let base_dir = workspace_dir.path().join("base");
+    let app_dir = workspace_dir.path().join("app");
+    for (dir, manifest, lib) in [
+        (
+            &base_dir,
+            r#"
+[package]
+name = "test-dependents-base"
+version = "0.3.1"
+edition = "2021"
+"#,
+            "pub fn base() {}",
+        ),
+        (
+            &app_dir,
+            r#"
+[package]
+name = "test-dependents-app"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+test-dependents-base = { path = "../base", version = "0.3" }
+"#,
+            "pub use test_dependents_base::base;",
+        ),
+    ] {
+        std::fs::create_dir_all(dir.join("src"))?;
+        std::fs::write(dir.join("Cargo.toml"), manifest)?;
+        std::fs::write(dir.join("src/lib.rs"), lib)?;
+    }
+
+    cache_local_crate_at(&service, "test-dependents-app", &app_dir).await?;
+
+    let dependents = |version: Option<&str>| GetDependentsInCacheParams {
+        crate_name: "test-dependents-base".to_string(),
+        version: version.map(str::to_string),
+    };
+
+    let response = service
+        .get_dependents_in_cache(Parameters(dependents(None)))
+        .await;
+    let output: DependentsInCacheOutput = serde_json::from_str(&response)?;
+    assert_eq!(output.dependents.len(), 1, "{response}");
+    let dependent = &output.dependents[0];
+    assert_eq!(dependent.name, "test-dependents-app");
+    assert_eq!(dependent.version, "0.1.0");
+    assert_eq!(dependent.req, "^0.3");
+    assert_eq!(dependent.kind, "normal");
+
+    // A version outside the requirement has no dependents
+    let response = service
+        .get_dependents_in_cache(Parameters(dependents(Some("0.4.0"))))
+        .await;
+    let output: DependentsInCacheOutput = serde_json::from_str(&response)?;
+    assert!(output.dependents.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cache_from_mock_registry_offline() -> Result<()> {
+    use rust_docs_mcp::cache::downloader::sparse_index_path;
+    use rust_docs_mcp::cache::registry::Registry;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    // Package a tiny crate the way crates.io serves it
+    let mut archive = tar::Builder::new(flate2::write::GzEncoder::new(
+        Vec::new(),
+        flate2::Compression::default(),
+    ));
+    let files: [(&str, &[u8]); 2] = [
+        (
+            "mock-registry-crate-0.1.0/Cargo.toml",
+            b"[package]\nname = \"mock-registry-crate\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        ),
+        (
+            "mock-registry-crate-0.1.0/src/lib.rs",
+            b"/// Served by a mock registry.\npub fn mocked() {}\n",
+        ),
+    ];
+    for (path, contents) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        archive.append_data(&mut header, path, contents)?;
+    }
+    let crate_file = archive.into_inner()?.finish()?;
+
+    // A mock registry serving the index entry and the archive, 404 for anything else
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let base = format!("http://127.0.0.1:{}", listener.local_addr()?.port());
+    let index_path = format!("GET /index/{} ", sparse_index_path("mock-registry-crate"));
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut request_line = String::new();
+            if BufReader::new(&stream)
+                .read_line(&mut request_line)
+                .is_err()
+            {
+                continue;
+            }
+            let (status, body) = if request_line.starts_with(&index_path) {
+                (
+                    "200 OK",
+                    br#"{"name":"mock-registry-crate","vers":"0.1.0","deps":[],"cksum":"00","features":{},"yanked":false}"#.to_vec(),
+                )
+            } else if request_line.starts_with("GET /crates/mock-registry-crate/0.1.0/download ") {
+                ("200 OK", crate_file.clone())
+            } else {
+                ("404 Not Found", Vec::new())
+            };
+            let header = format!(
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            let _ = stream.write_all(header.as_bytes());
+            let _ = stream.write_all(&body);
+        }
+    });
+
+    let temp_dir = TempDir::new()?;
+    let config = ServiceConfig {
+        registry: Registry::default()
+            .with_download_url(&format!("{base}/crates"))?
+            .with_index_url(&format!("{base}/index"))?
+            .with_docsrs_url(&format!("{base}/docs"))?,
+        ..ServiceConfig::default()
+    };
+    let service = RustDocsService::with_config(Some(temp_dir.path().to_path_buf()), config)?;
+
+    let params = CacheCrateParams {
+        crate_name: "mock-registry-crate".to_string(),
+        source_type: "cratesio".to_string(),
+        version: Some("0.1.0".to_string()),
+        github_url: None,
+        branch: None,
+        tag: None,
+        path: None,
+        members: None,
+        update: None,
+        target: None,
+        scope: None,
+        keep_full_docs: None,
+    };
+    let response = service.cache_crate(Parameters(params)).await;
+    let task_output = parse_cache_task_started(&response)?;
+    let result = wait_for_task_completion(&service, &task_output.task_id, TEST_TIMEOUT).await?;
+    assert!(
+        matches!(result, TaskResult::Success),
+        "Failed to cache from the mock registry: {result:?}"
+    );
+
+    // Item links point at the configured docs.rs base
+    let item = get_item_details_success(
+        &service,
+        GetItemDetailsParams {
+            crate_name: "mock-registry-crate".to_string(),
+            version: "0.1.0".to_string(),
+            item_id: None,
+            path: Some("mock_registry_crate::mocked".to_string()),
+            member: None,
+            fields: None,
+            target: None,
+            resolve_field_types: None,
+            format: None,
+        },
+    )
+    .await?;
+    let docsrs_url = item
+        .docsrs_url
+        .context("crates.io items should link to docs.rs")?;
+    assert!(
+        docsrs_url.starts_with(&format!("{base}/docs/mock-registry-crate/0.1.0/")),
+        "Unexpected docs.rs link: {docsrs_url}"
+    );
+
+    Ok(())
+}